@@ -58,8 +58,10 @@ cd "$(dirname "$0")"
 echo "Step 1: Building Rust Services"
 echo "================================"
 
-# Build all Rust services
+# Build all Rust services (deltran-schema first - shared NATS payloads)
+build_rust_service "deltran-schema" "services/deltran-schema"
 build_rust_service "clearing-engine" "services/clearing-engine"
+build_rust_service "clearing-client" "services/clearing-client"
 build_rust_service "compliance-engine" "services/compliance-engine"
 build_rust_service "risk-engine" "services/risk-engine"
 build_rust_service "liquidity-router" "services/liquidity-router"
@@ -0,0 +1,9 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Client side of the clearing engine's proto; the engine owns the definition
+    tonic_build::configure()
+        .build_server(false)
+        .build_client(true)
+        .out_dir("src/generated")
+        .compile(&["../clearing-engine/proto/clearing.proto"], &["../clearing-engine/proto/"])?;
+    Ok(())
+}
@@ -0,0 +1,702 @@
+// This file is @generated by prost-build.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetCurrentWindowRequest {
+    /// Global, ADGM, Europe, etc.
+    #[prost(string, tag = "1")]
+    pub region: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetWindowStatusRequest {
+    #[prost(int64, tag = "1")]
+    pub window_id: i64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ForceCloseWindowRequest {
+    #[prost(int64, tag = "1")]
+    pub window_id: i64,
+    #[prost(string, tag = "2")]
+    pub reason: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OpenNewWindowRequest {
+    #[prost(string, tag = "1")]
+    pub region: ::prost::alloc::string::String,
+    #[prost(bool, tag = "2")]
+    pub emergency_mode: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ProcessWindowRequest {
+    #[prost(int64, tag = "1")]
+    pub window_id: i64,
+    #[prost(bool, tag = "2")]
+    pub force_settlement: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetProcessingResultRequest {
+    #[prost(int64, tag = "1")]
+    pub window_id: i64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StreamWindowRequest {
+    #[prost(string, tag = "1")]
+    pub region: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StreamSettlementRequest {
+    #[prost(int64, tag = "1")]
+    pub window_id: i64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EmergencyRequest {
+    #[prost(string, tag = "1")]
+    pub region: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub reason: ::prost::alloc::string::String,
+    #[prost(int32, tag = "3")]
+    pub max_obligations: i32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RollbackRequest {
+    #[prost(int64, tag = "1")]
+    pub window_id: i64,
+    #[prost(string, tag = "2")]
+    pub reason: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OperationStatusRequest {
+    #[prost(string, tag = "1")]
+    pub operation_id: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct WindowResponse {
+    #[prost(message, optional, tag = "1")]
+    pub window: ::core::option::Option<Window>,
+    #[prost(bool, tag = "2")]
+    pub success: bool,
+    #[prost(string, tag = "3")]
+    pub message: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct WindowStatusResponse {
+    #[prost(string, tag = "1")]
+    pub status: ::prost::alloc::string::String,
+    #[prost(int32, tag = "2")]
+    pub obligations_count: i32,
+    #[prost(string, tag = "3")]
+    pub total_volume: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub netted_volume: ::prost::alloc::string::String,
+    #[prost(double, tag = "5")]
+    pub netting_efficiency: f64,
+    #[prost(string, tag = "6")]
+    pub timestamp: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct WindowCloseResult {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(int64, tag = "3")]
+    pub window_id: i64,
+    #[prost(string, tag = "4")]
+    pub closed_at: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ProcessWindowResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(int64, tag = "3")]
+    pub window_id: i64,
+    #[prost(string, tag = "4")]
+    pub processing_started_at: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ClearingResult {
+    #[prost(int64, tag = "1")]
+    pub window_id: i64,
+    /// Success, PartialSuccess, Failed
+    #[prost(string, tag = "2")]
+    pub status: ::prost::alloc::string::String,
+    #[prost(int32, tag = "3")]
+    pub obligations_processed: i32,
+    #[prost(message, repeated, tag = "4")]
+    pub net_positions: ::prost::alloc::vec::Vec<NetPosition>,
+    #[prost(message, repeated, tag = "5")]
+    pub settlement_instructions: ::prost::alloc::vec::Vec<SettlementInstructionProto>,
+    #[prost(string, tag = "6")]
+    pub total_saved: ::prost::alloc::string::String,
+    #[prost(double, tag = "7")]
+    pub efficiency: f64,
+    #[prost(message, repeated, tag = "8")]
+    pub errors: ::prost::alloc::vec::Vec<ClearingError>,
+    #[prost(int64, tag = "9")]
+    pub processing_time_ms: i64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RollbackResult {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(int64, tag = "3")]
+    pub window_id: i64,
+    #[prost(string, tag = "4")]
+    pub rolled_back_at: ::prost::alloc::string::String,
+    #[prost(string, repeated, tag = "5")]
+    pub operations_rolled_back: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OperationStatusResponse {
+    #[prost(string, tag = "1")]
+    pub operation_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub operation_type: ::prost::alloc::string::String,
+    /// Pending, InProgress, Committed, RolledBack, Failed
+    #[prost(string, tag = "3")]
+    pub state: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub started_at: ::prost::alloc::string::String,
+    #[prost(string, tag = "5")]
+    pub completed_at: ::prost::alloc::string::String,
+    #[prost(string, tag = "6")]
+    pub error_message: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct WindowUpdate {
+    #[prost(int64, tag = "1")]
+    pub window_id: i64,
+    /// Opened, Closing, Closed, Processing, Completed, Failed
+    #[prost(string, tag = "2")]
+    pub event_type: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub timestamp: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub details: ::prost::alloc::string::String,
+    #[prost(map = "string, string", tag = "5")]
+    pub metadata: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::string::String,
+    >,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SettlementStatusUpdate {
+    #[prost(int64, tag = "1")]
+    pub window_id: i64,
+    #[prost(string, tag = "2")]
+    pub settlement_id: ::prost::alloc::string::String,
+    /// Pending, InProgress, Completed, Failed
+    #[prost(string, tag = "3")]
+    pub status: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub timestamp: ::prost::alloc::string::String,
+    #[prost(string, tag = "5")]
+    pub details: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Window {
+    #[prost(int64, tag = "1")]
+    pub id: i64,
+    #[prost(string, tag = "2")]
+    pub start_time: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub end_time: ::prost::alloc::string::String,
+    /// Scheduled, Open, Closing, Closed, Processing, Settling, Completed, Failed, RolledBack
+    #[prost(string, tag = "4")]
+    pub status: ::prost::alloc::string::String,
+    #[prost(string, tag = "5")]
+    pub region: ::prost::alloc::string::String,
+    #[prost(int32, tag = "6")]
+    pub total_obligations: i32,
+    #[prost(string, tag = "7")]
+    pub total_volume: ::prost::alloc::string::String,
+    #[prost(string, tag = "8")]
+    pub netted_volume: ::prost::alloc::string::String,
+    #[prost(string, tag = "9")]
+    pub saved_amount: ::prost::alloc::string::String,
+    #[prost(double, tag = "10")]
+    pub netting_efficiency: f64,
+    #[prost(string, repeated, tag = "11")]
+    pub settlement_instructions: ::prost::alloc::vec::Vec<
+        ::prost::alloc::string::String,
+    >,
+    #[prost(message, optional, tag = "12")]
+    pub metadata: ::core::option::Option<WindowMetadata>,
+    #[prost(string, tag = "13")]
+    pub created_at: ::prost::alloc::string::String,
+    #[prost(string, tag = "14")]
+    pub closed_at: ::prost::alloc::string::String,
+    #[prost(string, tag = "15")]
+    pub processed_at: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct WindowMetadata {
+    #[prost(string, tag = "1")]
+    pub cutoff_time: ::prost::alloc::string::String,
+    #[prost(int64, tag = "2")]
+    pub grace_period_seconds: i64,
+    #[prost(int32, tag = "3")]
+    pub max_obligations: i32,
+    #[prost(double, tag = "4")]
+    pub min_netting_efficiency: f64,
+    #[prost(bool, tag = "5")]
+    pub auto_settle: bool,
+    #[prost(bool, tag = "6")]
+    pub emergency_mode: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NetPosition {
+    #[prost(message, optional, tag = "1")]
+    pub bank_pair: ::core::option::Option<BankPair>,
+    #[prost(string, tag = "2")]
+    pub currency: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub gross_debit: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub gross_credit: ::prost::alloc::string::String,
+    #[prost(string, tag = "5")]
+    pub net_amount: ::prost::alloc::string::String,
+    /// Debit, Credit, Neutral
+    #[prost(string, tag = "6")]
+    pub direction: ::prost::alloc::string::String,
+    #[prost(int32, tag = "7")]
+    pub obligations_count: i32,
+    #[prost(double, tag = "8")]
+    pub netting_ratio: f64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BankPair {
+    #[prost(string, tag = "1")]
+    pub bank_a: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub bank_b: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SettlementInstructionProto {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(int64, tag = "2")]
+    pub window_id: i64,
+    #[prost(string, tag = "3")]
+    pub from_bank: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub to_bank: ::prost::alloc::string::String,
+    #[prost(string, tag = "5")]
+    pub amount: ::prost::alloc::string::String,
+    #[prost(string, tag = "6")]
+    pub currency: ::prost::alloc::string::String,
+    /// NetSettlement, GrossSettlement, EmergencySettlement
+    #[prost(string, tag = "7")]
+    pub instruction_type: ::prost::alloc::string::String,
+    #[prost(int32, tag = "8")]
+    pub priority: i32,
+    #[prost(string, tag = "9")]
+    pub deadline: ::prost::alloc::string::String,
+    #[prost(string, tag = "10")]
+    pub metadata: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ClearingError {
+    #[prost(string, tag = "1")]
+    pub error_type: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub message: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub timestamp: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub details: ::prost::alloc::string::String,
+}
+/// Generated client implementations.
+pub mod clearing_service_client {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    use tonic::codegen::http::Uri;
+    /// Clearing Engine Service - manages clearing windows and orchestration
+    #[derive(Debug, Clone)]
+    pub struct ClearingServiceClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl ClearingServiceClient<tonic::transport::Channel> {
+        /// Attempt to create a new client by connecting to a given endpoint.
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> ClearingServiceClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_origin(inner: T, origin: Uri) -> Self {
+            let inner = tonic::client::Grpc::with_origin(inner, origin);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> ClearingServiceClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T::ResponseBody: Default,
+            T: tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+                Response = http::Response<
+                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                >,
+            >,
+            <T as tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+            >>::Error: Into<StdError> + Send + Sync,
+        {
+            ClearingServiceClient::new(InterceptedService::new(inner, interceptor))
+        }
+        /// Compress requests with the given encoding.
+        ///
+        /// This requires the server to support it otherwise it might respond with an
+        /// error.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.send_compressed(encoding);
+            self
+        }
+        /// Enable decompressing responses.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.accept_compressed(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_decoding_message_size(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_encoding_message_size(limit);
+            self
+        }
+        /// Window management
+        pub async fn get_current_window(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetCurrentWindowRequest>,
+        ) -> std::result::Result<tonic::Response<super::WindowResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/clearing.ClearingService/GetCurrentWindow",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("clearing.ClearingService", "GetCurrentWindow"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_window_status(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetWindowStatusRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::WindowStatusResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/clearing.ClearingService/GetWindowStatus",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("clearing.ClearingService", "GetWindowStatus"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn force_close_window(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ForceCloseWindowRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::WindowCloseResult>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/clearing.ClearingService/ForceCloseWindow",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("clearing.ClearingService", "ForceCloseWindow"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn open_new_window(
+            &mut self,
+            request: impl tonic::IntoRequest<super::OpenNewWindowRequest>,
+        ) -> std::result::Result<tonic::Response<super::WindowResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/clearing.ClearingService/OpenNewWindow",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("clearing.ClearingService", "OpenNewWindow"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Window processing
+        pub async fn process_window(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ProcessWindowRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ProcessWindowResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/clearing.ClearingService/ProcessWindow",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("clearing.ClearingService", "ProcessWindow"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_processing_result(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetProcessingResultRequest>,
+        ) -> std::result::Result<tonic::Response<super::ClearingResult>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/clearing.ClearingService/GetProcessingResult",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("clearing.ClearingService", "GetProcessingResult"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// Streaming
+        pub async fn stream_window_updates(
+            &mut self,
+            request: impl tonic::IntoRequest<super::StreamWindowRequest>,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::WindowUpdate>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/clearing.ClearingService/StreamWindowUpdates",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("clearing.ClearingService", "StreamWindowUpdates"),
+                );
+            self.inner.server_streaming(req, path, codec).await
+        }
+        pub async fn stream_settlement_status(
+            &mut self,
+            request: impl tonic::IntoRequest<super::StreamSettlementRequest>,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::SettlementStatusUpdate>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/clearing.ClearingService/StreamSettlementStatus",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("clearing.ClearingService", "StreamSettlementStatus"),
+                );
+            self.inner.server_streaming(req, path, codec).await
+        }
+        /// Manual intervention
+        pub async fn trigger_emergency_clearing(
+            &mut self,
+            request: impl tonic::IntoRequest<super::EmergencyRequest>,
+        ) -> std::result::Result<tonic::Response<super::ClearingResult>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/clearing.ClearingService/TriggerEmergencyClearing",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "clearing.ClearingService",
+                        "TriggerEmergencyClearing",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn rollback_window(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RollbackRequest>,
+        ) -> std::result::Result<tonic::Response<super::RollbackResult>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/clearing.ClearingService/RollbackWindow",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("clearing.ClearingService", "RollbackWindow"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Atomic operations
+        pub async fn get_operation_status(
+            &mut self,
+            request: impl tonic::IntoRequest<super::OperationStatusRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::OperationStatusResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/clearing.ClearingService/GetOperationStatus",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("clearing.ClearingService", "GetOperationStatus"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+    }
+}
@@ -0,0 +1,173 @@
+// Clearing Client - typed access to the clearing engine's ClearingService for ops tools
+// The raw generated client is re-exported for anything the wrapper does not cover.
+
+use tonic::metadata::errors::InvalidMetadataValue;
+use tonic::metadata::{Ascii, MetadataValue};
+use tonic::service::interceptor::InterceptedService;
+use tonic::service::Interceptor;
+use tonic::transport::{Channel, Endpoint};
+use tonic::{Request, Status, Streaming};
+use uuid::Uuid;
+
+// Generated by build.rs from ../clearing-engine/proto/clearing.proto
+pub mod proto {
+    include!("generated/clearing.rs");
+}
+
+pub use proto::clearing_service_client::ClearingServiceClient;
+use proto::*;
+
+/// Default address of the clearing engine's gRPC API (GRPC_PORT)
+pub const DEFAULT_ENDPOINT: &str = "http://localhost:50055";
+
+/// Sends the ops JWT with every call; the engine only serves tokens with its admin role
+#[derive(Debug, Clone, Default)]
+pub struct BearerToken(Option<MetadataValue<Ascii>>);
+
+impl Interceptor for BearerToken {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        if let Some(token) = &self.0 {
+            request.metadata_mut().insert("authorization", token.clone());
+        }
+        Ok(request)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ClearingClient {
+    channel: Channel,
+    inner: ClearingServiceClient<InterceptedService<Channel, BearerToken>>,
+}
+
+impl ClearingClient {
+    /// Connect to a clearing engine, e.g. DEFAULT_ENDPOINT
+    pub async fn connect(endpoint: impl Into<String>) -> Result<Self, tonic::transport::Error> {
+        let channel = Endpoint::from_shared(endpoint.into())?.connect().await?;
+        Ok(Self::new(channel))
+    }
+
+    pub fn new(channel: Channel) -> Self {
+        Self {
+            inner: ClearingServiceClient::with_interceptor(channel.clone(), BearerToken::default()),
+            channel,
+        }
+    }
+
+    /// Authenticate every call with `jwt`, a token with the engine's admin role
+    pub fn with_token(self, jwt: &str) -> Result<Self, InvalidMetadataValue> {
+        let token = MetadataValue::try_from(format!("Bearer {}", jwt))?;
+        Ok(Self {
+            inner: ClearingServiceClient::with_interceptor(self.channel.clone(), BearerToken(Some(token))),
+            channel: self.channel,
+        })
+    }
+
+    /// Generated client, for per-call options such as compression or metadata
+    pub fn raw(&mut self) -> &mut ClearingServiceClient<InterceptedService<Channel, BearerToken>> {
+        &mut self.inner
+    }
+
+    /// Open window of `region` ("" = the engine's region), None if no window is open
+    pub async fn current_window(&mut self, region: &str) -> Result<Option<Window>, Status> {
+        let response = self
+            .inner
+            .get_current_window(GetCurrentWindowRequest { region: region.to_string() })
+            .await?
+            .into_inner();
+        Ok(response.window)
+    }
+
+    pub async fn window_status(&mut self, window_id: i64) -> Result<WindowStatusResponse, Status> {
+        Ok(self.inner.get_window_status(GetWindowStatusRequest { window_id }).await?.into_inner())
+    }
+
+    /// Close a window now, skipping its grace period
+    pub async fn force_close_window(&mut self, window_id: i64, reason: &str) -> Result<WindowCloseResult, Status> {
+        let request = ForceCloseWindowRequest { window_id, reason: reason.to_string() };
+        Ok(self.inner.force_close_window(request).await?.into_inner())
+    }
+
+    /// Open a window outside the schedule; fails with ALREADY_EXISTS while one is open
+    pub async fn open_window(&mut self, region: &str, emergency_mode: bool) -> Result<Window, Status> {
+        let request = OpenNewWindowRequest { region: region.to_string(), emergency_mode };
+        self.inner
+            .open_new_window(request)
+            .await?
+            .into_inner()
+            .window
+            .ok_or_else(|| Status::internal("Clearing engine returned no window"))
+    }
+
+    /// Start netting a closed window; poll processing_result or stream window updates for the outcome
+    pub async fn process_window(&mut self, window_id: i64, force_settlement: bool) -> Result<ProcessWindowResponse, Status> {
+        let request = ProcessWindowRequest { window_id, force_settlement };
+        Ok(self.inner.process_window(request).await?.into_inner())
+    }
+
+    pub async fn processing_result(&mut self, window_id: i64) -> Result<ClearingResult, Status> {
+        Ok(self.inner.get_processing_result(GetProcessingResultRequest { window_id }).await?.into_inner())
+    }
+
+    /// Window lifecycle events of `region` ("" = all)
+    pub async fn window_updates(&mut self, region: &str) -> Result<Streaming<WindowUpdate>, Status> {
+        let request = StreamWindowRequest { region: region.to_string() };
+        Ok(self.inner.stream_window_updates(request).await?.into_inner())
+    }
+
+    /// Settlement status of the instructions of one window, or of every window for None
+    pub async fn settlement_status(&mut self, window_id: Option<i64>) -> Result<Streaming<SettlementStatusUpdate>, Status> {
+        let request = StreamSettlementRequest { window_id: window_id.unwrap_or(0) };
+        Ok(self.inner.stream_settlement_status(request).await?.into_inner())
+    }
+
+    /// Close and net the current window immediately, over at most `max_obligations` obligations
+    pub async fn emergency_clearing(
+        &mut self,
+        region: &str,
+        reason: &str,
+        max_obligations: Option<i32>,
+    ) -> Result<ClearingResult, Status> {
+        let request = EmergencyRequest {
+            region: region.to_string(),
+            reason: reason.to_string(),
+            max_obligations: max_obligations.unwrap_or(0),
+        };
+        Ok(self.inner.trigger_emergency_clearing(request).await?.into_inner())
+    }
+
+    pub async fn rollback_window(&mut self, window_id: i64, reason: &str) -> Result<RollbackResult, Status> {
+        let request = RollbackRequest { window_id, reason: reason.to_string() };
+        Ok(self.inner.rollback_window(request).await?.into_inner())
+    }
+
+    pub async fn operation_status(&mut self, operation_id: Uuid) -> Result<OperationStatusResponse, Status> {
+        let request = OperationStatusRequest { operation_id: operation_id.to_string() };
+        Ok(self.inner.get_operation_status(request).await?.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_unreachable_engine() {
+        // Nothing listens on port 1
+        let channel = Endpoint::from_static("http://127.0.0.1:1").connect_lazy();
+        let mut client = ClearingClient::new(channel);
+
+        let err = client.window_status(1).await.unwrap_err();
+        assert_eq!(err.code(), tonic::Code::Unavailable);
+    }
+
+    #[tokio::test]
+    async fn test_bearer_token() {
+        let channel = Endpoint::from_static("http://127.0.0.1:1").connect_lazy();
+        assert!(ClearingClient::new(channel.clone()).with_token("not\na header").is_err());
+
+        let mut token = BearerToken(Some(MetadataValue::from_static("Bearer abc")));
+        let request = token.call(Request::new(())).unwrap();
+        assert_eq!(request.metadata().get("authorization").unwrap(), "Bearer abc");
+        assert!(BearerToken::default().call(Request::new(())).unwrap().metadata().is_empty());
+    }
+}
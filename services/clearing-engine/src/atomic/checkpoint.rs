@@ -30,18 +30,18 @@ impl CheckpointManager {
 
         let checkpoint_id = Uuid::new_v4();
 
-        sqlx::query!(
+        sqlx::query(
             r#"
             INSERT INTO clearing_operation_checkpoints
             (id, operation_id, checkpoint_name, checkpoint_order, checkpoint_data, created_at)
             VALUES ($1, $2, $3, $4, $5, NOW())
             "#,
-            checkpoint_id,
-            operation_id,
-            checkpoint_name,
-            checkpoint_order,
-            checkpoint_data,
         )
+        .bind(checkpoint_id)
+        .bind(operation_id)
+        .bind(&checkpoint_name)
+        .bind(checkpoint_order)
+        .bind(checkpoint_data)
         .execute(&self.pool)
         .await?;
 
@@ -55,16 +55,15 @@ impl CheckpointManager {
 
     /// Retrieve all checkpoints for an operation in order
     pub async fn get_checkpoints(&self, operation_id: Uuid) -> Result<Vec<OperationCheckpoint>> {
-        let checkpoints = sqlx::query_as!(
-            OperationCheckpoint,
+        let checkpoints = sqlx::query_as::<_, OperationCheckpoint>(
             r#"
             SELECT id, operation_id, checkpoint_name, checkpoint_order, checkpoint_data, created_at
             FROM clearing_operation_checkpoints
             WHERE operation_id = $1
             ORDER BY checkpoint_order ASC
             "#,
-            operation_id,
         )
+        .bind(operation_id)
         .fetch_all(&self.pool)
         .await?;
 
@@ -76,16 +75,15 @@ impl CheckpointManager {
         &self,
         operation_id: Uuid,
     ) -> Result<Vec<OperationCheckpoint>> {
-        let checkpoints = sqlx::query_as!(
-            OperationCheckpoint,
+        let checkpoints = sqlx::query_as::<_, OperationCheckpoint>(
             r#"
             SELECT id, operation_id, checkpoint_name, checkpoint_order, checkpoint_data, created_at
             FROM clearing_operation_checkpoints
             WHERE operation_id = $1
             ORDER BY checkpoint_order DESC
             "#,
-            operation_id,
         )
+        .bind(operation_id)
         .fetch_all(&self.pool)
         .await?;
 
@@ -98,16 +96,15 @@ impl CheckpointManager {
         operation_id: Uuid,
         checkpoint_name: &str,
     ) -> Result<OperationCheckpoint> {
-        let checkpoint = sqlx::query_as!(
-            OperationCheckpoint,
+        let checkpoint = sqlx::query_as::<_, OperationCheckpoint>(
             r#"
             SELECT id, operation_id, checkpoint_name, checkpoint_order, checkpoint_data, created_at
             FROM clearing_operation_checkpoints
             WHERE operation_id = $1 AND checkpoint_name = $2
             "#,
-            operation_id,
-            checkpoint_name,
         )
+        .bind(operation_id)
+        .bind(checkpoint_name)
         .fetch_optional(&self.pool)
         .await?
         .ok_or_else(|| ClearingError::CheckpointNotFound {
@@ -120,13 +117,13 @@ impl CheckpointManager {
 
     /// Delete all checkpoints for an operation
     pub async fn delete_checkpoints(&self, operation_id: Uuid) -> Result<()> {
-        sqlx::query!(
+        sqlx::query(
             r#"
             DELETE FROM clearing_operation_checkpoints
             WHERE operation_id = $1
             "#,
-            operation_id,
         )
+        .bind(operation_id)
         .execute(&self.pool)
         .await?;
 
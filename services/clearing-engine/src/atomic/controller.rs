@@ -1,8 +1,7 @@
 use crate::atomic::operation::AtomicOperationHandler;
 use crate::database::DbPool;
 use crate::errors::{ClearingError, Result};
-use crate::models::{AtomicOperation, AtomicOperationType, AtomicState};
-use std::sync::Arc;
+use crate::models::{AtomicOperation, AtomicOperationType};
 use tracing::{info, warn};
 use uuid::Uuid;
 
@@ -27,8 +26,7 @@ impl AtomicController {
 
     /// Get an existing operation
     pub async fn get_operation(&self, operation_id: Uuid) -> Result<AtomicOperation> {
-        let operation = sqlx::query_as!(
-            AtomicOperation,
+        let operation = sqlx::query_as::<_, AtomicOperation>(
             r#"
             SELECT operation_id, window_id, operation_type, state,
                    parent_operation_id, checkpoints, started_at, completed_at,
@@ -36,21 +34,18 @@ impl AtomicController {
             FROM clearing_atomic_operations
             WHERE operation_id = $1
             "#,
-            operation_id,
         )
+        .bind(operation_id)
         .fetch_optional(&self.pool)
         .await?
-        .ok_or_else(|| {
-            ClearingError::Internal(format!("Operation not found: {}", operation_id))
-        })?;
+        .ok_or(ClearingError::OperationNotFound(operation_id))?;
 
         Ok(operation)
     }
 
     /// Get all operations for a window
     pub async fn get_window_operations(&self, window_id: i64) -> Result<Vec<AtomicOperation>> {
-        let operations = sqlx::query_as!(
-            AtomicOperation,
+        let operations = sqlx::query_as::<_, AtomicOperation>(
             r#"
             SELECT operation_id, window_id, operation_type, state,
                    parent_operation_id, checkpoints, started_at, completed_at,
@@ -59,8 +54,8 @@ impl AtomicController {
             WHERE window_id = $1
             ORDER BY started_at ASC
             "#,
-            window_id,
         )
+        .bind(window_id)
         .fetch_all(&self.pool)
         .await?;
 
@@ -69,26 +64,26 @@ impl AtomicController {
 
     /// Check if there are any failed operations for a window
     pub async fn has_failed_operations(&self, window_id: i64) -> Result<bool> {
-        let count = sqlx::query_scalar!(
+        let count: i64 = sqlx::query_scalar(
             r#"
-            SELECT COUNT(*) as "count!"
+            SELECT COUNT(*)
             FROM clearing_atomic_operations
             WHERE window_id = $1 AND state = 'Failed'
             "#,
-            window_id,
         )
+        .bind(window_id)
         .fetch_one(&self.pool)
         .await?;
 
         Ok(count > 0)
     }
 
-    /// Rollback all operations for a window in reverse order
-    pub async fn rollback_window_operations(&self, window_id: i64, reason: String) -> Result<()> {
+    /// Rollback all operations for a window in reverse order.
+    /// Returns the operations that were rolled back.
+    pub async fn rollback_window_operations(&self, window_id: i64, reason: String) -> Result<Vec<Uuid>> {
         info!("Rolling back all operations for window {}", window_id);
 
-        let operations = sqlx::query_as!(
-            AtomicOperation,
+        let operations = sqlx::query_as::<_, AtomicOperation>(
             r#"
             SELECT operation_id, window_id, operation_type, state,
                    parent_operation_id, checkpoints, started_at, completed_at,
@@ -97,33 +92,22 @@ impl AtomicController {
             WHERE window_id = $1 AND state IN ('Committed', 'InProgress')
             ORDER BY started_at DESC
             "#,
-            window_id,
         )
+        .bind(window_id)
         .fetch_all(&self.pool)
         .await?;
 
         info!("Found {} operations to rollback", operations.len());
 
+        let mut rolled_back = Vec::with_capacity(operations.len());
         for operation in operations {
-            // Skip operations already rolled back
-            if operation.state == AtomicState::RolledBack.as_str() {
-                continue;
-            }
-
-            let handler = AtomicOperationHandler::new(
-                self.pool.clone(),
-                window_id,
-                match operation.operation_type.as_str() {
-                    "WindowClose" => AtomicOperationType::WindowClose,
-                    "ObligationCollection" => AtomicOperationType::ObligationCollection,
-                    "NettingCalculation" => AtomicOperationType::NettingCalculation,
-                    "InstructionGeneration" => AtomicOperationType::InstructionGeneration,
-                    "SettlementInitiation" => AtomicOperationType::SettlementInitiation,
-                    "WindowOpen" => AtomicOperationType::WindowOpen,
-                    _ => continue,
-                },
-            )
-            .await?;
+            let handler = match AtomicOperationHandler::resume(self.pool.clone(), &operation) {
+                Ok(handler) => handler,
+                Err(e) => {
+                    warn!("Skipping operation {}: {}", operation.operation_id, e);
+                    continue;
+                }
+            };
 
             match handler.rollback(reason.clone()).await {
                 Ok(_) => {
@@ -131,6 +115,7 @@ impl AtomicController {
                         "Successfully rolled back operation {} ({})",
                         operation.operation_id, operation.operation_type
                     );
+                    rolled_back.push(operation.operation_id);
                 }
                 Err(e) => {
                     warn!(
@@ -141,48 +126,49 @@ impl AtomicController {
             }
         }
 
-        Ok(())
+        Ok(rolled_back)
     }
 
     /// Get operation statistics for a window
     pub async fn get_window_stats(&self, window_id: i64) -> Result<OperationStats> {
-        let stats = sqlx::query!(
-            r#"
-            SELECT
-                COUNT(*) as total,
-                COUNT(*) FILTER (WHERE state = 'Pending') as pending,
-                COUNT(*) FILTER (WHERE state = 'InProgress') as in_progress,
-                COUNT(*) FILTER (WHERE state = 'Committed') as committed,
-                COUNT(*) FILTER (WHERE state = 'RolledBack') as rolled_back,
-                COUNT(*) FILTER (WHERE state = 'Failed') as failed
-            FROM clearing_atomic_operations
-            WHERE window_id = $1
-            "#,
-            window_id,
-        )
-        .fetch_one(&self.pool)
-        .await?;
+        let (total, pending, in_progress, committed, rolled_back, failed): (i64, i64, i64, i64, i64, i64) =
+            sqlx::query_as(
+                r#"
+                SELECT
+                    COUNT(*) as total,
+                    COUNT(*) FILTER (WHERE state = 'Pending') as pending,
+                    COUNT(*) FILTER (WHERE state = 'InProgress') as in_progress,
+                    COUNT(*) FILTER (WHERE state = 'Committed') as committed,
+                    COUNT(*) FILTER (WHERE state = 'RolledBack') as rolled_back,
+                    COUNT(*) FILTER (WHERE state = 'Failed') as failed
+                FROM clearing_atomic_operations
+                WHERE window_id = $1
+                "#,
+            )
+            .bind(window_id)
+            .fetch_one(&self.pool)
+            .await?;
 
         Ok(OperationStats {
-            total: stats.total.unwrap_or(0) as u32,
-            pending: stats.pending.unwrap_or(0) as u32,
-            in_progress: stats.in_progress.unwrap_or(0) as u32,
-            committed: stats.committed.unwrap_or(0) as u32,
-            rolled_back: stats.rolled_back.unwrap_or(0) as u32,
-            failed: stats.failed.unwrap_or(0) as u32,
+            total: total as u32,
+            pending: pending as u32,
+            in_progress: in_progress as u32,
+            committed: committed as u32,
+            rolled_back: rolled_back as u32,
+            failed: failed as u32,
         })
     }
 
     /// Clean up old completed operations (older than retention days)
     pub async fn cleanup_old_operations(&self, retention_days: i32) -> Result<u64> {
-        let result = sqlx::query!(
+        let result = sqlx::query(
             r#"
             DELETE FROM clearing_atomic_operations
             WHERE state IN ('Committed', 'RolledBack')
-            AND started_at < NOW() - INTERVAL '1 day' * $1
+            AND started_at < NOW() - make_interval(days => $1)
             "#,
-            retention_days,
         )
+        .bind(retention_days)
         .execute(&self.pool)
         .await?;
 
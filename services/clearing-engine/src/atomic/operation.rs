@@ -30,18 +30,18 @@ impl AtomicOperationHandler {
         let checkpoint_manager = Arc::new(CheckpointManager::new(pool.clone()));
 
         // Create operation record in database
-        sqlx::query!(
+        sqlx::query(
             r#"
             INSERT INTO clearing_atomic_operations
             (operation_id, window_id, operation_type, state, checkpoints, started_at)
             VALUES ($1, $2, $3, $4, $5, NOW())
             "#,
-            operation_id,
-            window_id,
-            operation_type.as_str(),
-            AtomicState::Pending.as_str(),
-            json!([]),
         )
+        .bind(operation_id)
+        .bind(window_id)
+        .bind(operation_type.as_str())
+        .bind(AtomicState::Pending.as_str())
+        .bind(json!([]))
         .execute(&pool)
         .await?;
 
@@ -61,6 +61,24 @@ impl AtomicOperationHandler {
         })
     }
 
+    /// Handler for an operation that already has a row, e.g. to roll it back later
+    pub fn resume(pool: DbPool, operation: &AtomicOperation) -> Result<Self> {
+        let operation_type = AtomicOperationType::from_str(&operation.operation_type).ok_or_else(|| {
+            ClearingError::Internal(format!("Unknown operation type: {}", operation.operation_type))
+        })?;
+        let checkpoint_manager = Arc::new(CheckpointManager::new(pool.clone()));
+
+        Ok(Self {
+            pool,
+            checkpoint_manager,
+            operation_id: operation.operation_id,
+            window_id: operation.window_id,
+            operation_type,
+            state: Arc::new(RwLock::new(AtomicState::from_str(&operation.state))),
+            checkpoint_counter: Arc::new(RwLock::new(0)),
+        })
+    }
+
     /// Get the operation ID
     pub fn operation_id(&self) -> Uuid {
         self.operation_id
@@ -82,15 +100,15 @@ impl AtomicOperationHandler {
         let mut state = self.state.write().await;
         *state = AtomicState::InProgress;
 
-        sqlx::query!(
+        sqlx::query(
             r#"
             UPDATE clearing_atomic_operations
             SET state = $1
             WHERE operation_id = $2
             "#,
-            AtomicState::InProgress.as_str(),
-            self.operation_id,
         )
+        .bind(AtomicState::InProgress.as_str())
+        .bind(self.operation_id)
         .execute(&self.pool)
         .await?;
 
@@ -127,15 +145,15 @@ impl AtomicOperationHandler {
         let mut state = self.state.write().await;
         *state = AtomicState::Committed;
 
-        sqlx::query!(
+        sqlx::query(
             r#"
             UPDATE clearing_atomic_operations
             SET state = $1, completed_at = NOW()
             WHERE operation_id = $2
             "#,
-            AtomicState::Committed.as_str(),
-            self.operation_id,
         )
+        .bind(AtomicState::Committed.as_str())
+        .bind(self.operation_id)
         .execute(&self.pool)
         .await?;
 
@@ -196,16 +214,16 @@ impl AtomicOperationHandler {
 
         *state = AtomicState::RolledBack;
 
-        sqlx::query!(
+        sqlx::query(
             r#"
             UPDATE clearing_atomic_operations
             SET state = $1, rolled_back_at = NOW(), rollback_reason = $2
             WHERE operation_id = $3
             "#,
-            AtomicState::RolledBack.as_str(),
-            reason,
-            self.operation_id,
         )
+        .bind(AtomicState::RolledBack.as_str())
+        .bind(&reason)
+        .bind(self.operation_id)
         .execute(&self.pool)
         .await?;
 
@@ -224,17 +242,17 @@ impl AtomicOperationHandler {
         let mut state = self.state.write().await;
         *state = AtomicState::Failed;
 
-        sqlx::query!(
+        sqlx::query(
             r#"
             UPDATE clearing_atomic_operations
             SET state = $1, error_message = $2, error_code = $3, completed_at = NOW()
             WHERE operation_id = $4
             "#,
-            AtomicState::Failed.as_str(),
-            error_message,
-            error_code,
-            self.operation_id,
         )
+        .bind(AtomicState::Failed.as_str())
+        .bind(&error_message)
+        .bind(&error_code)
+        .bind(self.operation_id)
         .execute(&self.pool)
         .await?;
 
@@ -1,5 +1,7 @@
+use crate::models::NettingMode;
 use serde::{Deserialize, Serialize};
 use std::env;
+use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -8,6 +10,7 @@ pub struct Config {
     pub nats: NatsConfig,
     pub clearing: ClearingConfig,
     pub clients: ClientsConfig,
+    pub auth: AuthConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,6 +41,22 @@ pub struct ClearingConfig {
     pub max_obligations_per_window: u32,
     pub auto_settle: bool,
     pub min_netting_efficiency: f64,
+    /// Netting mode of newly opened windows
+    pub netting_mode: NettingMode,
+    /// DelTran account participants pay into and are paid out of in multilateral mode
+    pub settlement_account_id: Option<Uuid>,
+    /// Whether newly opened windows offset opposing exposures in different currencies
+    pub cross_currency_netting: bool,
+    /// Oldest FX quote, relative to the window cutoff, cross-currency netting converts at
+    pub max_fx_rate_age_seconds: i64,
+}
+
+/// JWT checked on admin operations, over REST and gRPC alike
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthConfig {
+    pub jwt_secret: String,
+    /// Role a token needs for admin operations
+    pub admin_role: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -57,6 +76,29 @@ impl Config {
         let nats_url = env::var("NATS_URL")
             .unwrap_or_else(|_| "nats://nats:4222".to_string());
 
+        let netting_mode = match env::var("NETTING_MODE") {
+            Ok(mode) => mode
+                .parse::<NettingMode>()
+                .map_err(|_| format!("Invalid NETTING_MODE: {}", mode))?,
+            Err(_) => NettingMode::Bilateral,
+        };
+        let settlement_account_id: Option<Uuid> = env::var("DELTRAN_SETTLEMENT_ACCOUNT_ID")
+            .ok()
+            .map(|id| id.parse())
+            .transpose()?;
+        if netting_mode == NettingMode::Multilateral && settlement_account_id.is_none() {
+            return Err("NETTING_MODE=Multilateral needs DELTRAN_SETTLEMENT_ACCOUNT_ID".into());
+        }
+        let cross_currency_netting = match env::var("CROSS_CURRENCY_NETTING") {
+            Ok(enabled) => enabled
+                .parse()
+                .map_err(|_| format!("Invalid CROSS_CURRENCY_NETTING: {}", enabled))?,
+            Err(_) => false,
+        };
+        if cross_currency_netting && netting_mode == NettingMode::Multilateral {
+            return Err("CROSS_CURRENCY_NETTING is only supported with NETTING_MODE=Bilateral".into());
+        }
+
         Ok(Config {
             server: ServerConfig {
                 http_port: env::var("HTTP_PORT")
@@ -84,6 +126,12 @@ impl Config {
                 max_obligations_per_window: 10000,
                 auto_settle: true,
                 min_netting_efficiency: 0.5,
+                netting_mode,
+                settlement_account_id,
+                cross_currency_netting,
+                max_fx_rate_age_seconds: env::var("FX_MAX_RATE_AGE_SECONDS")
+                    .unwrap_or_else(|_| "3600".to_string())
+                    .parse()?,
             },
             clients: ClientsConfig {
                 obligation_engine_url: env::var("OBLIGATION_ENGINE_URL")
@@ -93,6 +141,12 @@ impl Config {
                 risk_engine_url: env::var("RISK_ENGINE_URL")
                     .unwrap_or_else(|_| "http://risk-engine:8084".to_string()),
             },
+            auth: AuthConfig {
+                jwt_secret: env::var("JWT_SECRET")
+                    .unwrap_or_else(|_| "deltran-secret-key-change-in-production".to_string()),
+                admin_role: env::var("ADMIN_ROLE")
+                    .unwrap_or_else(|_| "admin".to_string()),
+            },
         })
     }
 }
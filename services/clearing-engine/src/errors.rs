@@ -1,3 +1,5 @@
+use actix_web::{error::ResponseError, http::StatusCode, HttpResponse};
+use serde_json::json;
 use thiserror::Error;
 use uuid::Uuid;
 use rust_decimal::Decimal;
@@ -11,7 +13,7 @@ pub enum ClearingError {
     Nats(String),
 
     #[error("gRPC error: {0}")]
-    Grpc(#[from] tonic::Status),
+    Grpc(Box<tonic::Status>),
 
     #[error("HTTP client error: {0}")]
     HttpClient(#[from] reqwest::Error),
@@ -77,6 +79,9 @@ pub enum ClearingError {
         reason: String,
     },
 
+    #[error("Atomic operation not found: {0}")]
+    OperationNotFound(Uuid),
+
     #[error("Checkpoint not found: {checkpoint_name} for operation {operation_id}")]
     CheckpointNotFound {
         checkpoint_name: String,
@@ -122,6 +127,12 @@ pub enum ClearingError {
 
 pub type Result<T> = std::result::Result<T, ClearingError>;
 
+impl From<tonic::Status> for ClearingError {
+    fn from(status: tonic::Status) -> Self {
+        ClearingError::Grpc(Box::new(status))
+    }
+}
+
 impl From<async_nats::Error> for ClearingError {
     fn from(err: async_nats::Error) -> Self {
         ClearingError::Nats(err.to_string())
@@ -133,3 +144,68 @@ impl From<async_nats::jetstream::context::PublishError> for ClearingError {
         ClearingError::Nats(err.to_string())
     }
 }
+
+impl From<ClearingError> for tonic::Status {
+    fn from(err: ClearingError) -> Self {
+        match err {
+            ClearingError::Grpc(status) => *status,
+            ClearingError::WindowNotFound(_)
+            | ClearingError::OperationNotFound(_)
+            | ClearingError::ObligationNotFound(_)
+            | ClearingError::CheckpointNotFound { .. } => tonic::Status::not_found(err.to_string()),
+            ClearingError::InvalidWindowState { .. } => tonic::Status::failed_precondition(err.to_string()),
+            ClearingError::WindowAlreadyOpen => tonic::Status::already_exists(err.to_string()),
+            ClearingError::WindowLocked { .. } => tonic::Status::aborted(err.to_string()),
+            ClearingError::Validation(_) | ClearingError::InvalidCurrency(_) => {
+                tonic::Status::invalid_argument(err.to_string())
+            }
+            _ => tonic::Status::internal(err.to_string()),
+        }
+    }
+}
+
+impl ResponseError for ClearingError {
+    fn error_response(&self) -> HttpResponse {
+        let status_code = self.status_code();
+
+        HttpResponse::build(status_code).json(json!({
+            "error": {
+                "code": status_code.as_u16(),
+                "message": self.to_string(),
+                "type": self.error_type()
+            }
+        }))
+    }
+
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ClearingError::WindowNotFound(_)
+            | ClearingError::OperationNotFound(_)
+            | ClearingError::ObligationNotFound(_)
+            | ClearingError::CheckpointNotFound { .. } => StatusCode::NOT_FOUND,
+            ClearingError::InvalidWindowState { .. }
+            | ClearingError::WindowAlreadyOpen
+            | ClearingError::WindowLocked { .. } => StatusCode::CONFLICT,
+            ClearingError::Validation(_) | ClearingError::InvalidCurrency(_) => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl ClearingError {
+    fn error_type(&self) -> &str {
+        match self {
+            ClearingError::WindowNotFound(_)
+            | ClearingError::OperationNotFound(_)
+            | ClearingError::ObligationNotFound(_)
+            | ClearingError::CheckpointNotFound { .. } => "not_found",
+            ClearingError::InvalidWindowState { .. } => "invalid_window_state",
+            ClearingError::WindowAlreadyOpen => "window_already_open",
+            ClearingError::WindowLocked { .. } => "window_locked",
+            ClearingError::Validation(_) | ClearingError::InvalidCurrency(_) => "validation_error",
+            ClearingError::Database(_) | ClearingError::DatabaseError(_) => "database_error",
+            ClearingError::Nats(_) => "messaging_error",
+            _ => "internal_error",
+        }
+    }
+}
@@ -0,0 +1,658 @@
+// Clearing gRPC Server - ClearingService of proto/clearing.proto
+// Processing runs go through the WindowProcessor, which records them as atomic operations so
+// RollbackWindow can undo them and GetOperationStatus can report on them.
+
+use crate::config::AuthConfig;
+use crate::errors::ClearingError;
+use crate::middleware::auth::{authorize, AuthError};
+use crate::models::{
+    ClearingStatus, ClearingWindow, NetPosition as NetPositionRow, SettlementInstruction,
+    SettlementStatusChange, WindowEvent, WindowStatus,
+};
+use crate::processing::{self, WindowProcessor, WindowResult};
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::StreamExt;
+use tonic::service::Interceptor;
+use tonic::{Request, Response, Status};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+// Generated by build.rs into src/generated
+pub mod clearing {
+    include!("../generated/clearing.rs");
+}
+
+use clearing::clearing_service_server::ClearingService;
+use clearing::*;
+
+type ResponseStream<T> = Pin<Box<dyn tokio_stream::Stream<Item = Result<T, Status>> + Send>>;
+
+#[derive(Clone)]
+pub struct ClearingGrpcServer {
+    processor: Arc<WindowProcessor>,
+}
+
+/// Authenticates every ClearingService call with the JWT and admin role the REST admin API takes
+#[derive(Clone)]
+pub struct AuthInterceptor {
+    auth: AuthConfig,
+}
+
+impl AuthInterceptor {
+    pub fn new(auth: AuthConfig) -> Self {
+        Self { auth }
+    }
+}
+
+impl Interceptor for AuthInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        let authorization = request
+            .metadata()
+            .get("authorization")
+            .map(|value| value.to_str().unwrap_or(""));
+
+        match authorize(&self.auth.jwt_secret, authorization, Some(&self.auth.admin_role)) {
+            Ok(claims) => {
+                request.extensions_mut().insert(claims);
+                Ok(request)
+            }
+            Err(AuthError::Unauthorized(reason)) => Err(Status::unauthenticated(reason)),
+            Err(AuthError::Forbidden) => Err(Status::permission_denied("Insufficient role")),
+        }
+    }
+}
+
+impl ClearingGrpcServer {
+    pub fn new(processor: Arc<WindowProcessor>) -> Self {
+        Self { processor }
+    }
+
+    /// Requests name a region; this engine clears only the region of its WindowManager
+    fn check_region(&self, region: &str) -> Result<(), ClearingError> {
+        let own_region = self.processor.window_manager().region();
+        if region_matches(region, own_region) {
+            Ok(())
+        } else {
+            Err(ClearingError::Validation(format!(
+                "This clearing engine serves region {}, not {}",
+                own_region, region
+            )))
+        }
+    }
+
+    /// Full result of a processing run: totals plus the positions and instructions it persisted
+    async fn clearing_result(&self, result: &WindowResult) -> Result<ClearingResult, Status> {
+        let orchestrator = self.processor.orchestrator();
+        let (positions, instructions) = match result.status {
+            ClearingStatus::Success | ClearingStatus::PartialSuccess => (
+                orchestrator.net_positions(result.window_id).await?,
+                orchestrator.settlement_instructions(result.window_id).await?,
+            ),
+            _ => (vec![], vec![]),
+        };
+
+        Ok(clearing_result(result, &positions, &instructions))
+    }
+}
+
+#[tonic::async_trait]
+impl ClearingService for ClearingGrpcServer {
+    async fn get_current_window(
+        &self,
+        request: Request<GetCurrentWindowRequest>,
+    ) -> Result<Response<WindowResponse>, Status> {
+        let req = request.into_inner();
+        self.check_region(&req.region)?;
+
+        let response = match self.processor.window_manager().current_window().await? {
+            Some(window) => WindowResponse {
+                window: Some(window_to_proto(&window)),
+                success: true,
+                message: String::new(),
+            },
+            None => WindowResponse {
+                window: None,
+                success: false,
+                message: format!("No open window for region {}", self.processor.window_manager().region()),
+            },
+        };
+
+        Ok(Response::new(response))
+    }
+
+    async fn get_window_status(
+        &self,
+        request: Request<GetWindowStatusRequest>,
+    ) -> Result<Response<WindowStatusResponse>, Status> {
+        let window = self.processor.window_manager().get_window(request.into_inner().window_id).await?;
+
+        Ok(Response::new(WindowStatusResponse {
+            status: window.status.clone(),
+            obligations_count: window.obligations_count,
+            total_volume: window.total_gross_value.to_string(),
+            netted_volume: window.total_net_value.to_string(),
+            netting_efficiency: to_f64(window.netting_efficiency),
+            timestamp: Utc::now().to_rfc3339(),
+        }))
+    }
+
+    async fn force_close_window(
+        &self,
+        request: Request<ForceCloseWindowRequest>,
+    ) -> Result<Response<WindowCloseResult>, Status> {
+        let req = request.into_inner();
+        info!("Force closing window {}: {}", req.window_id, req.reason);
+
+        let closed_at = self.processor.window_manager().force_close_window(req.window_id, &req.reason).await?;
+
+        Ok(Response::new(WindowCloseResult {
+            success: true,
+            message: format!("Window {} closed", req.window_id),
+            window_id: req.window_id,
+            closed_at: closed_at.to_rfc3339(),
+        }))
+    }
+
+    async fn open_new_window(
+        &self,
+        request: Request<OpenNewWindowRequest>,
+    ) -> Result<Response<WindowResponse>, Status> {
+        let req = request.into_inner();
+        self.check_region(&req.region)?;
+
+        if let Some(current) = self.processor.window_manager().current_window().await? {
+            if current.status == WindowStatus::Open.as_str() {
+                return Err(ClearingError::WindowAlreadyOpen.into());
+            }
+        }
+
+        let window = self.processor.window_manager().open_window(req.emergency_mode).await?;
+        info!("Opened clearing window {} (emergency: {})", window.id, req.emergency_mode);
+
+        Ok(Response::new(WindowResponse {
+            window: Some(window_to_proto(&window)),
+            success: true,
+            message: format!("Window {} opened", window.id),
+        }))
+    }
+
+    async fn process_window(
+        &self,
+        request: Request<ProcessWindowRequest>,
+    ) -> Result<Response<ProcessWindowResponse>, Status> {
+        let req = request.into_inner();
+        let started_at = self.processor.start(req.window_id, req.force_settlement).await?;
+
+        Ok(Response::new(ProcessWindowResponse {
+            success: true,
+            message: format!("Processing of window {} started", req.window_id),
+            window_id: req.window_id,
+            processing_started_at: started_at.to_rfc3339(),
+        }))
+    }
+
+    async fn get_processing_result(
+        &self,
+        request: Request<GetProcessingResultRequest>,
+    ) -> Result<Response<ClearingResult>, Status> {
+        let window_id = request.into_inner().window_id;
+
+        let window = self.processor.window_manager().get_window(window_id).await?;
+        if window.status == WindowStatus::Processing.as_str() {
+            return Err(Status::unavailable(format!("Window {} is still processing", window_id)));
+        }
+        let result = processing::window_result(&window)
+            .map_err(|_| Status::not_found(format!("Window {} has not been processed", window_id)))?;
+
+        Ok(Response::new(self.clearing_result(&result).await?))
+    }
+
+    type StreamWindowUpdatesStream = ResponseStream<WindowUpdate>;
+
+    async fn stream_window_updates(
+        &self,
+        request: Request<StreamWindowRequest>,
+    ) -> Result<Response<Self::StreamWindowUpdatesStream>, Status> {
+        let region = request.into_inner().region;
+        let rx = self.processor.window_manager().subscribe();
+
+        let stream = BroadcastStream::new(rx).filter_map(move |event| match event {
+            Ok(event) if event_in_region(&event, &region) => {
+                Some(Ok(window_update(&event)))
+            }
+            Ok(_) => None,
+            Err(e) => {
+                warn!("Window update stream lagged: {}", e);
+                None
+            }
+        });
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    type StreamSettlementStatusStream = ResponseStream<SettlementStatusUpdate>;
+
+    async fn stream_settlement_status(
+        &self,
+        request: Request<StreamSettlementRequest>,
+    ) -> Result<Response<Self::StreamSettlementStatusStream>, Status> {
+        let window_id = request.into_inner().window_id;
+        let rx = self.processor.orchestrator().subscribe_settlements();
+
+        // window_id 0 streams every window
+        let stream = BroadcastStream::new(rx).filter_map(move |change| match change {
+            Ok(change) if window_id == 0 || change.window_id == window_id => {
+                Some(Ok(settlement_update(&change)))
+            }
+            Ok(_) => None,
+            Err(e) => {
+                warn!("Settlement status stream lagged: {}", e);
+                None
+            }
+        });
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    async fn trigger_emergency_clearing(
+        &self,
+        request: Request<EmergencyRequest>,
+    ) -> Result<Response<ClearingResult>, Status> {
+        let req = request.into_inner();
+        self.check_region(&req.region)?;
+        warn!("🚨 Emergency clearing requested for {}: {}", self.processor.window_manager().region(), req.reason);
+
+        let window = self
+            .processor
+            .window_manager()
+            .current_window()
+            .await?
+            .ok_or_else(|| Status::failed_precondition("No open window to clear"))?;
+
+        if window.status != WindowStatus::Closed.as_str() {
+            self.processor.window_manager().force_close_window(window.id, &req.reason).await?;
+        }
+
+        let max_obligations = (req.max_obligations > 0).then_some(req.max_obligations as i64);
+        let outcome = self.processor.process_now(window.id, true, max_obligations).await?;
+        let mut result = self.clearing_result(&outcome).await?;
+
+        // Keep accepting obligations while the emergency is handled
+        if let Err(e) = self.processor.window_manager().open_window(true).await {
+            error!("Failed to open a window after emergency clearing: {}", e);
+            result.errors.push(clearing_error("WindowOpenFailed", &e));
+        }
+
+        Ok(Response::new(result))
+    }
+
+    async fn rollback_window(
+        &self,
+        request: Request<RollbackRequest>,
+    ) -> Result<Response<RollbackResult>, Status> {
+        let req = request.into_inner();
+        let rolled_back = self.processor.rollback(req.window_id, &req.reason).await?;
+
+        Ok(Response::new(RollbackResult {
+            success: true,
+            message: format!("Rolled back {} operations of window {}", rolled_back.len(), req.window_id),
+            window_id: req.window_id,
+            rolled_back_at: Utc::now().to_rfc3339(),
+            operations_rolled_back: rolled_back.iter().map(Uuid::to_string).collect(),
+        }))
+    }
+
+    async fn get_operation_status(
+        &self,
+        request: Request<OperationStatusRequest>,
+    ) -> Result<Response<OperationStatusResponse>, Status> {
+        let operation_id = Uuid::parse_str(&request.into_inner().operation_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid operation_id: {}", e)))?;
+
+        let operation = self.processor.atomic_controller().get_operation(operation_id).await?;
+
+        Ok(Response::new(OperationStatusResponse {
+            operation_id: operation.operation_id.to_string(),
+            operation_type: operation.operation_type,
+            state: operation.state,
+            started_at: operation.started_at.to_rfc3339(),
+            completed_at: timestamp(operation.completed_at.or(operation.rolled_back_at)),
+            error_message: operation.error_message.unwrap_or_default(),
+        }))
+    }
+}
+
+/// Empty region means the engine's own region
+fn region_matches(requested: &str, region: &str) -> bool {
+    requested.is_empty() || requested.eq_ignore_ascii_case(region)
+}
+
+fn event_region(event: &WindowEvent) -> Option<&str> {
+    event.event_data.get("region").and_then(|r| r.as_str())
+}
+
+fn event_in_region(event: &WindowEvent, requested: &str) -> bool {
+    match event_region(event) {
+        Some(region) => region_matches(requested, region),
+        None => true,
+    }
+}
+
+fn to_f64(value: Decimal) -> f64 {
+    value.to_f64().unwrap_or(0.0)
+}
+
+fn timestamp(at: Option<DateTime<Utc>>) -> String {
+    at.map(|t| t.to_rfc3339()).unwrap_or_default()
+}
+
+fn window_to_proto(window: &ClearingWindow) -> Window {
+    let flag = |key: &str| window.metadata.get(key).and_then(|v| v.as_bool()).unwrap_or(false);
+
+    Window {
+        id: window.id,
+        start_time: window.start_time.to_rfc3339(),
+        end_time: window.end_time.to_rfc3339(),
+        status: window.status.clone(),
+        region: window.region.clone(),
+        total_obligations: window.obligations_count,
+        total_volume: window.total_gross_value.to_string(),
+        netted_volume: window.total_net_value.to_string(),
+        saved_amount: window.saved_amount.to_string(),
+        netting_efficiency: to_f64(window.netting_efficiency),
+        settlement_instructions: window
+            .settlement_instructions
+            .iter()
+            .flatten()
+            .map(Uuid::to_string)
+            .collect(),
+        metadata: Some(WindowMetadata {
+            cutoff_time: window.cutoff_time.to_rfc3339(),
+            grace_period_seconds: window.grace_period_seconds as i64,
+            max_obligations: window.metadata.get("max_obligations").and_then(|v| v.as_i64()).unwrap_or(0) as i32,
+            min_netting_efficiency: window.metadata.get("min_netting_efficiency").and_then(|v| v.as_f64()).unwrap_or(0.0),
+            auto_settle: flag("auto_settle"),
+            emergency_mode: flag("emergency_mode"),
+        }),
+        created_at: window.created_at.to_rfc3339(),
+        closed_at: timestamp(window.closed_at),
+        processed_at: timestamp(window.processed_at),
+    }
+}
+
+fn net_position_to_proto(position: &NetPositionRow) -> NetPosition {
+    NetPosition {
+        bank_pair: Some(BankPair {
+            bank_a: position.bank_a_id.to_string(),
+            bank_b: position.bank_b_id.to_string(),
+        }),
+        currency: position.currency.clone(),
+        gross_debit: position.gross_debit_a_to_b.to_string(),
+        gross_credit: position.gross_credit_b_to_a.to_string(),
+        net_amount: position.net_amount.to_string(),
+        direction: position.net_direction.clone(),
+        obligations_count: position.obligations_netted,
+        netting_ratio: to_f64(position.netting_ratio),
+    }
+}
+
+fn instruction_to_proto(instruction: &SettlementInstruction) -> SettlementInstructionProto {
+    SettlementInstructionProto {
+        id: instruction.id.to_string(),
+        window_id: instruction.window_id,
+        from_bank: instruction.payer_bank_id.to_string(),
+        to_bank: instruction.payee_bank_id.to_string(),
+        amount: instruction.amount.to_string(),
+        currency: instruction.currency.clone(),
+        instruction_type: instruction.instruction_type.clone(),
+        priority: instruction.priority,
+        deadline: instruction.deadline.to_rfc3339(),
+        metadata: instruction.instruction_data.to_string(),
+    }
+}
+
+fn clearing_result(
+    result: &WindowResult,
+    positions: &[NetPositionRow],
+    instructions: &[SettlementInstruction],
+) -> ClearingResult {
+    let status = match result.status {
+        ClearingStatus::Success => "Success",
+        ClearingStatus::PartialSuccess => "PartialSuccess",
+        ClearingStatus::Failed => "Failed",
+        ClearingStatus::RolledBack => "RolledBack",
+    };
+
+    ClearingResult {
+        window_id: result.window_id,
+        status: status.to_string(),
+        obligations_processed: result.obligations_count,
+        net_positions: positions.iter().map(net_position_to_proto).collect(),
+        settlement_instructions: instructions.iter().map(instruction_to_proto).collect(),
+        total_saved: result.saved_amount.to_string(),
+        efficiency: to_f64(result.efficiency_percent),
+        errors: result
+            .error
+            .iter()
+            .map(|message| clearing::ClearingError {
+                error_type: "ClearingFailed".to_string(),
+                message: message.clone(),
+                timestamp: timestamp(result.processed_at),
+                details: String::new(),
+            })
+            .collect(),
+        processing_time_ms: result.processing_time_ms as i64,
+    }
+}
+
+fn clearing_error(error_type: &str, err: &ClearingError) -> clearing::ClearingError {
+    clearing::ClearingError {
+        error_type: error_type.to_string(),
+        message: err.to_string(),
+        timestamp: Utc::now().to_rfc3339(),
+        details: format!("{:?}", err),
+    }
+}
+
+fn window_update(event: &WindowEvent) -> WindowUpdate {
+    let mut metadata = HashMap::new();
+    for (key, value) in [("old_status", &event.old_status), ("new_status", &event.new_status), ("triggered_by", &event.triggered_by)] {
+        if let Some(value) = value {
+            metadata.insert(key.to_string(), value.clone());
+        }
+    }
+    if let Some(fields) = event.event_data.as_object() {
+        for (key, value) in fields {
+            let value = value.as_str().map(str::to_string).unwrap_or_else(|| value.to_string());
+            metadata.insert(key.clone(), value);
+        }
+    }
+
+    WindowUpdate {
+        window_id: event.window_id,
+        event_type: event.event_type.clone(),
+        timestamp: event.created_at.to_rfc3339(),
+        details: match &event.old_status {
+            Some(old) => format!("{} -> {}", old, event.new_status.as_deref().unwrap_or("?")),
+            None => format!("Window {}", event.event_type.to_lowercase()),
+        },
+        metadata,
+    }
+}
+
+/// Settlement engine statuses (PENDING, EXECUTING, ...) in the wording of the proto
+fn settlement_status_label(status: &str) -> &'static str {
+    match status.to_ascii_uppercase().as_str() {
+        "EXECUTING" | "IN_PROGRESS" | "INPROGRESS" => "InProgress",
+        "COMPLETED" | "SETTLED" => "Completed",
+        "FAILED" | "CANCELLED" | "REJECTED" => "Failed",
+        _ => "Pending",
+    }
+}
+
+fn settlement_update(change: &SettlementStatusChange) -> SettlementStatusUpdate {
+    let details = match change.settlement_id {
+        Some(settlement_id) if change.details.is_empty() => format!("settlement {}", settlement_id),
+        Some(settlement_id) => format!("settlement {}: {}", settlement_id, change.details),
+        None => change.details.clone(),
+    };
+
+    SettlementStatusUpdate {
+        window_id: change.window_id,
+        settlement_id: change.instruction_id.to_string(),
+        status: settlement_status_label(&change.status).to_string(),
+        timestamp: change.occurred_at.to_rfc3339(),
+        details,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn window(metadata: serde_json::Value) -> ClearingWindow {
+        let now = Utc::now();
+        ClearingWindow {
+            id: 1_700_000_000,
+            window_name: "CLEAR_Global_20231114_2213".to_string(),
+            start_time: now,
+            end_time: now + chrono::Duration::hours(6),
+            cutoff_time: now + chrono::Duration::hours(5),
+            status: WindowStatus::Open.as_str().to_string(),
+            region: "Global".to_string(),
+            transactions_count: 0,
+            obligations_count: 12,
+            total_gross_value: Decimal::new(1_000_000, 2),
+            total_net_value: Decimal::new(250_000, 2),
+            saved_amount: Decimal::new(750_000, 2),
+            netting_efficiency: Decimal::new(7500, 2),
+            settlement_instructions: Some(vec![Uuid::nil()]),
+            metadata,
+            created_at: now,
+            closed_at: None,
+            processed_at: None,
+            completed_at: None,
+            grace_period_seconds: 1800,
+            grace_period_started: None,
+        }
+    }
+
+    #[test]
+    fn test_auth_interceptor() {
+        use crate::middleware::auth::Claims;
+        use jsonwebtoken::{encode, EncodingKey, Header};
+
+        let mut interceptor = AuthInterceptor::new(AuthConfig {
+            jwt_secret: "secret".to_string(),
+            admin_role: "admin".to_string(),
+        });
+        let request = |role: Option<&str>| {
+            let mut request = Request::new(());
+            if let Some(role) = role {
+                let claims = Claims {
+                    sub: "ops@deltran".to_string(),
+                    role: role.to_string(),
+                    permissions: vec![],
+                    exp: (Utc::now().timestamp() + 600) as usize,
+                };
+                let jwt = encode(&Header::default(), &claims, &EncodingKey::from_secret(b"secret")).unwrap();
+                request.metadata_mut().insert("authorization", format!("Bearer {}", jwt).parse().unwrap());
+            }
+            request
+        };
+
+        let authorized = interceptor.call(request(Some("admin"))).unwrap();
+        assert_eq!(authorized.extensions().get::<Claims>().unwrap().sub, "ops@deltran");
+        assert_eq!(interceptor.call(request(Some("viewer"))).unwrap_err().code(), tonic::Code::PermissionDenied);
+        assert_eq!(interceptor.call(request(None)).unwrap_err().code(), tonic::Code::Unauthenticated);
+    }
+
+    #[test]
+    fn test_region_matches() {
+        assert!(region_matches("", "Global"));
+        assert!(region_matches("global", "Global"));
+        assert!(!region_matches("ADGM", "Global"));
+    }
+
+    #[test]
+    fn test_window_to_proto() {
+        let proto = window_to_proto(&window(json!({ "emergency_mode": true })));
+
+        assert_eq!(proto.total_obligations, 12);
+        assert_eq!(proto.total_volume, "10000.00");
+        assert_eq!(proto.netting_efficiency, 75.0);
+        assert_eq!(proto.settlement_instructions, vec![Uuid::nil().to_string()]);
+        assert_eq!(proto.closed_at, "");
+
+        let metadata = proto.metadata.unwrap();
+        assert!(metadata.emergency_mode);
+        assert!(!metadata.auto_settle);
+        assert_eq!(metadata.grace_period_seconds, 1800);
+    }
+
+    #[test]
+    fn test_window_update() {
+        let event = WindowEvent {
+            id: Uuid::new_v4(),
+            window_id: 42,
+            event_type: "Closed".to_string(),
+            old_status: Some("Open".to_string()),
+            new_status: Some("Closed".to_string()),
+            event_data: json!({ "region": "ADGM", "forced": true }),
+            triggered_by: Some("clearing-engine".to_string()),
+            created_at: Utc::now(),
+        };
+
+        assert_eq!(event_region(&event), Some("ADGM"));
+        assert!(event_in_region(&event, "adgm") && event_in_region(&event, ""));
+        assert!(!event_in_region(&event, "Global"));
+
+        let update = window_update(&event);
+        assert_eq!((update.window_id, update.event_type.as_str()), (42, "Closed"));
+        assert_eq!(update.details, "Open -> Closed");
+        assert_eq!(update.metadata["region"], "ADGM");
+        assert_eq!(update.metadata["forced"], "true");
+        assert_eq!(update.metadata["old_status"], "Open");
+    }
+
+    #[test]
+    fn test_settlement_update() {
+        assert_eq!(settlement_status_label("PENDING"), "Pending");
+        assert_eq!(settlement_status_label("EXECUTING"), "InProgress");
+        assert_eq!(settlement_status_label("Completed"), "Completed");
+        assert_eq!(settlement_status_label("CANCELLED"), "Failed");
+
+        let settlement_id = Uuid::new_v4();
+        let change = SettlementStatusChange {
+            window_id: 7,
+            instruction_id: Uuid::new_v4(),
+            settlement_id: Some(settlement_id),
+            status: "FAILED".to_string(),
+            details: "insufficient funds".to_string(),
+            occurred_at: Utc::now(),
+        };
+
+        let update = settlement_update(&change);
+        assert_eq!(update.settlement_id, change.instruction_id.to_string());
+        assert_eq!(update.status, "Failed");
+        assert_eq!(update.details, format!("settlement {}: insufficient funds", settlement_id));
+    }
+
+    #[test]
+    fn test_errors_map_to_status_codes() {
+        assert_eq!(Status::from(ClearingError::WindowNotFound(1)).code(), tonic::Code::NotFound);
+        assert_eq!(Status::from(ClearingError::WindowAlreadyOpen).code(), tonic::Code::AlreadyExists);
+        assert_eq!(
+            Status::from(ClearingError::InvalidWindowState { expected: "Open".into(), actual: "Closed".into() }).code(),
+            tonic::Code::FailedPrecondition
+        );
+        assert_eq!(Status::from(ClearingError::DatabaseError("down".into())).code(), tonic::Code::Internal);
+    }
+}
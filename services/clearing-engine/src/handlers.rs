@@ -0,0 +1,284 @@
+// REST API - clearing windows, their netting results and admin operations
+// Everything is read from the database via the WindowProcessor, the same state the gRPC API serves.
+
+use crate::config::AuthConfig;
+use crate::errors::ClearingError;
+use crate::middleware::auth::JwtAuth;
+use crate::models::NettingMode;
+use crate::processing::WindowProcessor;
+use actix_web::{web, HttpResponse};
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+const DEFAULT_WINDOWS_LIMIT: i64 = 20;
+const MAX_WINDOWS_LIMIT: i64 = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct ListWindowsQuery {
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AdminReasonRequest {
+    pub reason: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NettingModeRequest {
+    pub netting_mode: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CrossCurrencyRequest {
+    pub enabled: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ReprocessRequest {
+    #[serde(default)]
+    pub force_settlement: bool,
+}
+
+/// List recent windows of this engine's region, newest first
+pub async fn get_windows(
+    processor: web::Data<Arc<WindowProcessor>>,
+    query: web::Query<ListWindowsQuery>,
+) -> Result<HttpResponse, ClearingError> {
+    let windows = processor.window_manager().list_windows(page_limit(query.limit)).await?;
+    Ok(HttpResponse::Ok().json(json!({ "windows": windows })))
+}
+
+/// Window currently accepting (or closing for) obligations
+pub async fn get_current_window(
+    processor: web::Data<Arc<WindowProcessor>>,
+) -> Result<HttpResponse, ClearingError> {
+    match processor.window_manager().current_window().await? {
+        Some(window) => Ok(HttpResponse::Ok().json(json!({ "window": window }))),
+        None => Ok(HttpResponse::NotFound().json(json!({
+            "error": {
+                "code": 404,
+                "message": format!("No open window for region {}", processor.window_manager().region()),
+                "type": "not_found"
+            }
+        }))),
+    }
+}
+
+pub async fn get_window(
+    processor: web::Data<Arc<WindowProcessor>>,
+    window_id: web::Path<i64>,
+) -> Result<HttpResponse, ClearingError> {
+    let window = processor.window_manager().get_window(*window_id).await?;
+    Ok(HttpResponse::Ok().json(window))
+}
+
+/// Netting result of a processed window: gross, net, efficiency, cycles eliminated
+pub async fn get_window_result(
+    processor: web::Data<Arc<WindowProcessor>>,
+    window_id: web::Path<i64>,
+) -> Result<HttpResponse, ClearingError> {
+    let result = processor.result(*window_id).await?;
+    Ok(HttpResponse::Ok().json(result))
+}
+
+pub async fn get_window_positions(
+    processor: web::Data<Arc<WindowProcessor>>,
+    window_id: web::Path<i64>,
+) -> Result<HttpResponse, ClearingError> {
+    // 404 for unknown windows rather than an empty list
+    let window = processor.window_manager().get_window(*window_id).await?;
+    let positions = processor.orchestrator().net_positions(window.id).await?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "window_id": window.id,
+        "count": positions.len(),
+        "net_positions": positions
+    })))
+}
+
+pub async fn get_window_instructions(
+    processor: web::Data<Arc<WindowProcessor>>,
+    window_id: web::Path<i64>,
+) -> Result<HttpResponse, ClearingError> {
+    let window = processor.window_manager().get_window(*window_id).await?;
+    let instructions = processor.orchestrator().settlement_instructions(window.id).await?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "window_id": window.id,
+        "count": instructions.len(),
+        "settlement_instructions": instructions
+    })))
+}
+
+/// Totals over all windows of this engine's region
+pub async fn get_metrics(
+    processor: web::Data<Arc<WindowProcessor>>,
+) -> Result<HttpResponse, ClearingError> {
+    let stats = processor.window_manager().stats().await?;
+    Ok(HttpResponse::Ok().json(stats))
+}
+
+/// Admin: close a window now, skipping its grace period
+pub async fn force_close_window(
+    processor: web::Data<Arc<WindowProcessor>>,
+    window_id: web::Path<i64>,
+    request: web::Json<AdminReasonRequest>,
+) -> Result<HttpResponse, ClearingError> {
+    let reason = required_reason(&request.reason)?;
+    info!("Force closing window {} via admin API: {}", window_id, reason);
+
+    let closed_at = processor.window_manager().force_close_window(*window_id, reason).await?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "window_id": *window_id,
+        "status": "Closed",
+        "closed_at": closed_at
+    })))
+}
+
+/// Admin: net a closed window, or net a Failed/RolledBack window again
+pub async fn reprocess_window(
+    processor: web::Data<Arc<WindowProcessor>>,
+    window_id: web::Path<i64>,
+    request: Option<web::Json<ReprocessRequest>>,
+) -> Result<HttpResponse, ClearingError> {
+    let force_settlement = request.map(|r| r.force_settlement).unwrap_or(false);
+    let started_at = processor.start(*window_id, force_settlement).await?;
+
+    Ok(HttpResponse::Accepted().json(json!({
+        "window_id": *window_id,
+        "status": "Processing",
+        "processing_started_at": started_at
+    })))
+}
+
+/// Admin: net a window bilaterally or multilaterally against the settlement account
+pub async fn set_netting_mode(
+    processor: web::Data<Arc<WindowProcessor>>,
+    window_id: web::Path<i64>,
+    request: web::Json<NettingModeRequest>,
+) -> Result<HttpResponse, ClearingError> {
+    let netting_mode: NettingMode = request.netting_mode.parse()?;
+    info!("Window {} nets {} via admin API", window_id, netting_mode.as_str());
+
+    let window = processor.set_netting_mode(*window_id, netting_mode).await?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "window_id": window.id,
+        "status": window.status,
+        "netting_mode": window.netting_mode()
+    })))
+}
+
+/// Admin: offset opposing exposures in different currencies at the rates frozen at cutoff
+pub async fn set_cross_currency(
+    processor: web::Data<Arc<WindowProcessor>>,
+    window_id: web::Path<i64>,
+    request: web::Json<CrossCurrencyRequest>,
+) -> Result<HttpResponse, ClearingError> {
+    info!("Window {} cross-currency netting set to {} via admin API", window_id, request.enabled);
+
+    let window = processor.set_cross_currency(*window_id, request.enabled).await?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "window_id": window.id,
+        "status": window.status,
+        "cross_currency": window.cross_currency(),
+        "fx_snapshot": window.fx_snapshot()
+    })))
+}
+
+/// Admin: roll back the processing of a window and discard its positions and instructions
+pub async fn rollback_window(
+    processor: web::Data<Arc<WindowProcessor>>,
+    window_id: web::Path<i64>,
+    request: web::Json<AdminReasonRequest>,
+) -> Result<HttpResponse, ClearingError> {
+    let reason = required_reason(&request.reason)?;
+    warn!("Rollback of window {} requested via admin API: {}", window_id, reason);
+
+    let rolled_back = processor.rollback(*window_id, reason).await?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "window_id": *window_id,
+        "status": "RolledBack",
+        "operations_rolled_back": rolled_back
+    })))
+}
+
+fn page_limit(limit: Option<i64>) -> i64 {
+    limit.unwrap_or(DEFAULT_WINDOWS_LIMIT).clamp(1, MAX_WINDOWS_LIMIT)
+}
+
+/// Admin operations are audited by their reason
+fn required_reason(reason: &str) -> Result<&str, ClearingError> {
+    let reason = reason.trim();
+    if reason.is_empty() {
+        return Err(ClearingError::Validation("reason is required".to_string()));
+    }
+    Ok(reason)
+}
+
+/// Clearing API; admin operations need a JWT with the admin role
+pub fn configure_routes(cfg: &mut web::ServiceConfig, auth: &AuthConfig) {
+    cfg.service(
+        web::scope("/api/v1/clearing")
+            .route("/windows", web::get().to(get_windows))
+            .route("/windows/current", web::get().to(get_current_window))
+            .route("/windows/{id}", web::get().to(get_window))
+            .route("/windows/{id}/result", web::get().to(get_window_result))
+            .route("/windows/{id}/positions", web::get().to(get_window_positions))
+            .route("/windows/{id}/instructions", web::get().to(get_window_instructions))
+            .route("/metrics", web::get().to(get_metrics))
+            .service(
+                web::scope("/admin")
+                    .wrap(JwtAuth::new(auth.jwt_secret.clone()).require_role(auth.admin_role.clone()))
+                    .route("/windows/{id}/close", web::post().to(force_close_window))
+                    .route("/windows/{id}/netting-mode", web::put().to(set_netting_mode))
+                    .route("/windows/{id}/cross-currency", web::put().to(set_cross_currency))
+                    .route("/windows/{id}/reprocess", web::post().to(reprocess_window))
+                    .route("/windows/{id}/rollback", web::post().to(rollback_window)),
+            ),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{body::to_bytes, ResponseError};
+
+    #[test]
+    fn test_page_limit() {
+        assert_eq!(page_limit(None), DEFAULT_WINDOWS_LIMIT);
+        assert_eq!(page_limit(Some(0)), 1);
+        assert_eq!(page_limit(Some(500)), MAX_WINDOWS_LIMIT);
+    }
+
+    #[test]
+    fn test_required_reason() {
+        assert_eq!(required_reason("  settlement outage ").unwrap(), "settlement outage");
+        assert!(matches!(required_reason(" "), Err(ClearingError::Validation(_))));
+    }
+
+    #[test]
+    fn test_netting_mode_parse() {
+        assert_eq!("MULTILATERAL".parse::<NettingMode>().unwrap(), NettingMode::Multilateral);
+        assert!(matches!("trilateral".parse::<NettingMode>(), Err(ClearingError::Validation(_))));
+    }
+
+    #[actix_web::test]
+    async fn test_error_responses() {
+        assert_eq!(ClearingError::WindowNotFound(1).status_code().as_u16(), 404);
+        assert_eq!(ClearingError::WindowAlreadyOpen.status_code().as_u16(), 409);
+        assert_eq!(ClearingError::Validation("bad".into()).status_code().as_u16(), 400);
+        assert_eq!(ClearingError::DatabaseError("down".into()).status_code().as_u16(), 500);
+
+        let err = ClearingError::InvalidWindowState { expected: "Closed".into(), actual: "Open".into() };
+        let body = to_bytes(err.error_response().into_body()).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"]["code"], 409);
+        assert_eq!(body["error"]["type"], "invalid_window_state");
+    }
+}
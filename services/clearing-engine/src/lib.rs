@@ -1,6 +1,7 @@
 // Clearing Engine Library
 // Production-ready clearing and netting implementation
 
+pub mod atomic;
 pub mod cache;
 pub mod config;
 pub mod consensus;
@@ -9,9 +10,13 @@ pub mod errors;
 pub mod models;
 pub mod netting;
 pub mod window;
+pub mod grpc;
+pub mod handlers;
 pub mod orchestrator;
+pub mod processing;
 pub mod iso20022;
 pub mod metrics;
+pub mod middleware;
 pub mod nats_consumer;
 
 // Re-exports
@@ -20,6 +25,9 @@ pub use models::*;
 pub use netting::NettingEngine;
 pub use window::{WindowManager, WindowConfig};
 pub use orchestrator::{ClearingOrchestrator, ClearingResult};
+pub use atomic::AtomicController;
+pub use grpc::ClearingGrpcServer;
+pub use processing::{WindowProcessor, WindowResult};
 
 /// Version information
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
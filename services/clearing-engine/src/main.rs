@@ -1,20 +1,16 @@
 use actix_web::{web, App, HttpResponse, HttpServer, Responder};
-use chrono::Utc;
 use prometheus::{Encoder, TextEncoder};
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
+use std::sync::Arc;
 use tracing::{info, error};
 use tracing_subscriber;
-use clearing_engine::nats_consumer;
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct ClearingWindow {
-    id: i64,
-    status: String,
-    start_time: String,
-    end_time: String,
-    obligations_count: i32,
-    total_volume: String,
-}
+use clearing_engine::config::Config;
+use clearing_engine::grpc::server::clearing::clearing_service_server::ClearingServiceServer;
+use clearing_engine::grpc::AuthInterceptor;
+use clearing_engine::{database, handlers, nats_consumer};
+use clearing_engine::{
+    AtomicController, ClearingGrpcServer, ClearingOrchestrator, WindowConfig, WindowManager, WindowProcessor,
+};
 
 #[derive(Debug, Serialize)]
 struct HealthResponse {
@@ -23,18 +19,6 @@ struct HealthResponse {
     version: String,
 }
 
-#[derive(Debug, Serialize)]
-struct WindowsResponse {
-    windows: Vec<ClearingWindow>,
-}
-
-#[derive(Debug, Serialize)]
-struct MetricsResponse {
-    total_windows: u64,
-    active_windows: u64,
-    netting_efficiency: f64,
-}
-
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     // Initialize logging
@@ -63,15 +47,68 @@ async fn main() -> std::io::Result<()> {
     }
     info!("✅ NATS consumer started successfully");
 
+    // Window state shared by the REST and gRPC APIs
+    let config = Config::from_env()
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))?;
+    let db_pool = database::create_pool(&config.database)
+        .await
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+    let nats_client = async_nats::connect(&nats_url)
+        .await
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+
+    let window_config = WindowConfig {
+        netting_mode: config.clearing.netting_mode,
+        cross_currency: config.clearing.cross_currency_netting,
+        ..WindowConfig::default()
+    };
+    let window_manager = Arc::new(WindowManager::new(Arc::new(db_pool.clone()), window_config));
+    let mut orchestrator = ClearingOrchestrator::new(
+        window_manager.clone(),
+        Arc::new(db_pool.clone()),
+        Some(nats_client.clone()),
+    )
+    .with_max_fx_rate_age(chrono::Duration::seconds(config.clearing.max_fx_rate_age_seconds));
+    if let Some(account) = config.clearing.settlement_account_id {
+        orchestrator = orchestrator.with_settlement_account(account);
+    }
+    let orchestrator = Arc::new(orchestrator);
+    let atomic_controller = Arc::new(AtomicController::new(db_pool));
+
+    if let Err(e) = nats_consumer::start_settlement_status_consumer(nats_client, orchestrator.clone()).await {
+        error!("Failed to start settlement status consumer: {}", e);
+        return Err(std::io::Error::new(std::io::ErrorKind::Other, e));
+    }
+
+    let processor = Arc::new(WindowProcessor::new(window_manager, orchestrator, atomic_controller));
+
+    // ClearingService gRPC API - window management, processing and status streams for ops tools
+    let grpc_server = ClearingGrpcServer::new(processor.clone());
+    let grpc_auth = AuthInterceptor::new(config.auth.clone());
+    let grpc_addr = format!("0.0.0.0:{}", config.server.grpc_port)
+        .parse()
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+
+    tokio::spawn(async move {
+        info!("🚀 Clearing gRPC server starting on {}", grpc_addr);
+        if let Err(e) = tonic::transport::Server::builder()
+            .add_service(ClearingServiceServer::with_interceptor(grpc_server, grpc_auth))
+            .serve(grpc_addr)
+            .await
+        {
+            error!("gRPC server error: {}", e);
+        }
+    });
+
     let bind_address = format!("0.0.0.0:{}", service_port);
+    let auth = config.auth.clone();
 
-    HttpServer::new(|| {
+    HttpServer::new(move || {
         App::new()
+            .app_data(web::Data::new(processor.clone()))
             .route("/health", web::get().to(health_check))
             .route("/metrics", web::get().to(prometheus_metrics))
-            .route("/api/v1/clearing/windows", web::get().to(get_windows))
-            .route("/api/v1/clearing/windows/current", web::get().to(get_current_window))
-            .route("/api/v1/clearing/metrics", web::get().to(get_metrics))
+            .configure(|cfg| handlers::configure_routes(cfg, &auth))
     })
     .bind(&bind_address)?
     .run()
@@ -86,35 +123,6 @@ async fn health_check() -> impl Responder {
     })
 }
 
-async fn get_windows() -> impl Responder {
-    HttpResponse::Ok().json(WindowsResponse {
-        windows: vec![],
-    })
-}
-
-async fn get_current_window() -> impl Responder {
-    let window = ClearingWindow {
-        id: Utc::now().timestamp(),
-        status: "Open".to_string(),
-        start_time: Utc::now().to_rfc3339(),
-        end_time: (Utc::now() + chrono::Duration::hours(6)).to_rfc3339(),
-        obligations_count: 0,
-        total_volume: "0.00".to_string(),
-    };
-
-    HttpResponse::Ok().json(serde_json::json!({
-        "window": window
-    }))
-}
-
-async fn get_metrics() -> impl Responder {
-    HttpResponse::Ok().json(MetricsResponse {
-        total_windows: 0,
-        active_windows: 0,
-        netting_efficiency: 0.0,
-    })
-}
-
 async fn prometheus_metrics() -> impl Responder {
     let encoder = TextEncoder::new();
     let metric_families = prometheus::gather();
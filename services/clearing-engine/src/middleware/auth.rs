@@ -1,6 +1,6 @@
 use actix_web::{
     dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
-    Error, HttpMessage, HttpResponse,
+    Error, HttpMessage,
 };
 use futures_util::future::LocalBoxFuture;
 use jsonwebtoken::{decode, DecodingKey, Validation, Algorithm};
@@ -18,11 +18,59 @@ pub struct Claims {
 
 pub struct JwtAuth {
     secret: String,
+    required_role: Option<String>,
 }
 
 impl JwtAuth {
     pub fn new(secret: String) -> Self {
-        Self { secret }
+        Self { secret, required_role: None }
+    }
+
+    /// Only let through tokens whose role is `role`; others are rejected with 403
+    pub fn require_role(mut self, role: impl Into<String>) -> Self {
+        self.required_role = Some(role.into());
+        self
+    }
+}
+
+/// Why a request was not authorized
+#[derive(Debug, Clone, PartialEq)]
+pub enum AuthError {
+    /// No usable bearer token
+    Unauthorized(&'static str),
+    /// Valid token without the required role
+    Forbidden,
+}
+
+/// Validate the `Authorization: Bearer <jwt>` header value and, if given, the token's role.
+/// Shared by the REST middleware and the gRPC interceptor.
+pub fn authorize(
+    secret: &str,
+    authorization: Option<&str>,
+    required_role: Option<&str>,
+) -> Result<Claims, AuthError> {
+    let authorization = authorization.ok_or(AuthError::Unauthorized("Missing Authorization header"))?;
+    let token = authorization
+        .strip_prefix("Bearer ")
+        .ok_or(AuthError::Unauthorized("Invalid auth header format"))?;
+
+    let claims = decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(secret.as_bytes()),
+        &Validation::new(Algorithm::HS256),
+    )
+    .map_err(|err| {
+        tracing::warn!("JWT validation failed: {:?}", err);
+        AuthError::Unauthorized("Invalid or expired token")
+    })?
+    .claims;
+
+    match required_role {
+        Some(role) if claims.role != role => {
+            tracing::warn!("{} with role {} denied, {} required", claims.sub, claims.role, role);
+            Err(AuthError::Forbidden)
+        }
+        _ => Ok(claims),
     }
 }
 
@@ -42,6 +90,7 @@ where
         ready(Ok(JwtAuthMiddleware {
             service: Rc::new(service),
             secret: self.secret.clone(),
+            required_role: self.required_role.clone(),
         }))
     }
 }
@@ -49,6 +98,7 @@ where
 pub struct JwtAuthMiddleware<S> {
     service: Rc<S>,
     secret: String,
+    required_role: Option<String>,
 }
 
 impl<S, B> Service<ServiceRequest> for JwtAuthMiddleware<S>
@@ -67,52 +117,56 @@ where
         // Skip auth for health and metrics endpoints
         if req.path() == "/health" || req.path() == "/metrics" {
             let fut = self.service.call(req);
-            return Box::pin(async move { fut.await });
+            return Box::pin(fut);
         }
 
-        // Extract token from Authorization header
-        let auth_header = req.headers().get("Authorization");
-
-        let token = match auth_header {
-            Some(value) => {
-                let auth_str = value.to_str().unwrap_or("");
-                if auth_str.starts_with("Bearer ") {
-                    &auth_str[7..]
-                } else {
-                    return Box::pin(async {
-                        Err(actix_web::error::ErrorUnauthorized("Invalid auth header format"))
-                    });
-                }
-            }
-            None => {
-                return Box::pin(async {
-                    Err(actix_web::error::ErrorUnauthorized("Missing Authorization header"))
-                });
-            }
-        };
-
-        // Validate token
-        let secret = self.secret.clone();
-        let validation = Validation::new(Algorithm::HS256);
+        let authorization = req
+            .headers()
+            .get("Authorization")
+            .map(|value| value.to_str().unwrap_or(""));
 
-        match decode::<Claims>(
-            token,
-            &DecodingKey::from_secret(secret.as_bytes()),
-            &validation,
-        ) {
-            Ok(token_data) => {
+        match authorize(&self.secret, authorization, self.required_role.as_deref()) {
+            Ok(claims) => {
                 // Add user info to request extensions for handlers to access
-                req.extensions_mut().insert(token_data.claims.clone());
+                req.extensions_mut().insert(claims);
 
                 let fut = self.service.call(req);
-                Box::pin(async move { fut.await })
+                Box::pin(fut)
             }
-            Err(err) => {
-                tracing::warn!("JWT validation failed: {:?}", err);
-                Box::pin(async {
-                    Err(actix_web::error::ErrorUnauthorized("Invalid or expired token"))
-                })
+            Err(AuthError::Unauthorized(reason)) => {
+                Box::pin(async move { Err(actix_web::error::ErrorUnauthorized(reason)) })
+            }
+            Err(AuthError::Forbidden) => {
+                Box::pin(async { Err(actix_web::error::ErrorForbidden("Insufficient role")) })
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+
+    fn token(role: &str) -> String {
+        let claims = Claims {
+            sub: "ops@deltran".to_string(),
+            role: role.to_string(),
+            permissions: vec![],
+            exp: (chrono::Utc::now().timestamp() + 600) as usize,
+        };
+        let jwt = encode(&Header::default(), &claims, &EncodingKey::from_secret(b"secret")).unwrap();
+        format!("Bearer {}", jwt)
+    }
+
+    #[test]
+    fn test_authorize() {
+        let admin = token("admin");
+        assert_eq!(authorize("secret", Some(&admin), Some("admin")).unwrap().sub, "ops@deltran");
+        assert!(matches!(authorize("secret", Some(&token("viewer")), Some("admin")), Err(AuthError::Forbidden)));
+        assert!(authorize("secret", Some(&token("viewer")), None).is_ok());
+        assert!(matches!(authorize("other", Some(&admin), None), Err(AuthError::Unauthorized(_))));
+        assert!(matches!(authorize("secret", None, None), Err(AuthError::Unauthorized(_))));
+        assert!(matches!(authorize("secret", Some("Basic abc"), None), Err(AuthError::Unauthorized(_))));
+    }
+}
@@ -1,6 +1,6 @@
 use actix_web::{
     dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
-    Error, HttpResponse, http::StatusCode,
+    Error,
 };
 use futures_util::future::LocalBoxFuture;
 use governor::{
@@ -66,14 +66,14 @@ where
         // Skip rate limiting for health endpoint
         if req.path() == "/health" {
             let fut = self.service.call(req);
-            return Box::pin(async move { fut.await });
+            return Box::pin(fut);
         }
 
         // Check rate limit
         match self.limiter.check() {
             Ok(_) => {
                 let fut = self.service.call(req);
-                Box::pin(async move { fut.await })
+                Box::pin(fut)
             }
             Err(_) => {
                 tracing::warn!("Rate limit exceeded for path: {}", req.path());
@@ -1,3 +1,5 @@
+use crate::errors::ClearingError;
+use crate::netting::cross_currency::FxSnapshot;
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
@@ -31,6 +33,32 @@ pub struct ClearingWindow {
     pub grace_period_started: Option<DateTime<Utc>>,
 }
 
+impl ClearingWindow {
+    /// Netting mode chosen for this window, bilateral unless its metadata says otherwise
+    pub fn netting_mode(&self) -> NettingMode {
+        self.metadata
+            .get("netting_mode")
+            .and_then(|m| m.as_str())
+            .and_then(|m| m.parse().ok())
+            .unwrap_or_default()
+    }
+
+    /// Whether opposing exposures in different currencies are offset when netting this window
+    pub fn cross_currency(&self) -> bool {
+        self.metadata
+            .get("cross_currency")
+            .and_then(|c| c.as_bool())
+            .unwrap_or(false)
+    }
+
+    /// FX rates frozen for this window the first time it was netted across currencies
+    pub fn fx_snapshot(&self) -> Option<FxSnapshot> {
+        self.metadata
+            .get("fx_snapshot")
+            .and_then(|s| serde_json::from_value(s.clone()).ok())
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum WindowStatus {
     Scheduled,
@@ -75,6 +103,41 @@ impl WindowStatus {
     }
 }
 
+/// How a window's obligations are settled
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum NettingMode {
+    /// One instruction per bank pair, payer to payee
+    #[default]
+    Bilateral,
+    /// One pay-in or pay-out per participant and currency against the DelTran settlement account
+    Multilateral,
+}
+
+impl NettingMode {
+    pub fn as_str(&self) -> &str {
+        match self {
+            NettingMode::Bilateral => "Bilateral",
+            NettingMode::Multilateral => "Multilateral",
+        }
+    }
+}
+
+impl std::str::FromStr for NettingMode {
+    type Err = ClearingError;
+
+    /// Case-insensitive
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "bilateral" => Ok(NettingMode::Bilateral),
+            "multilateral" => Ok(NettingMode::Multilateral),
+            _ => Err(ClearingError::Validation(format!(
+                "netting_mode must be Bilateral or Multilateral, got {}",
+                s
+            ))),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ClearingRegion {
     Global,
@@ -150,6 +213,18 @@ impl AtomicOperationType {
             AtomicOperationType::WindowOpen => "WindowOpen",
         }
     }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "WindowClose" => Some(AtomicOperationType::WindowClose),
+            "ObligationCollection" => Some(AtomicOperationType::ObligationCollection),
+            "NettingCalculation" => Some(AtomicOperationType::NettingCalculation),
+            "InstructionGeneration" => Some(AtomicOperationType::InstructionGeneration),
+            "SettlementInitiation" => Some(AtomicOperationType::SettlementInitiation),
+            "WindowOpen" => Some(AtomicOperationType::WindowOpen),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -239,6 +314,19 @@ pub struct SettlementInstruction {
     pub created_at: DateTime<Utc>,
 }
 
+// ===== SETTLEMENT STATUS =====
+
+/// Status change of a settlement instruction generated by this engine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettlementStatusChange {
+    pub window_id: i64,
+    pub instruction_id: Uuid,
+    pub settlement_id: Option<Uuid>,      // Reference to settlement engine, once it picked the instruction up
+    pub status: String,                   // PENDING, EXECUTING, COMPLETED, FAILED, CANCELLED
+    pub details: String,
+    pub occurred_at: DateTime<Utc>,
+}
+
 // ===== CLEARING METRICS =====
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -293,4 +381,5 @@ pub enum ClearingStatus {
     Success,
     PartialSuccess,
     Failed,
+    RolledBack,
 }
@@ -3,68 +3,17 @@
 
 use async_nats::Client;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use tracing::{info, error, warn};
 use uuid::Uuid;
 use futures_util::StreamExt;
 use rust_decimal::Decimal;
 use crate::models::NetPosition;
-
-#[derive(Debug, Deserialize, Serialize, Clone)]
-pub struct CanonicalPayment {
-    pub deltran_tx_id: Uuid,
-    pub uetr: Option<Uuid>,
-    pub end_to_end_id: String,
-    pub instruction_id: String,
-    pub instructed_amount: Decimal,
-    pub settlement_amount: Decimal,
-    pub currency: String,
-    pub debtor: Party,
-    pub creditor: Party,
-    pub debtor_agent: FinancialInstitution,
-    pub creditor_agent: FinancialInstitution,
-    pub status: String,
-}
-
-#[derive(Debug, Deserialize, Serialize, Clone)]
-pub struct Party {
-    pub name: String,
-}
-
-#[derive(Debug, Deserialize, Serialize, Clone)]
-pub struct FinancialInstitution {
-    pub bic: String,
-    pub name: Option<String>,
-    pub country: Option<String>,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-pub struct ObligationCreatedEvent {
-    pub obligation_id: Uuid,
-    pub deltran_tx_id: Uuid,
-    pub uetr: Option<Uuid>,
-    pub amount: Decimal,
-    pub currency: String,
-    pub debtor_country: String,
-    pub creditor_country: String,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct ClearingSubmission {
-    pub payment: CanonicalPayment,
-    pub obligation: ObligationCreatedEvent,
-}
-
-/// Local clearing submission - direct from Obligation Engine (bypasses Risk/Liquidity)
-#[derive(Debug, Deserialize)]
-pub struct LocalClearingSubmission {
-    pub payment: CanonicalPayment,
-    pub obligation: ObligationCreatedEvent,
-    pub routing_type: String,        // "LOCAL_DIRECT"
-    pub jurisdiction: String,        // Country code
-    pub settlement_type: String,     // "INSTANT"
-    pub skip_risk_check: bool,
-    pub skip_liquidity_router: bool,
-}
+use crate::orchestrator::ClearingOrchestrator;
+use deltran_schema::EventEnvelope;
+use deltran_schema::envelope::payload_value;
+use deltran_schema::clearing::{ClearingSubmission, LocalRouting};
+use deltran_schema::payment::CanonicalPayment;
 
 #[derive(Debug, Serialize)]
 pub struct ClearingAcceptedEvent {
@@ -101,9 +50,10 @@ pub async fn start_clearing_consumer(nats_url: &str) -> anyhow::Result<()> {
         info!("🔄 Clearing consumer task started");
 
         while let Some(msg) = subscriber.next().await {
-            // Parse ClearingSubmission from message
-            match serde_json::from_slice::<ClearingSubmission>(&msg.payload) {
-                Ok(submission) => {
+            // Parse ClearingSubmission from message (clearing.submission envelope or legacy bare payload)
+            match EventEnvelope::<ClearingSubmission>::from_slice(&msg.payload) {
+                Ok(envelope) => {
+                    let submission = envelope.payload;
                     info!(
                         "🌐 Received clearing request for obligation: {} (Payment: {}, Currency: {}, Amount: {})",
                         submission.obligation.obligation_id,
@@ -168,24 +118,31 @@ pub async fn start_clearing_consumer(nats_url: &str) -> anyhow::Result<()> {
         info!("🏠 Local clearing consumer task started");
 
         while let Some(msg) = local_subscriber.next().await {
-            // Parse LocalClearingSubmission from message
-            match serde_json::from_slice::<LocalClearingSubmission>(&msg.payload) {
-                Ok(submission) => {
+            // Parse ClearingSubmission from message - local submissions carry their routing
+            let parsed = EventEnvelope::<ClearingSubmission>::from_slice(&msg.payload)
+                .map_err(|e| e.to_string())
+                .and_then(|envelope| match envelope.payload.local.clone() {
+                    Some(routing) => Ok((envelope.payload, routing)),
+                    None => Err("local clearing submission without local routing".to_string()),
+                });
+
+            match parsed {
+                Ok((submission, routing)) => {
                     info!(
                         "🏠 LOCAL clearing request: {} (Jurisdiction: {}, Type: {})",
                         submission.obligation.obligation_id,
-                        submission.jurisdiction,
-                        submission.settlement_type
+                        routing.jurisdiction,
+                        routing.settlement_type
                     );
 
                     // For LOCAL payments, we optimize token/fiat routing between banks
                     // in the same jurisdiction - no FX risk, instant settlement possible
-                    match process_local_clearing(&submission, &nats_for_local).await {
+                    match process_local_clearing(&submission, &routing, &nats_for_local).await {
                         Ok(()) => {
                             info!(
                                 "✅ Local clearing processed for {} in {} (instant settlement)",
                                 submission.obligation.obligation_id,
-                                submission.jurisdiction
+                                routing.jurisdiction
                             );
                         }
                         Err(e) => {
@@ -197,7 +154,7 @@ pub async fn start_clearing_consumer(nats_url: &str) -> anyhow::Result<()> {
                     }
                 }
                 Err(e) => {
-                    error!("Failed to parse LocalClearingSubmission from NATS message: {}", e);
+                    error!("Failed to parse local ClearingSubmission from NATS message: {}", e);
                 }
             }
         }
@@ -210,6 +167,67 @@ pub async fn start_clearing_consumer(nats_url: &str) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Settlement outcome published by the settlement engine on deltran.settlement.completed
+#[derive(Debug, Deserialize)]
+struct SettlementCompleted {
+    settlement_id: Uuid,
+    instruction_id: Uuid,
+    status: String,
+    #[serde(default)]
+    confirmation_reference: Option<String>,
+    #[serde(default)]
+    error_message: Option<String>,
+}
+
+/// Track settlement of the instructions this engine generated, feeding StreamSettlementStatus
+pub async fn start_settlement_status_consumer(
+    nats_client: Client,
+    orchestrator: Arc<ClearingOrchestrator>,
+) -> anyhow::Result<()> {
+    let mut subscriber = nats_client.subscribe("deltran.settlement.completed").await?;
+    info!("📡 Subscribed to: deltran.settlement.completed (settlement status)");
+
+    tokio::spawn(async move {
+        while let Some(msg) = subscriber.next().await {
+            let result = match payload_value(&msg.payload)
+                .map_err(|e| e.to_string())
+                .and_then(|payload| serde_json::from_value::<SettlementCompleted>(payload).map_err(|e| e.to_string()))
+            {
+                Ok(result) => result,
+                Err(e) => {
+                    error!("Failed to parse settlement result from NATS message: {}", e);
+                    continue;
+                }
+            };
+
+            let details = result.error_message
+                .or(result.confirmation_reference)
+                .unwrap_or_default();
+
+            match orchestrator
+                .record_settlement_status(result.instruction_id, Some(result.settlement_id), &result.status, details)
+                .await
+            {
+                Ok(Some(window_id)) => {
+                    info!(
+                        "💰 Instruction {} of window {} is {} (settlement {})",
+                        result.instruction_id, window_id, result.status, result.settlement_id
+                    );
+                }
+                // Settlement of a payment that was not netted here
+                Ok(None) => {}
+                Err(e) => {
+                    error!("Failed to record settlement status for instruction {}: {}", result.instruction_id, e);
+                }
+            }
+        }
+
+        warn!("⚠️ Settlement status consumer task ended");
+    });
+
+    Ok(())
+}
+
 /// Add obligation to current clearing window
 async fn add_to_clearing_window(submission: &ClearingSubmission) -> anyhow::Result<i64> {
     // TODO: Implement proper window management
@@ -345,7 +363,8 @@ pub async fn publish_to_liquidity_router(
 /// Process LOCAL clearing - direct token/fiat routing between banks in same jurisdiction
 /// This path bypasses Risk Engine and Liquidity Router (no FX risk, single jurisdiction)
 async fn process_local_clearing(
-    submission: &LocalClearingSubmission,
+    submission: &ClearingSubmission,
+    routing: &LocalRouting,
     nats_client: &Client,
 ) -> anyhow::Result<()> {
     let obligation = &submission.obligation;
@@ -353,8 +372,8 @@ async fn process_local_clearing(
 
     info!(
         "🏠 Processing local clearing: {} → {} ({} {})",
-        payment.debtor_agent.bic,
-        payment.creditor_agent.bic,
+        payment.debtor_agent.bic.as_deref().unwrap_or("?"),
+        payment.creditor_agent.bic.as_deref().unwrap_or("?"),
         obligation.amount,
         obligation.currency
     );
@@ -380,7 +399,7 @@ async fn process_local_clearing(
                 "to_bank_bic": payment.creditor_agent.bic,
                 "amount": obligation.amount,
                 "currency": obligation.currency,
-                "jurisdiction": submission.jurisdiction,
+                "jurisdiction": routing.jurisdiction,
                 "settlement_type": "INSTANT",
             });
 
@@ -402,8 +421,8 @@ async fn process_local_clearing(
                 "to_bank_bic": payment.creditor_agent.bic,
                 "amount": obligation.amount,
                 "currency": obligation.currency,
-                "jurisdiction": submission.jurisdiction,
-                "settlement_rail": get_local_rail(&submission.jurisdiction),
+                "jurisdiction": routing.jurisdiction,
+                "settlement_rail": get_local_rail(&routing.jurisdiction),
             });
 
             nats_client
@@ -416,21 +435,7 @@ async fn process_local_clearing(
             // Default to clearing window (batch with other local transactions)
             info!("📋 LOCAL: Adding to clearing window for batch processing");
 
-            // Convert to standard ClearingSubmission for window processing
-            let standard_submission = ClearingSubmission {
-                payment: payment.clone(),
-                obligation: ObligationCreatedEvent {
-                    obligation_id: obligation.obligation_id,
-                    deltran_tx_id: obligation.deltran_tx_id,
-                    uetr: obligation.uetr,
-                    amount: obligation.amount,
-                    currency: obligation.currency.clone(),
-                    debtor_country: obligation.debtor_country.clone(),
-                    creditor_country: obligation.creditor_country.clone(),
-                },
-            };
-
-            let window_id = add_to_clearing_window(&standard_submission).await?;
+            let window_id = add_to_clearing_window(submission).await?;
             info!("📋 Added local obligation {} to window {}", obligation.obligation_id, window_id);
         }
     }
@@ -439,7 +444,7 @@ async fn process_local_clearing(
     let local_event = serde_json::json!({
         "event_type": "clearing.local.processed",
         "obligation_id": obligation.obligation_id,
-        "jurisdiction": submission.jurisdiction,
+        "jurisdiction": routing.jurisdiction,
         "routing_decision": routing_decision,
         "timestamp": chrono::Utc::now().to_rfc3339(),
     });
@@ -160,7 +160,7 @@ fn calculate_bilateral_position(
 }
 
 /// Create deterministic hash for bank pair
-fn create_bank_pair_hash(bank_a: Uuid, bank_b: Uuid) -> String {
+pub(crate) fn create_bank_pair_hash(bank_a: Uuid, bank_b: Uuid) -> String {
     let (first, second) = if bank_a < bank_b {
         (bank_a, bank_b)
     } else {
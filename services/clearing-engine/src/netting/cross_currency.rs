@@ -0,0 +1,337 @@
+// Cross-currency Module - Offsets opposing exposures of the same two banks in different currencies
+// A bank owing its counterparty INR while being owed AED by it only settles what is left once the
+// two are converted against each other at the rates frozen at the window's cutoff.
+
+use crate::errors::ClearingError;
+use crate::models::NetPosition;
+use chrono::{DateTime, Utc};
+use deltran_schema::Currency;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// Mid rate of a currency pair: 1 base_currency = rate quote_currency
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, sqlx::FromRow)]
+pub struct FxRate {
+    pub base_currency: String,
+    pub quote_currency: String,
+    pub rate: Decimal,
+    pub observed_at: DateTime<Utc>,
+}
+
+/// Rates a window is netted at, the last quotes at or before its cutoff. Pairs not quoted
+/// within `max_rate_age_seconds` of the cutoff are left out, so they are not offset.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FxSnapshot {
+    pub taken_at: DateTime<Utc>,
+    #[serde(default)]
+    pub max_rate_age_seconds: i64,
+    pub rates: Vec<FxRate>,
+}
+
+impl FxSnapshot {
+    /// Units of `to` per unit of `from`, from the pair quoted either way round
+    pub fn rate(&self, from: &str, to: &str) -> Option<Decimal> {
+        self.rates
+            .iter()
+            .find_map(|r| {
+                if r.base_currency == from && r.quote_currency == to {
+                    Some(r.rate)
+                } else if r.base_currency == to && r.quote_currency == from {
+                    Decimal::ONE.checked_div(r.rate)
+                } else {
+                    None
+                }
+            })
+            .filter(|rate| *rate > Decimal::ZERO)
+    }
+}
+
+/// One offset: `from_bank_id` owed `from_amount` to `to_bank_id`, which owed `to_amount` back
+/// in another currency. Both are discharged against each other at `rate`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FxConversion {
+    pub bank_pair_hash: String,
+    pub from_bank_id: Uuid,
+    pub to_bank_id: Uuid,
+    pub from_currency: String,
+    pub from_amount: Decimal,
+    pub to_currency: String,
+    pub to_amount: Decimal,
+    pub rate: Decimal,
+}
+
+/// Offset the unbalanced positions of each bank pair that run in opposite directions in
+/// different currencies. Positions are reduced in place to the residual still to settle; pairs
+/// of currencies the snapshot has no rate for are left as they are. Only meant for bilateral
+/// positions: offsetting a participant's legs against the settlement account would leave the
+/// account short in one currency and long in the other.
+pub fn offset_positions(
+    positions: &mut [NetPosition],
+    snapshot: &FxSnapshot,
+) -> Result<Vec<FxConversion>, ClearingError> {
+    let mut pairs: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for (idx, position) in positions.iter().enumerate() {
+        if position.net_payer_id.is_some() && position.net_amount > Decimal::ZERO {
+            pairs.entry(position.bank_pair_hash.clone()).or_default().push(idx);
+        }
+    }
+
+    let mut conversions = Vec::new();
+
+    for (bank_pair_hash, indices) in pairs {
+        // Split by which of the two banks pays, each side in currency order
+        let first = &positions[indices[0]];
+        let lower = first.bank_a_id.min(first.bank_b_id);
+        let (mut forward, mut backward): (Vec<usize>, Vec<usize>) = indices
+            .into_iter()
+            .partition(|&idx| positions[idx].net_payer_id == Some(lower));
+        forward.sort_by(|&a, &b| positions[a].currency.cmp(&positions[b].currency));
+        backward.sort_by(|&a, &b| positions[a].currency.cmp(&positions[b].currency));
+
+        for &owed in &forward {
+            for &owed_back in &backward {
+                let (from, to) = (&positions[owed], &positions[owed_back]);
+                if from.net_amount.is_zero() {
+                    break;
+                }
+                if to.net_amount.is_zero() || from.currency == to.currency {
+                    continue;
+                }
+                let Some(rate) = snapshot.rate(&from.currency, &to.currency) else {
+                    continue;
+                };
+
+                let (from_amount, to_amount) = offset_amounts(
+                    from.net_amount,
+                    minor_units(&from.currency),
+                    to.net_amount,
+                    minor_units(&to.currency),
+                    rate,
+                )?;
+                if from_amount.is_zero() || to_amount.is_zero() {
+                    continue;
+                }
+
+                conversions.push(FxConversion {
+                    bank_pair_hash: bank_pair_hash.clone(),
+                    from_bank_id: lower,
+                    to_bank_id: from.net_receiver_id.ok_or(ClearingError::NodeNotFound)?,
+                    from_currency: from.currency.clone(),
+                    from_amount,
+                    to_currency: to.currency.clone(),
+                    to_amount,
+                    rate,
+                });
+                reduce_position(&mut positions[owed], from_amount)?;
+                reduce_position(&mut positions[owed_back], to_amount)?;
+            }
+        }
+    }
+
+    Ok(conversions)
+}
+
+/// Largest part of `owed` and `owed_back` (in the other currency) that offset each other at
+/// `rate`, each rounded to the minor units of its currency
+fn offset_amounts(
+    owed: Decimal,
+    owed_dp: u32,
+    owed_back: Decimal,
+    owed_back_dp: u32,
+    rate: Decimal,
+) -> Result<(Decimal, Decimal), ClearingError> {
+    let converted = owed
+        .checked_mul(rate)
+        .ok_or(ClearingError::CalculationOverflow)?
+        .round_dp(owed_back_dp);
+
+    if converted <= owed_back {
+        return Ok((owed, converted));
+    }
+
+    let owed_part = owed_back
+        .checked_div(rate)
+        .ok_or(ClearingError::CalculationOverflow)?
+        .round_dp(owed_dp)
+        .min(owed);
+    Ok((owed_part, owed_back))
+}
+
+/// ISO 4217 minor units of a currency (2 for codes outside the registry)
+fn minor_units(currency: &str) -> u32 {
+    Currency::from_code(currency).map_or(2, |currency| currency.minor_units())
+}
+
+/// Take `amount` off the net amount of a position, balancing it once nothing is left
+fn reduce_position(position: &mut NetPosition, amount: Decimal) -> Result<(), ClearingError> {
+    position.net_amount = position
+        .net_amount
+        .checked_sub(amount)
+        .ok_or(ClearingError::CalculationUnderflow)?;
+
+    if position.net_amount.is_zero() {
+        position.net_direction = "BALANCED".to_string();
+        position.net_payer_id = None;
+        position.net_receiver_id = None;
+    }
+
+    let gross_amount = position
+        .gross_debit_a_to_b
+        .checked_add(position.gross_credit_b_to_a)
+        .ok_or(ClearingError::CalculationOverflow)?;
+    position.amount_saved = gross_amount
+        .checked_sub(position.net_amount)
+        .ok_or(ClearingError::CalculationUnderflow)?;
+    position.netting_ratio = if gross_amount > Decimal::ZERO {
+        position.net_amount.checked_div(gross_amount).unwrap_or(Decimal::ZERO)
+    } else {
+        Decimal::ZERO
+    };
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::netting::calculator::create_bank_pair_hash;
+
+    fn snapshot(rates: &[(&str, &str, Decimal)]) -> FxSnapshot {
+        let now = Utc::now();
+        FxSnapshot {
+            taken_at: now,
+            max_rate_age_seconds: 3600,
+            rates: rates
+                .iter()
+                .map(|(base, quote, rate)| FxRate {
+                    base_currency: base.to_string(),
+                    quote_currency: quote.to_string(),
+                    rate: *rate,
+                    observed_at: now,
+                })
+                .collect(),
+        }
+    }
+
+    fn position(payer: Uuid, receiver: Uuid, currency: &str, amount: Decimal) -> NetPosition {
+        let (bank_a_id, bank_b_id) = (payer.min(receiver), payer.max(receiver));
+        NetPosition {
+            id: Uuid::new_v4(),
+            window_id: 1,
+            bank_pair_hash: create_bank_pair_hash(payer, receiver),
+            bank_a_id,
+            bank_b_id,
+            currency: currency.to_string(),
+            gross_debit_a_to_b: if payer == bank_a_id { amount } else { Decimal::ZERO },
+            gross_credit_b_to_a: if payer == bank_b_id { amount } else { Decimal::ZERO },
+            net_amount: amount,
+            net_direction: if payer == bank_a_id { "A_TO_B" } else { "B_TO_A" }.to_string(),
+            net_payer_id: Some(payer),
+            net_receiver_id: Some(receiver),
+            obligations_netted: 1,
+            netting_ratio: Decimal::ONE,
+            amount_saved: Decimal::ZERO,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_offset_leaves_residual() {
+        let (mut a, mut b) = (Uuid::new_v4(), Uuid::new_v4());
+        if a > b {
+            std::mem::swap(&mut a, &mut b);
+        }
+        // A owes B 100,000 INR (4,400 AED at 0.044), B owes A 1,500 AED (34,090.91 INR):
+        // the AED is offset entirely, leaving A to pay B 65,909.09 INR
+        let mut positions = vec![
+            position(a, b, "INR", Decimal::from(100_000)),
+            position(b, a, "AED", Decimal::from(1_500)),
+        ];
+
+        let conversions =
+            offset_positions(&mut positions, &snapshot(&[("INR", "AED", Decimal::new(44, 3))])).unwrap();
+
+        assert_eq!(conversions.len(), 1);
+        let leg = &conversions[0];
+        assert_eq!((leg.from_bank_id, leg.to_bank_id), (a, b));
+        assert_eq!((leg.from_currency.as_str(), leg.from_amount), ("INR", Decimal::new(3_409_091, 2)));
+        assert_eq!((leg.to_currency.as_str(), leg.to_amount), ("AED", Decimal::from(1_500)));
+
+        assert_eq!(positions[0].net_amount, Decimal::new(6_590_909, 2));
+        assert_eq!(positions[0].net_payer_id, Some(a));
+        assert_eq!(positions[0].amount_saved, Decimal::new(3_409_091, 2));
+        assert_eq!(positions[1].net_direction, "BALANCED");
+        assert_eq!(positions[1].net_amount, Decimal::ZERO);
+        assert_eq!(positions[1].net_payer_id, None);
+    }
+
+    #[test]
+    fn test_inverse_and_missing_rates() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        // Only AED/INR is quoted: INR→AED uses its inverse, USD is never offset
+        let rates = snapshot(&[("AED", "INR", Decimal::from(25))]);
+        assert_eq!(rates.rate("INR", "AED"), Some(Decimal::new(4, 2)));
+        assert_eq!(rates.rate("USD", "AED"), None);
+
+        let mut positions = vec![
+            position(a, b, "INR", Decimal::from(2_500)),
+            position(b, a, "AED", Decimal::from(400)),
+            position(b, a, "USD", Decimal::from(50)),
+        ];
+        let conversions = offset_positions(&mut positions, &rates).unwrap();
+
+        // The INR is fully offset by 100 AED
+        assert_eq!(conversions.len(), 1);
+        assert_eq!(positions[0].net_direction, "BALANCED");
+        assert_eq!(positions[1].net_amount, Decimal::from(300));
+        assert_eq!(positions[2].net_amount, Decimal::from(50));
+    }
+
+    #[test]
+    fn test_rounds_to_minor_units() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        // 1 KWD = 487.3 JPY: 100,000 JPY is 205.212 KWD, 50 KWD is 24,365 JPY
+        let rates = snapshot(&[("KWD", "JPY", Decimal::new(4873, 1))]);
+
+        let mut positions = vec![
+            position(a, b, "JPY", Decimal::from(100_000)),
+            position(b, a, "KWD", Decimal::from(50)),
+        ];
+        let conversions = offset_positions(&mut positions, &rates).unwrap();
+        let leg = &conversions[0];
+        let (jpy, kwd) = if leg.from_currency == "JPY" {
+            (leg.from_amount, leg.to_amount)
+        } else {
+            (leg.to_amount, leg.from_amount)
+        };
+        assert_eq!((jpy, kwd), (Decimal::from(24_365), Decimal::from(50)));
+        assert_eq!(jpy.scale(), 0);
+        assert_eq!(positions[0].net_amount, Decimal::from(75_635));
+
+        let mut positions = vec![
+            position(a, b, "JPY", Decimal::from(10_000)),
+            position(b, a, "KWD", Decimal::from(50)),
+        ];
+        offset_positions(&mut positions, &rates).unwrap();
+        // 10,000 JPY = 20.521 KWD (three decimals), leaving 29.479 KWD
+        assert_eq!(positions[0].net_direction, "BALANCED");
+        assert_eq!(positions[1].net_amount, Decimal::new(29_479, 3));
+    }
+
+    #[test]
+    fn test_same_direction_not_offset() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut positions = vec![
+            position(a, b, "INR", Decimal::from(1_000)),
+            position(a, b, "AED", Decimal::from(100)),
+        ];
+        let conversions =
+            offset_positions(&mut positions, &snapshot(&[("INR", "AED", Decimal::new(44, 3))])).unwrap();
+
+        assert!(conversions.is_empty());
+        assert_eq!(positions[0].net_amount, Decimal::from(1_000));
+        assert_eq!(positions[1].net_amount, Decimal::from(100));
+    }
+}
@@ -4,10 +4,13 @@
 pub mod graph_builder;
 pub mod calculator;
 pub mod optimizer;
+pub mod multilateral;
+pub mod cross_currency;
 
 use crate::errors::ClearingError;
 use crate::models::NetPosition;
 use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
 
@@ -102,6 +105,27 @@ impl NettingEngine {
         Ok(all_positions)
     }
 
+    /// Calculate each participant's multilateral position per currency against the
+    /// settlement account. Net positions do not depend on the optimizer.
+    pub fn calculate_participant_positions(
+        &self,
+        settlement_account: Uuid,
+    ) -> Result<Vec<NetPosition>, ClearingError> {
+        let mut all_positions = Vec::new();
+
+        for (currency, graph) in &self.graphs {
+            let positions = multilateral::calculate_positions(
+                graph,
+                currency,
+                self.window_id,
+                settlement_account,
+            )?;
+            all_positions.extend(positions);
+        }
+
+        Ok(all_positions)
+    }
+
     /// Optimize netting by detecting and eliminating cycles
     pub fn optimize(&mut self) -> Result<OptimizerStats, ClearingError> {
         let mut total_cycles = 0;
@@ -141,6 +165,61 @@ pub struct OptimizerStats {
     pub amount_eliminated: Decimal,
 }
 
+/// Settlement cost of one netting mode
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ModeStats {
+    /// Instructions needed to settle the window
+    pub settlements: usize,
+    /// Value the paying banks have to fund
+    pub net_value: Decimal,
+    /// Share of the gross obligations that does not need funding, percent
+    pub efficiency_percent: Decimal,
+}
+
+impl ModeStats {
+    /// Bilateral positions: every unbalanced pair settles its net amount
+    pub fn bilateral(gross_value: Decimal, positions: &[NetPosition]) -> Self {
+        Self::from_settlements(
+            gross_value,
+            positions.iter().filter(|p| p.net_direction != "BALANCED"),
+        )
+    }
+
+    /// Multilateral positions: pay-ins fund the pay-outs, so only pay-ins count as net value
+    pub fn multilateral(gross_value: Decimal, positions: &[NetPosition]) -> Self {
+        let settlements = positions.iter().filter(|p| p.net_direction != "BALANCED").count();
+        Self {
+            settlements,
+            ..Self::from_settlements(gross_value, positions.iter().filter(|p| p.net_direction == "A_TO_B"))
+        }
+    }
+
+    fn from_settlements<'a>(gross_value: Decimal, settlements: impl Iterator<Item = &'a NetPosition>) -> Self {
+        let (count, net_value) = settlements.fold((0, Decimal::ZERO), |(count, total), p| (count + 1, total + p.net_amount));
+        let efficiency_percent = if gross_value > Decimal::ZERO {
+            (gross_value - net_value)
+                .checked_div(gross_value)
+                .unwrap_or(Decimal::ZERO)
+                * Decimal::from(100)
+        } else {
+            Decimal::ZERO
+        };
+
+        Self {
+            settlements: count,
+            net_value,
+            efficiency_percent: efficiency_percent.round_dp(2),
+        }
+    }
+}
+
+/// Both netting modes evaluated on the same window
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ModeComparison {
+    pub bilateral: ModeStats,
+    pub multilateral: ModeStats,
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct NettingStats {
     pub currencies_count: usize,
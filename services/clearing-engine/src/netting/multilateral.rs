@@ -0,0 +1,156 @@
+// Multilateral Module - Net position of each participant against the central counterparty
+// Every bank ends up with a single pay-in or pay-out per currency, whatever the number of
+// counterparties it had obligations with.
+
+use super::{calculator, graph_builder, CurrencyGraph};
+use crate::errors::ClearingError;
+use crate::models::NetPosition;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+/// Calculate one position per participant of a currency graph against `settlement_account`.
+/// Bank A is the participant and bank B the settlement account, so A_TO_B is a pay-in and
+/// B_TO_A a pay-out.
+pub fn calculate_positions(
+    graph: &CurrencyGraph,
+    currency: &str,
+    window_id: i64,
+    settlement_account: Uuid,
+) -> Result<Vec<NetPosition>, ClearingError> {
+    let mut positions = Vec::with_capacity(graph.node_count());
+
+    for node_idx in graph.node_indices() {
+        let participant = graph.node_weight(node_idx).ok_or(ClearingError::NodeNotFound)?;
+
+        // Owes to others (debit) and is owed by others (credit)
+        let (credit, debit) = graph_builder::calculate_node_flows(graph, node_idx);
+        let obligations: usize = graph
+            .edges_directed(node_idx, petgraph::Direction::Outgoing)
+            .chain(graph.edges_directed(node_idx, petgraph::Direction::Incoming))
+            .map(|e| e.weight().count)
+            .sum();
+
+        let net = debit
+            .checked_sub(credit)
+            .ok_or(ClearingError::CalculationUnderflow)?;
+        let net_amount = net.abs();
+
+        let (net_direction, net_payer_id, net_receiver_id) = if net > Decimal::ZERO {
+            ("A_TO_B".to_string(), Some(participant.bank_id), Some(settlement_account))
+        } else if net < Decimal::ZERO {
+            ("B_TO_A".to_string(), Some(settlement_account), Some(participant.bank_id))
+        } else {
+            ("BALANCED".to_string(), None, None)
+        };
+
+        let gross_amount = debit
+            .checked_add(credit)
+            .ok_or(ClearingError::CalculationOverflow)?;
+
+        let amount_saved = gross_amount
+            .checked_sub(net_amount)
+            .ok_or(ClearingError::CalculationUnderflow)?;
+
+        let netting_ratio = if gross_amount > Decimal::ZERO {
+            net_amount
+                .checked_div(gross_amount)
+                .unwrap_or(Decimal::ZERO)
+        } else {
+            Decimal::ZERO
+        };
+
+        positions.push(NetPosition {
+            id: Uuid::new_v4(),
+            window_id,
+            bank_pair_hash: calculator::create_bank_pair_hash(participant.bank_id, settlement_account),
+            bank_a_id: participant.bank_id,
+            bank_b_id: settlement_account,
+            currency: currency.to_string(),
+            gross_debit_a_to_b: debit,
+            gross_credit_b_to_a: credit,
+            net_amount,
+            net_direction,
+            net_payer_id,
+            net_receiver_id,
+            obligations_netted: obligations as i32,
+            netting_ratio,
+            amount_saved,
+            created_at: Utc::now(),
+        });
+    }
+
+    Ok(positions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_participant_positions() {
+        let mut graph = petgraph::Graph::new();
+        let ccp = Uuid::new_v4();
+        let banks: Vec<_> = (0..3).map(|_| Uuid::new_v4()).collect();
+        let idx: Vec<_> = banks
+            .iter()
+            .map(|b| graph_builder::find_or_create_node(&mut graph, *b, format!("BANK_{}", b)))
+            .collect();
+
+        // A→B 100, B→C 60, C→A 60, A→C 10: A pays in 50, B is paid out 40, C is paid out 10
+        for (from, to, amount) in [(0, 1, 100), (1, 2, 60), (2, 0, 60), (0, 2, 10)] {
+            graph_builder::add_or_update_edge(&mut graph, idx[from], idx[to], Decimal::from(amount), Uuid::new_v4());
+        }
+
+        let positions = calculate_positions(&graph, "USD", 1, ccp).unwrap();
+        assert_eq!(positions.len(), 3);
+
+        let a = positions.iter().find(|p| p.bank_a_id == banks[0]).unwrap();
+        assert_eq!(a.bank_b_id, ccp);
+        assert_eq!(a.net_direction, "A_TO_B");
+        assert_eq!(a.net_amount, Decimal::from(50));
+        assert_eq!((a.net_payer_id, a.net_receiver_id), (Some(banks[0]), Some(ccp)));
+        assert_eq!(a.gross_debit_a_to_b, Decimal::from(110));
+        assert_eq!(a.gross_credit_b_to_a, Decimal::from(60));
+        assert_eq!(a.obligations_netted, 3);
+
+        let b = positions.iter().find(|p| p.bank_a_id == banks[1]).unwrap();
+        assert_eq!(b.net_direction, "B_TO_A");
+        assert_eq!(b.net_amount, Decimal::from(40));
+        assert_eq!(b.net_receiver_id, Some(banks[1]));
+
+        // Pay-ins fund the pay-outs exactly
+        let total = |direction: &str| -> Decimal {
+            positions.iter().filter(|p| p.net_direction == direction).map(|p| p.net_amount).sum()
+        };
+        assert_eq!(total("A_TO_B"), total("B_TO_A"));
+    }
+
+    #[test]
+    fn test_mode_comparison() {
+        use crate::netting::{ModeStats, NettingEngine};
+
+        // Four banks each owing the next 100 and the one after that 50: multilaterally everybody
+        // is flat, while bilateral positions (before the optimizer) only cancel the opposite 50s
+        let mut engine = NettingEngine::new(1);
+        let banks: Vec<_> = (0..4).map(|_| Uuid::new_v4()).collect();
+        let mut gross = Decimal::ZERO;
+        for i in 0..4 {
+            for (step, amount) in [(1, 100), (2, 50)] {
+                engine
+                    .add_obligation("USD".to_string(), banks[i], banks[(i + step) % 4], Decimal::from(amount), Uuid::new_v4())
+                    .unwrap();
+                gross += Decimal::from(amount);
+            }
+        }
+
+        let multilateral = ModeStats::multilateral(gross, &engine.calculate_participant_positions(Uuid::new_v4()).unwrap());
+        let bilateral = ModeStats::bilateral(gross, &engine.calculate_net_positions().unwrap());
+
+        assert_eq!(multilateral.settlements, 0);
+        assert_eq!(multilateral.efficiency_percent, Decimal::from(100));
+        assert_eq!(bilateral.settlements, 4);
+        assert_eq!(bilateral.net_value, Decimal::from(400));
+        assert!(bilateral.efficiency_percent < multilateral.efficiency_percent);
+    }
+}
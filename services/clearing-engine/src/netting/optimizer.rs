@@ -1,21 +1,40 @@
-// Optimizer Module - Detects and eliminates cycles to minimize settlements
+// Optimizer Module - Eliminates cycles to minimize settlements
+//
+// Netting is solved as a min-cost circulation over the obligation graph: every edge A→B may
+// carry anything between 0 and what A owes B, every unit carried costs 1, and each bank's net
+// position must stay what it was. Starting from the obligations themselves, we cancel negative
+// cycles of the residual graph until none is left, which is exactly the optimality condition.
+// The result settles the minimum total value, and since such a flow cannot contain a cycle,
+// opposite bilateral pairs and rings are all gone.
+//
+// Each round cancels a minimum-mean cycle (Goldberg-Tarjan, found with Karp's algorithm): the
+// number of rounds is bounded by the size of the graph alone, whatever the amounts, whereas
+// cancelling whichever negative cycle turns up first can take a number of rounds that grows
+// with the amounts.
 
 use super::{CurrencyGraph, OptimizerStats};
 use crate::errors::ClearingError;
-use petgraph::algo::{is_cyclic_directed, kosaraju_scc};
-use petgraph::graph::NodeIndex;
+use petgraph::algo::is_cyclic_directed;
+use petgraph::graph::{EdgeIndex, NodeIndex};
 use petgraph::visit::EdgeRef;
 use rust_decimal::Decimal;
-use tracing::{info, warn};
+use tracing::info;
+
+/// Arc of the residual graph: raise edge `edge` towards its obligation (forward, cost +1)
+/// or lower it towards zero (backward, cost -1)
+#[derive(Debug, Clone, Copy)]
+struct ResidualArc {
+    from: NodeIndex,
+    to: NodeIndex,
+    edge: EdgeIndex,
+    forward: bool,
+}
 
-/// Optimize graph by detecting and eliminating cycles
+/// Optimize graph by cancelling cycles until the settled value is minimal
 pub fn optimize_graph(
     graph: &mut CurrencyGraph,
     currency: &str,
 ) -> Result<OptimizerStats, ClearingError> {
-    let mut cycles_found = 0;
-    let mut amount_eliminated = Decimal::ZERO;
-
     info!("Starting optimization for currency: {}", currency);
 
     // Check if graph has cycles (use &*graph to dereference to immutable)
@@ -27,37 +46,24 @@ pub fn optimize_graph(
         });
     }
 
-    // Find strongly connected components (SCCs) (use &*graph to dereference to immutable)
-    let sccs = kosaraju_scc(&*graph);
+    let gross_before = total_amount(graph)?;
 
-    for scc in sccs {
-        if scc.len() <= 1 {
-            continue; // Skip single-node components
-        }
+    // An edge never carries more than was originally owed on it
+    let capacity: Vec<Decimal> = graph.edge_weights().map(|e| e.amount).collect();
 
-        // Process cycle
-        match process_cycle(graph, &scc) {
-            Ok(eliminated) => {
-                cycles_found += 1;
-                amount_eliminated = amount_eliminated
-                    .checked_add(eliminated)
-                    .ok_or(ClearingError::CalculationOverflow)?;
-
-                info!(
-                    "Eliminated cycle with {} banks, saved: {}",
-                    scc.len(),
-                    eliminated
-                );
-            }
-            Err(e) => {
-                warn!("Failed to process cycle: {:?}", e);
-            }
-        }
+    let mut cycles_found = 0;
+    while let Some(cycle) = find_minimum_mean_cycle(graph, &capacity) {
+        cancel_cycle(graph, &capacity, &cycle)?;
+        cycles_found += 1;
     }
 
     // Remove zero-value edges
     cleanup_zero_edges(graph);
 
+    let amount_eliminated = gross_before
+        .checked_sub(total_amount(graph)?)
+        .ok_or(ClearingError::CalculationUnderflow)?;
+
     info!(
         "Optimization complete: {} cycles eliminated, {} saved",
         cycles_found, amount_eliminated
@@ -69,79 +75,150 @@ pub fn optimize_graph(
     })
 }
 
-/// Process a single cycle and eliminate minimum flow
-fn process_cycle(
-    graph: &mut CurrencyGraph,
-    cycle_nodes: &[NodeIndex],
-) -> Result<Decimal, ClearingError> {
-    if cycle_nodes.is_empty() {
-        return Ok(Decimal::ZERO);
+/// Residual arcs of the graph given each edge's original amount
+fn residual_arcs(graph: &CurrencyGraph, capacity: &[Decimal]) -> Vec<ResidualArc> {
+    let mut arcs = Vec::with_capacity(graph.edge_count() * 2);
+
+    for edge in graph.edge_references() {
+        let amount = edge.weight().amount;
+
+        if amount > Decimal::ZERO {
+            arcs.push(ResidualArc {
+                from: edge.target(),
+                to: edge.source(),
+                edge: edge.id(),
+                forward: false,
+            });
+        }
+        if capacity[edge.id().index()] > amount {
+            arcs.push(ResidualArc {
+                from: edge.source(),
+                to: edge.target(),
+                edge: edge.id(),
+                forward: true,
+            });
+        }
     }
 
-    // Find minimum edge weight in the cycle
-    let min_flow = find_minimum_flow(graph, cycle_nodes)?;
+    arcs
+}
 
-    if min_flow == Decimal::ZERO {
-        return Ok(Decimal::ZERO);
+/// Find a minimum-mean cycle of the residual graph if its mean cost is negative (Karp's
+/// algorithm, walks of every length from a virtual source)
+fn find_minimum_mean_cycle(graph: &CurrencyGraph, capacity: &[Decimal]) -> Option<Vec<ResidualArc>> {
+    let arcs = residual_arcs(graph, capacity);
+    let node_count = graph.node_count();
+    if node_count == 0 {
+        return None;
     }
 
-    // Reduce all edges in the cycle by the minimum flow
-    for i in 0..cycle_nodes.len() {
-        let from = cycle_nodes[i];
-        let to = cycle_nodes[(i + 1) % cycle_nodes.len()];
+    // walk[k][v]: cheapest walk of exactly k arcs ending at v, and the arc it ends with
+    let mut walk: Vec<Vec<Option<(i64, usize)>>> = vec![vec![None; node_count]; node_count + 1];
+    walk[0] = vec![Some((0, usize::MAX)); node_count];
+    for k in 1..=node_count {
+        for (i, arc) in arcs.iter().enumerate() {
+            let Some((cost, _)) = walk[k - 1][arc.from.index()] else {
+                continue;
+            };
+            let cost = cost + if arc.forward { 1 } else { -1 };
+            if !matches!(walk[k][arc.to.index()], Some((best, _)) if best <= cost) {
+                walk[k][arc.to.index()] = Some((cost, i));
+            }
+        }
+    }
 
-        if let Some(edge_idx) = graph.find_edge(from, to) {
-            if let Some(edge) = graph.edge_weight_mut(edge_idx) {
-                edge.amount = edge.amount
-                    .checked_sub(min_flow)
-                    .unwrap_or(Decimal::ZERO);
+    // Minimum mean = min over v of max over k of (walk[n][v] - walk[k][v]) / (n - k),
+    // kept as a fraction (cost, length) to stay exact
+    let n = node_count as i64;
+    let mut best: Option<(i64, i64, usize)> = None;
+    for (v, last) in walk[node_count].iter().enumerate() {
+        let Some((cost_n, _)) = *last else {
+            continue;
+        };
+        let worst = (0..node_count)
+            .filter_map(|k| walk[k][v].map(|(cost_k, _)| (cost_n - cost_k, n - k as i64)))
+            .max_by(|(a, a_len), (b, b_len)| (a * b_len).cmp(&(b * a_len)));
+        if let Some((cost, len)) = worst {
+            if !matches!(best, Some((best_cost, best_len, _)) if best_cost * len <= cost * best_len) {
+                best = Some((cost, len, v));
             }
         }
     }
 
-    Ok(min_flow.checked_mul(Decimal::from(cycle_nodes.len()))
-        .ok_or(ClearingError::CalculationOverflow)?)
+    let (cost, _, end) = best?;
+    if cost >= 0 {
+        return None;
+    }
+
+    // Every cycle on the cheapest n-arc walk to `end` has the minimum mean: walk it back and
+    // cut out the first one
+    let mut nodes = vec![end; node_count + 1];
+    let mut walk_arcs = vec![0; node_count + 1];
+    for k in (1..=node_count).rev() {
+        let (_, arc) = walk[k][nodes[k]]?;
+        walk_arcs[k] = arc;
+        nodes[k - 1] = arcs[arc].from.index();
+    }
+
+    let mut seen = vec![None; node_count];
+    for (k, &node) in nodes.iter().enumerate() {
+        if let Some(first) = seen[node] {
+            return Some(walk_arcs[first + 1..=k].iter().map(|&arc| arcs[arc]).collect());
+        }
+        seen[node] = Some(k);
+    }
+
+    None
 }
 
-/// Find minimum flow in a cycle
-fn find_minimum_flow(
-    graph: &CurrencyGraph,
-    cycle_nodes: &[NodeIndex],
-) -> Result<Decimal, ClearingError> {
-    let mut min_flow = Decimal::MAX;
-
-    for i in 0..cycle_nodes.len() {
-        let from = cycle_nodes[i];
-        let to = cycle_nodes[(i + 1) % cycle_nodes.len()];
-
-        if let Some(edge_idx) = graph.find_edge(from, to) {
-            if let Some(edge) = graph.edge_weight(edge_idx) {
-                if edge.amount < min_flow {
-                    min_flow = edge.amount;
-                }
-            }
+/// Push the cycle's bottleneck around it; net positions are unchanged since every bank on
+/// the cycle gains and loses the same amount
+fn cancel_cycle(
+    graph: &mut CurrencyGraph,
+    capacity: &[Decimal],
+    cycle: &[ResidualArc],
+) -> Result<(), ClearingError> {
+    let mut bottleneck = Decimal::MAX;
+    for arc in cycle {
+        let amount = graph.edge_weight(arc.edge).map(|e| e.amount).unwrap_or(Decimal::ZERO);
+        let residual = if arc.forward {
+            capacity[arc.edge.index()] - amount
         } else {
-            // If any edge is missing, this isn't a valid cycle
-            return Ok(Decimal::ZERO);
-        }
+            amount
+        };
+        bottleneck = bottleneck.min(residual);
     }
 
-    if min_flow == Decimal::MAX {
-        Ok(Decimal::ZERO)
-    } else {
-        Ok(min_flow)
+    for arc in cycle {
+        if let Some(edge) = graph.edge_weight_mut(arc.edge) {
+            edge.amount = if arc.forward {
+                edge.amount.checked_add(bottleneck).ok_or(ClearingError::CalculationOverflow)?
+            } else {
+                edge.amount.checked_sub(bottleneck).ok_or(ClearingError::CalculationUnderflow)?
+            };
+        }
     }
+
+    Ok(())
+}
+
+fn total_amount(graph: &CurrencyGraph) -> Result<Decimal, ClearingError> {
+    graph.edge_weights().try_fold(Decimal::ZERO, |total, e| {
+        total.checked_add(e.amount).ok_or(ClearingError::CalculationOverflow)
+    })
 }
 
 /// Remove edges with zero or near-zero amounts
 fn cleanup_zero_edges(graph: &mut CurrencyGraph) {
     let threshold = Decimal::new(1, 8); // 0.00000001
 
-    let edges_to_remove: Vec<_> = graph
+    // Highest index first: removing an edge moves the last edge into its slot
+    let mut edges_to_remove: Vec<_> = graph
         .edge_references()
         .filter(|e| e.weight().amount < threshold)
         .map(|e| e.id())
         .collect();
+    edges_to_remove.sort_by_key(|e| std::cmp::Reverse(e.index()));
 
     for edge_id in edges_to_remove {
         graph.remove_edge(edge_id);
@@ -182,27 +259,53 @@ pub fn detect_simple_cycles(
 
 /// Calculate potential savings from cycle elimination
 pub fn calculate_potential_savings(graph: &CurrencyGraph) -> Decimal {
-    let mut total_savings = Decimal::ZERO;
-
-    let cycles = detect_simple_cycles(graph);
-
-    for cycle in cycles {
-        if let Ok(min_flow) = find_minimum_flow(graph, &cycle) {
-            total_savings = total_savings
-                .checked_add(min_flow.checked_mul(Decimal::from(cycle.len())).unwrap_or(Decimal::ZERO))
-                .unwrap_or(total_savings);
-        }
-    }
+    let mut optimized = graph.clone();
 
-    total_savings
+    optimize_graph(&mut optimized, "")
+        .map(|stats| stats.amount_eliminated)
+        .unwrap_or(Decimal::ZERO)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::netting::graph_builder;
+    use proptest::prelude::*;
+    use std::collections::HashMap;
+    use std::time::{Duration, Instant};
     use uuid::Uuid;
 
+    fn build_graph(banks: usize, obligations: &[(usize, usize, Decimal)]) -> (CurrencyGraph, Vec<NodeIndex>) {
+        let mut graph = petgraph::Graph::new();
+        let nodes: Vec<_> = (0..banks)
+            .map(|i| graph_builder::find_or_create_node(&mut graph, Uuid::new_v4(), format!("BANK_{}", i)))
+            .collect();
+
+        for &(from, to, amount) in obligations {
+            graph_builder::add_or_update_edge(&mut graph, nodes[from], nodes[to], amount, Uuid::new_v4());
+        }
+
+        (graph, nodes)
+    }
+
+    fn amount(graph: &CurrencyGraph, from: NodeIndex, to: NodeIndex) -> Decimal {
+        graph
+            .find_edge(from, to)
+            .and_then(|e| graph.edge_weight(e))
+            .map(|e| e.amount)
+            .unwrap_or(Decimal::ZERO)
+    }
+
+    fn net_positions(graph: &CurrencyGraph) -> HashMap<Uuid, Decimal> {
+        graph
+            .node_indices()
+            .map(|node| {
+                let (incoming, outgoing) = graph_builder::calculate_node_flows(graph, node);
+                (graph[node].bank_id, incoming - outgoing)
+            })
+            .collect()
+    }
+
     #[test]
     fn test_cycle_detection() {
         let mut graph = petgraph::Graph::new();
@@ -227,31 +330,168 @@ mod tests {
 
     #[test]
     fn test_cycle_elimination() {
-        let mut graph = petgraph::Graph::new();
-        let a = Uuid::new_v4();
-        let b = Uuid::new_v4();
-        let c = Uuid::new_v4();
+        // Ring with min flow of 50
+        let (mut graph, n) = build_graph(
+            3,
+            &[(0, 1, Decimal::from(100)), (1, 2, Decimal::from(50)), (2, 0, Decimal::from(75))],
+        );
+
+        let stats = optimize_graph(&mut graph, "USD").unwrap();
+
+        // 50 taken off each of the three edges
+        assert_eq!(stats.amount_eliminated, Decimal::from(150));
+        assert_eq!(stats.cycles_found, 1);
+        assert_eq!(amount(&graph, n[0], n[1]), Decimal::from(50));
+        assert_eq!(amount(&graph, n[2], n[0]), Decimal::from(25));
+        assert!(graph.find_edge(n[1], n[2]).is_none());
+    }
 
-        let idx_a = graph_builder::find_or_create_node(&mut graph, a, "A".to_string());
-        let idx_b = graph_builder::find_or_create_node(&mut graph, b, "B".to_string());
-        let idx_c = graph_builder::find_or_create_node(&mut graph, c, "C".to_string());
+    #[test]
+    fn test_scc_that_is_not_a_ring() {
+        // One SCC of two bilateral pairs sharing B: A⇄B, B⇄C
+        let (mut graph, n) = build_graph(
+            3,
+            &[
+                (0, 1, Decimal::from(100)),
+                (1, 0, Decimal::from(40)),
+                (1, 2, Decimal::from(30)),
+                (2, 1, Decimal::from(70)),
+            ],
+        );
+
+        let stats = optimize_graph(&mut graph, "USD").unwrap();
+
+        assert_eq!(stats.amount_eliminated, Decimal::from(140));
+        assert_eq!(amount(&graph, n[0], n[1]), Decimal::from(60));
+        assert_eq!(amount(&graph, n[2], n[1]), Decimal::from(40));
+        assert_eq!(graph.edge_count(), 2);
+    }
 
-        // Create cycle with min flow of 50
-        graph_builder::add_or_update_edge(&mut graph, idx_a, idx_b, Decimal::from(100), Uuid::new_v4());
-        graph_builder::add_or_update_edge(&mut graph, idx_b, idx_c, Decimal::from(50), Uuid::new_v4());
-        graph_builder::add_or_update_edge(&mut graph, idx_c, idx_a, Decimal::from(75), Uuid::new_v4());
+    #[test]
+    fn test_settlement_rerouted_over_shorter_path() {
+        // A owes D 10 via B and 5 directly, and the ring D→E→A→D could absorb the direct 5.
+        // Cancelling only the ring would leave 20; routing 5 more directly A→D leaves 15.
+        let (mut graph, n) = build_graph(
+            5,
+            &[
+                (0, 1, Decimal::from(10)),
+                (1, 3, Decimal::from(10)),
+                (0, 3, Decimal::from(5)),
+                (3, 4, Decimal::from(5)),
+                (4, 0, Decimal::from(5)),
+            ],
+        );
+        let before = net_positions(&graph);
+
+        let stats = optimize_graph(&mut graph, "USD").unwrap();
+
+        assert_eq!(graph_builder::calculate_gross_value(&graph), Decimal::from(15));
+        assert_eq!(stats.amount_eliminated, Decimal::from(20));
+        assert_eq!(amount(&graph, n[0], n[3]), Decimal::from(5));
+        assert_eq!(net_positions(&graph), before);
+    }
 
-        let cycle = vec![idx_a, idx_b, idx_c];
-        let eliminated = process_cycle(&mut graph, &cycle).unwrap();
+    #[test]
+    fn test_acyclic_graph_untouched() {
+        let (mut graph, _) = build_graph(3, &[(0, 1, Decimal::from(10)), (1, 2, Decimal::from(5))]);
+
+        let stats = optimize_graph(&mut graph, "USD").unwrap();
 
-        // Should eliminate 50 * 3 = 150
-        assert_eq!(eliminated, Decimal::from(150));
+        assert_eq!(stats.cycles_found, 0);
+        assert_eq!(stats.amount_eliminated, Decimal::ZERO);
+        assert_eq!(graph.edge_count(), 2);
+    }
 
-        // Check remaining edges
-        let edge_ab = graph.find_edge(idx_a, idx_b).unwrap();
-        assert_eq!(graph.edge_weight(edge_ab).unwrap().amount, Decimal::from(50));
+    #[test]
+    fn test_potential_savings() {
+        let (graph, _) = build_graph(
+            3,
+            &[(0, 1, Decimal::from(100)), (1, 2, Decimal::from(50)), (2, 0, Decimal::from(75))],
+        );
+
+        assert_eq!(calculate_potential_savings(&graph), Decimal::from(150));
+        // The graph itself is not modified
+        assert_eq!(graph_builder::calculate_gross_value(&graph), Decimal::from(225));
+    }
 
-        let edge_bc = graph.find_edge(idx_b, idx_c).unwrap();
-        assert_eq!(graph.edge_weight(edge_bc).unwrap().amount, Decimal::ZERO);
+    fn obligations() -> impl Strategy<Value = (usize, Vec<(usize, usize, Decimal)>)> {
+        (2usize..8).prop_flat_map(|banks| {
+            let obligation = (0..banks, 0..banks, 1i64..1_000_000, 0u32..3)
+                .prop_filter("no self-obligations", |(from, to, _, _)| from != to)
+                .prop_map(|(from, to, units, scale)| (from, to, Decimal::new(units, scale)));
+            (Just(banks), prop::collection::vec(obligation, 0..24))
+        })
+    }
+
+    /// Denser windows with amounts up to 10^18 units, down to 8 decimals
+    fn large_obligations() -> impl Strategy<Value = (usize, Vec<(usize, usize, Decimal)>)> {
+        (8usize..24).prop_flat_map(|banks| {
+            let obligation = (0..banks, 0..banks, 1i64..1_000_000_000_000_000_000, 0u32..9)
+                .prop_filter("no self-obligations", |(from, to, _, _)| from != to)
+                .prop_map(|(from, to, units, scale)| (from, to, Decimal::new(units, scale)));
+            (Just(banks), prop::collection::vec(obligation, 40..120))
+        })
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn prop_large_amounts_optimize_quickly((banks, obligations) in large_obligations()) {
+            let (mut graph, _) = build_graph(banks, &obligations);
+            let before = net_positions(&graph);
+
+            // Rounds depend on the graph size, never on the amounts
+            let started = Instant::now();
+            optimize_graph(&mut graph, "USD").unwrap();
+            prop_assert!(started.elapsed() < Duration::from_secs(5), "took {:?}", started.elapsed());
+
+            prop_assert!(!is_cyclic_directed(&graph));
+            prop_assert_eq!(net_positions(&graph), before);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn prop_net_positions_invariant((banks, obligations) in obligations()) {
+            let (mut graph, _) = build_graph(banks, &obligations);
+            let before = net_positions(&graph);
+
+            optimize_graph(&mut graph, "USD").unwrap();
+
+            prop_assert_eq!(net_positions(&graph), before);
+        }
+
+        #[test]
+        fn prop_result_is_minimal((banks, obligations) in obligations()) {
+            let (original, _) = build_graph(banks, &obligations);
+            let mut graph = original.clone();
+            let gross_before = graph_builder::calculate_gross_value(&graph);
+
+            let stats = optimize_graph(&mut graph, "USD").unwrap();
+            let gross_after = graph_builder::calculate_gross_value(&graph);
+
+            prop_assert!(!is_cyclic_directed(&graph));
+            prop_assert_eq!(stats.amount_eliminated, gross_before - gross_after);
+
+            // Every remaining edge pays part of what was owed on it, never more
+            for edge in graph.edge_references() {
+                let owed = amount(&original, edge.source(), edge.target());
+                prop_assert!(edge.weight().amount > Decimal::ZERO && edge.weight().amount <= owed);
+            }
+
+            // Each net payer has to pay at least its net position
+            let lower_bound: Decimal = net_positions(&original).values().filter(|n| n.is_sign_positive()).sum();
+            prop_assert!(gross_after >= lower_bound);
+
+            // Optimal: no negative cycle left against the original obligations
+            let mut residual = original.clone();
+            for edge in residual.edge_indices().collect::<Vec<_>>() {
+                let (from, to) = residual.edge_endpoints(edge).unwrap();
+                residual[edge].amount = amount(&graph, from, to);
+            }
+            let capacity: Vec<Decimal> = original.edge_weights().map(|e| e.amount).collect();
+            prop_assert!(find_minimum_mean_cycle(&residual, &capacity).is_none());
+        }
     }
 }
@@ -1,22 +1,37 @@
 // Clearing Orchestrator - Coordinates the entire clearing process
 
 use crate::errors::{ClearingError, Result};
-use crate::models::{NetPosition, SettlementInstruction, WindowStatus};
-use crate::netting::NettingEngine;
+use crate::models::{ClearingWindow, NetPosition, NettingMode, SettlementInstruction, SettlementStatusChange, WindowStatus};
+use crate::netting::cross_currency::{self, FxConversion, FxRate, FxSnapshot};
+use crate::netting::{ModeComparison, ModeStats, NettingEngine};
 use crate::window::WindowManager;
-use chrono::Utc;
+use chrono::{Duration, Utc};
 use rust_decimal::Decimal;
 use sqlx::PgPool;
 use std::collections::HashMap;
 use std::sync::Arc;
+use tokio::sync::broadcast;
 use tracing::{info, warn, error};
 use uuid::Uuid;
 
+/// Settlement status changes buffered per subscriber before it starts lagging
+const SETTLEMENT_UPDATES_CAPACITY: usize = 1024;
+
+/// Statuses the settlement engine reports for a settled instruction
+const SETTLED_STATUSES: [&str; 3] = ["SUCCESS", "COMPLETED", "SETTLED"];
+
+/// Oldest FX quote, relative to the window cutoff, cross-currency netting converts at
+const DEFAULT_MAX_FX_RATE_AGE_SECONDS: i64 = 3600;
+
 /// Main orchestrator for clearing process
 pub struct ClearingOrchestrator {
     window_manager: Arc<WindowManager>,
     db_pool: Arc<PgPool>,
     nats_client: Option<async_nats::Client>,
+    settlement_updates: broadcast::Sender<SettlementStatusChange>,
+    /// DelTran account multilateral windows settle against
+    settlement_account: Option<Uuid>,
+    max_fx_rate_age: Duration,
 }
 
 impl ClearingOrchestrator {
@@ -25,15 +40,50 @@ impl ClearingOrchestrator {
         db_pool: Arc<PgPool>,
         nats_client: Option<async_nats::Client>,
     ) -> Self {
+        let (settlement_updates, _) = broadcast::channel(SETTLEMENT_UPDATES_CAPACITY);
         Self {
             window_manager,
             db_pool,
             nats_client,
+            settlement_updates,
+            settlement_account: None,
+            max_fx_rate_age: Duration::seconds(DEFAULT_MAX_FX_RATE_AGE_SECONDS),
         }
     }
 
+    /// Enable multilateral netting, settling every participant against `account`
+    pub fn with_settlement_account(mut self, account: Uuid) -> Self {
+        self.settlement_account = Some(account);
+        self
+    }
+
+    /// Leave currency pairs not quoted within `age` of a window's cutoff out of its FX snapshot
+    pub fn with_max_fx_rate_age(mut self, age: Duration) -> Self {
+        self.max_fx_rate_age = age;
+        self
+    }
+
+    pub fn settlement_account(&self) -> Option<Uuid> {
+        self.settlement_account
+    }
+
+    /// Subscribe to status changes of the settlement instructions this engine generates
+    pub fn subscribe_settlements(&self) -> broadcast::Receiver<SettlementStatusChange> {
+        self.settlement_updates.subscribe()
+    }
+
     /// Execute complete clearing cycle for a window
     pub async fn execute_clearing(&self, window_id: i64) -> Result<ClearingResult> {
+        self.execute_clearing_limited(window_id, None).await
+    }
+
+    /// Execute a clearing cycle over at most `max_obligations` of the window's oldest
+    /// pending obligations (emergency clearing); the rest stay pending
+    pub async fn execute_clearing_limited(
+        &self,
+        window_id: i64,
+        max_obligations: Option<i64>,
+    ) -> Result<ClearingResult> {
         info!("Starting clearing execution for window {}", window_id);
 
         let start_time = std::time::Instant::now();
@@ -47,9 +97,20 @@ impl ClearingOrchestrator {
             });
         }
 
+        let netting_mode = window.netting_mode();
+        let settlement_account = match (netting_mode, self.settlement_account) {
+            (NettingMode::Multilateral, None) => {
+                return Err(ClearingError::Configuration(
+                    "Multilateral netting needs DELTRAN_SETTLEMENT_ACCOUNT_ID".to_string(),
+                ))
+            }
+            // Only labels the counterparty of the comparison positions in bilateral mode
+            (_, account) => account.unwrap_or_else(Uuid::nil),
+        };
+
         // Step 2: Collect obligations
         info!("Collecting obligations for window {}", window_id);
-        let obligations = self.collect_obligations(window_id).await?;
+        let obligations = self.collect_obligations(window_id, max_obligations).await?;
         info!("Collected {} obligations", obligations.len());
 
         // Step 3: Build netting engine and add obligations
@@ -64,6 +125,9 @@ impl ClearingOrchestrator {
             )?;
         }
 
+        // Multilateral positions are the banks' overall nets, whatever the optimizer does
+        let participant_positions = netting_engine.calculate_participant_positions(settlement_account)?;
+
         // Step 4: Optimize (eliminate cycles)
         info!("Optimizing netting graph for window {}", window_id);
         let optimizer_stats = netting_engine.optimize()?;
@@ -73,30 +137,62 @@ impl ClearingOrchestrator {
         );
 
         // Step 5: Calculate net positions
-        info!("Calculating net positions for window {}", window_id);
-        let net_positions = netting_engine.calculate_net_positions()?;
+        info!("Calculating {} net positions for window {}", netting_mode.as_str(), window_id);
+        let bilateral_positions = netting_engine.calculate_net_positions()?;
+        let gross_value = self.calculate_gross_value(&obligations);
+        let mut mode_comparison = ModeComparison {
+            bilateral: ModeStats::bilateral(gross_value, &bilateral_positions),
+            multilateral: ModeStats::multilateral(gross_value, &participant_positions),
+        };
+        let mut net_positions = match netting_mode {
+            NettingMode::Bilateral => bilateral_positions,
+            NettingMode::Multilateral => participant_positions,
+        };
         info!("Calculated {} net positions", net_positions.len());
 
+        // Step 5b: Offset opposing exposures in different currencies at the frozen rates. Bilateral
+        // only: against the settlement account an offset would unbalance it in both currencies.
+        if window.cross_currency() && netting_mode == NettingMode::Multilateral {
+            warn!(
+                "Skipping cross-currency netting for window {}: not supported in multilateral mode",
+                window_id
+            );
+        }
+        let (fx_snapshot, fx_conversions) = if window.cross_currency() && netting_mode == NettingMode::Bilateral {
+            let snapshot = self.fx_snapshot(&window, &net_positions).await?;
+            let conversions = cross_currency::offset_positions(&mut net_positions, &snapshot)?;
+            info!(
+                "Offset {} cross-currency exposures at {} rates frozen at {}",
+                conversions.len(),
+                snapshot.rates.len(),
+                snapshot.taken_at
+            );
+            (Some(snapshot), conversions)
+        } else {
+            (None, Vec::new())
+        };
+        let mode_stats = match netting_mode {
+            NettingMode::Bilateral => ModeStats::bilateral(gross_value, &net_positions),
+            NettingMode::Multilateral => ModeStats::multilateral(gross_value, &net_positions),
+        };
+        if fx_snapshot.is_some() {
+            // Bilateral now settles the offset positions - compare what is actually settled
+            mode_comparison.bilateral = mode_stats.clone();
+        }
+
         // Step 6: Persist net positions
         self.save_net_positions(&net_positions).await?;
 
         // Step 7: Generate settlement instructions
         info!("Generating settlement instructions for window {}", window_id);
-        let instructions = self.generate_settlement_instructions(&net_positions).await?;
+        let instructions = self
+            .generate_settlement_instructions(&net_positions, netting_mode)
+            .await?;
         info!("Generated {} settlement instructions", instructions.len());
 
         // Step 8: Calculate metrics
-        let stats = netting_engine.get_stats();
-        let gross_value = self.calculate_gross_value(&obligations);
-        let net_value = self.calculate_net_value(&net_positions);
-        let efficiency = if gross_value > Decimal::ZERO {
-            (gross_value - net_value)
-                .checked_div(gross_value)
-                .unwrap_or(Decimal::ZERO)
-                * Decimal::from(100)
-        } else {
-            Decimal::ZERO
-        };
+        let net_value = mode_stats.net_value;
+        let efficiency = mode_stats.efficiency_percent;
 
         // Step 9: Update window metrics
         self.window_manager
@@ -138,20 +234,236 @@ impl ClearingOrchestrator {
             efficiency_percent: efficiency,
             cycles_eliminated: optimizer_stats.cycles_found,
             processing_time_ms: processing_time,
+            netting_mode,
+            mode_comparison,
+            fx_snapshot,
+            fx_conversions,
+            net_positions,
+            instructions,
         })
     }
 
-    /// Collect all obligations for a window
-    async fn collect_obligations(&self, window_id: i64) -> Result<Vec<Obligation>> {
+    /// Record a status reported by the settlement engine for one of our instructions.
+    /// Returns the instruction's window, or None if the instruction was not generated here.
+    /// A settled pay-in may release the held pay-outs of its window.
+    pub async fn record_settlement_status(
+        &self,
+        instruction_id: Uuid,
+        settlement_id: Option<Uuid>,
+        status: &str,
+        details: String,
+    ) -> Result<Option<i64>> {
+        let instruction: Option<(i64, String, String)> = sqlx::query_as(
+            r#"
+            UPDATE settlement_instructions
+            SET status = $1, settlement_id = COALESCE($2, settlement_id)
+            WHERE id = $3
+            RETURNING window_id, instruction_type, currency
+            "#,
+        )
+        .bind(status)
+        .bind(settlement_id)
+        .bind(instruction_id)
+        .fetch_optional(self.db_pool.as_ref())
+        .await
+        .map_err(|e| ClearingError::DatabaseError(e.to_string()))?;
+
+        let Some((window_id, instruction_type, currency)) = instruction else {
+            return Ok(None);
+        };
+
+        self.notify_settlement(SettlementStatusChange {
+            window_id,
+            instruction_id,
+            settlement_id,
+            status: status.to_string(),
+            details,
+            occurred_at: Utc::now(),
+        });
+
+        if instruction_type == "CCP_PAY_IN" && SETTLED_STATUSES.contains(&status.to_ascii_uppercase().as_str()) {
+            self.release_pay_outs(window_id, &currency).await?;
+        }
+
+        Ok(Some(window_id))
+    }
+
+    /// Release the HELD pay-outs of a window and currency once every pay-in of that currency
+    /// has settled, so the settlement account only pays out what it has been paid
+    async fn release_pay_outs(&self, window_id: i64, currency: &str) -> Result<()> {
+        let released: Vec<(Uuid, Uuid, Uuid, Decimal)> = sqlx::query_as(
+            r#"
+            UPDATE settlement_instructions
+            SET status = 'PENDING'
+            WHERE window_id = $1 AND currency = $2
+              AND instruction_type = 'CCP_PAY_OUT' AND status = 'HELD'
+              AND NOT EXISTS (
+                  SELECT 1 FROM settlement_instructions
+                  WHERE window_id = $1 AND currency = $2
+                    AND instruction_type = 'CCP_PAY_IN' AND UPPER(status) <> ALL($3)
+              )
+            RETURNING id, payer_bank_id, payee_bank_id, amount
+            "#,
+        )
+        .bind(window_id)
+        .bind(currency)
+        .bind(&SETTLED_STATUSES[..])
+        .fetch_all(self.db_pool.as_ref())
+        .await
+        .map_err(|e| ClearingError::DatabaseError(e.to_string()))?;
+
+        if !released.is_empty() {
+            info!("Released {} {} pay-outs of window {}: all pay-ins settled", released.len(), currency, window_id);
+        }
+        for (instruction_id, payer, payee, amount) in released {
+            self.notify_settlement(SettlementStatusChange {
+                window_id,
+                instruction_id,
+                settlement_id: None,
+                status: "PENDING".to_string(),
+                details: format!("{} {} from {} to {}, pay-ins settled", amount, currency, payer, payee),
+                occurred_at: Utc::now(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Net positions calculated for a window, largest first
+    pub async fn net_positions(&self, window_id: i64) -> Result<Vec<NetPosition>> {
+        sqlx::query_as::<_, NetPosition>(
+            r#"
+            SELECT id, window_id, bank_pair_hash, bank_a_id, bank_b_id, currency,
+                   gross_debit_a_to_b, gross_credit_b_to_a, net_amount, net_direction,
+                   net_payer_id, net_receiver_id, obligations_netted, netting_ratio,
+                   amount_saved, created_at
+            FROM net_positions
+            WHERE window_id = $1
+            ORDER BY net_amount DESC
+            "#,
+        )
+        .bind(window_id)
+        .fetch_all(self.db_pool.as_ref())
+        .await
+        .map_err(|e| ClearingError::DatabaseError(e.to_string()))
+    }
+
+    /// Settlement instructions generated for a window, in settlement order
+    pub async fn settlement_instructions(&self, window_id: i64) -> Result<Vec<SettlementInstruction>> {
+        sqlx::query_as::<_, SettlementInstruction>(
+            r#"
+            SELECT id, window_id, net_position_id, payer_bank_id, payee_bank_id,
+                   amount, currency, instruction_type, priority, deadline, status,
+                   sent_to_settlement_at, settlement_id, instruction_data, created_at
+            FROM settlement_instructions
+            WHERE window_id = $1
+            ORDER BY priority ASC, created_at ASC
+            "#,
+        )
+        .bind(window_id)
+        .fetch_all(self.db_pool.as_ref())
+        .await
+        .map_err(|e| ClearingError::DatabaseError(e.to_string()))
+    }
+
+    /// Delete the net positions and instructions of a window so it can be netted again.
+    /// Refused once the settlement engine has picked up any of the instructions.
+    pub async fn discard_results(&self, window_id: i64) -> Result<()> {
+        let mut tx = self
+            .db_pool
+            .begin()
+            .await
+            .map_err(|e| ClearingError::DatabaseError(e.to_string()))?;
+
+        let in_settlement: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM settlement_instructions WHERE window_id = $1 AND status NOT IN ('PENDING', 'HELD')",
+        )
+        .bind(window_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| ClearingError::DatabaseError(e.to_string()))?;
+
+        if in_settlement > 0 {
+            return Err(ClearingError::InvalidWindowState {
+                expected: "all settlement instructions PENDING or HELD".to_string(),
+                actual: format!("{} instructions in settlement", in_settlement),
+            });
+        }
+
+        sqlx::query("DELETE FROM settlement_instructions WHERE window_id = $1")
+            .bind(window_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ClearingError::DatabaseError(e.to_string()))?;
+        sqlx::query("DELETE FROM net_positions WHERE window_id = $1")
+            .bind(window_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ClearingError::DatabaseError(e.to_string()))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| ClearingError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// FX rates of the window's currencies as last quoted at its cutoff (or now, for a window
+    /// closed early), frozen on the window the first time they are taken. Pairs whose last quote
+    /// is older than the maximum rate age are left out.
+    async fn fx_snapshot(&self, window: &ClearingWindow, positions: &[NetPosition]) -> Result<FxSnapshot> {
+        if let Some(snapshot) = window.fx_snapshot() {
+            return Ok(snapshot);
+        }
+
+        let mut currencies: Vec<&str> = positions.iter().map(|p| p.currency.as_str()).collect();
+        currencies.sort_unstable();
+        currencies.dedup();
+        let taken_at = window.cutoff_time.min(Utc::now());
+
+        let rates = sqlx::query_as::<_, FxRate>(
+            r#"
+            SELECT DISTINCT ON (base_currency, quote_currency)
+                   base_currency, quote_currency, mid_price AS rate, tick_timestamp AS observed_at
+            FROM fx_rate_ticks
+            WHERE tick_timestamp <= $1 AND tick_timestamp >= $3
+              AND base_currency = ANY($2) AND quote_currency = ANY($2)
+            ORDER BY base_currency, quote_currency, tick_timestamp DESC
+            "#,
+        )
+        .bind(taken_at)
+        .bind(&currencies)
+        .bind(taken_at - self.max_fx_rate_age)
+        .fetch_all(self.db_pool.as_ref())
+        .await
+        .map_err(|e| ClearingError::DatabaseError(e.to_string()))?;
+
+        let snapshot = FxSnapshot {
+            taken_at,
+            max_rate_age_seconds: self.max_fx_rate_age.num_seconds(),
+            rates,
+        };
+        self.window_manager.freeze_fx_snapshot(window.id, &snapshot).await
+    }
+
+    fn notify_settlement(&self, change: SettlementStatusChange) {
+        // No subscribers is fine - nobody is streaming settlement status
+        let _ = self.settlement_updates.send(change);
+    }
+
+    /// Collect pending obligations for a window, oldest first (LIMIT NULL = all)
+    async fn collect_obligations(&self, window_id: i64, limit: Option<i64>) -> Result<Vec<Obligation>> {
         let obligations = sqlx::query_as::<_, Obligation>(
             r#"
             SELECT id, window_id, payer_id, payee_id, amount, currency, created_at
             FROM obligations
             WHERE window_id = $1 AND status = 'PENDING'
             ORDER BY created_at ASC
+            LIMIT $2
             "#,
         )
         .bind(window_id)
+        .bind(limit)
         .fetch_all(self.db_pool.as_ref())
         .await
         .map_err(|e| ClearingError::DatabaseError(e.to_string()))?;
@@ -200,6 +512,7 @@ impl ClearingOrchestrator {
     async fn generate_settlement_instructions(
         &self,
         positions: &[NetPosition],
+        netting_mode: NettingMode,
     ) -> Result<Vec<SettlementInstruction>> {
         let mut instructions = Vec::new();
 
@@ -220,6 +533,13 @@ impl ClearingOrchestrator {
                 continue;
             };
 
+            // Pay-outs are HELD until every pay-in of their currency has settled (release_pay_outs)
+            let (instruction_type, priority, status) = match netting_mode {
+                NettingMode::Bilateral => ("NET_SETTLEMENT", 1, "PENDING"),
+                NettingMode::Multilateral if position.net_direction == "A_TO_B" => ("CCP_PAY_IN", 1, "PENDING"),
+                NettingMode::Multilateral => ("CCP_PAY_OUT", 2, "HELD"),
+            };
+
             let instruction = SettlementInstruction {
                 id: Uuid::new_v4(),
                 window_id: position.window_id,
@@ -228,15 +548,16 @@ impl ClearingOrchestrator {
                 payee_bank_id: payee,
                 amount: position.net_amount,
                 currency: position.currency.clone(),
-                instruction_type: "NET_SETTLEMENT".to_string(),
-                priority: 1,
-                deadline: Utc::now() + chrono::Duration::hours(2),
-                status: "PENDING".to_string(),
+                instruction_type: instruction_type.to_string(),
+                priority,
+                deadline: Utc::now() + Duration::hours(2),
+                status: status.to_string(),
                 sent_to_settlement_at: None,
                 settlement_id: None,
                 instruction_data: serde_json::json!({
                     "bank_pair_hash": position.bank_pair_hash,
                     "obligations_netted": position.obligations_netted,
+                    "netting_mode": netting_mode.as_str(),
                 }),
                 created_at: Utc::now(),
             };
@@ -268,6 +589,18 @@ impl ClearingOrchestrator {
             .await
             .map_err(|e| ClearingError::DatabaseError(e.to_string()))?;
 
+            self.notify_settlement(SettlementStatusChange {
+                window_id: instruction.window_id,
+                instruction_id: instruction.id,
+                settlement_id: None,
+                status: instruction.status.clone(),
+                details: format!(
+                    "{} {} from {} to {}",
+                    instruction.amount, instruction.currency, instruction.payer_bank_id, instruction.payee_bank_id
+                ),
+                occurred_at: instruction.created_at,
+            });
+
             instructions.push(instruction);
         }
 
@@ -281,13 +614,6 @@ impl ClearingOrchestrator {
             .fold(Decimal::ZERO, |acc, o| acc + o.amount)
     }
 
-    /// Calculate total net value
-    fn calculate_net_value(&self, positions: &[NetPosition]) -> Decimal {
-        positions
-            .iter()
-            .fold(Decimal::ZERO, |acc, p| acc + p.net_amount)
-    }
-
     /// Publish clearing completion event to NATS
     async fn publish_clearing_event(
         &self,
@@ -340,4 +666,11 @@ pub struct ClearingResult {
     pub efficiency_percent: Decimal,
     pub cycles_eliminated: usize,
     pub processing_time_ms: u64,
+    pub netting_mode: NettingMode,
+    pub mode_comparison: ModeComparison,
+    /// Rates the window was netted across currencies at, if it was
+    pub fx_snapshot: Option<FxSnapshot>,
+    pub fx_conversions: Vec<FxConversion>,
+    pub net_positions: Vec<NetPosition>,
+    pub instructions: Vec<SettlementInstruction>,
 }
@@ -0,0 +1,421 @@
+// Window Processor - runs netting for a window and reports what it produced
+// Every processing run is recorded as an atomic NettingCalculation operation, so a window can be
+// rolled back and reprocessed. Results are read back from the database, so they survive restarts
+// and are the same whichever instance processed the window.
+
+use crate::atomic::AtomicController;
+use crate::errors::{ClearingError, Result};
+use crate::models::{AtomicOperationType, ClearingStatus, ClearingWindow, NettingMode, WindowStatus};
+use crate::netting::cross_currency::{FxConversion, FxSnapshot};
+use crate::netting::ModeComparison;
+use crate::orchestrator::ClearingOrchestrator;
+use crate::window::WindowManager;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use serde_json::json;
+use std::sync::Arc;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+pub struct WindowProcessor {
+    window_manager: Arc<WindowManager>,
+    orchestrator: Arc<ClearingOrchestrator>,
+    atomic_controller: Arc<AtomicController>,
+}
+
+/// Netting outcome of a processed window
+#[derive(Debug, Clone, Serialize)]
+pub struct WindowResult {
+    pub window_id: i64,
+    pub status: ClearingStatus,
+    pub obligations_count: i32,
+    pub gross_value: Decimal,
+    pub net_value: Decimal,
+    pub saved_amount: Decimal,
+    pub efficiency_percent: Decimal,
+    pub cycles_eliminated: u64,
+    pub processing_time_ms: u64,
+    pub netting_mode: NettingMode,
+    /// What the window would have cost to settle in either mode
+    pub mode_comparison: Option<ModeComparison>,
+    pub cross_currency: bool,
+    /// Rates frozen at cutoff that opposing exposures in different currencies were offset at
+    pub fx_snapshot: Option<FxSnapshot>,
+    pub fx_conversions: Vec<FxConversion>,
+    pub processed_at: Option<DateTime<Utc>>,
+    pub error: Option<String>,
+}
+
+impl WindowProcessor {
+    pub fn new(
+        window_manager: Arc<WindowManager>,
+        orchestrator: Arc<ClearingOrchestrator>,
+        atomic_controller: Arc<AtomicController>,
+    ) -> Self {
+        Self {
+            window_manager,
+            orchestrator,
+            atomic_controller,
+        }
+    }
+
+    pub fn window_manager(&self) -> &Arc<WindowManager> {
+        &self.window_manager
+    }
+
+    pub fn orchestrator(&self) -> &Arc<ClearingOrchestrator> {
+        &self.orchestrator
+    }
+
+    pub fn atomic_controller(&self) -> &Arc<AtomicController> {
+        &self.atomic_controller
+    }
+
+    /// Choose bilateral or multilateral netting for a window
+    pub async fn set_netting_mode(&self, window_id: i64, netting_mode: NettingMode) -> Result<ClearingWindow> {
+        if netting_mode == NettingMode::Multilateral && self.orchestrator.settlement_account().is_none() {
+            return Err(ClearingError::Validation(
+                "Multilateral netting needs DELTRAN_SETTLEMENT_ACCOUNT_ID".to_string(),
+            ));
+        }
+
+        self.window_manager.set_netting_mode(window_id, netting_mode).await
+    }
+
+    /// Offset opposing exposures in different currencies when netting a window
+    pub async fn set_cross_currency(&self, window_id: i64, enabled: bool) -> Result<ClearingWindow> {
+        self.window_manager.set_cross_currency(window_id, enabled).await
+    }
+
+    /// Move a window to Processing and net it in the background.
+    /// A Failed or RolledBack window is reprocessed: its previous positions and instructions are discarded.
+    pub async fn start(self: &Arc<Self>, window_id: i64, force_settlement: bool) -> Result<DateTime<Utc>> {
+        self.claim(window_id, force_settlement).await?;
+        let started_at = Utc::now();
+
+        let processor = self.clone();
+        tokio::spawn(async move {
+            match processor.run(window_id, None).await {
+                Ok(result) => info!("Window {} processed: {:?}", window_id, result.status),
+                Err(e) => error!("Processing window {} failed: {}", window_id, e),
+            }
+        });
+
+        Ok(started_at)
+    }
+
+    /// Net a ready window now and wait for the outcome (emergency clearing), over at most
+    /// `max_obligations` obligations. Same state checks as `start`.
+    pub async fn process_now(
+        &self,
+        window_id: i64,
+        force_settlement: bool,
+        max_obligations: Option<i64>,
+    ) -> Result<WindowResult> {
+        self.claim(window_id, force_settlement).await?;
+        self.run(window_id, max_obligations).await
+    }
+
+    /// Move a ready window to Processing. The status is switched by a single conditional UPDATE,
+    /// so of two concurrent requests only one gets to net the window.
+    async fn claim(&self, window_id: i64, force_settlement: bool) -> Result<()> {
+        let window = self.window_manager.get_window(window_id).await?;
+
+        let mut ready = vec![WindowStatus::Closed, WindowStatus::Failed, WindowStatus::RolledBack];
+        // Still in its grace period unless that expired or settlement is forced
+        if force_settlement || self.window_manager.is_grace_period_expired(&window) {
+            ready.push(WindowStatus::Closing);
+        }
+
+        let previous = self
+            .window_manager
+            .transition_status(window_id, &ready, WindowStatus::Processing)
+            .await?;
+
+        let previous_status = WindowStatus::from_str(&previous.status);
+        if matches!(previous_status, WindowStatus::Failed | WindowStatus::RolledBack) {
+            info!("Reprocessing window {} ({})", window_id, previous.status);
+            if let Err(e) = self.orchestrator.discard_results(window_id).await {
+                self.window_manager.update_status(window_id, previous_status).await?;
+                return Err(e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Net a window already in Processing, over at most `max_obligations` obligations.
+    /// A failed run leaves the window Failed and is reported in the result, not as an error.
+    pub async fn run(&self, window_id: i64, max_obligations: Option<i64>) -> Result<WindowResult> {
+        let started = std::time::Instant::now();
+        let operation = self
+            .atomic_controller
+            .create_operation(window_id, AtomicOperationType::NettingCalculation)
+            .await?;
+        operation.start().await?;
+
+        match self.orchestrator.execute_clearing_limited(window_id, max_obligations).await {
+            Ok(result) => {
+                let instruction_ids: Vec<Uuid> = result.instructions.iter().map(|i| i.id).collect();
+                operation
+                    .checkpoint(
+                        "netting_calculated".to_string(),
+                        json!({
+                            "net_positions": result.net_positions.iter().map(|p| p.id).collect::<Vec<_>>(),
+                            "instructions": instruction_ids,
+                        }),
+                    )
+                    .await?;
+                operation.commit().await?;
+
+                self.window_manager
+                    .record_processing(
+                        window_id,
+                        &instruction_ids,
+                        json!({
+                            "cycles_eliminated": result.cycles_eliminated,
+                            "processing_time_ms": result.processing_time_ms,
+                            "mode_comparison": result.mode_comparison,
+                            "fx_conversions": result.fx_conversions,
+                            "last_error": null,
+                        }),
+                    )
+                    .await?;
+
+                Ok(WindowResult {
+                    window_id,
+                    status: ClearingStatus::Success,
+                    obligations_count: result.obligations_count as i32,
+                    gross_value: result.gross_value,
+                    net_value: result.net_value,
+                    saved_amount: result.saved_amount,
+                    efficiency_percent: result.efficiency_percent,
+                    cycles_eliminated: result.cycles_eliminated as u64,
+                    processing_time_ms: result.processing_time_ms,
+                    netting_mode: result.netting_mode,
+                    mode_comparison: Some(result.mode_comparison),
+                    cross_currency: result.fx_snapshot.is_some(),
+                    fx_snapshot: result.fx_snapshot,
+                    fx_conversions: result.fx_conversions,
+                    processed_at: Some(Utc::now()),
+                    error: None,
+                })
+            }
+            Err(e) => {
+                let (netting_mode, cross_currency, fx_snapshot) = match self.window_manager.get_window(window_id).await {
+                    Ok(window) => (window.netting_mode(), window.cross_currency(), window.fx_snapshot()),
+                    Err(_) => (NettingMode::default(), false, None),
+                };
+                let processing_time_ms = started.elapsed().as_millis() as u64;
+                operation.fail(e.to_string(), None).await?;
+                if let Err(status_err) = self.window_manager.update_status(window_id, WindowStatus::Failed).await {
+                    error!("Failed to mark window {} as Failed: {}", window_id, status_err);
+                }
+                if let Err(record_err) = self
+                    .window_manager
+                    .record_processing(
+                        window_id,
+                        &[],
+                        json!({
+                            "processing_time_ms": processing_time_ms,
+                            "mode_comparison": null,
+                            "fx_conversions": [],
+                            "last_error": e.to_string(),
+                        }),
+                    )
+                    .await
+                {
+                    error!("Failed to record error of window {}: {}", window_id, record_err);
+                }
+
+                Ok(WindowResult {
+                    window_id,
+                    status: ClearingStatus::Failed,
+                    obligations_count: 0,
+                    gross_value: Decimal::ZERO,
+                    net_value: Decimal::ZERO,
+                    saved_amount: Decimal::ZERO,
+                    efficiency_percent: Decimal::ZERO,
+                    cycles_eliminated: 0,
+                    processing_time_ms,
+                    netting_mode,
+                    mode_comparison: None,
+                    cross_currency,
+                    fx_snapshot,
+                    fx_conversions: Vec::new(),
+                    processed_at: None,
+                    error: Some(e.to_string()),
+                })
+            }
+        }
+    }
+
+    /// Outcome of the last processing run of a window
+    pub async fn result(&self, window_id: i64) -> Result<WindowResult> {
+        let window = self.window_manager.get_window(window_id).await?;
+        window_result(&window)
+    }
+
+    /// Undo the processing of a window: roll back its atomic operations and discard its
+    /// positions and instructions. Returns the rolled back operations.
+    pub async fn rollback(&self, window_id: i64, reason: &str) -> Result<Vec<Uuid>> {
+        let window = self.window_manager.get_window(window_id).await?;
+        if window.status == WindowStatus::Processing.as_str() {
+            return Err(ClearingError::InvalidWindowState {
+                expected: "not Processing".to_string(),
+                actual: window.status,
+            });
+        }
+        warn!("Rolling back window {}: {}", window_id, reason);
+
+        self.orchestrator.discard_results(window_id).await?;
+        let rolled_back = self
+            .atomic_controller
+            .rollback_window_operations(window_id, reason.to_string())
+            .await?;
+        self.window_manager.update_status(window_id, WindowStatus::RolledBack).await?;
+
+        Ok(rolled_back)
+    }
+}
+
+/// Result recorded on a window by its last processing run
+pub fn window_result(window: &ClearingWindow) -> Result<WindowResult> {
+    let status = match WindowStatus::from_str(&window.status) {
+        WindowStatus::Failed => ClearingStatus::Failed,
+        WindowStatus::RolledBack => ClearingStatus::RolledBack,
+        WindowStatus::Settling | WindowStatus::Completed => ClearingStatus::Success,
+        _ => {
+            return Err(ClearingError::InvalidWindowState {
+                expected: "Settling, Completed, Failed or RolledBack".to_string(),
+                actual: window.status.clone(),
+            })
+        }
+    };
+    let metadata_u64 = |key: &str| window.metadata.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+
+    Ok(WindowResult {
+        window_id: window.id,
+        status,
+        obligations_count: window.obligations_count,
+        gross_value: window.total_gross_value,
+        net_value: window.total_net_value,
+        saved_amount: window.saved_amount,
+        efficiency_percent: window.netting_efficiency,
+        cycles_eliminated: metadata_u64("cycles_eliminated"),
+        processing_time_ms: metadata_u64("processing_time_ms"),
+        netting_mode: window.netting_mode(),
+        mode_comparison: window
+            .metadata
+            .get("mode_comparison")
+            .and_then(|c| serde_json::from_value(c.clone()).ok()),
+        cross_currency: window.cross_currency(),
+        fx_snapshot: window.fx_snapshot(),
+        fx_conversions: window
+            .metadata
+            .get("fx_conversions")
+            .and_then(|c| serde_json::from_value(c.clone()).ok())
+            .unwrap_or_default(),
+        processed_at: window.processed_at,
+        error: window.metadata.get("last_error").and_then(|v| v.as_str()).map(str::to_string),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(status: WindowStatus, metadata: serde_json::Value) -> ClearingWindow {
+        let now = Utc::now();
+        ClearingWindow {
+            id: 1_700_000_000,
+            window_name: "CLEAR_Global_20231114_2213".to_string(),
+            start_time: now,
+            end_time: now + chrono::Duration::hours(6),
+            cutoff_time: now + chrono::Duration::hours(5),
+            status: status.as_str().to_string(),
+            region: "Global".to_string(),
+            transactions_count: 0,
+            obligations_count: 12,
+            total_gross_value: Decimal::new(1_000_000, 2),
+            total_net_value: Decimal::new(250_000, 2),
+            saved_amount: Decimal::new(750_000, 2),
+            netting_efficiency: Decimal::new(7500, 2),
+            settlement_instructions: None,
+            metadata,
+            created_at: now,
+            closed_at: Some(now),
+            processed_at: Some(now),
+            completed_at: None,
+            grace_period_seconds: 1800,
+            grace_period_started: None,
+        }
+    }
+
+    #[test]
+    fn test_window_result() {
+        let result = window_result(&window(
+            WindowStatus::Settling,
+            json!({
+                "cycles_eliminated": 3,
+                "processing_time_ms": 420,
+                "netting_mode": "Multilateral",
+                "mode_comparison": {
+                    "bilateral": { "settlements": 5, "net_value": "3000.00", "efficiency_percent": "70.00" },
+                    "multilateral": { "settlements": 4, "net_value": "2500.00", "efficiency_percent": "75.00" }
+                },
+                "cross_currency": true,
+                "fx_snapshot": {
+                    "taken_at": "2023-11-14T22:13:20Z",
+                    "rates": [{ "base_currency": "INR", "quote_currency": "AED", "rate": "0.044", "observed_at": "2023-11-14T22:13:00Z" }]
+                },
+                "fx_conversions": [{
+                    "bank_pair_hash": "a:b",
+                    "from_bank_id": "00000000-0000-0000-0000-00000000000a",
+                    "to_bank_id": "00000000-0000-0000-0000-00000000000b",
+                    "from_currency": "INR",
+                    "from_amount": "34090.91",
+                    "to_currency": "AED",
+                    "to_amount": "1500",
+                    "rate": "0.044"
+                }],
+                "last_error": null
+            }),
+        ))
+        .unwrap();
+
+        assert!(matches!(result.status, ClearingStatus::Success));
+        assert_eq!(result.saved_amount, Decimal::new(750_000, 2));
+        assert_eq!(result.cycles_eliminated, 3);
+        assert_eq!(result.processing_time_ms, 420);
+        assert_eq!(result.error, None);
+        assert_eq!(result.netting_mode, NettingMode::Multilateral);
+        let comparison = result.mode_comparison.unwrap();
+        assert_eq!(comparison.bilateral.settlements, 5);
+        assert_eq!(comparison.multilateral.net_value, Decimal::new(250_000, 2));
+        assert!(result.cross_currency);
+        assert_eq!(result.fx_snapshot.unwrap().rate("AED", "INR").unwrap().round_dp(2), Decimal::new(2273, 2));
+        assert_eq!(result.fx_conversions.len(), 1);
+        assert_eq!(result.fx_conversions[0].to_amount, Decimal::from(1_500));
+
+        let failed = window_result(&window(WindowStatus::Failed, json!({ "last_error": "relation obligations does not exist" }))).unwrap();
+        assert!(matches!(failed.status, ClearingStatus::Failed));
+        assert_eq!(failed.error.as_deref(), Some("relation obligations does not exist"));
+        assert_eq!(failed.cycles_eliminated, 0);
+        assert_eq!(failed.netting_mode, NettingMode::Bilateral);
+        assert!(failed.mode_comparison.is_none());
+        assert!(!failed.cross_currency);
+        assert!(failed.fx_conversions.is_empty());
+    }
+
+    #[test]
+    fn test_unprocessed_window_has_no_result() {
+        for status in [WindowStatus::Open, WindowStatus::Closed, WindowStatus::Processing] {
+            assert!(matches!(
+                window_result(&window(status, json!({}))),
+                Err(ClearingError::InvalidWindowState { .. })
+            ));
+        }
+    }
+}
@@ -5,19 +5,25 @@ pub mod scheduler;
 // pub mod grace_period;    // Not implemented yet
 
 use crate::errors::ClearingError;
-use crate::models::{ClearingWindow, WindowStatus};
+use crate::models::{ClearingWindow, NettingMode, WindowEvent, WindowStatus};
+use crate::netting::cross_currency::FxSnapshot;
 use chrono::{Duration, Utc};
 use rust_decimal::Decimal;
+use serde_json::json;
 use sqlx::PgPool;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 use uuid::Uuid;
 
+/// Window events buffered per subscriber before it starts lagging
+const WINDOW_UPDATES_CAPACITY: usize = 256;
+
 /// Window Manager handles clearing window lifecycle
 pub struct WindowManager {
     db_pool: Arc<PgPool>,
     current_window: Arc<RwLock<Option<ClearingWindow>>>,
     config: WindowConfig,
+    updates: broadcast::Sender<WindowEvent>,
 }
 
 /// Configuration for clearing windows
@@ -31,6 +37,10 @@ pub struct WindowConfig {
     pub window_duration_hours: i64,
     /// Region for this window manager
     pub region: String,
+    /// Netting mode of newly opened windows
+    pub netting_mode: NettingMode,
+    /// Whether newly opened windows are netted across currencies
+    pub cross_currency: bool,
 }
 
 impl Default for WindowConfig {
@@ -40,27 +50,61 @@ impl Default for WindowConfig {
             grace_period_minutes: 30,
             window_duration_hours: 6,
             region: "Global".to_string(),
+            netting_mode: NettingMode::Bilateral,
+            cross_currency: false,
         }
     }
 }
 
+/// Window totals for the metrics endpoint
+#[derive(Debug, Clone, serde::Serialize, sqlx::FromRow)]
+pub struct WindowStats {
+    pub total_windows: i64,
+    pub active_windows: i64,
+    pub processed_windows: i64,
+    pub netting_efficiency: Decimal,     // Average over processed windows, percent
+    pub total_gross_value: Decimal,
+    pub total_net_value: Decimal,
+    pub total_saved: Decimal,
+    /// Average efficiency each netting mode would have reached over the same windows
+    pub bilateral_efficiency: Decimal,
+    pub multilateral_efficiency: Decimal,
+}
+
 impl WindowManager {
     pub fn new(db_pool: Arc<PgPool>, config: WindowConfig) -> Self {
+        let (updates, _) = broadcast::channel(WINDOW_UPDATES_CAPACITY);
         Self {
             db_pool,
             current_window: Arc::new(RwLock::new(None)),
             config,
+            updates,
         }
     }
 
+    /// Region this manager opens windows for
+    pub fn region(&self) -> &str {
+        &self.config.region
+    }
+
+    /// Subscribe to window lifecycle events (opened, closing, status changes)
+    pub fn subscribe(&self) -> broadcast::Receiver<WindowEvent> {
+        self.updates.subscribe()
+    }
+
     /// Create a new clearing window
     pub async fn create_window(&self) -> Result<ClearingWindow, ClearingError> {
+        self.open_window(false).await
+    }
+
+    /// Create a new clearing window, flagged as opened outside the schedule in emergency mode
+    pub async fn open_window(&self, emergency_mode: bool) -> Result<ClearingWindow, ClearingError> {
         let now = Utc::now();
         let end_time = now + Duration::hours(self.config.window_duration_hours);
         let cutoff_time = end_time - Duration::minutes(self.config.grace_period_minutes as i64);
 
         let window = ClearingWindow {
-            id: 0, // Will be set by database
+            id: now.timestamp(), // Unix timestamp as ID
             window_name: format!("CLEAR_{}_{}", self.config.region, now.format("%Y%m%d_%H%M")),
             start_time: now,
             end_time,
@@ -74,7 +118,11 @@ impl WindowManager {
             saved_amount: Decimal::ZERO,
             netting_efficiency: Decimal::ZERO,
             settlement_instructions: None,
-            metadata: serde_json::json!({}),
+            metadata: json!({
+                "emergency_mode": emergency_mode,
+                "netting_mode": self.config.netting_mode.as_str(),
+                "cross_currency": self.config.cross_currency,
+            }),
             created_at: now,
             closed_at: None,
             processed_at: None,
@@ -87,14 +135,19 @@ impl WindowManager {
         let inserted = sqlx::query_as::<_, ClearingWindow>(
             r#"
             INSERT INTO clearing_windows (
-                window_name, start_time, end_time, cutoff_time, status, region,
+                id, window_name, start_time, end_time, cutoff_time, status, region,
                 transactions_count, obligations_count, total_gross_value, total_net_value,
                 saved_amount, netting_efficiency, metadata, created_at,
                 grace_period_seconds
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+            ) VALUES (
+                -- A window opened in the same second as the previous one (emergency reopen) takes the next id
+                GREATEST($1, (SELECT COALESCE(MAX(id), 0) + 1 FROM clearing_windows)),
+                $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16
+            )
             RETURNING *
             "#,
         )
+        .bind(window.id)
         .bind(&window.window_name)
         .bind(&window.start_time)
         .bind(&window.end_time)
@@ -117,6 +170,8 @@ impl WindowManager {
         // Update current window
         *self.current_window.write().await = Some(inserted.clone());
 
+        self.publish(&inserted, "Opened", None, json!({ "emergency_mode": emergency_mode }));
+
         Ok(inserted)
     }
 
@@ -125,8 +180,38 @@ impl WindowManager {
         self.current_window.read().await.clone()
     }
 
+    /// Current window, reloaded from the database (latest Open or Closing window of the
+    /// region) when this instance has not opened one since it started
+    pub async fn current_window(&self) -> Result<Option<ClearingWindow>, ClearingError> {
+        if let Some(window) = self.get_current_window().await {
+            return Ok(Some(window));
+        }
+
+        let window = sqlx::query_as::<_, ClearingWindow>(
+            r#"
+            SELECT * FROM clearing_windows
+            WHERE region = $1 AND status IN ($2, $3)
+            ORDER BY start_time DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(&self.config.region)
+        .bind(WindowStatus::Open.as_str())
+        .bind(WindowStatus::Closing.as_str())
+        .fetch_optional(self.db_pool.as_ref())
+        .await
+        .map_err(|e| ClearingError::DatabaseError(e.to_string()))?;
+
+        if let Some(ref window) = window {
+            *self.current_window.write().await = Some(window.clone());
+        }
+
+        Ok(window)
+    }
+
     /// Close current window and start grace period
     pub async fn close_window(&self, window_id: i64) -> Result<(), ClearingError> {
+        let window = self.get_window(window_id).await?;
         let now = Utc::now();
 
         sqlx::query(
@@ -143,15 +228,65 @@ impl WindowManager {
         .await
         .map_err(|e| ClearingError::DatabaseError(e.to_string()))?;
 
+        self.set_current_status(window_id, WindowStatus::Closing).await;
+        self.publish(
+            &window,
+            WindowStatus::Closing.as_str(),
+            Some(window.status.clone()),
+            json!({ "grace_period_seconds": window.grace_period_seconds }),
+        );
+
         Ok(())
     }
 
+    /// Close an Open or Closing window immediately, skipping the grace period
+    pub async fn force_close_window(
+        &self,
+        window_id: i64,
+        reason: &str,
+    ) -> Result<chrono::DateTime<Utc>, ClearingError> {
+        let window = self.get_window(window_id).await?;
+        if window.status != WindowStatus::Open.as_str() && window.status != WindowStatus::Closing.as_str() {
+            return Err(ClearingError::InvalidWindowState {
+                expected: format!("{} or {}", WindowStatus::Open.as_str(), WindowStatus::Closing.as_str()),
+                actual: window.status,
+            });
+        }
+
+        let now = Utc::now();
+        sqlx::query(
+            r#"
+            UPDATE clearing_windows
+            SET status = $1, closed_at = COALESCE(closed_at, $2)
+            WHERE id = $3
+            "#,
+        )
+        .bind(WindowStatus::Closed.as_str())
+        .bind(&now)
+        .bind(window_id)
+        .execute(self.db_pool.as_ref())
+        .await
+        .map_err(|e| ClearingError::DatabaseError(e.to_string()))?;
+
+        self.set_current_status(window_id, WindowStatus::Closed).await;
+        self.publish(
+            &window,
+            WindowStatus::Closed.as_str(),
+            Some(window.status.clone()),
+            json!({ "forced": true, "reason": reason }),
+        );
+
+        Ok(window.closed_at.unwrap_or(now))
+    }
+
     /// Update window status
     pub async fn update_status(
         &self,
         window_id: i64,
         new_status: WindowStatus,
     ) -> Result<(), ClearingError> {
+        let window = self.get_window(window_id).await?;
+
         sqlx::query(
             r#"
             UPDATE clearing_windows
@@ -166,13 +301,82 @@ impl WindowManager {
         .map_err(|e| ClearingError::DatabaseError(e.to_string()))?;
 
         // Update in-memory state
-        if let Some(mut window) = self.current_window.write().await.as_mut() {
+        self.set_current_status(window_id, new_status.clone()).await;
+        self.publish(&window, new_status.as_str(), Some(window.status.clone()), json!({}));
+
+        Ok(())
+    }
+
+    /// Move a window to `new_status` only if it is in one of `from`, in a single statement, so
+    /// concurrent callers cannot both make the same transition. Returns the window as it was.
+    pub async fn transition_status(
+        &self,
+        window_id: i64,
+        from: &[WindowStatus],
+        new_status: WindowStatus,
+    ) -> Result<ClearingWindow, ClearingError> {
+        let from: Vec<&str> = from.iter().map(|s| s.as_str()).collect();
+
+        let previous = sqlx::query_as::<_, ClearingWindow>(
+            r#"
+            UPDATE clearing_windows w
+            SET status = $1
+            FROM clearing_windows prev
+            WHERE w.id = $2 AND prev.id = w.id AND w.status = ANY($3)
+            RETURNING prev.*
+            "#,
+        )
+        .bind(new_status.as_str())
+        .bind(window_id)
+        .bind(&from)
+        .fetch_optional(self.db_pool.as_ref())
+        .await
+        .map_err(|e| ClearingError::DatabaseError(e.to_string()))?;
+
+        let Some(window) = previous else {
+            let window = self.get_window(window_id).await?;
+            return Err(ClearingError::InvalidWindowState {
+                expected: from.join(", "),
+                actual: window.status,
+            });
+        };
+
+        self.set_current_status(window_id, new_status.clone()).await;
+        self.publish(&window, new_status.as_str(), Some(window.status.clone()), json!({}));
+
+        Ok(window)
+    }
+
+    async fn set_current_status(&self, window_id: i64, status: WindowStatus) {
+        if let Some(window) = self.current_window.write().await.as_mut() {
             if window.id == window_id {
-                window.status = new_status.as_str().to_string();
+                window.status = status.as_str().to_string();
             }
         }
+    }
 
-        Ok(())
+    /// Broadcast a window event; shaped like the rows the status trigger writes to
+    /// clearing_window_events (region travels in event_data)
+    fn publish(&self, window: &ClearingWindow, event_type: &str, old_status: Option<String>, mut event_data: serde_json::Value) {
+        event_data["window_name"] = json!(window.window_name);
+        event_data["region"] = json!(window.region);
+
+        let new_status = match event_type {
+            "Opened" => WindowStatus::Open.as_str(),
+            status => status,
+        };
+
+        // No subscribers is fine - nobody is streaming updates
+        let _ = self.updates.send(WindowEvent {
+            id: Uuid::new_v4(),
+            window_id: window.id,
+            event_type: event_type.to_string(),
+            old_status,
+            new_status: Some(new_status.to_string()),
+            event_data,
+            triggered_by: Some(crate::SERVICE_NAME.to_string()),
+            created_at: Utc::now(),
+        });
     }
 
     /// Update window metrics after clearing
@@ -201,11 +405,11 @@ impl WindowManager {
             "#,
         )
         .bind(obligations_count)
-        .bind(&gross_value)
-        .bind(&net_value)
-        .bind(&saved)
-        .bind(&efficiency)
-        .bind(&Utc::now())
+        .bind(gross_value)
+        .bind(net_value)
+        .bind(saved)
+        .bind(efficiency)
+        .bind(Utc::now())
         .bind(window_id)
         .execute(self.db_pool.as_ref())
         .await
@@ -220,22 +424,173 @@ impl WindowManager {
             "SELECT * FROM clearing_windows WHERE id = $1"
         )
         .bind(window_id)
-        .fetch_one(self.db_pool.as_ref())
+        .fetch_optional(self.db_pool.as_ref())
         .await
-        .map_err(|e| ClearingError::DatabaseError(e.to_string()))
+        .map_err(|e| ClearingError::DatabaseError(e.to_string()))?
+        .ok_or(ClearingError::WindowNotFound(window_id))
     }
 
     /// List recent windows
     pub async fn list_windows(&self, limit: i64) -> Result<Vec<ClearingWindow>, ClearingError> {
         sqlx::query_as::<_, ClearingWindow>(
-            "SELECT * FROM clearing_windows ORDER BY created_at DESC LIMIT $1"
+            "SELECT * FROM clearing_windows WHERE region = $1 ORDER BY created_at DESC LIMIT $2"
         )
+        .bind(&self.config.region)
         .bind(limit)
         .fetch_all(self.db_pool.as_ref())
         .await
         .map_err(|e| ClearingError::DatabaseError(e.to_string()))
     }
 
+    /// Store what a processing run produced beyond the metrics: the instructions it generated
+    /// and `details` (cycles eliminated, processing time, last error) merged into the metadata
+    pub async fn record_processing(
+        &self,
+        window_id: i64,
+        instruction_ids: &[Uuid],
+        details: serde_json::Value,
+    ) -> Result<(), ClearingError> {
+        sqlx::query(
+            r#"
+            UPDATE clearing_windows
+            SET settlement_instructions = $1,
+                metadata = COALESCE(metadata, '{}'::jsonb) || $2
+            WHERE id = $3
+            "#,
+        )
+        .bind(instruction_ids)
+        .bind(&details)
+        .bind(window_id)
+        .execute(self.db_pool.as_ref())
+        .await
+        .map_err(|e| ClearingError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Choose how a window is netted; only before it is netted, or after a failed or rolled back run
+    pub async fn set_netting_mode(
+        &self,
+        window_id: i64,
+        netting_mode: NettingMode,
+    ) -> Result<ClearingWindow, ClearingError> {
+        self.set_netting_option(window_id, json!({ "netting_mode": netting_mode.as_str() }))
+            .await
+    }
+
+    /// Offset opposing exposures in different currencies when netting a window, or stop doing so
+    pub async fn set_cross_currency(
+        &self,
+        window_id: i64,
+        enabled: bool,
+    ) -> Result<ClearingWindow, ClearingError> {
+        self.set_netting_option(window_id, json!({ "cross_currency": enabled }))
+            .await
+    }
+
+    /// Freeze the FX rates a window is netted at. A snapshot already frozen is kept, so a
+    /// reprocessed window converts at the same rates; returns the snapshot in force.
+    pub async fn freeze_fx_snapshot(
+        &self,
+        window_id: i64,
+        snapshot: &FxSnapshot,
+    ) -> Result<FxSnapshot, ClearingError> {
+        let snapshot = serde_json::to_value(snapshot).map_err(ClearingError::Serialization)?;
+
+        let frozen: serde_json::Value = sqlx::query_scalar(
+            r#"
+            UPDATE clearing_windows
+            SET metadata = CASE
+                    WHEN COALESCE(metadata, '{}'::jsonb) ? 'fx_snapshot' THEN metadata
+                    ELSE COALESCE(metadata, '{}'::jsonb) || jsonb_build_object('fx_snapshot', $1::jsonb)
+                END
+            WHERE id = $2
+            RETURNING metadata -> 'fx_snapshot'
+            "#,
+        )
+        .bind(&snapshot)
+        .bind(window_id)
+        .fetch_optional(self.db_pool.as_ref())
+        .await
+        .map_err(|e| ClearingError::DatabaseError(e.to_string()))?
+        .ok_or(ClearingError::WindowNotFound(window_id))?;
+
+        serde_json::from_value(frozen).map_err(ClearingError::Serialization)
+    }
+
+    /// Merge netting options into the metadata of a window that has not been netted yet
+    async fn set_netting_option(
+        &self,
+        window_id: i64,
+        options: serde_json::Value,
+    ) -> Result<ClearingWindow, ClearingError> {
+        // Checked in the UPDATE itself so processing cannot start in between
+        let configurable: Vec<&str> = [
+            WindowStatus::Open,
+            WindowStatus::Closing,
+            WindowStatus::Closed,
+            WindowStatus::Failed,
+            WindowStatus::RolledBack,
+        ]
+        .iter()
+        .map(|s| s.as_str())
+        .collect();
+
+        let updated = sqlx::query_as::<_, ClearingWindow>(
+            r#"
+            UPDATE clearing_windows
+            SET metadata = COALESCE(metadata, '{}'::jsonb) || $1
+            WHERE id = $2 AND status = ANY($3)
+            RETURNING *
+            "#,
+        )
+        .bind(&options)
+        .bind(window_id)
+        .bind(&configurable)
+        .fetch_optional(self.db_pool.as_ref())
+        .await
+        .map_err(|e| ClearingError::DatabaseError(e.to_string()))?;
+
+        let Some(updated) = updated else {
+            let window = self.get_window(window_id).await?;
+            return Err(ClearingError::InvalidWindowState {
+                expected: "not yet netted, Failed or RolledBack".to_string(),
+                actual: window.status,
+            });
+        };
+
+        let mut current = self.current_window.write().await;
+        if current.as_ref().map(|w| w.id) == Some(window_id) {
+            *current = Some(updated.clone());
+        }
+
+        Ok(updated)
+    }
+
+    /// Totals over all windows of this region
+    pub async fn stats(&self) -> Result<WindowStats, ClearingError> {
+        sqlx::query_as::<_, WindowStats>(
+            r#"
+            SELECT
+                COUNT(*) AS total_windows,
+                COUNT(*) FILTER (WHERE status IN ('Open', 'Closing', 'Processing', 'Settling')) AS active_windows,
+                COUNT(*) FILTER (WHERE processed_at IS NOT NULL) AS processed_windows,
+                COALESCE(AVG(netting_efficiency) FILTER (WHERE processed_at IS NOT NULL), 0) AS netting_efficiency,
+                COALESCE(SUM(total_gross_value), 0) AS total_gross_value,
+                COALESCE(SUM(total_net_value), 0) AS total_net_value,
+                COALESCE(SUM(saved_amount), 0) AS total_saved,
+                COALESCE(AVG((metadata #>> '{mode_comparison,bilateral,efficiency_percent}')::numeric), 0) AS bilateral_efficiency,
+                COALESCE(AVG((metadata #>> '{mode_comparison,multilateral,efficiency_percent}')::numeric), 0) AS multilateral_efficiency
+            FROM clearing_windows
+            WHERE region = $1
+            "#,
+        )
+        .bind(&self.config.region)
+        .fetch_one(self.db_pool.as_ref())
+        .await
+        .map_err(|e| ClearingError::DatabaseError(e.to_string()))
+    }
+
     /// Check if grace period has expired
     pub fn is_grace_period_expired(&self, window: &ClearingWindow) -> bool {
         if let Some(grace_started) = window.grace_period_started {
@@ -2,33 +2,19 @@
 // Listens to deltran.compliance.check and processes payment compliance
 
 use async_nats::Client;
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 use tracing::{info, error, warn};
 use uuid::Uuid;
 use futures_util::StreamExt;
+use deltran_schema::EventEnvelope;
+use deltran_schema::envelope::encode_following;
+use deltran_schema::payment::CanonicalPayment;
 
 use compliance_engine::aml::AmlScorer;
 use compliance_engine::sanctions::SanctionsMatcher;
 use compliance_engine::pep::PepChecker;
 use compliance_engine::models::{SanctionsResult, PepResult};
 
-#[derive(Debug, Deserialize, Serialize)]
-pub struct CanonicalPayment {
-    pub deltran_tx_id: Uuid,
-    pub uetr: Option<Uuid>,
-    pub end_to_end_id: String,
-    pub instruction_id: String,
-    pub debtor: Party,
-    pub creditor: Party,
-    pub instructed_amount: rust_decimal::Decimal,
-    pub currency: String,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-pub struct Party {
-    pub name: String,
-}
-
 #[derive(Debug, Serialize)]
 pub struct ComplianceCheckResult {
     pub deltran_tx_id: Uuid,
@@ -62,9 +48,10 @@ pub async fn start_compliance_consumer(nats_url: &str) -> anyhow::Result<()> {
         info!("🔄 Compliance consumer task started");
 
         while let Some(msg) = subscriber.next().await {
-            // Parse CanonicalPayment from message
-            match serde_json::from_slice::<CanonicalPayment>(&msg.payload) {
-                Ok(payment) => {
+            // Parse CanonicalPayment from message (payment.canonical envelope or legacy bare payload)
+            match EventEnvelope::<CanonicalPayment>::from_slice(&msg.payload) {
+                Ok(envelope) => {
+                    let payment = envelope.payload;
                     info!("🔍 Received compliance check request for: {} (E2E: {})",
                           payment.deltran_tx_id, payment.end_to_end_id);
 
@@ -77,7 +64,7 @@ pub async fn start_compliance_consumer(nats_url: &str) -> anyhow::Result<()> {
                                   payment.deltran_tx_id, result.aml_score, result.sanctions_score);
 
                             // Publish to Obligation Engine (next in chain)
-                            if let Err(e) = publish_to_obligation_engine(&nats_client, &payment).await {
+                            if let Err(e) = publish_to_obligation_engine(&nats_client, &payment, envelope.correlation_id).await {
                                 error!("Failed to route to Obligation Engine: {}", e);
                             }
                         }
@@ -111,9 +98,12 @@ async fn run_compliance_checks(payment: &CanonicalPayment) -> ComplianceCheckRes
     let sanctions_matcher = SanctionsMatcher::new();
     let pep_checker = PepChecker::new();
 
-    // Check sanctions for both parties (using empty string for country as Party doesn't have it)
+    let debtor_country = payment.debtor.country_code.as_deref().unwrap_or_default();
+    let creditor_country = payment.creditor.country_code.as_deref().unwrap_or_default();
+
+    // Check sanctions for both parties
     let debtor_sanctions = sanctions_matcher
-        .check_sanctions(&payment.debtor.name, "")
+        .check_sanctions(&payment.debtor.name, debtor_country)
         .unwrap_or_else(|_| SanctionsResult {
             is_sanctioned: false,
             match_details: vec![],
@@ -122,7 +112,7 @@ async fn run_compliance_checks(payment: &CanonicalPayment) -> ComplianceCheckRes
         });
 
     let creditor_sanctions = sanctions_matcher
-        .check_sanctions(&payment.creditor.name, "")
+        .check_sanctions(&payment.creditor.name, creditor_country)
         .unwrap_or_else(|_| SanctionsResult {
             is_sanctioned: false,
             match_details: vec![],
@@ -132,9 +122,9 @@ async fn run_compliance_checks(payment: &CanonicalPayment) -> ComplianceCheckRes
 
     let sanctions_score = (debtor_sanctions.confidence + creditor_sanctions.confidence) / 2.0;
 
-    // Check PEP (using empty string for country as Party doesn't have it)
+    // Check PEP
     let debtor_pep = pep_checker
-        .check_pep(&payment.debtor.name, "")
+        .check_pep(&payment.debtor.name, debtor_country)
         .unwrap_or_else(|_| PepResult {
             is_pep: false,
             pep_type: None,
@@ -144,7 +134,7 @@ async fn run_compliance_checks(payment: &CanonicalPayment) -> ComplianceCheckRes
         });
 
     let creditor_pep = pep_checker
-        .check_pep(&payment.creditor.name, "")
+        .check_pep(&payment.creditor.name, creditor_country)
         .unwrap_or_else(|_| PepResult {
             is_pep: false,
             pep_type: None,
@@ -190,9 +180,10 @@ async fn run_compliance_checks(payment: &CanonicalPayment) -> ComplianceCheckRes
     }
 }
 
-async fn publish_to_obligation_engine(nats_client: &Client, payment: &CanonicalPayment) -> anyhow::Result<()> {
+/// Forward the payment, continuing the flow of the compliance check request
+async fn publish_to_obligation_engine(nats_client: &Client, payment: &CanonicalPayment, correlation_id: Uuid) -> anyhow::Result<()> {
     let subject = "deltran.obligation.create";
-    let payload = serde_json::to_vec(payment)?;
+    let payload = encode_following(payment, correlation_id)?;
 
     nats_client.publish(subject, payload.into()).await?;
 
@@ -0,0 +1,79 @@
+// Decimal amounts on the wire - written as strings (exact), read from strings or JSON numbers.
+// The gateway publishes rust_decimal with serde-float (numbers) while the engines were built with
+// serde-with-str (strings only), so neither feature flag can be relied on across services.
+// Use with #[serde(with = "deltran_schema::amount")].
+
+use std::fmt;
+use std::str::FromStr;
+
+use rust_decimal::Decimal;
+use serde::de::{self, Visitor};
+use serde::{Deserializer, Serializer};
+
+pub fn serialize<S: Serializer>(amount: &Decimal, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&amount.to_string())
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Decimal, D::Error> {
+    deserializer.deserialize_any(AmountVisitor)
+}
+
+#[derive(Clone, Copy)]
+struct AmountVisitor;
+
+impl<'de> Visitor<'de> for AmountVisitor {
+    type Value = Decimal;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a decimal amount as string or number")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Decimal, E> {
+        Decimal::from_str(value.trim())
+            .or_else(|_| Decimal::from_scientific(value.trim()))
+            .map_err(|_| E::invalid_value(de::Unexpected::Str(value), &self))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Decimal, E> {
+        Ok(Decimal::from(value))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Decimal, E> {
+        Ok(Decimal::from(value))
+    }
+
+    // Shortest representation that round-trips (100.1, not 100.09999999999999)
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<Decimal, E> {
+        self.visit_str(&value.to_string())
+            .map_err(|_: E| E::invalid_value(de::Unexpected::Float(value), &self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal::Decimal;
+    use serde::{Deserialize, Serialize};
+    use std::str::FromStr;
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Amount {
+        #[serde(with = "super")]
+        value: Decimal,
+    }
+
+    fn read(json: &str) -> Decimal {
+        serde_json::from_str::<Amount>(json).unwrap().value
+    }
+
+    #[test]
+    fn test_amount_formats() {
+        assert_eq!(read(r#"{"value": "1500.25"}"#), Decimal::from_str("1500.25").unwrap());
+        assert_eq!(read(r#"{"value": 100.1}"#), Decimal::from_str("100.1").unwrap());
+        assert_eq!(read(r#"{"value": 42}"#), Decimal::from(42));
+        assert_eq!(read(r#"{"value": "1e3"}"#), Decimal::from(1000));
+        assert!(serde_json::from_str::<Amount>(r#"{"value": "ten"}"#).is_err());
+
+        let written = serde_json::to_string(&Amount { value: Decimal::from_str("0.10").unwrap() }).unwrap();
+        assert_eq!(written, r#"{"value":"0.10"}"#);
+    }
+}
@@ -0,0 +1,78 @@
+// Clearing Submission - payment plus its obligation, sent to the clearing engine
+// International payments arrive on deltran.clearing.submit (via risk and liquidity);
+// local payments come straight from the obligation engine on deltran.clearing.submit.local
+// and carry their routing in `local`.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::envelope::{Event, SchemaError, LEGACY_VERSION};
+use crate::obligation::ObligationCreated;
+use crate::payment::CanonicalPayment;
+
+pub const CLEARING_SUBMISSION_EVENT: &str = "clearing.submission";
+
+/// Routing fields flattened into the submission before envelopes existed
+const LEGACY_LOCAL_FIELDS: [&str; 5] = [
+    "routing_type",
+    "jurisdiction",
+    "settlement_type",
+    "skip_risk_check",
+    "skip_liquidity_router",
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClearingSubmission {
+    pub payment: CanonicalPayment,
+    pub obligation: ObligationCreated,
+    #[serde(default)]
+    pub local: Option<LocalRouting>,     // Some = local payment, bypassing risk and liquidity
+}
+
+/// Direct token/fiat routing between banks of one jurisdiction
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LocalRouting {
+    pub routing_type: String,            // "LOCAL_DIRECT"
+    pub jurisdiction: String,            // Country code
+    pub settlement_type: String,         // "INSTANT"
+    pub skip_risk_check: bool,
+    pub skip_liquidity_router: bool,
+}
+
+impl LocalRouting {
+    /// Instant settlement within `jurisdiction` - no FX risk, no cross-currency optimization
+    pub fn direct(jurisdiction: impl Into<String>) -> Self {
+        Self {
+            routing_type: "LOCAL_DIRECT".to_string(),
+            jurisdiction: jurisdiction.into(),
+            settlement_type: "INSTANT".to_string(),
+            skip_risk_check: true,
+            skip_liquidity_router: true,
+        }
+    }
+}
+
+impl Event for ClearingSubmission {
+    const TYPE: &'static str = CLEARING_SUBMISSION_EVENT;
+    const VERSION: u32 = 1;
+
+    fn correlation_id(&self) -> Uuid {
+        self.payment.deltran_tx_id
+    }
+
+    fn upgrade(version: u32, mut payload: Value) -> Result<Self, SchemaError> {
+        if version == LEGACY_VERSION {
+            if let Some(fields) = payload.as_object_mut() {
+                if fields.contains_key("routing_type") {
+                    let local: serde_json::Map<String, Value> = LEGACY_LOCAL_FIELDS.iter()
+                        .filter_map(|field| fields.remove(*field).map(|value| (field.to_string(), value)))
+                        .collect();
+                    fields.insert("local".to_string(), Value::Object(local));
+                }
+            }
+        }
+
+        Ok(serde_json::from_value(payload)?)
+    }
+}
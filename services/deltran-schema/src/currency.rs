@@ -0,0 +1,328 @@
+// ISO 4217 Currency Registry - alphabetic code, numeric code and minor units
+// Every active currency is representable; amounts are checked against the minor units
+// of their currency (JPY 0, KWD / OMR / BHD 3, CLF 4) when parsed and when rendered.
+// Shared so the gateway and the clearing engine never disagree on how an amount is rounded.
+
+use std::fmt;
+use std::str::FromStr;
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// ISO 4217 currency, serialized as its alphabetic code ("AED")
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Currency {
+    code: &'static str,
+    numeric: u16,
+    minor_units: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum CurrencyError {
+    #[error("Unknown ISO 4217 currency: {0}")]
+    Unknown(String),
+
+    #[error("Amount {amount} {currency} has more than {minor_units} decimal place(s)")]
+    MinorUnits { currency: &'static str, minor_units: u32, amount: Decimal },
+}
+
+impl Currency {
+    // Currencies referenced directly by the services (corridors and tests)
+    pub const USD: Currency = Currency::iso("USD", 840, 2);
+    pub const EUR: Currency = Currency::iso("EUR", 978, 2);
+    pub const GBP: Currency = Currency::iso("GBP", 826, 2);
+    pub const AED: Currency = Currency::iso("AED", 784, 2);
+    pub const INR: Currency = Currency::iso("INR", 356, 2);
+    pub const SAR: Currency = Currency::iso("SAR", 682, 2);
+    pub const QAR: Currency = Currency::iso("QAR", 634, 2);
+    pub const OMR: Currency = Currency::iso("OMR", 512, 3);
+    pub const KWD: Currency = Currency::iso("KWD", 414, 3);
+
+    const fn iso(code: &'static str, numeric: u16, minor_units: u32) -> Self {
+        Self { code, numeric, minor_units }
+    }
+
+    /// Look up an alphabetic code (case-insensitive)
+    pub fn from_code(code: &str) -> Option<Self> {
+        let code = code.trim();
+        ISO_4217.iter().copied().find(|c| c.code.eq_ignore_ascii_case(code))
+    }
+
+    /// Look up a numeric code (e.g. 784 for AED)
+    pub fn from_numeric(numeric: u16) -> Option<Self> {
+        ISO_4217.iter().copied().find(|c| c.numeric == numeric)
+    }
+
+    pub fn code(&self) -> &'static str {
+        self.code
+    }
+
+    pub fn numeric(&self) -> u16 {
+        self.numeric
+    }
+
+    pub fn minor_units(&self) -> u32 {
+        self.minor_units
+    }
+
+    /// Reject amounts with more significant decimals than the currency has minor units
+    /// (trailing zeros are fine: "1500.00" JPY is accepted, "1500.5" JPY is not)
+    pub fn check_amount(&self, amount: Decimal) -> Result<(), CurrencyError> {
+        if amount.normalize().scale() > self.minor_units {
+            return Err(CurrencyError::MinorUnits {
+                currency: self.code,
+                minor_units: self.minor_units,
+                amount,
+            });
+        }
+        Ok(())
+    }
+
+    /// Amount as rendered in outbound XML, with exactly `minor_units` decimals
+    pub fn format_amount(&self, amount: Decimal) -> Result<String, CurrencyError> {
+        self.check_amount(amount)?;
+        let mut amount = amount.normalize();
+        amount.rescale(self.minor_units);
+        Ok(amount.to_string())
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code)
+    }
+}
+
+impl FromStr for Currency {
+    type Err = CurrencyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Currency::from_code(s).ok_or_else(|| CurrencyError::Unknown(s.to_string()))
+    }
+}
+
+impl Serialize for Currency {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.code)
+    }
+}
+
+impl<'de> Deserialize<'de> for Currency {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let code = String::deserialize(deserializer)?;
+        code.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Active ISO 4217 currencies (funds and precious metals without minor units excluded)
+const ISO_4217: &[Currency] = &[
+    Currency::AED,
+    Currency::iso("AFN", 971, 2),
+    Currency::iso("ALL", 8, 2),
+    Currency::iso("AMD", 51, 2),
+    Currency::iso("AOA", 973, 2),
+    Currency::iso("ARS", 32, 2),
+    Currency::iso("AUD", 36, 2),
+    Currency::iso("AWG", 533, 2),
+    Currency::iso("AZN", 944, 2),
+    Currency::iso("BAM", 977, 2),
+    Currency::iso("BBD", 52, 2),
+    Currency::iso("BDT", 50, 2),
+    Currency::iso("BGN", 975, 2),
+    Currency::iso("BHD", 48, 3),
+    Currency::iso("BIF", 108, 0),
+    Currency::iso("BMD", 60, 2),
+    Currency::iso("BND", 96, 2),
+    Currency::iso("BOB", 68, 2),
+    Currency::iso("BOV", 984, 2),
+    Currency::iso("BRL", 986, 2),
+    Currency::iso("BSD", 44, 2),
+    Currency::iso("BTN", 64, 2),
+    Currency::iso("BWP", 72, 2),
+    Currency::iso("BYN", 933, 2),
+    Currency::iso("BZD", 84, 2),
+    Currency::iso("CAD", 124, 2),
+    Currency::iso("CDF", 976, 2),
+    Currency::iso("CHE", 947, 2),
+    Currency::iso("CHF", 756, 2),
+    Currency::iso("CHW", 948, 2),
+    Currency::iso("CLF", 990, 4),
+    Currency::iso("CLP", 152, 0),
+    Currency::iso("CNY", 156, 2),
+    Currency::iso("COP", 170, 2),
+    Currency::iso("COU", 970, 2),
+    Currency::iso("CRC", 188, 2),
+    Currency::iso("CUP", 192, 2),
+    Currency::iso("CVE", 132, 2),
+    Currency::iso("CZK", 203, 2),
+    Currency::iso("DJF", 262, 0),
+    Currency::iso("DKK", 208, 2),
+    Currency::iso("DOP", 214, 2),
+    Currency::iso("DZD", 12, 2),
+    Currency::iso("EGP", 818, 2),
+    Currency::iso("ERN", 232, 2),
+    Currency::iso("ETB", 230, 2),
+    Currency::EUR,
+    Currency::iso("FJD", 242, 2),
+    Currency::iso("FKP", 238, 2),
+    Currency::GBP,
+    Currency::iso("GEL", 981, 2),
+    Currency::iso("GHS", 936, 2),
+    Currency::iso("GIP", 292, 2),
+    Currency::iso("GMD", 270, 2),
+    Currency::iso("GNF", 324, 0),
+    Currency::iso("GTQ", 320, 2),
+    Currency::iso("GYD", 328, 2),
+    Currency::iso("HKD", 344, 2),
+    Currency::iso("HNL", 340, 2),
+    Currency::iso("HTG", 332, 2),
+    Currency::iso("HUF", 348, 2),
+    Currency::iso("IDR", 360, 2),
+    Currency::iso("ILS", 376, 2),
+    Currency::INR,
+    Currency::iso("IQD", 368, 3),
+    Currency::iso("IRR", 364, 2),
+    Currency::iso("ISK", 352, 0),
+    Currency::iso("JMD", 388, 2),
+    Currency::iso("JOD", 400, 3),
+    Currency::iso("JPY", 392, 0),
+    Currency::iso("KES", 404, 2),
+    Currency::iso("KGS", 417, 2),
+    Currency::iso("KHR", 116, 2),
+    Currency::iso("KMF", 174, 0),
+    Currency::iso("KPW", 408, 2),
+    Currency::iso("KRW", 410, 0),
+    Currency::KWD,
+    Currency::iso("KYD", 136, 2),
+    Currency::iso("KZT", 398, 2),
+    Currency::iso("LAK", 418, 2),
+    Currency::iso("LBP", 422, 2),
+    Currency::iso("LKR", 144, 2),
+    Currency::iso("LRD", 430, 2),
+    Currency::iso("LSL", 426, 2),
+    Currency::iso("LYD", 434, 3),
+    Currency::iso("MAD", 504, 2),
+    Currency::iso("MDL", 498, 2),
+    Currency::iso("MGA", 969, 2),
+    Currency::iso("MKD", 807, 2),
+    Currency::iso("MMK", 104, 2),
+    Currency::iso("MNT", 496, 2),
+    Currency::iso("MOP", 446, 2),
+    Currency::iso("MRU", 929, 2),
+    Currency::iso("MUR", 480, 2),
+    Currency::iso("MVR", 462, 2),
+    Currency::iso("MWK", 454, 2),
+    Currency::iso("MXN", 484, 2),
+    Currency::iso("MXV", 979, 2),
+    Currency::iso("MYR", 458, 2),
+    Currency::iso("MZN", 943, 2),
+    Currency::iso("NAD", 516, 2),
+    Currency::iso("NGN", 566, 2),
+    Currency::iso("NIO", 558, 2),
+    Currency::iso("NOK", 578, 2),
+    Currency::iso("NPR", 524, 2),
+    Currency::iso("NZD", 554, 2),
+    Currency::OMR,
+    Currency::iso("PAB", 590, 2),
+    Currency::iso("PEN", 604, 2),
+    Currency::iso("PGK", 598, 2),
+    Currency::iso("PHP", 608, 2),
+    Currency::iso("PKR", 586, 2),
+    Currency::iso("PLN", 985, 2),
+    Currency::iso("PYG", 600, 0),
+    Currency::QAR,
+    Currency::iso("RON", 946, 2),
+    Currency::iso("RSD", 941, 2),
+    Currency::iso("RUB", 643, 2),
+    Currency::iso("RWF", 646, 0),
+    Currency::SAR,
+    Currency::iso("SBD", 90, 2),
+    Currency::iso("SCR", 690, 2),
+    Currency::iso("SDG", 938, 2),
+    Currency::iso("SEK", 752, 2),
+    Currency::iso("SGD", 702, 2),
+    Currency::iso("SHP", 654, 2),
+    Currency::iso("SLE", 925, 2),
+    Currency::iso("SOS", 706, 2),
+    Currency::iso("SRD", 968, 2),
+    Currency::iso("SSP", 728, 2),
+    Currency::iso("STN", 930, 2),
+    Currency::iso("SVC", 222, 2),
+    Currency::iso("SYP", 760, 2),
+    Currency::iso("SZL", 748, 2),
+    Currency::iso("THB", 764, 2),
+    Currency::iso("TJS", 972, 2),
+    Currency::iso("TMT", 934, 2),
+    Currency::iso("TND", 788, 3),
+    Currency::iso("TOP", 776, 2),
+    Currency::iso("TRY", 949, 2),
+    Currency::iso("TTD", 780, 2),
+    Currency::iso("TWD", 901, 2),
+    Currency::iso("TZS", 834, 2),
+    Currency::iso("UAH", 980, 2),
+    Currency::iso("UGX", 800, 0),
+    Currency::USD,
+    Currency::iso("USN", 997, 2),
+    Currency::iso("UYI", 940, 0),
+    Currency::iso("UYU", 858, 2),
+    Currency::iso("UYW", 927, 4),
+    Currency::iso("UZS", 860, 2),
+    Currency::iso("VED", 926, 2),
+    Currency::iso("VES", 928, 2),
+    Currency::iso("VND", 704, 0),
+    Currency::iso("VUV", 548, 0),
+    Currency::iso("WST", 882, 2),
+    Currency::iso("XAF", 950, 0),
+    Currency::iso("XCD", 951, 2),
+    Currency::iso("XCG", 532, 2),
+    Currency::iso("XOF", 952, 0),
+    Currency::iso("XPF", 953, 0),
+    Currency::iso("YER", 886, 2),
+    Currency::iso("ZAR", 710, 2),
+    Currency::iso("ZMW", 967, 2),
+    Currency::iso("ZWG", 924, 2),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(value: &str) -> Decimal {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn test_registry_lookup() {
+        let jpy = Currency::from_code("jpy").unwrap();
+        assert_eq!(jpy.code(), "JPY");
+        assert_eq!(jpy.numeric(), 392);
+        assert_eq!(jpy.minor_units(), 0);
+        assert_eq!("ILS".parse::<Currency>().unwrap(), Currency::from_numeric(376).unwrap());
+        assert_eq!(Currency::from_code("AED"), Some(Currency::AED));
+        assert_eq!("XXX".parse::<Currency>(), Err(CurrencyError::Unknown("XXX".to_string())));
+
+        // Codes and numeric codes are unique
+        for (i, a) in ISO_4217.iter().enumerate() {
+            assert!(ISO_4217[i + 1..].iter().all(|b| a.code != b.code && a.numeric != b.numeric), "{}", a);
+        }
+    }
+
+    #[test]
+    fn test_minor_units() {
+        assert!(Currency::from_code("JPY").unwrap().check_amount(dec("1500.00")).is_ok());
+        assert!(Currency::from_code("JPY").unwrap().check_amount(dec("1500.5")).is_err());
+        assert!(Currency::KWD.check_amount(dec("12.125")).is_ok());
+        assert!(Currency::AED.check_amount(dec("12.125")).is_err());
+
+        assert_eq!(Currency::OMR.format_amount(dec("10.5")).unwrap(), "10.500");
+        assert_eq!(Currency::from_code("JPY").unwrap().format_amount(dec("1500.00")).unwrap(), "1500");
+        assert_eq!(Currency::AED.format_amount(dec("1000")).unwrap(), "1000.00");
+    }
+
+    #[test]
+    fn test_serde_as_code() {
+        assert_eq!(serde_json::to_string(&Currency::OMR).unwrap(), "\"OMR\"");
+        assert_eq!(serde_json::from_str::<Currency>("\"jpy\"").unwrap().code(), "JPY");
+        assert!(serde_json::from_str::<Currency>("\"ABC\"").is_err());
+    }
+}
@@ -0,0 +1,207 @@
+// Event Envelope - type, version, event id and correlation id around every payload
+// The correlation id follows a payment through all engines (it is the deltran_tx_id of the
+// payment that started the flow); each hop forwards it with EventEnvelope::follow / encode_following.
+
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+/// Version assigned to bare payloads published before envelopes existed
+pub const LEGACY_VERSION: u32 = 0;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SchemaError {
+    #[error("Malformed message: {0}")]
+    Malformed(#[from] serde_json::Error),
+
+    #[error("Expected a {expected} message, got {found}")]
+    UnexpectedType { expected: &'static str, found: String },
+
+    #[error("{event_type} version {version} is newer than the supported version {supported}")]
+    UnsupportedVersion { event_type: String, version: u32, supported: u32 },
+}
+
+/// Payload type published on NATS
+pub trait Event: Serialize + DeserializeOwned {
+    /// Envelope type, e.g. "payment.canonical"
+    const TYPE: &'static str;
+
+    /// Version written by this build; every version up to it can be read
+    const VERSION: u32;
+
+    /// Correlation id of a flow started by this payload
+    fn correlation_id(&self) -> Uuid;
+
+    /// Read a payload written as `version` (LEGACY_VERSION for bare pre-envelope messages).
+    /// Additive changes need nothing; override when a version renames or restructures fields.
+    fn upgrade(version: u32, payload: Value) -> Result<Self, SchemaError> {
+        let _ = version;
+        Ok(serde_json::from_value(payload)?)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventEnvelope<T> {
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub version: u32,
+    pub event_id: Uuid,
+    pub correlation_id: Uuid,
+    pub occurred_at: DateTime<Utc>,
+    pub payload: T,
+}
+
+impl<T: Event> EventEnvelope<T> {
+    /// Envelope starting a new flow
+    pub fn new(payload: T) -> Self {
+        let correlation_id = payload.correlation_id();
+        Self::with_correlation(payload, correlation_id)
+    }
+
+    pub fn with_correlation(payload: T, correlation_id: Uuid) -> Self {
+        Self {
+            event_type: T::TYPE.to_string(),
+            version: T::VERSION,
+            event_id: Uuid::new_v4(),
+            correlation_id,
+            occurred_at: Utc::now(),
+            payload,
+        }
+    }
+
+    /// Envelope for the next hop of the same flow
+    pub fn follow<U: Event>(&self, payload: U) -> EventEnvelope<U> {
+        EventEnvelope::with_correlation(payload, self.correlation_id)
+    }
+
+    pub fn to_vec(&self) -> Result<Vec<u8>, SchemaError> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    /// Read an enveloped message of type T, or a bare legacy payload
+    pub fn from_slice(bytes: &[u8]) -> Result<Self, SchemaError> {
+        let message: Value = serde_json::from_slice(bytes)?;
+
+        if !is_envelope(&message) {
+            let payload = T::upgrade(LEGACY_VERSION, message)?;
+            return Ok(Self {
+                event_type: T::TYPE.to_string(),
+                version: LEGACY_VERSION,
+                event_id: Uuid::new_v4(),
+                correlation_id: payload.correlation_id(),
+                occurred_at: Utc::now(),
+                payload,
+            });
+        }
+
+        let envelope: EventEnvelope<Value> = serde_json::from_value(message)?;
+        if envelope.event_type != T::TYPE {
+            return Err(SchemaError::UnexpectedType { expected: T::TYPE, found: envelope.event_type });
+        }
+        if envelope.version > T::VERSION {
+            return Err(SchemaError::UnsupportedVersion {
+                event_type: envelope.event_type,
+                version: envelope.version,
+                supported: T::VERSION,
+            });
+        }
+
+        Ok(EventEnvelope {
+            payload: T::upgrade(envelope.version, envelope.payload)?,
+            event_type: envelope.event_type,
+            version: envelope.version,
+            event_id: envelope.event_id,
+            correlation_id: envelope.correlation_id,
+            occurred_at: envelope.occurred_at,
+        })
+    }
+}
+
+/// Serialize `payload` in a new envelope without taking ownership of it
+pub fn encode<T: Event>(payload: &T) -> Result<Vec<u8>, SchemaError> {
+    encode_following(payload, payload.correlation_id())
+}
+
+/// Serialize `payload` in an envelope continuing the flow `correlation_id`
+pub fn encode_following<T: Event>(payload: &T, correlation_id: Uuid) -> Result<Vec<u8>, SchemaError> {
+    let envelope = EventEnvelope {
+        event_type: T::TYPE.to_string(),
+        version: T::VERSION,
+        event_id: Uuid::new_v4(),
+        correlation_id,
+        occurred_at: Utc::now(),
+        payload,
+    };
+    Ok(serde_json::to_vec(&envelope)?)
+}
+
+/// Payload of any message - the envelope's payload, or the whole message if it predates envelopes.
+/// For consumers that only pick a few fields (e.g. ids) out of many message types.
+pub fn payload_value(bytes: &[u8]) -> Result<Value, SchemaError> {
+    let mut message: Value = serde_json::from_slice(bytes)?;
+    if is_envelope(&message) {
+        return Ok(message["payload"].take());
+    }
+    Ok(message)
+}
+
+fn is_envelope(message: &Value) -> bool {
+    message.get("type").is_some_and(Value::is_string)
+        && message.get("version").is_some_and(Value::is_u64)
+        && message.get("payload").is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Ping {
+        id: Uuid,
+    }
+
+    impl Event for Ping {
+        const TYPE: &'static str = "test.ping";
+        const VERSION: u32 = 2;
+
+        fn correlation_id(&self) -> Uuid {
+            self.id
+        }
+    }
+
+    #[test]
+    fn test_envelope_roundtrip() {
+        let ping = Ping { id: Uuid::new_v4() };
+        let bytes = encode(&ping).unwrap();
+
+        let envelope = EventEnvelope::<Ping>::from_slice(&bytes).unwrap();
+        assert_eq!((envelope.event_type.as_str(), envelope.version), ("test.ping", 2));
+        assert_eq!(envelope.correlation_id, ping.id);
+        assert_eq!(envelope.payload, ping);
+
+        let next = envelope.follow(Ping { id: Uuid::new_v4() });
+        assert_eq!(next.correlation_id, ping.id);
+        assert_ne!(next.event_id, envelope.event_id);
+        assert_eq!(payload_value(&next.to_vec().unwrap()).unwrap()["id"], next.payload.id.to_string());
+    }
+
+    #[test]
+    fn test_legacy_and_unsupported_messages() {
+        let id = Uuid::new_v4();
+        let legacy = serde_json::to_vec(&serde_json::json!({ "id": id })).unwrap();
+        let envelope = EventEnvelope::<Ping>::from_slice(&legacy).unwrap();
+        assert_eq!((envelope.version, envelope.correlation_id), (LEGACY_VERSION, id));
+        assert_eq!(payload_value(&legacy).unwrap()["id"], id.to_string());
+
+        let mut newer: Value = serde_json::from_slice(&encode(&Ping { id }).unwrap()).unwrap();
+        newer["version"] = 3.into();
+        let err = EventEnvelope::<Ping>::from_slice(newer.to_string().as_bytes()).unwrap_err();
+        assert!(matches!(err, SchemaError::UnsupportedVersion { version: 3, supported: 2, .. }));
+
+        newer["type"] = "test.pong".into();
+        let err = EventEnvelope::<Ping>::from_slice(newer.to_string().as_bytes()).unwrap_err();
+        assert!(matches!(err, SchemaError::UnexpectedType { expected: "test.ping", .. }));
+    }
+}
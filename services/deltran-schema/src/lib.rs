@@ -0,0 +1,21 @@
+// DelTran Schema - versioned NATS payloads shared by every publisher and consumer
+// Messages travel in an EventEnvelope (type, version, event id, correlation id, payload).
+// Consumers read every version up to the one they were built with, plus the bare payloads
+// published before envelopes existed (LEGACY_VERSION), and reject newer versions explicitly
+// instead of mis-reading them.
+//
+// Subjects and the payload they carry:
+//   deltran.compliance.check, deltran.obligation.create,
+//   deltran.notification.*, deltran.token.mint          payment.canonical   (payment::CanonicalPayment)
+//   deltran.events.obligation.created                   obligation.created  (obligation::ObligationCreated)
+//   deltran.clearing.submit, deltran.clearing.submit.local  clearing.submission (clearing::ClearingSubmission)
+
+pub mod amount;
+pub mod clearing;
+pub mod currency;
+pub mod envelope;
+pub mod obligation;
+pub mod payment;
+
+pub use currency::Currency;
+pub use envelope::{Event, EventEnvelope, SchemaError, LEGACY_VERSION};
@@ -0,0 +1,32 @@
+// Obligation Created - published by the obligation engine on deltran.events.obligation.created
+// and carried inside every clearing submission
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::envelope::Event;
+
+pub const OBLIGATION_CREATED_EVENT: &str = "obligation.created";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ObligationCreated {
+    pub obligation_id: Uuid,
+    pub deltran_tx_id: Uuid,
+    #[serde(default)]
+    pub uetr: Option<Uuid>,
+    #[serde(with = "crate::amount")]
+    pub amount: Decimal,
+    pub currency: String,
+    pub debtor_country: String,
+    pub creditor_country: String,
+}
+
+impl Event for ObligationCreated {
+    const TYPE: &'static str = OBLIGATION_CREATED_EVENT;
+    const VERSION: u32 = 1;
+
+    fn correlation_id(&self) -> Uuid {
+        self.deltran_tx_id
+    }
+}
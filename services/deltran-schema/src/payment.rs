@@ -0,0 +1,117 @@
+// Canonical Payment - the part of the gateway's CanonicalPayment the engines read
+// The gateway publishes its full model (a superset); unknown fields are ignored here so the
+// gateway can add fields without a version bump. Only renames and removals need a new VERSION.
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::envelope::{Event, SchemaError, LEGACY_VERSION};
+
+/// Envelope type of payments on deltran.compliance.check, deltran.obligation.create,
+/// deltran.notification.* and deltran.token.mint
+pub const PAYMENT_EVENT: &str = "payment.canonical";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CanonicalPayment {
+    pub deltran_tx_id: Uuid,
+    #[serde(default)]
+    pub uetr: Option<Uuid>,
+    pub end_to_end_id: String,
+    #[serde(default)]
+    pub instruction_id: String,
+
+    #[serde(with = "crate::amount")]
+    pub instructed_amount: Decimal,
+    #[serde(with = "crate::amount")]
+    pub settlement_amount: Decimal,      // after netting; equals instructed_amount until then
+    pub currency: String,
+
+    pub debtor: Party,
+    pub creditor: Party,
+    pub debtor_agent: FinancialInstitution,
+    pub creditor_agent: FinancialInstitution,
+
+    #[serde(default)]
+    pub status: String,                  // SCREAMING_SNAKE_CASE PaymentStatus of the gateway
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Party {
+    pub name: String,
+    #[serde(default)]
+    pub country_code: Option<String>,    // ISO 3166-1 alpha-2
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FinancialInstitution {
+    #[serde(default)]
+    pub bic: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default, alias = "country")]
+    pub country_code: Option<String>,
+}
+
+impl FinancialInstitution {
+    /// Country of the institution - as published, else from the BIC (XXXXYYZZAAA, YY = country)
+    pub fn country(&self) -> Option<String> {
+        let published = self.country_code.as_deref().map(str::trim).filter(|code| !code.is_empty());
+        if let Some(code) = published {
+            return Some(code.to_uppercase());
+        }
+
+        self.bic.as_deref()
+            .and_then(|bic| bic.get(4..6))
+            .filter(|code| code.chars().all(|c| c.is_ascii_alphabetic()))
+            .map(str::to_uppercase)
+    }
+}
+
+impl Event for CanonicalPayment {
+    const TYPE: &'static str = PAYMENT_EVENT;
+    const VERSION: u32 = 1;
+
+    fn correlation_id(&self) -> Uuid {
+        self.deltran_tx_id
+    }
+
+    fn upgrade(version: u32, mut payload: Value) -> Result<Self, SchemaError> {
+        // Bare payloads of the compliance engine carried no settlement_amount
+        if version == LEGACY_VERSION {
+            if let Some(fields) = payload.as_object_mut() {
+                if !fields.contains_key("settlement_amount") {
+                    if let Some(amount) = fields.get("instructed_amount").cloned() {
+                        fields.insert("settlement_amount".to_string(), amount);
+                    }
+                }
+            }
+        }
+
+        Ok(serde_json::from_value(payload)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agent(bic: Option<&str>, country_code: Option<&str>) -> FinancialInstitution {
+        FinancialInstitution {
+            bic: bic.map(str::to_string),
+            name: None,
+            country_code: country_code.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_agent_country() {
+        assert_eq!(agent(Some("BANKAEADXXX"), None).country().as_deref(), Some("AE"));
+        assert_eq!(agent(Some("BANKAEAD"), Some("in")).country().as_deref(), Some("IN"));
+        assert_eq!(agent(Some("BANKAEAD"), Some(" ")).country().as_deref(), Some("AE"));
+        assert_eq!(agent(Some("BANK"), None).country(), None);
+        assert_eq!(agent(Some("BANK12AD"), None).country(), None);
+        assert_eq!(agent(None, None).country(), None);
+    }
+}
@@ -0,0 +1,152 @@
+// Compatibility tests - every published version of every payload must stay readable
+// Fixtures live in tests/fixtures/<event type>/v<version>[_<variant>].json. When a payload
+// gets a new VERSION, add a fixture for it and keep the old ones.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use deltran_schema::clearing::ClearingSubmission;
+use deltran_schema::envelope::{encode, payload_value};
+use deltran_schema::obligation::ObligationCreated;
+use deltran_schema::payment::CanonicalPayment;
+use deltran_schema::{Event, EventEnvelope, SchemaError, LEGACY_VERSION};
+use rust_decimal::Decimal;
+use serde_json::Value;
+use uuid::Uuid;
+
+fn fixture_dir<T: Event>() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(T::TYPE)
+}
+
+fn fixture(event_type: &str, name: &str) -> Vec<u8> {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(event_type).join(name);
+    fs::read(&path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e))
+}
+
+/// Version a fixture file was written with, from its name
+fn fixture_version(path: &Path) -> u32 {
+    let stem = path.file_stem().unwrap().to_str().unwrap();
+    stem.trim_start_matches('v').split('_').next().unwrap().parse().unwrap()
+}
+
+/// Every fixture of T decodes, reports its version, and survives a re-encode unchanged
+fn check_fixtures<T: Event + PartialEq + std::fmt::Debug>() {
+    let mut versions = Vec::new();
+
+    for entry in fs::read_dir(fixture_dir::<T>()).unwrap() {
+        let path = entry.unwrap().path();
+        let version = fixture_version(&path);
+        let bytes = fs::read(&path).unwrap();
+
+        let envelope = EventEnvelope::<T>::from_slice(&bytes)
+            .unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+        assert_eq!(envelope.version, version, "{}", path.display());
+        assert_eq!(envelope.event_type, T::TYPE);
+
+        // Consumers that forward a payload re-publish it at the current version
+        let reencoded = EventEnvelope::<T>::from_slice(&encode(&envelope.payload).unwrap()).unwrap();
+        assert_eq!(reencoded.payload, envelope.payload, "{}", path.display());
+        assert_eq!(reencoded.version, T::VERSION);
+        assert_eq!(reencoded.correlation_id, envelope.correlation_id);
+
+        versions.push(version);
+    }
+
+    for version in LEGACY_VERSION..=T::VERSION {
+        assert!(versions.contains(&version), "{} has no fixture for version {}", T::TYPE, version);
+    }
+}
+
+#[test]
+fn test_payment_fixtures() {
+    check_fixtures::<CanonicalPayment>();
+}
+
+#[test]
+fn test_obligation_fixtures() {
+    check_fixtures::<ObligationCreated>();
+}
+
+#[test]
+fn test_clearing_fixtures() {
+    check_fixtures::<ClearingSubmission>();
+}
+
+#[test]
+fn test_gateway_payment_v0() {
+    let envelope = EventEnvelope::<CanonicalPayment>::from_slice(&fixture("payment.canonical", "v0_gateway.json")).unwrap();
+    let payment = envelope.payload;
+
+    assert_eq!(envelope.correlation_id, payment.deltran_tx_id);
+    assert_eq!(payment.instructed_amount, Decimal::from_str("150000.5").unwrap());
+    assert_eq!(payment.debtor.country_code.as_deref(), Some("AE"));
+    assert_eq!(payment.creditor_agent.bic, None);
+    assert_eq!(payment.creditor_agent.country().as_deref(), Some("IN"));
+    assert_eq!(payment.status, "VALIDATED");
+}
+
+#[test]
+fn test_compliance_payment_v0() {
+    let payment = EventEnvelope::<CanonicalPayment>::from_slice(&fixture("payment.canonical", "v0_compliance.json"))
+        .unwrap()
+        .payload;
+
+    assert_eq!(payment.settlement_amount, payment.instructed_amount);
+    assert_eq!(payment.debtor.country_code, None);
+    assert_eq!(payment.debtor_agent.country().as_deref(), Some("AE"));
+    assert_eq!(payment.creditor_agent.country().as_deref(), Some("IN"));
+    assert!(payment.status.is_empty());
+}
+
+#[test]
+fn test_clearing_submission_v0() {
+    let local = EventEnvelope::<ClearingSubmission>::from_slice(&fixture("clearing.submission", "v0_local.json"))
+        .unwrap()
+        .payload;
+    let routing = local.local.expect("flattened routing fields become `local`");
+    assert_eq!((routing.routing_type.as_str(), routing.jurisdiction.as_str()), ("LOCAL_DIRECT", "AE"));
+    assert!(routing.skip_risk_check && routing.skip_liquidity_router);
+
+    let international = EventEnvelope::<ClearingSubmission>::from_slice(&fixture("clearing.submission", "v0_international.json"))
+        .unwrap()
+        .payload;
+    assert_eq!(international.local, None);
+    assert_eq!(international.payment.deltran_tx_id, international.obligation.deltran_tx_id);
+}
+
+#[test]
+fn test_amounts_written_as_strings() {
+    let payment = EventEnvelope::<CanonicalPayment>::from_slice(&fixture("payment.canonical", "v0_gateway.json"))
+        .unwrap()
+        .payload;
+
+    let payload = payload_value(&encode(&payment).unwrap()).unwrap();
+    assert_eq!(payload["instructed_amount"], "150000.5");
+    assert_eq!(payload["settlement_amount"], "150000.5");
+}
+
+#[test]
+fn test_newer_version_rejected() {
+    let mut message: Value = serde_json::from_slice(&fixture("obligation.created", "v1.json")).unwrap();
+    message["version"] = (ObligationCreated::VERSION + 1).into();
+
+    let err = EventEnvelope::<ObligationCreated>::from_slice(message.to_string().as_bytes()).unwrap_err();
+    assert!(matches!(err, SchemaError::UnsupportedVersion { .. }), "{}", err);
+}
+
+#[test]
+fn test_wrong_type_rejected() {
+    let err = EventEnvelope::<ClearingSubmission>::from_slice(&fixture("payment.canonical", "v1.json")).unwrap_err();
+    assert!(matches!(err, SchemaError::UnexpectedType { expected: "clearing.submission", .. }), "{}", err);
+}
+
+#[test]
+fn test_unknown_fields_tolerated() {
+    let mut message: Value = serde_json::from_slice(&fixture("obligation.created", "v1.json")).unwrap();
+    message["trace_id"] = "abc".into();
+    message["payload"]["netting_window"] = 42.into();
+
+    let envelope = EventEnvelope::<ObligationCreated>::from_slice(message.to_string().as_bytes()).unwrap();
+    assert_eq!(envelope.payload.obligation_id, Uuid::parse_str("c1d2e3f4-a5b6-4c7d-8e9f-0a1b2c3d4e5f").unwrap());
+}
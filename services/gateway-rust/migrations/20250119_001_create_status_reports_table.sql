@@ -0,0 +1,34 @@
+-- Gateway Service - Outbound Status Reports
+-- pain.002 / pacs.002 documents generated for the originating bank
+
+-- Remember which ISO message created the payment so later state changes
+-- are reported back in the matching format (pain.001 -> pain.002, pacs.008 -> pacs.002)
+ALTER TABLE payments ADD COLUMN IF NOT EXISTS source_message_type VARCHAR(16) NOT NULL DEFAULT 'pain.001';
+
+CREATE INDEX IF NOT EXISTS idx_payments_message_id ON payments(message_id) WHERE message_id IS NOT NULL;
+
+CREATE TABLE IF NOT EXISTS status_reports (
+    report_id UUID PRIMARY KEY,
+
+    -- ISO 20022 identifiers
+    message_type VARCHAR(16) NOT NULL,          -- pain.002 / pacs.002
+    message_id VARCHAR(35) NOT NULL,            -- GrpHdr/MsgId of the report
+    original_message_id VARCHAR(35) NOT NULL,   -- MsgId of the reported pain.001 / pacs.008
+
+    -- Collection
+    recipient_bic VARCHAR(11) NOT NULL,
+    group_status VARCHAR(4),
+    deltran_tx_ids UUID[] NOT NULL,
+    xml_document TEXT NOT NULL,
+
+    -- Timestamps
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    collected_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_status_reports_recipient ON status_reports(recipient_bic, created_at);
+CREATE INDEX idx_status_reports_uncollected ON status_reports(recipient_bic) WHERE collected_at IS NULL;
+CREATE INDEX idx_status_reports_original_message ON status_reports(original_message_id);
+
+COMMENT ON TABLE status_reports IS 'Outbound pain.002 / pacs.002 status reports awaiting collection by the originating bank';
+COMMENT ON COLUMN status_reports.collected_at IS 'Set when the report was returned by GET /reports/:bic';
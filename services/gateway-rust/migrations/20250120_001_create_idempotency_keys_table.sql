@@ -0,0 +1,19 @@
+-- Gateway Service - Idempotent Ingestion
+-- Replayed pain.001 / pacs.008 submissions return the original deltran_tx_ids
+
+-- Client supplied Idempotency-Key header -> payments created by the first submission
+CREATE TABLE IF NOT EXISTS idempotency_keys (
+    idempotency_key VARCHAR(255) PRIMARY KEY,
+    message_type VARCHAR(16) NOT NULL,
+    message_id VARCHAR(35) NOT NULL,
+    deltran_tx_ids UUID[] NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_idempotency_keys_created_at ON idempotency_keys(created_at);
+
+-- Duplicate detection on ISO identifiers (MsgId + EndToEndId per sending bank)
+CREATE INDEX IF NOT EXISTS idx_payments_dedup
+    ON payments(message_id, end_to_end_id, debtor_agent_bic, created_at DESC);
+
+COMMENT ON TABLE idempotency_keys IS 'Idempotency-Key replay store, entries older than DEDUP_HORIZON_HOURS are ignored and may be reused';
@@ -0,0 +1,30 @@
+-- Gateway Service - Payment Returns (pacs.004)
+-- One row per returned transaction, linked to the original payment
+
+CREATE TABLE IF NOT EXISTS payment_returns (
+    return_id VARCHAR(35) NOT NULL,              -- RtrId
+    return_message_id VARCHAR(35) NOT NULL,      -- GrpHdr/MsgId of the pacs.004
+    deltran_tx_id UUID NOT NULL REFERENCES payments(deltran_tx_id) ON DELETE CASCADE,
+
+    -- Original payment references as received
+    original_uetr UUID,
+    original_end_to_end_id VARCHAR(35),
+
+    -- Returned amount (may be less than the original for partial returns)
+    returned_amount DECIMAL(18, 5) NOT NULL,
+    currency VARCHAR(3) NOT NULL,
+    settlement_date DATE,
+
+    -- ExternalReturnReason1Code (AC04, AM09, MS03, ...) or proprietary code
+    reason_code VARCHAR(35),
+    reason_description TEXT,
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (return_message_id, return_id)
+);
+
+CREATE INDEX idx_payment_returns_tx_id ON payment_returns(deltran_tx_id);
+CREATE INDEX idx_payment_returns_reason_code ON payment_returns(reason_code);
+
+COMMENT ON TABLE payment_returns IS 'pacs.004 returns with ISO return reason codes';
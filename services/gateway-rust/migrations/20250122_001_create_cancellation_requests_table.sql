@@ -0,0 +1,32 @@
+-- Gateway Service - Cancellation Requests (camt.056 / camt.029)
+-- Audit trail of every cancellation request and how DelTran resolved it
+
+CREATE TABLE IF NOT EXISTS cancellation_requests (
+    cancellation_id VARCHAR(35) NOT NULL,        -- CxlId (or generated)
+    assignment_id VARCHAR(35) NOT NULL,          -- Assgnmt/Id of the camt.056
+    assigner_bic VARCHAR(11),
+    deltran_tx_id UUID REFERENCES payments(deltran_tx_id) ON DELETE CASCADE,  -- NULL when no original was found
+
+    -- Original payment references as received
+    original_uetr UUID,
+    original_end_to_end_id VARCHAR(35),
+    original_message_id VARCHAR(35),
+
+    -- ExternalCancellationReason1Code (DUPL, CUST, FRAD, ...) or proprietary code
+    reason_code VARCHAR(35),
+    reason_description TEXT,
+
+    -- Resolution returned in the camt.029
+    status VARCHAR(4) NOT NULL CHECK (status IN ('ACCR', 'RJCR')),
+    rejection_reason VARCHAR(35),                -- NOOR, ARDT, ARJT, AGNT, ...
+    resolution_message_id VARCHAR(35) NOT NULL,  -- Assgnmt/Id of the camt.029
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (assignment_id, cancellation_id)
+);
+
+CREATE INDEX idx_cancellation_requests_tx_id ON cancellation_requests(deltran_tx_id);
+CREATE INDEX idx_cancellation_requests_status ON cancellation_requests(status);
+
+COMMENT ON TABLE cancellation_requests IS 'camt.056 cancellation requests with their camt.029 resolution';
@@ -0,0 +1,18 @@
+-- Gateway Service - Payment Tracking
+-- Extends the payment_events audit trail into a per-UETR timeline fed by all engines
+
+ALTER TABLE payment_events
+    ADD COLUMN IF NOT EXISTS uetr UUID,
+    -- gateway, compliance, obligation, risk, clearing, settlement, token, partner
+    ADD COLUMN IF NOT EXISTS source VARCHAR(16) NOT NULL DEFAULT 'gateway',
+    ADD COLUMN IF NOT EXISTS payment_status VARCHAR(50),   -- DelTran PaymentStatus implied by the event
+    ADD COLUMN IF NOT EXISTS reason_code VARCHAR(35),
+    ADD COLUMN IF NOT EXISTS agent_bic VARCHAR(11),        -- reporting agent of a partner trck.001
+    ALTER COLUMN event_type TYPE VARCHAR(64),
+    ALTER COLUMN created_at TYPE TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_payment_events_uetr ON payment_events(uetr, created_at) WHERE uetr IS NOT NULL;
+
+COMMENT ON COLUMN payment_events.event_status IS 'ExternalPaymentTransactionStatus1Code reported in trck.001 / trck.002';
+COMMENT ON COLUMN payment_events.created_at IS 'When the event occurred in the reporting engine';
+COMMENT ON TABLE payment_events IS 'Payment tracking timeline, exposed via GET /payment/uetr/:uetr/timeline and trck.002';
@@ -0,0 +1,55 @@
+-- Gateway Service - Bulk pain.001 Batches
+-- Files uploaded to POST /iso20022/pain.001/bulk and the outcome of every transaction they contain
+
+CREATE TABLE IF NOT EXISTS payment_batches (
+    batch_id UUID PRIMARY KEY,
+    file_name VARCHAR(255),
+
+    -- From the pain.001 group header / first PmtInf
+    message_id VARCHAR(35),                      -- GrpHdr/MsgId
+    debtor_agent_bic VARCHAR(11),                -- recipient of the batch pain.002
+
+    -- Progress
+    status VARCHAR(16) NOT NULL CHECK (status IN ('PROCESSING', 'COMPLETED', 'FAILED')),
+    group_status VARCHAR(4),                     -- ACTC, PART, RJCT
+    declared_transactions INTEGER,               -- GrpHdr/NbOfTxs
+    processed_transactions INTEGER NOT NULL DEFAULT 0,
+    accepted_transactions INTEGER NOT NULL DEFAULT 0,
+    rejected_transactions INTEGER NOT NULL DEFAULT 0,
+    duplicate_transactions INTEGER NOT NULL DEFAULT 0,
+
+    status_report_id UUID REFERENCES status_reports(report_id),
+    error TEXT,
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_payment_batches_message_id ON payment_batches(message_id);
+CREATE INDEX idx_payment_batches_processing ON payment_batches(created_at) WHERE status = 'PROCESSING';
+
+CREATE TABLE IF NOT EXISTS payment_batch_transactions (
+    batch_id UUID NOT NULL REFERENCES payment_batches(batch_id) ON DELETE CASCADE,
+    sequence INTEGER NOT NULL,                   -- position of the CdtTrfTxInf in the file
+
+    payment_info_id VARCHAR(35),
+    end_to_end_id VARCHAR(35),
+    instruction_id VARCHAR(35),
+    uetr UUID,
+    deltran_tx_id UUID,                          -- created payment, or the original one for duplicates
+
+    outcome VARCHAR(16) NOT NULL CHECK (outcome IN ('accepted', 'rejected', 'duplicate')),
+    reason_code VARCHAR(4),                      -- ExternalStatusReason1Code (FF01, AM03, DUPL, ...)
+    reason_info TEXT,
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (batch_id, sequence)
+);
+
+CREATE INDEX idx_payment_batch_transactions_outcome ON payment_batch_transactions(batch_id, outcome);
+CREATE INDEX idx_payment_batch_transactions_tx_id ON payment_batch_transactions(deltran_tx_id) WHERE deltran_tx_id IS NOT NULL;
+
+COMMENT ON TABLE payment_batches IS 'Bulk pain.001 uploads processed in the background with partial acceptance';
+COMMENT ON TABLE payment_batch_transactions IS 'Per-transaction outcome of a bulk pain.001 (reported as RJCT in the batch pain.002)';
@@ -0,0 +1,23 @@
+-- Gateway Service - Transactional Outbox
+-- NATS messages written in the same transaction as the payment row; the relay publishes them
+
+CREATE TABLE IF NOT EXISTS outbox (
+    outbox_id UUID PRIMARY KEY,
+    deltran_tx_id UUID REFERENCES payments(deltran_tx_id),
+
+    subject VARCHAR(255) NOT NULL,               -- e.g. deltran.compliance.check
+    payload BYTEA NOT NULL,
+
+    -- Delivery
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    delivered_at TIMESTAMPTZ,
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_outbox_pending ON outbox(next_attempt_at) WHERE delivered_at IS NULL;
+CREATE INDEX idx_outbox_tx_id ON outbox(deltran_tx_id);
+
+COMMENT ON TABLE outbox IS 'Pending and delivered NATS messages (transactional outbox, published by the relay task)';
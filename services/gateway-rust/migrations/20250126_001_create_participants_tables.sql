@@ -0,0 +1,45 @@
+-- Gateway Service - Participant Access
+-- Registered participants (identified by BIC) and the audit trail of refused requests
+
+CREATE TABLE IF NOT EXISTS participants (
+    participant_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    bic VARCHAR(11) NOT NULL UNIQUE,             -- BIC8 or BIC11 of the institution
+    name VARCHAR(140) NOT NULL,
+
+    -- mTLS: SHA-256 fingerprint of the client certificate (hex, lowercase, no separators)
+    cert_fingerprint VARCHAR(64) UNIQUE,
+
+    role VARCHAR(16) NOT NULL DEFAULT 'participant' CHECK (role IN ('participant', 'operator')),
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS auth_audit_log (
+    audit_id UUID PRIMARY KEY,
+
+    -- Who (as far as known) and what
+    participant_bic VARCHAR(11),                 -- authenticated participant, NULL if authentication failed
+    subject VARCHAR(255),                        -- JWT sub or certificate fingerprint
+    auth_method VARCHAR(16),                     -- jwt, client_cert
+    http_method VARCHAR(8) NOT NULL,
+    path VARCHAR(255) NOT NULL,
+    message_type VARCHAR(16),                    -- e.g. pain.001, camt.054
+
+    -- Why it was refused
+    reason VARCHAR(32) NOT NULL,                 -- e.g. invalid_token, not_debtor_agent
+    claimed_bic VARCHAR(11),                     -- BIC the request acted for (debtor agent, account servicer, ...)
+    detail TEXT,
+
+    occurred_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_auth_audit_log_participant ON auth_audit_log(participant_bic, occurred_at DESC);
+CREATE INDEX idx_auth_audit_log_occurred_at ON auth_audit_log(occurred_at DESC);
+
+-- Bulk batches are visible to the participant that uploaded them
+ALTER TABLE payment_batches ADD COLUMN IF NOT EXISTS submitted_by_bic VARCHAR(11);
+
+COMMENT ON TABLE participants IS 'Institutions allowed to call the gateway (JWT bic claim or mTLS client certificate)';
+COMMENT ON TABLE auth_audit_log IS 'Rejected authentication and authorization attempts';
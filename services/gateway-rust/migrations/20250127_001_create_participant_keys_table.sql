@@ -0,0 +1,26 @@
+-- Gateway Service - Message Signing Keys
+-- Public keys participants sign inbound ISO messages with (detached JWS), several per participant
+-- while a key is being rotated
+
+CREATE TABLE IF NOT EXISTS participant_keys (
+    participant_id UUID NOT NULL REFERENCES participants(participant_id),
+    kid VARCHAR(64) NOT NULL,                    -- JWS "kid" header
+
+    algorithm VARCHAR(8) NOT NULL CHECK (algorithm IN ('ES256', 'ES384', 'RS256', 'RS384', 'RS512', 'PS256', 'PS384', 'PS512', 'EdDSA')),
+    public_key_pem TEXT NOT NULL,
+
+    -- Rotation: a key verifies signatures between valid_from and valid_until unless revoked
+    valid_from TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    valid_until TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ,
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (participant_id, kid)
+);
+
+-- Outbound reports carry the gateway's detached JWS
+ALTER TABLE status_reports ADD COLUMN IF NOT EXISTS signature TEXT;
+
+COMMENT ON TABLE participant_keys IS 'Participant public keys for verifying signed inbound ISO 20022 messages';
+COMMENT ON COLUMN status_reports.signature IS 'Detached JWS over xml_document, returned in the X-JWS-Signature header';
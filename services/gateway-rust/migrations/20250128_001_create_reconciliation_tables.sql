@@ -0,0 +1,67 @@
+-- Gateway Service - camt.053 Reconciliation
+-- End-of-day statements, their balance check and the match result of every entry
+
+CREATE TABLE IF NOT EXISTS bank_statements (
+    statement_id UUID PRIMARY KEY,
+    statement_reference VARCHAR(35) NOT NULL,    -- Stmt/Id
+    message_id VARCHAR(35) NOT NULL,             -- GrpHdr/MsgId
+
+    account_id VARCHAR(34) NOT NULL,             -- IBAN or Othr/Id
+    account_iban VARCHAR(34),
+    account_servicer_bic VARCHAR(11),            -- Acct/Svcr
+    received_from_bic VARCHAR(11) NOT NULL,      -- participant that sent the statement
+    currency VARCHAR(3) NOT NULL,
+    from_date TIMESTAMPTZ,
+    to_date TIMESTAMPTZ,
+
+    -- Balance check: opening + credits - debits = closing (balances signed, DBIT negative)
+    opening_balance DECIMAL(20, 2),              -- OPBD
+    closing_balance DECIMAL(20, 2),              -- CLBD
+    total_credits DECIMAL(20, 2) NOT NULL,       -- booked entries only
+    total_debits DECIMAL(20, 2) NOT NULL,
+    balance_difference DECIMAL(20, 2),           -- NULL when a balance is missing
+
+    entry_count INTEGER NOT NULL,
+    matched_entries INTEGER NOT NULL DEFAULT 0,
+    break_count INTEGER NOT NULL DEFAULT 0,
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    UNIQUE (account_id, statement_reference)
+);
+
+CREATE INDEX idx_bank_statements_received_from ON bank_statements(received_from_bic, created_at DESC);
+CREATE INDEX idx_bank_statements_breaks ON bank_statements(created_at) WHERE break_count > 0;
+
+CREATE TABLE IF NOT EXISTS bank_statement_entries (
+    statement_id UUID NOT NULL REFERENCES bank_statements(statement_id) ON DELETE CASCADE,
+    sequence INTEGER NOT NULL,                   -- position of the Ntry in the statement
+
+    entry_reference VARCHAR(35),                 -- AcctSvcrRef
+    amount DECIMAL(20, 2) NOT NULL,
+    currency VARCHAR(3) NOT NULL,
+    credit_debit_indicator VARCHAR(4) NOT NULL CHECK (credit_debit_indicator IN ('CRDT', 'DBIT')),
+    entry_status VARCHAR(4) NOT NULL,            -- BOOK, PDNG, INFO
+    booking_date DATE,
+    value_date DATE,
+    end_to_end_id VARCHAR(35),
+    transaction_id VARCHAR(35),
+    uetr UUID,
+
+    match_status VARCHAR(16) NOT NULL CHECK (match_status IN (
+        'matched', 'amount_mismatch', 'status_mismatch', 'unmatched', 'no_reference', 'not_booked'
+    )),
+    deltran_tx_id UUID,                          -- matched payment
+    break_reason TEXT,
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (statement_id, sequence)
+);
+
+CREATE INDEX idx_bank_statement_entries_breaks ON bank_statement_entries(statement_id)
+    WHERE match_status NOT IN ('matched', 'not_booked');
+CREATE INDEX idx_bank_statement_entries_tx_id ON bank_statement_entries(deltran_tx_id) WHERE deltran_tx_id IS NOT NULL;
+
+COMMENT ON TABLE bank_statements IS 'camt.053 statements received for EOD reconciliation, with their balance check';
+COMMENT ON TABLE bank_statement_entries IS 'camt.053 entries matched against gateway payments (UETR, then EndToEndId); non-matched booked entries are breaks';
@@ -0,0 +1,51 @@
+-- Gateway Service - camt.054 Funding Queue
+-- Credit entries kept until they fund a payment: pending (not yet booked) entries and booked
+-- credits whose payment is unknown. Matched late when the pain.001 arrives or the entry is booked.
+
+CREATE TABLE IF NOT EXISTS funding_events (
+    event_id UUID PRIMARY KEY,
+
+    account_id VARCHAR(34) NOT NULL,             -- IBAN or Othr/Id of the notified account
+    account_servicer_bic VARCHAR(11),            -- Acct/Svcr, checked against the sender on receipt
+    received_from_bic VARCHAR(11) NOT NULL,      -- participant that sent the camt.054
+    entry_reference VARCHAR(35),                 -- NtryRef - a re-sent entry updates this row
+
+    amount DECIMAL(18, 5) NOT NULL,
+    currency VARCHAR(3) NOT NULL,
+    entry_status VARCHAR(4) NOT NULL,            -- BOOK, PDNG, INFO
+    booking_date VARCHAR(35),
+    value_date VARCHAR(35),
+
+    -- Matching inputs
+    end_to_end_id VARCHAR(35),
+    instruction_id VARCHAR(35),
+    uetr UUID,
+    debtor_name VARCHAR(140),
+    debtor_account VARCHAR(34),
+    remittance_info TEXT,
+
+    -- Match result
+    state VARCHAR(16) NOT NULL CHECK (state IN ('pending', 'unmatched', 'matched')),
+    deltran_tx_id UUID,
+    match_method VARCHAR(16) CHECK (match_method IN ('reference', 'fuzzy', 'manual')),
+    matched_by_bic VARCHAR(11),                  -- operator of a manual match
+    match_note TEXT,
+    matched_at TIMESTAMPTZ,
+    last_match_attempt_at TIMESTAMPTZ,           -- background matcher retries the oldest attempts first
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX idx_funding_events_entry_reference ON funding_events(account_id, entry_reference)
+    WHERE entry_reference IS NOT NULL;
+CREATE INDEX idx_funding_events_open ON funding_events(currency, amount) WHERE state = 'unmatched';
+CREATE INDEX idx_funding_events_retry ON funding_events(last_match_attempt_at NULLS FIRST) WHERE state = 'unmatched';
+CREATE INDEX idx_funding_events_end_to_end_id ON funding_events(end_to_end_id) WHERE state = 'unmatched';
+CREATE INDEX idx_funding_events_uetr ON funding_events(uetr) WHERE state = 'unmatched';
+CREATE INDEX idx_funding_events_tx_id ON funding_events(deltran_tx_id) WHERE deltran_tx_id IS NOT NULL;
+
+CREATE INDEX idx_payments_awaiting_funding ON payments(currency, instructed_amount)
+    WHERE status IN ('Received', 'Validated', 'Accepted', 'Pending', 'PendingFunding');
+
+COMMENT ON TABLE funding_events IS 'camt.054 credits waiting for (or matched to) the payment they fund';
@@ -0,0 +1,14 @@
+-- Gateway Service - Amount-aware Funding
+-- A payment may be funded by several camt.054 credits. Tokens are minted once the credits cover
+-- the instructed amount (within FUNDING_TOLERANCE_BPS), for the funded amount at most; anything
+-- credited beyond the instructed amount is flagged for refund.
+
+ALTER TABLE payments
+    ADD COLUMN IF NOT EXISTS funded_amount DECIMAL(18, 5) NOT NULL DEFAULT 0,      -- sum of matched credits
+    ADD COLUMN IF NOT EXISTS overfunded_amount DECIMAL(18, 5) NOT NULL DEFAULT 0,  -- credited beyond the instructed amount
+    ADD COLUMN IF NOT EXISTS refund_status VARCHAR(16) CHECK (refund_status IN ('required', 'refunded'));
+
+CREATE INDEX IF NOT EXISTS idx_payments_refund_required ON payments(funded_at) WHERE refund_status = 'required';
+
+COMMENT ON COLUMN payments.funded_amount IS 'Sum of the camt.054 credits matched to the payment';
+COMMENT ON COLUMN payments.overfunded_amount IS 'Credited beyond the instructed amount - to be refunded to the debtor agent';
@@ -0,0 +1,13 @@
+-- Gateway Service - Payment Search
+-- GET /payments filters on the corridor (debtor agent country -> creditor agent country, as in the
+-- corridor rules) and pages newest first by (created_at, deltran_tx_id).
+
+ALTER TABLE payments
+    ADD COLUMN IF NOT EXISTS corridor VARCHAR(5) GENERATED ALWAYS AS (
+        SUBSTRING(debtor_agent_bic FROM 5 FOR 2) || '_' || SUBSTRING(creditor_agent_bic FROM 5 FOR 2)
+    ) STORED;
+
+CREATE INDEX IF NOT EXISTS idx_payments_search_keyset ON payments(created_at DESC, deltran_tx_id DESC);
+CREATE INDEX IF NOT EXISTS idx_payments_corridor ON payments(corridor, created_at DESC);
+
+COMMENT ON COLUMN payments.corridor IS 'Debtor agent country _ creditor agent country (e.g. AE_IN), derived from the BICs';
@@ -0,0 +1,46 @@
+-- Gateway Service - Webhook Notifications
+-- Participants' endpoints for payment notifications and every delivery made to them. Deliveries
+-- are retried with exponential backoff and dead-lettered after WEBHOOK_MAX_ATTEMPTS; a replay is a
+-- new delivery of the same payload.
+
+CREATE TABLE IF NOT EXISTS webhook_endpoints (
+    endpoint_id UUID PRIMARY KEY,
+    participant_bic VARCHAR(11) NOT NULL,
+    url TEXT NOT NULL,
+    event_types TEXT[] NOT NULL DEFAULT '{}',    -- notification types (status_update, ...), empty = all
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX idx_webhook_endpoints_url ON webhook_endpoints(participant_bic, url) WHERE active;
+CREATE INDEX idx_webhook_endpoints_institution ON webhook_endpoints(LEFT(participant_bic, 8)) WHERE active;
+
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    delivery_id UUID PRIMARY KEY,
+    endpoint_id UUID NOT NULL REFERENCES webhook_endpoints(endpoint_id),
+    participant_bic VARCHAR(11) NOT NULL,
+    url TEXT NOT NULL,                           -- endpoint URL at the time of the notification
+    event_type VARCHAR(64) NOT NULL,
+    deltran_tx_id UUID,
+    payload JSONB NOT NULL,
+
+    -- Delivery
+    state VARCHAR(16) NOT NULL CHECK (state IN ('pending', 'delivered', 'dead')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_status_code INTEGER,
+    last_error TEXT,
+    replay_of UUID REFERENCES webhook_deliveries(delivery_id),
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    delivered_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_webhook_deliveries_due ON webhook_deliveries(next_attempt_at) WHERE state = 'pending';
+CREATE INDEX idx_webhook_deliveries_participant ON webhook_deliveries(participant_bic, created_at DESC);
+CREATE INDEX idx_webhook_deliveries_tx_id ON webhook_deliveries(deltran_tx_id) WHERE deltran_tx_id IS NOT NULL;
+
+COMMENT ON TABLE webhook_endpoints IS 'Participant HTTPS endpoints for signed payment notifications';
+COMMENT ON TABLE webhook_deliveries IS 'Webhook deliveries with retry state and history (dead = attempts exhausted)';
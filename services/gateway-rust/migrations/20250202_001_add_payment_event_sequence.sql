@@ -0,0 +1,10 @@
+-- Gateway Service - Live Payment Stream
+-- Every tracking event gets a gateway-wide sequence number. GET /ws/payments pushes events in
+-- sequence order and a reconnecting client resumes with ?from_sequence= (the last one it saw).
+
+ALTER TABLE payment_events
+    ADD COLUMN IF NOT EXISTS sequence BIGSERIAL;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_payment_events_sequence ON payment_events(sequence);
+
+COMMENT ON COLUMN payment_events.sequence IS 'Gateway-wide event order - resume point of the /ws/payments stream';
@@ -0,0 +1,19 @@
+-- Gateway Service - Idempotency-Keys per submitting participant
+-- A key only replays submissions of the participant that used it. It is reserved before the
+-- submission is processed (completed_at NULL) and completed with the payments created, so two
+-- concurrent requests with the same key cannot both be processed.
+
+ALTER TABLE idempotency_keys
+    ADD COLUMN IF NOT EXISTS submitter_bic VARCHAR(11) NOT NULL DEFAULT '',
+    ADD COLUMN IF NOT EXISTS completed_at TIMESTAMPTZ;
+
+-- Keys stored so far were written once processing finished; unscoped ones match no submitter
+-- and expire with the dedup horizon
+UPDATE idempotency_keys SET completed_at = created_at WHERE completed_at IS NULL;
+
+ALTER TABLE idempotency_keys DROP CONSTRAINT IF EXISTS idempotency_keys_pkey;
+ALTER TABLE idempotency_keys ADD PRIMARY KEY (submitter_bic, idempotency_key);
+ALTER TABLE idempotency_keys ALTER COLUMN submitter_bic DROP DEFAULT;
+
+COMMENT ON COLUMN idempotency_keys.submitter_bic IS 'Authenticated participant that submitted the message';
+COMMENT ON COLUMN idempotency_keys.completed_at IS 'NULL while the first submission is still being processed';
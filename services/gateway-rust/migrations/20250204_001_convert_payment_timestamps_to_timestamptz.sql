@@ -0,0 +1,11 @@
+-- Gateway Service - payments timestamps as TIMESTAMPTZ
+-- Every other gateway table stores TIMESTAMPTZ and the service reads these columns as UTC
+-- DateTime values; existing values were written by NOW() in a UTC session.
+
+ALTER TABLE payments
+    ALTER COLUMN created_at TYPE TIMESTAMPTZ USING created_at AT TIME ZONE 'UTC',
+    ALTER COLUMN updated_at TYPE TIMESTAMPTZ USING updated_at AT TIME ZONE 'UTC',
+    ALTER COLUMN funded_at TYPE TIMESTAMPTZ USING funded_at AT TIME ZONE 'UTC',
+    ALTER COLUMN cleared_at TYPE TIMESTAMPTZ USING cleared_at AT TIME ZONE 'UTC',
+    ALTER COLUMN settled_at TYPE TIMESTAMPTZ USING settled_at AT TIME ZONE 'UTC',
+    ALTER COLUMN completed_at TYPE TIMESTAMPTZ USING completed_at AT TIME ZONE 'UTC';
@@ -0,0 +1,21 @@
+-- Gateway Service - Participant Accounts
+-- Accounts each participant services. A camt.054 credit may fund another bank's payment only when
+-- its sender services the credited account here - the Acct/Svcr named in the message is not trusted.
+
+CREATE TABLE IF NOT EXISTS participant_accounts (
+    account_id VARCHAR(34) PRIMARY KEY,          -- IBAN or Othr/Id as notified in camt.054 Acct/Id, no spaces, upper case
+    participant_id UUID NOT NULL REFERENCES participants(participant_id),   -- account servicer
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_participant_accounts_participant ON participant_accounts(participant_id);
+
+-- Set on receipt when the sender services the credited account; otherwise the credit only funds
+-- payments of which the sender is the debtor agent
+ALTER TABLE funding_events ADD COLUMN IF NOT EXISTS servicer_verified BOOLEAN NOT NULL DEFAULT FALSE;
+
+COMMENT ON TABLE participant_accounts IS 'Accounts serviced by each participant - camt.054 funding authorization';
+COMMENT ON COLUMN funding_events.servicer_verified IS 'Sender services account_id according to participant_accounts';
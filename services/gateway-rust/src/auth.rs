@@ -0,0 +1,193 @@
+// Participant Authentication - who is calling the gateway
+// Every ISO 20022 and query endpoint requires a registered participant, identified either by an
+// mTLS client certificate (terminated at the ingress, which forwards the certificate fingerprint
+// in a trusted header) or by an HS256 JWT whose `bic` claim names the participant. Handlers then
+// check the caller against the debtor agent / account servicer of the message. Every refused
+// request is written to auth_audit_log.
+
+use anyhow::{bail, Result};
+use axum::http::HeaderMap;
+use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
+use serde::Deserialize;
+use sqlx::PgPool;
+use tracing::{error, warn};
+
+use crate::db;
+use crate::metrics::METRICS;
+use crate::models::participant::{AuthAuditEntry, AuthFailure, AuthMethod, AuthenticatedParticipant, Participant};
+
+/// JWT claims issued to participants (same shape as the engines' tokens, plus the BIC)
+#[derive(Debug, Deserialize)]
+pub struct Claims {
+    pub sub: String,
+    pub bic: String,
+    pub exp: usize,
+}
+
+/// Caller that could not be authenticated (subject as far as it is known)
+#[derive(Debug, Clone)]
+pub struct Refused {
+    pub reason: AuthFailure,
+    pub subject: Option<String>,
+}
+
+impl Refused {
+    fn new(reason: AuthFailure, subject: Option<String>) -> Self {
+        Self { reason, subject }
+    }
+}
+
+pub struct Authenticator {
+    db: PgPool,
+    jwt: Option<(DecodingKey, Validation)>,
+    client_cert_header: Option<String>,
+}
+
+impl Authenticator {
+    /// JWT_SECRET enables bearer tokens, AUTH_CLIENT_CERT_HEADER enables mTLS; at least one is required
+    pub fn new(db: PgPool, jwt_secret: Option<&str>, client_cert_header: Option<&str>) -> Result<Self> {
+        let jwt = jwt_secret
+            .filter(|secret| !secret.is_empty())
+            .map(|secret| (DecodingKey::from_secret(secret.as_bytes()), Validation::new(Algorithm::HS256)));
+        let client_cert_header = client_cert_header
+            .map(str::trim)
+            .filter(|header| !header.is_empty())
+            .map(str::to_ascii_lowercase);
+
+        if jwt.is_none() && client_cert_header.is_none() {
+            bail!("No participant authentication configured: set JWT_SECRET and/or AUTH_CLIENT_CERT_HEADER");
+        }
+
+        Ok(Self { db, jwt, client_cert_header })
+    }
+
+    pub fn methods(&self) -> Vec<&'static str> {
+        let mut methods = Vec::new();
+        if self.client_cert_header.is_some() {
+            methods.push(AuthMethod::ClientCert.as_str());
+        }
+        if self.jwt.is_some() {
+            methods.push(AuthMethod::Jwt.as_str());
+        }
+        methods
+    }
+
+    /// Resolve the calling participant. The client certificate wins over a bearer token.
+    /// The outer error is an infrastructure failure, the inner one a refused caller.
+    pub async fn authenticate(&self, headers: &HeaderMap) -> Result<Result<AuthenticatedParticipant, Refused>> {
+        if let Some(fingerprint) = self.client_cert_fingerprint(headers) {
+            let participant = db::get_participant_by_fingerprint(&self.db, &fingerprint).await?;
+            return Ok(match participant {
+                None => Err(Refused::new(AuthFailure::UnknownCertificate, Some(fingerprint))),
+                Some(participant) => Self::admit(participant, AuthMethod::ClientCert, fingerprint),
+            });
+        }
+
+        let Some(token) = bearer_token(headers) else {
+            return Ok(Err(Refused::new(AuthFailure::MissingCredentials, None)));
+        };
+        let Some((key, validation)) = &self.jwt else {
+            return Ok(Err(Refused::new(AuthFailure::InvalidToken, None)));
+        };
+
+        let claims = match decode::<Claims>(token, key, validation) {
+            Ok(data) => data.claims,
+            Err(e) => {
+                warn!("🔐 Rejected JWT: {}", e);
+                return Ok(Err(Refused::new(AuthFailure::InvalidToken, None)));
+            }
+        };
+
+        Ok(match db::get_participant_by_bic(&self.db, &claims.bic).await? {
+            None => Err(Refused::new(AuthFailure::UnknownParticipant, Some(claims.sub))),
+            Some(participant) => Self::admit(participant, AuthMethod::Jwt, claims.sub),
+        })
+    }
+
+    fn admit(participant: Participant, method: AuthMethod, subject: String) -> Result<AuthenticatedParticipant, Refused> {
+        if !participant.active {
+            return Err(Refused::new(AuthFailure::InactiveParticipant, Some(subject)));
+        }
+        Ok(AuthenticatedParticipant { participant, method, subject })
+    }
+
+    fn client_cert_fingerprint(&self, headers: &HeaderMap) -> Option<String> {
+        let header = self.client_cert_header.as_deref()?;
+        headers.get(header)
+            .and_then(|v| v.to_str().ok())
+            .map(normalize_fingerprint)
+            .filter(|fingerprint| !fingerprint.is_empty())
+    }
+
+    pub async fn audit(&self, entry: AuthAuditEntry) {
+        audit(&self.db, entry).await
+    }
+}
+
+/// Write a refused request to the audit trail - never fails the request itself
+pub async fn audit(db: &PgPool, entry: AuthAuditEntry) {
+    METRICS.track_auth_rejection(entry.reason.as_str());
+    warn!("🚫 {} {} refused: {} (participant: {:?}, claimed BIC: {:?})",
+          entry.http_method, entry.path, entry.reason.as_str(), entry.participant_bic, entry.claimed_bic);
+
+    if let Err(e) = db::insert_auth_audit(db, &entry).await {
+        error!("Failed to write auth audit entry {}: {}", entry.audit_id, e);
+    }
+}
+
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers.get(axum::http::header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+}
+
+/// SHA-256 fingerprints arrive as "AB:CD:..." or plain hex depending on the ingress
+pub fn normalize_fingerprint(value: &str) -> String {
+    value.chars()
+        .filter(|c| c.is_ascii_hexdigit())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+
+    #[test]
+    fn test_normalize_fingerprint() {
+        assert_eq!(normalize_fingerprint("AB:cd:01"), "abcd01");
+        assert_eq!(normalize_fingerprint(" abcd01 "), "abcd01");
+    }
+
+    #[test]
+    fn test_bearer_token() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(bearer_token(&headers), None);
+
+        headers.insert(axum::http::header::AUTHORIZATION, "Bearer abc.def.ghi".parse().unwrap());
+        assert_eq!(bearer_token(&headers), Some("abc.def.ghi"));
+
+        headers.insert(axum::http::header::AUTHORIZATION, "Basic dXNlcg==".parse().unwrap());
+        assert_eq!(bearer_token(&headers), None);
+    }
+
+    #[test]
+    fn test_claims_decode() {
+        let exp = (chrono::Utc::now().timestamp() + 600) as usize;
+        let token = encode(
+            &Header::default(),
+            &serde_json::json!({ "sub": "ops@bankaead", "bic": "BANKAEADXXX", "exp": exp }),
+            &EncodingKey::from_secret(b"secret"),
+        ).unwrap();
+
+        let validation = Validation::new(Algorithm::HS256);
+        let claims = decode::<Claims>(&token, &DecodingKey::from_secret(b"secret"), &validation).unwrap().claims;
+        assert_eq!(claims.bic, "BANKAEADXXX");
+        assert_eq!(claims.sub, "ops@bankaead");
+
+        assert!(decode::<Claims>(&token, &DecodingKey::from_secret(b"other"), &validation).is_err());
+    }
+}
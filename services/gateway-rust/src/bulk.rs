@@ -0,0 +1,362 @@
+// Bulk pain.001 Ingestion - large files uploaded to POST /iso20022/pain.001/bulk
+// The upload is spooled to disk and processed in the background: a blocking reader streams the
+// file one CdtTrfTxInf at a time, and every transaction is validated, deduplicated, persisted and
+// routed on its own. A rejected transaction never fails the batch - its outcome is stored and
+// reported in one pain.002 (ACTC / PART / RJCT). Progress is polled via GET /batches/:batch_id.
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{bail, Result};
+use sqlx::PgPool;
+use tokio::sync::mpsc;
+use tracing::{info, warn, error};
+use uuid::Uuid;
+
+use crate::auth;
+use crate::db;
+use crate::idempotency::{DuplicateOf, IdempotencyGuard};
+use crate::iso20022::{BulkTransaction, Pain001Stream, TransactionRejection};
+use crate::metrics::METRICS;
+use crate::models::batch::{BatchTransaction, PaymentBatch, TransactionOutcome};
+use crate::models::participant::{AuthAuditEntry, AuthFailure, AuthenticatedParticipant};
+use crate::nats_router::NatsRouter;
+use crate::status_reports::StatusReporter;
+use crate::tracking::PaymentTracker;
+use crate::validation::{RuleEngine, XsdValidator};
+
+/// Default upper bound for an uploaded file (BULK_MAX_FILE_MB)
+pub const DEFAULT_BULK_MAX_FILE_MB: usize = 100;
+
+/// Batch counters are persisted every N transactions
+const PROGRESS_INTERVAL: i32 = 100;
+
+/// Transactions read ahead of the (slower) async processing
+const READ_AHEAD: usize = 256;
+
+/// What the blocking file reader hands to the async processor
+enum ParsedItem {
+    Header { message_id: String, declared_transactions: Option<i32>, sender_bic: Option<String> },
+    Transaction(Box<BulkTransaction>),
+}
+
+pub struct BulkIngestion {
+    db: PgPool,
+    xsd: Arc<XsdValidator>,
+    rules: Arc<RuleEngine>,
+    idempotency: Arc<IdempotencyGuard>,
+    reporter: Arc<StatusReporter>,
+    tracker: Arc<PaymentTracker>,
+    spool_dir: PathBuf,
+}
+
+impl BulkIngestion {
+    pub fn new(
+        db: PgPool,
+        xsd: Arc<XsdValidator>,
+        rules: Arc<RuleEngine>,
+        idempotency: Arc<IdempotencyGuard>,
+        reporter: Arc<StatusReporter>,
+        tracker: Arc<PaymentTracker>,
+        spool_dir: PathBuf,
+    ) -> Self {
+        Self { db, xsd, rules, idempotency, reporter, tracker, spool_dir }
+    }
+
+    /// Parse BULK_MAX_FILE_MB, falling back to the default on invalid input
+    pub fn max_file_bytes_from_env_value(value: &str) -> usize {
+        value.trim().parse::<usize>()
+            .ok()
+            .filter(|mb| *mb > 0)
+            .unwrap_or(DEFAULT_BULK_MAX_FILE_MB)
+            * 1024 * 1024
+    }
+
+    /// Where the upload of a batch is spooled until it has been processed
+    pub fn spool_path(&self, batch_id: Uuid) -> PathBuf {
+        self.spool_dir.join(format!("pain001-bulk-{}.xml", batch_id))
+    }
+
+    /// Register a spooled upload and process it in the background
+    pub async fn submit(
+        self: &Arc<Self>,
+        mut batch: PaymentBatch,
+        path: PathBuf,
+        submitter: AuthenticatedParticipant,
+    ) -> Result<PaymentBatch> {
+        db::upsert_payment_batch(&self.db, &batch).await?;
+        let submitted = batch.clone();
+
+        let ingestion = self.clone();
+        tokio::spawn(async move {
+            ingestion.process(&mut batch, &path, &submitter).await;
+            if let Err(e) = tokio::fs::remove_file(&path).await {
+                warn!("Failed to remove spooled file {}: {}", path.display(), e);
+            }
+        });
+
+        Ok(submitted)
+    }
+
+    async fn process(&self, batch: &mut PaymentBatch, path: &Path, submitter: &AuthenticatedParticipant) {
+        let start = std::time::Instant::now();
+        let (sender, mut receiver) = mpsc::channel(READ_AHEAD);
+
+        let reader = {
+            let path = path.to_path_buf();
+            let xsd = self.xsd.clone();
+            tokio::task::spawn_blocking(move || read_file(&path, &xsd, &sender))
+        };
+
+        let mut transactions = Vec::new();
+        let mut refused = None;
+        while let Some(item) = receiver.recv().await {
+            match item {
+                ParsedItem::Header { message_id, declared_transactions, sender_bic } => {
+                    // An enveloped file must come from the uploading participant
+                    if sender_bic.is_some() && !submitter.may_act_for(sender_bic.as_deref()) {
+                        let entry = AuthAuditEntry::forbidden(submitter, "POST", "/iso20022/pain.001/bulk", AuthFailure::NotSender)
+                            .message_type("pain.001")
+                            .claimed_bic(sender_bic.as_deref())
+                            .detail(format!("batch {}", batch.batch_id));
+                        auth::audit(&self.db, entry).await;
+                        refused = Some(format!("{} is not the AppHdr sender {:?}", submitter.bic, sender_bic));
+                        break;
+                    }
+
+                    info!("📦 Batch {}: pain.001 {} with {:?} transaction(s)", batch.batch_id, message_id, declared_transactions);
+                    batch.message_id = Some(message_id);
+                    batch.declared_transactions = declared_transactions;
+                    self.save(batch).await;
+                }
+                ParsedItem::Transaction(transaction) => {
+                    if batch.debtor_agent_bic.is_none() {
+                        batch.debtor_agent_bic = transaction.debtor_agent_bic.clone();
+                    }
+
+                    let result = self.ingest(batch.batch_id, *transaction, submitter).await;
+                    METRICS.track_bulk_transaction(result.outcome.as_str());
+                    if let Err(e) = db::insert_batch_transaction(&self.db, &result).await {
+                        error!("Failed to store outcome of transaction {} of batch {}: {}", result.sequence, batch.batch_id, e);
+                    }
+
+                    batch.record(result.outcome);
+                    if batch.processed_transactions % PROGRESS_INTERVAL == 0 {
+                        self.save(batch).await;
+                    }
+                    transactions.push(result);
+                }
+            }
+        }
+
+        // The reader stops at the first unreadable part of the file; everything before it stands
+        drop(receiver);
+        let failure = match reader.await {
+            _ if refused.is_some() => refused,
+            Ok(Ok(())) => None,
+            Ok(Err(e)) => Some(e.to_string()),
+            Err(e) => Some(format!("Batch reader aborted: {}", e)),
+        };
+        if let Some(reason) = &failure {
+            warn!("❌ Batch {} stopped after {} transaction(s): {}", batch.batch_id, batch.processed_transactions, reason);
+        }
+        batch.finish(failure);
+
+        if !transactions.is_empty() {
+            match self.reporter.report_batch(batch, &transactions).await {
+                Ok(report) => batch.status_report_id = Some(report.report_id),
+                Err(e) => error!("Failed to generate pain.002 for batch {}: {}", batch.batch_id, e),
+            }
+        }
+        self.save(batch).await;
+
+        METRICS.track_bulk_batch(batch.status.as_str());
+        METRICS.payment_processing_duration_seconds.observe(start.elapsed().as_secs_f64());
+        info!("✅ Batch {} {}: {} accepted, {} rejected, {} duplicate ({:?})",
+              batch.batch_id, batch.status.as_str(), batch.accepted_transactions,
+              batch.rejected_transactions, batch.duplicate_transactions, batch.group_status);
+    }
+
+    /// Validate, deduplicate, persist and route a single transaction
+    async fn ingest(&self, batch_id: Uuid, transaction: BulkTransaction, submitter: &AuthenticatedParticipant) -> BatchTransaction {
+        let result = BatchTransaction {
+            batch_id,
+            sequence: transaction.sequence as i32,
+            payment_info_id: max35(transaction.payment_info_id),
+            end_to_end_id: max35(transaction.end_to_end_id),
+            instruction_id: max35(transaction.instruction_id),
+            uetr: transaction.uetr,
+            deltran_tx_id: None,
+            outcome: TransactionOutcome::Accepted,
+            reason_code: None,
+            reason_info: None,
+        };
+
+        let payment = match transaction.payment {
+            Ok(payment) => payment,
+            Err(rejection) => return rejected(result, rejection),
+        };
+
+        // Only the debtor agent may submit a payment, as for single pain.001 messages
+        if !submitter.may_submit(&payment) {
+            let entry = AuthAuditEntry::forbidden(submitter, "POST", "/iso20022/pain.001/bulk", AuthFailure::NotDebtorAgent)
+                .message_type("pain.001")
+                .claimed_bic(payment.debtor_agent.bic.as_deref())
+                .detail(format!("batch {} transaction {}", batch_id, result.sequence));
+            auth::audit(&self.db, entry).await;
+            return rejected(result, forbidden_rejection(&submitter.bic));
+        }
+
+        match self.idempotency.find_duplicate(&payment).await {
+            Ok(Some(duplicate)) => return duplicate_of(result, duplicate),
+            Ok(None) => {}
+            Err(e) => {
+                error!("Duplicate check failed for transaction {} of batch {}: {}", result.sequence, batch_id, e);
+                return rejected(result, agent_rejection("Duplicate check unavailable"));
+            }
+        }
+
+        // Corridor business rules, as for single pain.001 messages
+        let violations = self.rules.validate(&payment);
+        if let Some(first) = violations.first() {
+            let info = violations.iter().map(|v| v.message.as_str()).collect::<Vec<_>>().join("; ");
+            return rejected(result, TransactionRejection { code: first.code, info });
+        }
+
+        METRICS.payments_total.inc();
+        METRICS.payments_received.inc();
+
+        // Gateway → Compliance only, exactly as for single pain.001 messages (via the outbox)
+        let stored = match NatsRouter::compliance_message(&payment) {
+            Ok(compliance) => self.idempotency.store(&payment, "pain.001", &[compliance]).await,
+            Err(e) => Err(e),
+        };
+        match stored {
+            Ok(Some(duplicate)) => return duplicate_of(result, duplicate),
+            Ok(None) => {}
+            Err(e) => {
+                METRICS.db_errors_total.inc();
+                error!("Failed to store transaction {} of batch {}: {}", result.sequence, batch_id, e);
+                return rejected(result, agent_rejection("Payment could not be stored"));
+            }
+        }
+        METRICS.db_operations_total.inc();
+
+        if let Err(e) = self.tracker.record_status_change(&payment).await {
+            error!("Failed to record tracking event for {}: {}", payment.deltran_tx_id, e);
+        }
+
+        BatchTransaction { deltran_tx_id: Some(payment.deltran_tx_id), ..result }
+    }
+
+    async fn save(&self, batch: &PaymentBatch) {
+        if let Err(e) = db::upsert_payment_batch(&self.db, batch).await {
+            error!("Failed to update batch {}: {}", batch.batch_id, e);
+        }
+    }
+}
+
+/// Blocking side: stream the spooled file, schema-validate each transaction on its own
+fn read_file(path: &Path, xsd: &XsdValidator, sender: &mpsc::Sender<ParsedItem>) -> Result<()> {
+    let stream = Pain001Stream::open(BufReader::new(File::open(path)?))?;
+    if let Some(app_header) = stream.app_header().filter(|h| h.message_type() != Some("pain.001")) {
+        bail!("AppHdr MsgDefIdr {} is not a pain.001", app_header.msg_def_idr);
+    }
+    let header = ParsedItem::Header {
+        message_id: stream.group_header().msg_id.clone(),
+        declared_transactions: stream.declared_transactions(),
+        sender_bic: stream.app_header().and_then(|h| h.from_bic()).map(str::to_string),
+    };
+    if sender.blocking_send(header).is_err() {
+        return Ok(());
+    }
+
+    for transaction in stream {
+        let mut transaction = transaction?;
+        if transaction.payment.is_ok() {
+            if let Err(report) = xsd.validate("pain.001", &transaction.document) {
+                transaction.payment = Err(TransactionRejection::invalid_format(report.to_string()));
+            }
+        }
+
+        // Receiver gone - the processor has stopped, nothing left to do
+        if sender.blocking_send(ParsedItem::Transaction(Box::new(transaction))).is_err() {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+fn rejected(result: BatchTransaction, rejection: TransactionRejection) -> BatchTransaction {
+    BatchTransaction {
+        outcome: TransactionOutcome::Rejected,
+        reason_code: Some(rejection.code.to_string()),
+        reason_info: Some(rejection.info),
+        ..result
+    }
+}
+
+/// DUPL - the transaction repeats an earlier payment of the same debtor agent
+fn duplicate_of(result: BatchTransaction, duplicate: DuplicateOf) -> BatchTransaction {
+    BatchTransaction {
+        deltran_tx_id: Some(duplicate.deltran_tx_id),
+        outcome: TransactionOutcome::Duplicate,
+        reason_code: Some("DUPL".to_string()),
+        reason_info: Some(format!(
+            "Duplicate of payment {} (matched on {})", duplicate.deltran_tx_id, duplicate.matched_on.as_str()
+        )),
+        ..result
+    }
+}
+
+/// MS03 - NotSpecifiedReasonAgentGenerated (DelTran could not take the transaction over)
+fn agent_rejection(info: &str) -> TransactionRejection {
+    TransactionRejection { code: "MS03", info: info.to_string() }
+}
+
+/// AG01 - TransactionForbidden (the uploading participant is not the debtor agent)
+fn forbidden_rejection(submitter_bic: &str) -> TransactionRejection {
+    TransactionRejection { code: "AG01", info: format!("{} is not the debtor agent of this transaction", submitter_bic) }
+}
+
+/// Identifiers are stored as Max35Text even when the file violates the schema
+fn max35(value: Option<String>) -> Option<String> {
+    value.map(|v| v.chars().take(35).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_file_bytes_from_env_value() {
+        assert_eq!(BulkIngestion::max_file_bytes_from_env_value("250"), 250 * 1024 * 1024);
+        assert_eq!(BulkIngestion::max_file_bytes_from_env_value("0"), DEFAULT_BULK_MAX_FILE_MB * 1024 * 1024);
+        assert_eq!(BulkIngestion::max_file_bytes_from_env_value("big"), DEFAULT_BULK_MAX_FILE_MB * 1024 * 1024);
+    }
+
+    #[test]
+    fn test_rejected_keeps_identifiers() {
+        let result = BatchTransaction {
+            batch_id: Uuid::new_v4(),
+            sequence: 7,
+            payment_info_id: Some("PMT-1".to_string()),
+            end_to_end_id: max35(Some("E2E-".repeat(20))),
+            instruction_id: None,
+            uetr: None,
+            deltran_tx_id: None,
+            outcome: TransactionOutcome::Accepted,
+            reason_code: None,
+            reason_info: None,
+        };
+
+        let result = rejected(result, TransactionRejection { code: "AM03", info: "Currency XXX is not supported".to_string() });
+        assert_eq!(result.outcome, TransactionOutcome::Rejected);
+        assert_eq!(result.reason_code.as_deref(), Some("AM03"));
+        assert_eq!(result.end_to_end_id.map(|e| e.len()), Some(35));
+        assert_eq!(result.sequence, 7);
+    }
+}
@@ -1,15 +1,29 @@
 // Database layer for Gateway Service
 // Persists canonical payments and provides query interface
 
-use sqlx::PgPool;
+use sqlx::{PgConnection, PgExecutor, PgPool};
 use uuid::Uuid;
 use anyhow::Result;
-use tracing::{info, error};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use tracing::info;
 
 use crate::models::canonical::{CanonicalPayment, PaymentStatus};
+use crate::models::tracking::{EventSource, PaymentEvent};
+use crate::models::batch::{BatchStatus, BatchTransaction, PaymentBatch, TransactionOutcome};
+use crate::models::outbox::OutboxMessage;
+use crate::models::participant::{AuthAuditEntry, Participant, ParticipantAccount, ParticipantKey, ParticipantRole};
+use crate::models::reconciliation::{BankStatement, EntryMatchStatus, StatementEntryRecord};
+use crate::models::search::{status_from_db, PaymentCursor, PaymentFilter, PaymentSummary};
+use crate::models::webhook::{DeliveryState, WebhookDelivery, WebhookEndpoint};
+use crate::models::stream::{StreamEvent, StreamFilter};
+use crate::models::funding::{FundingCandidate, FundingEventRecord, FundingEventState, FundingRefund, MatchMethod, PaymentFunding};
+use crate::iso20022::{PaymentReturn, CancellationResolution};
+use crate::status_reports::StatusReportRecord;
 
 /// Insert a new payment into the database
-pub async fn insert_payment(pool: &PgPool, payment: &CanonicalPayment) -> Result<()> {
+/// `source_message_type` is the ISO message that created it (pain.001 / pacs.008)
+pub async fn insert_payment<'e>(executor: impl PgExecutor<'e>, payment: &CanonicalPayment, source_message_type: &str) -> Result<()> {
     info!("Inserting payment to DB: {}", payment.deltran_tx_id);
 
     sqlx::query!(
@@ -20,6 +34,7 @@ pub async fn insert_payment(pool: &PgPool, payment: &CanonicalPayment) -> Result
             uetr,
             end_to_end_id,
             instruction_id,
+            message_id,
             instructed_amount,
             settlement_amount,
             currency,
@@ -30,9 +45,13 @@ pub async fn insert_payment(pool: &PgPool, payment: &CanonicalPayment) -> Result
             status,
             created_at,
             updated_at,
-            raw_iso_message
+            raw_iso_message,
+            source_message_type,
+            debtor_iban,
+            debtor_account,
+            remittance_info
         ) VALUES (
-            $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, NOW(), NOW(), $14
+            $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, NOW(), NOW(), $15, $16, $17, $18, $19
         )
         "#,
         payment.deltran_tx_id,
@@ -40,6 +59,7 @@ pub async fn insert_payment(pool: &PgPool, payment: &CanonicalPayment) -> Result
         payment.uetr,
         payment.end_to_end_id,
         payment.instruction_id,
+        payment.message_id,
         payment.instructed_amount,
         payment.settlement_amount,
         payment.currency.to_string(),
@@ -49,13 +69,64 @@ pub async fn insert_payment(pool: &PgPool, payment: &CanonicalPayment) -> Result
         payment.creditor_agent.bic,
         payment.status.to_string(),
         None::<String>, // raw_iso_message - can add later
+        source_message_type,
+        payment.debtor_account.iban,
+        payment.debtor_account.other.as_ref().or(payment.debtor_account.bban.as_ref()),
+        Some(payment.remittance_info.as_str()).filter(|info| !info.is_empty()),
     )
-    .execute(pool)
+    .execute(executor)
     .await?;
 
     Ok(())
 }
 
+/// Insert a new payment with the NATS messages it triggers, unless it duplicates a payment of the
+/// same sending bank within the dedup horizon. Its UETR and MsgId + EndToEndId are locked for the
+/// transaction first, so of two concurrent submissions only one is inserted; the other gets the
+/// original (deltran_tx_id, matched_on_uetr).
+pub async fn insert_payment_unless_duplicate(
+    pool: &PgPool,
+    payment: &CanonicalPayment,
+    source_message_type: &str,
+    messages: &[OutboxMessage],
+    horizon_secs: f64,
+) -> Result<Option<(Uuid, bool)>> {
+    let mut tx = pool.begin().await?;
+
+    lock_dedup_keys(&mut tx, payment).await?;
+    if let Some(duplicate) = find_duplicate_payment(&mut *tx, payment, horizon_secs).await? {
+        return Ok(Some(duplicate));
+    }
+
+    insert_payment(&mut *tx, payment, source_message_type).await?;
+    for message in messages {
+        insert_outbox_message(&mut *tx, message).await?;
+    }
+
+    tx.commit().await?;
+    Ok(None)
+}
+
+/// Transaction-level advisory locks on the dedup keys of `payment` (UETR first, then MsgId +
+/// EndToEndId, always in this order), per sending bank
+async fn lock_dedup_keys(conn: &mut PgConnection, payment: &CanonicalPayment) -> Result<()> {
+    let bic = payment.debtor_agent.bic.as_deref().unwrap_or_default();
+    let mut keys = Vec::new();
+    if let Some(uetr) = payment.uetr {
+        keys.push(format!("payment-dedup:{}:uetr:{}", bic, uetr));
+    }
+    keys.push(format!("payment-dedup:{}:msg:{}:{}", bic, payment.message_id, payment.end_to_end_id));
+
+    for key in keys {
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtextextended($1, 0))")
+            .bind(key)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    Ok(())
+}
+
 /// Get payment by DelTran TX ID
 pub async fn get_payment_by_id(pool: &PgPool, tx_id: Uuid) -> Result<Option<CanonicalPayment>> {
     info!("Fetching payment from DB: {}", tx_id);
@@ -68,6 +139,7 @@ pub async fn get_payment_by_id(pool: &PgPool, tx_id: Uuid) -> Result<Option<Cano
             uetr,
             end_to_end_id,
             instruction_id,
+            message_id,
             instructed_amount,
             settlement_amount,
             currency,
@@ -98,10 +170,10 @@ pub async fn get_payment_by_id(pool: &PgPool, tx_id: Uuid) -> Result<Option<Cano
                 uetr: r.uetr,
                 end_to_end_id: r.end_to_end_id,
                 instruction_id: r.instruction_id,
-                message_id: format!("MSG-{}", r.deltran_tx_id),
+                message_id: r.message_id.unwrap_or_else(|| format!("MSG-{}", r.deltran_tx_id)),
                 instructed_amount: r.instructed_amount,
                 settlement_amount: r.settlement_amount,
-                currency: r.currency.parse().unwrap_or_default(),
+                currency: r.currency.parse()?,
                 exchange_rate: None,
                 debtor: Party {
                     name: r.debtor_name.unwrap_or_default(),
@@ -149,6 +221,7 @@ pub async fn get_payment_by_id(pool: &PgPool, tx_id: Uuid) -> Result<Option<Cano
                 charges: vec![],
                 remittance_info: String::new(),
                 remittance_structured: None,
+                purpose_code: None,
                 risk_score: None,
                 compliance_status: ComplianceStatus::Pending,
                 sanctions_checked: false,
@@ -165,7 +238,7 @@ pub async fn get_payment_by_id(pool: &PgPool, tx_id: Uuid) -> Result<Option<Cano
 }
 
 /// Update payment status
-pub async fn update_payment_status(pool: &PgPool, tx_id: Uuid, status: PaymentStatus) -> Result<()> {
+pub async fn update_payment_status<'e>(executor: impl PgExecutor<'e>, tx_id: Uuid, status: PaymentStatus) -> Result<()> {
     info!("Updating payment status: {} -> {:?}", tx_id, status);
 
     sqlx::query!(
@@ -177,12 +250,155 @@ pub async fn update_payment_status(pool: &PgPool, tx_id: Uuid, status: PaymentSt
         status.to_string(),
         tx_id
     )
-    .execute(pool)
+    .execute(executor)
     .await?;
 
     Ok(())
 }
 
+/// Update payment status together with the NATS messages the change triggers (single transaction)
+pub async fn update_payment_status_with_outbox(
+    pool: &PgPool,
+    tx_id: Uuid,
+    status: PaymentStatus,
+    messages: &[OutboxMessage],
+) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    update_payment_status(&mut *tx, tx_id, status).await?;
+    for message in messages {
+        insert_outbox_message(&mut *tx, message).await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Payments matching `filter`, newest first, after `cursor` (keyset over created_at, deltran_tx_id)
+pub async fn search_payments(
+    pool: &PgPool,
+    filter: &PaymentFilter,
+    cursor: Option<PaymentCursor>,
+    limit: i64,
+) -> Result<Vec<PaymentSummary>> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            deltran_tx_id,
+            uetr,
+            end_to_end_id,
+            instruction_id,
+            message_id,
+            status,
+            instructed_amount,
+            settlement_amount,
+            funded_amount,
+            currency,
+            corridor,
+            debtor_name,
+            creditor_name,
+            debtor_agent_bic,
+            creditor_agent_bic,
+            source_message_type,
+            created_at,
+            updated_at
+        FROM payments
+        WHERE (cardinality($1::VARCHAR[]) = 0 OR status = ANY($1))
+        AND ($2::VARCHAR IS NULL OR corridor = $2)
+        AND ($3::VARCHAR IS NULL OR currency = $3)
+        AND ($4::VARCHAR IS NULL OR UPPER(LEFT(debtor_agent_bic, 8)) = LEFT($4, 8))
+        AND ($5::VARCHAR IS NULL OR UPPER(LEFT(creditor_agent_bic, 8)) = LEFT($5, 8))
+        AND ($6::VARCHAR IS NULL OR UPPER(LEFT(debtor_agent_bic, 8)) = LEFT($6, 8)
+                                 OR UPPER(LEFT(creditor_agent_bic, 8)) = LEFT($6, 8))
+        AND ($7::DECIMAL IS NULL OR instructed_amount >= $7)
+        AND ($8::DECIMAL IS NULL OR instructed_amount <= $8)
+        AND ($9::TIMESTAMPTZ IS NULL OR created_at >= $9)
+        AND ($10::TIMESTAMPTZ IS NULL OR created_at < $10)
+        AND ($11::VARCHAR IS NULL OR uetr::TEXT LIKE $11 || '%')
+        AND ($12::TIMESTAMPTZ IS NULL OR (created_at, deltran_tx_id) < ($12, $13::UUID))
+        ORDER BY created_at DESC, deltran_tx_id DESC
+        LIMIT $14
+        "#,
+        &filter.statuses,
+        filter.corridor,
+        filter.currency,
+        filter.debtor_bic,
+        filter.creditor_bic,
+        filter.participant_bic,
+        filter.min_amount,
+        filter.max_amount,
+        filter.created_from,
+        filter.created_to,
+        filter.uetr_prefix,
+        cursor.map(|c| c.created_at),
+        cursor.map(|c| c.deltran_tx_id),
+        limit
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter()
+        .map(|r| PaymentSummary {
+            deltran_tx_id: r.deltran_tx_id,
+            uetr: r.uetr,
+            end_to_end_id: r.end_to_end_id,
+            instruction_id: r.instruction_id,
+            message_id: r.message_id,
+            status: status_from_db(&r.status).unwrap_or(PaymentStatus::Received),
+            instructed_amount: r.instructed_amount,
+            settlement_amount: r.settlement_amount,
+            funded_amount: r.funded_amount,
+            currency: r.currency,
+            corridor: r.corridor,
+            debtor_name: r.debtor_name,
+            creditor_name: r.creditor_name,
+            debtor_agent_bic: r.debtor_agent_bic,
+            creditor_agent_bic: r.creditor_agent_bic,
+            source_message_type: r.source_message_type,
+            created_at: r.created_at,
+            updated_at: r.updated_at,
+        })
+        .collect())
+}
+
+/// Number of payments matching `filter` per stored status
+pub async fn count_payments_by_status(pool: &PgPool, filter: &PaymentFilter) -> Result<Vec<(String, i64)>> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT status, COUNT(*) AS "count!"
+        FROM payments
+        WHERE (cardinality($1::VARCHAR[]) = 0 OR status = ANY($1))
+        AND ($2::VARCHAR IS NULL OR corridor = $2)
+        AND ($3::VARCHAR IS NULL OR currency = $3)
+        AND ($4::VARCHAR IS NULL OR UPPER(LEFT(debtor_agent_bic, 8)) = LEFT($4, 8))
+        AND ($5::VARCHAR IS NULL OR UPPER(LEFT(creditor_agent_bic, 8)) = LEFT($5, 8))
+        AND ($6::VARCHAR IS NULL OR UPPER(LEFT(debtor_agent_bic, 8)) = LEFT($6, 8)
+                                 OR UPPER(LEFT(creditor_agent_bic, 8)) = LEFT($6, 8))
+        AND ($7::DECIMAL IS NULL OR instructed_amount >= $7)
+        AND ($8::DECIMAL IS NULL OR instructed_amount <= $8)
+        AND ($9::TIMESTAMPTZ IS NULL OR created_at >= $9)
+        AND ($10::TIMESTAMPTZ IS NULL OR created_at < $10)
+        AND ($11::VARCHAR IS NULL OR uetr::TEXT LIKE $11 || '%')
+        GROUP BY status
+        "#,
+        &filter.statuses,
+        filter.corridor,
+        filter.currency,
+        filter.debtor_bic,
+        filter.creditor_bic,
+        filter.participant_bic,
+        filter.min_amount,
+        filter.max_amount,
+        filter.created_from,
+        filter.created_to,
+        filter.uetr_prefix
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(|r| (r.status, r.count)).collect())
+}
+
 /// Get payments by status
 pub async fn get_payments_by_status(pool: &PgPool, status: PaymentStatus, limit: i64) -> Result<Vec<Uuid>> {
     let rows = sqlx::query!(
@@ -220,8 +436,8 @@ pub async fn get_pending_funding(pool: &PgPool) -> Result<Vec<Uuid>> {
 }
 
 /// Update payment status by end_to_end_id (for camt.054 funding matching)
-pub async fn update_payment_status_by_e2e(
-    pool: &PgPool,
+pub async fn update_payment_status_by_e2e<'e>(
+    executor: impl PgExecutor<'e>,
     end_to_end_id: &str,
     status: PaymentStatus
 ) -> Result<()> {
@@ -236,12 +452,31 @@ pub async fn update_payment_status_by_e2e(
         status.to_string(),
         end_to_end_id
     )
-    .execute(pool)
+    .execute(executor)
     .await?;
 
     Ok(())
 }
 
+/// Update payment status by end_to_end_id together with the NATS messages the change triggers
+/// (single transaction)
+pub async fn update_payment_status_by_e2e_with_outbox(
+    pool: &PgPool,
+    end_to_end_id: &str,
+    status: PaymentStatus,
+    messages: &[OutboxMessage],
+) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    update_payment_status_by_e2e(&mut *tx, end_to_end_id, status).await?;
+    for message in messages {
+        insert_outbox_message(&mut *tx, message).await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
 /// Get payment by end_to_end_id (for camt.054 matching)
 pub async fn get_payment_by_e2e(pool: &PgPool, end_to_end_id: &str) -> Result<Option<CanonicalPayment>> {
     info!("Fetching payment from DB by E2E: {}", end_to_end_id);
@@ -254,6 +489,7 @@ pub async fn get_payment_by_e2e(pool: &PgPool, end_to_end_id: &str) -> Result<Op
             uetr,
             end_to_end_id,
             instruction_id,
+            message_id,
             instructed_amount,
             settlement_amount,
             currency,
@@ -283,10 +519,10 @@ pub async fn get_payment_by_e2e(pool: &PgPool, end_to_end_id: &str) -> Result<Op
                 uetr: r.uetr,
                 end_to_end_id: r.end_to_end_id,
                 instruction_id: r.instruction_id,
-                message_id: format!("MSG-{}", r.deltran_tx_id),
+                message_id: r.message_id.unwrap_or_else(|| format!("MSG-{}", r.deltran_tx_id)),
                 instructed_amount: r.instructed_amount,
                 settlement_amount: r.settlement_amount,
-                currency: r.currency.parse().unwrap_or_default(),
+                currency: r.currency.parse()?,
                 exchange_rate: None,
                 debtor: Party {
                     name: r.debtor_name.unwrap_or_default(),
@@ -334,6 +570,7 @@ pub async fn get_payment_by_e2e(pool: &PgPool, end_to_end_id: &str) -> Result<Op
                 charges: vec![],
                 remittance_info: String::new(),
                 remittance_structured: None,
+                purpose_code: None,
                 risk_score: None,
                 compliance_status: ComplianceStatus::Pending,
                 sanctions_checked: false,
@@ -349,10 +586,2156 @@ pub async fn get_payment_by_e2e(pool: &PgPool, end_to_end_id: &str) -> Result<Op
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Get the ISO message type that created a payment (pain.001 / pacs.008)
+pub async fn get_source_message_type(pool: &PgPool, tx_id: Uuid) -> Result<Option<String>> {
+    let row = sqlx::query!(
+        r#"
+        SELECT source_message_type
+        FROM payments
+        WHERE deltran_tx_id = $1
+        "#,
+        tx_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|r| r.source_message_type))
+}
+
+/// Persist a generated pain.002 / pacs.002 report
+pub async fn insert_status_report(pool: &PgPool, report: &StatusReportRecord) -> Result<()> {
+    info!("Storing {} {} for {}", report.message_type, report.message_id, report.recipient_bic);
+
+    sqlx::query!(
+        r#"
+        INSERT INTO status_reports (
+            report_id,
+            message_type,
+            message_id,
+            original_message_id,
+            recipient_bic,
+            group_status,
+            deltran_tx_ids,
+            xml_document,
+            signature,
+            created_at
+        ) VALUES (
+            $1, $2, $3, $4, $5, $6, $7, $8, $9, $10
+        )
+        "#,
+        report.report_id,
+        report.message_type,
+        report.message_id,
+        report.original_message_id,
+        report.recipient_bic,
+        report.group_status,
+        &report.deltran_tx_ids,
+        report.xml_document,
+        report.signature,
+        report.created_at,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Return all uncollected reports for a bank and mark them as collected
+pub async fn collect_status_reports(pool: &PgPool, recipient_bic: &str, limit: i64) -> Result<Vec<StatusReportRecord>> {
+    let mut reports = sqlx::query_as!(
+        StatusReportRecord,
+        r#"
+        UPDATE status_reports
+        SET collected_at = NOW()
+        WHERE report_id IN (
+            SELECT report_id
+            FROM status_reports
+            WHERE recipient_bic = $1 AND collected_at IS NULL
+            ORDER BY created_at ASC
+            LIMIT $2
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING
+            report_id,
+            message_type,
+            message_id,
+            original_message_id,
+            recipient_bic,
+            group_status,
+            deltran_tx_ids,
+            xml_document,
+            signature,
+            created_at,
+            collected_at
+        "#,
+        recipient_bic,
+        limit
+    )
+    .fetch_all(pool)
+    .await?;
+
+    // RETURNING does not preserve the subquery order
+    reports.sort_by_key(|r| r.created_at);
+    Ok(reports)
+}
+
+/// Get a single report addressed to a bank (collected or not)
+pub async fn get_status_report(pool: &PgPool, recipient_bic: &str, report_id: Uuid) -> Result<Option<StatusReportRecord>> {
+    let row = sqlx::query_as!(
+        StatusReportRecord,
+        r#"
+        SELECT
+            report_id,
+            message_type,
+            message_id,
+            original_message_id,
+            recipient_bic,
+            group_status,
+            deltran_tx_ids,
+            xml_document,
+            signature,
+            created_at,
+            collected_at
+        FROM status_reports
+        WHERE recipient_bic = $1 AND report_id = $2
+        "#,
+        recipient_bic,
+        report_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row)
+}
+
+/// Find an earlier payment of the same sending bank with the same UETR, or the same MsgId +
+/// EndToEndId, created within the dedup horizon. Returns (deltran_tx_id, matched_on_uetr).
+pub async fn find_duplicate_payment<'e>(
+    executor: impl PgExecutor<'e>,
+    payment: &CanonicalPayment,
+    horizon_secs: f64,
+) -> Result<Option<(Uuid, bool)>> {
+    let row = sqlx::query!(
+        r#"
+        SELECT
+            deltran_tx_id,
+            COALESCE(uetr = $1, FALSE) AS "matched_on_uetr!"
+        FROM payments
+        WHERE created_at > NOW() - make_interval(secs => $5)
+        AND debtor_agent_bic IS NOT DISTINCT FROM $4
+        AND (uetr = $1 OR (message_id = $2 AND end_to_end_id = $3))
+        ORDER BY 2 DESC, created_at ASC
+        LIMIT 1
+        "#,
+        payment.uetr,
+        payment.message_id,
+        payment.end_to_end_id,
+        payment.debtor_agent.bic,
+        horizon_secs
+    )
+    .fetch_optional(executor)
+    .await?;
+
+    Ok(row.map(|r| (r.deltran_tx_id, r.matched_on_uetr)))
+}
+
+/// Look up a submitter's Idempotency-Key within the dedup horizon: (message_id, deltran_tx_ids),
+/// deltran_tx_ids None while the first submission is still being processed
+pub async fn get_idempotency_key(
+    pool: &PgPool,
+    submitter_bic: &str,
+    idempotency_key: &str,
+    horizon_secs: f64,
+) -> Result<Option<(String, Option<Vec<Uuid>>)>> {
+    let row = sqlx::query!(
+        r#"
+        SELECT message_id, deltran_tx_ids, completed_at
+        FROM idempotency_keys
+        WHERE submitter_bic = $1
+        AND idempotency_key = $2
+        AND created_at > NOW() - make_interval(secs => $3)
+        "#,
+        submitter_bic,
+        idempotency_key,
+        horizon_secs
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|r| (r.message_id, r.completed_at.map(|_| r.deltran_tx_ids))))
+}
+
+/// Reserve a submitter's Idempotency-Key for a submission about to be processed. Returns false
+/// if the key is held: used within the dedup horizon, or reserved less than `lease_secs` ago by a
+/// submission that has not completed. Expired keys and abandoned reservations are taken over.
+pub async fn reserve_idempotency_key(
+    pool: &PgPool,
+    submitter_bic: &str,
+    idempotency_key: &str,
+    message_type: &str,
+    message_id: &str,
+    horizon_secs: f64,
+    lease_secs: f64,
+) -> Result<bool> {
+    let row = sqlx::query!(
+        r#"
+        INSERT INTO idempotency_keys (submitter_bic, idempotency_key, message_type, message_id, deltran_tx_ids, created_at)
+        VALUES ($1, $2, $3, $4, '{}', NOW())
+        ON CONFLICT (submitter_bic, idempotency_key) DO UPDATE
+        SET message_type = EXCLUDED.message_type,
+            message_id = EXCLUDED.message_id,
+            deltran_tx_ids = EXCLUDED.deltran_tx_ids,
+            created_at = EXCLUDED.created_at,
+            completed_at = NULL
+        WHERE idempotency_keys.created_at <= NOW() - make_interval(secs => $5)
+           OR (idempotency_keys.completed_at IS NULL
+               AND idempotency_keys.created_at <= NOW() - make_interval(secs => $6))
+        RETURNING idempotency_key
+        "#,
+        submitter_bic,
+        idempotency_key,
+        message_type,
+        message_id,
+        horizon_secs,
+        lease_secs
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.is_some())
+}
+
+/// Complete a reserved Idempotency-Key with the payments returned for it
+pub async fn complete_idempotency_key(
+    pool: &PgPool,
+    submitter_bic: &str,
+    idempotency_key: &str,
+    deltran_tx_ids: &[Uuid],
+) -> Result<()> {
+    sqlx::query!(
+        r#"
+        UPDATE idempotency_keys
+        SET deltran_tx_ids = $3, completed_at = NOW()
+        WHERE submitter_bic = $1 AND idempotency_key = $2 AND completed_at IS NULL
+        "#,
+        submitter_bic,
+        idempotency_key,
+        deltran_tx_ids
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Drop the reservation of a submission that failed, so a retry is processed
+pub async fn release_idempotency_key(pool: &PgPool, submitter_bic: &str, idempotency_key: &str) -> Result<()> {
+    sqlx::query!(
+        r#"
+        DELETE FROM idempotency_keys
+        WHERE submitter_bic = $1 AND idempotency_key = $2 AND completed_at IS NULL
+        "#,
+        submitter_bic,
+        idempotency_key
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Resolve a payment by UETR (for pacs.004 / camt.056 matching)
+pub async fn get_payment_id_by_uetr(pool: &PgPool, uetr: Uuid) -> Result<Option<Uuid>> {
+    let row = sqlx::query!(
+        r#"
+        SELECT deltran_tx_id
+        FROM payments
+        WHERE uetr = $1
+        ORDER BY created_at DESC
+        LIMIT 1
+        "#,
+        uetr
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|r| r.deltran_tx_id))
+}
+
+/// Record a pacs.004 return. Returns false if this return was already recorded.
+pub async fn insert_payment_return<'e>(executor: impl PgExecutor<'e>, tx_id: Uuid, ret: &PaymentReturn) -> Result<bool> {
+    info!("Recording return {} for payment {}", ret.return_id, tx_id);
+
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO payment_returns (
+            return_id,
+            return_message_id,
+            deltran_tx_id,
+            original_uetr,
+            original_end_to_end_id,
+            returned_amount,
+            currency,
+            settlement_date,
+            reason_code,
+            reason_description
+        ) VALUES (
+            $1, $2, $3, $4, $5, $6, $7, $8, $9, $10
+        )
+        ON CONFLICT (return_message_id, return_id) DO NOTHING
+        "#,
+        ret.return_id,
+        ret.return_message_id,
+        tx_id,
+        ret.original_uetr,
+        ret.original_end_to_end_id,
+        ret.returned_amount,
+        ret.currency,
+        ret.settlement_date,
+        ret.reason_code,
+        ret.reason_description,
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(result.rows_affected() == 1)
+}
+
+/// Record a pacs.004 return, mark the payment Returned and store the NATS messages the return
+/// triggers (single transaction). Returns false, changing nothing, if the return was already recorded.
+pub async fn record_payment_return_with_outbox(
+    pool: &PgPool,
+    tx_id: Uuid,
+    ret: &PaymentReturn,
+    messages: &[OutboxMessage],
+) -> Result<bool> {
+    let mut tx = pool.begin().await?;
+
+    if !insert_payment_return(&mut *tx, tx_id, ret).await? {
+        return Ok(false);
+    }
+    update_payment_status(&mut *tx, tx_id, PaymentStatus::Returned).await?;
+    for message in messages {
+        insert_outbox_message(&mut *tx, message).await?;
+    }
+
+    tx.commit().await?;
+    Ok(true)
+}
+
+/// Record how a camt.056 cancellation request was resolved
+pub async fn insert_cancellation_request(
+    pool: &PgPool,
+    tx_id: Option<Uuid>,
+    resolution: &CancellationResolution,
+    resolution_message_id: &str,
+) -> Result<()> {
+    let request = &resolution.request;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO cancellation_requests (
+            cancellation_id,
+            assignment_id,
+            assigner_bic,
+            deltran_tx_id,
+            original_uetr,
+            original_end_to_end_id,
+            original_message_id,
+            reason_code,
+            reason_description,
+            status,
+            rejection_reason,
+            resolution_message_id
+        ) VALUES (
+            $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12
+        )
+        ON CONFLICT (assignment_id, cancellation_id) DO NOTHING
+        "#,
+        request.cancellation_id,
+        request.assignment_id,
+        request.assigner_bic,
+        tx_id,
+        request.original_uetr,
+        request.original_end_to_end_id,
+        request.original_message_id,
+        request.reason_code,
+        request.reason_description,
+        resolution.status.code(),
+        resolution.rejection_code,
+        resolution_message_id,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
 
+/// Append an event to a payment's tracking timeline. Returns its stream sequence (None if already stored).
+pub async fn insert_payment_event(pool: &PgPool, event: &PaymentEvent) -> Result<Option<i64>> {
+    let sequence = sqlx::query_scalar!(
+        r#"
+        INSERT INTO payment_events (
+            event_id,
+            deltran_tx_id,
+            uetr,
+            source,
+            event_type,
+            event_status,
+            payment_status,
+            reason_code,
+            agent_bic,
+            event_data,
+            created_at
+        ) VALUES (
+            $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11
+        )
+        ON CONFLICT (event_id) DO NOTHING
+        RETURNING sequence
+        "#,
+        event.event_id,
+        event.deltran_tx_id,
+        event.uetr,
+        event.source.as_str(),
+        event.event_type,
+        event.iso_status,
+        event.status,
+        event.reason_code,
+        event.agent_bic,
+        event.details,
+        event.occurred_at,
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(sequence)
+}
+
+/// Tracking timeline of a payment, oldest event first
+pub async fn get_payment_events(pool: &PgPool, tx_id: Uuid) -> Result<Vec<PaymentEvent>> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            event_id,
+            deltran_tx_id,
+            uetr,
+            source,
+            event_type,
+            event_status,
+            payment_status,
+            reason_code,
+            agent_bic,
+            event_data,
+            created_at
+        FROM payment_events
+        WHERE deltran_tx_id = $1
+        ORDER BY created_at ASC
+        "#,
+        tx_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter()
+        .map(|r| PaymentEvent {
+            event_id: r.event_id,
+            deltran_tx_id: r.deltran_tx_id,
+            uetr: r.uetr,
+            source: EventSource::parse(&r.source).unwrap_or(EventSource::Gateway),
+            event_type: r.event_type,
+            status: r.payment_status,
+            iso_status: r.event_status,
+            reason_code: r.reason_code,
+            agent_bic: r.agent_bic,
+            details: r.event_data.unwrap_or_default(),
+            occurred_at: r.created_at,
+        })
+        .collect())
+}
+
+/// Tracking events after `after_sequence` visible to a /ws/payments connection, in stream order
+pub async fn list_stream_events(
+    pool: &PgPool,
+    filter: &StreamFilter,
+    after_sequence: i64,
+    limit: i64,
+) -> Result<Vec<StreamEvent>> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            e.sequence,
+            e.event_id,
+            e.deltran_tx_id,
+            e.uetr,
+            e.source,
+            e.event_type,
+            e.event_status,
+            e.payment_status,
+            e.reason_code,
+            e.agent_bic,
+            e.event_data,
+            e.created_at,
+            p.end_to_end_id,
+            p.debtor_agent_bic,
+            p.creditor_agent_bic
+        FROM payment_events e
+        JOIN payments p ON p.deltran_tx_id = e.deltran_tx_id
+        WHERE e.sequence > $1
+          AND ($2::TEXT IS NULL
+               OR UPPER(LEFT(p.debtor_agent_bic, 8)) = UPPER(LEFT($2, 8))
+               OR UPPER(LEFT(p.creditor_agent_bic, 8)) = UPPER(LEFT($2, 8)))
+          AND (CARDINALITY($3::UUID[]) = 0 OR e.uetr = ANY($3))
+        ORDER BY e.sequence ASC
+        LIMIT $4
+        "#,
+        after_sequence,
+        filter.participant_bic.as_deref(),
+        &filter.uetrs,
+        limit
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter()
+        .map(|r| StreamEvent {
+            sequence: r.sequence,
+            end_to_end_id: r.end_to_end_id,
+            debtor_agent_bic: r.debtor_agent_bic,
+            creditor_agent_bic: r.creditor_agent_bic,
+            event: PaymentEvent {
+                event_id: r.event_id,
+                deltran_tx_id: r.deltran_tx_id,
+                uetr: r.uetr,
+                source: EventSource::parse(&r.source).unwrap_or(EventSource::Gateway),
+                event_type: r.event_type,
+                status: r.payment_status,
+                iso_status: r.event_status,
+                reason_code: r.reason_code,
+                agent_bic: r.agent_bic,
+                details: r.event_data.unwrap_or_default(),
+                occurred_at: r.created_at,
+            },
+        })
+        .collect())
+}
+
+/// Sequence of the latest tracking event (0 before the first one)
+pub async fn latest_event_sequence(pool: &PgPool) -> Result<i64> {
+    let sequence = sqlx::query_scalar!(
+        r#"SELECT COALESCE(MAX(sequence), 0) AS "sequence!" FROM payment_events"#
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(sequence)
+}
+
+/// Resolve a payment by the obligation created for it (clearing events only carry obligation_id)
+pub async fn get_payment_id_by_obligation(pool: &PgPool, obligation_id: Uuid) -> Result<Option<Uuid>> {
+    let row = sqlx::query!(
+        r#"
+        SELECT deltran_tx_id
+        FROM payments
+        WHERE obligation_id = $1
+        LIMIT 1
+        "#,
+        obligation_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|r| r.deltran_tx_id))
+}
+
+/// Link a payment to its obligation (from deltran.events.obligation.created)
+pub async fn set_payment_obligation_id(pool: &PgPool, tx_id: Uuid, obligation_id: Uuid) -> Result<()> {
+    sqlx::query!(
+        r#"
+        UPDATE payments
+        SET obligation_id = $1, updated_at = NOW()
+        WHERE deltran_tx_id = $2 AND obligation_id IS DISTINCT FROM $1
+        "#,
+        obligation_id,
+        tx_id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Create or update a bulk batch record (progress counters, final status)
+pub async fn upsert_payment_batch(pool: &PgPool, batch: &PaymentBatch) -> Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO payment_batches (
+            batch_id,
+            file_name,
+            message_id,
+            debtor_agent_bic,
+            submitted_by_bic,
+            status,
+            group_status,
+            declared_transactions,
+            processed_transactions,
+            accepted_transactions,
+            rejected_transactions,
+            duplicate_transactions,
+            status_report_id,
+            error,
+            created_at,
+            updated_at,
+            completed_at
+        ) VALUES (
+            $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17
+        )
+        ON CONFLICT (batch_id) DO UPDATE SET
+            message_id = EXCLUDED.message_id,
+            debtor_agent_bic = EXCLUDED.debtor_agent_bic,
+            status = EXCLUDED.status,
+            group_status = EXCLUDED.group_status,
+            declared_transactions = EXCLUDED.declared_transactions,
+            processed_transactions = EXCLUDED.processed_transactions,
+            accepted_transactions = EXCLUDED.accepted_transactions,
+            rejected_transactions = EXCLUDED.rejected_transactions,
+            duplicate_transactions = EXCLUDED.duplicate_transactions,
+            status_report_id = EXCLUDED.status_report_id,
+            error = EXCLUDED.error,
+            updated_at = EXCLUDED.updated_at,
+            completed_at = EXCLUDED.completed_at
+        "#,
+        batch.batch_id,
+        batch.file_name,
+        batch.message_id,
+        batch.debtor_agent_bic,
+        batch.submitted_by_bic,
+        batch.status.as_str(),
+        batch.group_status,
+        batch.declared_transactions,
+        batch.processed_transactions,
+        batch.accepted_transactions,
+        batch.rejected_transactions,
+        batch.duplicate_transactions,
+        batch.status_report_id,
+        batch.error,
+        batch.created_at,
+        batch.updated_at,
+        batch.completed_at,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Get a bulk batch by id
+pub async fn get_payment_batch(pool: &PgPool, batch_id: Uuid) -> Result<Option<PaymentBatch>> {
+    let row = sqlx::query!(
+        r#"
+        SELECT
+            batch_id,
+            file_name,
+            message_id,
+            debtor_agent_bic,
+            submitted_by_bic,
+            status,
+            group_status,
+            declared_transactions,
+            processed_transactions,
+            accepted_transactions,
+            rejected_transactions,
+            duplicate_transactions,
+            status_report_id,
+            error,
+            created_at,
+            updated_at,
+            completed_at
+        FROM payment_batches
+        WHERE batch_id = $1
+        "#,
+        batch_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|r| PaymentBatch {
+        batch_id: r.batch_id,
+        file_name: r.file_name,
+        message_id: r.message_id,
+        debtor_agent_bic: r.debtor_agent_bic,
+        submitted_by_bic: r.submitted_by_bic,
+        status: BatchStatus::parse(&r.status).unwrap_or(BatchStatus::Failed),
+        group_status: r.group_status,
+        declared_transactions: r.declared_transactions,
+        processed_transactions: r.processed_transactions,
+        accepted_transactions: r.accepted_transactions,
+        rejected_transactions: r.rejected_transactions,
+        duplicate_transactions: r.duplicate_transactions,
+        status_report_id: r.status_report_id,
+        error: r.error,
+        created_at: r.created_at,
+        updated_at: r.updated_at,
+        completed_at: r.completed_at,
+    }))
+}
+
+/// Record the outcome of one transaction of a bulk batch
+pub async fn insert_batch_transaction(pool: &PgPool, transaction: &BatchTransaction) -> Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO payment_batch_transactions (
+            batch_id,
+            sequence,
+            payment_info_id,
+            end_to_end_id,
+            instruction_id,
+            uetr,
+            deltran_tx_id,
+            outcome,
+            reason_code,
+            reason_info
+        ) VALUES (
+            $1, $2, $3, $4, $5, $6, $7, $8, $9, $10
+        )
+        ON CONFLICT (batch_id, sequence) DO NOTHING
+        "#,
+        transaction.batch_id,
+        transaction.sequence,
+        transaction.payment_info_id,
+        transaction.end_to_end_id,
+        transaction.instruction_id,
+        transaction.uetr,
+        transaction.deltran_tx_id,
+        transaction.outcome.as_str(),
+        transaction.reason_code,
+        transaction.reason_info,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Transactions of a batch in file order, optionally only one outcome
+pub async fn get_batch_transactions(
+    pool: &PgPool,
+    batch_id: Uuid,
+    outcome: Option<TransactionOutcome>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<BatchTransaction>> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            batch_id,
+            sequence,
+            payment_info_id,
+            end_to_end_id,
+            instruction_id,
+            uetr,
+            deltran_tx_id,
+            outcome,
+            reason_code,
+            reason_info
+        FROM payment_batch_transactions
+        WHERE batch_id = $1 AND ($2::VARCHAR IS NULL OR outcome = $2)
+        ORDER BY sequence ASC
+        LIMIT $3 OFFSET $4
+        "#,
+        batch_id,
+        outcome.map(|o| o.as_str()),
+        limit,
+        offset
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter()
+        .map(|r| BatchTransaction {
+            batch_id: r.batch_id,
+            sequence: r.sequence,
+            payment_info_id: r.payment_info_id,
+            end_to_end_id: r.end_to_end_id,
+            instruction_id: r.instruction_id,
+            uetr: r.uetr,
+            deltran_tx_id: r.deltran_tx_id,
+            outcome: TransactionOutcome::parse(&r.outcome).unwrap_or(TransactionOutcome::Rejected),
+            reason_code: r.reason_code,
+            reason_info: r.reason_info,
+        })
+        .collect())
+}
+
+/// Store a message for the outbox relay
+pub async fn insert_outbox_message<'e>(executor: impl PgExecutor<'e>, message: &OutboxMessage) -> Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO outbox (
+            outbox_id, deltran_tx_id, subject, payload, attempts, next_attempt_at, created_at
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7)
+        "#,
+        message.outbox_id,
+        message.deltran_tx_id,
+        message.subject,
+        message.payload,
+        message.attempts,
+        message.next_attempt_at,
+        message.created_at,
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Lock undelivered messages that are due, oldest first.
+/// Rows locked by another relay instance are skipped; locks are held until `conn`'s transaction ends.
+pub async fn lock_due_outbox_messages(conn: &mut PgConnection, limit: i64) -> Result<Vec<OutboxMessage>> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT outbox_id, deltran_tx_id, subject, payload, attempts, last_error,
+               next_attempt_at, delivered_at, created_at
+        FROM outbox
+        WHERE delivered_at IS NULL AND next_attempt_at <= NOW()
+        ORDER BY created_at
+        LIMIT $1
+        FOR UPDATE SKIP LOCKED
+        "#,
+        limit
+    )
+    .fetch_all(conn)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| OutboxMessage {
+            outbox_id: r.outbox_id,
+            deltran_tx_id: r.deltran_tx_id,
+            subject: r.subject,
+            payload: r.payload,
+            attempts: r.attempts,
+            last_error: r.last_error,
+            next_attempt_at: r.next_attempt_at,
+            delivered_at: r.delivered_at,
+            created_at: r.created_at,
+        })
+        .collect())
+}
+
+pub async fn mark_outbox_delivered(conn: &mut PgConnection, outbox_id: Uuid, delivered_at: DateTime<Utc>) -> Result<()> {
+    sqlx::query!(
+        r#"
+        UPDATE outbox
+        SET delivered_at = $2, attempts = attempts + 1, last_error = NULL
+        WHERE outbox_id = $1
+        "#,
+        outbox_id,
+        delivered_at
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Record a failed publish and schedule the next attempt
+pub async fn mark_outbox_failed(conn: &mut PgConnection, outbox_id: Uuid, error: &str, next_attempt_at: DateTime<Utc>) -> Result<()> {
+    sqlx::query!(
+        r#"
+        UPDATE outbox
+        SET attempts = attempts + 1, last_error = $2, next_attempt_at = $3
+        WHERE outbox_id = $1
+        "#,
+        outbox_id,
+        error,
+        next_attempt_at
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Undelivered message count and creation time of the oldest one
+pub async fn outbox_backlog(pool: &PgPool) -> Result<(i64, Option<DateTime<Utc>>)> {
+    let row = sqlx::query!(
+        r#"
+        SELECT COUNT(*) AS "pending!", MIN(created_at) AS oldest
+        FROM outbox
+        WHERE delivered_at IS NULL
+        "#
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok((row.pending, row.oldest))
+}
+
+/// Participant registered under `bic` - an exact BIC11 entry wins over the institution's BIC8
+pub async fn get_participant_by_bic(pool: &PgPool, bic: &str) -> Result<Option<Participant>> {
+    let row = sqlx::query!(
+        r#"
+        SELECT participant_id, bic, name, cert_fingerprint, role, active
+        FROM participants
+        WHERE bic = UPPER($1) OR bic = UPPER(LEFT($1, 8))
+        ORDER BY LENGTH(bic) DESC
+        LIMIT 1
+        "#,
+        bic.trim()
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|r| Participant {
+        participant_id: r.participant_id,
+        bic: r.bic,
+        name: r.name,
+        cert_fingerprint: r.cert_fingerprint,
+        role: ParticipantRole::parse(&r.role).unwrap_or(ParticipantRole::Participant),
+        active: r.active,
+    }))
+}
+
+/// Registered account `account_id` (IBAN or Othr/Id, compared without spaces and case)
+pub async fn get_participant_account(pool: &PgPool, account_id: &str) -> Result<Option<ParticipantAccount>> {
+    let row = sqlx::query!(
+        r#"
+        SELECT account_id, participant_id, active
+        FROM participant_accounts
+        WHERE account_id = UPPER(REPLACE($1, ' ', ''))
+        "#,
+        account_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|r| ParticipantAccount {
+        account_id: r.account_id,
+        participant_id: r.participant_id,
+        active: r.active,
+    }))
+}
+
+/// Participant owning the client certificate with this SHA-256 fingerprint
+pub async fn get_participant_by_fingerprint(pool: &PgPool, fingerprint: &str) -> Result<Option<Participant>> {
+    let row = sqlx::query!(
+        r#"
+        SELECT participant_id, bic, name, cert_fingerprint, role, active
+        FROM participants
+        WHERE cert_fingerprint = $1
+        "#,
+        fingerprint
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|r| Participant {
+        participant_id: r.participant_id,
+        bic: r.bic,
+        name: r.name,
+        cert_fingerprint: r.cert_fingerprint,
+        role: ParticipantRole::parse(&r.role).unwrap_or(ParticipantRole::Participant),
+        active: r.active,
+    }))
+}
+
+/// Signing key `kid` of a participant, if it is currently valid (not revoked, within its window)
+pub async fn get_participant_key(pool: &PgPool, participant_id: Uuid, kid: &str) -> Result<Option<ParticipantKey>> {
+    let row = sqlx::query!(
+        r#"
+        SELECT participant_id, kid, algorithm, public_key_pem, valid_from, valid_until
+        FROM participant_keys
+        WHERE participant_id = $1 AND kid = $2
+          AND revoked_at IS NULL
+          AND valid_from <= NOW()
+          AND (valid_until IS NULL OR valid_until > NOW())
+        "#,
+        participant_id,
+        kid
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|r| ParticipantKey {
+        participant_id: r.participant_id,
+        kid: r.kid,
+        algorithm: r.algorithm,
+        public_key_pem: r.public_key_pem,
+        valid_from: r.valid_from,
+        valid_until: r.valid_until,
+    }))
+}
+
+/// Record a refused authentication / authorization attempt
+pub async fn insert_auth_audit(pool: &PgPool, entry: &AuthAuditEntry) -> Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO auth_audit_log (
+            audit_id,
+            participant_bic,
+            subject,
+            auth_method,
+            http_method,
+            path,
+            message_type,
+            reason,
+            claimed_bic,
+            detail,
+            occurred_at
+        ) VALUES (
+            $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11
+        )
+        "#,
+        entry.audit_id,
+        entry.participant_bic,
+        entry.subject,
+        entry.auth_method.map(|m| m.as_str()),
+        entry.http_method,
+        entry.path,
+        entry.message_type,
+        entry.reason.as_str(),
+        entry.claimed_bic,
+        entry.detail,
+        entry.occurred_at,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Store a camt.053 statement header with its balance check and match counters
+pub async fn insert_bank_statement<'e>(executor: impl PgExecutor<'e>, statement: &BankStatement) -> Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO bank_statements (
+            statement_id,
+            statement_reference,
+            message_id,
+            account_id,
+            account_iban,
+            account_servicer_bic,
+            received_from_bic,
+            currency,
+            from_date,
+            to_date,
+            opening_balance,
+            closing_balance,
+            total_credits,
+            total_debits,
+            balance_difference,
+            entry_count,
+            matched_entries,
+            break_count,
+            created_at
+        ) VALUES (
+            $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19
+        )
+        "#,
+        statement.statement_id,
+        statement.statement_reference,
+        statement.message_id,
+        statement.account_id,
+        statement.account_iban,
+        statement.account_servicer_bic,
+        statement.received_from_bic,
+        statement.currency,
+        statement.from_date,
+        statement.to_date,
+        statement.opening_balance,
+        statement.closing_balance,
+        statement.total_credits,
+        statement.total_debits,
+        statement.balance_difference,
+        statement.entry_count,
+        statement.matched_entries,
+        statement.break_count,
+        statement.created_at,
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Store one statement entry with its match result
+pub async fn insert_statement_entry<'e>(executor: impl PgExecutor<'e>, entry: &StatementEntryRecord) -> Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO bank_statement_entries (
+            statement_id,
+            sequence,
+            entry_reference,
+            amount,
+            currency,
+            credit_debit_indicator,
+            entry_status,
+            booking_date,
+            value_date,
+            end_to_end_id,
+            transaction_id,
+            uetr,
+            match_status,
+            deltran_tx_id,
+            break_reason
+        ) VALUES (
+            $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15
+        )
+        "#,
+        entry.statement_id,
+        entry.sequence,
+        entry.entry_reference,
+        entry.amount,
+        entry.currency,
+        entry.credit_debit_indicator,
+        entry.entry_status,
+        entry.booking_date,
+        entry.value_date,
+        entry.end_to_end_id,
+        entry.transaction_id,
+        entry.uetr,
+        entry.match_status.as_str(),
+        entry.deltran_tx_id,
+        entry.break_reason,
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Get a stored statement by our id
+pub async fn get_bank_statement(pool: &PgPool, statement_id: Uuid) -> Result<Option<BankStatement>> {
+    let row = sqlx::query!(
+        r#"
+        SELECT
+            statement_id,
+            statement_reference,
+            message_id,
+            account_id,
+            account_iban,
+            account_servicer_bic,
+            received_from_bic,
+            currency,
+            from_date,
+            to_date,
+            opening_balance,
+            closing_balance,
+            total_credits,
+            total_debits,
+            balance_difference,
+            entry_count,
+            matched_entries,
+            break_count,
+            created_at
+        FROM bank_statements
+        WHERE statement_id = $1
+        "#,
+        statement_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|r| BankStatement {
+        statement_id: r.statement_id,
+        statement_reference: r.statement_reference,
+        message_id: r.message_id,
+        account_id: r.account_id,
+        account_iban: r.account_iban,
+        account_servicer_bic: r.account_servicer_bic,
+        received_from_bic: r.received_from_bic,
+        currency: r.currency,
+        from_date: r.from_date,
+        to_date: r.to_date,
+        opening_balance: r.opening_balance,
+        closing_balance: r.closing_balance,
+        total_credits: r.total_credits,
+        total_debits: r.total_debits,
+        balance_difference: r.balance_difference,
+        entry_count: r.entry_count,
+        matched_entries: r.matched_entries,
+        break_count: r.break_count,
+        created_at: r.created_at,
+    }))
+}
+
+/// Id of an already received statement (camt.053 statements are re-sent on retries)
+pub async fn get_bank_statement_id(pool: &PgPool, account_id: &str, statement_reference: &str) -> Result<Option<Uuid>> {
+    let row = sqlx::query!(
+        r#"
+        SELECT statement_id
+        FROM bank_statements
+        WHERE account_id = $1 AND statement_reference = $2
+        "#,
+        account_id,
+        statement_reference
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|r| r.statement_id))
+}
+
+/// Entries of a statement that did not reconcile, in statement order
+pub async fn get_statement_breaks(pool: &PgPool, statement_id: Uuid) -> Result<Vec<StatementEntryRecord>> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            statement_id,
+            sequence,
+            entry_reference,
+            amount,
+            currency,
+            credit_debit_indicator,
+            entry_status,
+            booking_date,
+            value_date,
+            end_to_end_id,
+            transaction_id,
+            uetr,
+            match_status,
+            deltran_tx_id,
+            break_reason
+        FROM bank_statement_entries
+        WHERE statement_id = $1 AND match_status NOT IN ('matched', 'not_booked')
+        ORDER BY sequence ASC
+        "#,
+        statement_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter()
+        .map(|r| StatementEntryRecord {
+            statement_id: r.statement_id,
+            sequence: r.sequence,
+            entry_reference: r.entry_reference,
+            amount: r.amount,
+            currency: r.currency,
+            credit_debit_indicator: r.credit_debit_indicator,
+            entry_status: r.entry_status,
+            booking_date: r.booking_date,
+            value_date: r.value_date,
+            end_to_end_id: r.end_to_end_id,
+            transaction_id: r.transaction_id,
+            uetr: r.uetr,
+            match_status: EntryMatchStatus::parse(&r.match_status).unwrap_or(EntryMatchStatus::Unmatched),
+            deltran_tx_id: r.deltran_tx_id,
+            break_reason: r.break_reason,
+        })
+        .collect())
+}
+
+/// funding_events row as selected (state / method as stored strings)
+struct FundingEventRow {
+    event_id: Uuid,
+    account_id: String,
+    account_servicer_bic: Option<String>,
+    servicer_verified: bool,
+    received_from_bic: String,
+    entry_reference: Option<String>,
+    amount: Decimal,
+    currency: String,
+    entry_status: String,
+    booking_date: Option<String>,
+    value_date: Option<String>,
+    end_to_end_id: Option<String>,
+    instruction_id: Option<String>,
+    uetr: Option<Uuid>,
+    debtor_name: Option<String>,
+    debtor_account: Option<String>,
+    remittance_info: Option<String>,
+    state: String,
+    deltran_tx_id: Option<Uuid>,
+    match_method: Option<String>,
+    matched_by_bic: Option<String>,
+    match_note: Option<String>,
+    matched_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<FundingEventRow> for FundingEventRecord {
+    fn from(r: FundingEventRow) -> Self {
+        Self {
+            event_id: r.event_id,
+            account_id: r.account_id,
+            account_servicer_bic: r.account_servicer_bic,
+            servicer_verified: r.servicer_verified,
+            received_from_bic: r.received_from_bic,
+            entry_reference: r.entry_reference,
+            amount: r.amount,
+            currency: r.currency,
+            entry_status: r.entry_status,
+            booking_date: r.booking_date,
+            value_date: r.value_date,
+            end_to_end_id: r.end_to_end_id,
+            instruction_id: r.instruction_id,
+            uetr: r.uetr,
+            debtor_name: r.debtor_name,
+            debtor_account: r.debtor_account,
+            remittance_info: r.remittance_info,
+            state: FundingEventState::parse(&r.state).unwrap_or(FundingEventState::Unmatched),
+            deltran_tx_id: r.deltran_tx_id,
+            match_method: r.match_method.as_deref().and_then(MatchMethod::parse),
+            matched_by_bic: r.matched_by_bic,
+            match_note: r.match_note,
+            matched_at: r.matched_at,
+            created_at: r.created_at,
+            updated_at: r.updated_at,
+        }
+    }
+}
+
+/// Store a camt.054 credit entry. A re-sent entry (same account + NtryRef) updates the stored one:
+/// its booking status moves on, a matched entry stays matched.
+pub async fn upsert_funding_event(pool: &PgPool, event: &FundingEventRecord) -> Result<FundingEventRecord> {
+    let row = sqlx::query_as!(
+        FundingEventRow,
+        r#"
+        INSERT INTO funding_events (
+            event_id,
+            account_id,
+            account_servicer_bic,
+            servicer_verified,
+            received_from_bic,
+            entry_reference,
+            amount,
+            currency,
+            entry_status,
+            booking_date,
+            value_date,
+            end_to_end_id,
+            instruction_id,
+            uetr,
+            debtor_name,
+            debtor_account,
+            remittance_info,
+            state,
+            created_at,
+            updated_at
+        ) VALUES (
+            $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $19
+        )
+        ON CONFLICT (account_id, entry_reference) WHERE entry_reference IS NOT NULL DO UPDATE SET
+            entry_status = EXCLUDED.entry_status,
+            booking_date = EXCLUDED.booking_date,
+            value_date = EXCLUDED.value_date,
+            state = CASE WHEN funding_events.state = 'matched' THEN 'matched' ELSE EXCLUDED.state END,
+            updated_at = EXCLUDED.updated_at
+        RETURNING
+            event_id,
+            account_id,
+            account_servicer_bic,
+            servicer_verified,
+            received_from_bic,
+            entry_reference,
+            amount,
+            currency,
+            entry_status,
+            booking_date,
+            value_date,
+            end_to_end_id,
+            instruction_id,
+            uetr,
+            debtor_name,
+            debtor_account,
+            remittance_info,
+            state,
+            deltran_tx_id,
+            match_method,
+            matched_by_bic,
+            match_note,
+            matched_at,
+            created_at,
+            updated_at
+        "#,
+        event.event_id,
+        event.account_id,
+        event.account_servicer_bic,
+        event.servicer_verified,
+        event.received_from_bic,
+        event.entry_reference,
+        event.amount,
+        event.currency,
+        event.entry_status,
+        event.booking_date,
+        event.value_date,
+        event.end_to_end_id,
+        event.instruction_id,
+        event.uetr,
+        event.debtor_name,
+        event.debtor_account,
+        event.remittance_info,
+        event.state.as_str(),
+        event.created_at,
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(row.into())
+}
+
+/// Get a stored funding event
+pub async fn get_funding_event(pool: &PgPool, event_id: Uuid) -> Result<Option<FundingEventRecord>> {
+    let row = sqlx::query_as!(
+        FundingEventRow,
+        r#"
+        SELECT
+            event_id,
+            account_id,
+            account_servicer_bic,
+            servicer_verified,
+            received_from_bic,
+            entry_reference,
+            amount,
+            currency,
+            entry_status,
+            booking_date,
+            value_date,
+            end_to_end_id,
+            instruction_id,
+            uetr,
+            debtor_name,
+            debtor_account,
+            remittance_info,
+            state,
+            deltran_tx_id,
+            match_method,
+            matched_by_bic,
+            match_note,
+            matched_at,
+            created_at,
+            updated_at
+        FROM funding_events
+        WHERE event_id = $1
+        "#,
+        event_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(Into::into))
+}
+
+/// Funding events, newest first - optionally only one sender and / or one state
+pub async fn list_funding_events(
+    pool: &PgPool,
+    received_from_bic: Option<&str>,
+    state: Option<FundingEventState>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<FundingEventRecord>> {
+    let rows = sqlx::query_as!(
+        FundingEventRow,
+        r#"
+        SELECT
+            event_id,
+            account_id,
+            account_servicer_bic,
+            servicer_verified,
+            received_from_bic,
+            entry_reference,
+            amount,
+            currency,
+            entry_status,
+            booking_date,
+            value_date,
+            end_to_end_id,
+            instruction_id,
+            uetr,
+            debtor_name,
+            debtor_account,
+            remittance_info,
+            state,
+            deltran_tx_id,
+            match_method,
+            matched_by_bic,
+            match_note,
+            matched_at,
+            created_at,
+            updated_at
+        FROM funding_events
+        WHERE ($1::VARCHAR IS NULL OR received_from_bic = $1)
+        AND ($2::VARCHAR IS NULL OR state = $2)
+        ORDER BY created_at DESC
+        LIMIT $3 OFFSET $4
+        "#,
+        received_from_bic,
+        state.map(|s| s.as_str()),
+        limit,
+        offset
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(Into::into).collect())
+}
+
+/// Queued credits that may fund a newly arrived payment: same UETR / EndToEndId, or the same
+/// amount (fuzzy candidates) - always in the payment's currency
+pub async fn find_open_funding_events(
+    pool: &PgPool,
+    uetr: Option<Uuid>,
+    end_to_end_id: &str,
+    amount: Decimal,
+    currency: &str,
+    limit: i64,
+) -> Result<Vec<FundingEventRecord>> {
+    let rows = sqlx::query_as!(
+        FundingEventRow,
+        r#"
+        SELECT
+            event_id,
+            account_id,
+            account_servicer_bic,
+            servicer_verified,
+            received_from_bic,
+            entry_reference,
+            amount,
+            currency,
+            entry_status,
+            booking_date,
+            value_date,
+            end_to_end_id,
+            instruction_id,
+            uetr,
+            debtor_name,
+            debtor_account,
+            remittance_info,
+            state,
+            deltran_tx_id,
+            match_method,
+            matched_by_bic,
+            match_note,
+            matched_at,
+            created_at,
+            updated_at
+        FROM funding_events
+        WHERE state = 'unmatched'
+        AND (uetr = $1 OR end_to_end_id = $2 OR amount = $3)
+        AND currency = $4
+        ORDER BY created_at ASC
+        LIMIT $5
+        "#,
+        uetr,
+        end_to_end_id,
+        amount,
+        currency,
+        limit
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(Into::into).collect())
+}
+
+/// Queued credits least recently tried by the background matcher; marks them as attempted
+pub async fn next_funding_match_attempts(pool: &PgPool, limit: i64) -> Result<Vec<FundingEventRecord>> {
+    let rows = sqlx::query_as!(
+        FundingEventRow,
+        r#"
+        UPDATE funding_events
+        SET last_match_attempt_at = NOW()
+        WHERE event_id IN (
+            SELECT event_id
+            FROM funding_events
+            WHERE state = 'unmatched'
+            ORDER BY last_match_attempt_at ASC NULLS FIRST
+            LIMIT $1
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING
+            event_id,
+            account_id,
+            account_servicer_bic,
+            servicer_verified,
+            received_from_bic,
+            entry_reference,
+            amount,
+            currency,
+            entry_status,
+            booking_date,
+            value_date,
+            end_to_end_id,
+            instruction_id,
+            uetr,
+            debtor_name,
+            debtor_account,
+            remittance_info,
+            state,
+            deltran_tx_id,
+            match_method,
+            matched_by_bic,
+            match_note,
+            matched_at,
+            created_at,
+            updated_at
+        "#,
+        limit
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(Into::into).collect())
+}
+
+/// Number of booked credits waiting for their payment
+pub async fn count_unmatched_funding_events(pool: &PgPool) -> Result<i64> {
+    let row = sqlx::query!(
+        r#"
+        SELECT COUNT(*) AS "count!"
+        FROM funding_events
+        WHERE state = 'unmatched'
+        "#
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(row.count)
+}
+
+/// Mark a queued credit as funding `tx_id`. False when it was matched (or is not booked) meanwhile.
+pub async fn claim_funding_event<'e>(
+    executor: impl PgExecutor<'e>,
+    event_id: Uuid,
+    tx_id: Uuid,
+    method: MatchMethod,
+    matched_by_bic: Option<&str>,
+    note: Option<&str>,
+) -> Result<bool> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE funding_events
+        SET state = 'matched',
+            deltran_tx_id = $2,
+            match_method = $3,
+            matched_by_bic = $4,
+            match_note = $5,
+            matched_at = NOW(),
+            updated_at = NOW()
+        WHERE event_id = $1 AND state = 'unmatched'
+        "#,
+        event_id,
+        tx_id,
+        method.as_str(),
+        matched_by_bic,
+        note
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(result.rows_affected() == 1)
+}
+
+/// Status and funding amounts of a payment, locked until the transaction ends so concurrent credits add up
+pub async fn lock_payment_funding(conn: &mut PgConnection, tx_id: Uuid) -> Result<Option<PaymentFunding>> {
+    let row = sqlx::query!(
+        r#"
+        SELECT status, instructed_amount, funded_amount, overfunded_amount
+        FROM payments
+        WHERE deltran_tx_id = $1
+        FOR UPDATE
+        "#,
+        tx_id
+    )
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(row.map(|r| PaymentFunding {
+        status: status_from_db(&r.status).unwrap_or(PaymentStatus::Received),
+        instructed_amount: r.instructed_amount,
+        funded_amount: r.funded_amount,
+        overfunded_amount: r.overfunded_amount,
+    }))
+}
+
+/// Store the funding of a payment after a credit (status unchanged when None). A growing overfunded
+/// amount flags a refund; `settlement_amount` (the minted amount) is only set once fully funded.
+pub async fn update_payment_funding(
+    conn: &mut PgConnection,
+    tx_id: Uuid,
+    status: Option<PaymentStatus>,
+    funded_amount: Decimal,
+    overfunded_amount: Decimal,
+    settlement_amount: Option<Decimal>,
+) -> Result<()> {
+    sqlx::query!(
+        r#"
+        UPDATE payments
+        SET status = COALESCE($2, status),
+            funded_amount = $3,
+            refund_status = CASE WHEN $4 > overfunded_amount THEN 'required' ELSE refund_status END,
+            overfunded_amount = $4,
+            settlement_amount = COALESCE($5, settlement_amount),
+            funded_at = CASE WHEN $5::DECIMAL IS NOT NULL THEN NOW() ELSE funded_at END,
+            updated_at = NOW()
+        WHERE deltran_tx_id = $1
+        "#,
+        tx_id,
+        status.map(|status| format!("{:?}", status)),
+        funded_amount,
+        overfunded_amount,
+        settlement_amount
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Over-funded payments whose excess still has to be refunded, oldest first -
+/// optionally only one debtor agent institution's (BIC8)
+pub async fn list_funding_refunds(
+    pool: &PgPool,
+    debtor_agent_bic: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<FundingRefund>> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            deltran_tx_id,
+            end_to_end_id,
+            debtor_agent_bic,
+            currency,
+            instructed_amount,
+            funded_amount,
+            overfunded_amount,
+            funded_at
+        FROM payments
+        WHERE refund_status = 'required'
+        AND ($1::VARCHAR IS NULL OR UPPER(LEFT(debtor_agent_bic, 8)) = UPPER(LEFT($1, 8)))
+        ORDER BY funded_at ASC NULLS FIRST
+        LIMIT $2 OFFSET $3
+        "#,
+        debtor_agent_bic,
+        limit,
+        offset
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter()
+        .map(|r| FundingRefund {
+            deltran_tx_id: r.deltran_tx_id,
+            end_to_end_id: r.end_to_end_id,
+            debtor_agent_bic: r.debtor_agent_bic,
+            currency: r.currency,
+            instructed_amount: r.instructed_amount,
+            funded_amount: r.funded_amount,
+            refund_amount: r.overfunded_amount,
+            funded_at: r.funded_at,
+        })
+        .collect())
+}
+
+/// Payments still waiting for funding with exactly this amount and currency, and no partial credit yet
+pub async fn find_funding_candidates(
+    pool: &PgPool,
+    amount: Decimal,
+    currency: &str,
+    limit: i64,
+) -> Result<Vec<FundingCandidate>> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            deltran_tx_id,
+            end_to_end_id,
+            instruction_id,
+            debtor_name,
+            debtor_iban,
+            debtor_account,
+            remittance_info,
+            debtor_agent_bic
+        FROM payments
+        WHERE status IN ('Received', 'Validated', 'Accepted', 'Pending', 'PendingFunding')
+        AND currency = $1 AND instructed_amount = $2 AND funded_amount = 0
+        ORDER BY created_at ASC
+        LIMIT $3
+        "#,
+        currency,
+        amount,
+        limit
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter()
+        .map(|r| FundingCandidate {
+            deltran_tx_id: r.deltran_tx_id,
+            end_to_end_id: r.end_to_end_id,
+            instruction_id: r.instruction_id,
+            debtor_name: r.debtor_name,
+            debtor_iban: r.debtor_iban,
+            debtor_account: r.debtor_account,
+            remittance_info: r.remittance_info,
+            debtor_agent_bic: r.debtor_agent_bic,
+        })
+        .collect())
+}
+
+/// webhook_deliveries row (query_as! target)
+struct WebhookDeliveryRow {
+    delivery_id: Uuid,
+    endpoint_id: Uuid,
+    participant_bic: String,
+    url: String,
+    event_type: String,
+    deltran_tx_id: Option<Uuid>,
+    payload: serde_json::Value,
+    state: String,
+    attempts: i32,
+    next_attempt_at: DateTime<Utc>,
+    last_status_code: Option<i32>,
+    last_error: Option<String>,
+    replay_of: Option<Uuid>,
+    created_at: DateTime<Utc>,
+    delivered_at: Option<DateTime<Utc>>,
+}
+
+impl From<WebhookDeliveryRow> for WebhookDelivery {
+    fn from(r: WebhookDeliveryRow) -> Self {
+        Self {
+            delivery_id: r.delivery_id,
+            endpoint_id: r.endpoint_id,
+            participant_bic: r.participant_bic,
+            url: r.url,
+            event_type: r.event_type,
+            deltran_tx_id: r.deltran_tx_id,
+            payload: r.payload,
+            state: DeliveryState::parse(&r.state).unwrap_or(DeliveryState::Pending),
+            attempts: r.attempts,
+            next_attempt_at: r.next_attempt_at,
+            last_status_code: r.last_status_code,
+            last_error: r.last_error,
+            replay_of: r.replay_of,
+            created_at: r.created_at,
+            delivered_at: r.delivered_at,
+        }
+    }
+}
+
+/// Register a webhook endpoint
+pub async fn insert_webhook_endpoint(pool: &PgPool, endpoint: &WebhookEndpoint) -> Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO webhook_endpoints (
+            endpoint_id,
+            participant_bic,
+            url,
+            event_types,
+            active,
+            created_at,
+            updated_at
+        ) VALUES (
+            $1, $2, $3, $4, $5, $6, $7
+        )
+        "#,
+        endpoint.endpoint_id,
+        endpoint.participant_bic,
+        endpoint.url,
+        &endpoint.event_types,
+        endpoint.active,
+        endpoint.created_at,
+        endpoint.updated_at,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Get a webhook endpoint (active or not)
+pub async fn get_webhook_endpoint(pool: &PgPool, endpoint_id: Uuid) -> Result<Option<WebhookEndpoint>> {
+    let row = sqlx::query_as!(
+        WebhookEndpoint,
+        r#"
+        SELECT endpoint_id, participant_bic, url, event_types, active, created_at, updated_at
+        FROM webhook_endpoints
+        WHERE endpoint_id = $1
+        "#,
+        endpoint_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row)
+}
+
+/// Active webhook endpoints - optionally only one institution's (BIC8)
+pub async fn list_webhook_endpoints(pool: &PgPool, participant_bic: Option<&str>) -> Result<Vec<WebhookEndpoint>> {
+    let rows = sqlx::query_as!(
+        WebhookEndpoint,
+        r#"
+        SELECT endpoint_id, participant_bic, url, event_types, active, created_at, updated_at
+        FROM webhook_endpoints
+        WHERE active AND ($1::VARCHAR IS NULL OR LEFT(participant_bic, 8) = UPPER(LEFT($1, 8)))
+        ORDER BY created_at ASC
+        "#,
+        participant_bic
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+/// Active webhook endpoints of the institutions (BIC8) of `bics`
+pub async fn find_webhook_endpoints(pool: &PgPool, bics: &[String]) -> Result<Vec<WebhookEndpoint>> {
+    let institutions: Vec<String> = bics.iter()
+        .filter(|bic| bic.len() >= 8)
+        .map(|bic| bic[..8].to_ascii_uppercase())
+        .collect();
+
+    let rows = sqlx::query_as!(
+        WebhookEndpoint,
+        r#"
+        SELECT endpoint_id, participant_bic, url, event_types, active, created_at, updated_at
+        FROM webhook_endpoints
+        WHERE active AND LEFT(participant_bic, 8) = ANY($1)
+        "#,
+        &institutions
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+/// Stop delivering to an endpoint - its delivery history is kept, pending deliveries are dead-lettered
+pub async fn deactivate_webhook_endpoint(pool: &PgPool, endpoint_id: Uuid) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query!(
+        r#"
+        UPDATE webhook_endpoints
+        SET active = FALSE, updated_at = NOW()
+        WHERE endpoint_id = $1
+        "#,
+        endpoint_id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        r#"
+        UPDATE webhook_deliveries
+        SET state = 'dead', last_error = 'endpoint removed'
+        WHERE endpoint_id = $1 AND state = 'pending'
+        "#,
+        endpoint_id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Store a webhook delivery (new notification or replay)
+pub async fn insert_webhook_delivery(pool: &PgPool, delivery: &WebhookDelivery) -> Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO webhook_deliveries (
+            delivery_id,
+            endpoint_id,
+            participant_bic,
+            url,
+            event_type,
+            deltran_tx_id,
+            payload,
+            state,
+            attempts,
+            next_attempt_at,
+            replay_of,
+            created_at
+        ) VALUES (
+            $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12
+        )
+        "#,
+        delivery.delivery_id,
+        delivery.endpoint_id,
+        delivery.participant_bic,
+        delivery.url,
+        delivery.event_type,
+        delivery.deltran_tx_id,
+        delivery.payload,
+        delivery.state.as_str(),
+        delivery.attempts,
+        delivery.next_attempt_at,
+        delivery.replay_of,
+        delivery.created_at,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Due deliveries, oldest first. Leased for `lease_secs` so other dispatchers skip them while they
+/// are attempted; a crashed attempt is retried once the lease expires.
+pub async fn lease_due_webhook_deliveries(pool: &PgPool, limit: i64, lease_secs: i64) -> Result<Vec<WebhookDelivery>> {
+    let rows = sqlx::query_as!(
+        WebhookDeliveryRow,
+        r#"
+        UPDATE webhook_deliveries
+        SET next_attempt_at = NOW() + make_interval(secs => $2::BIGINT::DOUBLE PRECISION)
+        WHERE delivery_id IN (
+            SELECT delivery_id
+            FROM webhook_deliveries
+            WHERE state = 'pending' AND next_attempt_at <= NOW()
+            ORDER BY next_attempt_at ASC
+            LIMIT $1
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING
+            delivery_id,
+            endpoint_id,
+            participant_bic,
+            url,
+            event_type,
+            deltran_tx_id,
+            payload,
+            state,
+            attempts,
+            next_attempt_at,
+            last_status_code,
+            last_error,
+            replay_of,
+            created_at,
+            delivered_at
+        "#,
+        limit,
+        lease_secs
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(Into::into).collect())
+}
+
+/// Record an attempt: delivered, pending until `next_attempt_at`, or dead
+pub async fn record_webhook_attempt(pool: &PgPool, delivery: &WebhookDelivery) -> Result<()> {
+    sqlx::query!(
+        r#"
+        UPDATE webhook_deliveries
+        SET state = $2,
+            attempts = $3,
+            next_attempt_at = $4,
+            last_status_code = $5,
+            last_error = $6,
+            delivered_at = $7
+        WHERE delivery_id = $1
+        "#,
+        delivery.delivery_id,
+        delivery.state.as_str(),
+        delivery.attempts,
+        delivery.next_attempt_at,
+        delivery.last_status_code,
+        delivery.last_error,
+        delivery.delivered_at,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Get a webhook delivery
+pub async fn get_webhook_delivery(pool: &PgPool, delivery_id: Uuid) -> Result<Option<WebhookDelivery>> {
+    let row = sqlx::query_as!(
+        WebhookDeliveryRow,
+        r#"
+        SELECT
+            delivery_id,
+            endpoint_id,
+            participant_bic,
+            url,
+            event_type,
+            deltran_tx_id,
+            payload,
+            state,
+            attempts,
+            next_attempt_at,
+            last_status_code,
+            last_error,
+            replay_of,
+            created_at,
+            delivered_at
+        FROM webhook_deliveries
+        WHERE delivery_id = $1
+        "#,
+        delivery_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(Into::into))
+}
+
+/// Delivery history, newest first - optionally only one institution's (BIC8) and / or one state
+pub async fn list_webhook_deliveries(
+    pool: &PgPool,
+    participant_bic: Option<&str>,
+    state: Option<DeliveryState>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<WebhookDelivery>> {
+    let rows = sqlx::query_as!(
+        WebhookDeliveryRow,
+        r#"
+        SELECT
+            delivery_id,
+            endpoint_id,
+            participant_bic,
+            url,
+            event_type,
+            deltran_tx_id,
+            payload,
+            state,
+            attempts,
+            next_attempt_at,
+            last_status_code,
+            last_error,
+            replay_of,
+            created_at,
+            delivered_at
+        FROM webhook_deliveries
+        WHERE ($1::VARCHAR IS NULL OR LEFT(participant_bic, 8) = UPPER(LEFT($1, 8)))
+        AND ($2::VARCHAR IS NULL OR state = $2)
+        ORDER BY created_at DESC
+        LIMIT $3 OFFSET $4
+        "#,
+        participant_bic,
+        state.map(|s| s.as_str()),
+        limit,
+        offset
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(Into::into).collect())
+}
+
+/// Deliveries waiting for an attempt and dead-lettered deliveries
+pub async fn webhook_backlog(pool: &PgPool) -> Result<(i64, i64)> {
+    let row = sqlx::query!(
+        r#"
+        SELECT
+            COUNT(*) FILTER (WHERE state = 'pending') AS "pending!",
+            COUNT(*) FILTER (WHERE state = 'dead') AS "dead!"
+        FROM webhook_deliveries
+        "#
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok((row.pending, row.dead))
+}
+
+#[cfg(test)]
+mod tests {
     #[sqlx::test]
     async fn test_insert_payment() {
         // TODO: Add DB tests
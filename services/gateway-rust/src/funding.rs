@@ -0,0 +1,392 @@
+// Funding Queue - camt.054 credits that could not fund a payment on arrival
+// Every credit entry is stored in funding_events. Pending entries wait until a re-sent camt.054
+// books them; booked credits without a payment wait for their pain.001 / pacs.008. Matching is by
+// reference (UETR, then EndToEndId) or fuzzy (same amount and currency, plus debtor account /
+// remittance info / debtor name). New payments are matched on arrival and a background matcher
+// retries the queue; operators match the rest via POST /funding/events/:event_id/match.
+// Credits add up per payment: tokens are minted once they cover the instructed amount (within
+// FUNDING_TOLERANCE_BPS), for the funded amount at most, through the outbox in the same
+// transaction as the Funded status. Excess credits are flagged for refund.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use tracing::{info, warn, error};
+use uuid::Uuid;
+
+use crate::db;
+use crate::iso20022::camt054::FundingEvent;
+use crate::metrics::METRICS;
+use crate::models::canonical::{CanonicalPayment, PaymentStatus};
+use crate::models::funding::{best_match, FundingCandidate, FundingEventRecord, FundingEventState, FundingProgress, MatchMethod};
+use crate::nats_router::NatsRouter;
+use crate::status_reports::StatusReporter;
+use crate::tracking::PaymentTracker;
+
+/// Default pause between background matching rounds (FUNDING_MATCH_INTERVAL_SECS)
+pub const DEFAULT_FUNDING_MATCH_INTERVAL_SECS: u64 = 30;
+
+/// Queued credits retried per background round
+const MATCH_BATCH_SIZE: i64 = 100;
+
+/// Fuzzy candidates considered per credit / payment
+const CANDIDATE_LIMIT: i64 = 50;
+
+/// Default shortfall accepted as fully funded, in basis points of the instructed amount (FUNDING_TOLERANCE_BPS)
+pub const DEFAULT_FUNDING_TOLERANCE_BPS: u32 = 0;
+
+/// Payment states in which a credit may still fund the payment
+pub fn awaits_funding(status: &PaymentStatus) -> bool {
+    matches!(
+        status,
+        PaymentStatus::Received | PaymentStatus::Validated | PaymentStatus::Accepted
+            | PaymentStatus::Pending | PaymentStatus::PendingFunding
+    )
+}
+
+/// Payment states in which a matched credit is taken: it funds a payment still awaiting funding,
+/// and is excess to refund for a payment funded already
+pub fn accepts_credit(status: &PaymentStatus) -> bool {
+    awaits_funding(status)
+        || matches!(
+            status,
+            PaymentStatus::Funded | PaymentStatus::ReadyForClearing | PaymentStatus::Clearing | PaymentStatus::Netted
+                | PaymentStatus::ReadyForSettlement | PaymentStatus::Settling | PaymentStatus::Executed
+                | PaymentStatus::Reconciled | PaymentStatus::Completed
+        )
+}
+
+pub struct FundingQueue {
+    db: PgPool,
+    reporter: Arc<StatusReporter>,
+    tracker: Arc<PaymentTracker>,
+    match_interval: Duration,
+    tolerance_bps: u32,
+}
+
+impl FundingQueue {
+    pub fn new(
+        db: PgPool,
+        reporter: Arc<StatusReporter>,
+        tracker: Arc<PaymentTracker>,
+        match_interval: Duration,
+        tolerance_bps: u32,
+    ) -> Self {
+        Self { db, reporter, tracker, match_interval, tolerance_bps }
+    }
+
+    pub fn match_interval_from_env_value(value: &str) -> Duration {
+        let secs = value.trim().parse::<u64>().ok().filter(|secs| *secs > 0).unwrap_or_else(|| {
+            warn!("Invalid FUNDING_MATCH_INTERVAL_SECS '{}', using {}s", value, DEFAULT_FUNDING_MATCH_INTERVAL_SECS);
+            DEFAULT_FUNDING_MATCH_INTERVAL_SECS
+        });
+        Duration::from_secs(secs)
+    }
+
+    pub fn tolerance_bps_from_env_value(value: &str) -> u32 {
+        value.trim().parse::<u32>().ok().filter(|bps| *bps <= 10_000).unwrap_or_else(|| {
+            warn!("Invalid FUNDING_TOLERANCE_BPS '{}', using {}", value, DEFAULT_FUNDING_TOLERANCE_BPS);
+            DEFAULT_FUNDING_TOLERANCE_BPS
+        })
+    }
+
+    /// Store a credit entry sent by `sender_bic` (or move a re-sent one on). `servicer_verified`:
+    /// the sender services the credited account according to participant_accounts.
+    pub async fn record(&self, sender_bic: &str, servicer_verified: bool, event: &FundingEvent) -> Result<FundingEventRecord> {
+        let booked = event.status == "BOOK";
+        let record = FundingEventRecord {
+            event_id: Uuid::new_v4(),
+            account_id: event.account.clone(),
+            account_servicer_bic: event.account_servicer_bic.clone(),
+            servicer_verified,
+            received_from_bic: sender_bic.to_string(),
+            entry_reference: event.entry_reference.clone(),
+            amount: event.amount,
+            currency: event.currency.code().to_string(),
+            entry_status: event.status.clone(),
+            booking_date: event.booking_date.clone(),
+            value_date: event.value_date.clone(),
+            end_to_end_id: event.end_to_end_id.clone(),
+            instruction_id: event.instruction_id.clone(),
+            uetr: event.uetr,
+            debtor_name: event.debtor_name.clone(),
+            debtor_account: event.debtor_account.clone(),
+            remittance_info: event.remittance_info.clone(),
+            state: if booked { FundingEventState::Unmatched } else { FundingEventState::Pending },
+            deltran_tx_id: None,
+            match_method: None,
+            matched_by_bic: None,
+            match_note: None,
+            matched_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        db::upsert_funding_event(&self.db, &record).await
+    }
+
+    /// Payment the credit's references point to: UETR first, EndToEndId as fallback. A payment in
+    /// another currency is no match - the credit is left to fuzzy matching and the operators.
+    pub async fn find_by_reference(&self, event: &FundingEventRecord) -> Result<Option<CanonicalPayment>> {
+        let mut payment = None;
+        if let Some(uetr) = event.uetr {
+            if let Some(tx_id) = db::get_payment_id_by_uetr(&self.db, uetr).await? {
+                payment = db::get_payment_by_id(&self.db, tx_id).await?;
+            }
+        }
+        if payment.is_none() {
+            if let Some(end_to_end_id) = event.end_to_end_id.as_deref() {
+                payment = db::get_payment_by_e2e(&self.db, end_to_end_id).await?;
+            }
+        }
+
+        Ok(payment.filter(|payment| {
+            let same_currency = event.same_currency(payment);
+            if !same_currency {
+                warn!("⚠️ Funding event {} ({}) references payment {} in {} - not matched by reference",
+                      event.event_id, event.currency, payment.deltran_tx_id, payment.currency.code());
+                METRICS.track_funding_event("currency_mismatch");
+            }
+            same_currency
+        }))
+    }
+
+    /// The one payment awaiting funding that the credit fuzzily matches, if unambiguous
+    pub async fn find_fuzzy(&self, event: &FundingEventRecord) -> Result<Option<CanonicalPayment>> {
+        let candidates = db::find_funding_candidates(&self.db, event.amount, &event.currency, CANDIDATE_LIMIT).await?;
+        let scored = candidates.iter()
+            .filter(|candidate| event.may_fund(candidate.debtor_agent_bic.as_deref()))
+            .map(|candidate| (event.fuzzy_score(candidate), candidate.deltran_tx_id));
+
+        match best_match(scored) {
+            Some(tx_id) => db::get_payment_by_id(&self.db, tx_id).await,
+            None => Ok(None),
+        }
+    }
+
+    /// Apply a queued credit to `payment`: partially funded, Funded with token minting, or excess to
+    /// refund. None when the credit was matched by someone else in the meantime, or is in another
+    /// currency than the payment (it stays queued for an operator).
+    pub async fn fund(
+        &self,
+        event: &FundingEventRecord,
+        payment: &mut CanonicalPayment,
+        method: MatchMethod,
+        matched_by_bic: Option<&str>,
+        note: Option<&str>,
+    ) -> Result<Option<FundingProgress>> {
+        let tx_id = payment.deltran_tx_id;
+        if !event.same_currency(payment) {
+            warn!("⚠️ Funding event {} is in {}, payment {} in {} - left queued for manual review",
+                  event.event_id, event.currency, tx_id, payment.currency.code());
+            METRICS.track_funding_event("currency_mismatch");
+            return Ok(None);
+        }
+
+        let mut tx = self.db.begin().await?;
+
+        // Payment row locked first: concurrent credits for the same payment add up one after the other.
+        // Its status is read under that lock - `payment` may predate a concurrent credit.
+        let Some(funding) = db::lock_payment_funding(&mut tx, tx_id).await? else {
+            return Ok(None);
+        };
+        if !db::claim_funding_event(&mut *tx, event.event_id, tx_id, method, matched_by_bic, note).await? {
+            return Ok(None);
+        }
+
+        let progress = if awaits_funding(&funding.status) {
+            funding.credit(event.amount, self.tolerance_bps)
+        } else {
+            FundingProgress::Overfunded { excess: event.amount }
+        };
+        let (status, funded_amount, mint_amount) = match progress {
+            FundingProgress::Partial { funded, .. } => (Some(PaymentStatus::PendingFunding), funded, None),
+            FundingProgress::Funded { funded, mint_amount, .. } => (Some(PaymentStatus::Funded), funded, Some(mint_amount)),
+            FundingProgress::Overfunded { .. } => (None, funding.funded_amount + event.amount, None),
+        };
+        db::update_payment_funding(
+            &mut tx, tx_id, status, funded_amount, funding.overfunded_amount + progress.excess(), mint_amount,
+        ).await?;
+
+        // Tokens can ONLY be minted AFTER funding is confirmed via camt.054 (1:1 backing guarantee) -
+        // the mint is committed together with the Funded status
+        if let FundingProgress::Funded { mint_amount, .. } = progress {
+            payment.settlement_amount = mint_amount;
+            payment.update_status(PaymentStatus::Funded, None);
+            db::insert_outbox_message(&mut *tx, &NatsRouter::token_mint_message(payment)?).await?;
+        }
+        tx.commit().await?;
+
+        METRICS.track_funding_event(method.as_str());
+        if progress.excess() > Decimal::ZERO {
+            warn!("💸 OVER-FUNDED payment {} ({}): {} {} flagged for refund",
+                  tx_id, payment.end_to_end_id, progress.excess(), event.currency);
+            METRICS.track_funding_event("overfunded");
+        }
+
+        match progress {
+            FundingProgress::Partial { funded, outstanding } => {
+                info!("🪙 PARTIAL FUNDING ({}): {} {} for payment {} - {} funded, {} outstanding",
+                      method.as_str(), event.amount, event.currency, tx_id, funded, outstanding);
+                METRICS.track_funding_event("partial");
+
+                if !matches!(funding.status, PaymentStatus::PendingFunding) {
+                    payment.update_status(PaymentStatus::PendingFunding, None);
+                    self.report(payment).await;
+                }
+            }
+            FundingProgress::Funded { mint_amount, .. } => {
+                info!("💰 FUNDING CONFIRMED ({}): {} {} for payment {} ({}) - minting {}",
+                      method.as_str(), event.amount, event.currency, tx_id, payment.end_to_end_id, mint_amount);
+
+                self.report(payment).await;
+            }
+            FundingProgress::Overfunded { .. } => {}
+        }
+
+        Ok(Some(progress))
+    }
+
+    async fn report(&self, payment: &CanonicalPayment) {
+        if let Err(e) = self.reporter.report_status_change(payment).await {
+            error!("Failed to generate status report for {}: {}", payment.deltran_tx_id, e);
+        }
+        if let Err(e) = self.tracker.record_status_change(payment).await {
+            error!("Failed to record tracking event for {}: {}", payment.deltran_tx_id, e);
+        }
+    }
+
+    /// Late match: apply queued credits to a newly arrived payment - every credit carrying its
+    /// references, or a single fuzzy match for the full amount. Returns the resulting funding.
+    pub async fn match_arrival(&self, payment: &mut CanonicalPayment) -> Result<Option<FundingProgress>> {
+        if !awaits_funding(&payment.status) {
+            return Ok(None);
+        }
+
+        let events = db::find_open_funding_events(
+            &self.db, payment.uetr, &payment.end_to_end_id, payment.instructed_amount, payment.currency.code(), CANDIDATE_LIMIT,
+        ).await?;
+        let events: Vec<_> = events.into_iter()
+            .filter(|event| event.may_fund(payment.debtor_agent.bic.as_deref()))
+            .collect();
+
+        let by_reference: Vec<_> = events.iter()
+            .filter(|event| {
+                (event.uetr.is_some() && event.uetr == payment.uetr)
+                    || event.end_to_end_id.as_deref() == Some(payment.end_to_end_id.as_str())
+            })
+            .collect();
+
+        let mut progress = None;
+        if by_reference.is_empty() {
+            let candidate = FundingCandidate::from(&*payment);
+            let scored = events.iter()
+                .filter(|event| event.amount == payment.instructed_amount)
+                .map(|event| (event.fuzzy_score(&candidate), event));
+            if let Some(event) = best_match(scored) {
+                progress = self.fund(event, payment, MatchMethod::Fuzzy, None, None).await?;
+            }
+        } else {
+            for event in by_reference {
+                if let Some(applied) = self.fund(event, payment, MatchMethod::Reference, None, None).await? {
+                    progress = Some(applied);
+                }
+            }
+        }
+
+        if progress.is_some() {
+            METRICS.track_funding_event("late");
+        }
+        Ok(progress)
+    }
+
+    /// Retry one queued credit against the payments that exist now
+    async fn retry(&self, event: &FundingEventRecord) -> Result<bool> {
+        let by_reference = self.find_by_reference(event).await?
+            .filter(|payment| accepts_credit(&payment.status) && event.may_fund(payment.debtor_agent.bic.as_deref()));
+
+        let (mut payment, method) = match by_reference {
+            Some(payment) => (payment, MatchMethod::Reference),
+            None => match self.find_fuzzy(event).await? {
+                Some(payment) => (payment, MatchMethod::Fuzzy),
+                None => return Ok(false),
+            },
+        };
+
+        let applied = self.fund(event, &mut payment, method, None, None).await?.is_some();
+        if applied {
+            METRICS.track_funding_event("late");
+        }
+        Ok(applied)
+    }
+
+    /// One background round over the least recently tried credits. Returns the number applied.
+    pub async fn match_once(&self) -> Result<usize> {
+        let events = db::next_funding_match_attempts(&self.db, MATCH_BATCH_SIZE).await?;
+
+        let mut applied = 0;
+        for event in &events {
+            match self.retry(event).await {
+                Ok(true) => applied += 1,
+                Ok(false) => {}
+                Err(e) => error!("Funding match for event {} failed: {}", event.event_id, e),
+            }
+        }
+
+        METRICS.funding_queue_unmatched.set(db::count_unmatched_funding_events(&self.db).await?);
+        Ok(applied)
+    }
+}
+
+/// Retry queued credits until the process exits
+pub fn start_funding_matcher(queue: Arc<FundingQueue>) {
+    info!("💰 Funding matcher started (interval {:?})", queue.match_interval);
+
+    tokio::spawn(async move {
+        loop {
+            match queue.match_once().await {
+                Ok(0) => {}
+                Ok(applied) => info!("💰 Funding matcher applied {} queued credit(s)", applied),
+                Err(e) => error!("Funding matcher failed: {}", e),
+            }
+            tokio::time::sleep(queue.match_interval).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_awaits_funding() {
+        assert!(awaits_funding(&PaymentStatus::Received));
+        assert!(awaits_funding(&PaymentStatus::PendingFunding));
+        assert!(!awaits_funding(&PaymentStatus::Funded));
+        assert!(!awaits_funding(&PaymentStatus::Rejected));
+
+        assert!(accepts_credit(&PaymentStatus::PendingFunding));
+        assert!(accepts_credit(&PaymentStatus::Settling));
+        assert!(!accepts_credit(&PaymentStatus::Returned));
+    }
+
+    #[test]
+    fn test_match_interval_from_env_value() {
+        assert_eq!(FundingQueue::match_interval_from_env_value("5"), Duration::from_secs(5));
+        assert_eq!(
+            FundingQueue::match_interval_from_env_value("soon"),
+            Duration::from_secs(DEFAULT_FUNDING_MATCH_INTERVAL_SECS)
+        );
+    }
+
+    #[test]
+    fn test_tolerance_bps_from_env_value() {
+        assert_eq!(FundingQueue::tolerance_bps_from_env_value("25"), 25);
+        assert_eq!(FundingQueue::tolerance_bps_from_env_value("0"), 0);
+        assert_eq!(FundingQueue::tolerance_bps_from_env_value("20000"), DEFAULT_FUNDING_TOLERANCE_BPS);
+    }
+}
@@ -0,0 +1,202 @@
+// Idempotent Ingestion - duplicate detection for pain.001 / pacs.008
+// A retried submission returns the original deltran_tx_ids instead of creating new payments.
+// Keys: Idempotency-Key header (or the sender's AppHdr BizMsgIdr) of the same submitter, UETR or
+// MsgId + EndToEndId of the same sending bank. A key is reserved before the submission is processed,
+// so of two concurrent requests only one gets through; UETR and MsgId + EndToEndId are checked again
+// under a lock while the payment is stored. A resend flagged PssblDplct is expected to replay; an
+// unflagged one is logged.
+
+use std::time::Duration;
+
+use anyhow::Result;
+use serde::Serialize;
+use sqlx::PgPool;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::db;
+use crate::iso20022::AppHdr;
+use crate::metrics::METRICS;
+use crate::models::canonical::CanonicalPayment;
+use crate::models::outbox::OutboxMessage;
+
+pub const DEFAULT_DEDUP_HORIZON_HOURS: u64 = 24;
+
+/// Age after which a reservation whose submission never completed (crashed gateway) is taken over
+const RESERVATION_LEASE_SECS: f64 = 300.0;
+
+/// Identifier on which a submission was recognised as a duplicate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateKey {
+    IdempotencyKey,
+    BusinessMessageId,
+    Uetr,
+    MessageAndEndToEndId,
+}
+
+impl DuplicateKey {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DuplicateKey::IdempotencyKey => "idempotency_key",
+            DuplicateKey::BusinessMessageId => "business_message_id",
+            DuplicateKey::Uetr => "uetr",
+            DuplicateKey::MessageAndEndToEndId => "message_and_end_to_end_id",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct DuplicateOf {
+    pub deltran_tx_id: Uuid,
+    pub matched_on: DuplicateKey,
+}
+
+/// Outcome of an Idempotency-Key reservation
+#[derive(Debug)]
+pub enum KeyReplay {
+    /// Unknown (or expired) key, now reserved - process the message
+    New,
+    /// Same key and same MsgId - return the original payments
+    Replay(Vec<Uuid>),
+    /// Same key, first submission still being processed
+    InProgress,
+    /// Same key reused for a different message
+    Conflict { original_message_id: String },
+}
+
+pub struct IdempotencyGuard {
+    db: PgPool,
+    horizon: Duration,
+}
+
+impl IdempotencyGuard {
+    pub fn new(db: PgPool, horizon: Duration) -> Self {
+        Self { db, horizon }
+    }
+
+    /// Parse DEDUP_HORIZON_HOURS, falling back to the default on invalid input
+    pub fn horizon_from_env_value(value: &str) -> Duration {
+        let hours = value.trim().parse::<u64>().unwrap_or_else(|_| {
+            warn!("Invalid DEDUP_HORIZON_HOURS '{}', using {}h", value, DEFAULT_DEDUP_HORIZON_HOURS);
+            DEFAULT_DEDUP_HORIZON_HOURS
+        });
+        Duration::from_secs(hours * 3600)
+    }
+
+    pub fn horizon(&self) -> Duration {
+        self.horizon
+    }
+
+    fn horizon_secs(&self) -> f64 {
+        self.horizon.as_secs_f64()
+    }
+
+    /// Replay key of an AppHdr: BizMsgIdr is unique per sending BIC
+    pub fn app_header_key(header: &AppHdr) -> Option<String> {
+        let from_bic = header.from_bic()?;
+        Some(format!("head.001:{}:{}", from_bic.to_uppercase(), header.biz_msg_idr))
+    }
+
+    /// Reserve an Idempotency-Key (or AppHdr key) of `submitter_bic` for this submission, or
+    /// resolve it against the earlier submission holding it
+    pub async fn reserve_key(
+        &self,
+        submitter_bic: &str,
+        idempotency_key: &str,
+        matched_on: DuplicateKey,
+        message_type: &str,
+        message_id: &str,
+    ) -> Result<KeyReplay> {
+        let reserved = db::reserve_idempotency_key(
+            &self.db, submitter_bic, idempotency_key, message_type, message_id, self.horizon_secs(), RESERVATION_LEASE_SECS,
+        ).await?;
+        if reserved {
+            return Ok(KeyReplay::New);
+        }
+
+        let replay = match db::get_idempotency_key(&self.db, submitter_bic, idempotency_key, self.horizon_secs()).await? {
+            // Released by a failed submission in the meantime - the client retries
+            None => KeyReplay::InProgress,
+            Some((original_message_id, _)) if original_message_id != message_id => {
+                KeyReplay::Conflict { original_message_id }
+            }
+            Some((_, None)) => KeyReplay::InProgress,
+            Some((_, Some(deltran_tx_ids))) => {
+                METRICS.track_duplicate_submission(matched_on.as_str());
+                KeyReplay::Replay(deltran_tx_ids)
+            }
+        };
+
+        Ok(replay)
+    }
+
+    /// Find an earlier payment this one duplicates (UETR first, then MsgId + EndToEndId)
+    pub async fn find_duplicate(&self, payment: &CanonicalPayment) -> Result<Option<DuplicateOf>> {
+        let duplicate = db::find_duplicate_payment(&self.db, payment, self.horizon_secs()).await?;
+        Ok(duplicate_of(duplicate))
+    }
+
+    /// Store a new payment with its NATS messages - unless a concurrent submission of the same
+    /// payment got there first, then the earlier payment it duplicates
+    pub async fn store(
+        &self,
+        payment: &CanonicalPayment,
+        source_message_type: &str,
+        messages: &[OutboxMessage],
+    ) -> Result<Option<DuplicateOf>> {
+        let duplicate = db::insert_payment_unless_duplicate(
+            &self.db, payment, source_message_type, messages, self.horizon_secs(),
+        ).await?;
+        Ok(duplicate_of(duplicate))
+    }
+
+    /// Store the payments returned for a reserved Idempotency-Key
+    pub async fn complete_key(&self, submitter_bic: &str, idempotency_key: &str, deltran_tx_ids: &[Uuid]) -> Result<()> {
+        db::complete_idempotency_key(&self.db, submitter_bic, idempotency_key, deltran_tx_ids).await
+    }
+
+    /// Release the reservation of a submission that failed
+    pub async fn release_key(&self, submitter_bic: &str, idempotency_key: &str) -> Result<()> {
+        db::release_idempotency_key(&self.db, submitter_bic, idempotency_key).await
+    }
+}
+
+fn duplicate_of(duplicate: Option<(Uuid, bool)>) -> Option<DuplicateOf> {
+    let duplicate = duplicate.map(|(deltran_tx_id, matched_on_uetr)| DuplicateOf {
+        deltran_tx_id,
+        matched_on: if matched_on_uetr { DuplicateKey::Uetr } else { DuplicateKey::MessageAndEndToEndId },
+    });
+
+    if let Some(duplicate) = &duplicate {
+        METRICS.track_duplicate_submission(duplicate.matched_on.as_str());
+    }
+
+    duplicate
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_horizon_from_env_value() {
+        assert_eq!(IdempotencyGuard::horizon_from_env_value("48"), Duration::from_secs(48 * 3600));
+        assert_eq!(
+            IdempotencyGuard::horizon_from_env_value("two days"),
+            Duration::from_secs(DEFAULT_DEDUP_HORIZON_HOURS * 3600)
+        );
+    }
+
+    #[test]
+    fn test_duplicate_key_labels() {
+        assert_eq!(DuplicateKey::Uetr.as_str(), "uetr");
+        assert_eq!(serde_json::to_string(&DuplicateKey::MessageAndEndToEndId).unwrap(), "\"message_and_end_to_end_id\"");
+    }
+
+    #[test]
+    fn test_app_header_key() {
+        let header = AppHdr::new("bankaeadxxx", "DLTRAEADXXX", "pacs.008.001.08", "MSG-001");
+        assert_eq!(IdempotencyGuard::app_header_key(&header).as_deref(), Some("head.001:BANKAEADXXX:MSG-001"));
+    }
+}
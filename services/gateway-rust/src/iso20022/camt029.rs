@@ -0,0 +1,300 @@
+// camt.029.001.13 - Resolution Of Investigation
+// DelTran's answer to a camt.056 cancellation request (CNCL / RJCR / PECR)
+
+use serde::{Deserialize, Serialize};
+use chrono::Utc;
+use anyhow::{Result, anyhow};
+
+use super::camt056::{CancellationRequest, CaseAssignment, OriginalGroupInformation};
+use super::outbound::{self, OutboundDocument};
+use crate::models::canonical::PaymentStatus;
+
+/// Message definition of generated camt.029 resolutions
+pub const CAMT029_MESSAGE_DEFINITION: &str = "camt.029.001.13";
+
+/// camt.029 Document root
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Document {
+    #[serde(rename = "@xmlns", default, skip_serializing_if = "Option::is_none")]
+    pub xmlns: Option<String>,
+
+    #[serde(rename = "RsltnOfInvstgtn")]
+    pub resolution_of_investigation: ResolutionOfInvestigation,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ResolutionOfInvestigation {
+    #[serde(rename = "Assgnmt")]
+    pub assignment: CaseAssignment,
+
+    #[serde(rename = "Sts")]
+    pub status: InvestigationStatus,
+
+    #[serde(rename = "CxlDtls", default)]
+    pub cancellation_details: Vec<UnderlyingTransactionStatus>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct InvestigationStatus {
+    #[serde(rename = "Conf")]
+    pub confirmation: String,   // CNCL, RJCR, PECR
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UnderlyingTransactionStatus {
+    #[serde(rename = "TxInfAndSts", default)]
+    pub transaction_info_and_status: Vec<TransactionInfoAndStatus>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TransactionInfoAndStatus {
+    #[serde(rename = "CxlStsId", skip_serializing_if = "Option::is_none")]
+    pub cancellation_status_id: Option<String>,
+
+    #[serde(rename = "OrgnlGrpInf", skip_serializing_if = "Option::is_none")]
+    pub original_group_info: Option<OriginalGroupInformation>,
+
+    #[serde(rename = "OrgnlInstrId", skip_serializing_if = "Option::is_none")]
+    pub original_instruction_id: Option<String>,
+
+    #[serde(rename = "OrgnlEndToEndId", skip_serializing_if = "Option::is_none")]
+    pub original_end_to_end_id: Option<String>,
+
+    #[serde(rename = "OrgnlTxId", skip_serializing_if = "Option::is_none")]
+    pub original_transaction_id: Option<String>,
+
+    #[serde(rename = "OrgnlUETR", skip_serializing_if = "Option::is_none")]
+    pub original_uetr: Option<String>,
+
+    #[serde(rename = "TxCxlSts", skip_serializing_if = "Option::is_none")]
+    pub transaction_cancellation_status: Option<String>,   // ACCR, RJCR, PDCR
+
+    #[serde(rename = "CxlStsRsnInf", default, skip_serializing_if = "Vec::is_empty")]
+    pub cancellation_status_reason_info: Vec<CancellationStatusReasonInfo>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CancellationStatusReasonInfo {
+    #[serde(rename = "Rsn", skip_serializing_if = "Option::is_none")]
+    pub reason: Option<Reason>,
+
+    #[serde(rename = "AddtlInf", default, skip_serializing_if = "Vec::is_empty")]
+    pub additional_info: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Reason {
+    #[serde(rename = "Cd", skip_serializing_if = "Option::is_none")]
+    pub cd: Option<String>,     // ExternalPaymentCancellationRejection1Code (NOOR, ARDT, AGNT, ...)
+
+    #[serde(rename = "Prtry", skip_serializing_if = "Option::is_none")]
+    pub prtry: Option<String>,
+}
+
+/// Outcome of a single cancellation request (TxCxlSts)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CancellationStatus {
+    Accepted,   // ACCR
+    Rejected,   // RJCR
+}
+
+impl CancellationStatus {
+    pub fn code(&self) -> &'static str {
+        match self {
+            CancellationStatus::Accepted => "ACCR",
+            CancellationStatus::Rejected => "RJCR",
+        }
+    }
+}
+
+/// Resolution of one camt.056 transaction
+#[derive(Debug, Clone)]
+pub struct CancellationResolution {
+    pub request: CancellationRequest,
+    pub status: CancellationStatus,
+    pub rejection_code: Option<String>,
+    pub rejection_info: Option<String>,
+}
+
+/// ExternalPaymentCancellationRejection1Code for a payment that cannot be cancelled
+/// (`None` = original payment not found)
+pub fn rejection_reason(status: Option<&PaymentStatus>) -> (&'static str, Option<String>) {
+    match status {
+        None => ("NOOR", Some("Original payment not found".to_string())),
+        Some(PaymentStatus::Returned) => ("ARDT", Some("Payment already returned".to_string())),
+        Some(PaymentStatus::Rejected | PaymentStatus::Cancelled | PaymentStatus::Failed) => ("ARJT", None),
+        Some(status) => ("AGNT", Some(format!("Payment already in status {:?}", status))),
+    }
+}
+
+/// Investigation confirmation for the whole case
+pub fn confirmation_code(resolutions: &[CancellationResolution]) -> &'static str {
+    let accepted = resolutions.iter().filter(|r| r.status == CancellationStatus::Accepted).count();
+    match accepted {
+        0 => "RJCR",
+        n if n == resolutions.len() => "CNCL",
+        _ => "PECR",
+    }
+}
+
+/// Build the camt.029 answering a camt.056 assignment.
+/// The assignment is reversed: DelTran becomes the assigner, the requesting bank the assignee.
+pub fn build_camt029(
+    request_assignment: &CaseAssignment,
+    resolutions: &[CancellationResolution],
+) -> Result<OutboundDocument> {
+    if resolutions.is_empty() {
+        return Err(anyhow!("Cannot build camt.029 without cancellation results"));
+    }
+
+    let transaction_info_and_status = resolutions.iter()
+        .map(|resolution| {
+            let request = &resolution.request;
+            let reason_info = match (&resolution.rejection_code, &resolution.rejection_info) {
+                (None, None) => vec![],
+                (code, info) => vec![CancellationStatusReasonInfo {
+                    reason: code.as_ref().map(|code| {
+                        if (1..=4).contains(&code.len()) {
+                            Reason { cd: Some(code.clone()), prtry: None }
+                        } else {
+                            Reason { cd: None, prtry: Some(code.chars().take(35).collect()) }
+                        }
+                    }),
+                    additional_info: info.iter().map(|text| text.chars().take(105).collect()).collect(),
+                }],
+            };
+
+            TransactionInfoAndStatus {
+                cancellation_status_id: Some(outbound::generate_identifier("CXLSTS")),
+                original_group_info: match (&request.original_message_id, &request.original_message_name_id) {
+                    (Some(id), Some(name)) => Some(OriginalGroupInformation {
+                        original_message_id: id.clone(),
+                        original_message_name_id: name.clone(),
+                    }),
+                    _ => None,
+                },
+                original_instruction_id: request.original_instruction_id.clone(),
+                original_end_to_end_id: request.original_end_to_end_id.clone(),
+                original_transaction_id: request.original_transaction_id.clone(),
+                original_uetr: request.original_uetr.map(|u| u.to_string()),
+                transaction_cancellation_status: Some(resolution.status.code().to_string()),
+                cancellation_status_reason_info: reason_info,
+            }
+        })
+        .collect();
+
+    let message_id = outbound::generate_identifier("CAMT029");
+    let confirmation = confirmation_code(resolutions).to_string();
+
+    let document = Document {
+        xmlns: Some(format!("urn:iso:std:iso:20022:tech:xsd:{}", CAMT029_MESSAGE_DEFINITION)),
+        resolution_of_investigation: ResolutionOfInvestigation {
+            assignment: CaseAssignment {
+                id: message_id.clone(),
+                assigner: request_assignment.assignee.clone(),
+                assignee: request_assignment.assigner.clone(),
+                creation_date_time: outbound::iso_date_time(Utc::now()),
+            },
+            status: InvestigationStatus {
+                confirmation: confirmation.clone(),
+            },
+            cancellation_details: vec![UnderlyingTransactionStatus {
+                transaction_info_and_status,
+            }],
+        },
+    };
+
+    Ok(OutboundDocument {
+        message_definition: CAMT029_MESSAGE_DEFINITION,
+        xml: outbound::render_xml(&document)?,
+        message_id,
+        group_status: Some(confirmation),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iso20022::camt056::Party50Choice;
+
+    fn request(cancellation_id: &str) -> CancellationRequest {
+        CancellationRequest {
+            assignment_id: "CXL-ASSGN-001".to_string(),
+            case_id: None,
+            assigner_bic: Some("BANKAEADXXX".to_string()),
+            cancellation_id: cancellation_id.to_string(),
+            original_message_id: Some("MSG-1".to_string()),
+            original_message_name_id: Some("pacs.008.001.13".to_string()),
+            original_instruction_id: None,
+            original_end_to_end_id: Some(format!("E2E-{}", cancellation_id)),
+            original_transaction_id: None,
+            original_uetr: Some(uuid::Uuid::new_v4()),
+            reason_code: Some("DUPL".to_string()),
+            reason_description: None,
+        }
+    }
+
+    #[test]
+    fn test_build_camt029_partial_cancellation() {
+        let assignment = CaseAssignment {
+            id: "CXL-ASSGN-001".to_string(),
+            assigner: Party50Choice::agent_bic("BANKAEADXXX"),
+            assignee: Party50Choice::agent_bic("DLTRAEADXXX"),
+            creation_date_time: "2025-01-21T08:00:00Z".to_string(),
+        };
+        let resolutions = vec![
+            CancellationResolution {
+                request: request("CXL001"),
+                status: CancellationStatus::Accepted,
+                rejection_code: None,
+                rejection_info: None,
+            },
+            CancellationResolution {
+                request: request("CXL002"),
+                status: CancellationStatus::Rejected,
+                rejection_code: Some("AGNT".to_string()),
+                rejection_info: Some("Payment already settling".to_string()),
+            },
+        ];
+
+        let document = build_camt029(&assignment, &resolutions).unwrap();
+        assert_eq!(document.group_status.as_deref(), Some("PECR"));
+
+        let xsd = std::fs::read_to_string(concat!(
+            env!("CARGO_MANIFEST_DIR"), "/../../iso20022/exceptions_investigations/camt.029.001.13.xsd"
+        )).unwrap();
+        let validator = crate::validation::xsd::XsdValidator::new(
+            vec![crate::validation::xsd::SchemaModel::parse(&xsd).unwrap()],
+            crate::validation::xsd::ValidationMode::Strict,
+        );
+        assert!(validator.validate("camt.029", &document.xml).is_ok(), "{:?}", validator.validate("camt.029", &document.xml).err());
+
+        let parsed: Document = quick_xml::de::from_str(&document.xml).unwrap();
+        let resolution = &parsed.resolution_of_investigation;
+        assert_eq!(resolution.assignment.assignee.bic(), Some("BANKAEADXXX"));
+        assert_eq!(resolution.assignment.assigner.bic(), Some("DLTRAEADXXX"));
+
+        let statuses = &resolution.cancellation_details[0].transaction_info_and_status;
+        assert_eq!(statuses[0].transaction_cancellation_status.as_deref(), Some("ACCR"));
+        assert_eq!(statuses[1].transaction_cancellation_status.as_deref(), Some("RJCR"));
+        assert_eq!(statuses[1].cancellation_status_reason_info[0].reason.as_ref().unwrap().cd.as_deref(), Some("AGNT"));
+    }
+
+    #[test]
+    fn test_confirmation_code() {
+        let resolution = |status| CancellationResolution { request: request("X"), status, rejection_code: None, rejection_info: None };
+        assert_eq!(confirmation_code(&[resolution(CancellationStatus::Accepted)]), "CNCL");
+        assert_eq!(confirmation_code(&[resolution(CancellationStatus::Rejected)]), "RJCR");
+    }
+
+    #[test]
+    fn test_rejection_reason() {
+        assert_eq!(rejection_reason(None).0, "NOOR");
+        assert_eq!(rejection_reason(Some(&PaymentStatus::Returned)).0, "ARDT");
+        assert_eq!(rejection_reason(Some(&PaymentStatus::Cancelled)), ("ARJT", None));
+        assert_eq!(
+            rejection_reason(Some(&PaymentStatus::Settling)),
+            ("AGNT", Some("Payment already in status Settling".to_string()))
+        );
+    }
+}
@@ -218,19 +218,30 @@ pub struct Party {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StatementSummary {
     pub statement_id: String,
+    pub message_id: String,                     // GrpHdr/MsgId
     pub account_id: String,
     pub account_iban: Option<String>,
+    pub account_servicer_bic: Option<String>,
     pub currency: String,
     pub from_date: Option<DateTime<Utc>>,
     pub to_date: Option<DateTime<Utc>>,
-    pub opening_balance: Option<Decimal>,
+    pub opening_balance: Option<Decimal>,       // signed: negative when DBIT
     pub closing_balance: Option<Decimal>,
     pub total_entries: usize,
-    pub total_credits: Decimal,
+    pub total_credits: Decimal,                 // booked entries only
     pub total_debits: Decimal,
     pub entries: Vec<StatementEntry>,
 }
 
+impl StatementSummary {
+    /// opening + credits - debits - closing; zero when the statement balances
+    pub fn balance_difference(&self) -> Option<Decimal> {
+        let opening = self.opening_balance?;
+        let closing = self.closing_balance?;
+        Some(opening + self.total_credits - self.total_debits - closing)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StatementEntry {
     pub entry_id: Uuid,
@@ -238,6 +249,7 @@ pub struct StatementEntry {
     pub currency: String,
     pub credit_debit_indicator: String,
     pub status: String,
+    pub entry_reference: Option<String>,        // AcctSvcrRef
     pub booking_date: Option<NaiveDate>,
     pub value_date: Option<NaiveDate>,
     pub end_to_end_id: Option<String>,
@@ -270,13 +282,8 @@ pub fn to_statement_summaries(document: &Document) -> Result<Vec<StatementSummar
             .unwrap_or_else(|| "XXX".to_string());
 
         // Extract opening and closing balances
-        let opening_balance = stmt.bal.iter()
-            .find(|b| b.tp.code_or_proprietary.cd.as_deref() == Some("OPBD"))
-            .and_then(|b| b.amt.value.parse::<Decimal>().ok());
-
-        let closing_balance = stmt.bal.iter()
-            .find(|b| b.tp.code_or_proprietary.cd.as_deref() == Some("CLBD"))
-            .and_then(|b| b.amt.value.parse::<Decimal>().ok());
+        let opening_balance = booked_balance(&stmt.bal, "OPBD");
+        let closing_balance = booked_balance(&stmt.bal, "CLBD");
 
         // Parse date range
         let (from_date, to_date) = if let Some(ref fr_to) = stmt.fr_to_dt {
@@ -300,10 +307,13 @@ pub fn to_statement_summaries(document: &Document) -> Result<Vec<StatementSummar
                 let amount: Decimal = entry.amt.value.parse()
                     .unwrap_or(Decimal::ZERO);
 
-                if entry.cdt_dbt_ind == "CRDT" {
-                    total_credits += amount;
-                } else {
-                    total_debits += amount;
+                // Pending / information entries do not move the booked balance
+                if entry.sts.cd == "BOOK" {
+                    if entry.cdt_dbt_ind == "CRDT" {
+                        total_credits += amount;
+                    } else {
+                        total_debits += amount;
+                    }
                 }
 
                 let booking_date = entry.booking_dt.as_ref()
@@ -339,6 +349,7 @@ pub fn to_statement_summaries(document: &Document) -> Result<Vec<StatementSummar
                     currency: entry.amt.currency.clone(),
                     credit_debit_indicator: entry.cdt_dbt_ind.clone(),
                     status: entry.sts.cd.clone(),
+                    entry_reference: entry.acct_svcr_ref.clone(),
                     booking_date,
                     value_date,
                     end_to_end_id,
@@ -352,8 +363,10 @@ pub fn to_statement_summaries(document: &Document) -> Result<Vec<StatementSummar
 
         summaries.push(StatementSummary {
             statement_id: stmt.id.clone(),
+            message_id: document.bank_to_customer_statement.grp_hdr.msg_id.clone(),
             account_id,
             account_iban: stmt.acct.id.iban.clone(),
+            account_servicer_bic: stmt.acct.svcr.as_ref().and_then(|s| s.fin_instn_id.bic.clone()),
             currency,
             from_date,
             to_date,
@@ -369,6 +382,15 @@ pub fn to_statement_summaries(document: &Document) -> Result<Vec<StatementSummar
     Ok(summaries)
 }
 
+/// Balance of the given type (OPBD, CLBD), negative when the account is in debit
+fn booked_balance(balances: &[Balance], code: &str) -> Option<Decimal> {
+    let balance = balances.iter()
+        .find(|b| b.tp.code_or_proprietary.cd.as_deref() == Some(code))?;
+    let amount = balance.amt.value.parse::<Decimal>().ok()?;
+
+    Some(if balance.cdt_dbt_ind == "DBIT" { -amount } else { amount })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -427,5 +449,7 @@ mod tests {
         assert_eq!(summaries[0].currency, "AED");
         assert_eq!(summaries[0].opening_balance, Some(Decimal::new(100000000, 2)));
         assert_eq!(summaries[0].closing_balance, Some(Decimal::new(105000000, 2)));
+        // No entries: the 50,000.00 difference is a break
+        assert_eq!(summaries[0].balance_difference(), Some(Decimal::new(-5000000, 2)));
     }
 }
@@ -9,7 +9,7 @@ use uuid::Uuid;
 use rust_decimal::Decimal;
 use std::str::FromStr;
 
-use crate::models::canonical::{Currency, AccountIdentification};
+use crate::models::canonical::Currency;
 
 // camt.054.001.10 - BankToCustomerDebitCreditNotification
 #[derive(Debug, Deserialize, Serialize)]
@@ -165,10 +165,10 @@ pub struct ReportEntry {
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct ActiveOrHistoricCurrencyAndAmount {
-    #[serde(rename = "Ccy")]
+    #[serde(rename = "@Ccy")]
     pub currency: String,
 
-    #[serde(rename = "$value")]
+    #[serde(rename = "$text")]
     pub value: String,
 }
 
@@ -317,6 +317,7 @@ pub struct RemittanceInformation {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FundingEvent {
     pub account: String,  // IBAN or other account ID
+    pub account_servicer_bic: Option<String>,  // Acct/Svcr - the bank holding the account
     pub amount: Decimal,
     pub currency: Currency,
     pub credit_debit: CreditDebit,
@@ -327,6 +328,7 @@ pub struct FundingEvent {
     pub instruction_id: Option<String>,
     pub uetr: Option<Uuid>,
     pub debtor_name: Option<String>,
+    pub debtor_account: Option<String>,  // RltdPties/DbtrAcct - IBAN or other account ID
     pub creditor_name: Option<String>,
     pub remittance_info: Option<String>,
     pub entry_reference: Option<String>,
@@ -352,14 +354,16 @@ pub fn extract_funding_events(camt054: &Document) -> Result<Vec<FundingEvent>> {
         let account_id = notification.account.identification.iban.clone()
             .or_else(|| notification.account.identification.other.as_ref().map(|o| o.id.clone()))
             .unwrap_or_default();
+        let account_servicer_bic = notification.account.servicer.as_ref()
+            .and_then(|s| s.financial_institution_identification.bic.clone());
 
         for entry in &notification.entry {
             // Parse amount
             let amount = Decimal::from_str(&entry.amount.value)
                 .context("Invalid amount in camt.054")?;
 
-            let currency = Currency::from_str(&entry.amount.currency)
-                .unwrap_or(Currency::Usd);
+            let currency: Currency = entry.amount.currency.parse()?;
+            currency.check_amount(amount)?;
 
             let credit_debit = match entry.credit_debit_indicator.as_str() {
                 "CRDT" => CreditDebit::Credit,  // Money IN - THIS IS FUNDING!
@@ -368,7 +372,7 @@ pub fn extract_funding_events(camt054: &Document) -> Result<Vec<FundingEvent>> {
             };
 
             // Extract transaction details if present
-            let (end_to_end_id, instruction_id, uetr, debtor_name, creditor_name, remittance_info) =
+            let (end_to_end_id, instruction_id, uetr, debtor_name, debtor_account, creditor_name, remittance_info) =
                 if let Some(entry_detail) = entry.entry_details.first() {
                     if let Some(tx_detail) = entry_detail.transaction_details.first() {
                         let e2e = tx_detail.references.as_ref()
@@ -381,22 +385,27 @@ pub fn extract_funding_events(camt054: &Document) -> Result<Vec<FundingEvent>> {
                         let debtor = tx_detail.related_parties.as_ref()
                             .and_then(|rp| rp.debtor.as_ref())
                             .and_then(|d| d.name.clone());
+                        let debtor_account = tx_detail.related_parties.as_ref()
+                            .and_then(|rp| rp.debtor_account.as_ref())
+                            .and_then(|a| a.identification.iban.clone()
+                                .or_else(|| a.identification.other.as_ref().map(|o| o.id.clone())));
                         let creditor = tx_detail.related_parties.as_ref()
                             .and_then(|rp| rp.creditor.as_ref())
                             .and_then(|c| c.name.clone());
                         let remit = tx_detail.remittance_information.as_ref()
                             .and_then(|ri| ri.unstructured.first().cloned());
 
-                        (e2e, instr, uetr_uuid, debtor, creditor, remit)
+                        (e2e, instr, uetr_uuid, debtor, debtor_account, creditor, remit)
                     } else {
-                        (None, None, None, None, None, None)
+                        (None, None, None, None, None, None, None)
                     }
                 } else {
-                    (None, None, None, None, None, None)
+                    (None, None, None, None, None, None, None)
                 };
 
             let event = FundingEvent {
                 account: account_id.clone(),
+                account_servicer_bic: account_servicer_bic.clone(),
                 amount,
                 currency,
                 credit_debit,
@@ -407,6 +416,7 @@ pub fn extract_funding_events(camt054: &Document) -> Result<Vec<FundingEvent>> {
                 instruction_id,
                 uetr,
                 debtor_name,
+                debtor_account,
                 creditor_name,
                 remittance_info,
                 entry_reference: entry.entry_reference.clone(),
@@ -483,6 +493,7 @@ mod tests {
     fn test_is_credit_event() {
         let event = FundingEvent {
             account: "AE070331234567890123456".to_string(),
+            account_servicer_bic: None,
             amount: Decimal::new(10000, 2),
             currency: Currency::AED,
             credit_debit: CreditDebit::Credit,
@@ -493,6 +504,7 @@ mod tests {
             instruction_id: None,
             uetr: None,
             debtor_name: None,
+            debtor_account: None,
             creditor_name: None,
             remittance_info: None,
             entry_reference: None,
@@ -0,0 +1,322 @@
+// camt.056.001.11 - FI to FI Payment Cancellation Request
+// Asks DelTran to cancel a previously submitted payment (answered with camt.029)
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use anyhow::{Result, anyhow, Context};
+
+/// camt.056 Document root
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Document {
+    #[serde(rename = "FIToFIPmtCxlReq")]
+    pub cancellation_request: FIToFIPaymentCancellationRequest,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct FIToFIPaymentCancellationRequest {
+    #[serde(rename = "Assgnmt")]
+    pub assignment: CaseAssignment,
+
+    #[serde(rename = "Case", default)]
+    pub case: Option<Case>,
+
+    #[serde(rename = "Undrlyg")]
+    pub underlying: Vec<UnderlyingTransaction>,
+}
+
+/// Case assignment (shared with camt.029)
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct CaseAssignment {
+    #[serde(rename = "Id")]
+    pub id: String,
+
+    #[serde(rename = "Assgnr")]
+    pub assigner: Party50Choice,
+
+    #[serde(rename = "Assgne")]
+    pub assignee: Party50Choice,
+
+    #[serde(rename = "CreDtTm")]
+    pub creation_date_time: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Case {
+    #[serde(rename = "Id")]
+    pub id: String,
+
+    #[serde(rename = "Cretr")]
+    pub creator: Party50Choice,
+}
+
+/// Party or agent (choice)
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct Party50Choice {
+    #[serde(rename = "Pty", default, skip_serializing_if = "Option::is_none")]
+    pub party: Option<PartyIdentification>,
+
+    #[serde(rename = "Agt", default, skip_serializing_if = "Option::is_none")]
+    pub agent: Option<BranchAndFinancialInstitutionIdentification>,
+}
+
+impl Party50Choice {
+    pub fn agent_bic(bic: &str) -> Self {
+        Self {
+            party: None,
+            agent: Some(BranchAndFinancialInstitutionIdentification {
+                financial_institution_id: FinancialInstitutionIdentification {
+                    bicfi: Some(bic.to_string()),
+                    name: None,
+                },
+            }),
+        }
+    }
+
+    pub fn bic(&self) -> Option<&str> {
+        self.agent.as_ref()
+            .and_then(|a| a.financial_institution_id.bicfi.as_deref())
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PartyIdentification {
+    #[serde(rename = "Nm", default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct BranchAndFinancialInstitutionIdentification {
+    #[serde(rename = "FinInstnId")]
+    pub financial_institution_id: FinancialInstitutionIdentification,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct FinancialInstitutionIdentification {
+    #[serde(rename = "BICFI", default, skip_serializing_if = "Option::is_none")]
+    pub bicfi: Option<String>,
+
+    #[serde(rename = "Nm", default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UnderlyingTransaction {
+    #[serde(rename = "OrgnlGrpInfAndCxl", default)]
+    pub original_group_info_and_cancellation: Option<OriginalGroupHeader>,
+
+    #[serde(rename = "TxInf", default)]
+    pub transaction_info: Vec<PaymentTransaction>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct OriginalGroupHeader {
+    #[serde(rename = "GrpCxlId", default)]
+    pub group_cancellation_id: Option<String>,
+
+    #[serde(rename = "OrgnlMsgId")]
+    pub original_message_id: String,
+
+    #[serde(rename = "OrgnlMsgNmId")]
+    pub original_message_name_id: String,
+
+    #[serde(rename = "CxlRsnInf", default)]
+    pub cancellation_reason_info: Vec<CancellationReasonInfo>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct OriginalGroupInformation {
+    #[serde(rename = "OrgnlMsgId")]
+    pub original_message_id: String,
+
+    #[serde(rename = "OrgnlMsgNmId")]
+    pub original_message_name_id: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PaymentTransaction {
+    #[serde(rename = "CxlId", default)]
+    pub cancellation_id: Option<String>,
+
+    #[serde(rename = "OrgnlGrpInf", default)]
+    pub original_group_info: Option<OriginalGroupInformation>,
+
+    #[serde(rename = "OrgnlInstrId", default)]
+    pub original_instruction_id: Option<String>,
+
+    #[serde(rename = "OrgnlEndToEndId", default)]
+    pub original_end_to_end_id: Option<String>,
+
+    #[serde(rename = "OrgnlTxId", default)]
+    pub original_transaction_id: Option<String>,
+
+    #[serde(rename = "OrgnlUETR", default)]
+    pub original_uetr: Option<String>,
+
+    #[serde(rename = "CxlRsnInf", default)]
+    pub cancellation_reason_info: Vec<CancellationReasonInfo>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CancellationReasonInfo {
+    #[serde(rename = "Rsn", default)]
+    pub reason: Option<Reason>,
+
+    #[serde(rename = "AddtlInf", default)]
+    pub additional_info: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Reason {
+    #[serde(rename = "Cd", default)]
+    pub cd: Option<String>,     // ExternalCancellationReason1Code (DUPL, CUST, FRAD, TECH, ...)
+
+    #[serde(rename = "Prtry", default)]
+    pub prtry: Option<String>,
+}
+
+/// Cancellation request for a single transaction, as handled by DelTran
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CancellationRequest {
+    pub assignment_id: String,
+    pub case_id: Option<String>,
+    pub assigner_bic: Option<String>,
+    pub cancellation_id: String,
+    pub original_message_id: Option<String>,
+    pub original_message_name_id: Option<String>,
+    pub original_instruction_id: Option<String>,
+    pub original_end_to_end_id: Option<String>,
+    pub original_transaction_id: Option<String>,
+    pub original_uetr: Option<Uuid>,
+    pub reason_code: Option<String>,
+    pub reason_description: Option<String>,
+}
+
+/// Parse camt.056 XML message
+pub fn parse_camt056(xml: &str) -> Result<Document> {
+    quick_xml::de::from_str(xml)
+        .map_err(|e| anyhow!("Failed to parse camt.056 XML: {}", e))
+}
+
+/// Convert camt.056 to per-transaction cancellation requests.
+/// Transaction reasons win over the group cancellation reason.
+pub fn to_cancellation_requests(document: &Document) -> Result<Vec<CancellationRequest>> {
+    let request = &document.cancellation_request;
+    let mut requests = Vec::new();
+
+    for underlying in &request.underlying {
+        let group = underlying.original_group_info_and_cancellation.as_ref();
+
+        for tx in &underlying.transaction_info {
+            if tx.original_uetr.is_none() && tx.original_end_to_end_id.is_none() {
+                return Err(anyhow!(
+                    "camt.056 transaction {} has neither OrgnlUETR nor OrgnlEndToEndId",
+                    tx.cancellation_id.as_deref().unwrap_or("<no CxlId>")
+                ));
+            }
+
+            let original_uetr = tx.original_uetr.as_ref()
+                .map(|uetr| Uuid::parse_str(uetr).context(format!("Invalid OrgnlUETR: {}", uetr)))
+                .transpose()?;
+
+            let reason_info = tx.cancellation_reason_info.first()
+                .or_else(|| group.and_then(|g| g.cancellation_reason_info.first()));
+            let reason_code = reason_info
+                .and_then(|r| r.reason.as_ref())
+                .and_then(|r| r.cd.clone().or_else(|| r.prtry.clone()));
+            let reason_description = reason_info
+                .filter(|r| !r.additional_info.is_empty())
+                .map(|r| r.additional_info.join(" "));
+
+            let (original_message_id, original_message_name_id) = match (&tx.original_group_info, group) {
+                (Some(info), _) => (Some(info.original_message_id.clone()), Some(info.original_message_name_id.clone())),
+                (None, Some(g)) => (Some(g.original_message_id.clone()), Some(g.original_message_name_id.clone())),
+                (None, None) => (None, None),
+            };
+
+            requests.push(CancellationRequest {
+                assignment_id: request.assignment.id.clone(),
+                case_id: request.case.as_ref().map(|c| c.id.clone()),
+                assigner_bic: request.assignment.assigner.bic().map(str::to_string),
+                cancellation_id: tx.cancellation_id.clone()
+                    .unwrap_or_else(|| Uuid::new_v4().to_string()),
+                original_message_id,
+                original_message_name_id,
+                original_instruction_id: tx.original_instruction_id.clone(),
+                original_end_to_end_id: tx.original_end_to_end_id.clone(),
+                original_transaction_id: tx.original_transaction_id.clone(),
+                original_uetr,
+                reason_code,
+                reason_description,
+            });
+        }
+    }
+
+    Ok(requests)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CAMT056: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Document xmlns="urn:iso:std:iso:20022:tech:xsd:camt.056.001.11">
+  <FIToFIPmtCxlReq>
+    <Assgnmt>
+      <Id>CXL-ASSGN-001</Id>
+      <Assgnr><Agt><FinInstnId><BICFI>BANKAEADXXX</BICFI></FinInstnId></Agt></Assgnr>
+      <Assgne><Agt><FinInstnId><BICFI>DLTRAEADXXX</BICFI></FinInstnId></Agt></Assgne>
+      <CreDtTm>2025-01-21T08:00:00Z</CreDtTm>
+    </Assgnmt>
+    <Undrlyg>
+      <OrgnlGrpInfAndCxl>
+        <OrgnlMsgId>MSG-1</OrgnlMsgId>
+        <OrgnlMsgNmId>pacs.008.001.13</OrgnlMsgNmId>
+        <CxlRsnInf><Rsn><Cd>CUST</Cd></Rsn></CxlRsnInf>
+      </OrgnlGrpInfAndCxl>
+      <TxInf>
+        <CxlId>CXL001</CxlId>
+        <OrgnlEndToEndId>E2E123456</OrgnlEndToEndId>
+        <OrgnlUETR>8a562c67-ca16-48ba-b074-65581be6f011</OrgnlUETR>
+        <CxlRsnInf>
+          <Rsn><Cd>DUPL</Cd></Rsn>
+          <AddtlInf>Duplicate payment</AddtlInf>
+        </CxlRsnInf>
+      </TxInf>
+      <TxInf>
+        <CxlId>CXL002</CxlId>
+        <OrgnlEndToEndId>E2E999</OrgnlEndToEndId>
+      </TxInf>
+    </Undrlyg>
+  </FIToFIPmtCxlReq>
+</Document>"#;
+
+    #[test]
+    fn test_parse_camt056() {
+        let document = parse_camt056(CAMT056).unwrap();
+        let requests = to_cancellation_requests(&document).unwrap();
+        assert_eq!(requests.len(), 2);
+
+        assert_eq!(requests[0].assignment_id, "CXL-ASSGN-001");
+        assert_eq!(requests[0].assigner_bic.as_deref(), Some("BANKAEADXXX"));
+        assert_eq!(requests[0].cancellation_id, "CXL001");
+        assert_eq!(requests[0].original_message_id.as_deref(), Some("MSG-1"));
+        assert_eq!(requests[0].reason_code.as_deref(), Some("DUPL"));
+        assert_eq!(requests[0].reason_description.as_deref(), Some("Duplicate payment"));
+        assert!(requests[0].original_uetr.is_some());
+
+        // Falls back to the group cancellation reason
+        assert_eq!(requests[1].reason_code.as_deref(), Some("CUST"));
+        assert_eq!(requests[1].original_uetr, None);
+    }
+
+    #[test]
+    fn test_sample_conforms_to_xsd() {
+        use crate::validation::xsd::{SchemaModel, XsdValidator, ValidationMode};
+
+        let xsd = std::fs::read_to_string(concat!(
+            env!("CARGO_MANIFEST_DIR"), "/../../iso20022/exceptions_investigations/camt.056.001.11.xsd"
+        )).unwrap();
+        let validator = XsdValidator::new(vec![SchemaModel::parse(&xsd).unwrap()], ValidationMode::Strict);
+        assert!(validator.validate("camt.056", CAMT056).is_ok(), "{:?}", validator.validate("camt.056", CAMT056).err());
+    }
+}
@@ -0,0 +1,286 @@
+// head.001 - Business Application Header (AppHdr)
+// Real ISO 20022 traffic carries the Document in an envelope together with its AppHdr
+// (From / To BIC, BizMsgIdr, MsgDefIdr, CreDt, PssblDplct). Inbound messages are accepted
+// bare or enveloped (any envelope element); outbound ones are wrapped in <BizMsg>.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+
+use super::outbound;
+use crate::validation::xsd::ISO_NAMESPACE_PREFIX;
+
+pub const HEAD001_MESSAGE_DEFINITION: &str = "head.001.001.02";
+
+/// Envelope element of outbound messages (AppHdr followed by Document)
+pub const ENVELOPE_ELEMENT: &str = "BizMsg";
+
+/// Business Application Header
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct AppHdr {
+    #[serde(rename = "@xmlns", default, skip_serializing_if = "Option::is_none")]
+    pub xmlns: Option<String>,
+
+    pub fr: AddressedParty,
+    pub to: AddressedParty,
+    pub biz_msg_idr: String,
+    pub msg_def_idr: String,        // e.g. pacs.008.001.08
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub biz_svc: Option<String>,
+    pub cre_dt: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpy_dplct: Option<String>,  // CODU, COPY, DUPL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pssbl_dplct: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prty: Option<String>,
+}
+
+/// Fr / To (Party44Choice) - only financial institutions are addressed by BIC
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AddressedParty {
+    #[serde(rename = "FIId", default, skip_serializing_if = "Option::is_none")]
+    pub financial_institution: Option<FinancialInstitution>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FinancialInstitution {
+    #[serde(rename = "FinInstnId")]
+    pub financial_institution_id: FinancialInstitutionId,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FinancialInstitutionId {
+    #[serde(rename = "BICFI", default, skip_serializing_if = "Option::is_none")]
+    pub bicfi: Option<String>,
+}
+
+impl AddressedParty {
+    pub fn institution(bic: &str) -> Self {
+        Self {
+            financial_institution: Some(FinancialInstitution {
+                financial_institution_id: FinancialInstitutionId { bicfi: Some(bic.to_string()) },
+            }),
+        }
+    }
+
+    pub fn bic(&self) -> Option<&str> {
+        self.financial_institution.as_ref()
+            .and_then(|fi| fi.financial_institution_id.bicfi.as_deref())
+    }
+}
+
+impl AppHdr {
+    /// Header for an outbound document from `from_bic` to `to_bic`
+    pub fn new(from_bic: &str, to_bic: &str, message_definition: &str, business_message_id: &str) -> Self {
+        Self {
+            xmlns: Some(format!("{}{}", ISO_NAMESPACE_PREFIX, HEAD001_MESSAGE_DEFINITION)),
+            fr: AddressedParty::institution(from_bic),
+            to: AddressedParty::institution(to_bic),
+            biz_msg_idr: business_message_id.to_string(),
+            msg_def_idr: message_definition.to_string(),
+            biz_svc: None,
+            cre_dt: outbound::iso_date_time(Utc::now()),
+            cpy_dplct: None,
+            pssbl_dplct: None,
+            prty: None,
+        }
+    }
+
+    pub fn from_bic(&self) -> Option<&str> {
+        self.fr.bic()
+    }
+
+    pub fn to_bic(&self) -> Option<&str> {
+        self.to.bic()
+    }
+
+    /// Message type of MsgDefIdr, e.g. "pacs.008"
+    pub fn message_type(&self) -> Option<&str> {
+        message_type_of(&self.msg_def_idr)
+    }
+
+    pub fn is_possible_duplicate(&self) -> bool {
+        self.pssbl_dplct.unwrap_or(false)
+    }
+}
+
+/// "pacs.008.001.08" -> "pacs.008"
+pub fn message_type_of(message_definition: &str) -> Option<&str> {
+    let mut dots = message_definition.match_indices('.').map(|(i, _)| i);
+    let end = dots.nth(1).unwrap_or(message_definition.len());
+    let message_type = &message_definition[..end];
+    (message_type.len() == 8 && message_type.as_bytes()[4] == b'.').then_some(message_type)
+}
+
+/// Inbound message split into its AppHdr (if any) and the Document it carries
+#[derive(Debug)]
+pub struct BusinessMessage<'a> {
+    pub header: Option<AppHdr>,
+    pub document: &'a str,
+}
+
+impl<'a> BusinessMessage<'a> {
+    /// Accepts a bare Document or an envelope holding AppHdr and Document (at any depth)
+    pub fn parse(xml: &'a str) -> Result<Self> {
+        let mut reader = Reader::from_str(xml);
+        let mut depth = 0usize;
+        let mut open: Option<(bool, usize, usize)> = None;     // (is header, start offset, depth)
+        let mut header = None;
+        let mut document = None;
+
+        loop {
+            let offset = reader.buffer_position();
+            match reader.read_event().context("Malformed ISO 20022 message")? {
+                Event::Start(e) => {
+                    depth += 1;
+                    if open.is_none() {
+                        match e.local_name().as_ref() {
+                            b"AppHdr" if header.is_none() => open = Some((true, offset, depth)),
+                            b"Document" if document.is_none() => open = Some((false, offset, depth)),
+                            _ => {}
+                        }
+                    }
+                }
+                Event::End(_) => {
+                    if let Some((is_header, start, _)) = open.filter(|(_, _, d)| *d == depth) {
+                        let element = &xml[start..reader.buffer_position()];
+                        if is_header {
+                            header = Some(element);
+                        } else {
+                            document = Some(element);
+                        }
+                        open = None;
+                    }
+                    depth = depth.saturating_sub(1);
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+            if document.is_some() && (header.is_some() || depth == 0) {
+                break;
+            }
+        }
+
+        let document = document.ok_or_else(|| anyhow!("No ISO 20022 Document element found"))?;
+        let header = header
+            .map(|xml| quick_xml::de::from_str::<AppHdr>(xml).context("Invalid head.001 AppHdr"))
+            .transpose()?;
+
+        Ok(Self { header, document })
+    }
+
+    /// MsgDefIdr of the AppHdr, otherwise the Document namespace
+    pub fn message_definition(&self) -> Option<String> {
+        if let Some(header) = &self.header {
+            return Some(header.msg_def_idr.clone());
+        }
+
+        let mut reader = Reader::from_str(self.document);
+        loop {
+            match reader.read_event().ok()? {
+                Event::Start(e) | Event::Empty(e) => {
+                    let namespace = e.try_get_attribute("xmlns").ok()??.unescape_value().ok()?;
+                    return namespace.strip_prefix(ISO_NAMESPACE_PREFIX).map(str::to_string);
+                }
+                Event::Eof => return None,
+                _ => {}
+            }
+        }
+    }
+
+    /// Message type used for dispatch, e.g. "pain.001"
+    pub fn message_type(&self) -> Option<String> {
+        let definition = self.message_definition()?;
+        message_type_of(&definition).map(str::to_string)
+    }
+}
+
+/// Wrap a rendered Document (with or without XML declaration) into <BizMsg> with its AppHdr
+pub fn wrap(header: &AppHdr, document_xml: &str) -> Result<String> {
+    let header_xml = quick_xml::se::to_string(header).context("Failed to serialize head.001 AppHdr")?;
+    let document_xml = match document_xml.strip_prefix("<?xml") {
+        Some(rest) => rest.split_once("?>").map(|(_, doc)| doc).unwrap_or(rest),
+        None => document_xml,
+    };
+
+    Ok(format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<{}>{}{}</{}>",
+        ENVELOPE_ELEMENT, header_xml, document_xml.trim_start(), ENVELOPE_ELEMENT
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENVELOPE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Envelope xmlns="urn:swift:xsd:envelope">
+  <AppHdr xmlns="urn:iso:std:iso:20022:tech:xsd:head.001.001.02">
+    <Fr><FIId><FinInstnId><BICFI>BANKAEADXXX</BICFI></FinInstnId></FIId></Fr>
+    <To><FIId><FinInstnId><BICFI>DLTRAEADXXX</BICFI></FinInstnId></FIId></To>
+    <BizMsgIdr>MSG-BAH-001</BizMsgIdr>
+    <MsgDefIdr>pacs.008.001.08</MsgDefIdr>
+    <BizSvc>swift.cbprplus.02</BizSvc>
+    <CreDt>2025-01-28T09:30:00Z</CreDt>
+    <PssblDplct>true</PssblDplct>
+  </AppHdr>
+  <Document xmlns="urn:iso:std:iso:20022:tech:xsd:pacs.008.001.08"><FIToFICstmrCdtTrf/></Document>
+</Envelope>"#;
+
+    #[test]
+    fn test_parse_envelope() {
+        let message = BusinessMessage::parse(ENVELOPE).unwrap();
+        let header = message.header.as_ref().unwrap();
+
+        assert_eq!(header.from_bic(), Some("BANKAEADXXX"));
+        assert_eq!(header.to_bic(), Some("DLTRAEADXXX"));
+        assert_eq!(header.biz_msg_idr, "MSG-BAH-001");
+        assert_eq!(header.message_type(), Some("pacs.008"));
+        assert!(header.is_possible_duplicate());
+        assert_eq!(
+            message.document,
+            r#"<Document xmlns="urn:iso:std:iso:20022:tech:xsd:pacs.008.001.08"><FIToFICstmrCdtTrf/></Document>"#
+        );
+        assert_eq!(message.message_type().as_deref(), Some("pacs.008"));
+    }
+
+    #[test]
+    fn test_parse_bare_document() {
+        let xml = r#"<?xml version="1.0"?><Document xmlns="urn:iso:std:iso:20022:tech:xsd:camt.054.001.08"><BkToCstmrDbtCdtNtfctn/></Document>"#;
+        let message = BusinessMessage::parse(xml).unwrap();
+
+        assert!(message.header.is_none());
+        assert_eq!(message.message_definition().as_deref(), Some("camt.054.001.08"));
+        assert_eq!(message.message_type().as_deref(), Some("camt.054"));
+
+        assert!(BusinessMessage::parse("<AppHdr><BizMsgIdr>X</BizMsgIdr></AppHdr>").is_err());
+    }
+
+    #[test]
+    fn test_wrap_round_trip() {
+        let header = AppHdr::new("DLTRAEADXXX", "BANKAEADXXX", "pain.002.001.14", "PAIN002-1");
+        let document = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<Document xmlns=\"urn:iso:std:iso:20022:tech:xsd:pain.002.001.14\"><CstmrPmtStsRpt/></Document>";
+        let xml = wrap(&header, document).unwrap();
+
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<BizMsg><AppHdr xmlns="));
+        assert_eq!(xml.matches("<?xml").count(), 1);
+
+        let message = BusinessMessage::parse(&xml).unwrap();
+        let parsed = message.header.unwrap();
+        assert_eq!(parsed.from_bic(), Some("DLTRAEADXXX"));
+        assert_eq!(parsed.msg_def_idr, "pain.002.001.14");
+        assert!(!parsed.is_possible_duplicate());
+        assert!(message.document.starts_with("<Document"));
+    }
+
+    #[test]
+    fn test_message_type_of() {
+        assert_eq!(message_type_of("pain.001.001.09"), Some("pain.001"));
+        assert_eq!(message_type_of("trck.001"), Some("trck.001"));
+        assert_eq!(message_type_of("pain"), None);
+    }
+}
@@ -1,17 +1,34 @@
 // ISO 20022 Message Parsers
-// Supports pain.001, pacs.008, camt.054, pacs.002, pain.002, camt.053
+// Supports pain.001 (single message or streamed bulk file), pacs.008, camt.054, pacs.002, pain.002,
+// camt.053, pacs.004, camt.056 (inbound) and camt.029 (outbound), trck.001 / trck.002 (payment tracking).
+// Any of them may arrive wrapped with a head.001 Business Application Header.
 
 pub mod pain001;
+pub mod pain001_stream;
 pub mod pacs008;
 pub mod camt054;
 pub mod pacs002;
 pub mod pain002;
 pub mod camt053;
+pub mod pacs004;
+pub mod camt056;
+pub mod camt029;
+pub mod trck001;
+pub mod trck002;
+pub mod head001;
+pub mod outbound;
 
 // Re-export commonly used types
 pub use pain001::{parse_pain001, to_canonical as pain001_to_canonical};
+pub use pain001_stream::{Pain001Stream, BulkTransaction, TransactionRejection};
 pub use pacs008::{parse_pacs008, to_canonical as pacs008_to_canonical};
 pub use camt054::{parse_camt054, extract_funding_events, FundingEvent, is_credit_event, is_booked};
-pub use pacs002::{parse_pacs002, to_payment_status_reports, PaymentStatusReport};
-pub use pain002::{parse_pain002, to_customer_payment_status, CustomerPaymentStatus};
+pub use pacs002::{parse_pacs002, to_payment_status_reports, build_pacs002, PaymentStatusReport};
+pub use pain002::{parse_pain002, to_customer_payment_status, build_pain002, build_pain002_batch, CustomerPaymentStatus};
 pub use camt053::{parse_camt053, to_statement_summaries, StatementSummary};
+pub use pacs004::{parse_pacs004, to_payment_returns, PaymentReturn};
+pub use camt056::{parse_camt056, to_cancellation_requests, CancellationRequest};
+pub use camt029::{build_camt029, rejection_reason, CancellationResolution, CancellationStatus};
+pub use trck001::{parse_trck001, to_tracker_updates, build_trck001, TrackerUpdate};
+pub use trck002::build_trck002;
+pub use head001::{AppHdr, BusinessMessage};
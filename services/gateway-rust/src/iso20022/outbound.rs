@@ -0,0 +1,166 @@
+// Outbound ISO 20022 helpers - status codes, identifiers and XML rendering
+// Shared by the pain.002 / pacs.002 builders; documents leave the gateway wrapped in a head.001 AppHdr
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use rust_decimal::Decimal;
+
+use super::head001::{self, AppHdr};
+use crate::models::canonical::{Currency, PaymentStatus, StatusReason};
+
+/// Transaction status (ExternalPaymentTransactionStatus1Code) as reported in TxSts and GrpSts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum IsoTransactionStatus {
+    Actc,   // AcceptedTechnicalValidation
+    Accp,   // AcceptedCustomerProfile
+    Acsp,   // AcceptedSettlementInProcess
+    Acsc,   // AcceptedSettlementCompleted
+    Pdng,   // Pending
+    Part,   // PartiallyAccepted (group level only)
+    Rjct,   // Rejected
+}
+
+impl IsoTransactionStatus {
+    pub fn code(&self) -> &'static str {
+        match self {
+            IsoTransactionStatus::Actc => "ACTC",
+            IsoTransactionStatus::Accp => "ACCP",
+            IsoTransactionStatus::Acsp => "ACSP",
+            IsoTransactionStatus::Acsc => "ACSC",
+            IsoTransactionStatus::Pdng => "PDNG",
+            IsoTransactionStatus::Part => "PART",
+            IsoTransactionStatus::Rjct => "RJCT",
+        }
+    }
+
+    /// Map a DelTran payment status to the ISO status reported to the originator
+    pub fn from_payment_status(status: &PaymentStatus) -> Self {
+        match status {
+            PaymentStatus::Received | PaymentStatus::Validated => IsoTransactionStatus::Actc,
+            PaymentStatus::Pending | PaymentStatus::PendingFunding => IsoTransactionStatus::Pdng,
+            PaymentStatus::Accepted
+            | PaymentStatus::Funded
+            | PaymentStatus::ReadyForClearing
+            | PaymentStatus::Clearing
+            | PaymentStatus::Netted => IsoTransactionStatus::Accp,
+            PaymentStatus::ReadyForSettlement | PaymentStatus::Settling => IsoTransactionStatus::Acsp,
+            PaymentStatus::Executed | PaymentStatus::Reconciled | PaymentStatus::Completed => IsoTransactionStatus::Acsc,
+            PaymentStatus::Rejected
+            | PaymentStatus::Failed
+            | PaymentStatus::Cancelled
+            | PaymentStatus::Returned => IsoTransactionStatus::Rjct,
+        }
+    }
+
+    /// Derive the group status (GrpSts) from the statuses of all transactions in the group
+    pub fn group_status(statuses: &[IsoTransactionStatus]) -> Option<Self> {
+        let first = *statuses.first()?;
+        if statuses.iter().all(|s| *s == first) {
+            return Some(first);
+        }
+        if statuses.contains(&IsoTransactionStatus::Rjct) {
+            return Some(IsoTransactionStatus::Part);
+        }
+        if statuses.contains(&IsoTransactionStatus::Pdng) {
+            return Some(IsoTransactionStatus::Pdng);
+        }
+        Some(IsoTransactionStatus::Accp)
+    }
+}
+
+/// Generated outbound report with the header fields needed for persistence
+#[derive(Debug, Clone)]
+pub struct OutboundDocument {
+    pub message_definition: &'static str,
+    pub message_id: String,
+    pub group_status: Option<String>,
+    pub xml: String,
+}
+
+impl OutboundDocument {
+    /// Wrap the document with an AppHdr (BizMsgIdr = MsgId) when sender and recipient are known
+    pub fn with_app_header(mut self, from_bic: Option<&str>, to_bic: Option<&str>) -> Result<Self> {
+        if let (Some(from_bic), Some(to_bic)) = (from_bic, to_bic) {
+            let header = AppHdr::new(from_bic, to_bic, self.message_definition, &self.message_id);
+            self.xml = head001::wrap(&header, &self.xml)?;
+        }
+        Ok(self)
+    }
+}
+
+/// Reason element content (StsRsnInf/Rsn) - Cd for ISO external codes, Prtry otherwise
+pub fn reason_code_parts(reason: &StatusReason) -> (Option<String>, Option<String>) {
+    if (1..=4).contains(&reason.code.len()) {
+        (Some(reason.code.clone()), None)
+    } else {
+        (None, Some(reason.code.chars().take(35).collect()))
+    }
+}
+
+/// Additional info lines for a reason (AddtlInf is Max105Text)
+pub fn reason_additional_info(reason: &StatusReason) -> Vec<String> {
+    std::iter::once(&reason.description)
+        .chain(reason.additional_info.iter())
+        .filter(|text| !text.is_empty())
+        .map(|text| text.chars().take(105).collect())
+        .collect()
+}
+
+/// Generate a Max35Text identifier with the given prefix (MsgId, StsId)
+pub fn generate_identifier(prefix: &str) -> String {
+    let mut id = format!("{}-{}", prefix, Uuid::new_v4().simple());
+    id.truncate(35);
+    id
+}
+
+/// ISODateTime for CreDtTm / AccptncDtTm
+pub fn iso_date_time(dt: chrono::DateTime<chrono::Utc>) -> String {
+    dt.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
+/// ActiveCurrencyAndAmount value with exactly the minor units of its currency
+pub fn render_amount(currency: Currency, amount: Decimal) -> Result<String> {
+    Ok(currency.format_amount(amount)?)
+}
+
+/// Serialize an ISO document struct to XML with declaration
+pub fn render_xml<T: Serialize>(document: &T) -> Result<String> {
+    let body = quick_xml::se::to_string(document).context("Failed to serialize ISO 20022 document")?;
+    Ok(format!("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n{}", body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_mapping() {
+        assert_eq!(IsoTransactionStatus::from_payment_status(&PaymentStatus::Validated).code(), "ACTC");
+        assert_eq!(IsoTransactionStatus::from_payment_status(&PaymentStatus::PendingFunding).code(), "PDNG");
+        assert_eq!(IsoTransactionStatus::from_payment_status(&PaymentStatus::Funded).code(), "ACCP");
+        assert_eq!(IsoTransactionStatus::from_payment_status(&PaymentStatus::Completed).code(), "ACSC");
+        assert_eq!(IsoTransactionStatus::from_payment_status(&PaymentStatus::Rejected).code(), "RJCT");
+    }
+
+    #[test]
+    fn test_group_status() {
+        use IsoTransactionStatus::*;
+        assert_eq!(IsoTransactionStatus::group_status(&[Accp, Accp]), Some(Accp));
+        assert_eq!(IsoTransactionStatus::group_status(&[Accp, Rjct]), Some(Part));
+        assert_eq!(IsoTransactionStatus::group_status(&[Actc, Pdng]), Some(Pdng));
+        assert_eq!(IsoTransactionStatus::group_status(&[]), None);
+    }
+
+    #[test]
+    fn test_reason_code_parts() {
+        let iso = StatusReason { code: "AM04".to_string(), description: "Insufficient funds".to_string(), additional_info: None };
+        assert_eq!(reason_code_parts(&iso), (Some("AM04".to_string()), None));
+
+        let proprietary = StatusReason { code: "COMPLIANCE_REJECT".to_string(), description: String::new(), additional_info: None };
+        assert_eq!(reason_code_parts(&proprietary), (None, Some("COMPLIANCE_REJECT".to_string())));
+        assert!(reason_additional_info(&proprietary).is_empty());
+    }
+}
@@ -2,15 +2,23 @@
 // Used for reporting status of payment instructions (Accept/Reject)
 
 use serde::{Deserialize, Serialize};
-use rust_decimal::Decimal;
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
 use anyhow::{Result, anyhow};
 
+use crate::models::canonical::CanonicalPayment;
+use super::outbound::{self, IsoTransactionStatus, OutboundDocument};
+
+/// Message definition of generated pacs.002 reports
+pub const PACS002_MESSAGE_DEFINITION: &str = "pacs.002.001.15";
+
 /// pacs.002 Document root
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct Document {
+    #[serde(rename = "@xmlns", default, skip_serializing_if = "Option::is_none")]
+    pub xmlns: Option<String>,
+
     #[serde(rename = "FIToFIPmtStsRpt")]
     pub fi_to_fi_payment_status_report: FIToFIPaymentStatusReport,
 }
@@ -19,10 +27,10 @@ pub struct Document {
 #[serde(rename_all = "PascalCase")]
 pub struct FIToFIPaymentStatusReport {
     pub grp_hdr: GroupHeader,
+    #[serde(rename = "OrgnlGrpInfAndSts", skip_serializing_if = "Option::is_none")]
+    pub original_group_info_and_status: Option<OriginalGroupInfoAndStatus>,
     #[serde(rename = "TxInfAndSts")]
     pub transaction_info_and_status: Vec<TransactionInfoAndStatus>,
-    #[serde(rename = "OrgnlGrpInfAndSts")]
-    pub original_group_info_and_status: Option<OriginalGroupInfoAndStatus>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -35,64 +43,66 @@ pub struct GroupHeader {
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct TransactionInfoAndStatus {
-    #[serde(rename = "StsId")]
+    #[serde(rename = "StsId", skip_serializing_if = "Option::is_none")]
     pub status_id: Option<String>,
 
-    #[serde(rename = "OrgnlInstrId")]
+    #[serde(rename = "OrgnlInstrId", skip_serializing_if = "Option::is_none")]
     pub original_instruction_id: Option<String>,
 
-    #[serde(rename = "OrgnlEndToEndId")]
+    #[serde(rename = "OrgnlEndToEndId", skip_serializing_if = "Option::is_none")]
     pub original_end_to_end_id: Option<String>,
 
-    #[serde(rename = "OrgnlTxId")]
+    #[serde(rename = "OrgnlTxId", skip_serializing_if = "Option::is_none")]
     pub original_transaction_id: Option<String>,
 
-    #[serde(rename = "OrgnlUETR")]
+    #[serde(rename = "OrgnlUETR", skip_serializing_if = "Option::is_none")]
     pub original_uetr: Option<String>,
 
     #[serde(rename = "TxSts")]
     pub transaction_status: String, // ACCP, ACSC, ACSP, ACTC, ACWC, PART, PDNG, RJCT
 
-    #[serde(rename = "StsRsnInf")]
+    #[serde(rename = "StsRsnInf", skip_serializing_if = "Option::is_none")]
     pub status_reason_info: Option<Vec<StatusReasonInfo>>,
 
-    #[serde(rename = "AccptncDtTm")]
+    #[serde(rename = "AccptncDtTm", skip_serializing_if = "Option::is_none")]
     pub acceptance_date_time: Option<String>,
 
-    #[serde(rename = "AcctSvcrRef")]
+    #[serde(rename = "AcctSvcrRef", skip_serializing_if = "Option::is_none")]
     pub account_servicer_reference: Option<String>,
 
-    #[serde(rename = "ClrSysRef")]
+    #[serde(rename = "ClrSysRef", skip_serializing_if = "Option::is_none")]
     pub clearing_system_reference: Option<String>,
 
-    #[serde(rename = "OrgnlTxRef")]
+    #[serde(rename = "OrgnlTxRef", skip_serializing_if = "Option::is_none")]
     pub original_transaction_reference: Option<OriginalTransactionReference>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct StatusReasonInfo {
-    #[serde(rename = "Rsn")]
+    #[serde(rename = "Rsn", skip_serializing_if = "Option::is_none")]
     pub reason: Option<Reason>,
 
-    #[serde(rename = "AddtlInf")]
+    #[serde(rename = "AddtlInf", skip_serializing_if = "Option::is_none")]
     pub additional_info: Option<Vec<String>>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct Reason {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub cd: Option<String>, // ISO 20022 reason code
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub prtry: Option<String>, // Proprietary reason code
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct OriginalTransactionReference {
-    #[serde(rename = "IntrBkSttlmAmt")]
+    #[serde(rename = "IntrBkSttlmAmt", skip_serializing_if = "Option::is_none")]
     pub interbank_settlement_amount: Option<Amount>,
 
-    #[serde(rename = "IntrBkSttlmDt")]
+    #[serde(rename = "IntrBkSttlmDt", skip_serializing_if = "Option::is_none")]
     pub interbank_settlement_date: Option<String>,
 }
 
@@ -114,7 +124,7 @@ pub struct OriginalGroupInfoAndStatus {
     #[serde(rename = "OrgnlMsgNmId")]
     pub original_message_name_id: String,
 
-    #[serde(rename = "GrpSts")]
+    #[serde(rename = "GrpSts", skip_serializing_if = "Option::is_none")]
     pub group_status: Option<String>,
 }
 
@@ -204,6 +214,73 @@ pub fn to_payment_status_reports(document: &Document) -> Result<Vec<PaymentStatu
     Ok(reports)
 }
 
+/// Build an outbound pacs.002 for payments of one original pacs.008.
+/// Group status is only reported when all transactions of the original message are included.
+pub fn build_pacs002(payments: &[CanonicalPayment], include_group_status: bool) -> Result<OutboundDocument> {
+    let first = payments.first()
+        .ok_or_else(|| anyhow!("Cannot build pacs.002 without transactions"))?;
+
+    let statuses: Vec<IsoTransactionStatus> = payments.iter()
+        .map(|p| IsoTransactionStatus::from_payment_status(&p.status))
+        .collect();
+
+    let group_status = if include_group_status {
+        IsoTransactionStatus::group_status(&statuses).map(|s| s.code().to_string())
+    } else {
+        None
+    };
+
+    let transaction_info_and_status = payments.iter()
+        .zip(&statuses)
+        .map(|(payment, status)| TransactionInfoAndStatus {
+            status_id: Some(outbound::generate_identifier("STS")),
+            original_instruction_id: Some(payment.instruction_id.clone()),
+            original_end_to_end_id: Some(payment.end_to_end_id.clone()),
+            original_transaction_id: None,
+            original_uetr: payment.uetr.map(|u| u.to_string()),
+            transaction_status: status.code().to_string(),
+            status_reason_info: payment.status_reason.as_ref().map(|reason| {
+                let (cd, prtry) = outbound::reason_code_parts(reason);
+                let additional_info = outbound::reason_additional_info(reason);
+                vec![StatusReasonInfo {
+                    reason: Some(Reason { cd, prtry }),
+                    additional_info: (!additional_info.is_empty()).then_some(additional_info),
+                }]
+            }),
+            acceptance_date_time: matches!(status, IsoTransactionStatus::Accp | IsoTransactionStatus::Acsp | IsoTransactionStatus::Acsc)
+                .then(|| outbound::iso_date_time(payment.updated_at)),
+            account_servicer_reference: None,
+            clearing_system_reference: None,
+            original_transaction_reference: None,
+        })
+        .collect();
+
+    let message_id = outbound::generate_identifier("PACS002");
+
+    let document = Document {
+        xmlns: Some(format!("urn:iso:std:iso:20022:tech:xsd:{}", PACS002_MESSAGE_DEFINITION)),
+        fi_to_fi_payment_status_report: FIToFIPaymentStatusReport {
+            grp_hdr: GroupHeader {
+                msg_id: message_id.clone(),
+                cre_dt_tm: outbound::iso_date_time(Utc::now()),
+            },
+            original_group_info_and_status: Some(OriginalGroupInfoAndStatus {
+                original_message_id: first.message_id.clone(),
+                original_message_name_id: "pacs.008.001.13".to_string(),
+                group_status: group_status.clone(),
+            }),
+            transaction_info_and_status,
+        },
+    };
+
+    Ok(OutboundDocument {
+        message_definition: PACS002_MESSAGE_DEFINITION,
+        xml: outbound::render_xml(&document)?,
+        message_id,
+        group_status,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -264,4 +341,42 @@ mod tests {
         assert!(matches!(reports[0].status, PaymentStatus::Rejected));
         assert_eq!(reports[0].reason_code, Some("AM04".to_string()));
     }
+
+    #[test]
+    fn test_build_pacs002_roundtrip() {
+        use crate::models::canonical::{Currency, Party, FinancialInstitution, PaymentStatus as CanonicalStatus, StatusReason};
+        use crate::validation::xsd::{SchemaModel, XsdValidator, ValidationMode};
+        use rust_decimal_macros::dec;
+
+        let party = |name: &str| Party { name: name.to_string(), postal_address: None, identification: None, country_code: "AE".to_string() };
+        let agent = |bic: &str| FinancialInstitution { bic: Some(bic.to_string()), name: bic.to_string(), country_code: "AE".to_string(), clearing_system_member_id: None };
+
+        let mut payment = CanonicalPayment::new(
+            "E2E-1".to_string(), "INSTR-1".to_string(), "MSG-1".to_string(),
+            dec!(100.00), Currency::AED,
+            party("Debtor"), party("Creditor"), agent("BANKAEADXXX"), agent("ICICINBBXXX"),
+        );
+        payment.update_status(CanonicalStatus::Rejected, Some(StatusReason {
+            code: "AM04".to_string(),
+            description: "Insufficient funds".to_string(),
+            additional_info: None,
+        }));
+
+        let xml = build_pacs002(&[payment], true).unwrap().xml;
+
+        let xsd = std::fs::read_to_string(concat!(
+            env!("CARGO_MANIFEST_DIR"), "/../../iso20022/payments_clearing_and_settlement/pacs.002.001.15.xsd"
+        )).unwrap();
+        let validator = XsdValidator::new(vec![SchemaModel::parse(&xsd).unwrap()], ValidationMode::Strict);
+        assert!(validator.validate("pacs.002", &xml).is_ok(), "{:?}", validator.validate("pacs.002", &xml).err());
+
+        let document = parse_pacs002(&xml).unwrap();
+        let report = &document.fi_to_fi_payment_status_report;
+        assert_eq!(report.original_group_info_and_status.as_ref().unwrap().group_status.as_deref(), Some("RJCT"));
+
+        let reports = to_payment_status_reports(&document).unwrap();
+        assert_eq!(reports[0].original_end_to_end_id.as_deref(), Some("E2E-1"));
+        assert_eq!(reports[0].status_code, "RJCT");
+        assert_eq!(reports[0].reason_code.as_deref(), Some("AM04"));
+    }
 }
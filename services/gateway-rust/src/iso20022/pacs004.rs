@@ -0,0 +1,255 @@
+// pacs.004.001.14 - Payment Return
+// Returns funds of a previously settled payment (links to it via OrgnlUETR / OrgnlEndToEndId)
+
+use serde::{Deserialize, Serialize};
+use rust_decimal::Decimal;
+use chrono::NaiveDate;
+use uuid::Uuid;
+use anyhow::{Result, anyhow, Context};
+use std::str::FromStr;
+
+/// pacs.004 Document root
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Document {
+    #[serde(rename = "PmtRtr")]
+    pub payment_return: PaymentReturnMessage,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct PaymentReturnMessage {
+    pub grp_hdr: GroupHeader,
+
+    #[serde(rename = "OrgnlGrpInf", default)]
+    pub original_group_info: Option<OriginalGroupInformation>,
+
+    #[serde(rename = "TxInf", default)]
+    pub transaction_info: Vec<TransactionInformation>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct GroupHeader {
+    pub msg_id: String,
+    pub cre_dt_tm: String,
+
+    #[serde(rename = "NbOfTxs")]
+    pub number_of_transactions: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct OriginalGroupInformation {
+    #[serde(rename = "OrgnlMsgId")]
+    pub original_message_id: String,
+
+    #[serde(rename = "OrgnlMsgNmId")]
+    pub original_message_name_id: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct TransactionInformation {
+    #[serde(rename = "RtrId", default)]
+    pub return_id: Option<String>,
+
+    #[serde(rename = "OrgnlGrpInf", default)]
+    pub original_group_info: Option<OriginalGroupInformation>,
+
+    #[serde(rename = "OrgnlInstrId", default)]
+    pub original_instruction_id: Option<String>,
+
+    #[serde(rename = "OrgnlEndToEndId", default)]
+    pub original_end_to_end_id: Option<String>,
+
+    #[serde(rename = "OrgnlTxId", default)]
+    pub original_transaction_id: Option<String>,
+
+    #[serde(rename = "OrgnlUETR", default)]
+    pub original_uetr: Option<String>,
+
+    #[serde(rename = "OrgnlIntrBkSttlmAmt", default)]
+    pub original_interbank_settlement_amount: Option<Amount>,
+
+    #[serde(rename = "RtrdIntrBkSttlmAmt")]
+    pub returned_interbank_settlement_amount: Amount,
+
+    #[serde(rename = "IntrBkSttlmDt", default)]
+    pub interbank_settlement_date: Option<String>,
+
+    #[serde(rename = "RtrRsnInf", default)]
+    pub return_reason_info: Vec<ReturnReasonInfo>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ReturnReasonInfo {
+    #[serde(rename = "Rsn", default)]
+    pub reason: Option<Reason>,
+
+    #[serde(rename = "AddtlInf", default)]
+    pub additional_info: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Reason {
+    pub cd: Option<String>,    // ExternalReturnReason1Code (AC04, AM09, MS03, ...)
+    pub prtry: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Amount {
+    #[serde(rename = "@Ccy")]
+    pub currency: String,
+
+    #[serde(rename = "$text")]
+    pub value: String,
+}
+
+/// Payment return representation for DelTran
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaymentReturn {
+    pub return_id: String,
+    pub return_message_id: String,
+    pub original_message_id: Option<String>,
+    pub original_end_to_end_id: Option<String>,
+    pub original_transaction_id: Option<String>,
+    pub original_uetr: Option<Uuid>,
+    pub returned_amount: Decimal,
+    pub currency: String,
+    pub settlement_date: Option<NaiveDate>,
+    pub reason_code: Option<String>,
+    pub reason_description: Option<String>,
+}
+
+/// Parse pacs.004 XML message
+pub fn parse_pacs004(xml: &str) -> Result<Document> {
+    quick_xml::de::from_str(xml)
+        .map_err(|e| anyhow!("Failed to parse pacs.004 XML: {}", e))
+}
+
+/// Convert pacs.004 to DelTran payment returns
+pub fn to_payment_returns(document: &Document) -> Result<Vec<PaymentReturn>> {
+    let message = &document.payment_return;
+    let mut returns = Vec::new();
+
+    for tx in &message.transaction_info {
+        if tx.original_uetr.is_none() && tx.original_end_to_end_id.is_none() {
+            return Err(anyhow!(
+                "pacs.004 transaction {} has neither OrgnlUETR nor OrgnlEndToEndId",
+                tx.return_id.as_deref().unwrap_or("<no RtrId>")
+            ));
+        }
+
+        let original_uetr = tx.original_uetr.as_ref()
+            .map(|uetr| Uuid::parse_str(uetr).context(format!("Invalid OrgnlUETR: {}", uetr)))
+            .transpose()?;
+
+        let returned_amount = Decimal::from_str(&tx.returned_interbank_settlement_amount.value)
+            .context("Invalid RtrdIntrBkSttlmAmt")?;
+
+        // First reason is authoritative, additional info is joined for the audit trail
+        let first_reason = tx.return_reason_info.first();
+        let reason_code = first_reason
+            .and_then(|r| r.reason.as_ref())
+            .and_then(|r| r.cd.clone().or_else(|| r.prtry.clone()));
+        let reason_description = first_reason
+            .filter(|r| !r.additional_info.is_empty())
+            .map(|r| r.additional_info.join(" "));
+
+        let original_message_id = tx.original_group_info.as_ref()
+            .or(message.original_group_info.as_ref())
+            .map(|g| g.original_message_id.clone());
+
+        returns.push(PaymentReturn {
+            return_id: tx.return_id.clone()
+                .unwrap_or_else(|| Uuid::new_v4().to_string()),
+            return_message_id: message.grp_hdr.msg_id.clone(),
+            original_message_id,
+            original_end_to_end_id: tx.original_end_to_end_id.clone(),
+            original_transaction_id: tx.original_transaction_id.clone(),
+            original_uetr,
+            returned_amount,
+            currency: tx.returned_interbank_settlement_amount.currency.clone(),
+            settlement_date: tx.interbank_settlement_date.as_ref()
+                .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok()),
+            reason_code,
+            reason_description,
+        });
+    }
+
+    Ok(returns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PACS004: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Document xmlns="urn:iso:std:iso:20022:tech:xsd:pacs.004.001.14">
+  <PmtRtr>
+    <GrpHdr>
+      <MsgId>RTR-20250120-001</MsgId>
+      <CreDtTm>2025-01-20T09:00:00Z</CreDtTm>
+      <NbOfTxs>1</NbOfTxs>
+      <SttlmInf><SttlmMtd>CLRG</SttlmMtd></SttlmInf>
+    </GrpHdr>
+    <TxInf>
+      <RtrId>RTR001</RtrId>
+      <OrgnlGrpInf>
+        <OrgnlMsgId>MSG-1</OrgnlMsgId>
+        <OrgnlMsgNmId>pacs.008.001.13</OrgnlMsgNmId>
+      </OrgnlGrpInf>
+      <OrgnlEndToEndId>E2E123456</OrgnlEndToEndId>
+      <OrgnlUETR>8a562c67-ca16-48ba-b074-65581be6f011</OrgnlUETR>
+      <RtrdIntrBkSttlmAmt Ccy="AED">1500.50</RtrdIntrBkSttlmAmt>
+      <IntrBkSttlmDt>2025-01-20</IntrBkSttlmDt>
+      <RtrRsnInf>
+        <Rsn><Cd>AC04</Cd></Rsn>
+        <AddtlInf>Account closed</AddtlInf>
+      </RtrRsnInf>
+    </TxInf>
+  </PmtRtr>
+</Document>"#;
+
+    #[test]
+    fn test_parse_pacs004() {
+        let document = parse_pacs004(PACS004).unwrap();
+        assert_eq!(document.payment_return.grp_hdr.msg_id, "RTR-20250120-001");
+        assert_eq!(document.payment_return.transaction_info.len(), 1);
+
+        let returns = to_payment_returns(&document).unwrap();
+        let ret = &returns[0];
+        assert_eq!(ret.return_id, "RTR001");
+        assert_eq!(ret.original_message_id.as_deref(), Some("MSG-1"));
+        assert_eq!(ret.original_end_to_end_id.as_deref(), Some("E2E123456"));
+        assert_eq!(ret.original_uetr, Some(Uuid::parse_str("8a562c67-ca16-48ba-b074-65581be6f011").unwrap()));
+        assert_eq!(ret.returned_amount, Decimal::from_str("1500.50").unwrap());
+        assert_eq!(ret.currency, "AED");
+        assert_eq!(ret.reason_code.as_deref(), Some("AC04"));
+        assert_eq!(ret.reason_description.as_deref(), Some("Account closed"));
+        assert_eq!(ret.settlement_date, NaiveDate::from_ymd_opt(2025, 1, 20));
+    }
+
+    #[test]
+    fn test_sample_conforms_to_xsd() {
+        use crate::validation::xsd::{SchemaModel, XsdValidator, ValidationMode};
+
+        let xsd = std::fs::read_to_string(concat!(
+            env!("CARGO_MANIFEST_DIR"), "/../../iso20022/payments_clearing_and_settlement/pacs.004.001.14.xsd"
+        )).unwrap();
+        let validator = XsdValidator::new(vec![SchemaModel::parse(&xsd).unwrap()], ValidationMode::Strict);
+        assert!(validator.validate("pacs.004", PACS004).is_ok(), "{:?}", validator.validate("pacs.004", PACS004).err());
+    }
+
+    #[test]
+    fn test_return_without_original_reference_rejected() {
+        let xml = PACS004
+            .replace("<OrgnlEndToEndId>E2E123456</OrgnlEndToEndId>", "")
+            .replace("<OrgnlUETR>8a562c67-ca16-48ba-b074-65581be6f011</OrgnlUETR>", "");
+        let document = parse_pacs004(&xml).unwrap();
+        assert!(to_payment_returns(&document).is_err());
+    }
+}
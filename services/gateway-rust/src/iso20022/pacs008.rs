@@ -185,10 +185,10 @@ pub struct CategoryPurpose {
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct ActiveCurrencyAndAmount {
-    #[serde(rename = "Ccy")]
+    #[serde(rename = "@Ccy")]
     pub currency: String,
 
-    #[serde(rename = "$value")]
+    #[serde(rename = "$text")]
     pub value: String,
 }
 
@@ -374,8 +374,8 @@ pub fn to_canonical(pacs008: &Document) -> Result<Vec<CanonicalPayment>> {
         let amount = Decimal::from_str(&tx.interbank_settlement_amount.value)
             .context("Invalid amount")?;
 
-        let currency = Currency::from_str(&tx.interbank_settlement_amount.currency)
-            .unwrap_or(Currency::Usd);
+        let currency: Currency = tx.interbank_settlement_amount.currency.parse()?;
+        currency.check_amount(amount)?;
 
         // Extract UETR if present
         let uetr = tx.payment_identification.uetr.as_ref()
@@ -469,12 +469,15 @@ pub fn to_canonical(pacs008: &Document) -> Result<Vec<CanonicalPayment>> {
             value_date: None,
             status: PaymentStatus::Received,
             status_reason: None,
-            charge_bearer: ChargeBearer::Shar,
+            charge_bearer: tx.charge_bearer.as_deref()
+                .and_then(ChargeBearer::from_code)
+                .unwrap_or(ChargeBearer::Shar),
             charges: vec![],
             remittance_info: tx.remittance_information.as_ref()
                 .and_then(|ri| ri.unstructured.first().cloned())
                 .unwrap_or_default(),
             remittance_structured: None,
+            purpose_code: tx.purpose.as_ref().and_then(|p| p.code.clone()),
             risk_score: None,
             compliance_status: ComplianceStatus::Pending,
             sanctions_checked: false,
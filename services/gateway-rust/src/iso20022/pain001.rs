@@ -4,7 +4,7 @@
 use quick_xml::de::from_str;
 use serde::{Deserialize, Serialize};
 use anyhow::{Result, Context};
-use chrono::{DateTime, Utc, NaiveDate};
+use chrono::NaiveDate;
 use rust_decimal::Decimal;
 use crate::models::canonical::*;
 
@@ -49,7 +49,7 @@ pub struct PaymentInformation {
     pub dbtr: Party,
     pub dbtr_acct: Account,
     pub dbtr_agt: FinancialInstitutionIdentification,
-    #[serde(rename = "CdtTrfTxInf")]
+    #[serde(rename = "CdtTrfTxInf", default)]
     pub credit_transfer_tx_info: Vec<CreditTransferTransactionInformation>,
 }
 
@@ -310,7 +310,8 @@ pub fn to_canonical(pain001: &Document) -> Result<Vec<CanonicalPayment>> {
     Ok(payments)
 }
 
-fn convert_transaction(
+/// Convert a single CdtTrfTxInf in the context of its group header and PmtInf
+pub fn convert_transaction(
     grp_hdr: &GroupHeader,
     pmt_inf: &PaymentInformation,
     tx_inf: &CreditTransferTransactionInformation,
@@ -321,8 +322,8 @@ fn convert_transaction(
         .context(format!("Failed to parse amount: {}", amount_str))?;
 
     // Parse currency
-    let currency = Currency::from_str(&tx_inf.amt.instructed_amount.currency)
-        .context(format!("Unsupported currency: {}", tx_inf.amt.instructed_amount.currency))?;
+    let currency: Currency = tx_inf.amt.instructed_amount.currency.parse()?;
+    currency.check_amount(amount)?;
 
     // Convert debtor
     let debtor = convert_party(&pmt_inf.dbtr)?;
@@ -366,15 +367,17 @@ fn convert_transaction(
 
     // Set charge bearer
     if let Some(chrg_br) = &tx_inf.chrg_br {
-        payment.charge_bearer = match chrg_br.as_str() {
-            "SHAR" => ChargeBearer::Shar,
-            "SLEV" => ChargeBearer::Slev,
-            "DEBT" => ChargeBearer::Debt,
-            "CRED" => ChargeBearer::Cred,
-            _ => ChargeBearer::Shar, // Default
-        };
+        payment.charge_bearer = ChargeBearer::from_code(chrg_br).unwrap_or(ChargeBearer::Shar);
     }
 
+    // Requested execution date (ISODate, or the date part of an ISODateTime)
+    payment.requested_execution_date = pmt_inf.reqd_exctn_dt.as_ref()
+        .and_then(|d| d.dt.as_deref().or(d.dt_tm.as_deref()))
+        .and_then(|d| NaiveDate::parse_from_str(d.get(..10)?, "%Y-%m-%d").ok());
+
+    // Purpose code
+    payment.purpose_code = tx_inf.purp.as_ref().and_then(|p| p.cd.clone());
+
     // Set remittance information
     if let Some(rmt_inf) = &tx_inf.rmt_inf {
         if let Some(ustrd) = &rmt_inf.ustrd {
@@ -0,0 +1,459 @@
+// pain.001 streaming reader - bulk files with thousands of CdtTrfTxInf
+// The file is read event by event: only the group header, the current PmtInf header and
+// one transaction are held in memory at a time. Every transaction is converted on its own,
+// so a bad transaction is rejected without failing the rest of the file.
+// The Document may be wrapped in an envelope with a head.001 AppHdr.
+
+use std::io::BufRead;
+
+use anyhow::{Result, Context, anyhow};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::{Reader, Writer};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::models::canonical::{CanonicalPayment, Currency};
+use super::head001::AppHdr;
+use super::pain001::{self, CreditTransferTransactionInformation, GroupHeader, PaymentInformation};
+
+/// Why a single transaction was not accepted (ExternalStatusReason1Code + text)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionRejection {
+    pub code: &'static str,
+    pub info: String,
+}
+
+impl TransactionRejection {
+    /// FF01 - InvalidFileFormat (schema violation, unreadable element)
+    pub fn invalid_format(info: impl Into<String>) -> Self {
+        Self { code: "FF01", info: info.into() }
+    }
+}
+
+/// One CdtTrfTxInf of a bulk file
+#[derive(Debug)]
+pub struct BulkTransaction {
+    pub sequence: usize,                    // 1-based position in the file
+    pub payment_info_id: Option<String>,
+    pub debtor_agent_bic: Option<String>,
+    pub end_to_end_id: Option<String>,
+    pub instruction_id: Option<String>,
+    pub uetr: Option<Uuid>,
+    /// Single-transaction pain.001 (group header + PmtInf header + this CdtTrfTxInf),
+    /// used to schema-validate the transaction independently of the rest of the file
+    pub document: String,
+    pub payment: Result<CanonicalPayment, TransactionRejection>,
+}
+
+/// Identifiers read leniently, so even an unreadable transaction can be reported
+#[derive(Debug, Default, Deserialize)]
+struct TransactionReference {
+    #[serde(rename = "PmtId", default)]
+    pmt_id: Option<PaymentReference>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PaymentReference {
+    #[serde(rename = "InstrId")]
+    instr_id: Option<String>,
+    #[serde(rename = "EndToEndId")]
+    end_to_end_id: Option<String>,
+    #[serde(rename = "UETR")]
+    uetr: Option<String>,
+}
+
+/// Document envelope and group header shared by all transactions of the file
+struct GroupContext {
+    grp_hdr: GroupHeader,
+    grp_hdr_xml: String,
+    envelope_open: String,      // <Document ...><CstmrCdtTrfInitn>
+    envelope_close: String,     // </CstmrCdtTrfInitn></Document>
+}
+
+/// PmtInf currently being read
+struct PaymentInfoContext {
+    header_xml: String,         // PmtInf start tag and the children preceding the first CdtTrfTxInf
+    close_xml: String,
+    header: Option<Result<PaymentInformation, String>>,   // parsed on the first CdtTrfTxInf
+}
+
+impl PaymentInfoContext {
+    fn new(start: &BytesStart) -> Result<Self> {
+        Ok(Self {
+            header_xml: render(Event::Start(start.clone()))?,
+            close_xml: close_tag(start),
+            header: None,
+        })
+    }
+
+    fn transaction(&mut self, group: &GroupContext, sequence: usize, tx_xml: String) -> BulkTransaction {
+        let header = self.header.get_or_insert_with(|| {
+            quick_xml::de::from_str::<PaymentInformation>(&format!("{}{}", self.header_xml, self.close_xml))
+                .map_err(|e| format!("Invalid PmtInf header: {}", e))
+        });
+
+        let ids = quick_xml::de::from_str::<TransactionReference>(&tx_xml)
+            .unwrap_or_default()
+            .pmt_id
+            .unwrap_or_default();
+
+        let payment = match header {
+            Ok(pmt_inf) => convert(&group.grp_hdr, pmt_inf, &tx_xml),
+            Err(reason) => Err(TransactionRejection::invalid_format(reason.clone())),
+        };
+        let pmt_inf = header.as_ref().ok();
+
+        BulkTransaction {
+            sequence,
+            payment_info_id: pmt_inf.map(|p| p.pmt_inf_id.clone()),
+            debtor_agent_bic: pmt_inf.and_then(|p| p.dbtr_agt.fin_instn_id.bicfi.clone()),
+            end_to_end_id: ids.end_to_end_id,
+            instruction_id: ids.instr_id,
+            uetr: ids.uetr.and_then(|u| Uuid::parse_str(&u).ok()),
+            document: format!(
+                "{}{}{}{}{}{}",
+                group.envelope_open, group.grp_hdr_xml, self.header_xml, tx_xml, self.close_xml, group.envelope_close
+            ),
+            payment,
+        }
+    }
+}
+
+/// Iterator over the transactions of a pain.001, one CdtTrfTxInf at a time.
+/// Items are `Err` only when the file itself cannot be read any further (malformed XML).
+pub struct Pain001Stream<R: BufRead> {
+    reader: Reader<R>,
+    app_header: Option<AppHdr>,
+    group: GroupContext,
+    payment_info: Option<PaymentInfoContext>,
+    sequence: usize,
+}
+
+impl<R: BufRead> Pain001Stream<R> {
+    /// Read the document (and the AppHdr of an enveloped file) up to and including GrpHdr
+    pub fn open(source: R) -> Result<Self> {
+        let mut reader = Reader::from_reader(source);
+        reader.trim_text(true);
+
+        let mut app_header = None;
+        let mut wrapped = false;
+        let mut envelope_open = String::new();
+        let mut envelope_close = Vec::new();
+        let mut buf = Vec::new();
+
+        loop {
+            buf.clear();
+            match reader.read_event_into(&mut buf).context("Malformed pain.001")? {
+                Event::Start(e) if e.local_name().as_ref() == b"GrpHdr" && envelope_close.len() == 2 => {
+                    let grp_hdr_xml = capture(&mut reader, e.into_owned())?;
+                    let grp_hdr = quick_xml::de::from_str::<GroupHeader>(&grp_hdr_xml)
+                        .context("Invalid pain.001 GrpHdr")?;
+
+                    envelope_close.reverse();
+                    return Ok(Self {
+                        reader,
+                        app_header,
+                        group: GroupContext {
+                            grp_hdr,
+                            grp_hdr_xml,
+                            envelope_open,
+                            envelope_close: envelope_close.concat(),
+                        },
+                        payment_info: None,
+                        sequence: 0,
+                    });
+                }
+                // Business message envelope: skip the wrapper, keep the AppHdr
+                Event::Start(e) if envelope_close.is_empty() && e.local_name().as_ref() == b"AppHdr" => {
+                    let header_xml = capture(&mut reader, e.into_owned())?;
+                    app_header = Some(quick_xml::de::from_str::<AppHdr>(&header_xml).context("Invalid head.001 AppHdr")?);
+                }
+                Event::Start(e) if envelope_close.is_empty() && !wrapped && app_header.is_none()
+                    && e.local_name().as_ref() != b"Document" => {
+                    wrapped = true;
+                }
+                Event::Start(e) => {
+                    let expected: &[u8] = if envelope_close.is_empty() { b"Document" } else { b"CstmrCdtTrfInitn" };
+                    if envelope_close.len() == 2 || e.local_name().as_ref() != expected {
+                        return Err(anyhow!(
+                            "Unexpected element <{}> - expected pain.001 Document/CstmrCdtTrfInitn/GrpHdr",
+                            String::from_utf8_lossy(e.name().as_ref())
+                        ));
+                    }
+                    envelope_open.push_str(&render(Event::Start(e.clone()))?);
+                    envelope_close.push(close_tag(&e));
+                }
+                Event::Eof => return Err(anyhow!("pain.001 has no GrpHdr")),
+                _ => {}
+            }
+        }
+    }
+
+    pub fn app_header(&self) -> Option<&AppHdr> {
+        self.app_header.as_ref()
+    }
+
+    pub fn group_header(&self) -> &GroupHeader {
+        &self.group.grp_hdr
+    }
+
+    /// GrpHdr/NbOfTxs, if numeric
+    pub fn declared_transactions(&self) -> Option<i32> {
+        self.group.grp_hdr.nb_of_txs.trim().parse().ok()
+    }
+
+    fn next_transaction(&mut self) -> Result<Option<BulkTransaction>> {
+        let mut buf = Vec::new();
+
+        loop {
+            buf.clear();
+            match self.reader.read_event_into(&mut buf).context("Malformed pain.001")? {
+                Event::Start(e) => {
+                    let start = e.into_owned();
+                    let name = start.local_name().as_ref().to_vec();
+
+                    match (&mut self.payment_info, name.as_slice()) {
+                        (None, b"PmtInf") => {
+                            self.payment_info = Some(PaymentInfoContext::new(&start)?);
+                        }
+                        (Some(payment_info), b"CdtTrfTxInf") => {
+                            let tx_xml = capture(&mut self.reader, start)?;
+                            self.sequence += 1;
+                            return Ok(Some(payment_info.transaction(&self.group, self.sequence, tx_xml)));
+                        }
+                        (Some(payment_info), _) => {
+                            let child_xml = capture(&mut self.reader, start)?;
+                            if payment_info.header.is_none() {
+                                payment_info.header_xml.push_str(&child_xml);
+                            }
+                        }
+                        // Supplementary data and other group level extensions are not used
+                        (None, _) => {
+                            capture(&mut self.reader, start)?;
+                        }
+                    }
+                }
+                Event::Empty(e) => {
+                    if let Some(payment_info) = self.payment_info.as_mut().filter(|p| p.header.is_none()) {
+                        payment_info.header_xml.push_str(&render(Event::Empty(e))?);
+                    }
+                }
+                Event::End(e) if e.local_name().as_ref() == b"PmtInf" => {
+                    self.payment_info = None;
+                }
+                Event::Eof => return Ok(None),
+                _ => {}
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for Pain001Stream<R> {
+    type Item = Result<BulkTransaction>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_transaction().transpose()
+    }
+}
+
+/// Convert one transaction, with ISO reason codes for the checks a bank reports individually
+fn convert(
+    grp_hdr: &GroupHeader,
+    pmt_inf: &PaymentInformation,
+    tx_xml: &str,
+) -> Result<CanonicalPayment, TransactionRejection> {
+    let tx: CreditTransferTransactionInformation = quick_xml::de::from_str(tx_xml)
+        .map_err(|e| TransactionRejection::invalid_format(format!("Invalid CdtTrfTxInf: {}", e)))?;
+
+    let instructed = &tx.amt.instructed_amount;
+    let amount = match instructed.value.trim().parse::<Decimal>() {
+        Ok(amount) if amount.is_zero() => {
+            return Err(TransactionRejection { code: "AM01", info: "Zero amount".to_string() });
+        }
+        Ok(amount) if amount.is_sign_positive() => amount,
+        _ => {
+            return Err(TransactionRejection { code: "AM12", info: format!("Invalid amount: {}", instructed.value) });
+        }
+    };
+    let Some(currency) = Currency::from_code(&instructed.currency) else {
+        return Err(TransactionRejection { code: "AM03", info: format!("Currency {} is not supported", instructed.currency) });
+    };
+    if let Err(e) = currency.check_amount(amount) {
+        return Err(TransactionRejection { code: "AM12", info: e.to_string() });
+    }
+
+    pain001::convert_transaction(grp_hdr, pmt_inf, &tx)
+        .map_err(|e| TransactionRejection::invalid_format(e.to_string()))
+}
+
+/// Copy an element and all its children (the start tag is already consumed) back to XML
+fn capture<R: BufRead>(reader: &mut Reader<R>, start: BytesStart<'static>) -> Result<String> {
+    let name = String::from_utf8_lossy(start.name().as_ref()).into_owned();
+    let mut writer = Writer::new(Vec::new());
+    writer.write_event(Event::Start(start))?;
+
+    let mut depth = 1;
+    let mut buf = Vec::new();
+    while depth > 0 {
+        buf.clear();
+        let event = reader.read_event_into(&mut buf)
+            .with_context(|| format!("Malformed pain.001 inside <{}>", name))?;
+        match &event {
+            Event::Start(_) => depth += 1,
+            Event::End(_) => depth -= 1,
+            Event::Eof => return Err(anyhow!("Unexpected end of file inside <{}>", name)),
+            _ => {}
+        }
+        writer.write_event(event)?;
+    }
+
+    Ok(String::from_utf8(writer.into_inner())?)
+}
+
+fn render(event: Event) -> Result<String> {
+    let mut writer = Writer::new(Vec::new());
+    writer.write_event(event)?;
+    Ok(String::from_utf8(writer.into_inner())?)
+}
+
+fn close_tag(start: &BytesStart) -> String {
+    format!("</{}>", String::from_utf8_lossy(start.name().as_ref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transaction(e2e: &str, amount: &str, currency: &str) -> String {
+        format!(r#"
+      <CdtTrfTxInf>
+        <PmtId><InstrId>I-{e2e}</InstrId><EndToEndId>{e2e}</EndToEndId></PmtId>
+        <Amt><InstdAmt Ccy="{currency}">{amount}</InstdAmt></Amt>
+        <CdtrAgt><FinInstnId><BICFI>ICICINBBXXX</BICFI></FinInstnId></CdtrAgt>
+        <Cdtr><Nm>Jane Smith</Nm><PstlAdr><Ctry>IN</Ctry></PstlAdr></Cdtr>
+        <CdtrAcct><Id><Othr><Id>123456789012</Id></Othr></Id></CdtrAcct>
+      </CdtTrfTxInf>"#)
+    }
+
+    fn payment_info(id: &str, bic: &str, transactions: &[String]) -> String {
+        format!(r#"
+    <PmtInf>
+      <PmtInfId>{id}</PmtInfId>
+      <PmtMtd>TRF</PmtMtd>
+      <ReqdExctnDt><Dt>2025-11-19</Dt></ReqdExctnDt>
+      <Dbtr><Nm>ACME Corp</Nm><PstlAdr><Ctry>AE</Ctry></PstlAdr></Dbtr>
+      <DbtrAcct><Id><IBAN>AE070331234567890123456</IBAN></Id></DbtrAcct>
+      <DbtrAgt><FinInstnId><BICFI>{bic}</BICFI></FinInstnId></DbtrAgt>{}
+    </PmtInf>"#, transactions.concat())
+    }
+
+    fn bulk_file(payment_infos: &[String]) -> String {
+        format!(r#"<?xml version="1.0" encoding="UTF-8"?>
+<Document xmlns="urn:iso:std:iso:20022:tech:xsd:pain.001.001.12">
+  <CstmrCdtTrfInitn>
+    <GrpHdr>
+      <MsgId>BULK-001</MsgId>
+      <CreDtTm>2025-11-18T14:30:00Z</CreDtTm>
+      <NbOfTxs>4</NbOfTxs>
+      <InitgPty><Nm>ACME Corp</Nm></InitgPty>
+    </GrpHdr>{}
+  </CstmrCdtTrfInitn>
+</Document>"#, payment_infos.concat())
+    }
+
+    #[test]
+    fn test_stream_converts_each_transaction_independently() {
+        let xml = bulk_file(&[
+            payment_info("PMT-1", "BANKAEADXXX", &[
+                transaction("E2E-1", "1000.00", "AED"),
+                transaction("E2E-2", "250.00", "XXX"),
+            ]),
+            payment_info("PMT-2", "BANKAEADXXX", &[
+                transaction("E2E-3", "0", "AED"),
+                transaction("E2E-4", "75.50", "USD"),
+            ]),
+        ]);
+
+        let stream = Pain001Stream::open(xml.as_bytes()).unwrap();
+        assert_eq!(stream.group_header().msg_id, "BULK-001");
+        assert_eq!(stream.declared_transactions(), Some(4));
+
+        let transactions: Vec<BulkTransaction> = stream.collect::<Result<_>>().unwrap();
+        assert_eq!(transactions.len(), 4);
+        assert_eq!(transactions.iter().map(|t| t.sequence).collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+
+        let accepted = transactions[0].payment.as_ref().unwrap();
+        assert_eq!(accepted.end_to_end_id, "E2E-1");
+        assert_eq!(accepted.message_id, "BULK-001");
+        assert_eq!(accepted.corridor, "AE_IN");
+
+        assert_eq!(transactions[1].payment.as_ref().unwrap_err().code, "AM03");
+        assert_eq!(transactions[1].end_to_end_id.as_deref(), Some("E2E-2"));
+        assert_eq!(transactions[2].payment.as_ref().unwrap_err().code, "AM01");
+        assert_eq!(transactions[3].payment_info_id.as_deref(), Some("PMT-2"));
+        assert_eq!(transactions[3].debtor_agent_bic.as_deref(), Some("BANKAEADXXX"));
+        assert!(transactions[3].payment.is_ok());
+    }
+
+    #[test]
+    fn test_enveloped_file_keeps_app_header() {
+        let document = bulk_file(&[payment_info("PMT-1", "BANKAEADXXX", &[transaction("E2E-1", "10.00", "AED")])]);
+        let xml = format!(
+            r#"<BizMsg><AppHdr xmlns="urn:iso:std:iso:20022:tech:xsd:head.001.001.02">
+  <Fr><FIId><FinInstnId><BICFI>BANKAEADXXX</BICFI></FinInstnId></FIId></Fr>
+  <To><FIId><FinInstnId><BICFI>DLTRAEADXXX</BICFI></FinInstnId></FIId></To>
+  <BizMsgIdr>BULK-001</BizMsgIdr><MsgDefIdr>pain.001.001.12</MsgDefIdr><CreDt>2025-11-18T14:30:00Z</CreDt>
+</AppHdr>{}</BizMsg>"#,
+            document.trim_start_matches(r#"<?xml version="1.0" encoding="UTF-8"?>"#)
+        );
+
+        let stream = Pain001Stream::open(xml.as_bytes()).unwrap();
+        assert_eq!(stream.app_header().and_then(AppHdr::from_bic), Some("BANKAEADXXX"));
+        assert_eq!(stream.group_header().msg_id, "BULK-001");
+
+        let transactions: Vec<BulkTransaction> = stream.collect::<Result<_>>().unwrap();
+        assert_eq!(transactions.len(), 1);
+        assert!(transactions[0].document.starts_with("<Document"));
+        assert!(transactions[0].payment.is_ok());
+    }
+
+    #[test]
+    fn test_standalone_documents_validate_independently() {
+        use crate::validation::xsd::{SchemaModel, XsdValidator, ValidationMode};
+
+        let incomplete = transaction("E2E-2", "10.00", "AED").replace(r#"<Amt><InstdAmt Ccy="AED">10.00</InstdAmt></Amt>"#, "");
+        let xml = bulk_file(&[payment_info("PMT-1", "BANKAEADXXX", &[
+            transaction("E2E-1", "10.00", "AED"),
+            incomplete,
+        ])]);
+
+        let xsd = std::fs::read_to_string(concat!(
+            env!("CARGO_MANIFEST_DIR"), "/../../iso20022/payments_initiation/pain.001.001.12.xsd"
+        )).unwrap();
+        let validator = XsdValidator::new(vec![SchemaModel::parse(&xsd).unwrap()], ValidationMode::Strict);
+
+        let transactions: Vec<BulkTransaction> = Pain001Stream::open(xml.as_bytes()).unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert!(validator.validate("pain.001", &transactions[0].document).is_ok(),
+                "{:?}", validator.validate("pain.001", &transactions[0].document).err());
+        assert!(validator.validate("pain.001", &transactions[1].document).is_err());
+        assert_eq!(transactions[1].payment.as_ref().unwrap_err().code, "FF01");
+        assert_eq!(transactions[1].end_to_end_id.as_deref(), Some("E2E-2"));
+    }
+
+    #[test]
+    fn test_malformed_file_stops_after_readable_transactions() {
+        let xml = bulk_file(&[payment_info("PMT-1", "BANKAEADXXX", &[
+            transaction("E2E-1", "10.00", "AED"),
+            "<CdtTrfTxInf><PmtId></CdtTrfTxInf>".to_string(),
+        ])]);
+
+        let mut stream = Pain001Stream::open(xml.as_bytes()).unwrap();
+        assert!(stream.next().unwrap().unwrap().payment.is_ok());
+        assert!(stream.next().unwrap().is_err());
+
+        assert!(Pain001Stream::open("<Document><Other/></Document>".as_bytes()).is_err());
+    }
+}
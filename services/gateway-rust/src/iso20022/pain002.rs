@@ -6,10 +6,20 @@ use chrono::{DateTime, Utc};
 use uuid::Uuid;
 use anyhow::{Result, anyhow};
 
+use crate::models::batch::{BatchTransaction, TransactionOutcome};
+use crate::models::canonical::CanonicalPayment;
+use super::outbound::{self, IsoTransactionStatus, OutboundDocument};
+
+/// Message definition of generated pain.002 reports
+pub const PAIN002_MESSAGE_DEFINITION: &str = "pain.002.001.14";
+
 /// pain.002 Document root
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct Document {
+    #[serde(rename = "@xmlns", default, skip_serializing_if = "Option::is_none")]
+    pub xmlns: Option<String>,
+
     #[serde(rename = "CstmrPmtStsRpt")]
     pub customer_payment_status_report: CustomerPaymentStatusReport,
 }
@@ -19,10 +29,10 @@ pub struct Document {
 pub struct CustomerPaymentStatusReport {
     pub grp_hdr: GroupHeader,
 
-    #[serde(rename = "OrgnlGrpInfAndSts")]
+    #[serde(rename = "OrgnlGrpInfAndSts", skip_serializing_if = "Option::is_none")]
     pub original_group_info_and_status: Option<OriginalGroupInfoAndStatus>,
 
-    #[serde(rename = "OrgnlPmtInfAndSts")]
+    #[serde(rename = "OrgnlPmtInfAndSts", skip_serializing_if = "Option::is_none")]
     pub original_payment_info_and_status: Option<Vec<OriginalPaymentInfoAndStatus>>,
 }
 
@@ -42,10 +52,10 @@ pub struct OriginalGroupInfoAndStatus {
     #[serde(rename = "OrgnlMsgNmId")]
     pub original_message_name_id: String,
 
-    #[serde(rename = "GrpSts")]
+    #[serde(rename = "GrpSts", skip_serializing_if = "Option::is_none")]
     pub group_status: Option<String>,
 
-    #[serde(rename = "StsRsnInf")]
+    #[serde(rename = "StsRsnInf", skip_serializing_if = "Option::is_none")]
     pub status_reason_info: Option<Vec<StatusReasonInfo>>,
 }
 
@@ -55,77 +65,82 @@ pub struct OriginalPaymentInfoAndStatus {
     #[serde(rename = "OrgnlPmtInfId")]
     pub original_payment_info_id: String,
 
-    #[serde(rename = "PmtInfSts")]
+    #[serde(rename = "PmtInfSts", skip_serializing_if = "Option::is_none")]
     pub payment_info_status: Option<String>,
 
-    #[serde(rename = "StsRsnInf")]
+    #[serde(rename = "StsRsnInf", skip_serializing_if = "Option::is_none")]
     pub status_reason_info: Option<Vec<StatusReasonInfo>>,
 
-    #[serde(rename = "TxInfAndSts")]
+    #[serde(rename = "TxInfAndSts", skip_serializing_if = "Option::is_none")]
     pub transaction_info_and_status: Option<Vec<TransactionInfoAndStatus>>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct TransactionInfoAndStatus {
-    #[serde(rename = "StsId")]
+    #[serde(rename = "StsId", skip_serializing_if = "Option::is_none")]
     pub status_id: Option<String>,
 
-    #[serde(rename = "OrgnlInstrId")]
+    #[serde(rename = "OrgnlInstrId", skip_serializing_if = "Option::is_none")]
     pub original_instruction_id: Option<String>,
 
     #[serde(rename = "OrgnlEndToEndId")]
     pub original_end_to_end_id: String,
 
+    #[serde(rename = "OrgnlUETR", skip_serializing_if = "Option::is_none")]
+    pub original_uetr: Option<String>,
+
     #[serde(rename = "TxSts")]
     pub transaction_status: String, // ACCP, ACTC, ACWC, PART, PDNG, RJCT
 
-    #[serde(rename = "StsRsnInf")]
+    #[serde(rename = "StsRsnInf", skip_serializing_if = "Option::is_none")]
     pub status_reason_info: Option<Vec<StatusReasonInfo>>,
 
-    #[serde(rename = "AccptncDtTm")]
+    #[serde(rename = "AccptncDtTm", skip_serializing_if = "Option::is_none")]
     pub acceptance_date_time: Option<String>,
 
-    #[serde(rename = "OrgnlTxRef")]
+    #[serde(rename = "OrgnlTxRef", skip_serializing_if = "Option::is_none")]
     pub original_transaction_reference: Option<OriginalTransactionReference>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct StatusReasonInfo {
-    #[serde(rename = "Rsn")]
+    #[serde(rename = "Rsn", skip_serializing_if = "Option::is_none")]
     pub reason: Option<Reason>,
 
-    #[serde(rename = "AddtlInf")]
+    #[serde(rename = "AddtlInf", skip_serializing_if = "Option::is_none")]
     pub additional_info: Option<Vec<String>>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct Reason {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub cd: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub prtry: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct OriginalTransactionReference {
-    #[serde(rename = "Amt")]
+    #[serde(rename = "Amt", skip_serializing_if = "Option::is_none")]
     pub amount: Option<Amount>,
 
-    #[serde(rename = "ReqdExctnDt")]
+    #[serde(rename = "ReqdExctnDt", skip_serializing_if = "Option::is_none")]
     pub requested_execution_date: Option<String>,
 
-    #[serde(rename = "Cdtr")]
+    #[serde(rename = "Cdtr", skip_serializing_if = "Option::is_none")]
     pub creditor: Option<Party>,
 
-    #[serde(rename = "CdtrAcct")]
+    #[serde(rename = "CdtrAcct", skip_serializing_if = "Option::is_none")]
     pub creditor_account: Option<Account>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Amount {
-    #[serde(rename = "InstdAmt")]
+    #[serde(rename = "InstdAmt", skip_serializing_if = "Option::is_none")]
     pub instructed_amount: Option<InstructedAmount>,
 }
 
@@ -141,22 +156,24 @@ pub struct InstructedAmount {
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct Party {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub nm: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct Account {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub id: Option<AccountId>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "UPPERCASE")]
 pub struct AccountId {
-    #[serde(rename = "IBAN")]
+    #[serde(rename = "IBAN", skip_serializing_if = "Option::is_none")]
     pub iban: Option<String>,
 
-    #[serde(rename = "Othr")]
+    #[serde(rename = "Othr", skip_serializing_if = "Option::is_none")]
     pub other: Option<OtherAccountId>,
 }
 
@@ -250,6 +267,158 @@ pub fn to_customer_payment_status(document: &Document) -> Result<Vec<CustomerPay
     Ok(statuses)
 }
 
+/// Build an outbound pain.002 for payments of one original pain.001.
+/// Group status is only reported when all transactions of the original message are included.
+/// The original PmtInfId is not retained on the canonical model, so the original MsgId is reported instead.
+pub fn build_pain002(payments: &[CanonicalPayment], include_group_status: bool) -> Result<OutboundDocument> {
+    let first = payments.first()
+        .ok_or_else(|| anyhow!("Cannot build pain.002 without transactions"))?;
+
+    let statuses: Vec<IsoTransactionStatus> = payments.iter()
+        .map(|p| IsoTransactionStatus::from_payment_status(&p.status))
+        .collect();
+
+    let group_status = if include_group_status {
+        IsoTransactionStatus::group_status(&statuses).map(|s| s.code().to_string())
+    } else {
+        None
+    };
+
+    let transaction_info_and_status = payments.iter()
+        .zip(&statuses)
+        .map(|(payment, status)| TransactionInfoAndStatus {
+            status_id: Some(outbound::generate_identifier("STS")),
+            original_instruction_id: Some(payment.instruction_id.clone()),
+            original_end_to_end_id: payment.end_to_end_id.clone(),
+            original_uetr: payment.uetr.map(|u| u.to_string()),
+            transaction_status: status.code().to_string(),
+            status_reason_info: payment.status_reason.as_ref().map(|reason| {
+                let (cd, prtry) = outbound::reason_code_parts(reason);
+                let additional_info = outbound::reason_additional_info(reason);
+                vec![StatusReasonInfo {
+                    reason: Some(Reason { cd, prtry }),
+                    additional_info: (!additional_info.is_empty()).then_some(additional_info),
+                }]
+            }),
+            acceptance_date_time: matches!(status, IsoTransactionStatus::Accp | IsoTransactionStatus::Acsp | IsoTransactionStatus::Acsc)
+                .then(|| outbound::iso_date_time(payment.updated_at)),
+            original_transaction_reference: None,
+        })
+        .collect();
+
+    let message_id = outbound::generate_identifier("PAIN002");
+
+    let document = Document {
+        xmlns: Some(format!("urn:iso:std:iso:20022:tech:xsd:{}", PAIN002_MESSAGE_DEFINITION)),
+        customer_payment_status_report: CustomerPaymentStatusReport {
+            grp_hdr: GroupHeader {
+                msg_id: message_id.clone(),
+                cre_dt_tm: outbound::iso_date_time(Utc::now()),
+            },
+            original_group_info_and_status: Some(OriginalGroupInfoAndStatus {
+                original_message_id: first.message_id.clone(),
+                original_message_name_id: "pain.001.001.12".to_string(),
+                group_status: group_status.clone(),
+                status_reason_info: None,
+            }),
+            original_payment_info_and_status: Some(vec![OriginalPaymentInfoAndStatus {
+                original_payment_info_id: first.message_id.clone(),
+                payment_info_status: group_status.clone(),
+                status_reason_info: None,
+                transaction_info_and_status: Some(transaction_info_and_status),
+            }]),
+        },
+    };
+
+    Ok(OutboundDocument {
+        message_definition: PAIN002_MESSAGE_DEFINITION,
+        xml: outbound::render_xml(&document)?,
+        message_id,
+        group_status,
+    })
+}
+
+/// Build the pain.002 answering a bulk pain.001 batch.
+/// Group and PmtInf statuses are ACTC / PART / RJCT; only rejected transactions are listed,
+/// accepted ones are reported individually as they progress.
+pub fn build_pain002_batch(original_message_id: &str, transactions: &[BatchTransaction]) -> Result<OutboundDocument> {
+    if transactions.is_empty() {
+        return Err(anyhow!("Cannot build pain.002 for an empty batch"));
+    }
+
+    let status_of = |t: &BatchTransaction| match t.outcome {
+        TransactionOutcome::Accepted => IsoTransactionStatus::Actc,
+        TransactionOutcome::Rejected | TransactionOutcome::Duplicate => IsoTransactionStatus::Rjct,
+    };
+
+    // One OrgnlPmtInfAndSts per PmtInf, in file order
+    let mut payment_infos: Vec<(String, Vec<&BatchTransaction>)> = Vec::new();
+    for transaction in transactions {
+        let id = transaction.payment_info_id.clone().unwrap_or_else(|| original_message_id.to_string());
+        match payment_infos.iter_mut().find(|(pmt_inf_id, _)| *pmt_inf_id == id) {
+            Some((_, group)) => group.push(transaction),
+            None => payment_infos.push((id, vec![transaction])),
+        }
+    }
+
+    let original_payment_info_and_status = payment_infos.into_iter()
+        .map(|(pmt_inf_id, group)| {
+            let statuses: Vec<IsoTransactionStatus> = group.iter().map(|t| status_of(t)).collect();
+            let rejected: Vec<TransactionInfoAndStatus> = group.iter()
+                .filter(|t| t.outcome != TransactionOutcome::Accepted)
+                .map(|t| TransactionInfoAndStatus {
+                    status_id: Some(outbound::generate_identifier("STS")),
+                    original_instruction_id: t.instruction_id.clone(),
+                    original_end_to_end_id: t.end_to_end_id.clone().unwrap_or_else(|| "NOTPROVIDED".to_string()),
+                    original_uetr: t.uetr.map(|u| u.to_string()),
+                    transaction_status: IsoTransactionStatus::Rjct.code().to_string(),
+                    status_reason_info: Some(vec![StatusReasonInfo {
+                        reason: t.reason_code.clone().map(|code| Reason { cd: Some(code), prtry: None }),
+                        additional_info: t.reason_info.as_ref().map(|info| vec![info.chars().take(105).collect()]),
+                    }]),
+                    acceptance_date_time: None,
+                    original_transaction_reference: None,
+                })
+                .collect();
+
+            OriginalPaymentInfoAndStatus {
+                original_payment_info_id: pmt_inf_id,
+                payment_info_status: IsoTransactionStatus::group_status(&statuses).map(|s| s.code().to_string()),
+                status_reason_info: None,
+                transaction_info_and_status: (!rejected.is_empty()).then_some(rejected),
+            }
+        })
+        .collect();
+
+    let statuses: Vec<IsoTransactionStatus> = transactions.iter().map(status_of).collect();
+    let group_status = IsoTransactionStatus::group_status(&statuses).map(|s| s.code().to_string());
+    let message_id = outbound::generate_identifier("PAIN002");
+
+    let document = Document {
+        xmlns: Some(format!("urn:iso:std:iso:20022:tech:xsd:{}", PAIN002_MESSAGE_DEFINITION)),
+        customer_payment_status_report: CustomerPaymentStatusReport {
+            grp_hdr: GroupHeader {
+                msg_id: message_id.clone(),
+                cre_dt_tm: outbound::iso_date_time(Utc::now()),
+            },
+            original_group_info_and_status: Some(OriginalGroupInfoAndStatus {
+                original_message_id: original_message_id.to_string(),
+                original_message_name_id: "pain.001.001.12".to_string(),
+                group_status: group_status.clone(),
+                status_reason_info: None,
+            }),
+            original_payment_info_and_status: Some(original_payment_info_and_status),
+        },
+    };
+
+    Ok(OutboundDocument {
+        message_definition: PAIN002_MESSAGE_DEFINITION,
+        xml: outbound::render_xml(&document)?,
+        message_id,
+        group_status,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -281,4 +450,84 @@ mod tests {
         assert_eq!(statuses[0].original_end_to_end_id, "E2E123456");
         assert!(matches!(statuses[0].status, PaymentStatus::Accepted));
     }
+
+    #[test]
+    fn test_build_pain002_validates_against_xsd() {
+        use crate::models::canonical::{Currency, Party, FinancialInstitution, PaymentStatus as CanonicalStatus};
+        use crate::validation::xsd::{SchemaModel, XsdValidator, ValidationMode};
+        use rust_decimal_macros::dec;
+
+        let party = |name: &str| Party { name: name.to_string(), postal_address: None, identification: None, country_code: "AE".to_string() };
+        let agent = |bic: &str| FinancialInstitution { bic: Some(bic.to_string()), name: bic.to_string(), country_code: "AE".to_string(), clearing_system_member_id: None };
+
+        let payments: Vec<CanonicalPayment> = ["E2E-1", "E2E-2"].iter().map(|e2e| {
+            CanonicalPayment::new(
+                e2e.to_string(), format!("I-{}", e2e), "MSG-1".to_string(),
+                dec!(250.00), Currency::AED,
+                party("Debtor"), party("Creditor"), agent("BANKAEADXXX"), agent("ICICINBBXXX"),
+            )
+        }).collect();
+        let mut payments = payments;
+        payments[1].update_status(CanonicalStatus::Rejected, None);
+
+        let xml = build_pain002(&payments, true).unwrap().xml;
+
+        let xsd = std::fs::read_to_string(concat!(
+            env!("CARGO_MANIFEST_DIR"), "/../../iso20022/payments_initiation/pain.002.001.14.xsd"
+        )).unwrap();
+        let validator = XsdValidator::new(vec![SchemaModel::parse(&xsd).unwrap()], ValidationMode::Strict);
+        assert!(validator.validate("pain.002", &xml).is_ok(), "{:?}", validator.validate("pain.002", &xml).err());
+
+        let document = parse_pain002(&xml).unwrap();
+        let group = document.customer_payment_status_report.original_group_info_and_status.as_ref().unwrap();
+        assert_eq!(group.original_message_id, "MSG-1");
+        assert_eq!(group.group_status.as_deref(), Some("PART"));
+
+        let statuses = to_customer_payment_status(&document).unwrap();
+        assert_eq!(statuses.len(), 2);
+        assert_eq!(statuses[0].status_code, "ACTC");
+        assert!(matches!(statuses[1].status, PaymentStatus::Rejected));
+    }
+
+    #[test]
+    fn test_build_pain002_batch_partial_acceptance() {
+        use crate::validation::xsd::{SchemaModel, XsdValidator, ValidationMode};
+
+        let transaction = |sequence: i32, pmt_inf: &str, outcome: TransactionOutcome, reason: Option<&str>| BatchTransaction {
+            batch_id: Uuid::nil(),
+            sequence,
+            payment_info_id: Some(pmt_inf.to_string()),
+            end_to_end_id: Some(format!("E2E-{}", sequence)),
+            instruction_id: None,
+            uetr: None,
+            deltran_tx_id: None,
+            outcome,
+            reason_code: reason.map(str::to_string),
+            reason_info: reason.map(|_| "Currency XXX is not supported".to_string()),
+        };
+        let transactions = vec![
+            transaction(1, "PMT-1", TransactionOutcome::Accepted, None),
+            transaction(2, "PMT-1", TransactionOutcome::Rejected, Some("AM03")),
+            transaction(3, "PMT-2", TransactionOutcome::Accepted, None),
+            transaction(4, "PMT-2", TransactionOutcome::Duplicate, Some("DUPL")),
+        ];
+
+        let document = build_pain002_batch("BULK-001", &transactions).unwrap();
+        assert_eq!(document.group_status.as_deref(), Some("PART"));
+
+        let xsd = std::fs::read_to_string(concat!(
+            env!("CARGO_MANIFEST_DIR"), "/../../iso20022/payments_initiation/pain.002.001.14.xsd"
+        )).unwrap();
+        let validator = XsdValidator::new(vec![SchemaModel::parse(&xsd).unwrap()], ValidationMode::Strict);
+        assert!(validator.validate("pain.002", &document.xml).is_ok(), "{:?}", validator.validate("pain.002", &document.xml).err());
+
+        let parsed = parse_pain002(&document.xml).unwrap();
+        let payment_infos = parsed.customer_payment_status_report.original_payment_info_and_status.unwrap();
+        assert_eq!(payment_infos.len(), 2);
+        assert_eq!(payment_infos[0].payment_info_status.as_deref(), Some("PART"));
+        let rejected = payment_infos[1].transaction_info_and_status.as_ref().unwrap();
+        assert_eq!(rejected.len(), 1);
+        assert_eq!(rejected[0].original_end_to_end_id, "E2E-4");
+        assert_eq!(rejected[0].status_reason_info.as_ref().unwrap()[0].reason.as_ref().unwrap().cd.as_deref(), Some("DUPL"));
+    }
 }
@@ -0,0 +1,431 @@
+// trck.001.001.04 - Payment Status Tracker Update
+// Outbound: DelTran publishes one update per tracking event of a payment.
+// Inbound: other agents report the status they see for a UETR.
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+use anyhow::{Result, anyhow, Context};
+
+use crate::models::canonical::CanonicalPayment;
+use crate::models::tracking::PaymentEvent;
+use super::outbound::{self, OutboundDocument};
+
+/// Message definition of generated trck.001 updates
+pub const TRCK001_MESSAGE_DEFINITION: &str = "trck.001.001.04";
+
+/// trck.001 Document root
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Document {
+    #[serde(rename = "@xmlns", default, skip_serializing_if = "Option::is_none")]
+    pub xmlns: Option<String>,
+
+    #[serde(rename = "PmtStsTrckrUpd")]
+    pub tracker_update: PaymentStatusTrackerUpdate,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PaymentStatusTrackerUpdate {
+    #[serde(rename = "GrpHdr")]
+    pub grp_hdr: TrackerHeader,
+
+    #[serde(rename = "TrckrStsAndTx")]
+    pub status_and_transactions: Vec<TrackerStatusAndTransaction>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TrackerHeader {
+    #[serde(rename = "MsgId")]
+    pub msg_id: String,
+
+    #[serde(rename = "CreDtTm", skip_serializing_if = "Option::is_none")]
+    pub cre_dt_tm: Option<String>,
+
+    #[serde(rename = "NbOfTxs", skip_serializing_if = "Option::is_none")]
+    pub number_of_transactions: Option<String>,
+
+    #[serde(rename = "TrckrInfrmgPty", skip_serializing_if = "Option::is_none")]
+    pub informing_party: Option<TrackerParty>,
+}
+
+/// Status and transaction block (shared with trck.002)
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TrackerStatusAndTransaction {
+    #[serde(rename = "TxSts")]
+    pub transaction_status: TrackerStatus,
+
+    #[serde(rename = "Tx")]
+    pub transactions: Vec<TrackerPaymentTransaction>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TrackerStatus {
+    #[serde(rename = "Sts")]
+    pub status: String,     // ExternalPaymentTransactionStatus1Code (ACSP, ACSC, RJCT, ...)
+
+    #[serde(rename = "Dt", skip_serializing_if = "Option::is_none")]
+    pub date: Option<DateAndDateTime>,
+
+    #[serde(rename = "StsRsn", default, skip_serializing_if = "Vec::is_empty")]
+    pub status_reason: Vec<StatusReason>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DateAndDateTime {
+    #[serde(rename = "Dt", skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+
+    #[serde(rename = "DtTm", skip_serializing_if = "Option::is_none")]
+    pub date_time: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct StatusReason {
+    #[serde(rename = "Rsn", skip_serializing_if = "Option::is_none")]
+    pub reason: Option<Reason>,
+
+    #[serde(rename = "AddtlInf", default, skip_serializing_if = "Vec::is_empty")]
+    pub additional_info: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Reason {
+    #[serde(rename = "Cd", skip_serializing_if = "Option::is_none")]
+    pub cd: Option<String>,
+
+    #[serde(rename = "Prtry", skip_serializing_if = "Option::is_none")]
+    pub prtry: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TrackerPaymentTransaction {
+    #[serde(rename = "TrckdMsgId", skip_serializing_if = "Option::is_none")]
+    pub tracked_message_id: Option<TrackedMessage>,
+
+    #[serde(rename = "TrckrInfrmgPty", skip_serializing_if = "Option::is_none")]
+    pub informing_party: Option<TrackerParty>,
+
+    #[serde(rename = "PmtId", skip_serializing_if = "Option::is_none")]
+    pub payment_id: Option<PaymentIdentification>,
+
+    #[serde(rename = "IntrBkSttlmAmt", skip_serializing_if = "Option::is_none")]
+    pub interbank_settlement_amount: Option<Amount>,
+
+    #[serde(rename = "TrckrRcrd", default, skip_serializing_if = "Vec::is_empty")]
+    pub tracker_records: Vec<TrackerRecord>,
+
+    #[serde(rename = "DbtrAgt", skip_serializing_if = "Option::is_none")]
+    pub debtor_agent: Option<Agent>,
+
+    #[serde(rename = "CdtrAgt", skip_serializing_if = "Option::is_none")]
+    pub creditor_agent: Option<Agent>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TrackedMessage {
+    #[serde(rename = "MsgId", skip_serializing_if = "Option::is_none")]
+    pub msg_id: Option<String>,
+
+    #[serde(rename = "MsgNmId", skip_serializing_if = "Option::is_none")]
+    pub msg_nm_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PaymentIdentification {
+    #[serde(rename = "InstrId", skip_serializing_if = "Option::is_none")]
+    pub instruction_id: Option<String>,
+
+    #[serde(rename = "EndToEndId", skip_serializing_if = "Option::is_none")]
+    pub end_to_end_id: Option<String>,
+
+    #[serde(rename = "TxId", skip_serializing_if = "Option::is_none")]
+    pub transaction_id: Option<String>,
+
+    #[serde(rename = "UETR", skip_serializing_if = "Option::is_none")]
+    pub uetr: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Amount {
+    #[serde(rename = "@Ccy")]
+    pub currency: String,
+
+    #[serde(rename = "$text")]
+    pub value: String,
+}
+
+/// One hop of the payment (TrckrRcrd) - DelTran reports one record per engine event
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TrackerRecord {
+    #[serde(rename = "PtyOrAgtId", skip_serializing_if = "Option::is_none")]
+    pub party_or_agent: Option<TrackerParty>,
+
+    #[serde(rename = "PrcgDtTm", skip_serializing_if = "Option::is_none")]
+    pub processing_date_time: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct TrackerParty {
+    #[serde(rename = "Nm", skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    #[serde(rename = "Id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<TrackerPartyId>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TrackerPartyId {
+    #[serde(rename = "FinInstnId", skip_serializing_if = "Option::is_none")]
+    pub financial_institution_id: Option<FinancialInstitutionIdentification>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Agent {
+    #[serde(rename = "FinInstnId")]
+    pub financial_institution_id: FinancialInstitutionIdentification,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct FinancialInstitutionIdentification {
+    #[serde(rename = "BICFI", skip_serializing_if = "Option::is_none")]
+    pub bicfi: Option<String>,
+}
+
+impl TrackerParty {
+    pub fn agent(bic: &str) -> Self {
+        Self {
+            name: None,
+            id: Some(TrackerPartyId {
+                financial_institution_id: Some(FinancialInstitutionIdentification { bicfi: Some(bic.to_string()) }),
+            }),
+        }
+    }
+
+    pub fn bic(&self) -> Option<&str> {
+        self.id.as_ref()
+            .and_then(|id| id.financial_institution_id.as_ref())
+            .and_then(|fi| fi.bicfi.as_deref())
+    }
+}
+
+/// Status update reported by another agent for one UETR
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackerUpdate {
+    pub message_id: String,
+    pub uetr: Uuid,
+    pub status: String,
+    pub status_at: Option<DateTime<Utc>>,
+    pub reason_code: Option<String>,
+    pub reason_description: Option<String>,
+    pub informing_agent_bic: Option<String>,
+}
+
+/// Parse trck.001 XML message
+pub fn parse_trck001(xml: &str) -> Result<Document> {
+    quick_xml::de::from_str(xml)
+        .map_err(|e| anyhow!("Failed to parse trck.001 XML: {}", e))
+}
+
+/// Convert trck.001 to DelTran tracker updates (one per tracked UETR)
+pub fn to_tracker_updates(document: &Document) -> Result<Vec<TrackerUpdate>> {
+    let message = &document.tracker_update;
+    let header_agent = message.grp_hdr.informing_party.as_ref().and_then(|p| p.bic());
+    let mut updates = Vec::new();
+
+    for block in &message.status_and_transactions {
+        let status = &block.transaction_status;
+        let status_at = status.date.as_ref()
+            .and_then(|d| d.date_time.as_deref())
+            .and_then(|dt| DateTime::parse_from_rfc3339(dt).ok())
+            .map(|dt| dt.with_timezone(&Utc));
+        let first_reason = status.status_reason.first();
+
+        for tx in &block.transactions {
+            let uetr = tx.payment_id.as_ref()
+                .and_then(|id| id.uetr.as_ref())
+                .ok_or_else(|| anyhow!("trck.001 {} has a transaction without UETR", message.grp_hdr.msg_id))?;
+
+            updates.push(TrackerUpdate {
+                message_id: message.grp_hdr.msg_id.clone(),
+                uetr: Uuid::parse_str(uetr).context(format!("Invalid UETR: {}", uetr))?,
+                status: status.status.clone(),
+                status_at,
+                reason_code: first_reason
+                    .and_then(|r| r.reason.as_ref())
+                    .and_then(|r| r.cd.clone().or_else(|| r.prtry.clone())),
+                reason_description: first_reason
+                    .filter(|r| !r.additional_info.is_empty())
+                    .map(|r| r.additional_info.join(" ")),
+                informing_agent_bic: tx.informing_party.as_ref()
+                    .and_then(|p| p.bic())
+                    .or(header_agent)
+                    .map(str::to_string),
+            });
+        }
+    }
+
+    Ok(updates)
+}
+
+/// TrckrStsAndTx for a payment: current status plus one TrckrRcrd per event
+pub fn tracker_status_and_transaction(
+    payment: &CanonicalPayment,
+    status: &str,
+    status_at: DateTime<Utc>,
+    reason_code: Option<&str>,
+    events: &[PaymentEvent],
+) -> Result<TrackerStatusAndTransaction> {
+    let agent = |bic: &Option<String>| bic.as_ref().map(|bic| Agent {
+        financial_institution_id: FinancialInstitutionIdentification { bicfi: Some(bic.clone()) },
+    });
+
+    Ok(TrackerStatusAndTransaction {
+        transaction_status: TrackerStatus {
+            status: status.to_string(),
+            date: Some(DateAndDateTime { date: None, date_time: Some(outbound::iso_date_time(status_at)) }),
+            status_reason: reason_code
+                .map(|code| {
+                    let (cd, prtry) = if (1..=4).contains(&code.len()) {
+                        (Some(code.to_string()), None)
+                    } else {
+                        (None, Some(code.chars().take(35).collect()))
+                    };
+                    vec![StatusReason { reason: Some(Reason { cd, prtry }), additional_info: vec![] }]
+                })
+                .unwrap_or_default(),
+        },
+        transactions: vec![TrackerPaymentTransaction {
+            tracked_message_id: Some(TrackedMessage {
+                msg_id: Some(payment.message_id.clone()),
+                msg_nm_id: None,
+            }),
+            informing_party: None,
+            payment_id: Some(PaymentIdentification {
+                instruction_id: Some(payment.instruction_id.clone()),
+                end_to_end_id: Some(payment.end_to_end_id.clone()),
+                transaction_id: None,
+                uetr: payment.uetr.map(|u| u.to_string()),
+            }),
+            interbank_settlement_amount: Some(Amount {
+                currency: payment.currency.to_string(),
+                value: outbound::render_amount(payment.currency, payment.settlement_amount)?,
+            }),
+            tracker_records: events.iter()
+                .map(|event| TrackerRecord {
+                    party_or_agent: Some(match &event.agent_bic {
+                        Some(bic) => TrackerParty::agent(bic),
+                        None => TrackerParty { name: Some(event.source.party_name().to_string()), id: None },
+                    }),
+                    processing_date_time: Some(outbound::iso_date_time(event.occurred_at)),
+                })
+                .collect(),
+            debtor_agent: agent(&payment.debtor_agent.bic),
+            creditor_agent: agent(&payment.creditor_agent.bic),
+        }],
+    })
+}
+
+/// Build the trck.001 update published for a single tracking event
+pub fn build_trck001(payment: &CanonicalPayment, event: &PaymentEvent, deltran_bic: Option<&str>) -> Result<OutboundDocument> {
+    if payment.uetr.is_none() {
+        return Err(anyhow!("Payment {} has no UETR - cannot be tracked", payment.deltran_tx_id));
+    }
+
+    let message_id = outbound::generate_identifier("TRCK001");
+
+    let document = Document {
+        xmlns: Some(format!("urn:iso:std:iso:20022:tech:xsd:{}", TRCK001_MESSAGE_DEFINITION)),
+        tracker_update: PaymentStatusTrackerUpdate {
+            grp_hdr: TrackerHeader {
+                msg_id: message_id.clone(),
+                cre_dt_tm: Some(outbound::iso_date_time(Utc::now())),
+                number_of_transactions: Some("1".to_string()),
+                informing_party: deltran_bic.map(TrackerParty::agent),
+            },
+            status_and_transactions: vec![tracker_status_and_transaction(
+                payment,
+                &event.iso_status,
+                event.occurred_at,
+                event.reason_code.as_deref(),
+                std::slice::from_ref(event),
+            )?],
+        },
+    };
+
+    Ok(OutboundDocument {
+        message_definition: TRCK001_MESSAGE_DEFINITION,
+        xml: outbound::render_xml(&document)?,
+        message_id,
+        group_status: Some(event.iso_status.clone()),
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::models::canonical::{Currency, Party, FinancialInstitution};
+    use crate::models::tracking::EventSource;
+    use rust_decimal_macros::dec;
+
+    pub(crate) fn tracked_payment() -> CanonicalPayment {
+        let party = |name: &str| Party { name: name.to_string(), postal_address: None, identification: None, country_code: "AE".to_string() };
+        let agent = |bic: &str| FinancialInstitution { bic: Some(bic.to_string()), name: "Bank".to_string(), country_code: "AE".to_string(), clearing_system_member_id: None };
+
+        let mut payment = CanonicalPayment::new(
+            "E2E-1".to_string(), "INSTR-1".to_string(), "MSG-1".to_string(),
+            dec!(1500.50), Currency::AED,
+            party("Debtor"), party("Creditor"), agent("BANKAEADXXX"), agent("ICICINBBXXX"),
+        );
+        payment.uetr = Some(Uuid::new_v4());
+        payment
+    }
+
+    pub(crate) fn event(payment: &CanonicalPayment, source: EventSource, iso_status: &str) -> PaymentEvent {
+        PaymentEvent {
+            event_id: Uuid::new_v4(),
+            deltran_tx_id: payment.deltran_tx_id,
+            uetr: payment.uetr,
+            source,
+            event_type: format!("{}.event", source.as_str()),
+            status: None,
+            iso_status: iso_status.to_string(),
+            reason_code: None,
+            agent_bic: None,
+            details: serde_json::Value::Null,
+            occurred_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_build_trck001_conforms_to_xsd() {
+        use crate::validation::xsd::{SchemaModel, XsdValidator, ValidationMode};
+
+        let payment = tracked_payment();
+        let document = build_trck001(&payment, &event(&payment, EventSource::Clearing, "ACSP"), Some("DLTRAEADXXX")).unwrap();
+        assert_eq!(document.group_status.as_deref(), Some("ACSP"));
+
+        let xsd = std::fs::read_to_string(concat!(
+            env!("CARGO_MANIFEST_DIR"), "/../../iso20022/payment_tracking/trck.001.001.04.xsd"
+        )).unwrap();
+        let validator = XsdValidator::new(vec![SchemaModel::parse(&xsd).unwrap()], ValidationMode::Strict);
+        assert!(validator.validate("trck.001", &document.xml).is_ok(), "{:?}", validator.validate("trck.001", &document.xml).err());
+    }
+
+    #[test]
+    fn test_parse_trck001_roundtrip() {
+        let payment = tracked_payment();
+        let mut partner_event = event(&payment, EventSource::Partner, "ACCC");
+        partner_event.agent_bic = Some("ICICINBBXXX".to_string());
+        partner_event.reason_code = Some("G000".to_string());
+
+        let document = build_trck001(&payment, &partner_event, Some("ICICINBBXXX")).unwrap();
+        let updates = to_tracker_updates(&parse_trck001(&document.xml).unwrap()).unwrap();
+
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].uetr, payment.uetr.unwrap());
+        assert_eq!(updates[0].status, "ACCC");
+        assert_eq!(updates[0].reason_code.as_deref(), Some("G000"));
+        assert_eq!(updates[0].informing_agent_bic.as_deref(), Some("ICICINBBXXX"));
+        assert!(updates[0].status_at.is_some());
+    }
+}
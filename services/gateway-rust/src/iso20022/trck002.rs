@@ -0,0 +1,138 @@
+// trck.002.001.03 - Payment Status Tracker Report
+// DelTran's view of a payment: current status plus the full event timeline (one TrckrRcrd per event)
+
+use serde::{Deserialize, Serialize};
+use chrono::Utc;
+use anyhow::{Result, anyhow};
+
+use crate::models::canonical::CanonicalPayment;
+use crate::models::tracking::PaymentEvent;
+use super::outbound::{self, IsoTransactionStatus, OutboundDocument};
+use super::trck001::{self, TrackerParty, TrackerStatusAndTransaction, TRCK001_MESSAGE_DEFINITION};
+
+/// Message definition of generated trck.002 reports
+pub const TRCK002_MESSAGE_DEFINITION: &str = "trck.002.001.03";
+
+/// trck.002 Document root
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Document {
+    #[serde(rename = "@xmlns", default, skip_serializing_if = "Option::is_none")]
+    pub xmlns: Option<String>,
+
+    #[serde(rename = "PmtStsTrckrRpt")]
+    pub tracker_report: PaymentStatusTrackerReport,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PaymentStatusTrackerReport {
+    #[serde(rename = "GrpHdr")]
+    pub grp_hdr: TrackerReportHeader,
+
+    #[serde(rename = "TrckrStsAndTx")]
+    pub status_and_transactions: Vec<TrackerStatusAndTransaction>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TrackerReportHeader {
+    #[serde(rename = "MsgId")]
+    pub msg_id: String,
+
+    #[serde(rename = "CreDtTm", skip_serializing_if = "Option::is_none")]
+    pub cre_dt_tm: Option<String>,
+
+    #[serde(rename = "NbOfTxs", skip_serializing_if = "Option::is_none")]
+    pub number_of_transactions: Option<String>,
+
+    #[serde(rename = "TrckrInfrmgPty", skip_serializing_if = "Option::is_none")]
+    pub informing_party: Option<TrackerParty>,
+
+    #[serde(rename = "OrgnlTrckrUpd", skip_serializing_if = "Option::is_none")]
+    pub original_tracker_update: Option<OriginalTrackerUpdate>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct OriginalTrackerUpdate {
+    #[serde(rename = "MsgId")]
+    pub msg_id: String,
+
+    #[serde(rename = "MsgNmId", skip_serializing_if = "Option::is_none")]
+    pub msg_nm_id: Option<String>,
+}
+
+/// Build a trck.002 report for a payment and its timeline.
+/// `original_update_id` links the report to the trck.001 it answers, if any.
+pub fn build_trck002(
+    payment: &CanonicalPayment,
+    events: &[PaymentEvent],
+    original_update_id: Option<&str>,
+    deltran_bic: Option<&str>,
+) -> Result<OutboundDocument> {
+    if payment.uetr.is_none() {
+        return Err(anyhow!("Payment {} has no UETR - cannot be tracked", payment.deltran_tx_id));
+    }
+
+    let status = IsoTransactionStatus::from_payment_status(&payment.status).code();
+    let message_id = outbound::generate_identifier("TRCK002");
+
+    let document = Document {
+        xmlns: Some(format!("urn:iso:std:iso:20022:tech:xsd:{}", TRCK002_MESSAGE_DEFINITION)),
+        tracker_report: PaymentStatusTrackerReport {
+            grp_hdr: TrackerReportHeader {
+                msg_id: message_id.clone(),
+                cre_dt_tm: Some(outbound::iso_date_time(Utc::now())),
+                number_of_transactions: Some("1".to_string()),
+                informing_party: deltran_bic.map(TrackerParty::agent),
+                original_tracker_update: original_update_id.map(|id| OriginalTrackerUpdate {
+                    msg_id: id.to_string(),
+                    msg_nm_id: Some(TRCK001_MESSAGE_DEFINITION.to_string()),
+                }),
+            },
+            status_and_transactions: vec![trck001::tracker_status_and_transaction(
+                payment,
+                status,
+                payment.updated_at,
+                payment.status_reason.as_ref().map(|r| r.code.as_str()),
+                events,
+            )?],
+        },
+    };
+
+    Ok(OutboundDocument {
+        message_definition: TRCK002_MESSAGE_DEFINITION,
+        xml: outbound::render_xml(&document)?,
+        message_id,
+        group_status: Some(status.to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::tracking::EventSource;
+    use crate::iso20022::trck001::tests::{event, tracked_payment};
+
+    #[test]
+    fn test_build_trck002_conforms_to_xsd() {
+        use crate::validation::xsd::{SchemaModel, XsdValidator, ValidationMode};
+
+        let payment = tracked_payment();
+        let events = vec![
+            event(&payment, EventSource::Gateway, "ACTC"),
+            event(&payment, EventSource::Compliance, "ACTC"),
+            event(&payment, EventSource::Clearing, "ACSP"),
+        ];
+        let document = build_trck002(&payment, &events, Some("TRCK-UPD-1"), Some("DLTRAEADXXX")).unwrap();
+
+        let xsd = std::fs::read_to_string(concat!(
+            env!("CARGO_MANIFEST_DIR"), "/../../iso20022/payment_tracking/trck.002.001.03.xsd"
+        )).unwrap();
+        let validator = XsdValidator::new(vec![SchemaModel::parse(&xsd).unwrap()], ValidationMode::Strict);
+        assert!(validator.validate("trck.002", &document.xml).is_ok(), "{:?}", validator.validate("trck.002", &document.xml).err());
+
+        let parsed: Document = quick_xml::de::from_str(&document.xml).unwrap();
+        let report = &parsed.tracker_report;
+        assert_eq!(report.grp_hdr.original_tracker_update.as_ref().unwrap().msg_id, "TRCK-UPD-1");
+        assert_eq!(report.status_and_transactions[0].transaction_status.status, "ACTC");
+        assert_eq!(report.status_and_transactions[0].transactions[0].tracker_records.len(), 3);
+    }
+}
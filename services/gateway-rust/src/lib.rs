@@ -5,3 +5,16 @@ pub mod models;
 pub mod iso20022;
 pub mod nats_router;
 pub mod db;
+pub mod validation;
+pub mod metrics;
+pub mod status_reports;
+pub mod idempotency;
+pub mod tracking;
+pub mod bulk;
+pub mod outbox;
+pub mod auth;
+pub mod signing;
+pub mod reconciliation;
+pub mod funding;
+pub mod webhooks;
+pub mod stream;
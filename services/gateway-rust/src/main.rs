@@ -2,10 +2,11 @@
 // Handles incoming ISO 20022 messages and routes to appropriate services via NATS
 
 use axum::{
-    extract::{State, Path},
-    http::StatusCode,
+    extract::{ws::WebSocketUpgrade, DefaultBodyLimit, Extension, Multipart, Path, Query, Request, State},
+    http::{header, HeaderMap, HeaderName, HeaderValue, Method, StatusCode},
+    middleware::{self, Next},
     response::{IntoResponse, Response},
-    routing::{post, get},
+    routing::{delete, post, get},
     Router, Json,
 };
 use serde::{Deserialize, Serialize};
@@ -16,22 +17,56 @@ use std::sync::Arc;
 use tracing::{info, error, warn, debug};
 use chrono::Utc;
 
-mod models;
-mod iso20022;
-mod nats_router;
-mod db;
-mod metrics;
+use deltran_gateway::{
+    models, iso20022, nats_router, db, metrics, validation, status_reports, idempotency, tracking,
+    bulk, outbox, auth, signing, reconciliation, funding, webhooks, stream,
+};
 
-use models::canonical::{CanonicalPayment, PaymentStatus};
+use models::canonical::{CanonicalPayment, PaymentStatus, StatusReason};
 use iso20022::pain001;
+use iso20022::{AppHdr, BusinessMessage, CancellationResolution, CancellationStatus};
 use nats_router::NatsRouter;
 use metrics::METRICS;
+use validation::{XsdValidator, ValidationMode, XsdValidationReport, RuleEngine};
+use validation::rules;
+use status_reports::{StatusReporter, StatusReportRecord, StatusReportType};
+use idempotency::{IdempotencyGuard, DuplicateKey, KeyReplay};
+use models::tracking::PaymentTimeline;
+use tracking::PaymentTracker;
+use models::batch::{BatchTransaction, PaymentBatch, TransactionOutcome};
+use bulk::BulkIngestion;
+use outbox::OutboxRelay;
+use auth::Authenticator;
+use signing::{MessageSigner, PublishedKey, SignatureVerifier, SIGNATURE_HEADER};
+use models::participant::{AuthAuditEntry, AuthFailure, AuthenticatedParticipant};
+use models::reconciliation::StatementReconciliation;
+use reconciliation::Reconciler;
+use funding::FundingQueue;
+use webhooks::WebhookDispatcher;
+use stream::PaymentStream;
+use models::search::{PaymentCursor, PaymentFilter, PaymentPage, PaymentSearchQuery, PaymentStats, PaymentSummary, EXPORT_MAX_ROWS};
+use models::funding::{FundingEventRecord, FundingEventState, FundingProgress, FundingRefund, MatchMethod};
+use models::webhook::{DeliveryState, WebhookDelivery, WebhookEndpoint};
+use models::stream::StreamFilter;
 
 #[derive(Clone)]
 pub struct AppState {
     pub db: PgPool,
     pub nats: NatsClient,
     pub router: Arc<NatsRouter>,
+    pub xsd: Arc<XsdValidator>,
+    pub rules: Arc<RuleEngine>,
+    pub reporter: Arc<StatusReporter>,
+    pub idempotency: Arc<IdempotencyGuard>,
+    pub tracker: Arc<PaymentTracker>,
+    pub bulk: Arc<BulkIngestion>,
+    pub auth: Arc<Authenticator>,
+    pub signer: Arc<MessageSigner>,
+    pub verifier: Arc<SignatureVerifier>,
+    pub reconciler: Arc<Reconciler>,
+    pub funding: Arc<FundingQueue>,
+    pub webhooks: Arc<WebhookDispatcher>,
+    pub stream: Arc<PaymentStream>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -42,6 +77,65 @@ pub struct MessageResponse {
     pub timestamp: chrono::DateTime<Utc>,
 }
 
+#[derive(Debug, Serialize)]
+pub struct BatchResponse {
+    #[serde(flatten)]
+    pub batch: PaymentBatch,
+    pub progress_percent: Option<f64>,
+}
+
+impl From<PaymentBatch> for BatchResponse {
+    fn from(batch: PaymentBatch) -> Self {
+        Self { progress_percent: batch.progress_percent(), batch }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BatchTransactionsQuery {
+    pub outcome: Option<String>,    // accepted, rejected, duplicate
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FundingEventsQuery {
+    pub state: Option<String>,      // pending, unmatched, matched
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FundingRefundsQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WebhookEndpointRequest {
+    pub url: String,
+    #[serde(default)]
+    pub event_types: Vec<String>,   // status_update, customer_status - empty = all
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WebhookDeliveriesQuery {
+    pub state: Option<String>,      // pending, delivered, dead
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StreamQuery {
+    pub uetr: Option<String>,       // comma-separated UETRs - default: all the caller's payments
+    pub from_sequence: Option<i64>, // last sequence received before a reconnect
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ManualFundingMatch {
+    pub deltran_tx_id: Uuid,
+    pub note: Option<String>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct HealthResponse {
     pub status: &'static str,
@@ -55,6 +149,10 @@ pub struct HealthResponse {
 pub enum GatewayError {
     ParseError(String),
     ValidationError(String),
+    SchemaValidationError(XsdValidationReport),
+    Conflict(String),
+    Unauthorized(String),
+    Forbidden(String),
     DatabaseError(sqlx::Error),
     NatsError(async_nats::Error),
     InternalError(String),
@@ -62,9 +160,23 @@ pub enum GatewayError {
 
 impl IntoResponse for GatewayError {
     fn into_response(self) -> Response {
+        if let GatewayError::SchemaValidationError(report) = self {
+            return (StatusCode::BAD_REQUEST, Json(serde_json::json!({
+                "error": format!("Validation error: {}", report),
+                "message_type": report.message_type,
+                "message_definition": report.message_definition,
+                "violations": report.violations,
+                "timestamp": Utc::now(),
+            }))).into_response();
+        }
+
         let (status, message) = match self {
             GatewayError::ParseError(msg) => (StatusCode::BAD_REQUEST, format!("Parse error: {}", msg)),
             GatewayError::ValidationError(msg) => (StatusCode::BAD_REQUEST, format!("Validation error: {}", msg)),
+            GatewayError::SchemaValidationError(report) => (StatusCode::BAD_REQUEST, format!("Validation error: {}", report)),
+            GatewayError::Conflict(msg) => (StatusCode::CONFLICT, format!("Conflict: {}", msg)),
+            GatewayError::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, format!("Unauthorized: {}", msg)),
+            GatewayError::Forbidden(msg) => (StatusCode::FORBIDDEN, format!("Forbidden: {}", msg)),
             GatewayError::DatabaseError(e) => (StatusCode::INTERNAL_SERVER_ERROR, format!("Database error: {}", e)),
             GatewayError::NatsError(e) => (StatusCode::INTERNAL_SERVER_ERROR, format!("NATS error: {}", e)),
             GatewayError::InternalError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, format!("Internal error: {}", msg)),
@@ -89,10 +201,352 @@ impl From<async_nats::Error> for GatewayError {
     }
 }
 
+// Participant authentication - every route except /health and /metrics
+async fn require_participant(
+    State(state): State<AppState>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, GatewayError> {
+    let authenticated = state.auth.authenticate(request.headers()).await
+        .map_err(|e| GatewayError::InternalError(e.to_string()))?;
+
+    match authenticated {
+        Ok(participant) => {
+            request.extensions_mut().insert(participant);
+            Ok(next.run(request).await)
+        }
+        Err(refused) => {
+            let mut entry = AuthAuditEntry::new(request.method().as_str(), request.uri().path(), refused.reason);
+            entry.subject = refused.subject;
+            state.auth.audit(entry).await;
+            Err(GatewayError::Unauthorized(refused.reason.as_str().to_string()))
+        }
+    }
+}
+
+// Refused authorization - written to the audit trail before answering
+async fn forbidden(state: &AppState, entry: AuthAuditEntry, message: String) -> GatewayError {
+    state.auth.audit(entry).await;
+    GatewayError::Forbidden(message)
+}
+
+// pain.001 / pacs.008: the submitting participant must be the debtor agent of every payment
+async fn authorize_submission(
+    state: &AppState,
+    caller: &AuthenticatedParticipant,
+    message_type: &str,
+    payments: &[CanonicalPayment],
+) -> Result<(), GatewayError> {
+    let Some(foreign) = payments.iter().find(|p| !caller.may_submit(p)) else {
+        return Ok(());
+    };
+
+    let entry = AuthAuditEntry::forbidden(caller, "POST", &format!("/iso20022/{}", message_type), AuthFailure::NotDebtorAgent)
+        .message_type(message_type)
+        .claimed_bic(foreign.debtor_agent.bic.as_deref())
+        .detail(format!("end_to_end_id {}", foreign.end_to_end_id));
+    Err(forbidden(state, entry, format!("{} is not the debtor agent of {}", caller.bic, foreign.end_to_end_id)).await)
+}
+
+// Follow-up messages and queries: only the debtor / creditor agent sees a payment.
+// A refused caller is audited and gets the same answer as for an unknown payment.
+async fn authorize_access(
+    state: &AppState,
+    caller: &AuthenticatedParticipant,
+    payment: &CanonicalPayment,
+    method: &str,
+    path: &str,
+    message_type: Option<&str>,
+) -> bool {
+    if caller.may_access(payment) {
+        return true;
+    }
+
+    let mut entry = AuthAuditEntry::forbidden(caller, method, path, AuthFailure::NotPaymentParty)
+        .claimed_bic(payment.debtor_agent.bic.as_deref())
+        .detail(format!("deltran_tx_id {}", payment.deltran_tx_id));
+    if let Some(message_type) = message_type {
+        entry = entry.message_type(message_type);
+    }
+    state.auth.audit(entry).await;
+    false
+}
+
+// Signature stage - detached JWS of the submitting participant over the raw document
+async fn verify_signature(
+    state: &AppState,
+    caller: &AuthenticatedParticipant,
+    headers: &HeaderMap,
+    message_type: &str,
+    payload: &[u8],
+) -> Result<(), GatewayError> {
+    verify_signature_at(state, caller, headers, message_type, &format!("/iso20022/{}", message_type), payload).await
+}
+
+async fn verify_signature_at(
+    state: &AppState,
+    caller: &AuthenticatedParticipant,
+    headers: &HeaderMap,
+    message_type: &str,
+    path: &str,
+    payload: &[u8],
+) -> Result<(), GatewayError> {
+    let signature = headers.get(SIGNATURE_HEADER).and_then(|v| v.to_str().ok());
+    let verified = state.verifier.verify(caller, message_type, signature, payload).await
+        .map_err(|e| GatewayError::InternalError(e.to_string()))?;
+
+    match verified {
+        Ok(Some(kid)) => {
+            debug!("🔏 {} signature verified ({} key {})", message_type, caller.bic, kid);
+            Ok(())
+        }
+        Ok(None) => Ok(()),
+        Err(reason) => {
+            let entry = AuthAuditEntry::forbidden(caller, "POST", path, reason).message_type(message_type);
+            state.auth.audit(entry).await;
+            Err(GatewayError::Unauthorized(reason.as_str().to_string()))
+        }
+    }
+}
+
+// Outbound ISO document with the gateway's detached JWS (stored reports keep their original one)
+fn signed_xml(state: &AppState, xml: String, signature: Option<String>) -> Result<Response, GatewayError> {
+    let signature = match signature {
+        Some(signature) => signature,
+        None => state.signer.sign(xml.as_bytes()).map_err(|e| GatewayError::InternalError(e.to_string()))?,
+    };
+    Ok((
+        [(header::CONTENT_TYPE, "application/xml".to_string()), (HeaderName::from_static("x-jws-signature"), signature)],
+        xml,
+    ).into_response())
+}
+
+// Envelope stage - splits off the head.001 AppHdr. The header must announce the endpoint's message
+// type and name the calling participant as sender (From).
+async fn open_business_message<'a>(
+    state: &AppState,
+    caller: &AuthenticatedParticipant,
+    message_type: &str,
+    body: &'a str,
+) -> Result<BusinessMessage<'a>, GatewayError> {
+    let message = BusinessMessage::parse(body).map_err(|e| {
+        METRICS.iso_parse_errors_total.inc();
+        GatewayError::ParseError(e.to_string())
+    })?;
+
+    if let Some(header) = &message.header {
+        METRICS.track_app_header(message_type);
+
+        if header.message_type() != Some(message_type) {
+            return Err(GatewayError::ValidationError(format!(
+                "AppHdr MsgDefIdr {} does not match endpoint {}", header.msg_def_idr, message_type
+            )));
+        }
+        if !caller.may_act_for(header.from_bic()) {
+            let entry = AuthAuditEntry::forbidden(caller, "POST", &format!("/iso20022/{}", message_type), AuthFailure::NotSender)
+                .message_type(message_type)
+                .claimed_bic(header.from_bic())
+                .detail(format!("BizMsgIdr {}", header.biz_msg_idr));
+            return Err(forbidden(state, entry, format!("{} is not the AppHdr sender {:?}", caller.bic, header.from_bic())).await);
+        }
+
+        debug!("📨 {} AppHdr {} from {:?} to {:?} (possible duplicate: {})",
+               message_type, header.biz_msg_idr, header.from_bic(), header.to_bic(), header.is_possible_duplicate());
+    }
+
+    Ok(message)
+}
+
+// XSD validation stage - runs before any parsing into canonical model
+fn validate_xsd(state: &AppState, message_type: &str, body: &str) -> Result<(), GatewayError> {
+    let start = std::time::Instant::now();
+    let result = state.xsd.validate(message_type, body);
+    METRICS.xsd_validation_duration_seconds.observe(start.elapsed().as_secs_f64());
+    METRICS.track_xsd_validation(message_type, result.is_ok());
+
+    result.map_err(|report| {
+        warn!("❌ {} rejected by XSD validation: {} violation(s)", message_type, report.violations.len());
+        GatewayError::SchemaValidationError(report)
+    })
+}
+
+// Corridor business rules over the canonical payment - a violation rejects it with the ISO reason code
+fn apply_business_rules(state: &AppState, payment: &mut CanonicalPayment) -> bool {
+    let violations = state.rules.validate(payment);
+    let Some(reason) = rules::rejection_reason(&violations) else {
+        return true;
+    };
+
+    warn!("❌ Payment {} rejected by business rules: {} {}", payment.end_to_end_id, reason.code, reason.description);
+    METRICS.payments_rejected.inc();
+    payment.update_status(PaymentStatus::Rejected, Some(reason));
+    false
+}
+
+fn rejected_response(payment: &CanonicalPayment) -> MessageResponse {
+    let reason = payment.status_reason.as_ref();
+    MessageResponse {
+        deltran_tx_id: payment.deltran_tx_id,
+        status: "REJECTED".to_string(),
+        message: format!("Payment rejected: {} {}",
+                         reason.map_or("", |r| r.code.as_str()),
+                         reason.map_or("", |r| r.description.as_str())),
+        timestamp: Utc::now(),
+    }
+}
+
+// Idempotent ingestion - optional client supplied Idempotency-Key header, otherwise the
+// BizMsgIdr of the sender's AppHdr
+fn idempotency_key(headers: &HeaderMap, app_header: Option<&AppHdr>) -> Option<(String, DuplicateKey)> {
+    headers.get("Idempotency-Key")
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+        .map(|key| (key.to_string(), DuplicateKey::IdempotencyKey))
+        .or_else(|| {
+            app_header
+                .and_then(IdempotencyGuard::app_header_key)
+                .map(|key| (key, DuplicateKey::BusinessMessageId))
+        })
+}
+
+// Reserve the caller's key for this submission. A key already held within the dedup horizon is
+// answered with the original payments. A resend is expected to carry PssblDplct - unflagged
+// replays are still answered but logged.
+async fn reserve_idempotency_key(
+    state: &AppState,
+    caller: &AuthenticatedParticipant,
+    (key, matched_on): &(String, DuplicateKey),
+    message_type: &str,
+    message_id: &str,
+    app_header: Option<&AppHdr>,
+) -> Result<Option<Vec<MessageResponse>>, GatewayError> {
+    let replay = state.idempotency.reserve_key(&caller.bic, key, *matched_on, message_type, message_id).await
+        .map_err(|e| GatewayError::InternalError(e.to_string()))?;
+    let possible_duplicate = app_header.is_some_and(AppHdr::is_possible_duplicate);
+
+    match replay {
+        KeyReplay::New => {
+            if possible_duplicate {
+                info!("📨 {} flagged PssblDplct but not seen before - processing", key);
+                METRICS.track_possible_duplicate("new");
+            }
+            Ok(None)
+        }
+        KeyReplay::Replay(tx_ids) => {
+            if possible_duplicate {
+                METRICS.track_possible_duplicate("replayed");
+            } else if app_header.is_some() {
+                warn!("⚠️ {} resent without PssblDplct", key);
+                METRICS.track_possible_duplicate("unflagged_replay");
+            }
+            info!("♻️ {} {} replayed - returning {} original payment(s)", matched_on.as_str(), key, tx_ids.len());
+            Ok(Some(tx_ids.into_iter().map(|id| duplicate_response(id, *matched_on)).collect()))
+        }
+        KeyReplay::InProgress => Err(GatewayError::Conflict(format!(
+            "{} {} is still being processed - retry later", matched_on.as_str(), key
+        ))),
+        KeyReplay::Conflict { original_message_id } => Err(GatewayError::Conflict(format!(
+            "{} {} already used for message {}",
+            if *matched_on == DuplicateKey::BusinessMessageId { "AppHdr BizMsgIdr" } else { "Idempotency-Key" },
+            key,
+            original_message_id
+        ))),
+    }
+}
+
+fn duplicate_response(deltran_tx_id: Uuid, matched_on: DuplicateKey) -> MessageResponse {
+    MessageResponse {
+        deltran_tx_id,
+        status: "DUPLICATE".to_string(),
+        message: format!("Duplicate submission (matched on {}) - original payment returned", matched_on.as_str()),
+        timestamp: Utc::now(),
+    }
+}
+
+async fn complete_idempotency_key(state: &AppState, caller: &AuthenticatedParticipant, key: Option<&str>, responses: &[MessageResponse]) {
+    if let Some(key) = key {
+        let tx_ids: Vec<Uuid> = responses.iter().map(|r| r.deltran_tx_id).collect();
+        if let Err(e) = state.idempotency.complete_key(&caller.bic, key, &tx_ids).await {
+            error!("Failed to store Idempotency-Key {}: {}", key, e);
+        }
+    }
+}
+
+// A failed submission gives its key back - a retry is processed (payments already stored are
+// recognised as duplicates on UETR / MsgId + EndToEndId)
+async fn release_idempotency_key(state: &AppState, caller: &AuthenticatedParticipant, key: Option<&str>) {
+    if let Some(key) = key {
+        if let Err(e) = state.idempotency.release_key(&caller.bic, key).await {
+            error!("Failed to release Idempotency-Key {}: {}", key, e);
+        }
+    }
+}
+
+// Outbound pain.002 / pacs.002 generation - a failed report never fails the inbound request
+async fn report_intake(state: &AppState, payments: &[CanonicalPayment], report_type: StatusReportType) {
+    if payments.is_empty() {
+        return;
+    }
+    if let Err(e) = state.reporter.report_intake(payments, report_type).await {
+        error!("Failed to generate {} for intake: {}", report_type.message_type(), e);
+    }
+    for payment in payments {
+        track_status_change(state, payment).await;
+    }
+}
+
+// Late funding match for new payments against queued camt.054 credits - never fails the request
+async fn fund_from_queue(state: &AppState, payments: &mut [CanonicalPayment]) {
+    for payment in payments {
+        match state.funding.match_arrival(payment).await {
+            Ok(Some(progress)) => info!("💰 Payment {} {} from queued camt.054 entries", payment.deltran_tx_id, progress.as_str()),
+            Ok(None) => {}
+            Err(e) => error!("Funding match for {} failed: {}", payment.deltran_tx_id, e),
+        }
+    }
+}
+
+async fn report_status_change(state: &AppState, payment: &CanonicalPayment) {
+    if let Err(e) = state.reporter.report_status_change(payment).await {
+        error!("Failed to generate status report for {}: {}", payment.deltran_tx_id, e);
+    }
+    track_status_change(state, payment).await;
+}
+
+// Payment tracking timeline - like status reports, never fails the inbound request
+async fn track_status_change(state: &AppState, payment: &CanonicalPayment) {
+    if let Err(e) = state.tracker.record_status_change(payment).await {
+        error!("Failed to record tracking event for {}: {}", payment.deltran_tx_id, e);
+    }
+}
+
+// Link a return / cancellation to the original payment: UETR first, EndToEndId as fallback
+async fn find_original_payment(
+    state: &AppState,
+    uetr: Option<Uuid>,
+    end_to_end_id: Option<&str>,
+) -> Result<Option<CanonicalPayment>, GatewayError> {
+    let tx_id = match uetr {
+        Some(uetr) => db::get_payment_id_by_uetr(&state.db, uetr).await
+            .map_err(|e| GatewayError::InternalError(e.to_string()))?,
+        None => None,
+    };
+
+    match tx_id {
+        Some(tx_id) => db::get_payment_by_id(&state.db, tx_id).await,
+        None => match end_to_end_id {
+            Some(end_to_end_id) => db::get_payment_by_e2e(&state.db, end_to_end_id).await,
+            None => Ok(None),
+        },
+    }
+    .map_err(|e| GatewayError::InternalError(e.to_string()))
+}
+
 // Health check endpoint
 async fn health_check(State(state): State<AppState>) -> Json<HealthResponse> {
     let db_connected = sqlx::query("SELECT 1").fetch_optional(&state.db).await.is_ok();
-    let nats_connected = !state.nats.is_closed();
+    let nats_connected = state.nats.connection_state() == async_nats::connection::State::Connected;
 
     Json(HealthResponse {
         status: if db_connected && nats_connected { "healthy" } else { "degraded" },
@@ -103,15 +557,59 @@ async fn health_check(State(state): State<AppState>) -> Json<HealthResponse> {
     })
 }
 
+// Gateway public keys - participants verify outbound signatures with these (old keys stay listed after rotation)
+async fn signing_keys_handler(State(state): State<AppState>) -> Json<Vec<PublishedKey>> {
+    Json(state.signer.public_keys())
+}
+
 // Prometheus metrics endpoint
 async fn metrics_handler() -> Result<String, GatewayError> {
     METRICS.export()
         .map_err(|e| GatewayError::InternalError(format!("Failed to export metrics: {}", e)))
 }
 
+// Single ISO 20022 endpoint - dispatched on the AppHdr MsgDefIdr (or the Document namespace of a
+// bare Document) to the per-message handler, which then applies its own checks
+async fn handle_iso20022(
+    State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedParticipant>,
+    headers: HeaderMap,
+    body: String,
+) -> Result<Response, GatewayError> {
+    let message_type = BusinessMessage::parse(&body)
+        .map_err(|e| {
+            METRICS.iso_parse_errors_total.inc();
+            GatewayError::ParseError(e.to_string())
+        })?
+        .message_type()
+        .ok_or_else(|| GatewayError::ValidationError(
+            "Message type not found (no AppHdr MsgDefIdr or ISO 20022 Document namespace)".to_string()
+        ))?;
+
+    debug!("📨 POST /iso20022 dispatched as {}", message_type);
+
+    let (state, caller) = (State(state), Extension(caller));
+    let response = match message_type.as_str() {
+        "pain.001" => handle_pain001(state, caller, headers, body).await.into_response(),
+        "pacs.008" => handle_pacs008(state, caller, headers, body).await.into_response(),
+        "camt.054" => handle_camt054(state, caller, headers, body).await.into_response(),
+        "pacs.002" => handle_pacs002(state, caller, headers, body).await.into_response(),
+        "pain.002" => handle_pain002(state, caller, headers, body).await.into_response(),
+        "camt.053" => handle_camt053(state, caller, headers, body).await.into_response(),
+        "pacs.004" => handle_pacs004(state, caller, headers, body).await.into_response(),
+        "camt.056" => handle_camt056(state, caller, headers, body).await.into_response(),
+        "trck.001" => handle_trck001(state, caller, headers, body).await.into_response(),
+        other => return Err(GatewayError::ValidationError(format!("Unsupported message type {}", other))),
+    };
+
+    Ok(response)
+}
+
 // pain.001 - Customer Credit Transfer Initiation
 async fn handle_pain001(
     State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedParticipant>,
+    headers: HeaderMap,
     body: String,
 ) -> Result<Json<Vec<MessageResponse>>, GatewayError> {
     let start = std::time::Instant::now();
@@ -119,9 +617,13 @@ async fn handle_pain001(
 
     info!("Received pain.001 message");
 
+    verify_signature(&state, &caller, &headers, "pain.001", body.as_bytes()).await?;
+    let message = open_business_message(&state, &caller, "pain.001", &body).await?;
+    validate_xsd(&state, "pain.001", message.document)?;
+
     // Parse ISO message
     let parse_start = std::time::Instant::now();
-    let document = pain001::parse_pain001(&body)
+    let document = pain001::parse_pain001(message.document)
         .map_err(|e| {
             METRICS.iso_parse_errors_total.inc();
             GatewayError::ParseError(e.to_string())
@@ -131,52 +633,239 @@ async fn handle_pain001(
     // Convert to canonical model
     let canonical_payments = pain001::to_canonical(&document)
         .map_err(|e| GatewayError::ParseError(e.to_string()))?;
+    authorize_submission(&state, &caller, "pain.001", &canonical_payments).await?;
 
-    let mut responses = Vec::new();
-
-    for payment in canonical_payments {
-        info!("Processing payment: {} (end_to_end_id: {}, UETR: {:?})",
-              payment.deltran_tx_id, payment.end_to_end_id, payment.uetr);
+    let message_id = canonical_payments.first().map(|p| p.message_id.clone()).unwrap_or_default();
+    let idempotency_key = idempotency_key(&headers, message.header.as_ref());
+    if let Some(key) = &idempotency_key {
+        if let Some(responses) = reserve_idempotency_key(&state, &caller, key, "pain.001", &message_id, message.header.as_ref()).await? {
+            return Ok(Json(responses));
+        }
+    }
 
-        METRICS.payments_total.inc();
-        METRICS.payments_received.inc();
+    let mut responses = Vec::new();
+    let mut intake = Vec::new();
+
+    let stored: Result<(), GatewayError> = async {
+        for mut payment in canonical_payments {
+            info!("Processing payment: {} (end_to_end_id: {}, UETR: {:?})",
+                  payment.deltran_tx_id, payment.end_to_end_id, payment.uetr);
+
+            // Duplicate detection - no second insert, no second compliance run
+            if let Some(duplicate) = state.idempotency.find_duplicate(&payment).await
+                .map_err(|e| GatewayError::InternalError(e.to_string()))?
+            {
+                warn!("♻️ Duplicate payment {} (end_to_end_id: {}) - original {}",
+                      payment.deltran_tx_id, payment.end_to_end_id, duplicate.deltran_tx_id);
+                responses.push(duplicate_response(duplicate.deltran_tx_id, duplicate.matched_on));
+                continue;
+            }
 
-        // Persist to database
-        let db_start = std::time::Instant::now();
-        db::insert_payment(&state.db, &payment).await.map_err(|e| {
-            METRICS.db_errors_total.inc();
-            e
-        })?;
-        METRICS.db_operations_total.inc();
-        METRICS.db_operation_duration_seconds.observe(db_start.elapsed().as_secs_f64());
+            METRICS.payments_total.inc();
+            METRICS.payments_received.inc();
 
-        // CORRECT ORDER according to DelTran architecture:
-        // Gateway → Compliance (ONLY!)
-        // Compliance will route to Obligation if ALLOW
-        // Obligation will route based on payment type:
-        //   - INTERNATIONAL → Risk Engine → Liquidity Router → Clearing → Settlement
-        //   - LOCAL → Clearing (direct) → Settlement
+            // Rejected by corridor rules - stored for tracking and reported in the pain.002, never routed
+            if !apply_business_rules(&state, &mut payment) {
+                if let Some(duplicate) = state.idempotency.store(&payment, "pain.001", &[]).await
+                    .map_err(|e| GatewayError::InternalError(e.to_string()))?
+                {
+                    responses.push(duplicate_response(duplicate.deltran_tx_id, duplicate.matched_on));
+                    continue;
+                }
+                responses.push(rejected_response(&payment));
+                intake.push(payment);
+                continue;
+            }
 
-        info!("🔒 Routing to Compliance Engine for AML/KYC/sanctions check");
-        info!("   Compliance will then route to Obligation Engine if payment is ALLOWED");
-        info!("   Obligation will determine: INTERNATIONAL (Risk → Liquidity → Clearing) vs LOCAL (Clearing direct)");
-        state.router.route_to_compliance_engine(&payment).await?;
+            // CORRECT ORDER according to DelTran architecture:
+            // Gateway → Compliance (ONLY!)
+            // Compliance will route to Obligation if ALLOW
+            // Obligation will route based on payment type:
+            //   - INTERNATIONAL → Risk Engine → Liquidity Router → Clearing → Settlement
+            //   - LOCAL → Clearing (direct) → Settlement
+
+            info!("🔒 Routing to Compliance Engine for AML/KYC/sanctions check");
+            info!("   Compliance will then route to Obligation Engine if payment is ALLOWED");
+            info!("   Obligation will determine: INTERNATIONAL (Risk → Liquidity → Clearing) vs LOCAL (Clearing direct)");
+            let compliance = NatsRouter::compliance_message(&payment)
+                .map_err(|e| GatewayError::InternalError(e.to_string()))?;
+
+            // Persist payment and compliance message atomically - the outbox relay publishes it.
+            // Checked for duplicates again under lock: a concurrent resubmission may have won.
+            let db_start = std::time::Instant::now();
+            let duplicate = state.idempotency.store(&payment, "pain.001", &[compliance]).await.map_err(|e| {
+                METRICS.db_errors_total.inc();
+                GatewayError::InternalError(e.to_string())
+            })?;
+            METRICS.db_operations_total.inc();
+            METRICS.db_operation_duration_seconds.observe(db_start.elapsed().as_secs_f64());
+
+            if let Some(duplicate) = duplicate {
+                warn!("♻️ Concurrent duplicate payment {} (end_to_end_id: {}) - original {}",
+                      payment.deltran_tx_id, payment.end_to_end_id, duplicate.deltran_tx_id);
+                responses.push(duplicate_response(duplicate.deltran_tx_id, duplicate.matched_on));
+                continue;
+            }
 
-        responses.push(MessageResponse {
-            deltran_tx_id: payment.deltran_tx_id,
-            status: "RECEIVED".to_string(),
-            message: format!("Payment initiated: {} (UETR: {:?})", payment.end_to_end_id, payment.uetr),
-            timestamp: Utc::now(),
-        });
+            responses.push(MessageResponse {
+                deltran_tx_id: payment.deltran_tx_id,
+                status: "RECEIVED".to_string(),
+                message: format!("Payment initiated: {} (UETR: {:?})", payment.end_to_end_id, payment.uetr),
+                timestamp: Utc::now(),
+            });
+            intake.push(payment);
+        }
+        Ok(())
+    }.await;
+    if let Err(e) = stored {
+        release_idempotency_key(&state, &caller, idempotency_key.as_ref().map(|(key, _)| key.as_str())).await;
+        return Err(e);
     }
 
+    report_intake(&state, &intake, StatusReportType::Pain002).await;
+    fund_from_queue(&state, &mut intake).await;
+    complete_idempotency_key(&state, &caller, idempotency_key.as_ref().map(|(key, _)| key.as_str()), &responses).await;
+
     METRICS.payment_processing_duration_seconds.observe(start.elapsed().as_secs_f64());
     Ok(Json(responses))
 }
 
+// pain.001 bulk - multipart upload (field "file") streamed to disk and processed in the background.
+// Answers 202 with the batch; each transaction is accepted or rejected on its own (pain.002 PART).
+async fn handle_pain001_bulk(
+    State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedParticipant>,
+    headers: HeaderMap,
+    mut multipart: Multipart,
+) -> Result<Response, GatewayError> {
+    METRICS.track_iso_message("pain.001");
+
+    let mut field = loop {
+        match multipart.next_field().await.map_err(|e| GatewayError::ParseError(e.to_string()))? {
+            Some(field) if field.name() == Some("file") || field.file_name().is_some() => break field,
+            Some(_) => continue,
+            None => return Err(GatewayError::ValidationError("Multipart upload has no \"file\" part".to_string())),
+        }
+    };
+
+    let batch = PaymentBatch::new(field.file_name().map(str::to_string), Some(caller.bic.clone()));
+    let path = state.bulk.spool_path(batch.batch_id);
+
+    // Spool chunk by chunk - the file is never held in memory as a whole
+    let spooled: Result<usize, GatewayError> = async {
+        use tokio::io::AsyncWriteExt;
+
+        let mut file = tokio::fs::File::create(&path).await
+            .map_err(|e| GatewayError::InternalError(format!("Failed to spool upload: {}", e)))?;
+        let mut size = 0;
+        while let Some(chunk) = field.chunk().await.map_err(|e| GatewayError::ParseError(e.to_string()))? {
+            size += chunk.len();
+            file.write_all(&chunk).await
+                .map_err(|e| GatewayError::InternalError(format!("Failed to spool upload: {}", e)))?;
+        }
+        file.flush().await
+            .map_err(|e| GatewayError::InternalError(format!("Failed to spool upload: {}", e)))?;
+        Ok(size)
+    }.await;
+
+    let size = match spooled {
+        Ok(size) => size,
+        Err(e) => {
+            let _ = tokio::fs::remove_file(&path).await;
+            return Err(e);
+        }
+    };
+    info!("📦 Bulk pain.001 {:?} received ({} bytes) as batch {}", batch.file_name, size, batch.batch_id);
+
+    // The JWS covers the whole file, so a signed upload is read back once for verification
+    if headers.contains_key(SIGNATURE_HEADER) || state.verifier.is_required("pain.001") {
+        let verified = match tokio::fs::read(&path).await {
+            Ok(document) => verify_signature_at(&state, &caller, &headers, "pain.001", "/iso20022/pain.001/bulk", &document).await,
+            Err(e) => Err(GatewayError::InternalError(format!("Failed to read spooled upload: {}", e))),
+        };
+        if let Err(e) = verified {
+            let _ = tokio::fs::remove_file(&path).await;
+            return Err(e);
+        }
+    }
+
+    let batch = state.bulk.submit(batch, path, caller).await
+        .map_err(|e| GatewayError::InternalError(e.to_string()))?;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        [(header::LOCATION, format!("/batches/{}", batch.batch_id))],
+        Json(BatchResponse::from(batch)),
+    ).into_response())
+}
+
+// Bulk batch progress
+async fn get_batch(
+    State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedParticipant>,
+    Path(batch_id): Path<Uuid>,
+) -> Result<Json<BatchResponse>, GatewayError> {
+    let batch = find_batch(&state, &caller, batch_id, &format!("/batches/{}", batch_id)).await?;
+
+    Ok(Json(BatchResponse::from(batch)))
+}
+
+// A batch is visible to the participant that uploaded it and to its debtor agent
+async fn find_batch(
+    state: &AppState,
+    caller: &AuthenticatedParticipant,
+    batch_id: Uuid,
+    path: &str,
+) -> Result<PaymentBatch, GatewayError> {
+    let not_found = || GatewayError::ValidationError(format!("Batch not found: {}", batch_id));
+    let batch = db::get_payment_batch(&state.db, batch_id).await
+        .map_err(|e| GatewayError::InternalError(e.to_string()))?
+        .ok_or_else(not_found)?;
+
+    if caller.may_act_for(batch.submitted_by_bic.as_deref()) || caller.may_act_for(batch.debtor_agent_bic.as_deref()) {
+        return Ok(batch);
+    }
+
+    let entry = AuthAuditEntry::forbidden(caller, "GET", path, AuthFailure::NotRecipient)
+        .claimed_bic(batch.submitted_by_bic.as_deref())
+        .detail(format!("batch {}", batch_id));
+    state.auth.audit(entry).await;
+    Err(not_found())
+}
+
+// Per-transaction outcomes of a bulk batch (?outcome=rejected&limit=&offset=)
+async fn get_batch_transactions(
+    State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedParticipant>,
+    Path(batch_id): Path<Uuid>,
+    Query(query): Query<BatchTransactionsQuery>,
+) -> Result<Json<Vec<BatchTransaction>>, GatewayError> {
+    find_batch(&state, &caller, batch_id, &format!("/batches/{}/transactions", batch_id)).await?;
+
+    let outcome = match query.outcome.as_deref() {
+        Some(value) => Some(TransactionOutcome::parse(value)
+            .ok_or_else(|| GatewayError::ValidationError(format!("Unknown outcome: {}", value)))?),
+        None => None,
+    };
+
+    let transactions = db::get_batch_transactions(
+        &state.db,
+        batch_id,
+        outcome,
+        query.limit.unwrap_or(1000).clamp(1, 10_000),
+        query.offset.unwrap_or(0).max(0),
+    )
+    .await
+    .map_err(|e| GatewayError::InternalError(e.to_string()))?;
+
+    Ok(Json(transactions))
+}
+
 // pacs.008 - FI to FI Customer Credit Transfer
 async fn handle_pacs008(
     State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedParticipant>,
+    headers: HeaderMap,
     body: String,
 ) -> Result<Json<Vec<MessageResponse>>, GatewayError> {
     let start = std::time::Instant::now();
@@ -184,8 +873,12 @@ async fn handle_pacs008(
 
     info!("Received pacs.008 FI-to-FI payment message");
 
+    verify_signature(&state, &caller, &headers, "pacs.008", body.as_bytes()).await?;
+    let message = open_business_message(&state, &caller, "pacs.008", &body).await?;
+    validate_xsd(&state, "pacs.008", message.document)?;
+
     // Parse ISO message
-    let document = iso20022::parse_pacs008(&body)
+    let document = iso20022::parse_pacs008(message.document)
         .map_err(|e| {
             METRICS.iso_parse_errors_total.inc();
             GatewayError::ParseError(e.to_string())
@@ -194,39 +887,95 @@ async fn handle_pacs008(
     // Convert to canonical model
     let canonical_payments = iso20022::pacs008_to_canonical(&document)
         .map_err(|e| GatewayError::ParseError(e.to_string()))?;
+    authorize_submission(&state, &caller, "pacs.008", &canonical_payments).await?;
 
-    let mut responses = Vec::new();
+    let message_id = canonical_payments.first().map(|p| p.message_id.clone()).unwrap_or_default();
+    let idempotency_key = idempotency_key(&headers, message.header.as_ref());
+    if let Some(key) = &idempotency_key {
+        if let Some(responses) = reserve_idempotency_key(&state, &caller, key, "pacs.008", &message_id, message.header.as_ref()).await? {
+            return Ok(Json(responses));
+        }
+    }
 
-    for payment in canonical_payments {
-        info!("Processing pacs.008 payment: {} (end_to_end_id: {})",
-              payment.deltran_tx_id, payment.end_to_end_id);
+    let mut responses = Vec::new();
+    let mut intake = Vec::new();
+
+    let stored: Result<(), GatewayError> = async {
+        for mut payment in canonical_payments {
+            info!("Processing pacs.008 payment: {} (end_to_end_id: {})",
+                  payment.deltran_tx_id, payment.end_to_end_id);
+
+            if let Some(duplicate) = state.idempotency.find_duplicate(&payment).await
+                .map_err(|e| GatewayError::InternalError(e.to_string()))?
+            {
+                warn!("♻️ Duplicate pacs.008 payment {} - original {}", payment.end_to_end_id, duplicate.deltran_tx_id);
+                responses.push(duplicate_response(duplicate.deltran_tx_id, duplicate.matched_on));
+                continue;
+            }
 
-        // Persist to database
-        db::insert_payment(&state.db, &payment).await?;
+            if !apply_business_rules(&state, &mut payment) {
+                if let Some(duplicate) = state.idempotency.store(&payment, "pacs.008", &[]).await
+                    .map_err(|e| GatewayError::InternalError(e.to_string()))?
+                {
+                    responses.push(duplicate_response(duplicate.deltran_tx_id, duplicate.matched_on));
+                    continue;
+                }
+                responses.push(rejected_response(&payment));
+                intake.push(payment);
+                continue;
+            }
 
-        // Route to Settlement Engine (pacs.008 is settlement instruction)
-        state.router.route_to_settlement_engine(&payment).await?;
+            // Persist together with the Settlement Engine message (pacs.008 is settlement instruction),
+            // unless a concurrent resubmission of the same payment was stored first
+            let settlement = NatsRouter::settlement_message(&payment)
+                .map_err(|e| GatewayError::InternalError(e.to_string()))?;
+            if let Some(duplicate) = state.idempotency.store(&payment, "pacs.008", &[settlement])
+                .await
+                .map_err(|e| GatewayError::InternalError(e.to_string()))?
+            {
+                warn!("♻️ Concurrent duplicate pacs.008 payment {} - original {}", payment.end_to_end_id, duplicate.deltran_tx_id);
+                responses.push(duplicate_response(duplicate.deltran_tx_id, duplicate.matched_on));
+                continue;
+            }
 
-        responses.push(MessageResponse {
-            deltran_tx_id: payment.deltran_tx_id,
-            status: "RECEIVED".to_string(),
-            message: format!("Settlement instruction received: {}", payment.end_to_end_id),
-            timestamp: Utc::now(),
-        });
+            responses.push(MessageResponse {
+                deltran_tx_id: payment.deltran_tx_id,
+                status: "RECEIVED".to_string(),
+                message: format!("Settlement instruction received: {}", payment.end_to_end_id),
+                timestamp: Utc::now(),
+            });
+            intake.push(payment);
+        }
+        Ok(())
+    }.await;
+    if let Err(e) = stored {
+        release_idempotency_key(&state, &caller, idempotency_key.as_ref().map(|(key, _)| key.as_str())).await;
+        return Err(e);
     }
 
+    report_intake(&state, &intake, StatusReportType::Pacs002).await;
+    fund_from_queue(&state, &mut intake).await;
+    complete_idempotency_key(&state, &caller, idempotency_key.as_ref().map(|(key, _)| key.as_str()), &responses).await;
+
+    METRICS.payment_processing_duration_seconds.observe(start.elapsed().as_secs_f64());
     Ok(Json(responses))
 }
 
 // camt.054 - Bank to Customer Debit/Credit Notification (FUNDING!)
 async fn handle_camt054(
     State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedParticipant>,
+    headers: HeaderMap,
     body: String,
 ) -> Result<Json<Vec<MessageResponse>>, GatewayError> {
     info!("🚨 Received camt.054 FUNDING notification - CRITICAL");
 
+    verify_signature(&state, &caller, &headers, "camt.054", body.as_bytes()).await?;
+    let message = open_business_message(&state, &caller, "camt.054", &body).await?;
+    validate_xsd(&state, "camt.054", message.document)?;
+
     // Parse ISO message
-    let document = iso20022::parse_camt054(&body)
+    let document = iso20022::parse_camt054(message.document)
         .map_err(|e| GatewayError::ParseError(e.to_string()))?;
 
     // Extract funding events
@@ -236,64 +985,384 @@ async fn handle_camt054(
     let mut responses = Vec::new();
 
     for event in funding_events {
-        // Only process CREDIT events (money IN) that are BOOKED
+        // Only CREDIT events (money IN) fund payments
         if !iso20022::is_credit_event(&event) {
             info!("Skipping DEBIT event (money out): {:?}", event.entry_reference);
             continue;
         }
 
-        if !iso20022::is_booked(&event) {
-            warn!("Skipping PENDING funding event: {:?}", event.entry_reference);
+        // Only the account servicer reports on the account - refused entries are not stored
+        if event.account_servicer_bic.is_some() && !caller.may_act_for(event.account_servicer_bic.as_deref()) {
+            let entry = AuthAuditEntry::forbidden(&caller, "POST", "/iso20022/camt.054", AuthFailure::NotAccountServicer)
+                .message_type("camt.054")
+                .claimed_bic(event.account_servicer_bic.as_deref())
+                .detail(format!("entry {:?}", event.entry_reference));
+            state.auth.audit(entry).await;
             continue;
         }
 
+        // Funding another bank's payment requires servicing the credited account - looked up in
+        // participant_accounts, the Acct/Svcr named in the message is not trusted
+        let account = db::get_participant_account(&state.db, &event.account).await
+            .map_err(|e| GatewayError::InternalError(e.to_string()))?;
+        let services_account = caller.services_account(account.as_ref());
+
+        // Every credit is kept until it funds a payment (pending, unknown or not yet arrived)
+        let stored = state.funding.record(&caller.bic, services_account, &event).await
+            .map_err(|e| GatewayError::InternalError(e.to_string()))?;
+
+        match stored.state {
+            FundingEventState::Matched => {
+                info!("♻️ camt.054 entry {:?} already funded payment {:?}", event.entry_reference, stored.deltran_tx_id);
+                METRICS.track_funding_event("duplicate");
+                responses.push(funding_response(&stored, "DUPLICATE", "Entry already funded a payment".to_string()));
+                continue;
+            }
+            FundingEventState::Pending => {
+                info!("⏳ PENDING funding event {:?} queued until booked", event.entry_reference);
+                METRICS.track_funding_event("pending");
+                responses.push(funding_response(&stored, "PENDING", format!(
+                    "Entry {} not booked - queued until a camt.054 books it", event.status
+                )));
+                continue;
+            }
+            FundingEventState::Unmatched => {}
+        }
+
         info!("💰 FUNDING CONFIRMED: {} {} on account {}",
               event.amount, event.currency, event.account);
 
-        // Try to match to existing payment by end_to_end_id or instruction_id
-        if let Some(end_to_end_id) = &event.end_to_end_id {
-            info!("💰 Matching funding to payment with end_to_end_id: {}", end_to_end_id);
-
-            // Update payment status to Funded in database
-            db::update_payment_status_by_e2e(&state.db, end_to_end_id, PaymentStatus::Funded).await?;
-
-            // Retrieve the funded payment to route to Token Engine
-            if let Some(payment) = db::get_payment_by_e2e(&state.db, end_to_end_id).await? {
-                info!("🪙 CRITICAL: Routing to Token Engine for minting (1:1 backing guarantee)");
-                info!("   Amount: {} {}", event.amount, event.currency);
-                info!("   UETR: {:?}", payment.uetr);
-
-                // Route to Token Engine for minting
-                // Tokens can ONLY be minted AFTER funding is confirmed via camt.054
-                // This enforces DelTran's 1:1 backing guarantee
-                state.router.route_to_token_engine(&payment).await?;
-
-                responses.push(MessageResponse {
-                    deltran_tx_id: payment.deltran_tx_id,
-                    status: "FUNDED".to_string(),
-                    message: format!("Funding confirmed: {} {} | Token minting triggered", event.amount, event.currency),
-                    timestamp: Utc::now(),
-                });
-            } else {
-                warn!("⚠️ Payment not found for end_to_end_id: {} - cannot mint tokens", end_to_end_id);
+        // Match by UETR / EndToEndId of a payment in the same currency first, fuzzily on amount +
+        // currency + debtor details otherwise
+        let by_reference = state.funding.find_by_reference(&stored).await
+            .map_err(|e| GatewayError::InternalError(e.to_string()))?;
+        let matched = match by_reference {
+            Some(payment) => {
+                // Only the registered servicer of the credited account or the debtor agent may confirm
+                // funding - this is what mints tokens. Refused entries stay queued, the payment stays invisible.
+                if !caller.may_confirm_funding(account.as_ref(), &payment) {
+                    let reason = match event.account_servicer_bic {
+                        Some(_) => AuthFailure::NotAccountServicer,
+                        None => AuthFailure::NotDebtorAgent,
+                    };
+                    let entry = AuthAuditEntry::forbidden(&caller, "POST", "/iso20022/camt.054", reason)
+                        .message_type("camt.054")
+                        .claimed_bic(event.account_servicer_bic.as_deref().or(payment.debtor_agent.bic.as_deref()))
+                        .detail(format!("end_to_end_id {}", payment.end_to_end_id));
+                    state.auth.audit(entry).await;
+                    None
+                } else if !funding::accepts_credit(&payment.status) {
+                    warn!("⚠️ Payment {} is {:?} - funding entry {:?} queued", payment.deltran_tx_id, payment.status, event.entry_reference);
+                    None
+                } else {
+                    Some((payment, MatchMethod::Reference))
+                }
+            }
+            None => state.funding.find_fuzzy(&stored).await
+                .map_err(|e| GatewayError::InternalError(e.to_string()))?
+                .map(|payment| (payment, MatchMethod::Fuzzy)),
+        };
+
+        if let Some((mut payment, method)) = matched {
+            let progress = state.funding.fund(&stored, &mut payment, method, None, None).await
+                .map_err(|e| GatewayError::InternalError(e.to_string()))?;
+            if let Some(progress) = progress {
+                responses.push(progress_response(&payment, method, &stored, &progress));
+                continue;
             }
-        } else {
-            warn!("camt.054 entry has no end_to_end_id - cannot match to payment");
         }
+
+        warn!("⚠️ No payment for funding entry {:?} (end_to_end_id: {:?}) - queued for late matching",
+              event.entry_reference, event.end_to_end_id);
+        METRICS.track_funding_event("queued");
+        responses.push(funding_response(&stored, "QUEUED", format!(
+            "No matching payment yet - {} {} queued for late matching", event.amount, event.currency
+        )));
     }
 
     Ok(Json(responses))
 }
 
+// Response for a camt.054 entry that did not fund a payment (yet) - carries the funding event id
+fn funding_response(event: &FundingEventRecord, status: &str, message: String) -> MessageResponse {
+    MessageResponse {
+        deltran_tx_id: event.deltran_tx_id.unwrap_or(event.event_id),
+        status: status.to_string(),
+        message,
+        timestamp: Utc::now(),
+    }
+}
+
+// Response for a camt.054 entry applied to a payment - partially funded, funded or over-funded
+fn progress_response(payment: &CanonicalPayment, method: MatchMethod, event: &FundingEventRecord, progress: &FundingProgress) -> MessageResponse {
+    let (status, message) = match progress {
+        FundingProgress::Partial { funded, outstanding } => ("PARTIALLY_FUNDED", format!(
+            "Partial funding ({}): {} {} | {} of {} funded, {} outstanding",
+            method.as_str(), event.amount, event.currency, funded, payment.instructed_amount, outstanding
+        )),
+        FundingProgress::Funded { mint_amount, excess, .. } => {
+            let mut message = format!(
+                "Funding confirmed ({}): {} {} | Token minting triggered for {}",
+                method.as_str(), event.amount, event.currency, mint_amount
+            );
+            if !excess.is_zero() {
+                message.push_str(&format!(" | {} {} over-funded, flagged for refund", excess, event.currency));
+            }
+            ("FUNDED", message)
+        }
+        FundingProgress::Overfunded { excess } => ("OVERFUNDED", format!(
+            "Payment already funded ({}) - {} {} flagged for refund", method.as_str(), excess, event.currency
+        )),
+    };
+
+    MessageResponse {
+        deltran_tx_id: payment.deltran_tx_id,
+        status: status.to_string(),
+        message,
+        timestamp: Utc::now(),
+    }
+}
+
+// Funding queue - operators see every entry, participants the ones they sent (?state=&limit=&offset=)
+async fn list_funding_events(
+    State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedParticipant>,
+    Query(query): Query<FundingEventsQuery>,
+) -> Result<Json<Vec<FundingEventRecord>>, GatewayError> {
+    let funding_state = query.state.as_deref()
+        .map(|s| FundingEventState::parse(s).ok_or_else(|| GatewayError::ValidationError(format!("Unknown funding state: {}", s))))
+        .transpose()?;
+    let sender = (!caller.is_operator()).then_some(caller.bic.as_str());
+
+    let events = db::list_funding_events(
+        &state.db, sender, funding_state, query.limit.unwrap_or(100).clamp(1, 1000), query.offset.unwrap_or(0).max(0),
+    ).await.map_err(|e| GatewayError::InternalError(e.to_string()))?;
+
+    Ok(Json(events))
+}
+
+// Manual match of a queued credit to a payment awaiting funding - operators only
+async fn match_funding_event(
+    State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedParticipant>,
+    Path(event_id): Path<Uuid>,
+    Json(request): Json<ManualFundingMatch>,
+) -> Result<Json<MessageResponse>, GatewayError> {
+    let path = format!("/funding/events/{}/match", event_id);
+    if !caller.is_operator() {
+        let entry = AuthAuditEntry::forbidden(&caller, "POST", &path, AuthFailure::NotOperator)
+            .detail(format!("funding event {} -> payment {}", event_id, request.deltran_tx_id));
+        return Err(forbidden(&state, entry, "Manual funding matches are reserved to operators".to_string()).await);
+    }
+
+    let event = db::get_funding_event(&state.db, event_id).await
+        .map_err(|e| GatewayError::InternalError(e.to_string()))?
+        .ok_or_else(|| GatewayError::ValidationError(format!("Funding event not found: {}", event_id)))?;
+    match event.state {
+        FundingEventState::Matched => {
+            return Err(GatewayError::Conflict(format!("Funding event {} already funded {:?}", event_id, event.deltran_tx_id)));
+        }
+        FundingEventState::Pending => {
+            return Err(GatewayError::ValidationError(format!("Funding event {} is not booked ({})", event_id, event.entry_status)));
+        }
+        FundingEventState::Unmatched => {}
+    }
+
+    let mut payment = db::get_payment_by_id(&state.db, request.deltran_tx_id).await
+        .map_err(|e| GatewayError::InternalError(e.to_string()))?
+        .ok_or_else(|| GatewayError::ValidationError(format!("Payment not found: {}", request.deltran_tx_id)))?;
+    if !funding::accepts_credit(&payment.status) {
+        return Err(GatewayError::Conflict(format!("Payment {} is {:?} and cannot take a credit", payment.deltran_tx_id, payment.status)));
+    }
+    if !event.same_currency(&payment) {
+        return Err(GatewayError::ValidationError(format!(
+            "Funding currency {} differs from payment currency {}", event.currency, payment.currency.code()
+        )));
+    }
+
+    info!("🖐️ Manual funding match by {}: event {} -> payment {}", caller.bic, event_id, payment.deltran_tx_id);
+    let progress = state.funding.fund(&event, &mut payment, MatchMethod::Manual, Some(&caller.bic), request.note.as_deref()).await
+        .map_err(|e| GatewayError::InternalError(e.to_string()))?
+        .ok_or_else(|| GatewayError::Conflict(format!("Funding event {} was matched concurrently", event_id)))?;
+
+    Ok(Json(progress_response(&payment, MatchMethod::Manual, &event, &progress)))
+}
+
+// Over-funded payments awaiting a refund - operators see all, participants their own (?limit=&offset=)
+async fn list_funding_refunds(
+    State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedParticipant>,
+    Query(query): Query<FundingRefundsQuery>,
+) -> Result<Json<Vec<FundingRefund>>, GatewayError> {
+    let debtor_agent = (!caller.is_operator()).then_some(caller.bic.as_str());
+
+    let refunds = db::list_funding_refunds(
+        &state.db, debtor_agent, query.limit.unwrap_or(100).clamp(1, 1000), query.offset.unwrap_or(0).max(0),
+    ).await.map_err(|e| GatewayError::InternalError(e.to_string()))?;
+
+    Ok(Json(refunds))
+}
+
+// Register a webhook endpoint for the caller's payment notifications
+async fn register_webhook_endpoint(
+    State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedParticipant>,
+    Json(request): Json<WebhookEndpointRequest>,
+) -> Result<(StatusCode, Json<WebhookEndpoint>), GatewayError> {
+    let url = reqwest::Url::parse(request.url.trim())
+        .map_err(|e| GatewayError::ValidationError(format!("Invalid webhook URL {}: {}", request.url, e)))?;
+    if !matches!(url.scheme(), "https" | "http") || url.host_str().is_none() {
+        return Err(GatewayError::ValidationError(format!("Webhook URL must be http(s): {}", request.url)));
+    }
+    if let Some(unknown) = request.event_types.iter().find(|t| !webhooks::EVENT_TYPES.contains(&t.as_str())) {
+        return Err(GatewayError::ValidationError(format!(
+            "Unknown webhook event type {} (expected one of {})", unknown, webhooks::EVENT_TYPES.join(", ")
+        )));
+    }
+
+    let now = Utc::now();
+    let endpoint = WebhookEndpoint {
+        endpoint_id: Uuid::new_v4(),
+        participant_bic: caller.bic.clone(),
+        url: url.to_string(),
+        event_types: request.event_types,
+        active: true,
+        created_at: now,
+        updated_at: now,
+    };
+    db::insert_webhook_endpoint(&state.db, &endpoint).await.map_err(|e| match e.downcast_ref::<sqlx::Error>() {
+        Some(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() =>
+            GatewayError::Conflict(format!("Webhook endpoint {} is already registered", endpoint.url)),
+        _ => GatewayError::InternalError(e.to_string()),
+    })?;
+
+    info!("🔔 Webhook endpoint {} registered by {}: {}", endpoint.endpoint_id, caller.bic, endpoint.url);
+    Ok((StatusCode::CREATED, Json(endpoint)))
+}
+
+// Active webhook endpoints - operators see all, participants their own
+async fn list_webhook_endpoints(
+    State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedParticipant>,
+) -> Result<Json<Vec<WebhookEndpoint>>, GatewayError> {
+    let participant = (!caller.is_operator()).then_some(caller.bic.as_str());
+
+    let endpoints = db::list_webhook_endpoints(&state.db, participant).await
+        .map_err(|e| GatewayError::InternalError(e.to_string()))?;
+
+    Ok(Json(endpoints))
+}
+
+// Deactivate a webhook endpoint - its owner or an operator; its pending deliveries are dead-lettered
+async fn delete_webhook_endpoint(
+    State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedParticipant>,
+    Path(endpoint_id): Path<Uuid>,
+) -> Result<StatusCode, GatewayError> {
+    let endpoint = db::get_webhook_endpoint(&state.db, endpoint_id).await
+        .map_err(|e| GatewayError::InternalError(e.to_string()))?
+        .filter(|endpoint| endpoint.active)
+        .ok_or_else(|| GatewayError::ValidationError(format!("Webhook endpoint not found: {}", endpoint_id)))?;
+
+    if !caller.may_act_for(Some(&endpoint.participant_bic)) {
+        let path = format!("/webhooks/endpoints/{}", endpoint_id);
+        let entry = AuthAuditEntry::forbidden(&caller, "DELETE", &path, AuthFailure::NotEndpointOwner)
+            .detail(format!("endpoint of {}", endpoint.participant_bic));
+        return Err(forbidden(&state, entry, format!("Webhook endpoint {} belongs to another participant", endpoint_id)).await);
+    }
+
+    db::deactivate_webhook_endpoint(&state.db, endpoint_id).await
+        .map_err(|e| GatewayError::InternalError(e.to_string()))?;
+
+    info!("🔕 Webhook endpoint {} removed by {}", endpoint_id, caller.bic);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// Webhook delivery history - operators see all, participants their own (?state=&limit=&offset=)
+async fn list_webhook_deliveries(
+    State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedParticipant>,
+    Query(query): Query<WebhookDeliveriesQuery>,
+) -> Result<Json<Vec<WebhookDelivery>>, GatewayError> {
+    let participant = (!caller.is_operator()).then_some(caller.bic.as_str());
+    let delivery_state = query.state.as_deref()
+        .map(|s| DeliveryState::parse(s).ok_or_else(|| GatewayError::ValidationError(format!("Unknown delivery state: {}", s))))
+        .transpose()?;
+
+    let deliveries = db::list_webhook_deliveries(
+        &state.db, participant, delivery_state, query.limit.unwrap_or(100).clamp(1, 1000), query.offset.unwrap_or(0).max(0),
+    ).await.map_err(|e| GatewayError::InternalError(e.to_string()))?;
+
+    Ok(Json(deliveries))
+}
+
+// Deliver a past notification again (e.g. a dead-lettered one) as a new delivery
+async fn replay_webhook_delivery(
+    State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedParticipant>,
+    Path(delivery_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<WebhookDelivery>), GatewayError> {
+    let delivery = db::get_webhook_delivery(&state.db, delivery_id).await
+        .map_err(|e| GatewayError::InternalError(e.to_string()))?
+        .ok_or_else(|| GatewayError::ValidationError(format!("Webhook delivery not found: {}", delivery_id)))?;
+
+    if !caller.may_act_for(Some(&delivery.participant_bic)) {
+        let path = format!("/webhooks/deliveries/{}/replay", delivery_id);
+        let entry = AuthAuditEntry::forbidden(&caller, "POST", &path, AuthFailure::NotEndpointOwner)
+            .detail(format!("delivery to {}", delivery.participant_bic));
+        return Err(forbidden(&state, entry, format!("Webhook delivery {} belongs to another participant", delivery_id)).await);
+    }
+
+    let endpoint_active = db::get_webhook_endpoint(&state.db, delivery.endpoint_id).await
+        .map_err(|e| GatewayError::InternalError(e.to_string()))?
+        .is_some_and(|endpoint| endpoint.active);
+    if !endpoint_active {
+        return Err(GatewayError::Conflict(format!("Webhook endpoint {} was removed", delivery.endpoint_id)));
+    }
+
+    let replay = delivery.replay();
+    db::insert_webhook_delivery(&state.db, &replay).await
+        .map_err(|e| GatewayError::InternalError(e.to_string()))?;
+
+    info!("🔁 Webhook delivery {} replayed by {} as {}", delivery_id, caller.bic, replay.delivery_id);
+    Ok((StatusCode::ACCEPTED, Json(replay)))
+}
+
+// Live tracking events of the caller's payments over a WebSocket (JSON frames, see models::stream).
+// ?uetr= narrows the stream to given payments, ?from_sequence= resumes after a reconnect.
+async fn stream_payments(
+    State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedParticipant>,
+    Query(query): Query<StreamQuery>,
+    ws: WebSocketUpgrade,
+) -> Result<Response, GatewayError> {
+    let participant = (!caller.is_operator()).then(|| caller.bic.clone());
+    let filter = StreamFilter::new(participant, query.uetr.as_deref()).map_err(GatewayError::ValidationError)?;
+    if query.from_sequence.is_some_and(|sequence| sequence < 0) {
+        return Err(GatewayError::ValidationError("from_sequence must not be negative".to_string()));
+    }
+
+    info!("📺 Payment stream opened by {} ({} UETR(s), from sequence {:?})",
+          caller.bic, filter.uetrs.len(), query.from_sequence);
+    let stream = state.stream.clone();
+    Ok(ws.on_upgrade(move |socket| stream.serve(socket, filter, query.from_sequence)))
+}
+
 // pacs.002 - FI to FI Payment Status Report
 async fn handle_pacs002(
     State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedParticipant>,
+    headers: HeaderMap,
     body: String,
 ) -> Result<Json<MessageResponse>, GatewayError> {
     info!("📊 Received pacs.002 FI-to-FI Payment Status Report");
 
+    verify_signature(&state, &caller, &headers, "pacs.002", body.as_bytes()).await?;
+    let message = open_business_message(&state, &caller, "pacs.002", &body).await?;
+    validate_xsd(&state, "pacs.002", message.document)?;
+
     // Parse ISO message
-    let document = iso20022::parse_pacs002(&body)
+    let document = iso20022::parse_pacs002(message.document)
         .map_err(|e| GatewayError::ParseError(e.to_string()))?;
 
     // Convert to payment status reports
@@ -303,27 +1372,42 @@ async fn handle_pacs002(
     info!("Parsed {} payment status reports", status_reports.len());
 
     // Process each status report
-    for report in status_reports {
-        info!("Processing status for original_message_id: {}, status: {:?}",
-              report.original_message_id, report.status);
+    for report in &status_reports {
+        info!("Processing status for status_id: {}, status: {:?}",
+              report.status_id, report.status);
 
         // Update payment status in database if we have an end_to_end_id
-        if let Some(end_to_end_id) = &report.end_to_end_id {
+        if let Some(end_to_end_id) = &report.original_end_to_end_id {
             // Map ISO status to DelTran PaymentStatus
             let payment_status = match report.status {
-                iso20022::PaymentStatus::Accepted => PaymentStatus::Accepted,
-                iso20022::PaymentStatus::Pending => PaymentStatus::Pending,
-                iso20022::PaymentStatus::Rejected => PaymentStatus::Rejected,
-                iso20022::PaymentStatus::Unknown => PaymentStatus::Pending, // Conservative default
+                iso20022::pacs002::PaymentStatus::Accepted => PaymentStatus::Accepted,
+                iso20022::pacs002::PaymentStatus::Pending => PaymentStatus::Pending,
+                iso20022::pacs002::PaymentStatus::Rejected => PaymentStatus::Rejected,
+                iso20022::pacs002::PaymentStatus::Unknown => PaymentStatus::Pending, // Conservative default
             };
 
-            db::update_payment_status_by_e2e(&state.db, end_to_end_id, payment_status).await?;
-
-            // Retrieve the updated payment to route to Notification Engine
-            if let Some(payment) = db::get_payment_by_e2e(&state.db, end_to_end_id).await? {
-                info!("🔔 Routing status update to Notification Engine");
-                state.router.route_to_notification_engine(&payment, "status_update").await?;
+            // Only an agent of the payment may report its status
+            let Some(mut payment) = db::get_payment_by_e2e(&state.db, end_to_end_id)
+                .await
+                .map_err(|e| GatewayError::InternalError(e.to_string()))?
+            else {
+                warn!("⚠️ pacs.002 for unknown end_to_end_id: {}", end_to_end_id);
+                continue;
+            };
+            if !authorize_access(&state, &caller, &payment, "POST", "/iso20022/pacs.002", Some("pacs.002")).await {
+                continue;
             }
+
+            payment.update_status(payment_status.clone(), None);
+
+            // Status and notification committed together - the outbox relay publishes it
+            info!("🔔 Routing status update to Notification Engine");
+            let notification = NatsRouter::notification_message(&payment, "status_update")
+                .map_err(|e| GatewayError::InternalError(e.to_string()))?;
+            db::update_payment_status_by_e2e_with_outbox(&state.db, end_to_end_id, payment_status, &[notification])
+                .await
+                .map_err(|e| GatewayError::InternalError(e.to_string()))?;
+            report_status_change(&state, &payment).await;
         }
     }
 
@@ -338,12 +1422,18 @@ async fn handle_pacs002(
 // pain.002 - Customer Payment Status Report
 async fn handle_pain002(
     State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedParticipant>,
+    headers: HeaderMap,
     body: String,
 ) -> Result<Json<MessageResponse>, GatewayError> {
     info!("📊 Received pain.002 Customer Payment Status Report");
 
+    verify_signature(&state, &caller, &headers, "pain.002", body.as_bytes()).await?;
+    let message = open_business_message(&state, &caller, "pain.002", &body).await?;
+    validate_xsd(&state, "pain.002", message.document)?;
+
     // Parse ISO message
-    let document = iso20022::parse_pain002(&body)
+    let document = iso20022::parse_pain002(message.document)
         .map_err(|e| GatewayError::ParseError(e.to_string()))?;
 
     // Convert to customer payment status reports
@@ -353,27 +1443,43 @@ async fn handle_pain002(
     info!("Parsed {} customer payment status reports", status_reports.len());
 
     // Process each status report
-    for report in status_reports {
-        info!("Processing customer status for original_message_id: {}, status: {:?}",
-              report.original_message_id, report.status);
+    for report in &status_reports {
+        info!("Processing customer status for status_id: {}, status: {:?}",
+              report.status_id, report.status);
 
         // Update payment status in database if we have an end_to_end_id
-        if let Some(end_to_end_id) = &report.end_to_end_id {
+        let end_to_end_id = &report.original_end_to_end_id;
+        if !end_to_end_id.is_empty() {
             // Map ISO status to DelTran PaymentStatus
             let payment_status = match report.status {
-                iso20022::PaymentStatus::Accepted => PaymentStatus::Accepted,
-                iso20022::PaymentStatus::Pending => PaymentStatus::Pending,
-                iso20022::PaymentStatus::Rejected => PaymentStatus::Rejected,
-                iso20022::PaymentStatus::Unknown => PaymentStatus::Pending,
+                iso20022::pain002::PaymentStatus::Accepted => PaymentStatus::Accepted,
+                iso20022::pain002::PaymentStatus::Pending => PaymentStatus::Pending,
+                iso20022::pain002::PaymentStatus::Rejected => PaymentStatus::Rejected,
+                iso20022::pain002::PaymentStatus::Unknown => PaymentStatus::Pending,
             };
 
-            db::update_payment_status_by_e2e(&state.db, end_to_end_id, payment_status).await?;
-
-            // Retrieve the updated payment to route to Notification Engine
-            if let Some(payment) = db::get_payment_by_e2e(&state.db, end_to_end_id).await? {
-                info!("🔔 Routing customer status update to Notification Engine");
-                state.router.route_to_notification_engine(&payment, "customer_status").await?;
+            // Only an agent of the payment may report its status
+            let Some(mut payment) = db::get_payment_by_e2e(&state.db, end_to_end_id)
+                .await
+                .map_err(|e| GatewayError::InternalError(e.to_string()))?
+            else {
+                warn!("⚠️ pain.002 for unknown end_to_end_id: {}", end_to_end_id);
+                continue;
+            };
+            if !authorize_access(&state, &caller, &payment, "POST", "/iso20022/pain.002", Some("pain.002")).await {
+                continue;
             }
+
+            payment.update_status(payment_status.clone(), None);
+
+            // Status and notification committed together - the outbox relay publishes it
+            info!("🔔 Routing customer status update to Notification Engine");
+            let notification = NatsRouter::notification_message(&payment, "customer_status")
+                .map_err(|e| GatewayError::InternalError(e.to_string()))?;
+            db::update_payment_status_by_e2e_with_outbox(&state.db, end_to_end_id, payment_status, &[notification])
+                .await
+                .map_err(|e| GatewayError::InternalError(e.to_string()))?;
+            report_status_change(&state, &payment).await;
         }
     }
 
@@ -388,12 +1494,18 @@ async fn handle_pain002(
 // camt.053 - Bank to Customer Statement (EOD reconciliation)
 async fn handle_camt053(
     State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedParticipant>,
+    headers: HeaderMap,
     body: String,
-) -> Result<Json<MessageResponse>, GatewayError> {
+) -> Result<Json<Vec<MessageResponse>>, GatewayError> {
     info!("📊 Received camt.053 Bank Statement for EOD reconciliation");
 
+    verify_signature(&state, &caller, &headers, "camt.053", body.as_bytes()).await?;
+    let message = open_business_message(&state, &caller, "camt.053", &body).await?;
+    validate_xsd(&state, "camt.053", message.document)?;
+
     // Parse ISO message
-    let document = iso20022::parse_camt053(&body)
+    let document = iso20022::parse_camt053(message.document)
         .map_err(|e| GatewayError::ParseError(e.to_string()))?;
 
     // Convert to statement summaries
@@ -402,10 +1514,8 @@ async fn handle_camt053(
 
     info!("Parsed {} bank statements", statements.len());
 
-    let mut total_entries = 0;
-    let mut total_reconciled = 0;
+    let mut responses = Vec::new();
 
-    // Process each statement for reconciliation
     for statement in &statements {
         info!("💼 Processing statement {} for account {}",
               statement.statement_id, statement.account_id);
@@ -415,59 +1525,512 @@ async fn handle_camt053(
         info!("   Total Debits: {}", statement.total_debits);
         info!("   Entries: {}", statement.entries.len());
 
-        total_entries += statement.entries.len();
+        // Only the account servicer reports on the account
+        if !caller.may_act_for(statement.account_servicer_bic.as_deref().or(Some(caller.bic.as_str()))) {
+            let entry = AuthAuditEntry::forbidden(&caller, "POST", "/iso20022/camt.053", AuthFailure::NotAccountServicer)
+                .message_type("camt.053")
+                .claimed_bic(statement.account_servicer_bic.as_deref())
+                .detail(format!("statement {}", statement.statement_id));
+            let message = format!("{} is not the account servicer of statement {}", caller.bic, statement.statement_id);
+            return Err(forbidden(&state, entry, message).await);
+        }
 
-        // Process each entry for reconciliation
-        for entry in &statement.entries {
-            // Try to match to existing payment by end_to_end_id
-            if let Some(end_to_end_id) = &entry.end_to_end_id {
-                // Check if we have this payment in our system
-                if let Some(payment) = db::get_payment_by_e2e(&state.db, end_to_end_id).await? {
-                    info!("✅ Reconciled payment: {} ({})",
-                          end_to_end_id, entry.entry_reference);
+        let outcome = state.reconciler.reconcile(&caller, statement).await
+            .map_err(|e| GatewayError::InternalError(e.to_string()))?;
 
-                    // Update reconciliation status
-                    // Note: In production, you'd have a reconciliation table
-                    // For now, we just log successful reconciliation
-                    total_reconciled += 1;
+        for mut payment in outcome.reconciled {
+            payment.update_status(PaymentStatus::Reconciled, None);
 
-                    // Optionally route to Reporting Engine for analytics
-                    state.router.route_to_reporting_engine(&payment).await?;
-                } else {
-                    warn!("⚠️ Unmatched statement entry: {} (not in our system)", end_to_end_id);
-                }
-            } else {
-                debug!("Entry {} has no end_to_end_id - cannot reconcile", entry.entry_reference);
-            }
+            // Status and Reporting Engine message committed together - the outbox relay publishes it
+            let reporting = NatsRouter::reporting_message(&payment)
+                .map_err(|e| GatewayError::InternalError(e.to_string()))?;
+            db::update_payment_status_with_outbox(&state.db, payment.deltran_tx_id, PaymentStatus::Reconciled, &[reporting])
+                .await
+                .map_err(|e| GatewayError::InternalError(e.to_string()))?;
+            info!("✅ Reconciled payment: {} ({})", payment.end_to_end_id, payment.deltran_tx_id);
+
+            report_status_change(&state, &payment).await;
         }
+
+        let summary = &outcome.statement;
+        let status = if outcome.duplicate {
+            "DUPLICATE"
+        } else if summary.break_count > 0 || !summary.is_balanced() {
+            "BREAKS"
+        } else {
+            "RECONCILED"
+        };
+
+        responses.push(MessageResponse {
+            deltran_tx_id: summary.statement_id,
+            status: status.to_string(),
+            message: format!("Statement {}: {}/{} entries matched, {} breaks, balance difference {}",
+                             summary.statement_reference, summary.matched_entries, summary.entry_count,
+                             summary.break_count,
+                             summary.balance_difference.map(|d| d.to_string()).unwrap_or_else(|| "unknown".to_string())),
+            timestamp: Utc::now(),
+        });
     }
 
-    info!("🎯 Reconciliation complete: {}/{} entries matched", total_reconciled, total_entries);
+    Ok(Json(responses))
+}
 
-    Ok(Json(MessageResponse {
-        deltran_tx_id: Uuid::new_v4(),
-        status: "RECONCILED".to_string(),
-        message: format!("Processed {} statements with {}/{} entries reconciled",
-                        statements.len(), total_reconciled, total_entries),
-        timestamp: Utc::now(),
+// Stored statement with its break list - visible to the participant that sent it
+async fn get_reconciliation_statement(
+    State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedParticipant>,
+    Path(statement_id): Path<Uuid>,
+) -> Result<Json<StatementReconciliation>, GatewayError> {
+    let not_found = || GatewayError::ValidationError(format!("Statement not found: {}", statement_id));
+    let statement = db::get_bank_statement(&state.db, statement_id).await
+        .map_err(|e| GatewayError::InternalError(e.to_string()))?
+        .ok_or_else(not_found)?;
+
+    if !caller.may_act_for(Some(&statement.received_from_bic)) {
+        let path = format!("/reconciliation/statements/{}", statement_id);
+        let entry = AuthAuditEntry::forbidden(&caller, "GET", &path, AuthFailure::NotRecipient)
+            .claimed_bic(Some(&statement.received_from_bic))
+            .detail(format!("statement {}", statement_id));
+        state.auth.audit(entry).await;
+        return Err(not_found());
+    }
+
+    let breaks = db::get_statement_breaks(&state.db, statement_id).await
+        .map_err(|e| GatewayError::InternalError(e.to_string()))?;
+
+    Ok(Json(StatementReconciliation {
+        balanced: statement.is_balanced(),
+        statement,
+        breaks,
     }))
 }
 
+// pacs.004 - Payment Return
+async fn handle_pacs004(
+    State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedParticipant>,
+    headers: HeaderMap,
+    body: String,
+) -> Result<Json<Vec<MessageResponse>>, GatewayError> {
+    METRICS.track_iso_message("pacs.004");
+
+    info!("↩️ Received pacs.004 Payment Return");
+
+    verify_signature(&state, &caller, &headers, "pacs.004", body.as_bytes()).await?;
+    let message = open_business_message(&state, &caller, "pacs.004", &body).await?;
+    validate_xsd(&state, "pacs.004", message.document)?;
+
+    // Parse ISO message
+    let document = iso20022::parse_pacs004(message.document)
+        .map_err(|e| {
+            METRICS.iso_parse_errors_total.inc();
+            GatewayError::ParseError(e.to_string())
+        })?;
+
+    let returns = iso20022::to_payment_returns(&document)
+        .map_err(|e| GatewayError::ParseError(e.to_string()))?;
+
+    let mut responses = Vec::new();
+
+    for ret in returns {
+        let original = find_original_payment(&state, ret.original_uetr, ret.original_end_to_end_id.as_deref()).await?;
+
+        let Some(mut payment) = original else {
+            warn!("⚠️ Return {} does not match any payment (UETR: {:?}, end_to_end_id: {:?})",
+                  ret.return_id, ret.original_uetr, ret.original_end_to_end_id);
+            continue;
+        };
+        if !authorize_access(&state, &caller, &payment, "POST", "/iso20022/pacs.004", Some("pacs.004")).await {
+            continue;
+        }
+
+        // Token Engine reverses the mint, Obligation Engine cancels/reverses the obligation - the
+        // return, the Returned status and that message are committed together
+        let return_message = NatsRouter::return_message(&payment, &ret)
+            .map_err(|e| GatewayError::InternalError(e.to_string()))?;
+        let recorded = db::record_payment_return_with_outbox(&state.db, payment.deltran_tx_id, &ret, &[return_message]).await
+            .map_err(|e| GatewayError::InternalError(e.to_string()))?;
+        if !recorded {
+            info!("Return {} already processed for {}", ret.return_id, payment.deltran_tx_id);
+            responses.push(MessageResponse {
+                deltran_tx_id: payment.deltran_tx_id,
+                status: "DUPLICATE".to_string(),
+                message: format!("Return {} already processed", ret.return_id),
+                timestamp: Utc::now(),
+            });
+            continue;
+        }
+
+        info!("↩️ Payment {} returned: {} {} (reason: {:?})",
+              payment.deltran_tx_id, ret.returned_amount, ret.currency, ret.reason_code);
+
+        payment.update_status(PaymentStatus::Returned, ret.reason_code.as_ref().map(|code| StatusReason {
+            code: code.clone(),
+            description: ret.reason_description.clone().unwrap_or_default(),
+            additional_info: None,
+        }));
+        report_status_change(&state, &payment).await;
+
+        responses.push(MessageResponse {
+            deltran_tx_id: payment.deltran_tx_id,
+            status: "RETURNED".to_string(),
+            message: format!("Return {} processed: {} {} (reason: {})",
+                            ret.return_id, ret.returned_amount, ret.currency,
+                            ret.reason_code.as_deref().unwrap_or("none")),
+            timestamp: Utc::now(),
+        });
+    }
+
+    Ok(Json(responses))
+}
+
+// camt.056 - FI to FI Payment Cancellation Request (answered with camt.029)
+async fn handle_camt056(
+    State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedParticipant>,
+    headers: HeaderMap,
+    body: String,
+) -> Result<Response, GatewayError> {
+    METRICS.track_iso_message("camt.056");
+
+    info!("🛑 Received camt.056 Payment Cancellation Request");
+
+    verify_signature(&state, &caller, &headers, "camt.056", body.as_bytes()).await?;
+    let message = open_business_message(&state, &caller, "camt.056", &body).await?;
+    validate_xsd(&state, "camt.056", message.document)?;
+
+    // Parse ISO message
+    let document = iso20022::parse_camt056(message.document)
+        .map_err(|e| {
+            METRICS.iso_parse_errors_total.inc();
+            GatewayError::ParseError(e.to_string())
+        })?;
+
+    let requests = iso20022::to_cancellation_requests(&document)
+        .map_err(|e| GatewayError::ParseError(e.to_string()))?;
+
+    let mut resolutions = Vec::new();
+    let mut tx_ids = Vec::new();
+
+    for request in requests {
+        let mut original = find_original_payment(&state, request.original_uetr, request.original_end_to_end_id.as_deref()).await?;
+        if let Some(payment) = &original {
+            // Cancellation of another participant's payment is answered as for an unknown one
+            if !authorize_access(&state, &caller, payment, "POST", "/iso20022/camt.056", Some("camt.056")).await {
+                original = None;
+            }
+        }
+
+        let resolution = match original {
+            Some(mut payment) if payment.can_cancel() => {
+                info!("🛑 Cancelling payment {} (reason: {:?})", payment.deltran_tx_id, request.reason_code);
+
+                // Obligation, Clearing and Settlement engines drop the payment - committed together
+                // with the Cancelled status
+                let cancellations = NatsRouter::cancellation_messages(&payment, &request)
+                    .map_err(|e| GatewayError::InternalError(e.to_string()))?;
+                db::update_payment_status_with_outbox(&state.db, payment.deltran_tx_id, PaymentStatus::Cancelled, &cancellations).await
+                    .map_err(|e| GatewayError::InternalError(e.to_string()))?;
+                payment.update_status(PaymentStatus::Cancelled, Some(StatusReason {
+                    code: request.reason_code.clone().unwrap_or_else(|| "CUST".to_string()),
+                    description: request.reason_description.clone().unwrap_or_default(),
+                    additional_info: None,
+                }));
+                report_status_change(&state, &payment).await;
+
+                tx_ids.push(payment.deltran_tx_id);
+                (Some(payment.deltran_tx_id), CancellationResolution {
+                    request,
+                    status: CancellationStatus::Accepted,
+                    rejection_code: None,
+                    rejection_info: None,
+                })
+            }
+            original => {
+                let (code, info) = iso20022::rejection_reason(original.as_ref().map(|p| &p.status));
+                warn!("⚠️ Cancellation {} rejected: {} ({:?})", request.cancellation_id, code, info);
+
+                let tx_id = original.map(|p| p.deltran_tx_id);
+                tx_ids.extend(tx_id);
+                (tx_id, CancellationResolution {
+                    request,
+                    status: CancellationStatus::Rejected,
+                    rejection_code: Some(code.to_string()),
+                    rejection_info: info,
+                })
+            }
+        };
+
+        resolutions.push(resolution);
+    }
+
+    let assignment = &document.cancellation_request.assignment;
+    let results: Vec<CancellationResolution> = resolutions.iter().map(|(_, r)| r.clone()).collect();
+    let report = state.reporter.report_resolution(assignment, &results, tx_ids).await
+        .map_err(|e| GatewayError::InternalError(e.to_string()))?;
+
+    for (tx_id, resolution) in &resolutions {
+        db::insert_cancellation_request(&state.db, *tx_id, resolution, &report.message_id).await
+            .map_err(|e| GatewayError::InternalError(e.to_string()))?;
+    }
+
+    info!("📤 camt.029 {} resolved case {}: {:?}", report.message_id, assignment.id, report.group_status);
+
+    signed_xml(&state, report.xml_document, report.signature)
+}
+
+// trck.001 - Payment Status Tracker Update from another agent (answered with trck.002)
+async fn handle_trck001(
+    State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedParticipant>,
+    headers: HeaderMap,
+    body: String,
+) -> Result<Response, GatewayError> {
+    METRICS.track_iso_message("trck.001");
+
+    info!("📍 Received trck.001 Payment Status Tracker Update");
+
+    verify_signature(&state, &caller, &headers, "trck.001", body.as_bytes()).await?;
+    let message = open_business_message(&state, &caller, "trck.001", &body).await?;
+    validate_xsd(&state, "trck.001", message.document)?;
+
+    // Parse ISO message
+    let document = iso20022::parse_trck001(message.document)
+        .map_err(|e| {
+            METRICS.iso_parse_errors_total.inc();
+            GatewayError::ParseError(e.to_string())
+        })?;
+
+    let updates = iso20022::to_tracker_updates(&document)
+        .map_err(|e| GatewayError::ParseError(e.to_string()))?;
+
+    // trck.002 reports DelTran's view of the (first) tracked payment
+    let mut report = None;
+    for update in &updates {
+        let tracked = state.tracker.record_partner_update(update).await
+            .map_err(|e| GatewayError::InternalError(e.to_string()))?;
+
+        match tracked {
+            Some((payment, events)) if report.is_none() => {
+                report = Some(state.tracker.tracker_report(&payment, &events, Some(&update.message_id), &caller.bic)
+                    .map_err(|e| GatewayError::InternalError(e.to_string()))?);
+            }
+            Some(_) => {}
+            None => warn!("⚠️ trck.001 {} references unknown UETR {}", update.message_id, update.uetr),
+        }
+    }
+
+    let report = report
+        .ok_or_else(|| GatewayError::ValidationError("trck.001 does not reference any known UETR".to_string()))?;
+
+    signed_xml(&state, report.xml, None)
+}
+
 // Get payment status by DelTran TX ID
 async fn get_payment_status(
     State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedParticipant>,
     Path(tx_id): Path<Uuid>,
 ) -> Result<Json<CanonicalPayment>, GatewayError> {
     info!("Retrieving payment status for: {}", tx_id);
 
-    let payment = db::get_payment_by_id(&state.db, tx_id).await?;
+    let payment = db::get_payment_by_id(&state.db, tx_id)
+        .await
+        .map_err(|e| GatewayError::InternalError(e.to_string()))?;
 
     match payment {
-        Some(p) => Ok(Json(p)),
-        None => Err(GatewayError::ValidationError(format!("Payment not found: {}", tx_id))),
+        Some(p) if authorize_access(&state, &caller, &p, "GET", &format!("/payment/{}", tx_id), None).await => Ok(Json(p)),
+        _ => Err(GatewayError::ValidationError(format!("Payment not found: {}", tx_id))),
     }
 }
 
+// Payment search - operators see every payment, participants those they are debtor or creditor agent of
+async fn search_payments(
+    State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedParticipant>,
+    Query(query): Query<PaymentSearchQuery>,
+) -> Result<Json<PaymentPage>, GatewayError> {
+    let (filter, cursor) = payment_filter(&caller, &query)?;
+    let limit = query.limit.unwrap_or(100).clamp(1, 1000);
+
+    let mut payments = db::search_payments(&state.db, &filter, cursor, limit + 1).await
+        .map_err(|e| GatewayError::InternalError(e.to_string()))?;
+    let next_cursor = next_page(&mut payments, limit);
+
+    Ok(Json(PaymentPage { payments, next_cursor }))
+}
+
+// CSV export of a payment search - EXPORT_MAX_ROWS per response, X-Next-Cursor when there is more
+async fn export_payments(
+    State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedParticipant>,
+    Query(query): Query<PaymentSearchQuery>,
+) -> Result<Response, GatewayError> {
+    let (filter, cursor) = payment_filter(&caller, &query)?;
+    let limit = query.limit.unwrap_or(EXPORT_MAX_ROWS).clamp(1, EXPORT_MAX_ROWS);
+
+    let mut payments = db::search_payments(&state.db, &filter, cursor, limit + 1).await
+        .map_err(|e| GatewayError::InternalError(e.to_string()))?;
+    let next_cursor = next_page(&mut payments, limit);
+    info!("📤 Payment export for {}: {} row(s){}", caller.bic, payments.len(), if next_cursor.is_some() { ", truncated" } else { "" });
+
+    let mut csv = String::from(PaymentSummary::CSV_HEADER);
+    csv.push_str("\r\n");
+    for payment in &payments {
+        csv.push_str(&payment.to_csv_row());
+        csv.push_str("\r\n");
+    }
+
+    let mut response = (
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (header::CONTENT_DISPOSITION, "attachment; filename=\"payments.csv\"".to_string()),
+        ],
+        csv,
+    ).into_response();
+    if let Some(cursor) = next_cursor.and_then(|cursor| HeaderValue::from_str(&cursor).ok()) {
+        response.headers_mut().insert(HeaderName::from_static("x-next-cursor"), cursor);
+    }
+    Ok(response)
+}
+
+// Payments per status for dashboards - same filters and visibility as GET /payments
+async fn payment_stats(
+    State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedParticipant>,
+    Query(query): Query<PaymentSearchQuery>,
+) -> Result<Json<PaymentStats>, GatewayError> {
+    let (filter, _) = payment_filter(&caller, &query)?;
+
+    let counts = db::count_payments_by_status(&state.db, &filter).await
+        .map_err(|e| GatewayError::InternalError(e.to_string()))?;
+
+    Ok(Json(PaymentStats::from_counts(&counts)))
+}
+
+fn payment_filter(
+    caller: &AuthenticatedParticipant,
+    query: &PaymentSearchQuery,
+) -> Result<(PaymentFilter, Option<PaymentCursor>), GatewayError> {
+    let mut filter = PaymentFilter::try_from(query).map_err(GatewayError::ValidationError)?;
+    if !caller.is_operator() {
+        filter.participant_bic = Some(caller.bic.to_ascii_uppercase());
+    }
+
+    let cursor = query.cursor.as_deref()
+        .filter(|cursor| !cursor.is_empty())
+        .map(|cursor| PaymentCursor::decode(cursor).ok_or_else(|| GatewayError::ValidationError(format!("Invalid cursor: {}", cursor))))
+        .transpose()?;
+
+    Ok((filter, cursor))
+}
+
+// Searches fetch one row more than the page: drop it and return the cursor of the page's last row
+fn next_page(payments: &mut Vec<PaymentSummary>, limit: i64) -> Option<String> {
+    if payments.len() as i64 <= limit {
+        return None;
+    }
+    payments.truncate(limit as usize);
+    payments.last().map(|payment| payment.cursor().encode())
+}
+
+// Get payment by UETR
+async fn get_payment_by_uetr(
+    State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedParticipant>,
+    Path(uetr): Path<Uuid>,
+) -> Result<Json<CanonicalPayment>, GatewayError> {
+    let payment = find_original_payment(&state, Some(uetr), None).await?
+        .ok_or_else(|| GatewayError::ValidationError(format!("Payment not found for UETR: {}", uetr)))?;
+
+    if !authorize_access(&state, &caller, &payment, "GET", &format!("/payment/uetr/{}", uetr), None).await {
+        return Err(GatewayError::ValidationError(format!("Payment not found for UETR: {}", uetr)));
+    }
+
+    Ok(Json(payment))
+}
+
+// Tracking timeline by UETR (events from all engines, oldest first)
+async fn get_payment_timeline(
+    State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedParticipant>,
+    Path(uetr): Path<Uuid>,
+) -> Result<Json<PaymentTimeline>, GatewayError> {
+    let (payment, timeline) = state.tracker.timeline(uetr).await
+        .map_err(|e| GatewayError::InternalError(e.to_string()))?
+        .ok_or_else(|| GatewayError::ValidationError(format!("Payment not found for UETR: {}", uetr)))?;
+
+    if !authorize_access(&state, &caller, &payment, "GET", &format!("/payment/uetr/{}/timeline", uetr), None).await {
+        return Err(GatewayError::ValidationError(format!("Payment not found for UETR: {}", uetr)));
+    }
+
+    Ok(Json(timeline))
+}
+
+// Tracking timeline by UETR as ISO trck.002
+async fn get_payment_tracker_report(
+    State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedParticipant>,
+    Path(uetr): Path<Uuid>,
+) -> Result<Response, GatewayError> {
+    let (payment, timeline) = state.tracker.timeline(uetr).await
+        .map_err(|e| GatewayError::InternalError(e.to_string()))?
+        .ok_or_else(|| GatewayError::ValidationError(format!("Payment not found for UETR: {}", uetr)))?;
+
+    if !authorize_access(&state, &caller, &payment, "GET", &format!("/payment/uetr/{}/trck.002", uetr), Some("trck.002")).await {
+        return Err(GatewayError::ValidationError(format!("Payment not found for UETR: {}", uetr)));
+    }
+
+    let report = state.tracker.tracker_report(&payment, &timeline.events, None, &caller.bic)
+        .map_err(|e| GatewayError::InternalError(e.to_string()))?;
+
+    signed_xml(&state, report.xml, None)
+}
+
+// Collect outstanding pain.002 / pacs.002 reports for a bank (marks them collected)
+async fn collect_status_reports(
+    State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedParticipant>,
+    Path(bic): Path<String>,
+) -> Result<Json<Vec<StatusReportRecord>>, GatewayError> {
+    authorize_recipient(&state, &caller, &bic, &format!("/reports/{}", bic)).await?;
+    info!("📥 Status report collection by {}", bic);
+
+    let reports = db::collect_status_reports(&state.db, &bic.to_uppercase(), 100).await
+        .map_err(|e| GatewayError::InternalError(e.to_string()))?;
+
+    Ok(Json(reports))
+}
+
+// Raw ISO 20022 XML of a single report (re-download is allowed)
+async fn get_status_report_document(
+    State(state): State<AppState>,
+    Extension(caller): Extension<AuthenticatedParticipant>,
+    Path((bic, report_id)): Path<(String, Uuid)>,
+) -> Result<Response, GatewayError> {
+    authorize_recipient(&state, &caller, &bic, &format!("/reports/{}/{}", bic, report_id)).await?;
+    let report = db::get_status_report(&state.db, &bic.to_uppercase(), report_id).await
+        .map_err(|e| GatewayError::InternalError(e.to_string()))?
+        .ok_or_else(|| GatewayError::ValidationError(format!("Status report not found: {}", report_id)))?;
+
+    signed_xml(&state, report.xml_document, report.signature)
+}
+
+// Status reports are collected by their recipient only
+async fn authorize_recipient(
+    state: &AppState,
+    caller: &AuthenticatedParticipant,
+    bic: &str,
+    path: &str,
+) -> Result<(), GatewayError> {
+    if caller.may_act_for(Some(bic)) {
+        return Ok(());
+    }
+
+    let entry = AuthAuditEntry::forbidden(caller, "GET", path, AuthFailure::NotRecipient).claimed_bic(Some(bic));
+    Err(forbidden(state, entry, format!("{} may not collect reports for {}", caller.bic, bic)).await)
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     // Initialize tracing
@@ -486,6 +2049,60 @@ async fn main() -> anyhow::Result<()> {
         .unwrap_or_else(|_| "nats://localhost:4222".to_string());
     let bind_addr = std::env::var("BIND_ADDR")
         .unwrap_or_else(|_| "0.0.0.0:8080".to_string());
+    let catalog_path = std::env::var("ISO20022_CATALOG_PATH")
+        .unwrap_or_else(|_| "../../iso20022/iso_message_catalog.json".to_string());
+    let corridor_rules_path = std::env::var("CORRIDOR_RULES_PATH")
+        .unwrap_or_else(|_| "config/corridor_rules.json".to_string());
+    let xsd_mode = ValidationMode::from_env_value(
+        &std::env::var("XSD_VALIDATION_MODE").unwrap_or_else(|_| "lenient".to_string())
+    );
+    let deltran_bic = std::env::var("DELTRAN_BIC").ok().filter(|bic| !bic.is_empty());
+    let bulk_max_file_bytes = BulkIngestion::max_file_bytes_from_env_value(
+        &std::env::var("BULK_MAX_FILE_MB").unwrap_or_else(|_| bulk::DEFAULT_BULK_MAX_FILE_MB.to_string())
+    );
+    let bulk_spool_dir = std::env::var("BULK_SPOOL_DIR")
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|_| std::env::temp_dir());
+    let outbox_poll_interval = OutboxRelay::poll_interval_from_env_value(
+        &std::env::var("OUTBOX_POLL_INTERVAL_MS").unwrap_or_else(|_| outbox::DEFAULT_OUTBOX_POLL_INTERVAL_MS.to_string())
+    );
+    let funding_match_interval = FundingQueue::match_interval_from_env_value(
+        &std::env::var("FUNDING_MATCH_INTERVAL_SECS").unwrap_or_else(|_| funding::DEFAULT_FUNDING_MATCH_INTERVAL_SECS.to_string())
+    );
+    let funding_tolerance_bps = FundingQueue::tolerance_bps_from_env_value(
+        &std::env::var("FUNDING_TOLERANCE_BPS").unwrap_or_else(|_| funding::DEFAULT_FUNDING_TOLERANCE_BPS.to_string())
+    );
+    let webhook_max_attempts = WebhookDispatcher::max_attempts_from_env_value(
+        &std::env::var("WEBHOOK_MAX_ATTEMPTS").unwrap_or_else(|_| webhooks::DEFAULT_WEBHOOK_MAX_ATTEMPTS.to_string())
+    );
+    let webhook_timeout = WebhookDispatcher::timeout_from_env_value(
+        &std::env::var("WEBHOOK_TIMEOUT_SECS").unwrap_or_else(|_| webhooks::DEFAULT_WEBHOOK_TIMEOUT_SECS.to_string())
+    );
+    let jwt_secret = std::env::var("JWT_SECRET").ok();
+    let client_cert_header = std::env::var("AUTH_CLIENT_CERT_HEADER").ok();
+    let cors_allowed_origins = std::env::var("CORS_ALLOWED_ORIGINS").unwrap_or_default();
+    // No bundled default - the gateway must never sign with a key checked into the repository
+    let signing_keystore_path = std::env::var("SIGNING_KEYSTORE_PATH")
+        .map_err(|_| anyhow::anyhow!("SIGNING_KEYSTORE_PATH is required (gateway signing keystore)"))?;
+    let signature_required_messages = std::env::var("SIGNATURE_REQUIRED_MESSAGES")
+        .unwrap_or_else(|_| signing::DEFAULT_SIGNATURE_REQUIRED_MESSAGES.to_string());
+    let dedup_horizon = IdempotencyGuard::horizon_from_env_value(
+        &std::env::var("DEDUP_HORIZON_HOURS").unwrap_or_else(|_| idempotency::DEFAULT_DEDUP_HORIZON_HOURS.to_string())
+    );
+
+    // Load ISO 20022 schemas (fail fast if the catalog is broken)
+    info!("Loading XSD schemas from catalog: {}", catalog_path);
+    let xsd = Arc::new(XsdValidator::load_from_catalog(std::path::Path::new(&catalog_path), xsd_mode)?);
+
+    // Corridor business rules (fail fast on unknown rules / charge bearers)
+    info!("Loading corridor rules: {}", corridor_rules_path);
+    let rules = Arc::new(RuleEngine::load(std::path::Path::new(&corridor_rules_path))?);
+    info!("Corridor rules loaded: {} corridor(s)", rules.corridor_count());
+
+    // Gateway signing keys for outbound messages (fail fast on mismatched key pairs)
+    info!("Loading signing keystore: {}", signing_keystore_path);
+    let signer = Arc::new(MessageSigner::load(std::path::Path::new(&signing_keystore_path))?);
+    info!("🔏 Signing outbound messages with key {}", signer.active_kid());
 
     // Connect to PostgreSQL
     info!("Connecting to database: {}", database_url);
@@ -502,34 +2119,144 @@ async fn main() -> anyhow::Result<()> {
     info!("Connecting to NATS: {}", nats_url);
     let nats = async_nats::connect(&nats_url).await?;
 
+    // Participant authentication (JWT and / or mTLS client certificate)
+    let auth = Arc::new(Authenticator::new(db.clone(), jwt_secret.as_deref(), client_cert_header.as_deref())?);
+    info!("🔐 Participant authentication: {}", auth.methods().join(", "));
+
+    // Inbound detached JWS, checked against the participant key registry
+    let verifier = Arc::new(SignatureVerifier::new(db.clone(), &signature_required_messages));
+    info!("🔏 Signature required for: {}", signature_required_messages);
+
     // Initialize NATS router
     let router = Arc::new(NatsRouter::new(nats.clone()));
 
+    // Outbound pain.002 / pacs.002 generation
+    let reporter = Arc::new(StatusReporter::new(db.clone(), router.clone(), signer.clone(), deltran_bic.clone()));
+
+    // Duplicate detection for pain.001 / pacs.008 replays
+    let idempotency = Arc::new(IdempotencyGuard::new(db.clone(), dedup_horizon));
+    info!("Dedup horizon: {}h", idempotency.horizon().as_secs() / 3600);
+
+    // Per-UETR tracking timeline fed by all engines
+    let tracker = Arc::new(PaymentTracker::new(db.clone(), router.clone(), signer.clone(), deltran_bic));
+    tracking::start_tracking_consumer(tracker.clone(), nats.clone()).await?;
+
+    // Live /ws/payments streams, fed by the events every gateway instance records
+    let stream = Arc::new(PaymentStream::new(db.clone()));
+    stream::start_stream_consumer(stream.clone(), nats.clone()).await?;
+
+    // Outbox relay - publishes messages committed together with their payment
+    let outbox_relay = Arc::new(OutboxRelay::new(db.clone(), router.clone(), outbox_poll_interval));
+    outbox::start_outbox_relay(outbox_relay);
+
+    // Bulk pain.001 files, processed in the background
+    let bulk = Arc::new(BulkIngestion::new(
+        db.clone(),
+        xsd.clone(),
+        rules.clone(),
+        idempotency.clone(),
+        reporter.clone(),
+        tracker.clone(),
+        bulk_spool_dir,
+    ));
+
+    // camt.054 credits kept until they fund a payment, retried in the background
+    let funding = Arc::new(FundingQueue::new(
+        db.clone(),
+        reporter.clone(),
+        tracker.clone(),
+        funding_match_interval,
+        funding_tolerance_bps,
+    ));
+    funding::start_funding_matcher(funding.clone());
+
+    // Signed payment notifications POSTed to participants' webhook endpoints
+    let webhooks = Arc::new(WebhookDispatcher::new(db.clone(), signer.clone(), webhook_max_attempts, webhook_timeout)?);
+    webhooks::start_webhook_consumer(webhooks.clone(), nats.clone()).await?;
+    webhooks::start_webhook_dispatcher(webhooks.clone());
+
+    // camt.053 statements matched against gateway payments
+    let reconciler = Arc::new(Reconciler::new(db.clone()));
+
     // Create app state
     let state = AppState {
         db,
         nats,
         router,
+        xsd,
+        rules,
+        reporter,
+        idempotency,
+        tracker,
+        bulk,
+        auth,
+        signer,
+        verifier,
+        reconciler,
+        funding,
+        webhooks,
+        stream,
     };
 
-    // Build router with CORS and metrics
-    use tower_http::cors::{CorsLayer, Any};
+    // Build router with CORS and metrics - browsers only from the configured origins
+    use tower_http::cors::CorsLayer;
 
+    let origins: Vec<HeaderValue> = cors_allowed_origins
+        .split(',')
+        .map(str::trim)
+        .filter(|origin| !origin.is_empty())
+        .filter_map(|origin| origin.parse().ok())
+        .collect();
     let cors = CorsLayer::new()
-        .allow_origin(Any)
-        .allow_methods(Any)
-        .allow_headers(Any);
-
-    let app = Router::new()
-        .route("/health", get(health_check))
-        .route("/metrics", get(metrics_handler))
+        .allow_origin(origins)
+        .allow_methods([Method::GET, Method::POST, Method::DELETE])
+        .allow_headers([header::AUTHORIZATION, header::CONTENT_TYPE, HeaderName::from_static("idempotency-key"),
+                        HeaderName::from_static("x-jws-signature")])
+        .expose_headers([HeaderName::from_static("x-jws-signature")]);
+
+    // Everything but health and metrics requires an authenticated participant
+    let participant_routes = Router::new()
+        .route("/iso20022", post(handle_iso20022))
         .route("/iso20022/pain.001", post(handle_pain001))
+        .route(
+            "/iso20022/pain.001/bulk",
+            post(handle_pain001_bulk).layer(DefaultBodyLimit::max(bulk_max_file_bytes)),
+        )
         .route("/iso20022/pacs.008", post(handle_pacs008))
         .route("/iso20022/camt.054", post(handle_camt054))
         .route("/iso20022/pacs.002", post(handle_pacs002))
         .route("/iso20022/pain.002", post(handle_pain002))
         .route("/iso20022/camt.053", post(handle_camt053))
+        .route("/iso20022/pacs.004", post(handle_pacs004))
+        .route("/iso20022/camt.056", post(handle_camt056))
+        .route("/iso20022/trck.001", post(handle_trck001))
+        .route("/payments", get(search_payments))
+        .route("/payments/export", get(export_payments))
+        .route("/payments/stats", get(payment_stats))
         .route("/payment/:tx_id", get(get_payment_status))
+        .route("/payment/uetr/:uetr", get(get_payment_by_uetr))
+        .route("/payment/uetr/:uetr/timeline", get(get_payment_timeline))
+        .route("/payment/uetr/:uetr/trck.002", get(get_payment_tracker_report))
+        .route("/ws/payments", get(stream_payments))
+        .route("/reports/:bic", get(collect_status_reports))
+        .route("/reports/:bic/:report_id", get(get_status_report_document))
+        .route("/batches/:batch_id", get(get_batch))
+        .route("/batches/:batch_id/transactions", get(get_batch_transactions))
+        .route("/reconciliation/statements/:statement_id", get(get_reconciliation_statement))
+        .route("/funding/events", get(list_funding_events))
+        .route("/funding/events/:event_id/match", post(match_funding_event))
+        .route("/funding/refunds", get(list_funding_refunds))
+        .route("/webhooks/endpoints", post(register_webhook_endpoint).get(list_webhook_endpoints))
+        .route("/webhooks/endpoints/:endpoint_id", delete(delete_webhook_endpoint))
+        .route("/webhooks/deliveries", get(list_webhook_deliveries))
+        .route("/webhooks/deliveries/:delivery_id/replay", post(replay_webhook_delivery))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_participant));
+
+    let app = Router::new()
+        .route("/health", get(health_check))
+        .route("/metrics", get(metrics_handler))
+        .route("/signing-keys", get(signing_keys_handler))
+        .merge(participant_routes)
         .layer(cors)
         .with_state(state);
 
@@ -537,15 +2264,32 @@ async fn main() -> anyhow::Result<()> {
     let listener = tokio::net::TcpListener::bind(&bind_addr).await?;
     info!("✅ Gateway listening on: {}", bind_addr);
     info!("📨 Ready to receive ISO 20022 messages");
+    info!("   POST /iso20022 - Any supported message, dispatched on the head.001 AppHdr MsgDefIdr");
     info!("   POST /iso20022/pain.001 - Customer Credit Transfer Initiation");
+    info!("   POST /iso20022/pain.001/bulk - Bulk pain.001 file (multipart, partial acceptance)");
     info!("   POST /iso20022/pacs.008 - FI to FI Customer Credit Transfer");
     info!("   POST /iso20022/camt.054 - Funding Notification (CRITICAL)");
     info!("   POST /iso20022/pacs.002 - Payment Status Report");
     info!("   POST /iso20022/pain.002 - Customer Payment Status Report");
     info!("   POST /iso20022/camt.053 - Bank Statement (EOD)");
+    info!("   POST /iso20022/pacs.004 - Payment Return");
+    info!("   POST /iso20022/camt.056 - Payment Cancellation Request (camt.029 response)");
+    info!("   POST /iso20022/trck.001 - Payment Status Tracker Update (trck.002 response)");
+    info!("   GET  /payments - Search payments (filters, ?cursor= pagination; /payments/export CSV, /payments/stats per status)");
     info!("   GET  /payment/:tx_id - Get payment status");
+    info!("   GET  /payment/uetr/:uetr/timeline - Tracking timeline by UETR (JSON, or /trck.002)");
+    info!("   GET  /ws/payments - Live status updates over WebSocket (?uetr=, ?from_sequence= to resume)");
+    info!("   GET  /reports/:bic - Collect pain.002 / pacs.002 status reports");
+    info!("   GET  /batches/:batch_id - Bulk batch progress (/transactions for per-transaction outcomes)");
+    info!("   GET  /reconciliation/statements/:statement_id - camt.053 balance check and breaks");
+    info!("   GET  /funding/events - Queued camt.054 credits (POST /funding/events/:event_id/match for operators)");
+    info!("   GET  /funding/refunds - Over-funded payments awaiting a refund");
+    info!("   POST /webhooks/endpoints - Register a webhook endpoint (GET lists, DELETE /webhooks/endpoints/:endpoint_id removes)");
+    info!("   GET  /webhooks/deliveries - Webhook delivery history (POST /webhooks/deliveries/:delivery_id/replay)");
+    info!("   All endpoints except /health, /metrics and /signing-keys require a participant JWT or client certificate");
     info!("   GET  /health - Health check");
     info!("   GET  /metrics - Prometheus metrics");
+    info!("   GET  /signing-keys - Gateway public keys for X-JWS-Signature verification");
 
     axum::serve(listener, app).await?;
 
@@ -554,8 +2298,6 @@ async fn main() -> anyhow::Result<()> {
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-
     #[tokio::test]
     async fn test_health_endpoint() {
         // TODO: Add integration tests
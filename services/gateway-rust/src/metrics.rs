@@ -2,9 +2,10 @@
 // Tracks: throughput, latency, errors, payment status transitions
 
 use prometheus::{
-    Registry, Counter, Histogram, IntGauge, Opts, HistogramOpts,
-    register_counter_with_registry, register_histogram_with_registry,
-    register_int_gauge_with_registry, TextEncoder, Encoder,
+    Registry, Counter, CounterVec, Gauge, Histogram, IntGauge, Opts, HistogramOpts,
+    register_counter_with_registry, register_counter_vec_with_registry, register_gauge_with_registry,
+    register_histogram_with_registry, register_int_gauge_with_registry,
+    TextEncoder, Encoder,
 };
 use once_cell::sync::Lazy;
 use std::sync::Arc;
@@ -40,6 +41,55 @@ pub struct Metrics {
     pub iso_parse_duration_seconds: Histogram,
     pub iso_parse_errors_total: Counter,
 
+    // XSD validation metrics (labelled by message type)
+    pub xsd_validations_total: CounterVec,
+    pub xsd_validation_failures_total: CounterVec,
+    pub xsd_validation_duration_seconds: Histogram,
+
+    // Business rule metrics (labelled by rule and reason code)
+    pub business_rule_violations_total: CounterVec,
+
+    // Outbound status report metrics (pain.002 / pacs.002)
+    pub status_reports_generated_total: CounterVec,
+
+    // Idempotent ingestion metrics (labelled by matched identifier)
+    pub duplicate_submissions_total: CounterVec,
+
+    // Payment tracking metrics (labelled by reporting engine)
+    pub tracking_events_total: CounterVec,
+
+    // Bulk pain.001 metrics (batches by final status, transactions by outcome)
+    pub bulk_batches_total: CounterVec,
+    pub bulk_transactions_total: CounterVec,
+
+    // Participant access metrics (refused requests by audit reason)
+    pub auth_rejections_total: CounterVec,
+
+    // Inbound signature verification metrics (labelled by message type and result)
+    pub signature_verifications_total: CounterVec,
+
+    // Business Application Header metrics (enveloped inbound messages, PssblDplct outcome)
+    pub app_header_messages_total: CounterVec,
+    pub possible_duplicates_total: CounterVec,
+
+    // camt.053 reconciliation metrics (entry match results, statement balance check)
+    pub reconciliation_entries_total: CounterVec,
+    pub reconciliation_statements_total: CounterVec,
+
+    // camt.054 funding queue metrics (match outcomes, booked credits still waiting for a payment)
+    pub funding_events_total: CounterVec,
+    pub funding_queue_unmatched: IntGauge,
+
+    // Webhook notification metrics (attempt results, endpoint latency, backlog and dead letters)
+    pub webhook_deliveries_total: CounterVec,
+    pub webhook_delivery_duration_seconds: Histogram,
+    pub webhook_pending_deliveries: IntGauge,
+    pub webhook_dead_deliveries: IntGauge,
+
+    // Live payment stream metrics (open WebSocket connections, events pushed live or replayed)
+    pub stream_connections: IntGauge,
+    pub stream_events_total: CounterVec,
+
     // Database metrics
     pub db_operations_total: Counter,
     pub db_operation_duration_seconds: Histogram,
@@ -50,6 +100,12 @@ pub struct Metrics {
     pub nats_publish_errors_total: Counter,
     pub nats_publish_duration_seconds: Histogram,
 
+    // Transactional outbox metrics (backlog, age of the oldest pending message, delivery lag)
+    pub outbox_pending_messages: IntGauge,
+    pub outbox_lag_seconds: Gauge,
+    pub outbox_delivery_lag_seconds: Histogram,
+    pub outbox_delivery_failures_total: Counter,
+
     // Business metrics
     pub total_transaction_volume: Counter,
     pub clearing_batches_total: Counter,
@@ -173,6 +229,150 @@ impl Metrics {
             registry
         )?;
 
+        // XSD validation metrics
+        let xsd_validations_total = register_counter_vec_with_registry!(
+            Opts::new("deltran_xsd_validations_total", "ISO messages checked against XSD"),
+            &["message_type"],
+            registry
+        )?;
+
+        let xsd_validation_failures_total = register_counter_vec_with_registry!(
+            Opts::new("deltran_xsd_validation_failures_total", "ISO messages rejected by XSD validation"),
+            &["message_type"],
+            registry
+        )?;
+
+        let xsd_validation_duration_seconds = register_histogram_with_registry!(
+            HistogramOpts::new(
+                "deltran_xsd_validation_duration_seconds",
+                "XSD validation duration in seconds"
+            ).buckets(vec![0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5]),
+            registry
+        )?;
+
+        // Business rule metrics
+        let business_rule_violations_total = register_counter_vec_with_registry!(
+            Opts::new("deltran_business_rule_violations_total", "Payments failing a corridor business rule"),
+            &["rule", "code"],
+            registry
+        )?;
+
+        // Outbound status report metrics
+        let status_reports_generated_total = register_counter_vec_with_registry!(
+            Opts::new("deltran_status_reports_generated_total", "Outbound ISO status reports generated"),
+            &["message_type"],
+            registry
+        )?;
+
+        // Idempotent ingestion metrics
+        let duplicate_submissions_total = register_counter_vec_with_registry!(
+            Opts::new("deltran_duplicate_submissions_total", "Replayed submissions answered with the original payment"),
+            &["matched_on"],
+            registry
+        )?;
+
+        // Payment tracking metrics
+        let tracking_events_total = register_counter_vec_with_registry!(
+            Opts::new("deltran_tracking_events_total", "Payment tracking events recorded"),
+            &["source"],
+            registry
+        )?;
+
+        // Bulk pain.001 metrics
+        let bulk_batches_total = register_counter_vec_with_registry!(
+            Opts::new("deltran_bulk_batches_total", "Bulk pain.001 batches processed"),
+            &["status"],
+            registry
+        )?;
+
+        let bulk_transactions_total = register_counter_vec_with_registry!(
+            Opts::new("deltran_bulk_transactions_total", "Transactions of bulk pain.001 batches"),
+            &["outcome"],
+            registry
+        )?;
+
+        // Participant access metrics
+        let auth_rejections_total = register_counter_vec_with_registry!(
+            Opts::new("deltran_auth_rejections_total", "Requests refused by participant authentication or authorization"),
+            &["reason"],
+            registry
+        )?;
+
+        // Inbound signature verification metrics
+        let signature_verifications_total = register_counter_vec_with_registry!(
+            Opts::new("deltran_signature_verifications_total", "Inbound ISO message signature checks"),
+            &["message_type", "result"],
+            registry
+        )?;
+
+        // Business Application Header metrics
+        let app_header_messages_total = register_counter_vec_with_registry!(
+            Opts::new("deltran_app_header_messages_total", "Inbound ISO messages carrying a head.001 AppHdr"),
+            &["message_type"],
+            registry
+        )?;
+        let possible_duplicates_total = register_counter_vec_with_registry!(
+            Opts::new("deltran_possible_duplicates_total", "Submissions flagged PssblDplct or replayed without the flag"),
+            &["outcome"],
+            registry
+        )?;
+
+        // camt.053 reconciliation metrics
+        let reconciliation_entries_total = register_counter_vec_with_registry!(
+            Opts::new("deltran_reconciliation_entries_total", "camt.053 statement entries by match result"),
+            &["result"],
+            registry
+        )?;
+        let reconciliation_statements_total = register_counter_vec_with_registry!(
+            Opts::new("deltran_reconciliation_statements_total", "camt.053 statements by balance check outcome"),
+            &["balance"],
+            registry
+        )?;
+
+        // camt.054 funding queue metrics
+        let funding_events_total = register_counter_vec_with_registry!(
+            Opts::new("deltran_funding_events_total", "camt.054 credit entries by match outcome"),
+            &["outcome"],
+            registry
+        )?;
+        let funding_queue_unmatched = register_int_gauge_with_registry!(
+            Opts::new("deltran_funding_queue_unmatched", "Booked camt.054 credits waiting for their payment"),
+            registry
+        )?;
+
+        // Webhook notification metrics
+        let webhook_deliveries_total = register_counter_vec_with_registry!(
+            Opts::new("deltran_webhook_deliveries_total", "Webhook delivery attempts by result"),
+            &["result"],
+            registry
+        )?;
+        let webhook_delivery_duration_seconds = register_histogram_with_registry!(
+            HistogramOpts::new(
+                "deltran_webhook_delivery_duration_seconds",
+                "Participant endpoint response time in seconds"
+            ).buckets(vec![0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0]),
+            registry
+        )?;
+        let webhook_pending_deliveries = register_int_gauge_with_registry!(
+            Opts::new("deltran_webhook_pending_deliveries", "Webhook deliveries waiting for an attempt"),
+            registry
+        )?;
+        let webhook_dead_deliveries = register_int_gauge_with_registry!(
+            Opts::new("deltran_webhook_dead_deliveries", "Webhook deliveries dead-lettered after the last attempt"),
+            registry
+        )?;
+
+        // Live payment stream metrics
+        let stream_connections = register_int_gauge_with_registry!(
+            Opts::new("deltran_stream_connections", "Open /ws/payments connections"),
+            registry
+        )?;
+        let stream_events_total = register_counter_vec_with_registry!(
+            Opts::new("deltran_stream_events_total", "Tracking events pushed over /ws/payments"),
+            &["delivery"],
+            registry
+        )?;
+
         // Database metrics
         let db_operations_total = register_counter_with_registry!(
             Opts::new("deltran_db_operations_total", "Total database operations"),
@@ -211,6 +411,30 @@ impl Metrics {
             registry
         )?;
 
+        // Outbox metrics
+        let outbox_pending_messages = register_int_gauge_with_registry!(
+            Opts::new("deltran_outbox_pending_messages", "Outbox messages not yet delivered to NATS"),
+            registry
+        )?;
+
+        let outbox_lag_seconds = register_gauge_with_registry!(
+            Opts::new("deltran_outbox_lag_seconds", "Age of the oldest undelivered outbox message"),
+            registry
+        )?;
+
+        let outbox_delivery_lag_seconds = register_histogram_with_registry!(
+            HistogramOpts::new(
+                "deltran_outbox_delivery_lag_seconds",
+                "Time from outbox commit to NATS delivery in seconds"
+            ).buckets(vec![0.01, 0.05, 0.1, 0.5, 1.0, 5.0, 30.0, 60.0, 300.0]),
+            registry
+        )?;
+
+        let outbox_delivery_failures_total = register_counter_with_registry!(
+            Opts::new("deltran_outbox_delivery_failures_total", "Outbox publish attempts that failed and were rescheduled"),
+            registry
+        )?;
+
         // Business metrics
         let total_transaction_volume = register_counter_with_registry!(
             Opts::new("deltran_total_transaction_volume", "Total transaction volume in cents"),
@@ -249,12 +473,39 @@ impl Metrics {
             payment_processing_duration_seconds,
             iso_parse_duration_seconds,
             iso_parse_errors_total,
+            xsd_validations_total,
+            xsd_validation_failures_total,
+            xsd_validation_duration_seconds,
+            business_rule_violations_total,
+            status_reports_generated_total,
+            duplicate_submissions_total,
+            tracking_events_total,
+            bulk_batches_total,
+            bulk_transactions_total,
+            auth_rejections_total,
+            signature_verifications_total,
+            app_header_messages_total,
+            possible_duplicates_total,
+            reconciliation_entries_total,
+            reconciliation_statements_total,
+            funding_events_total,
+            funding_queue_unmatched,
+            webhook_deliveries_total,
+            webhook_delivery_duration_seconds,
+            webhook_pending_deliveries,
+            webhook_dead_deliveries,
+            stream_connections,
+            stream_events_total,
             db_operations_total,
             db_operation_duration_seconds,
             db_errors_total,
             nats_messages_published_total,
             nats_publish_errors_total,
             nats_publish_duration_seconds,
+            outbox_pending_messages,
+            outbox_lag_seconds,
+            outbox_delivery_lag_seconds,
+            outbox_delivery_failures_total,
             total_transaction_volume,
             clearing_batches_total,
             settlement_executions_total,
@@ -283,6 +534,78 @@ impl Metrics {
             _ => {}
         }
     }
+
+    /// Track XSD validation outcome by message type
+    pub fn track_xsd_validation(&self, message_type: &str, passed: bool) {
+        self.xsd_validations_total.with_label_values(&[message_type]).inc();
+        if !passed {
+            self.xsd_validation_failures_total.with_label_values(&[message_type]).inc();
+        }
+    }
+
+    pub fn track_rule_violation(&self, rule: &str, code: &str) {
+        self.business_rule_violations_total.with_label_values(&[rule, code]).inc();
+    }
+
+    /// Track a duplicate submission by the identifier it matched on
+    pub fn track_duplicate_submission(&self, matched_on: &str) {
+        self.duplicate_submissions_total.with_label_values(&[matched_on]).inc();
+    }
+
+    /// outcome: replayed, new (flagged PssblDplct) or unflagged_replay
+    pub fn track_possible_duplicate(&self, outcome: &str) {
+        self.possible_duplicates_total.with_label_values(&[outcome]).inc();
+    }
+
+    /// result: matched, amount_mismatch, status_mismatch, unmatched, no_reference, not_booked
+    pub fn track_reconciliation_entry(&self, result: &str) {
+        self.reconciliation_entries_total.with_label_values(&[result]).inc();
+    }
+
+    /// balance: balanced, unbalanced or duplicate (statement already received)
+    pub fn track_reconciliation_statement(&self, balance: &str) {
+        self.reconciliation_statements_total.with_label_values(&[balance]).inc();
+    }
+
+    /// outcome: reference, fuzzy, manual (applied by that method), late (applied from the queue),
+    /// partial, overfunded, pending, queued or duplicate
+    pub fn track_funding_event(&self, outcome: &str) {
+        self.funding_events_total.with_label_values(&[outcome]).inc();
+    }
+
+    /// result: delivered, retry (rescheduled) or dead (attempts exhausted)
+    pub fn track_webhook_delivery(&self, result: &str) {
+        self.webhook_deliveries_total.with_label_values(&[result]).inc();
+    }
+
+    /// delivery: live or replayed (resume from sequence)
+    pub fn track_stream_event(&self, delivery: &str) {
+        self.stream_events_total.with_label_values(&[delivery]).inc();
+    }
+
+    pub fn track_tracking_event(&self, source: &str) {
+        self.tracking_events_total.with_label_values(&[source]).inc();
+    }
+
+    pub fn track_bulk_transaction(&self, outcome: &str) {
+        self.bulk_transactions_total.with_label_values(&[outcome]).inc();
+    }
+
+    pub fn track_bulk_batch(&self, status: &str) {
+        self.bulk_batches_total.with_label_values(&[status]).inc();
+    }
+
+    pub fn track_auth_rejection(&self, reason: &str) {
+        self.auth_rejections_total.with_label_values(&[reason]).inc();
+    }
+
+    pub fn track_signature_verification(&self, message_type: &str, result: &str) {
+        self.signature_verifications_total.with_label_values(&[message_type, result]).inc();
+    }
+
+    pub fn track_app_header(&self, message_type: &str) {
+        self.app_header_messages_total.with_label_values(&[message_type]).inc();
+    }
 }
 
 // Global metrics instance
@@ -0,0 +1,198 @@
+// Bulk Ingestion Model - pain.001 files uploaded via POST /iso20022/pain.001/bulk
+// A batch is processed in the background; every CdtTrfTxInf gets its own outcome
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Processing state of an uploaded file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BatchStatus {
+    Processing,
+    Completed,
+    Failed,     // file could not be read to the end (malformed XML, unreadable header)
+}
+
+impl BatchStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BatchStatus::Processing => "PROCESSING",
+            BatchStatus::Completed => "COMPLETED",
+            BatchStatus::Failed => "FAILED",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "PROCESSING" => Some(BatchStatus::Processing),
+            "COMPLETED" => Some(BatchStatus::Completed),
+            "FAILED" => Some(BatchStatus::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// Outcome of a single transaction of a batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionOutcome {
+    Accepted,
+    Rejected,
+    Duplicate,  // already ingested within the dedup horizon - reported as RJCT / DUPL
+}
+
+impl TransactionOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransactionOutcome::Accepted => "accepted",
+            TransactionOutcome::Rejected => "rejected",
+            TransactionOutcome::Duplicate => "duplicate",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "accepted" => Some(TransactionOutcome::Accepted),
+            "rejected" => Some(TransactionOutcome::Rejected),
+            "duplicate" => Some(TransactionOutcome::Duplicate),
+            _ => None,
+        }
+    }
+}
+
+/// Batch record as returned by GET /batches/:batch_id
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaymentBatch {
+    pub batch_id: Uuid,
+    pub file_name: Option<String>,
+    pub message_id: Option<String>,             // GrpHdr/MsgId, known once the header is read
+    pub debtor_agent_bic: Option<String>,       // recipient of the batch pain.002
+    pub submitted_by_bic: Option<String>,       // participant that uploaded the file
+    pub status: BatchStatus,
+    pub group_status: Option<String>,           // ACTC, PART or RJCT once processed
+    pub declared_transactions: Option<i32>,     // GrpHdr/NbOfTxs
+    pub processed_transactions: i32,
+    pub accepted_transactions: i32,
+    pub rejected_transactions: i32,
+    pub duplicate_transactions: i32,
+    pub status_report_id: Option<Uuid>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl PaymentBatch {
+    pub fn new(file_name: Option<String>, submitted_by_bic: Option<String>) -> Self {
+        let now = Utc::now();
+        Self {
+            batch_id: Uuid::new_v4(),
+            file_name,
+            message_id: None,
+            debtor_agent_bic: None,
+            submitted_by_bic,
+            status: BatchStatus::Processing,
+            group_status: None,
+            declared_transactions: None,
+            processed_transactions: 0,
+            accepted_transactions: 0,
+            rejected_transactions: 0,
+            duplicate_transactions: 0,
+            status_report_id: None,
+            error: None,
+            created_at: now,
+            updated_at: now,
+            completed_at: None,
+        }
+    }
+
+    pub fn record(&mut self, outcome: TransactionOutcome) {
+        self.processed_transactions += 1;
+        match outcome {
+            TransactionOutcome::Accepted => self.accepted_transactions += 1,
+            TransactionOutcome::Rejected => self.rejected_transactions += 1,
+            TransactionOutcome::Duplicate => self.duplicate_transactions += 1,
+        }
+        self.updated_at = Utc::now();
+    }
+
+    /// Percentage of declared transactions processed (None until NbOfTxs is known)
+    pub fn progress_percent(&self) -> Option<f64> {
+        if self.status != BatchStatus::Processing {
+            return Some(100.0);
+        }
+        match self.declared_transactions {
+            Some(declared) if declared > 0 => {
+                Some((self.processed_transactions as f64 * 100.0 / declared as f64).min(100.0))
+            }
+            _ => None,
+        }
+    }
+
+    /// Group status of the batch pain.002: ACTC (all accepted), RJCT (none) or PART
+    pub fn derive_group_status(&self) -> Option<&'static str> {
+        match (self.processed_transactions, self.accepted_transactions) {
+            (0, _) => None,
+            (_, 0) => Some("RJCT"),
+            (processed, accepted) if processed == accepted => Some("ACTC"),
+            _ => Some("PART"),
+        }
+    }
+
+    pub fn finish(&mut self, error: Option<String>) {
+        self.status = if error.is_some() { BatchStatus::Failed } else { BatchStatus::Completed };
+        self.group_status = self.derive_group_status().map(str::to_string);
+        self.error = error;
+        self.updated_at = Utc::now();
+        self.completed_at = Some(self.updated_at);
+    }
+}
+
+/// Per-transaction result of a batch, as listed by GET /batches/:batch_id/transactions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchTransaction {
+    pub batch_id: Uuid,
+    pub sequence: i32,                      // 1-based position of the CdtTrfTxInf in the file
+    pub payment_info_id: Option<String>,
+    pub end_to_end_id: Option<String>,
+    pub instruction_id: Option<String>,
+    pub uetr: Option<Uuid>,
+    pub deltran_tx_id: Option<Uuid>,        // new payment, or the original one for duplicates
+    pub outcome: TransactionOutcome,
+    pub reason_code: Option<String>,        // ExternalStatusReason1Code for rejects (FF01, AM03, DUPL, ...)
+    pub reason_info: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_progress_and_group_status() {
+        let mut batch = PaymentBatch::new(Some("payroll.xml".to_string()), None);
+        assert_eq!(batch.progress_percent(), None);
+        assert_eq!(batch.derive_group_status(), None);
+
+        batch.declared_transactions = Some(4);
+        batch.record(TransactionOutcome::Accepted);
+        batch.record(TransactionOutcome::Accepted);
+        assert_eq!(batch.progress_percent(), Some(50.0));
+        assert_eq!(batch.derive_group_status(), Some("ACTC"));
+
+        batch.record(TransactionOutcome::Duplicate);
+        batch.record(TransactionOutcome::Rejected);
+        batch.finish(None);
+        assert_eq!(batch.status, BatchStatus::Completed);
+        assert_eq!(batch.group_status.as_deref(), Some("PART"));
+        assert_eq!(batch.progress_percent(), Some(100.0));
+    }
+
+    #[test]
+    fn test_outcome_roundtrip() {
+        for outcome in [TransactionOutcome::Accepted, TransactionOutcome::Rejected, TransactionOutcome::Duplicate] {
+            assert_eq!(TransactionOutcome::parse(outcome.as_str()), Some(outcome));
+        }
+        assert_eq!(BatchStatus::parse(BatchStatus::Failed.as_str()), Some(BatchStatus::Failed));
+    }
+}
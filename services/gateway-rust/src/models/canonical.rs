@@ -2,10 +2,16 @@
 // This is the single source of truth for payment data within DelTran
 
 use chrono::{DateTime, Utc, NaiveDate};
+use deltran_schema::payment::PAYMENT_EVENT;
+use deltran_schema::Event;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
 use uuid::Uuid;
 
+pub use deltran_schema::currency::Currency;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CanonicalPayment {
     // DelTran IDs
@@ -51,6 +57,8 @@ pub struct CanonicalPayment {
     // Remittance Information
     pub remittance_info: String,
     pub remittance_structured: Option<StructuredRemittance>,
+    #[serde(default)]
+    pub purpose_code: Option<String>,          // Purp/Cd (ExternalPurpose1Code, e.g. SALA)
 
     // Risk & Compliance
     pub risk_score: Option<f64>,
@@ -110,52 +118,7 @@ pub enum AccountType {
     Other,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-pub enum Currency {
-    Usd,
-    Eur,
-    Gbp,
-    Aed,
-    Inr,
-    Sar,
-    Qar,
-    Omr,
-    Kwd,
-}
-
-impl Currency {
-    pub fn to_string(&self) -> String {
-        match self {
-            Currency::Usd => "USD".to_string(),
-            Currency::Eur => "EUR".to_string(),
-            Currency::Gbp => "GBP".to_string(),
-            Currency::Aed => "AED".to_string(),
-            Currency::Inr => "INR".to_string(),
-            Currency::Sar => "SAR".to_string(),
-            Currency::Qar => "QAR".to_string(),
-            Currency::Omr => "OMR".to_string(),
-            Currency::Kwd => "KWD".to_string(),
-        }
-    }
-
-    pub fn from_str(s: &str) -> Option<Self> {
-        match s.to_uppercase().as_str() {
-            "USD" => Some(Currency::Usd),
-            "EUR" => Some(Currency::Eur),
-            "GBP" => Some(Currency::Gbp),
-            "AED" => Some(Currency::Aed),
-            "INR" => Some(Currency::Inr),
-            "SAR" => Some(Currency::Sar),
-            "QAR" => Some(Currency::Qar),
-            "OMR" => Some(Currency::Omr),
-            "KWD" => Some(Currency::Kwd),
-            _ => None,
-        }
-    }
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum PaymentStatus {
     // Initial states
@@ -187,6 +150,21 @@ pub enum PaymentStatus {
     Returned,               // pacs.004 return received
 }
 
+// Variant name, as stored in payments.status and payment_events.status ("PendingFunding")
+impl fmt::Display for PaymentStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+impl FromStr for PaymentStatus {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        super::search::status_from_db(value).ok_or_else(|| format!("unknown payment status: {}", value))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StatusReason {
     pub code: String,       // ISO 20022 reason code (e.g., "AC01", "AM04")
@@ -203,6 +181,28 @@ pub enum ChargeBearer {
     Cred,   // Creditor pays all
 }
 
+impl ChargeBearer {
+    /// ChargeBearerType1Code as used in ChrgBr
+    pub fn code(&self) -> &'static str {
+        match self {
+            ChargeBearer::Shar => "SHAR",
+            ChargeBearer::Slev => "SLEV",
+            ChargeBearer::Debt => "DEBT",
+            ChargeBearer::Cred => "CRED",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "SHAR" => Some(ChargeBearer::Shar),
+            "SLEV" => Some(ChargeBearer::Slev),
+            "DEBT" => Some(ChargeBearer::Debt),
+            "CRED" => Some(ChargeBearer::Cred),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Charge {
     pub charge_type: ChargeType,
@@ -255,6 +255,7 @@ pub enum Priority {
 
 // Helper functions for CanonicalPayment
 impl CanonicalPayment {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         end_to_end_id: String,
         instruction_id: String,
@@ -307,6 +308,7 @@ impl CanonicalPayment {
             charges: vec![],
             remittance_info: String::new(),
             remittance_structured: None,
+            purpose_code: None,
             risk_score: None,
             compliance_status: ComplianceStatus::Pending,
             sanctions_checked: false,
@@ -343,9 +345,21 @@ impl CanonicalPayment {
     }
 }
 
+// Published as payment.canonical - a superset of deltran_schema::payment::CanonicalPayment,
+// the shape every engine reads. Removing or renaming a field the engines read needs a new VERSION.
+impl Event for CanonicalPayment {
+    const TYPE: &'static str = PAYMENT_EVENT;
+    const VERSION: u32 = 1;
+
+    fn correlation_id(&self) -> Uuid {
+        self.deltran_tx_id
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use deltran_schema::EventEnvelope;
     use rust_decimal_macros::dec;
 
     #[test]
@@ -355,7 +369,7 @@ mod tests {
             "INSTR456".to_string(),
             "MSG789".to_string(),
             dec!(1000.00),
-            Currency::Usd,
+            Currency::USD,
             Party {
                 name: "John Doe".to_string(),
                 postal_address: None,
@@ -396,7 +410,7 @@ mod tests {
             "INSTR456".to_string(),
             "MSG789".to_string(),
             dec!(1000.00),
-            Currency::Usd,
+            Currency::USD,
             Party {
                 name: "John".to_string(),
                 postal_address: None,
@@ -432,4 +446,53 @@ mod tests {
         assert!(payment.is_final_status());
         assert!(!payment.can_cancel());
     }
+
+    #[test]
+    fn test_engines_read_published_payment() {
+        let mut payment = CanonicalPayment::new(
+            "E2E123".to_string(),
+            "INSTR456".to_string(),
+            "MSG789".to_string(),
+            dec!(1000.10),
+            Currency::AED,
+            Party {
+                name: "John".to_string(),
+                postal_address: None,
+                identification: None,
+                country_code: "AE".to_string(),
+            },
+            Party {
+                name: "Jane".to_string(),
+                postal_address: None,
+                identification: None,
+                country_code: "IN".to_string(),
+            },
+            FinancialInstitution {
+                bic: Some("BANKAEADXXX".to_string()),
+                name: "Bank A".to_string(),
+                country_code: "AE".to_string(),
+                clearing_system_member_id: None,
+            },
+            FinancialInstitution {
+                bic: None,
+                name: "Bank IN".to_string(),
+                country_code: "IN".to_string(),
+                clearing_system_member_id: None,
+            },
+        );
+        payment.update_status(PaymentStatus::Validated, None);
+
+        let bytes = deltran_schema::envelope::encode(&payment).unwrap();
+        let envelope = EventEnvelope::<deltran_schema::payment::CanonicalPayment>::from_slice(&bytes).unwrap();
+        let shared = envelope.payload;
+
+        assert_eq!(envelope.correlation_id, payment.deltran_tx_id);
+        assert_eq!(shared.deltran_tx_id, payment.deltran_tx_id);
+        assert_eq!(shared.instructed_amount, dec!(1000.10));
+        assert_eq!(shared.currency, "AED");
+        assert_eq!(shared.debtor.country_code.as_deref(), Some("AE"));
+        assert_eq!(shared.creditor_agent.bic, None);
+        assert_eq!(shared.creditor_agent.country().as_deref(), Some("IN"));
+        assert_eq!(shared.status, "VALIDATED");
+    }
 }
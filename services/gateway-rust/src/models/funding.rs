@@ -0,0 +1,459 @@
+// Funding Event Model - camt.054 credits kept until they fund a payment
+// Pending entries and credits whose payment is unknown (no EndToEndId, pain.001 not yet arrived)
+// wait in funding_events. They are matched by reference (UETR / EndToEndId) or fuzzily on
+// amount + currency plus debtor account / remittance info / debtor name, or by an operator.
+// A payment may be funded by several credits: it is fully funded once they cover the instructed
+// amount (within a tolerance); any excess is flagged for refund.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::canonical::{CanonicalPayment, PaymentStatus};
+use super::participant::same_institution;
+
+/// Minimum fuzzy score: a debtor account or remittance reference must agree
+pub const FUZZY_MATCH_THRESHOLD: u8 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FundingEventState {
+    Pending,    // entry not booked yet (PDNG / INFO)
+    Unmatched,  // booked credit waiting for its payment
+    Matched,    // funded a payment - never matched again
+}
+
+impl FundingEventState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FundingEventState::Pending => "pending",
+            FundingEventState::Unmatched => "unmatched",
+            FundingEventState::Matched => "matched",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(FundingEventState::Pending),
+            "unmatched" => Some(FundingEventState::Unmatched),
+            "matched" => Some(FundingEventState::Matched),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchMethod {
+    Reference,  // UETR or EndToEndId
+    Fuzzy,      // amount + currency + debtor account / remittance info / debtor name
+    Manual,     // operator via POST /funding/events/:event_id/match
+}
+
+impl MatchMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MatchMethod::Reference => "reference",
+            MatchMethod::Fuzzy => "fuzzy",
+            MatchMethod::Manual => "manual",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "reference" => Some(MatchMethod::Reference),
+            "fuzzy" => Some(MatchMethod::Fuzzy),
+            "manual" => Some(MatchMethod::Manual),
+            _ => None,
+        }
+    }
+}
+
+/// Stored camt.054 credit entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FundingEventRecord {
+    pub event_id: Uuid,
+    pub account_id: String,
+    pub account_servicer_bic: Option<String>,   // Acct/Svcr as named in the message - not trusted
+    pub servicer_verified: bool,                // sender services account_id per participant_accounts
+    pub received_from_bic: String,              // participant that sent the camt.054
+    pub entry_reference: Option<String>,        // NtryRef - a re-sent entry updates the stored one
+    pub amount: Decimal,
+    pub currency: String,
+    pub entry_status: String,                   // BOOK, PDNG, INFO
+    pub booking_date: Option<String>,
+    pub value_date: Option<String>,
+    pub end_to_end_id: Option<String>,
+    pub instruction_id: Option<String>,
+    pub uetr: Option<Uuid>,
+    pub debtor_name: Option<String>,
+    pub debtor_account: Option<String>,
+    pub remittance_info: Option<String>,
+    pub state: FundingEventState,
+    pub deltran_tx_id: Option<Uuid>,
+    pub match_method: Option<MatchMethod>,
+    pub matched_by_bic: Option<String>,         // operator of a manual match
+    pub match_note: Option<String>,
+    pub matched_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl FundingEventRecord {
+    pub fn is_booked(&self) -> bool {
+        self.entry_status == "BOOK"
+    }
+
+    /// Whether the sender may fund `debtor_agent_bic`'s payment without being asked again:
+    /// the sender was found to service the credited account when the entry was stored,
+    /// otherwise only the debtor agent confirms its own funding
+    pub fn may_fund(&self, debtor_agent_bic: Option<&str>) -> bool {
+        self.servicer_verified
+            || debtor_agent_bic.is_some_and(|bic| same_institution(&self.received_from_bic, bic))
+    }
+
+    /// A credit only funds a payment in its own currency, whatever references it matches by
+    pub fn same_currency(&self, payment: &CanonicalPayment) -> bool {
+        self.currency.eq_ignore_ascii_case(payment.currency.code())
+    }
+
+    /// Fuzzy score against a payment with the same amount and currency:
+    /// debtor account +2, EndToEndId / InstrId / remittance text in the remittance info +2,
+    /// debtor name +1. At least FUZZY_MATCH_THRESHOLD is required.
+    pub fn fuzzy_score(&self, candidate: &FundingCandidate) -> u8 {
+        let mut score = 0;
+
+        if let Some(account) = self.debtor_account.as_deref().map(normalize_account) {
+            let matches = [&candidate.debtor_iban, &candidate.debtor_account].iter()
+                .any(|a| a.as_deref().map(normalize_account).as_deref() == Some(account.as_str()));
+            if matches && !account.is_empty() {
+                score += 2;
+            }
+        }
+
+        if let Some(remittance) = self.remittance_info.as_deref().map(str::to_ascii_uppercase) {
+            let references = [Some(&candidate.end_to_end_id), Some(&candidate.instruction_id), candidate.remittance_info.as_ref()];
+            let matches = references.iter().flatten()
+                .map(|r| r.trim().to_ascii_uppercase())
+                .any(|r| r.len() >= 4 && remittance.contains(&r));
+            if matches {
+                score += 2;
+            }
+        }
+
+        if let (Some(a), Some(b)) = (self.debtor_name.as_deref(), candidate.debtor_name.as_deref()) {
+            let name = normalize_name(a);
+            if !name.is_empty() && name == normalize_name(b) {
+                score += 1;
+            }
+        }
+
+        score
+    }
+}
+
+/// Status and funding amounts of a payment (payments.funded_amount / overfunded_amount)
+#[derive(Debug, Clone)]
+pub struct PaymentFunding {
+    pub status: PaymentStatus,
+    pub instructed_amount: Decimal,
+    pub funded_amount: Decimal,                 // sum of matched credits
+    pub overfunded_amount: Decimal,             // credited beyond the instructed amount
+}
+
+impl PaymentFunding {
+    /// Funding after a further credit of `amount`. Once funded, every further credit is excess.
+    pub fn credit(&self, amount: Decimal, tolerance_bps: u32) -> FundingProgress {
+        let already_funded = self.funded_amount > Decimal::ZERO
+            && matches!(funding_progress(self.instructed_amount, self.funded_amount, tolerance_bps), FundingProgress::Funded { .. });
+        if already_funded {
+            return FundingProgress::Overfunded { excess: amount };
+        }
+
+        funding_progress(self.instructed_amount, self.funded_amount + amount, tolerance_bps)
+    }
+}
+
+/// Over-funded payment whose excess is to be refunded, as returned by GET /funding/refunds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FundingRefund {
+    pub deltran_tx_id: Uuid,
+    pub end_to_end_id: String,
+    pub debtor_agent_bic: Option<String>,
+    pub currency: String,
+    pub instructed_amount: Decimal,
+    pub funded_amount: Decimal,
+    pub refund_amount: Decimal,
+    pub funded_at: Option<DateTime<Utc>>,
+}
+
+/// Funding of a payment after a matched credit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FundingProgress {
+    /// Shortfall beyond the tolerance - the payment waits for further credits
+    Partial { funded: Decimal, outstanding: Decimal },
+    /// Covered within the tolerance: tokens are minted for `mint_amount`, `excess` is refunded
+    Funded { funded: Decimal, mint_amount: Decimal, excess: Decimal },
+    /// Credit for a payment that was already funded - refunded in full
+    Overfunded { excess: Decimal },
+}
+
+impl FundingProgress {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FundingProgress::Partial { .. } => "partial",
+            FundingProgress::Funded { .. } => "funded",
+            FundingProgress::Overfunded { .. } => "overfunded",
+        }
+    }
+
+    /// Amount credited beyond the instructed one
+    pub fn excess(&self) -> Decimal {
+        match self {
+            FundingProgress::Partial { .. } => Decimal::ZERO,
+            FundingProgress::Funded { excess, .. } | FundingProgress::Overfunded { excess } => *excess,
+        }
+    }
+}
+
+/// Funding of a payment that has received `funded` of `instructed` so far.
+/// A shortfall of up to `tolerance_bps` basis points of the instructed amount counts as funded;
+/// tokens are never minted beyond the instructed amount.
+pub fn funding_progress(instructed: Decimal, funded: Decimal, tolerance_bps: u32) -> FundingProgress {
+    let tolerance = instructed * Decimal::from(tolerance_bps) / Decimal::from(10_000);
+
+    if funded + tolerance < instructed {
+        return FundingProgress::Partial { funded, outstanding: instructed - funded };
+    }
+
+    FundingProgress::Funded {
+        funded,
+        mint_amount: funded.min(instructed),
+        excess: (funded - instructed).max(Decimal::ZERO),
+    }
+}
+
+/// Payment fields used for fuzzy funding matches
+#[derive(Debug, Clone)]
+pub struct FundingCandidate {
+    pub deltran_tx_id: Uuid,
+    pub end_to_end_id: String,
+    pub instruction_id: String,
+    pub debtor_name: Option<String>,
+    pub debtor_iban: Option<String>,
+    pub debtor_account: Option<String>,
+    pub remittance_info: Option<String>,
+    pub debtor_agent_bic: Option<String>,
+}
+
+impl From<&CanonicalPayment> for FundingCandidate {
+    fn from(payment: &CanonicalPayment) -> Self {
+        Self {
+            deltran_tx_id: payment.deltran_tx_id,
+            end_to_end_id: payment.end_to_end_id.clone(),
+            instruction_id: payment.instruction_id.clone(),
+            debtor_name: Some(payment.debtor.name.clone()).filter(|name| !name.is_empty()),
+            debtor_iban: payment.debtor_account.iban.clone(),
+            debtor_account: payment.debtor_account.other.clone().or_else(|| payment.debtor_account.bban.clone()),
+            remittance_info: Some(payment.remittance_info.clone()).filter(|info| !info.is_empty()),
+            debtor_agent_bic: payment.debtor_agent.bic.clone(),
+        }
+    }
+}
+
+/// Best candidate if it reaches the threshold and no other candidate scores the same
+pub fn best_match<T>(scored: impl IntoIterator<Item = (u8, T)>) -> Option<T> {
+    let mut best: Option<(u8, T)> = None;
+    let mut tied = false;
+
+    for (score, item) in scored {
+        match &best {
+            Some((top, _)) if score < *top => {}
+            Some((top, _)) if score == *top => tied = true,
+            _ => {
+                best = Some((score, item));
+                tied = false;
+            }
+        }
+    }
+
+    best.filter(|(score, _)| *score >= FUZZY_MATCH_THRESHOLD && !tied)
+        .map(|(_, item)| item)
+}
+
+/// Account ids compare without spaces and case ("AE07 0331 ..." == "ae070331...")
+fn normalize_account(account: &str) -> String {
+    account.chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+fn normalize_name(name: &str) -> String {
+    name.split_whitespace()
+        .map(str::to_ascii_uppercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn event() -> FundingEventRecord {
+        FundingEventRecord {
+            event_id: Uuid::new_v4(),
+            account_id: "AE070331234567890123456".to_string(),
+            account_servicer_bic: None,
+            servicer_verified: false,
+            received_from_bic: "BANKAEADXXX".to_string(),
+            entry_reference: Some("NTRY-1".to_string()),
+            amount: dec!(500.00),
+            currency: "AED".to_string(),
+            entry_status: "BOOK".to_string(),
+            booking_date: None,
+            value_date: None,
+            end_to_end_id: None,
+            instruction_id: None,
+            uetr: None,
+            debtor_name: Some("acme  trading llc".to_string()),
+            debtor_account: Some("AE12 0000 1111 2222".to_string()),
+            remittance_info: Some("Invoice 77 / E2E-FUND-1".to_string()),
+            state: FundingEventState::Unmatched,
+            deltran_tx_id: None,
+            match_method: None,
+            matched_by_bic: None,
+            match_note: None,
+            matched_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn candidate(end_to_end_id: &str, debtor_iban: Option<&str>) -> FundingCandidate {
+        FundingCandidate {
+            deltran_tx_id: Uuid::new_v4(),
+            end_to_end_id: end_to_end_id.to_string(),
+            instruction_id: "INSTR-X".to_string(),
+            debtor_name: Some("ACME Trading LLC".to_string()),
+            debtor_iban: debtor_iban.map(str::to_string),
+            debtor_account: None,
+            remittance_info: None,
+            debtor_agent_bic: Some("BANKAEADXXX".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        let event = event();
+        assert_eq!(event.fuzzy_score(&candidate("E2E-FUND-1", Some("AE120000111122 22"))), 5);
+        assert_eq!(event.fuzzy_score(&candidate("E2E-OTHER", Some("AE120000111122 22"))), 3);
+        assert_eq!(event.fuzzy_score(&candidate("E2E-OTHER", None)), 1);
+    }
+
+    #[test]
+    fn test_best_match() {
+        assert_eq!(best_match(vec![(3, "a"), (1, "b")]), Some("a"));
+        assert_eq!(best_match(vec![(3, "a"), (3, "b")]), None);
+        assert_eq!(best_match(vec![(1, "a")]), None);
+        assert_eq!(best_match(Vec::<(u8, &str)>::new()), None);
+    }
+
+    #[test]
+    fn test_funding_progress() {
+        assert_eq!(
+            funding_progress(dec!(1000.00), dec!(400.00), 0),
+            FundingProgress::Partial { funded: dec!(400.00), outstanding: dec!(600.00) }
+        );
+        assert_eq!(
+            funding_progress(dec!(1000.00), dec!(1000.00), 0),
+            FundingProgress::Funded { funded: dec!(1000.00), mint_amount: dec!(1000.00), excess: dec!(0) }
+        );
+
+        // 50 bps of 1000.00 = 5.00 short is still funded, minted for what was received
+        assert_eq!(
+            funding_progress(dec!(1000.00), dec!(995.00), 50),
+            FundingProgress::Funded { funded: dec!(995.00), mint_amount: dec!(995.00), excess: dec!(0) }
+        );
+        assert!(matches!(funding_progress(dec!(1000.00), dec!(994.99), 50), FundingProgress::Partial { .. }));
+
+        let overfunded = funding_progress(dec!(1000.00), dec!(1200.00), 50);
+        assert_eq!(
+            overfunded,
+            FundingProgress::Funded { funded: dec!(1200.00), mint_amount: dec!(1000.00), excess: dec!(200.00) }
+        );
+        assert_eq!(overfunded.excess(), dec!(200.00));
+    }
+
+    #[test]
+    fn test_payment_funding_credit() {
+        let mut funding = PaymentFunding {
+            status: PaymentStatus::PendingFunding,
+            instructed_amount: dec!(1000.00),
+            funded_amount: dec!(0),
+            overfunded_amount: dec!(0),
+        };
+        assert!(matches!(funding.credit(dec!(600.00), 0), FundingProgress::Partial { .. }));
+
+        funding.funded_amount = dec!(600.00);
+        assert_eq!(
+            funding.credit(dec!(400.00), 0),
+            FundingProgress::Funded { funded: dec!(1000.00), mint_amount: dec!(1000.00), excess: dec!(0) }
+        );
+
+        funding.funded_amount = dec!(1000.00);
+        assert_eq!(funding.credit(dec!(25.00), 0), FundingProgress::Overfunded { excess: dec!(25.00) });
+    }
+
+    #[test]
+    fn test_same_currency() {
+        use crate::models::canonical::{Currency, FinancialInstitution, Party};
+
+        let party = || Party {
+            name: "Party".to_string(),
+            postal_address: None,
+            identification: None,
+            country_code: "AE".to_string(),
+        };
+        let agent = |bic: &str| FinancialInstitution {
+            bic: Some(bic.to_string()),
+            name: "Bank".to_string(),
+            country_code: bic[4..6].to_string(),
+            clearing_system_member_id: None,
+        };
+        let mut payment = CanonicalPayment::new(
+            "E2E-FUND-1".to_string(), "INSTR-FUND-1".to_string(), "MSG-FUND-1".to_string(),
+            dec!(1000.00), Currency::USD,
+            party(), party(),
+            agent("BANKAEADXXX"), agent("OTHRINBBXXX"),
+        );
+        payment.uetr = Some(Uuid::new_v4());
+
+        // Same UETR and amount, but 1,000 INR is no funding for 1,000 USD
+        let mut event = event();
+        event.uetr = payment.uetr;
+        event.amount = dec!(1000.00);
+        event.currency = "INR".to_string();
+        assert!(!event.same_currency(&payment));
+
+        event.currency = "usd".to_string();
+        assert!(event.same_currency(&payment));
+    }
+
+    #[test]
+    fn test_may_fund() {
+        let mut event = event();
+        assert!(event.may_fund(Some("BANKAEAD")));
+        assert!(!event.may_fund(Some("OTHRINBBXXX")));
+
+        // Naming itself as Acct/Svcr does not let the sender fund another bank's payment
+        event.account_servicer_bic = Some("BANKAEADXXX".to_string());
+        assert!(!event.may_fund(Some("OTHRINBBXXX")));
+
+        event.servicer_verified = true;
+        assert!(event.may_fund(Some("OTHRINBBXXX")));
+        assert_eq!(FundingEventState::parse(event.state.as_str()), Some(FundingEventState::Unmatched));
+    }
+}
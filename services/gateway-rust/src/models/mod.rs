@@ -1,6 +1,15 @@
 // Models module
 
 pub mod canonical;
+pub mod tracking;
+pub mod batch;
+pub mod outbox;
+pub mod participant;
+pub mod reconciliation;
+pub mod funding;
+pub mod search;
+pub mod webhook;
+pub mod stream;
 
 // Re-export commonly used types
 pub use canonical::{CanonicalPayment, PaymentStatus, Currency, Party, FinancialInstitution};
@@ -0,0 +1,71 @@
+// Outbox Model - NATS messages stored alongside the payment they belong to
+// Written in the same transaction as the payment row, published later by the outbox relay
+
+use chrono::{DateTime, Utc};
+use deltran_schema::{Event, SchemaError};
+use serde::Serialize;
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct OutboxMessage {
+    pub outbox_id: Uuid,
+    pub deltran_tx_id: Option<Uuid>,
+    pub subject: String,
+    pub payload: Vec<u8>,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub next_attempt_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl OutboxMessage {
+    /// JSON message for `subject`, due immediately
+    pub fn json<T: Serialize>(deltran_tx_id: Option<Uuid>, subject: &str, body: &T) -> serde_json::Result<Self> {
+        Ok(Self::new(deltran_tx_id, subject, serde_json::to_vec(body)?))
+    }
+
+    /// Versioned event (deltran_schema envelope) for `subject`, due immediately
+    pub fn event<T: Event>(deltran_tx_id: Option<Uuid>, subject: &str, event: &T) -> Result<Self, SchemaError> {
+        Ok(Self::new(deltran_tx_id, subject, deltran_schema::envelope::encode(event)?))
+    }
+
+    fn new(deltran_tx_id: Option<Uuid>, subject: &str, payload: Vec<u8>) -> Self {
+        let now = Utc::now();
+        Self {
+            outbox_id: Uuid::new_v4(),
+            deltran_tx_id,
+            subject: subject.to_string(),
+            payload,
+            attempts: 0,
+            last_error: None,
+            next_attempt_at: now,
+            delivered_at: None,
+            created_at: now,
+        }
+    }
+
+    /// Seconds the message has been waiting for delivery
+    pub fn lag_seconds(&self, now: DateTime<Utc>) -> f64 {
+        let end = self.delivered_at.unwrap_or(now);
+        (end - self.created_at).num_milliseconds().max(0) as f64 / 1000.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_message() {
+        let tx_id = Uuid::new_v4();
+        let message = OutboxMessage::json(Some(tx_id), "deltran.compliance.check", &serde_json::json!({ "a": 1 })).unwrap();
+        assert_eq!(message.payload, br#"{"a":1}"#);
+        assert_eq!(message.deltran_tx_id, Some(tx_id));
+        assert_eq!(message.attempts, 0);
+        assert!(message.delivered_at.is_none());
+
+        let later = message.created_at + chrono::Duration::milliseconds(2500);
+        assert_eq!(message.lag_seconds(later), 2.5);
+    }
+}
@@ -0,0 +1,347 @@
+// Participant Model - the institution behind an authenticated gateway request
+// Identity comes from a JWT `bic` claim or an mTLS client certificate, both mapped to a row of
+// the participants table. Access checks compare BICs at institution level (BIC8).
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::canonical::CanonicalPayment;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParticipantRole {
+    Participant,
+    Operator,   // DelTran operations - sees every payment, may act for any BIC
+}
+
+impl ParticipantRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ParticipantRole::Participant => "participant",
+            ParticipantRole::Operator => "operator",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "participant" => Some(ParticipantRole::Participant),
+            "operator" => Some(ParticipantRole::Operator),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthMethod {
+    Jwt,
+    ClientCert,
+}
+
+impl AuthMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuthMethod::Jwt => "jwt",
+            AuthMethod::ClientCert => "client_cert",
+        }
+    }
+}
+
+/// Registered participant as stored in the participants table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Participant {
+    pub participant_id: Uuid,
+    pub bic: String,
+    pub name: String,
+    pub cert_fingerprint: Option<String>,
+    pub role: ParticipantRole,
+    pub active: bool,
+}
+
+impl Participant {
+    pub fn is_operator(&self) -> bool {
+        self.role == ParticipantRole::Operator
+    }
+
+    /// Whether `bic` (BIC8 or BIC11, any branch) belongs to this participant
+    pub fn is_institution(&self, bic: Option<&str>) -> bool {
+        bic.is_some_and(|bic| same_institution(&self.bic, bic))
+    }
+
+    /// pain.001 / pacs.008: only the debtor agent may submit a payment
+    pub fn may_submit(&self, payment: &CanonicalPayment) -> bool {
+        self.is_operator() || self.is_institution(payment.debtor_agent.bic.as_deref())
+    }
+
+    /// Payment queries and follow-up messages: debtor or creditor agent
+    pub fn may_access(&self, payment: &CanonicalPayment) -> bool {
+        self.is_operator()
+            || self.is_institution(payment.debtor_agent.bic.as_deref())
+            || self.is_institution(payment.creditor_agent.bic.as_deref())
+    }
+
+    /// camt.054: whether the credited account is registered as serviced by this participant
+    pub fn services_account(&self, account: Option<&ParticipantAccount>) -> bool {
+        self.is_operator()
+            || account.is_some_and(|account| account.active && account.participant_id == self.participant_id)
+    }
+
+    /// camt.054: the servicer of the credited account confirms funding; otherwise only the debtor agent may.
+    /// The Acct/Svcr named in the message is not trusted.
+    pub fn may_confirm_funding(&self, account: Option<&ParticipantAccount>, payment: &CanonicalPayment) -> bool {
+        self.services_account(account) || self.may_submit(payment)
+    }
+
+    /// Status reports and batches addressed to `bic`
+    pub fn may_act_for(&self, bic: Option<&str>) -> bool {
+        self.is_operator() || self.is_institution(bic)
+    }
+}
+
+/// Account registered in participant_accounts, serviced by `participant_id`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParticipantAccount {
+    pub account_id: String,
+    pub participant_id: Uuid,
+    pub active: bool,
+}
+
+/// Registered public key a participant signs inbound messages with (currently valid ones only)
+#[derive(Debug, Clone)]
+pub struct ParticipantKey {
+    pub participant_id: Uuid,
+    pub kid: String,
+    pub algorithm: String,      // JWS alg, e.g. ES256
+    pub public_key_pem: String,
+    pub valid_from: DateTime<Utc>,
+    pub valid_until: Option<DateTime<Utc>>,
+}
+
+/// Authenticated caller, attached to the request by the auth middleware
+#[derive(Debug, Clone)]
+pub struct AuthenticatedParticipant {
+    pub participant: Participant,
+    pub method: AuthMethod,
+    pub subject: String,    // JWT sub or certificate fingerprint
+}
+
+impl std::ops::Deref for AuthenticatedParticipant {
+    type Target = Participant;
+
+    fn deref(&self) -> &Participant {
+        &self.participant
+    }
+}
+
+/// Why a request was refused - stored as auth_audit_log.reason
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthFailure {
+    MissingCredentials,
+    InvalidToken,
+    UnknownCertificate,
+    UnknownParticipant,
+    InactiveParticipant,
+    NotDebtorAgent,
+    NotAccountServicer,
+    NotPaymentParty,
+    NotRecipient,
+    NotSender,
+    NotOperator,
+    NotEndpointOwner,
+    MissingSignature,
+    InvalidSignature,
+    UnknownSigningKey,
+}
+
+impl AuthFailure {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuthFailure::MissingCredentials => "missing_credentials",
+            AuthFailure::InvalidToken => "invalid_token",
+            AuthFailure::UnknownCertificate => "unknown_certificate",
+            AuthFailure::UnknownParticipant => "unknown_participant",
+            AuthFailure::InactiveParticipant => "inactive_participant",
+            AuthFailure::NotDebtorAgent => "not_debtor_agent",
+            AuthFailure::NotAccountServicer => "not_account_servicer",
+            AuthFailure::NotPaymentParty => "not_payment_party",
+            AuthFailure::NotRecipient => "not_recipient",
+            AuthFailure::NotSender => "not_sender",
+            AuthFailure::NotOperator => "not_operator",
+            AuthFailure::NotEndpointOwner => "not_endpoint_owner",
+            AuthFailure::MissingSignature => "missing_signature",
+            AuthFailure::InvalidSignature => "invalid_signature",
+            AuthFailure::UnknownSigningKey => "unknown_signing_key",
+        }
+    }
+}
+
+/// Refused request, written to auth_audit_log
+#[derive(Debug, Clone)]
+pub struct AuthAuditEntry {
+    pub audit_id: Uuid,
+    pub participant_bic: Option<String>,
+    pub subject: Option<String>,
+    pub auth_method: Option<AuthMethod>,
+    pub http_method: String,
+    pub path: String,
+    pub message_type: Option<String>,
+    pub reason: AuthFailure,
+    pub claimed_bic: Option<String>,
+    pub detail: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl AuthAuditEntry {
+    pub fn new(http_method: &str, path: &str, reason: AuthFailure) -> Self {
+        Self {
+            audit_id: Uuid::new_v4(),
+            participant_bic: None,
+            subject: None,
+            auth_method: None,
+            http_method: http_method.to_string(),
+            path: path.to_string(),
+            message_type: None,
+            reason,
+            claimed_bic: None,
+            detail: None,
+            occurred_at: Utc::now(),
+        }
+    }
+
+    /// Entry for an authenticated participant acting outside its own payments
+    pub fn forbidden(caller: &AuthenticatedParticipant, http_method: &str, path: &str, reason: AuthFailure) -> Self {
+        Self {
+            participant_bic: Some(caller.bic.clone()),
+            subject: Some(caller.subject.clone()),
+            auth_method: Some(caller.method),
+            ..Self::new(http_method, path, reason)
+        }
+    }
+
+    pub fn message_type(mut self, message_type: &str) -> Self {
+        self.message_type = Some(message_type.to_string());
+        self
+    }
+
+    pub fn claimed_bic(mut self, bic: Option<&str>) -> Self {
+        self.claimed_bic = bic.map(|bic| bic.chars().take(11).collect());
+        self
+    }
+
+    pub fn detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+}
+
+/// BIC8 comparison: institution code, country and location, ignoring the branch
+pub fn same_institution(a: &str, b: &str) -> bool {
+    let a = a.trim();
+    let b = b.trim();
+    a.len() >= 8 && b.len() >= 8 && a.is_char_boundary(8) && b.is_char_boundary(8)
+        && a[..8].eq_ignore_ascii_case(&b[..8])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn participant(bic: &str, role: ParticipantRole) -> Participant {
+        Participant {
+            participant_id: Uuid::new_v4(),
+            bic: bic.to_string(),
+            name: "Test Bank".to_string(),
+            cert_fingerprint: None,
+            role,
+            active: true,
+        }
+    }
+
+    #[test]
+    fn test_same_institution() {
+        assert!(same_institution("BANKAEAD", "BANKAEADXXX"));
+        assert!(same_institution("bankaeadxxx", "BANKAEAD001"));
+        assert!(!same_institution("BANKAEAD", "BANKINBB"));
+        assert!(!same_institution("BANK", "BANK"));
+    }
+
+    #[test]
+    fn test_participant_access() {
+        let bank = participant("BANKAEAD", ParticipantRole::Participant);
+        assert!(bank.may_act_for(Some("BANKAEADXXX")));
+        assert!(!bank.may_act_for(Some("OTHRINBBXXX")));
+        assert!(!bank.may_act_for(None));
+
+        let operator = participant("DLTRAEAD", ParticipantRole::Operator);
+        assert!(operator.may_act_for(Some("OTHRINBBXXX")));
+        assert!(operator.may_act_for(None));
+    }
+
+    fn payment_between(debtor_agent: &str, creditor_agent: &str) -> CanonicalPayment {
+        use crate::models::canonical::{Currency, FinancialInstitution, Party};
+        use rust_decimal_macros::dec;
+
+        let party = || Party {
+            name: "Party".to_string(),
+            postal_address: None,
+            identification: None,
+            country_code: "AE".to_string(),
+        };
+        let agent = |bic: &str| FinancialInstitution {
+            bic: Some(bic.to_string()),
+            name: "Bank".to_string(),
+            country_code: bic[4..6].to_string(),
+            clearing_system_member_id: None,
+        };
+        CanonicalPayment::new(
+            "E2E-AUTH".to_string(), "INSTR-AUTH".to_string(), "MSG-AUTH".to_string(),
+            dec!(100.00), Currency::AED,
+            party(), party(),
+            agent(debtor_agent), agent(creditor_agent),
+        )
+    }
+
+    #[test]
+    fn test_payment_access() {
+        let payment = payment_between("BANKAEADXXX", "OTHRINBBXXX");
+
+        let debtor_agent = participant("BANKAEAD", ParticipantRole::Participant);
+        let creditor_agent = participant("OTHRINBB", ParticipantRole::Participant);
+        let stranger = participant("THRDSARI", ParticipantRole::Participant);
+
+        assert!(debtor_agent.may_submit(&payment));
+        assert!(!creditor_agent.may_submit(&payment));
+        assert!(creditor_agent.may_access(&payment));
+        assert!(!stranger.may_access(&payment));
+    }
+
+    #[test]
+    fn test_confirm_funding() {
+        let payment = payment_between("BANKAEADXXX", "OTHRINBBXXX");
+
+        let debtor_agent = participant("BANKAEAD", ParticipantRole::Participant);
+        let servicer = participant("SRVCAEAD", ParticipantRole::Participant);
+        let stranger = participant("THRDSARI", ParticipantRole::Participant);
+        let account = ParticipantAccount {
+            account_id: "AE070331234567890123456".to_string(),
+            participant_id: servicer.participant_id,
+            active: true,
+        };
+
+        // The registered servicer of the credited account, otherwise only the debtor agent
+        assert!(servicer.may_confirm_funding(Some(&account), &payment));
+        assert!(debtor_agent.may_confirm_funding(Some(&account), &payment));
+        assert!(debtor_agent.may_confirm_funding(None, &payment));
+
+        // A stranger naming itself as Acct/Svcr services neither an unregistered account nor one
+        // registered to another participant
+        assert!(!stranger.services_account(None));
+        assert!(!stranger.may_confirm_funding(None, &payment));
+        assert!(!stranger.services_account(Some(&account)));
+        assert!(!stranger.may_confirm_funding(Some(&account), &payment));
+
+        let closed = ParticipantAccount { active: false, ..account };
+        assert!(!servicer.may_confirm_funding(Some(&closed), &payment));
+    }
+}
@@ -0,0 +1,175 @@
+// Reconciliation Model - camt.053 statements and the match result of every entry
+// A statement is stored with its balances; each entry is matched against gateway payments
+// (UETR, then EndToEndId). Entries that do not match cleanly are the statement's breaks.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Match result of a statement entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryMatchStatus {
+    Matched,
+    AmountMismatch,     // payment found, amount or currency differs
+    StatusMismatch,     // payment found, but rejected / cancelled / returned / failed
+    Unmatched,          // no payment with the entry's references
+    NoReference,        // entry carries neither UETR nor EndToEndId
+    NotBooked,          // PDNG / INFO entries are stored but not reconciled
+}
+
+impl EntryMatchStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EntryMatchStatus::Matched => "matched",
+            EntryMatchStatus::AmountMismatch => "amount_mismatch",
+            EntryMatchStatus::StatusMismatch => "status_mismatch",
+            EntryMatchStatus::Unmatched => "unmatched",
+            EntryMatchStatus::NoReference => "no_reference",
+            EntryMatchStatus::NotBooked => "not_booked",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "matched" => Some(EntryMatchStatus::Matched),
+            "amount_mismatch" => Some(EntryMatchStatus::AmountMismatch),
+            "status_mismatch" => Some(EntryMatchStatus::StatusMismatch),
+            "unmatched" => Some(EntryMatchStatus::Unmatched),
+            "no_reference" => Some(EntryMatchStatus::NoReference),
+            "not_booked" => Some(EntryMatchStatus::NotBooked),
+            _ => None,
+        }
+    }
+
+    /// Booked entries that need manual investigation
+    pub fn is_break(&self) -> bool {
+        !matches!(self, EntryMatchStatus::Matched | EntryMatchStatus::NotBooked)
+    }
+}
+
+/// Stored camt.053 statement as returned by GET /reconciliation/statements/:id
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BankStatement {
+    pub statement_id: Uuid,
+    pub statement_reference: String,            // Stmt/Id
+    pub message_id: String,                     // GrpHdr/MsgId
+    pub account_id: String,
+    pub account_iban: Option<String>,
+    pub account_servicer_bic: Option<String>,
+    pub received_from_bic: String,              // participant that sent the statement
+    pub currency: String,
+    pub from_date: Option<DateTime<Utc>>,
+    pub to_date: Option<DateTime<Utc>>,
+    pub opening_balance: Option<Decimal>,       // OPBD, negative when DBIT
+    pub closing_balance: Option<Decimal>,       // CLBD, negative when DBIT
+    pub total_credits: Decimal,                 // booked entries only
+    pub total_debits: Decimal,
+    /// opening + credits - debits - closing (None without both balances)
+    pub balance_difference: Option<Decimal>,
+    pub entry_count: i32,
+    pub matched_entries: i32,
+    pub break_count: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+impl BankStatement {
+    /// Opening + credits - debits = closing
+    pub fn is_balanced(&self) -> bool {
+        self.balance_difference.is_some_and(|difference| difference.is_zero())
+    }
+
+    pub fn record(&mut self, status: EntryMatchStatus) {
+        if status == EntryMatchStatus::Matched {
+            self.matched_entries += 1;
+        } else if status.is_break() {
+            self.break_count += 1;
+        }
+    }
+}
+
+/// One Ntry of a statement with its match result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatementEntryRecord {
+    pub statement_id: Uuid,
+    pub sequence: i32,                          // position of the Ntry in the statement
+    pub entry_reference: Option<String>,        // AcctSvcrRef
+    pub amount: Decimal,
+    pub currency: String,
+    pub credit_debit_indicator: String,         // CRDT or DBIT
+    pub entry_status: String,                   // BOOK, PDNG, INFO
+    pub booking_date: Option<NaiveDate>,
+    pub value_date: Option<NaiveDate>,
+    pub end_to_end_id: Option<String>,
+    pub transaction_id: Option<String>,
+    pub uetr: Option<Uuid>,
+    pub match_status: EntryMatchStatus,
+    pub deltran_tx_id: Option<Uuid>,            // matched payment
+    pub break_reason: Option<String>,
+}
+
+/// Statement with its break list
+#[derive(Debug, Clone, Serialize)]
+pub struct StatementReconciliation {
+    #[serde(flatten)]
+    pub statement: BankStatement,
+    pub balanced: bool,
+    pub breaks: Vec<StatementEntryRecord>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_match_status_labels() {
+        for status in [
+            EntryMatchStatus::Matched,
+            EntryMatchStatus::AmountMismatch,
+            EntryMatchStatus::StatusMismatch,
+            EntryMatchStatus::Unmatched,
+            EntryMatchStatus::NoReference,
+            EntryMatchStatus::NotBooked,
+        ] {
+            assert_eq!(EntryMatchStatus::parse(status.as_str()), Some(status));
+        }
+        assert!(EntryMatchStatus::Unmatched.is_break());
+        assert!(!EntryMatchStatus::NotBooked.is_break());
+    }
+
+    #[test]
+    fn test_statement_balance() {
+        let mut statement = BankStatement {
+            statement_id: Uuid::new_v4(),
+            statement_reference: "STMT-1".to_string(),
+            message_id: "MSG-1".to_string(),
+            account_id: "AE070331234567890123456".to_string(),
+            account_iban: None,
+            account_servicer_bic: None,
+            received_from_bic: "BANKAEADXXX".to_string(),
+            currency: "AED".to_string(),
+            from_date: None,
+            to_date: None,
+            opening_balance: Some(dec!(100.00)),
+            closing_balance: Some(dec!(150.00)),
+            total_credits: dec!(75.00),
+            total_debits: dec!(25.00),
+            balance_difference: Some(dec!(0.00)),
+            entry_count: 3,
+            matched_entries: 0,
+            break_count: 0,
+            created_at: Utc::now(),
+        };
+        assert!(statement.is_balanced());
+
+        statement.record(EntryMatchStatus::Matched);
+        statement.record(EntryMatchStatus::Unmatched);
+        statement.record(EntryMatchStatus::NotBooked);
+        assert_eq!((statement.matched_entries, statement.break_count), (1, 1));
+
+        statement.balance_difference = None;
+        assert!(!statement.is_balanced());
+    }
+}
@@ -0,0 +1,377 @@
+// Payment Search Model - filters, keyset cursor and CSV rows for GET /payments
+// Query parameters are validated into a PaymentFilter shared by the listing, the CSV export and
+// the per-status counts. Pages are newest first; the cursor is the (created_at, deltran_tx_id) of
+// the last payment of a page. Statuses are stored as the variant name ("PendingFunding") and
+// exposed as in the JSON API ("PENDING_FUNDING").
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Duration, NaiveDate, SecondsFormat, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::canonical::PaymentStatus;
+
+/// Rows per GET /payments/export response - continue with the returned cursor
+pub const EXPORT_MAX_ROWS: i64 = 10_000;
+
+/// Every payment status, in lifecycle order (dashboards list counts in this order)
+pub const PAYMENT_STATUSES: [PaymentStatus; 18] = [
+    PaymentStatus::Received,
+    PaymentStatus::Validated,
+    PaymentStatus::Accepted,
+    PaymentStatus::Pending,
+    PaymentStatus::Rejected,
+    PaymentStatus::PendingFunding,
+    PaymentStatus::Funded,
+    PaymentStatus::ReadyForClearing,
+    PaymentStatus::Clearing,
+    PaymentStatus::Netted,
+    PaymentStatus::ReadyForSettlement,
+    PaymentStatus::Settling,
+    PaymentStatus::Executed,
+    PaymentStatus::Reconciled,
+    PaymentStatus::Completed,
+    PaymentStatus::Failed,
+    PaymentStatus::Cancelled,
+    PaymentStatus::Returned,
+];
+
+/// Status as stored in payments.status
+pub fn status_to_db(status: &PaymentStatus) -> String {
+    status.to_string()
+}
+
+pub fn status_from_db(value: &str) -> Option<PaymentStatus> {
+    PAYMENT_STATUSES.iter().find(|status| status_to_db(status) == value).cloned()
+}
+
+/// Status as serialized by the JSON API ("PendingFunding" -> "PENDING_FUNDING")
+pub fn status_label(status: &PaymentStatus) -> String {
+    let mut label = String::new();
+    for (i, c) in status_to_db(status).chars().enumerate() {
+        if c.is_ascii_uppercase() && i > 0 {
+            label.push('_');
+        }
+        label.push(c.to_ascii_uppercase());
+    }
+    label
+}
+
+/// "PENDING_FUNDING", "pending_funding" or "PendingFunding"
+pub fn parse_status(value: &str) -> Option<PaymentStatus> {
+    let value = value.trim();
+    PAYMENT_STATUSES.iter()
+        .find(|status| status_label(status).eq_ignore_ascii_case(value) || status_to_db(status).eq_ignore_ascii_case(value))
+        .cloned()
+}
+
+/// Query parameters of GET /payments, /payments/export and /payments/stats
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PaymentSearchQuery {
+    pub status: Option<String>,         // comma-separated, e.g. PENDING_FUNDING,FUNDED
+    pub corridor: Option<String>,       // debtor agent country -> creditor agent country, e.g. AE_IN
+    pub currency: Option<String>,
+    pub debtor_bic: Option<String>,     // debtor agent, matched on the institution (BIC8)
+    pub creditor_bic: Option<String>,
+    pub min_amount: Option<String>,     // instructed amount, inclusive
+    pub max_amount: Option<String>,
+    pub from: Option<String>,           // created_at >= (RFC 3339 or YYYY-MM-DD)
+    pub to: Option<String>,             // created_at < (RFC 3339), or up to the end of YYYY-MM-DD
+    pub uetr_prefix: Option<String>,
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// Validated payment filter
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PaymentFilter {
+    pub statuses: Vec<String>,          // stored status names, empty = all
+    pub corridor: Option<String>,
+    pub currency: Option<String>,
+    pub debtor_bic: Option<String>,
+    pub creditor_bic: Option<String>,
+    pub participant_bic: Option<String>, // set for non-operators: debtor or creditor agent
+    pub min_amount: Option<Decimal>,
+    pub max_amount: Option<Decimal>,
+    pub created_from: Option<DateTime<Utc>>,
+    pub created_to: Option<DateTime<Utc>>,
+    pub uetr_prefix: Option<String>,
+}
+
+impl TryFrom<&PaymentSearchQuery> for PaymentFilter {
+    type Error = String;
+
+    fn try_from(query: &PaymentSearchQuery) -> Result<Self, Self::Error> {
+        let statuses = match non_empty(&query.status) {
+            Some(statuses) => statuses.split(',')
+                .map(|status| parse_status(status).map(|s| status_to_db(&s)).ok_or_else(|| format!("Unknown payment status: {}", status.trim())))
+                .collect::<Result<Vec<_>, _>>()?,
+            None => Vec::new(),
+        };
+
+        let corridor = non_empty(&query.corridor)
+            .map(|corridor| {
+                let corridor = corridor.trim().to_ascii_uppercase().replace("_TO_", "_").replace('-', "_");
+                let valid = corridor.len() == 5 && corridor.as_bytes()[2] == b'_'
+                    && corridor.chars().filter(|c| *c != '_').all(|c| c.is_ascii_uppercase());
+                if valid { Ok(corridor) } else { Err(format!("Invalid corridor: {} (expected e.g. AE_IN)", query.corridor.as_deref().unwrap_or_default())) }
+            })
+            .transpose()?;
+
+        let currency = non_empty(&query.currency)
+            .map(|currency| {
+                let currency = currency.trim().to_ascii_uppercase();
+                if currency.len() == 3 && currency.chars().all(|c| c.is_ascii_uppercase()) { Ok(currency) } else { Err(format!("Invalid currency: {}", currency)) }
+            })
+            .transpose()?;
+
+        let min_amount = non_empty(&query.min_amount).map(|a| parse_amount("min_amount", a)).transpose()?;
+        let max_amount = non_empty(&query.max_amount).map(|a| parse_amount("max_amount", a)).transpose()?;
+        if let (Some(min), Some(max)) = (min_amount, max_amount) {
+            if min > max {
+                return Err(format!("min_amount {} exceeds max_amount {}", min, max));
+            }
+        }
+
+        let created_from = non_empty(&query.from).map(|d| parse_date("from", d, false)).transpose()?;
+        let created_to = non_empty(&query.to).map(|d| parse_date("to", d, true)).transpose()?;
+
+        let uetr_prefix = non_empty(&query.uetr_prefix)
+            .map(|prefix| {
+                let prefix = prefix.trim().to_ascii_lowercase();
+                if prefix.len() <= 36 && prefix.chars().all(|c| c.is_ascii_hexdigit() || c == '-') { Ok(prefix) } else { Err(format!("Invalid UETR prefix: {}", prefix)) }
+            })
+            .transpose()?;
+
+        Ok(Self {
+            statuses,
+            corridor,
+            currency,
+            debtor_bic: non_empty(&query.debtor_bic).map(|bic| bic.trim().to_ascii_uppercase()),
+            creditor_bic: non_empty(&query.creditor_bic).map(|bic| bic.trim().to_ascii_uppercase()),
+            participant_bic: None,
+            min_amount,
+            max_amount,
+            created_from,
+            created_to,
+            uetr_prefix,
+        })
+    }
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().filter(|v| !v.trim().is_empty())
+}
+
+fn parse_amount(name: &str, value: &str) -> Result<Decimal, String> {
+    value.trim().parse::<Decimal>().map_err(|_| format!("Invalid {}: {}", name, value))
+}
+
+/// RFC 3339 timestamp, or a date: its start for `from`, the start of the next day for `to`
+fn parse_date(name: &str, value: &str, end_of_day: bool) -> Result<DateTime<Utc>, String> {
+    let value = value.trim();
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Ok(timestamp.with_timezone(&Utc));
+    }
+
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| format!("Invalid {}: {}", name, value))?;
+    let start = date.and_hms_opt(0, 0, 0).expect("midnight is a valid time").and_utc();
+    Ok(if end_of_day { start + Duration::days(1) } else { start })
+}
+
+/// Keyset position: the last payment of the previous page (pages are newest first)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PaymentCursor {
+    pub created_at: DateTime<Utc>,
+    pub deltran_tx_id: Uuid,
+}
+
+impl PaymentCursor {
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(format!(
+            "{}|{}", self.created_at.to_rfc3339_opts(SecondsFormat::Micros, true), self.deltran_tx_id
+        ))
+    }
+
+    pub fn decode(value: &str) -> Option<Self> {
+        let decoded = String::from_utf8(URL_SAFE_NO_PAD.decode(value.trim()).ok()?).ok()?;
+        let (created_at, tx_id) = decoded.split_once('|')?;
+        Some(Self {
+            created_at: DateTime::parse_from_rfc3339(created_at).ok()?.with_timezone(&Utc),
+            deltran_tx_id: tx_id.parse().ok()?,
+        })
+    }
+}
+
+/// One payment in a search result / CSV export
+#[derive(Debug, Clone, Serialize)]
+pub struct PaymentSummary {
+    pub deltran_tx_id: Uuid,
+    pub uetr: Option<Uuid>,
+    pub end_to_end_id: String,
+    pub instruction_id: String,
+    pub message_id: Option<String>,
+    pub status: PaymentStatus,
+    pub instructed_amount: Decimal,
+    pub settlement_amount: Decimal,
+    pub funded_amount: Decimal,
+    pub currency: String,
+    pub corridor: Option<String>,
+    pub debtor_name: Option<String>,
+    pub creditor_name: Option<String>,
+    pub debtor_agent_bic: Option<String>,
+    pub creditor_agent_bic: Option<String>,
+    pub source_message_type: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PaymentSummary {
+    pub const CSV_HEADER: &'static str = "deltran_tx_id,uetr,end_to_end_id,instruction_id,message_id,status,\
+        instructed_amount,settlement_amount,funded_amount,currency,corridor,debtor_name,creditor_name,\
+        debtor_agent_bic,creditor_agent_bic,source_message_type,created_at,updated_at";
+
+    pub fn cursor(&self) -> PaymentCursor {
+        PaymentCursor { created_at: self.created_at, deltran_tx_id: self.deltran_tx_id }
+    }
+
+    pub fn to_csv_row(&self) -> String {
+        let optional = |value: &Option<String>| value.as_deref().map(csv_field).unwrap_or_default();
+        [
+            self.deltran_tx_id.to_string(),
+            self.uetr.map(|uetr| uetr.to_string()).unwrap_or_default(),
+            csv_field(&self.end_to_end_id),
+            csv_field(&self.instruction_id),
+            optional(&self.message_id),
+            status_label(&self.status),
+            self.instructed_amount.to_string(),
+            self.settlement_amount.to_string(),
+            self.funded_amount.to_string(),
+            self.currency.clone(),
+            optional(&self.corridor),
+            optional(&self.debtor_name),
+            optional(&self.creditor_name),
+            optional(&self.debtor_agent_bic),
+            optional(&self.creditor_agent_bic),
+            csv_field(&self.source_message_type),
+            self.created_at.to_rfc3339_opts(SecondsFormat::Micros, true),
+            self.updated_at.to_rfc3339_opts(SecondsFormat::Micros, true),
+        ].join(",")
+    }
+}
+
+/// RFC 4180: quote fields with separators, quotes or line breaks
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// One page of GET /payments
+#[derive(Debug, Clone, Serialize)]
+pub struct PaymentPage {
+    pub payments: Vec<PaymentSummary>,
+    pub next_cursor: Option<String>,    // absent on the last page
+}
+
+/// GET /payments/stats - payments per status for the same filters
+#[derive(Debug, Clone, Serialize)]
+pub struct PaymentStats {
+    pub total: i64,
+    pub by_status: Vec<StatusCount>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusCount {
+    pub status: PaymentStatus,
+    pub count: i64,
+}
+
+impl PaymentStats {
+    /// Counts in lifecycle order, statuses without payments included as 0
+    pub fn from_counts(counts: &[(String, i64)]) -> Self {
+        let by_status: Vec<StatusCount> = PAYMENT_STATUSES.iter()
+            .map(|status| StatusCount {
+                status: status.clone(),
+                count: counts.iter().filter(|(s, _)| *s == status_to_db(status)).map(|(_, count)| count).sum(),
+            })
+            .collect();
+
+        Self { total: counts.iter().map(|(_, count)| count).sum(), by_status }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_status_names() {
+        assert_eq!(status_label(&PaymentStatus::ReadyForClearing), "READY_FOR_CLEARING");
+        assert_eq!(status_to_db(&PaymentStatus::PendingFunding), "PendingFunding");
+        assert_eq!(PaymentStatus::ReadyForSettlement.to_string(), "ReadyForSettlement");
+        assert!(matches!(parse_status("pending_funding"), Some(PaymentStatus::PendingFunding)));
+        assert!(matches!(parse_status("Funded"), Some(PaymentStatus::Funded)));
+        assert!(matches!(status_from_db("Netted"), Some(PaymentStatus::Netted)));
+        assert!(parse_status("LOST").is_none());
+    }
+
+    #[test]
+    fn test_filter_from_query() {
+        let query = PaymentSearchQuery {
+            status: Some("FUNDED, pending_funding".to_string()),
+            corridor: Some("ae-in".to_string()),
+            currency: Some("aed".to_string()),
+            min_amount: Some("100.50".to_string()),
+            max_amount: Some("1000".to_string()),
+            from: Some("2025-01-30".to_string()),
+            to: Some("2025-01-30".to_string()),
+            uetr_prefix: Some("EB6305C9".to_string()),
+            ..Default::default()
+        };
+        let filter = PaymentFilter::try_from(&query).unwrap();
+
+        assert_eq!(filter.statuses, vec!["Funded".to_string(), "PendingFunding".to_string()]);
+        assert_eq!(filter.corridor.as_deref(), Some("AE_IN"));
+        assert_eq!(filter.currency.as_deref(), Some("AED"));
+        assert_eq!(filter.min_amount, Some(dec!(100.50)));
+        assert_eq!(filter.created_to.unwrap() - filter.created_from.unwrap(), Duration::days(1));
+        assert_eq!(filter.uetr_prefix.as_deref(), Some("eb6305c9"));
+
+        for invalid in [
+            PaymentSearchQuery { status: Some("LOST".to_string()), ..Default::default() },
+            PaymentSearchQuery { corridor: Some("UAE_INDIA".to_string()), ..Default::default() },
+            PaymentSearchQuery { min_amount: Some("10".to_string()), max_amount: Some("5".to_string()), ..Default::default() },
+            PaymentSearchQuery { uetr_prefix: Some("eb63%".to_string()), ..Default::default() },
+            PaymentSearchQuery { from: Some("yesterday".to_string()), ..Default::default() },
+        ] {
+            assert!(PaymentFilter::try_from(&invalid).is_err());
+        }
+    }
+
+    #[test]
+    fn test_cursor_roundtrip() {
+        let cursor = PaymentCursor {
+            created_at: DateTime::parse_from_rfc3339("2025-01-30T10:15:30.123456Z").unwrap().with_timezone(&Utc),
+            deltran_tx_id: Uuid::new_v4(),
+        };
+        assert_eq!(PaymentCursor::decode(&cursor.encode()), Some(cursor));
+        assert_eq!(PaymentCursor::decode("not-a-cursor"), None);
+    }
+
+    #[test]
+    fn test_csv_field_and_stats() {
+        assert_eq!(csv_field("ACME, Inc \"UK\""), "\"ACME, Inc \"\"UK\"\"\"");
+        assert_eq!(csv_field("plain"), "plain");
+
+        let stats = PaymentStats::from_counts(&[("Funded".to_string(), 3), ("Rejected".to_string(), 1)]);
+        assert_eq!(stats.total, 4);
+        assert_eq!(stats.by_status.len(), PAYMENT_STATUSES.len());
+        assert_eq!(stats.by_status[6].count, 3);
+    }
+}
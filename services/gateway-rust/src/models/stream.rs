@@ -0,0 +1,126 @@
+// Payment Stream Model - tracking events pushed over GET /ws/payments
+// Events carry the gateway-wide sequence of payment_events; a client that reconnects with
+// ?from_sequence=<last seen> first receives what it missed, then live events.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::participant::same_institution;
+use super::tracking::PaymentEvent;
+
+/// UETRs a single connection may follow
+pub const STREAM_MAX_UETRS: usize = 100;
+
+/// Tracking event with its stream position and the payment's agents
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamEvent {
+    pub sequence: i64,
+    pub end_to_end_id: String,
+    pub debtor_agent_bic: Option<String>,
+    pub creditor_agent_bic: Option<String>,
+    #[serde(flatten)]
+    pub event: PaymentEvent,
+}
+
+/// Events a connection receives: payments of the participant (all for operators), optionally by UETR
+#[derive(Debug, Clone, Default)]
+pub struct StreamFilter {
+    pub participant_bic: Option<String>,    // None = operator
+    pub uetrs: Vec<Uuid>,                   // empty = all payments in scope
+}
+
+impl StreamFilter {
+    /// `uetrs` as given in ?uetr= (comma-separated)
+    pub fn new(participant_bic: Option<String>, uetrs: Option<&str>) -> Result<Self, String> {
+        let uetrs = uetrs.unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|uetr| !uetr.is_empty())
+            .map(|uetr| Uuid::parse_str(uetr).map_err(|_| format!("Invalid UETR: {}", uetr)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if uetrs.len() > STREAM_MAX_UETRS {
+            return Err(format!("At most {} UETRs per stream ({} given)", STREAM_MAX_UETRS, uetrs.len()));
+        }
+
+        Ok(Self { participant_bic, uetrs })
+    }
+
+    pub fn matches(&self, event: &StreamEvent) -> bool {
+        let in_scope = match &self.participant_bic {
+            None => true,
+            Some(bic) => [&event.debtor_agent_bic, &event.creditor_agent_bic]
+                .into_iter()
+                .flatten()
+                .any(|agent| same_institution(bic, agent)),
+        };
+
+        in_scope && (self.uetrs.is_empty() || event.event.uetr.is_some_and(|uetr| self.uetrs.contains(&uetr)))
+    }
+}
+
+/// Frame sent to the client (JSON text message)
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StreamMessage<'a> {
+    Event(&'a StreamEvent),
+    CaughtUp { sequence: i64 },     // missed events replayed - live events follow
+    Error { message: String },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::tracking::EventSource;
+    use chrono::Utc;
+
+    fn event(uetr: Uuid) -> StreamEvent {
+        StreamEvent {
+            sequence: 42,
+            end_to_end_id: "E2E-STREAM".to_string(),
+            debtor_agent_bic: Some("BANKAEADXXX".to_string()),
+            creditor_agent_bic: Some("OTHRINBB".to_string()),
+            event: PaymentEvent {
+                event_id: Uuid::new_v4(),
+                deltran_tx_id: Uuid::new_v4(),
+                uetr: Some(uetr),
+                source: EventSource::Settlement,
+                event_type: "settlement.completed".to_string(),
+                status: Some("Completed".to_string()),
+                iso_status: "ACCC".to_string(),
+                reason_code: None,
+                agent_bic: None,
+                details: serde_json::Value::Null,
+                occurred_at: Utc::now(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_filter_scope() {
+        let uetr = Uuid::new_v4();
+        let event = event(uetr);
+
+        assert!(StreamFilter::default().matches(&event));
+        assert!(StreamFilter::new(Some("OTHRINBB123".to_string()), None).unwrap().matches(&event));
+        assert!(!StreamFilter::new(Some("THRDGB2L".to_string()), None).unwrap().matches(&event));
+
+        let followed = StreamFilter::new(Some("BANKAEAD".to_string()), Some(&format!("{}, ", uetr))).unwrap();
+        assert!(followed.matches(&event));
+        let other = StreamFilter::new(None, Some(&Uuid::new_v4().to_string())).unwrap();
+        assert!(!other.matches(&event));
+
+        assert!(StreamFilter::new(None, Some("not-a-uetr")).is_err());
+    }
+
+    #[test]
+    fn test_message_frames() {
+        let frame = serde_json::to_value(StreamMessage::Event(&event(Uuid::new_v4()))).unwrap();
+        assert_eq!(frame["type"], "event");
+        assert_eq!(frame["sequence"], 42);
+        assert_eq!(frame["event_type"], "settlement.completed");
+
+        let caught_up = serde_json::to_value(StreamMessage::CaughtUp { sequence: 42 }).unwrap();
+        assert_eq!(caught_up, serde_json::json!({ "type": "caught_up", "sequence": 42 }));
+    }
+}
@@ -0,0 +1,104 @@
+// Payment Tracking Model - per-payment event timeline
+// Every engine (and the gateway itself) contributes events, keyed by deltran_tx_id / UETR
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Component that produced a tracking event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventSource {
+    Gateway,
+    Compliance,
+    Obligation,
+    Risk,
+    Clearing,
+    Settlement,
+    Token,
+    Partner,    // trck.001 update received from another agent
+}
+
+impl EventSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventSource::Gateway => "gateway",
+            EventSource::Compliance => "compliance",
+            EventSource::Obligation => "obligation",
+            EventSource::Risk => "risk",
+            EventSource::Clearing => "clearing",
+            EventSource::Settlement => "settlement",
+            EventSource::Token => "token",
+            EventSource::Partner => "partner",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "gateway" => Some(EventSource::Gateway),
+            "compliance" => Some(EventSource::Compliance),
+            "obligation" => Some(EventSource::Obligation),
+            "risk" => Some(EventSource::Risk),
+            "clearing" => Some(EventSource::Clearing),
+            "settlement" => Some(EventSource::Settlement),
+            "token" => Some(EventSource::Token),
+            "partner" => Some(EventSource::Partner),
+            _ => None,
+        }
+    }
+
+    /// Party name reported in trck TrckrRcrd/PtyOrAgtId/Nm
+    pub fn party_name(&self) -> &'static str {
+        match self {
+            EventSource::Gateway => "DelTran Gateway",
+            EventSource::Compliance => "DelTran Compliance Engine",
+            EventSource::Obligation => "DelTran Obligation Engine",
+            EventSource::Risk => "DelTran Risk Engine",
+            EventSource::Clearing => "DelTran Clearing Engine",
+            EventSource::Settlement => "DelTran Settlement Engine",
+            EventSource::Token => "DelTran Token Engine",
+            EventSource::Partner => "Partner Agent",
+        }
+    }
+}
+
+/// One entry of a payment's timeline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaymentEvent {
+    pub event_id: Uuid,
+    pub deltran_tx_id: Uuid,
+    pub uetr: Option<Uuid>,
+    pub source: EventSource,
+    pub event_type: String,           // e.g. "compliance.rejected", "settlement.completed"
+    pub status: Option<String>,       // DelTran PaymentStatus implied by the event, if any
+    pub iso_status: String,           // ExternalPaymentTransactionStatus1Code at the time of the event
+    pub reason_code: Option<String>,
+    pub agent_bic: Option<String>,    // agent that reported the event (partner updates)
+    pub details: serde_json::Value,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// Timeline of a payment as returned by GET /payment/uetr/:uetr/timeline
+#[derive(Debug, Clone, Serialize)]
+pub struct PaymentTimeline {
+    pub deltran_tx_id: Uuid,
+    pub uetr: Uuid,
+    pub end_to_end_id: String,
+    pub current_status: String,
+    pub iso_status: String,
+    pub events: Vec<PaymentEvent>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_source_roundtrip() {
+        for source in [EventSource::Gateway, EventSource::Clearing, EventSource::Partner] {
+            assert_eq!(EventSource::parse(source.as_str()), Some(source));
+        }
+        assert_eq!(serde_json::to_string(&EventSource::Settlement).unwrap(), "\"settlement\"");
+        assert_eq!(EventSource::parse("unknown"), None);
+    }
+}
@@ -0,0 +1,210 @@
+// Webhook Model - participant endpoints and the deliveries made to them
+// Participants register HTTPS endpoints for payment notifications (deltran.notification.*).
+// Every notification becomes one delivery per subscribed endpoint of the payment's debtor and
+// creditor agent; failed deliveries are retried with backoff and dead-lettered after the last attempt.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::canonical::{CanonicalPayment, PaymentStatus, StatusReason};
+
+/// Registered webhook endpoint of a participant
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookEndpoint {
+    pub endpoint_id: Uuid,
+    pub participant_bic: String,
+    pub url: String,
+    pub event_types: Vec<String>,               // e.g. status_update, customer_status - empty = all
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl WebhookEndpoint {
+    pub fn subscribes(&self, event_type: &str) -> bool {
+        self.active && (self.event_types.is_empty() || self.event_types.iter().any(|t| t == event_type))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryState {
+    Pending,    // waiting for its (next) attempt
+    Delivered,  // endpoint answered 2xx
+    Dead,       // attempts exhausted - replay via POST /webhooks/deliveries/:id/replay
+}
+
+impl DeliveryState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeliveryState::Pending => "pending",
+            DeliveryState::Delivered => "delivered",
+            DeliveryState::Dead => "dead",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(DeliveryState::Pending),
+            "delivered" => Some(DeliveryState::Delivered),
+            "dead" => Some(DeliveryState::Dead),
+            _ => None,
+        }
+    }
+}
+
+/// One notification for one endpoint, with its delivery history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    pub delivery_id: Uuid,
+    pub endpoint_id: Uuid,
+    pub participant_bic: String,
+    pub url: String,
+    pub event_type: String,
+    pub deltran_tx_id: Option<Uuid>,
+    pub payload: serde_json::Value,             // WebhookEvent, delivered as-is
+    pub state: DeliveryState,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_status_code: Option<i32>,
+    pub last_error: Option<String>,
+    pub replay_of: Option<Uuid>,                // delivery this one replays
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+impl WebhookDelivery {
+    /// New pending delivery of `event` to `endpoint`, due immediately
+    pub fn new(endpoint: &WebhookEndpoint, event: &WebhookEvent) -> serde_json::Result<Self> {
+        let now = Utc::now();
+        Ok(Self {
+            delivery_id: Uuid::new_v4(),
+            endpoint_id: endpoint.endpoint_id,
+            participant_bic: endpoint.participant_bic.clone(),
+            url: endpoint.url.clone(),
+            event_type: event.event_type.clone(),
+            deltran_tx_id: Some(event.deltran_tx_id),
+            payload: serde_json::to_value(event)?,
+            state: DeliveryState::Pending,
+            attempts: 0,
+            next_attempt_at: now,
+            last_status_code: None,
+            last_error: None,
+            replay_of: None,
+            created_at: now,
+            delivered_at: None,
+        })
+    }
+
+    /// Fresh delivery of the same payload to the same endpoint
+    pub fn replay(&self) -> Self {
+        let now = Utc::now();
+        Self {
+            delivery_id: Uuid::new_v4(),
+            state: DeliveryState::Pending,
+            attempts: 0,
+            next_attempt_at: now,
+            last_status_code: None,
+            last_error: None,
+            replay_of: Some(self.delivery_id),
+            created_at: now,
+            delivered_at: None,
+            ..self.clone()
+        }
+    }
+}
+
+/// Body POSTed to the endpoint (signed: detached JWS in X-JWS-Signature, keys at /signing-keys)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookEvent {
+    pub event_id: Uuid,
+    pub event_type: String,
+    pub occurred_at: DateTime<Utc>,
+    pub deltran_tx_id: Uuid,
+    pub uetr: Option<Uuid>,
+    pub end_to_end_id: String,
+    pub status: PaymentStatus,
+    pub status_reason: Option<StatusReason>,
+    pub instructed_amount: Decimal,
+    pub settlement_amount: Decimal,
+    pub currency: String,
+    pub debtor_agent_bic: Option<String>,
+    pub creditor_agent_bic: Option<String>,
+}
+
+impl WebhookEvent {
+    pub fn from_payment(event_type: &str, payment: &CanonicalPayment) -> Self {
+        Self {
+            event_id: Uuid::new_v4(),
+            event_type: event_type.to_string(),
+            occurred_at: payment.updated_at,
+            deltran_tx_id: payment.deltran_tx_id,
+            uetr: payment.uetr,
+            end_to_end_id: payment.end_to_end_id.clone(),
+            status: payment.status.clone(),
+            status_reason: payment.status_reason.clone(),
+            instructed_amount: payment.instructed_amount,
+            settlement_amount: payment.settlement_amount,
+            currency: payment.currency.code().to_string(),
+            debtor_agent_bic: payment.debtor_agent.bic.clone(),
+            creditor_agent_bic: payment.creditor_agent.bic.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoint(event_types: &[&str]) -> WebhookEndpoint {
+        WebhookEndpoint {
+            endpoint_id: Uuid::new_v4(),
+            participant_bic: "BANKAEADXXX".to_string(),
+            url: "https://bank.example/hooks".to_string(),
+            event_types: event_types.iter().map(|t| t.to_string()).collect(),
+            active: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_subscribes() {
+        assert!(endpoint(&[]).subscribes("status_update"));
+        assert!(endpoint(&["status_update"]).subscribes("status_update"));
+        assert!(!endpoint(&["customer_status"]).subscribes("status_update"));
+
+        let mut inactive = endpoint(&[]);
+        inactive.active = false;
+        assert!(!inactive.subscribes("status_update"));
+    }
+
+    #[test]
+    fn test_replay() {
+        let delivery = WebhookDelivery {
+            delivery_id: Uuid::new_v4(),
+            endpoint_id: Uuid::new_v4(),
+            participant_bic: "BANKAEADXXX".to_string(),
+            url: "https://bank.example/hooks".to_string(),
+            event_type: "status_update".to_string(),
+            deltran_tx_id: Some(Uuid::new_v4()),
+            payload: serde_json::json!({ "status": "FUNDED" }),
+            state: DeliveryState::Dead,
+            attempts: 8,
+            next_attempt_at: Utc::now(),
+            last_status_code: Some(503),
+            last_error: Some("HTTP 503".to_string()),
+            replay_of: None,
+            created_at: Utc::now(),
+            delivered_at: None,
+        };
+
+        let replay = delivery.replay();
+        assert_eq!(replay.replay_of, Some(delivery.delivery_id));
+        assert_eq!((replay.state, replay.attempts), (DeliveryState::Pending, 0));
+        assert_eq!(replay.payload, delivery.payload);
+        assert_eq!(DeliveryState::parse(DeliveryState::Dead.as_str()), Some(DeliveryState::Dead));
+    }
+}
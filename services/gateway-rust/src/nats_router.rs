@@ -2,11 +2,24 @@
 // This is the heart of DelTran's event-driven architecture
 
 use async_nats::Client as NatsClient;
+use deltran_schema::envelope::encode;
 use serde_json;
-use tracing::{info, error};
+use tracing::info;
 use anyhow::Result;
 
 use crate::models::canonical::CanonicalPayment;
+use crate::models::outbox::OutboxMessage;
+use crate::models::stream::StreamEvent;
+use crate::iso20022::{PaymentReturn, CancellationRequest};
+use crate::signing::SIGNATURE_HEADER;
+
+pub const COMPLIANCE_CHECK_SUBJECT: &str = "deltran.compliance.check";
+pub const SETTLEMENT_EXECUTE_SUBJECT: &str = "deltran.settlement.execute";
+pub const PAYMENT_STREAM_SUBJECT: &str = "deltran.gateway.payment_events";
+pub const TOKEN_MINT_SUBJECT: &str = "deltran.token.mint";
+pub const PAYMENT_RETURNED_SUBJECT: &str = "deltran.payment.returned";
+pub const REPORTING_PAYMENT_SUBJECT: &str = "deltran.reporting.payment";
+pub const CANCELLATION_SUBJECTS: [&str; 3] = ["deltran.obligation.cancel", "deltran.clearing.cancel", "deltran.settlement.cancel"];
 
 pub struct NatsRouter {
     client: NatsClient,
@@ -18,21 +31,17 @@ impl NatsRouter {
     }
 
     /// Route to Compliance Engine (AML/KYC/sanctions check) - FIRST IN CHAIN!
-    pub async fn route_to_compliance_engine(&self, payment: &CanonicalPayment) -> Result<()> {
-        let subject = "deltran.compliance.check";
-        let payload = serde_json::to_vec(&payment)?;
-
-        info!("🔒 Routing to Compliance Engine (AML/KYC/Sanctions): {} -> {}", payment.deltran_tx_id, subject);
-
-        self.client.publish(subject, payload.into()).await?;
+    /// Stored with the payment row and published by the outbox relay, as a payment.canonical event.
+    pub fn compliance_message(payment: &CanonicalPayment) -> Result<OutboxMessage> {
+        info!("🔒 Routing to Compliance Engine (AML/KYC/Sanctions): {} -> {}", payment.deltran_tx_id, COMPLIANCE_CHECK_SUBJECT);
 
-        Ok(())
+        Ok(OutboxMessage::event(Some(payment.deltran_tx_id), COMPLIANCE_CHECK_SUBJECT, payment)?)
     }
 
     /// Route to Obligation Engine (creates/matches obligations)
     pub async fn route_to_obligation_engine(&self, payment: &CanonicalPayment) -> Result<()> {
         let subject = "deltran.obligation.create";
-        let payload = serde_json::to_vec(&payment)?;
+        let payload = encode(payment)?;
 
         info!("Routing to Obligation Engine: {} -> {}", payment.deltran_tx_id, subject);
 
@@ -53,16 +62,12 @@ impl NatsRouter {
         Ok(())
     }
 
-    /// Route to Token Engine (mint tokens upon funding)
-    pub async fn route_to_token_engine(&self, payment: &CanonicalPayment) -> Result<()> {
-        let subject = "deltran.token.mint";
-        let payload = serde_json::to_vec(&payment)?;
-
-        info!("Routing to Token Engine: {} -> {}", payment.deltran_tx_id, subject);
+    /// Route to Token Engine (mint tokens upon funding), published by the outbox relay
+    /// Tokens are minted for `settlement_amount` - the funded amount, never more than instructed
+    pub fn token_mint_message(payment: &CanonicalPayment) -> Result<OutboxMessage> {
+        info!("Routing to Token Engine: {} -> {}", payment.deltran_tx_id, TOKEN_MINT_SUBJECT);
 
-        self.client.publish(subject, payload.into()).await?;
-
-        Ok(())
+        Ok(OutboxMessage::event(Some(payment.deltran_tx_id), TOKEN_MINT_SUBJECT, payment)?)
     }
 
     /// Route to Clearing Engine (multilateral netting)
@@ -77,38 +82,115 @@ impl NatsRouter {
         Ok(())
     }
 
-    /// Route to Settlement Engine (execute settlement)
-    pub async fn route_to_settlement_engine(&self, payment: &CanonicalPayment) -> Result<()> {
-        let subject = "deltran.settlement.execute";
-        let payload = serde_json::to_vec(&payment)?;
-
-        info!("Routing to Settlement Engine: {} -> {}", payment.deltran_tx_id, subject);
+    /// Route to Settlement Engine (execute settlement), published by the outbox relay
+    pub fn settlement_message(payment: &CanonicalPayment) -> Result<OutboxMessage> {
+        info!("Routing to Settlement Engine: {} -> {}", payment.deltran_tx_id, SETTLEMENT_EXECUTE_SUBJECT);
 
-        self.client.publish(subject, payload.into()).await?;
-
-        Ok(())
+        Ok(OutboxMessage::json(Some(payment.deltran_tx_id), SETTLEMENT_EXECUTE_SUBJECT, payment)?)
     }
 
-    /// Route to Notification Engine (send updates to banks)
-    pub async fn route_to_notification_engine(&self, payment: &CanonicalPayment, notification_type: &str) -> Result<()> {
+    /// Route to Notification Engine (send updates to banks), published by the outbox relay
+    pub fn notification_message(payment: &CanonicalPayment, notification_type: &str) -> Result<OutboxMessage> {
         let subject = format!("deltran.notification.{}", notification_type);
-        let payload = serde_json::to_vec(&payment)?;
 
         info!("Routing to Notification Engine: {} -> {}", payment.deltran_tx_id, subject);
 
-        self.client.publish(subject, payload.into()).await?;
+        Ok(OutboxMessage::event(Some(payment.deltran_tx_id), &subject, payment)?)
+    }
+
+    /// Route to Reporting Engine (metrics/analytics), published by the outbox relay
+    pub fn reporting_message(payment: &CanonicalPayment) -> Result<OutboxMessage> {
+        info!("Routing to Reporting Engine: {} -> {}", payment.deltran_tx_id, REPORTING_PAYMENT_SUBJECT);
+
+        Ok(OutboxMessage::json(Some(payment.deltran_tx_id), REPORTING_PAYMENT_SUBJECT, payment)?)
+    }
+
+    /// pacs.004 return, published by the outbox relay - Token Engine reverses the mint,
+    /// Obligation Engine cancels/reverses the obligation
+    pub fn return_message(payment: &CanonicalPayment, ret: &PaymentReturn) -> Result<OutboxMessage> {
+        let body = serde_json::json!({
+            "deltran_tx_id": payment.deltran_tx_id,
+            "obligation_id": payment.obligation_id,
+            "uetr": payment.uetr,
+            "end_to_end_id": payment.end_to_end_id,
+            "debtor_agent_bic": payment.debtor_agent.bic,
+            "creditor_agent_bic": payment.creditor_agent.bic,
+            "original_amount": payment.settlement_amount,
+            "return_id": ret.return_id,
+            "return_message_id": ret.return_message_id,
+            "returned_amount": ret.returned_amount,
+            "currency": ret.currency,
+            "reason_code": ret.reason_code,
+            "reason_description": ret.reason_description,
+            "timestamp": chrono::Utc::now(),
+        });
+
+        info!("↩️ Publishing payment return: {} -> {}", payment.deltran_tx_id, PAYMENT_RETURNED_SUBJECT);
+
+        Ok(OutboxMessage::json(Some(payment.deltran_tx_id), PAYMENT_RETURNED_SUBJECT, &body)?)
+    }
+
+    /// Propagate an accepted camt.056 cancellation, published by the outbox relay.
+    /// Obligation Engine cancels the obligation, Clearing Engine withdraws it from an open window,
+    /// Settlement Engine drops any queued instruction.
+    pub fn cancellation_messages(payment: &CanonicalPayment, request: &CancellationRequest) -> Result<Vec<OutboxMessage>> {
+        let command = serde_json::json!({
+            "deltran_tx_id": payment.deltran_tx_id,
+            "obligation_id": payment.obligation_id,
+            "uetr": payment.uetr,
+            "end_to_end_id": payment.end_to_end_id,
+            "debtor_agent_bic": payment.debtor_agent.bic,
+            "creditor_agent_bic": payment.creditor_agent.bic,
+            "amount": payment.settlement_amount,
+            "currency": payment.currency,
+            "cancellation_id": request.cancellation_id,
+            "assignment_id": request.assignment_id,
+            "reason_code": request.reason_code,
+            "withdraw_from_open_window": true,
+            "timestamp": chrono::Utc::now(),
+        });
+
+        let mut messages = Vec::with_capacity(CANCELLATION_SUBJECTS.len());
+        for subject in CANCELLATION_SUBJECTS {
+            info!("🛑 Publishing cancellation: {} -> {}", payment.deltran_tx_id, subject);
+            messages.push(OutboxMessage::json(Some(payment.deltran_tx_id), subject, &command)?);
+        }
+
+        Ok(messages)
+    }
+
+    /// Publish an outbound pain.002 / pacs.002 / camt.029 to the receiving bank's report subject,
+    /// with the gateway's detached JWS in the X-JWS-Signature header
+    pub async fn publish_status_report(&self, recipient_bic: &str, message_type: &str, xml: &str, signature: &str) -> Result<()> {
+        let subject = format!("deltran.reports.{}.{}", recipient_bic, message_type.replace('.', ""));
+
+        info!("Publishing {} status report -> {}", message_type, subject);
+
+        let mut headers = async_nats::HeaderMap::new();
+        headers.insert(SIGNATURE_HEADER, signature);
+        self.client.publish_with_headers(subject, headers, xml.as_bytes().to_vec().into()).await?;
 
         Ok(())
     }
 
-    /// Route to Reporting Engine (metrics/analytics)
-    pub async fn route_to_reporting_engine(&self, payment: &CanonicalPayment) -> Result<()> {
-        let subject = "deltran.reporting.payment";
-        let payload = serde_json::to_vec(&payment)?;
+    /// Publish a stored outbox message (delivery is confirmed by `flush`)
+    pub async fn publish_outbox_message(&self, message: &OutboxMessage) -> Result<()> {
+        self.client.publish(message.subject.clone(), message.payload.clone().into()).await?;
+
+        Ok(())
+    }
 
-        info!("Routing to Reporting Engine: {} -> {}", payment.deltran_tx_id, subject);
+    /// Fan a recorded tracking event out to the /ws/payments connections of every gateway instance
+    pub async fn publish_stream_event(&self, event: &StreamEvent) -> Result<()> {
+        let payload = serde_json::to_vec(event)?;
+        self.client.publish(PAYMENT_STREAM_SUBJECT, payload.into()).await?;
 
-        self.client.publish(subject, payload.into()).await?;
+        Ok(())
+    }
+
+    /// Wait until all buffered publishes have been written to the server
+    pub async fn flush(&self) -> Result<()> {
+        self.client.flush().await?;
 
         Ok(())
     }
@@ -128,8 +210,68 @@ impl NatsRouter {
 mod tests {
     use super::*;
 
+    use crate::models::canonical::{Currency, FinancialInstitution, Party};
+    use rust_decimal_macros::dec;
+
+    fn payment() -> CanonicalPayment {
+        let party = || Party {
+            name: "Party".to_string(),
+            postal_address: None,
+            identification: None,
+            country_code: "AE".to_string(),
+        };
+        let agent = |bic: &str| FinancialInstitution {
+            bic: Some(bic.to_string()),
+            name: "Bank".to_string(),
+            country_code: bic[4..6].to_string(),
+            clearing_system_member_id: None,
+        };
+        CanonicalPayment::new(
+            "E2E-ROUTE".to_string(), "INSTR-ROUTE".to_string(), "MSG-ROUTE".to_string(),
+            dec!(1000.00), Currency::AED,
+            party(), party(),
+            agent("BANKAEADXXX"), agent("OTHRINBBXXX"),
+        )
+    }
+
     #[tokio::test]
     async fn test_nats_routing() {
         // TODO: Add NATS routing tests
     }
+
+    #[test]
+    fn test_outbox_messages() {
+        let payment = payment();
+
+        let notification = NatsRouter::notification_message(&payment, "status_update").unwrap();
+        assert_eq!(notification.subject, "deltran.notification.status_update");
+        assert_eq!(notification.deltran_tx_id, Some(payment.deltran_tx_id));
+
+        let reporting = NatsRouter::reporting_message(&payment).unwrap();
+        assert_eq!(reporting.subject, REPORTING_PAYMENT_SUBJECT);
+        let reported: serde_json::Value = serde_json::from_slice(&reporting.payload).unwrap();
+        assert_eq!(reported["deltran_tx_id"], payment.deltran_tx_id.to_string());
+
+        let request = CancellationRequest {
+            assignment_id: "ASSGN-1".to_string(),
+            case_id: None,
+            assigner_bic: Some("BANKAEADXXX".to_string()),
+            cancellation_id: "CXL-1".to_string(),
+            original_message_id: None,
+            original_message_name_id: None,
+            original_instruction_id: None,
+            original_end_to_end_id: Some("E2E-ROUTE".to_string()),
+            original_transaction_id: None,
+            original_uetr: None,
+            reason_code: Some("DUPL".to_string()),
+            reason_description: None,
+        };
+        let cancellations = NatsRouter::cancellation_messages(&payment, &request).unwrap();
+        let subjects: Vec<_> = cancellations.iter().map(|m| m.subject.as_str()).collect();
+        assert_eq!(subjects, CANCELLATION_SUBJECTS);
+        assert!(cancellations.iter().all(|m| m.payload == cancellations[0].payload));
+        let command: serde_json::Value = serde_json::from_slice(&cancellations[0].payload).unwrap();
+        assert_eq!(command["cancellation_id"], "CXL-1");
+        assert_eq!(command["withdraw_from_open_window"], true);
+    }
 }
@@ -0,0 +1,163 @@
+// Outbox Relay - delivers NATS messages stored by the transactional outbox
+// Handlers commit a payment and the messages it triggers in one transaction; this task publishes
+// due messages, marks them delivered and retries failures with exponential backoff. Delivery is
+// at-least-once: a crash between publish and commit republishes the message, so consumers key on
+// deltran_tx_id.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use chrono::Utc;
+use sqlx::{PgConnection, PgPool};
+use tracing::{info, warn, error};
+
+use crate::db;
+use crate::metrics::METRICS;
+use crate::models::outbox::OutboxMessage;
+use crate::nats_router::NatsRouter;
+
+/// Default pause between polls when the outbox is drained (OUTBOX_POLL_INTERVAL_MS)
+pub const DEFAULT_OUTBOX_POLL_INTERVAL_MS: u64 = 500;
+
+/// Messages published per relay transaction
+const RELAY_BATCH_SIZE: i64 = 100;
+
+/// Upper bound for the retry backoff
+const MAX_RETRY_DELAY_SECS: i64 = 300;
+
+pub struct OutboxRelay {
+    db: PgPool,
+    router: Arc<NatsRouter>,
+    poll_interval: Duration,
+}
+
+impl OutboxRelay {
+    pub fn new(db: PgPool, router: Arc<NatsRouter>, poll_interval: Duration) -> Self {
+        Self { db, router, poll_interval }
+    }
+
+    pub fn poll_interval_from_env_value(value: &str) -> Duration {
+        let millis = value.trim().parse::<u64>().ok().filter(|ms| *ms > 0).unwrap_or_else(|| {
+            warn!("Invalid OUTBOX_POLL_INTERVAL_MS '{}', using {}ms", value, DEFAULT_OUTBOX_POLL_INTERVAL_MS);
+            DEFAULT_OUTBOX_POLL_INTERVAL_MS
+        });
+        Duration::from_millis(millis)
+    }
+
+    /// Delay before the next attempt after `attempts` failed publishes: 1s, 2s, 4s, ... capped at 5 min
+    pub fn retry_delay(attempts: i32) -> chrono::Duration {
+        let exponent = attempts.saturating_sub(1).clamp(0, 16) as u32;
+        chrono::Duration::seconds((1i64 << exponent).min(MAX_RETRY_DELAY_SECS))
+    }
+
+    /// Publish one batch of due messages. Returns the number delivered.
+    pub async fn relay_once(&self) -> Result<usize> {
+        let mut tx = self.db.begin().await?;
+        let messages = db::lock_due_outbox_messages(&mut tx, RELAY_BATCH_SIZE).await?;
+        if messages.is_empty() {
+            tx.commit().await?;
+            return Ok(0);
+        }
+
+        let publish_start = Instant::now();
+        let mut published = Vec::with_capacity(messages.len());
+        for message in &messages {
+            match self.router.publish_outbox_message(message).await {
+                Ok(()) => published.push(message),
+                Err(e) => self.fail(&mut tx, message, &e.to_string()).await?,
+            }
+        }
+
+        // Only a successful flush confirms the messages reached the server
+        if let Err(e) = self.router.flush().await {
+            for message in published {
+                self.fail(&mut tx, message, &format!("flush failed: {}", e)).await?;
+            }
+            tx.commit().await?;
+            return Ok(0);
+        }
+        METRICS.nats_publish_duration_seconds.observe(publish_start.elapsed().as_secs_f64());
+
+        let delivered_at = Utc::now();
+        for message in &published {
+            db::mark_outbox_delivered(&mut tx, message.outbox_id, delivered_at).await?;
+            METRICS.nats_messages_published_total.inc();
+            METRICS.outbox_delivery_lag_seconds.observe(message.lag_seconds(delivered_at));
+        }
+        tx.commit().await?;
+
+        Ok(published.len())
+    }
+
+    async fn fail(&self, conn: &mut PgConnection, message: &OutboxMessage, error: &str) -> Result<()> {
+        let attempts = message.attempts + 1;
+        let next_attempt_at = Utc::now() + Self::retry_delay(attempts);
+
+        METRICS.nats_publish_errors_total.inc();
+        METRICS.outbox_delivery_failures_total.inc();
+        warn!("📮 Outbox message {} -> {} failed (attempt {}), retry at {}: {}",
+              message.outbox_id, message.subject, attempts, next_attempt_at, error);
+
+        db::mark_outbox_failed(conn, message.outbox_id, error, next_attempt_at).await
+    }
+
+    /// Refresh the pending-count and oldest-message-age gauges
+    async fn update_lag_metrics(&self) -> Result<()> {
+        let (pending, oldest) = db::outbox_backlog(&self.db).await?;
+        let lag = oldest
+            .map(|created_at| (Utc::now() - created_at).num_milliseconds().max(0) as f64 / 1000.0)
+            .unwrap_or(0.0);
+
+        METRICS.outbox_pending_messages.set(pending);
+        METRICS.outbox_lag_seconds.set(lag);
+        Ok(())
+    }
+}
+
+/// Run the relay until the process exits; drains the backlog before sleeping
+pub fn start_outbox_relay(relay: Arc<OutboxRelay>) {
+    info!("📮 Outbox relay started (poll interval {:?})", relay.poll_interval);
+
+    tokio::spawn(async move {
+        loop {
+            let delivered = match relay.relay_once().await {
+                Ok(delivered) => delivered,
+                Err(e) => {
+                    error!("Outbox relay failed: {}", e);
+                    0
+                }
+            };
+
+            if let Err(e) = relay.update_lag_metrics().await {
+                error!("Failed to read outbox backlog: {}", e);
+            }
+
+            if delivered < RELAY_BATCH_SIZE as usize {
+                tokio::time::sleep(relay.poll_interval).await;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(OutboxRelay::retry_delay(1), chrono::Duration::seconds(1));
+        assert_eq!(OutboxRelay::retry_delay(4), chrono::Duration::seconds(8));
+        assert_eq!(OutboxRelay::retry_delay(12), chrono::Duration::seconds(MAX_RETRY_DELAY_SECS));
+        assert_eq!(OutboxRelay::retry_delay(i32::MAX), chrono::Duration::seconds(MAX_RETRY_DELAY_SECS));
+    }
+
+    #[test]
+    fn test_poll_interval_from_env_value() {
+        assert_eq!(OutboxRelay::poll_interval_from_env_value("250"), Duration::from_millis(250));
+        assert_eq!(
+            OutboxRelay::poll_interval_from_env_value("0"),
+            Duration::from_millis(DEFAULT_OUTBOX_POLL_INTERVAL_MS)
+        );
+    }
+}
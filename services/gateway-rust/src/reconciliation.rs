@@ -0,0 +1,259 @@
+// camt.053 Reconciliation - match EOD statement entries against gateway payments
+// Every statement is stored with its balance check (opening + credits - debits = closing) and
+// every entry with its match result: UETR first, EndToEndId as fallback, then amount, currency
+// and payment status. Matched payments are returned so the caller can move them to Reconciled;
+// everything else is a break, listed by GET /reconciliation/statements/:id.
+
+use anyhow::Result;
+use chrono::Utc;
+use sqlx::PgPool;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::db;
+use crate::iso20022::camt053::{StatementEntry, StatementSummary};
+use crate::metrics::METRICS;
+use crate::models::canonical::{CanonicalPayment, PaymentStatus};
+use crate::models::participant::AuthenticatedParticipant;
+use crate::models::reconciliation::{BankStatement, EntryMatchStatus, StatementEntryRecord};
+
+/// Result of one camt.053 statement
+#[derive(Debug)]
+pub struct StatementOutcome {
+    pub statement: BankStatement,
+    pub reconciled: Vec<CanonicalPayment>,     // matched payments not yet Reconciled / Completed
+    pub duplicate: bool,                        // statement was already received, nothing re-matched
+}
+
+pub struct Reconciler {
+    db: PgPool,
+}
+
+impl Reconciler {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    /// Match and persist one statement. Payments the caller is not a party of are never matched.
+    pub async fn reconcile(&self, caller: &AuthenticatedParticipant, summary: &StatementSummary) -> Result<StatementOutcome> {
+        if let Some(statement_id) = db::get_bank_statement_id(&self.db, &summary.account_id, &summary.statement_id).await? {
+            if let Some(statement) = db::get_bank_statement(&self.db, statement_id).await? {
+                info!("🔁 Statement {} for account {} already reconciled as {}",
+                      summary.statement_id, summary.account_id, statement_id);
+                METRICS.track_reconciliation_statement("duplicate");
+                return Ok(StatementOutcome { statement, reconciled: Vec::new(), duplicate: true });
+            }
+        }
+
+        let mut statement = BankStatement {
+            statement_id: Uuid::new_v4(),
+            statement_reference: summary.statement_id.clone(),
+            message_id: summary.message_id.clone(),
+            account_id: summary.account_id.clone(),
+            account_iban: summary.account_iban.clone(),
+            account_servicer_bic: summary.account_servicer_bic.clone(),
+            received_from_bic: caller.bic.clone(),
+            currency: summary.currency.clone(),
+            from_date: summary.from_date,
+            to_date: summary.to_date,
+            opening_balance: summary.opening_balance,
+            closing_balance: summary.closing_balance,
+            total_credits: summary.total_credits,
+            total_debits: summary.total_debits,
+            balance_difference: summary.balance_difference(),
+            entry_count: summary.entries.len() as i32,
+            matched_entries: 0,
+            break_count: 0,
+            created_at: Utc::now(),
+        };
+
+        let mut entries = Vec::with_capacity(summary.entries.len());
+        let mut reconciled: Vec<CanonicalPayment> = Vec::new();
+
+        for (sequence, entry) in summary.entries.iter().enumerate() {
+            let payment = if entry.status == "BOOK" {
+                self.find_payment(entry).await?.filter(|payment| caller.may_access(payment))
+            } else {
+                None
+            };
+
+            let (match_status, break_reason) = match_entry(entry, payment.as_ref());
+            statement.record(match_status);
+            METRICS.track_reconciliation_entry(match_status.as_str());
+
+            if match_status.is_break() {
+                warn!("⚠️ Statement {} entry {}: {} ({})", summary.statement_id, sequence + 1,
+                      match_status.as_str(), break_reason.as_deref().unwrap_or("-"));
+            }
+
+            entries.push(StatementEntryRecord {
+                statement_id: statement.statement_id,
+                sequence: sequence as i32 + 1,
+                entry_reference: entry.entry_reference.clone(),
+                amount: entry.amount,
+                currency: entry.currency.clone(),
+                credit_debit_indicator: entry.credit_debit_indicator.clone(),
+                entry_status: entry.status.clone(),
+                booking_date: entry.booking_date,
+                value_date: entry.value_date,
+                end_to_end_id: entry.end_to_end_id.clone(),
+                transaction_id: entry.transaction_id.clone(),
+                uetr: entry.uetr,
+                match_status,
+                deltran_tx_id: payment.as_ref().map(|p| p.deltran_tx_id),
+                break_reason,
+            });
+
+            if let Some(payment) = payment.filter(|_| match_status == EntryMatchStatus::Matched) {
+                let settled = matches!(payment.status, PaymentStatus::Reconciled | PaymentStatus::Completed);
+                if !settled && !reconciled.iter().any(|p| p.deltran_tx_id == payment.deltran_tx_id) {
+                    reconciled.push(payment);
+                }
+            }
+        }
+
+        if !statement.is_balanced() {
+            warn!("⚖️ Statement {} does not balance: difference {:?}",
+                  summary.statement_id, statement.balance_difference);
+        }
+        METRICS.track_reconciliation_statement(if statement.is_balanced() { "balanced" } else { "unbalanced" });
+
+        let mut tx = self.db.begin().await?;
+        db::insert_bank_statement(&mut *tx, &statement).await?;
+        for entry in &entries {
+            db::insert_statement_entry(&mut *tx, entry).await?;
+        }
+        tx.commit().await?;
+
+        info!("🎯 Statement {} reconciled: {}/{} entries matched, {} breaks",
+              summary.statement_id, statement.matched_entries, statement.entry_count, statement.break_count);
+
+        Ok(StatementOutcome { statement, reconciled, duplicate: false })
+    }
+
+    /// UETR first, EndToEndId as fallback
+    async fn find_payment(&self, entry: &StatementEntry) -> Result<Option<CanonicalPayment>> {
+        if let Some(uetr) = entry.uetr {
+            if let Some(tx_id) = db::get_payment_id_by_uetr(&self.db, uetr).await? {
+                return db::get_payment_by_id(&self.db, tx_id).await;
+            }
+        }
+
+        match entry.end_to_end_id.as_deref() {
+            Some(end_to_end_id) => db::get_payment_by_e2e(&self.db, end_to_end_id).await,
+            None => Ok(None),
+        }
+    }
+}
+
+/// Match result of a statement entry against the payment its references point to
+pub fn match_entry(entry: &StatementEntry, payment: Option<&CanonicalPayment>) -> (EntryMatchStatus, Option<String>) {
+    if entry.status != "BOOK" {
+        return (EntryMatchStatus::NotBooked, None);
+    }
+
+    let Some(payment) = payment else {
+        return match (entry.uetr, entry.end_to_end_id.as_deref()) {
+            (None, None) => (EntryMatchStatus::NoReference,
+                             Some("Entry carries neither UETR nor EndToEndId".to_string())),
+            (uetr, end_to_end_id) => (EntryMatchStatus::Unmatched, Some(format!(
+                "No payment for UETR {} / EndToEndId {}",
+                uetr.map(|u| u.to_string()).unwrap_or_else(|| "-".to_string()),
+                end_to_end_id.unwrap_or("-")
+            ))),
+        };
+    };
+
+    if !payment.currency.code().eq_ignore_ascii_case(&entry.currency) {
+        return (EntryMatchStatus::AmountMismatch, Some(format!(
+            "Entry currency {} differs from payment currency {}", entry.currency, payment.currency.code()
+        )));
+    }
+
+    // Settlement amount differs from the instructed one after netting - either is accepted
+    if entry.amount != payment.instructed_amount && entry.amount != payment.settlement_amount {
+        return (EntryMatchStatus::AmountMismatch, Some(format!(
+            "Entry amount {} differs from payment amount {}", entry.amount, payment.instructed_amount
+        )));
+    }
+
+    if matches!(payment.status, PaymentStatus::Rejected | PaymentStatus::Cancelled
+                              | PaymentStatus::Returned | PaymentStatus::Failed) {
+        return (EntryMatchStatus::StatusMismatch, Some(format!(
+            "Booked entry for payment in status {:?}", payment.status
+        )));
+    }
+
+    (EntryMatchStatus::Matched, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::canonical::{Currency, FinancialInstitution, Party};
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+
+    fn payment(amount: Decimal) -> CanonicalPayment {
+        let party = || Party {
+            name: "Party".to_string(),
+            postal_address: None,
+            identification: None,
+            country_code: "AE".to_string(),
+        };
+        let agent = |bic: &str| FinancialInstitution {
+            bic: Some(bic.to_string()),
+            name: "Bank".to_string(),
+            country_code: bic[4..6].to_string(),
+            clearing_system_member_id: None,
+        };
+        CanonicalPayment::new(
+            "E2E-RECON".to_string(), "INSTR-RECON".to_string(), "MSG-RECON".to_string(),
+            amount, Currency::AED,
+            party(), party(),
+            agent("BANKAEADXXX"), agent("OTHRINBBXXX"),
+        )
+    }
+
+    fn entry(amount: Decimal, status: &str) -> StatementEntry {
+        StatementEntry {
+            entry_id: Uuid::new_v4(),
+            amount,
+            currency: "AED".to_string(),
+            credit_debit_indicator: "DBIT".to_string(),
+            status: status.to_string(),
+            entry_reference: Some("REF-1".to_string()),
+            booking_date: None,
+            value_date: None,
+            end_to_end_id: Some("E2E-RECON".to_string()),
+            transaction_id: None,
+            uetr: None,
+            debtor_name: None,
+            creditor_name: None,
+        }
+    }
+
+    #[test]
+    fn test_match_entry() {
+        let payment = payment(dec!(250.00));
+
+        assert_eq!(match_entry(&entry(dec!(250.00), "BOOK"), Some(&payment)).0, EntryMatchStatus::Matched);
+        assert_eq!(match_entry(&entry(dec!(250.00), "PDNG"), Some(&payment)).0, EntryMatchStatus::NotBooked);
+        assert_eq!(match_entry(&entry(dec!(249.99), "BOOK"), Some(&payment)).0, EntryMatchStatus::AmountMismatch);
+        assert_eq!(match_entry(&entry(dec!(250.00), "BOOK"), None).0, EntryMatchStatus::Unmatched);
+
+        let mut other_currency = entry(dec!(250.00), "BOOK");
+        other_currency.currency = "USD".to_string();
+        assert_eq!(match_entry(&other_currency, Some(&payment)).0, EntryMatchStatus::AmountMismatch);
+
+        let mut no_reference = entry(dec!(250.00), "BOOK");
+        no_reference.end_to_end_id = None;
+        assert_eq!(match_entry(&no_reference, None).0, EntryMatchStatus::NoReference);
+
+        let mut rejected = payment.clone();
+        rejected.update_status(PaymentStatus::Rejected, None);
+        let (status, reason) = match_entry(&entry(dec!(250.00), "BOOK"), Some(&rejected));
+        assert_eq!(status, EntryMatchStatus::StatusMismatch);
+        assert!(reason.is_some());
+    }
+}
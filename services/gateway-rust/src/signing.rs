@@ -0,0 +1,301 @@
+// Message Signing - detached JWS over inbound and outbound ISO 20022 documents
+// Participants sign the raw XML body and send the compact detached JWS ("header..signature",
+// RFC 7515 Appendix F) in the X-JWS-Signature header; the `kid` selects one of the participant's
+// registered public keys. Every outbound pain/pacs/camt/trck document is signed with the active
+// key of the gateway keystore; retired keys stay published so older signatures remain verifiable.
+
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use jsonwebtoken::{crypto, Algorithm, DecodingKey, EncodingKey};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tracing::warn;
+
+use crate::db;
+use crate::metrics::METRICS;
+use crate::models::participant::{AuthFailure, Participant};
+
+/// HTTP / NATS header carrying the detached JWS
+pub const SIGNATURE_HEADER: &str = "X-JWS-Signature";
+
+/// Inbound message types that must be signed (SIGNATURE_REQUIRED_MESSAGES, "*" for all)
+pub const DEFAULT_SIGNATURE_REQUIRED_MESSAGES: &str = "camt.054";
+
+/// Protected header of a detached JWS
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JwsHeader {
+    pub alg: Algorithm,
+    pub kid: String,
+}
+
+/// Asymmetric algorithms only - a shared secret does not prove who signed
+pub fn is_signature_algorithm(alg: Algorithm) -> bool {
+    !matches!(alg, Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512)
+}
+
+pub fn encoding_key(alg: Algorithm, pem: &[u8]) -> Result<EncodingKey> {
+    Ok(match alg {
+        Algorithm::ES256 | Algorithm::ES384 => EncodingKey::from_ec_pem(pem)?,
+        Algorithm::EdDSA => EncodingKey::from_ed_pem(pem)?,
+        Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512 => bail!("{:?} is not a signature algorithm", alg),
+        _ => EncodingKey::from_rsa_pem(pem)?,
+    })
+}
+
+pub fn decoding_key(alg: Algorithm, pem: &[u8]) -> Result<DecodingKey> {
+    Ok(match alg {
+        Algorithm::ES256 | Algorithm::ES384 => DecodingKey::from_ec_pem(pem)?,
+        Algorithm::EdDSA => DecodingKey::from_ed_pem(pem)?,
+        Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512 => bail!("{:?} is not a signature algorithm", alg),
+        _ => DecodingKey::from_rsa_pem(pem)?,
+    })
+}
+
+/// Compact detached JWS over `payload`
+pub fn sign_detached(payload: &[u8], kid: &str, alg: Algorithm, key: &EncodingKey) -> Result<String> {
+    let header = serde_json::to_vec(&JwsHeader { alg, kid: kid.to_string() })?;
+    let header = URL_SAFE_NO_PAD.encode(header);
+    let signature = crypto::sign(signing_input(&header, payload).as_bytes(), key, alg)?;
+    Ok(format!("{}..{}", header, signature))
+}
+
+/// Split a compact detached JWS into its decoded header, encoded header and signature
+pub fn parse_detached(jws: &str) -> Option<(JwsHeader, &str, &str)> {
+    let (header, rest) = jws.trim().split_once('.')?;
+    let signature = rest.strip_prefix('.')?;
+    if signature.is_empty() || signature.contains('.') {
+        return None;
+    }
+
+    let decoded = URL_SAFE_NO_PAD.decode(header).ok()?;
+    let parsed = serde_json::from_slice(&decoded).ok()?;
+    Some((parsed, header, signature))
+}
+
+/// Check a detached JWS against one public key
+pub fn verify_detached(header: &str, signature: &str, payload: &[u8], alg: Algorithm, key: &DecodingKey) -> bool {
+    crypto::verify(signature, signing_input(header, payload).as_bytes(), key, alg).unwrap_or(false)
+}
+
+fn signing_input(header: &str, payload: &[u8]) -> String {
+    format!("{}.{}", header, URL_SAFE_NO_PAD.encode(payload))
+}
+
+#[derive(Debug, Deserialize)]
+struct KeystoreFile {
+    active_kid: String,
+    keys: Vec<KeystoreEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KeystoreEntry {
+    kid: String,
+    algorithm: Algorithm,
+    private_key: String,    // PEM (PKCS#8), relative to the keystore file
+    public_key: String,
+}
+
+struct SigningKey {
+    kid: String,
+    algorithm: Algorithm,
+    encoding_key: EncodingKey,
+    public_key_pem: String,
+}
+
+/// Gateway public key as published on GET /signing-keys
+#[derive(Debug, Clone, Serialize)]
+pub struct PublishedKey {
+    pub kid: String,
+    pub algorithm: Algorithm,
+    pub public_key_pem: String,
+    pub active: bool,
+}
+
+/// Signs outbound documents with the active key of the gateway keystore
+pub struct MessageSigner {
+    keys: Vec<SigningKey>,
+    active: usize,
+}
+
+impl MessageSigner {
+    /// Load a keystore manifest; keys are only replaced by editing it and restarting (rotation
+    /// keeps the previous key listed until no verifier needs it)
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read keystore {}", path.display()))?;
+        let file: KeystoreFile = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid keystore {}", path.display()))?;
+        let dir = path.parent().unwrap_or_else(|| Path::new("."));
+
+        let mut keys = Vec::with_capacity(file.keys.len());
+        for entry in file.keys {
+            let private_pem = std::fs::read(dir.join(&entry.private_key))
+                .with_context(|| format!("Failed to read private key of {}", entry.kid))?;
+            let public_key_pem = std::fs::read_to_string(dir.join(&entry.public_key))
+                .with_context(|| format!("Failed to read public key of {}", entry.kid))?;
+
+            let encoding_key = encoding_key(entry.algorithm, &private_pem)
+                .with_context(|| format!("Invalid private key for {}", entry.kid))?;
+            let decoding = decoding_key(entry.algorithm, public_key_pem.as_bytes())
+                .with_context(|| format!("Invalid public key for {}", entry.kid))?;
+
+            // The pair must belong together, otherwise every outbound signature is worthless
+            let probe = sign_detached(b"keystore", &entry.kid, entry.algorithm, &encoding_key)?;
+            let (_, header, signature) = parse_detached(&probe).ok_or_else(|| anyhow!("Unparseable probe signature"))?;
+            if !verify_detached(header, signature, b"keystore", entry.algorithm, &decoding) {
+                bail!("Public key of {} does not match its private key", entry.kid);
+            }
+
+            keys.push(SigningKey { kid: entry.kid, algorithm: entry.algorithm, encoding_key, public_key_pem });
+        }
+
+        let active = keys.iter().position(|k| k.kid == file.active_kid)
+            .ok_or_else(|| anyhow!("Active key {} is not in the keystore", file.active_kid))?;
+
+        Ok(Self { keys, active })
+    }
+
+    /// Test keys under tests/fixtures - never shipped, production keystores come from
+    /// SIGNING_KEYSTORE_PATH
+    #[cfg(test)]
+    pub(crate) fn load_test_keystore() -> Self {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/keystore/keystore.json");
+        Self::load(&path).unwrap()
+    }
+
+    pub fn active_kid(&self) -> &str {
+        &self.keys[self.active].kid
+    }
+
+    /// Detached JWS over an outbound document
+    pub fn sign(&self, payload: &[u8]) -> Result<String> {
+        let key = &self.keys[self.active];
+        sign_detached(payload, &key.kid, key.algorithm, &key.encoding_key)
+    }
+
+    pub fn public_keys(&self) -> Vec<PublishedKey> {
+        self.keys.iter()
+            .enumerate()
+            .map(|(i, key)| PublishedKey {
+                kid: key.kid.clone(),
+                algorithm: key.algorithm,
+                public_key_pem: key.public_key_pem.clone(),
+                active: i == self.active,
+            })
+            .collect()
+    }
+}
+
+/// Verifies inbound signatures against the participant key registry
+pub struct SignatureVerifier {
+    db: PgPool,
+    required: Vec<String>,
+}
+
+impl SignatureVerifier {
+    pub fn new(db: PgPool, required_messages: &str) -> Self {
+        let required = required_messages
+            .split(',')
+            .map(|m| m.trim().to_ascii_lowercase())
+            .filter(|m| !m.is_empty())
+            .collect();
+        Self { db, required }
+    }
+
+    pub fn is_required(&self, message_type: &str) -> bool {
+        self.required.iter().any(|m| m == "*" || m.eq_ignore_ascii_case(message_type))
+    }
+
+    /// Verify the signature of an inbound message from `participant`. Unsigned messages pass
+    /// (Ok(None)) unless their type requires a signature; a supplied signature must always be valid.
+    /// Returns the kid of the verifying key.
+    pub async fn verify(
+        &self,
+        participant: &Participant,
+        message_type: &str,
+        signature: Option<&str>,
+        payload: &[u8],
+    ) -> Result<Result<Option<String>, AuthFailure>> {
+        let Some(signature) = signature else {
+            if self.is_required(message_type) {
+                METRICS.track_signature_verification(message_type, "missing");
+                return Ok(Err(AuthFailure::MissingSignature));
+            }
+            METRICS.track_signature_verification(message_type, "unsigned");
+            return Ok(Ok(None));
+        };
+
+        let Some((header, encoded_header, signature)) = parse_detached(signature) else {
+            METRICS.track_signature_verification(message_type, "invalid");
+            return Ok(Err(AuthFailure::InvalidSignature));
+        };
+
+        let Some(key) = db::get_participant_key(&self.db, participant.participant_id, &header.kid).await? else {
+            METRICS.track_signature_verification(message_type, "unknown_key");
+            return Ok(Err(AuthFailure::UnknownSigningKey));
+        };
+
+        // The registered algorithm is authoritative - never let the header pick a weaker one
+        let valid = key.algorithm.parse::<Algorithm>().ok()
+            .filter(|alg| *alg == header.alg && is_signature_algorithm(*alg))
+            .and_then(|alg| match decoding_key(alg, key.public_key_pem.as_bytes()) {
+                Ok(decoding) => Some(verify_detached(encoded_header, signature, payload, alg, &decoding)),
+                Err(e) => {
+                    warn!("Unusable public key {} of {}: {}", key.kid, participant.bic, e);
+                    None
+                }
+            })
+            .unwrap_or(false);
+
+        if !valid {
+            METRICS.track_signature_verification(message_type, "invalid");
+            return Ok(Err(AuthFailure::InvalidSignature));
+        }
+
+        METRICS.track_signature_verification(message_type, "valid");
+        Ok(Ok(Some(key.kid)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_and_verify_detached() {
+        let signer = MessageSigner::load_test_keystore();
+        let xml = b"<Document><BkToCstmrDbtCdtNtfctn/></Document>";
+        let jws = signer.sign(xml).unwrap();
+
+        let (header, encoded_header, signature) = parse_detached(&jws).unwrap();
+        assert_eq!(header.kid, signer.active_kid());
+        assert_eq!(header.alg, Algorithm::ES256);
+
+        let published = &signer.public_keys()[0];
+        assert!(published.active);
+        let key = decoding_key(header.alg, published.public_key_pem.as_bytes()).unwrap();
+        assert!(verify_detached(encoded_header, signature, xml, header.alg, &key));
+        assert!(!verify_detached(encoded_header, signature, b"<Document/>", header.alg, &key));
+    }
+
+    #[test]
+    fn test_parse_detached_rejects_attached_payload() {
+        assert!(parse_detached("eyJhbGciOiJFUzI1NiIsImtpZCI6ImsifQ.cGF5bG9hZA.c2ln").is_none());
+        assert!(parse_detached("eyJhbGciOiJFUzI1NiIsImtpZCI6ImsifQ..").is_none());
+        assert!(parse_detached("eyJhbGciOiJFUzI1NiIsImtpZCI6ImsifQ..c2ln").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_required_messages() {
+        let db = PgPool::connect_lazy("postgres://localhost/unused").unwrap();
+        let verifier = SignatureVerifier::new(db.clone(), "camt.054, pacs.008");
+        assert!(verifier.is_required("camt.054"));
+        assert!(verifier.is_required("pacs.008"));
+        assert!(!verifier.is_required("pain.001"));
+
+        assert!(SignatureVerifier::new(db, "*").is_required("pain.001"));
+    }
+}
@@ -0,0 +1,274 @@
+// Outbound Status Reports - pain.002 / pacs.002 for the originating bank
+// Every state change of a payment produces a report that the bank can collect
+// via GET /reports/:bic or NATS deltran.reports.{BIC}.{pain002|pacs002}.
+// camt.029 resolutions of cancellation requests and the pain.002 answering a bulk
+// pain.001 batch use the same store and subjects. Reports are wrapped in a head.001 AppHdr
+// (From: DELTRAN_BIC, To: recipient) and carry the gateway's detached JWS over the whole
+// message (stored with the report, sent as X-JWS-Signature).
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::db;
+use crate::iso20022::{build_camt029, build_pacs002, build_pain002, build_pain002_batch, CancellationResolution};
+use crate::iso20022::camt056::CaseAssignment;
+use crate::iso20022::outbound::OutboundDocument;
+use crate::metrics::METRICS;
+use crate::models::batch::{BatchTransaction, PaymentBatch, TransactionOutcome};
+use crate::models::canonical::CanonicalPayment;
+use crate::nats_router::NatsRouter;
+use crate::signing::MessageSigner;
+
+/// Report format, chosen from the message that created the payment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusReportType {
+    Pain002,  // answers pain.001 (customer initiation)
+    Pacs002,  // answers pacs.008 (FI to FI)
+}
+
+impl StatusReportType {
+    pub fn for_source_message(source_message_type: &str) -> Self {
+        if source_message_type.starts_with("pacs.") {
+            StatusReportType::Pacs002
+        } else {
+            StatusReportType::Pain002
+        }
+    }
+
+    pub fn message_type(&self) -> &'static str {
+        match self {
+            StatusReportType::Pain002 => "pain.002",
+            StatusReportType::Pacs002 => "pacs.002",
+        }
+    }
+
+    fn build(&self, payments: &[CanonicalPayment], include_group_status: bool) -> Result<OutboundDocument> {
+        match self {
+            StatusReportType::Pain002 => build_pain002(payments, include_group_status),
+            StatusReportType::Pacs002 => build_pacs002(payments, include_group_status),
+        }
+    }
+}
+
+/// Persisted status report as returned to the collecting bank
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusReportRecord {
+    pub report_id: Uuid,
+    pub message_type: String,
+    pub message_id: String,
+    pub original_message_id: String,
+    pub recipient_bic: String,
+    pub group_status: Option<String>,
+    pub deltran_tx_ids: Vec<Uuid>,
+    pub xml_document: String,       // AppHdr envelope when the gateway has a BIC
+    pub signature: Option<String>,  // detached JWS over xml_document (gateway key)
+    pub created_at: DateTime<Utc>,
+    pub collected_at: Option<DateTime<Utc>>,
+}
+
+pub struct StatusReporter {
+    db: PgPool,
+    router: Arc<NatsRouter>,
+    signer: Arc<MessageSigner>,
+    deltran_bic: Option<String>,
+}
+
+impl StatusReporter {
+    pub fn new(db: PgPool, router: Arc<NatsRouter>, signer: Arc<MessageSigner>, deltran_bic: Option<String>) -> Self {
+        Self { db, router, signer, deltran_bic }
+    }
+
+    /// Group-level report for all transactions accepted from one inbound message
+    pub async fn report_intake(
+        &self,
+        payments: &[CanonicalPayment],
+        report_type: StatusReportType,
+    ) -> Result<Vec<StatusReportRecord>> {
+        self.generate(payments, report_type, true).await
+    }
+
+    /// Transaction-level report after a state change of a single payment
+    pub async fn report_status_change(&self, payment: &CanonicalPayment) -> Result<Option<StatusReportRecord>> {
+        let source = db::get_source_message_type(&self.db, payment.deltran_tx_id).await?
+            .unwrap_or_else(|| "pain.001".to_string());
+        let report_type = StatusReportType::for_source_message(&source);
+
+        let mut reports = self.generate(std::slice::from_ref(payment), report_type, false).await?;
+        Ok(reports.pop())
+    }
+
+    /// camt.029 answering a camt.056, addressed to the bank that requested the cancellation
+    pub async fn report_resolution(
+        &self,
+        assignment: &CaseAssignment,
+        resolutions: &[CancellationResolution],
+        deltran_tx_ids: Vec<Uuid>,
+    ) -> Result<StatusReportRecord> {
+        let recipient_bic = assignment.assigner.bic()
+            .ok_or_else(|| anyhow!("camt.056 {} has no assigner BIC - resolution not addressed", assignment.id))?
+            .to_string();
+        let document = build_camt029(assignment, resolutions)?
+            .with_app_header(self.deltran_bic.as_deref(), Some(&recipient_bic))?;
+
+        let record = StatusReportRecord {
+            report_id: Uuid::new_v4(),
+            message_type: "camt.029".to_string(),
+            message_id: document.message_id,
+            original_message_id: assignment.id.clone(),
+            recipient_bic,
+            group_status: document.group_status,
+            deltran_tx_ids,
+            signature: Some(self.signer.sign(document.xml.as_bytes())?),
+            xml_document: document.xml,
+            created_at: Utc::now(),
+            collected_at: None,
+        };
+
+        self.publish(&record).await?;
+
+        Ok(record)
+    }
+
+    /// Batch pain.002 (ACTC / PART / RJCT, rejected transactions listed) for a bulk pain.001
+    pub async fn report_batch(
+        &self,
+        batch: &PaymentBatch,
+        transactions: &[BatchTransaction],
+    ) -> Result<StatusReportRecord> {
+        let original_message_id = batch.message_id.clone()
+            .ok_or_else(|| anyhow!("Batch {} has no message id - pain.002 not generated", batch.batch_id))?;
+        let recipient_bic = batch.debtor_agent_bic.clone()
+            .ok_or_else(|| anyhow!("Batch {} has no debtor agent BIC - pain.002 not addressed", batch.batch_id))?;
+        let document = build_pain002_batch(&original_message_id, transactions)?
+            .with_app_header(self.deltran_bic.as_deref(), Some(&recipient_bic))?;
+
+        let record = StatusReportRecord {
+            report_id: Uuid::new_v4(),
+            message_type: "pain.002".to_string(),
+            message_id: document.message_id,
+            original_message_id,
+            recipient_bic,
+            group_status: document.group_status,
+            deltran_tx_ids: transactions.iter()
+                .filter(|t| t.outcome == TransactionOutcome::Accepted)
+                .filter_map(|t| t.deltran_tx_id)
+                .collect(),
+            signature: Some(self.signer.sign(document.xml.as_bytes())?),
+            xml_document: document.xml,
+            created_at: Utc::now(),
+            collected_at: None,
+        };
+
+        self.publish(&record).await?;
+
+        Ok(record)
+    }
+
+    async fn generate(
+        &self,
+        payments: &[CanonicalPayment],
+        report_type: StatusReportType,
+        include_group_status: bool,
+    ) -> Result<Vec<StatusReportRecord>> {
+        let mut reports = Vec::new();
+
+        for ((recipient_bic, original_message_id), group) in group_by_recipient(payments) {
+            let document = report_type.build(&group, include_group_status)?
+                .with_app_header(self.deltran_bic.as_deref(), Some(&recipient_bic))?;
+
+            let record = StatusReportRecord {
+                report_id: Uuid::new_v4(),
+                message_type: report_type.message_type().to_string(),
+                message_id: document.message_id,
+                original_message_id,
+                recipient_bic,
+                group_status: document.group_status,
+                deltran_tx_ids: group.iter().map(|p| p.deltran_tx_id).collect(),
+                signature: Some(self.signer.sign(document.xml.as_bytes())?),
+                xml_document: document.xml,
+                created_at: Utc::now(),
+                collected_at: None,
+            };
+
+            self.publish(&record).await?;
+            reports.push(record);
+        }
+
+        Ok(reports)
+    }
+
+    async fn publish(&self, record: &StatusReportRecord) -> Result<()> {
+        db::insert_status_report(&self.db, record).await?;
+        let signature = record.signature.as_deref().unwrap_or_default();
+        self.router.publish_status_report(&record.recipient_bic, &record.message_type, &record.xml_document, signature).await?;
+        METRICS.status_reports_generated_total.with_label_values(&[&record.message_type]).inc();
+
+        info!("📤 {} {} generated for {} ({} transaction(s))",
+              record.message_type, record.message_id, record.recipient_bic, record.deltran_tx_ids.len());
+
+        Ok(())
+    }
+}
+
+/// Reports go to the debtor agent (the bank that sent the original message),
+/// one report per original message
+fn group_by_recipient(payments: &[CanonicalPayment]) -> BTreeMap<(String, String), Vec<CanonicalPayment>> {
+    let mut groups: BTreeMap<(String, String), Vec<CanonicalPayment>> = BTreeMap::new();
+
+    for payment in payments {
+        match &payment.debtor_agent.bic {
+            Some(bic) => groups
+                .entry((bic.clone(), payment.message_id.clone()))
+                .or_default()
+                .push(payment.clone()),
+            None => warn!("No debtor agent BIC on {} - status report not addressed", payment.deltran_tx_id),
+        }
+    }
+
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::canonical::{Currency, Party, FinancialInstitution};
+    use rust_decimal_macros::dec;
+
+    fn payment(message_id: &str, debtor_bic: Option<&str>) -> CanonicalPayment {
+        let party = |name: &str| Party { name: name.to_string(), postal_address: None, identification: None, country_code: "AE".to_string() };
+        let agent = |bic: Option<&str>| FinancialInstitution { bic: bic.map(str::to_string), name: "Bank".to_string(), country_code: "AE".to_string(), clearing_system_member_id: None };
+
+        CanonicalPayment::new(
+            "E2E-1".to_string(), "INSTR-1".to_string(), message_id.to_string(),
+            dec!(10.00), Currency::AED,
+            party("Debtor"), party("Creditor"), agent(debtor_bic), agent(Some("ICICINBBXXX")),
+        )
+    }
+
+    #[test]
+    fn test_report_type_for_source_message() {
+        assert_eq!(StatusReportType::for_source_message("pain.001"), StatusReportType::Pain002);
+        assert_eq!(StatusReportType::for_source_message("pacs.008"), StatusReportType::Pacs002);
+    }
+
+    #[test]
+    fn test_group_by_recipient() {
+        let payments = vec![
+            payment("MSG-1", Some("BANKAEADXXX")),
+            payment("MSG-1", Some("BANKAEADXXX")),
+            payment("MSG-2", Some("BANKAEADXXX")),
+            payment("MSG-1", None),
+        ];
+
+        let groups = group_by_recipient(&payments);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[&("BANKAEADXXX".to_string(), "MSG-1".to_string())].len(), 2);
+    }
+}
@@ -0,0 +1,139 @@
+// Live Payment Stream - tracking events pushed to participants over GET /ws/payments
+// Every gateway instance publishes the events it records on deltran.gateway.payment_events and
+// fans the subject out to its own WebSocket connections. A connection opened with ?from_sequence=
+// first replays the missed events from payment_events, then continues live without repeats.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use async_nats::Client as NatsClient;
+use axum::extract::ws::{Message, WebSocket};
+use futures_util::StreamExt;
+use sqlx::PgPool;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{info, warn, error, debug};
+
+use crate::db;
+use crate::metrics::METRICS;
+use crate::models::stream::{StreamEvent, StreamFilter, StreamMessage};
+use crate::nats_router::PAYMENT_STREAM_SUBJECT;
+
+/// Live events buffered per connection before it is considered lagging (and replays from the DB)
+const STREAM_BUFFER: usize = 1024;
+
+/// Events read from payment_events per replay query
+const REPLAY_BATCH_SIZE: i64 = 500;
+
+/// Keep-alive for idle connections (proxies drop silent WebSockets)
+const PING_INTERVAL: Duration = Duration::from_secs(30);
+
+pub struct PaymentStream {
+    db: PgPool,
+    sender: broadcast::Sender<Arc<StreamEvent>>,
+}
+
+impl PaymentStream {
+    pub fn new(db: PgPool) -> Self {
+        let (sender, _) = broadcast::channel(STREAM_BUFFER);
+        Self { db, sender }
+    }
+
+    /// Hand an event to every open connection (dropped when none is open)
+    pub fn publish(&self, event: StreamEvent) {
+        let _ = self.sender.send(Arc::new(event));
+    }
+
+    /// Serve one WebSocket connection until the client leaves
+    pub async fn serve(self: Arc<Self>, mut socket: WebSocket, filter: StreamFilter, from_sequence: Option<i64>) {
+        METRICS.stream_connections.inc();
+
+        if let Err(e) = self.run(&mut socket, &filter, from_sequence).await {
+            debug!("Payment stream closed: {}", e);
+            let _ = send(&mut socket, &StreamMessage::Error { message: e.to_string() }).await;
+        }
+
+        METRICS.stream_connections.dec();
+    }
+
+    async fn run(&self, socket: &mut WebSocket, filter: &StreamFilter, from_sequence: Option<i64>) -> Result<()> {
+        // Subscribe before replaying so events recorded meanwhile are not lost
+        let mut live = self.sender.subscribe();
+
+        // Live events up to the watermark were replayed already
+        let mut watermark = match from_sequence {
+            Some(sequence) => self.replay(socket, filter, sequence).await?,
+            None => db::latest_event_sequence(&self.db).await?,
+        };
+        let mut last_sent = watermark;
+        send(socket, &StreamMessage::CaughtUp { sequence: watermark }).await?;
+
+        let mut ping = tokio::time::interval(PING_INTERVAL);
+        loop {
+            tokio::select! {
+                received = live.recv() => match received {
+                    Ok(event) => {
+                        if event.sequence > watermark && filter.matches(&event) {
+                            last_sent = last_sent.max(event.sequence);
+                            send(socket, &StreamMessage::Event(&event)).await?;
+                            METRICS.track_stream_event("live");
+                        }
+                    }
+                    Err(RecvError::Lagged(missed)) => {
+                        warn!("⚠️ Payment stream lagged by {} events - replaying from {}", missed, last_sent);
+                        watermark = self.replay(socket, filter, last_sent).await?;
+                        last_sent = watermark;
+                    }
+                    Err(RecvError::Closed) => return Ok(()),
+                },
+                incoming = socket.recv() => match incoming {
+                    Some(Ok(Message::Close(_))) | None => return Ok(()),
+                    Some(Ok(_)) => {}   // pings are answered by axum; the stream takes no commands
+                    Some(Err(e)) => return Err(e.into()),
+                },
+                _ = ping.tick() => socket.send(Message::Ping(Vec::new())).await?,
+            }
+        }
+    }
+
+    /// Send the stored events after `after_sequence`. Returns the last sequence sent.
+    async fn replay(&self, socket: &mut WebSocket, filter: &StreamFilter, after_sequence: i64) -> Result<i64> {
+        let mut after_sequence = after_sequence;
+        loop {
+            let events = db::list_stream_events(&self.db, filter, after_sequence, REPLAY_BATCH_SIZE).await?;
+            let Some(last) = events.last().map(|event| event.sequence) else {
+                return Ok(after_sequence);
+            };
+
+            for event in &events {
+                send(socket, &StreamMessage::Event(event)).await?;
+                METRICS.track_stream_event("replayed");
+            }
+            after_sequence = last;
+        }
+    }
+}
+
+async fn send(socket: &mut WebSocket, message: &StreamMessage<'_>) -> Result<()> {
+    socket.send(Message::Text(serde_json::to_string(message)?)).await?;
+    Ok(())
+}
+
+/// Feed this instance's connections from the events recorded by all gateway instances
+pub async fn start_stream_consumer(stream: Arc<PaymentStream>, nats: NatsClient) -> Result<()> {
+    let mut subscriber = nats.subscribe(PAYMENT_STREAM_SUBJECT.to_string()).await?;
+    info!("📡 Payment stream subscribed to: {}", PAYMENT_STREAM_SUBJECT);
+
+    tokio::spawn(async move {
+        while let Some(msg) = subscriber.next().await {
+            match serde_json::from_slice::<StreamEvent>(&msg.payload) {
+                Ok(event) => stream.publish(event),
+                Err(e) => error!("Unparseable payment stream event: {}", e),
+            }
+        }
+
+        warn!("⚠️ Payment stream consumer for {} ended", PAYMENT_STREAM_SUBJECT);
+    });
+
+    Ok(())
+}
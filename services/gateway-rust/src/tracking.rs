@@ -0,0 +1,319 @@
+// Payment Tracking - per-UETR event timeline aggregated from all engines
+// Engine events arrive on NATS, are linked to the gateway payment and stored in payment_events.
+// Every event is forwarded to the originating bank as a trck.001 update
+// (deltran.reports.{BIC}.trck001) and pushed to live GET /ws/payments streams; the full timeline
+// is available as JSON or trck.002.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_nats::Client as NatsClient;
+use chrono::{DateTime, Utc};
+use deltran_schema::envelope::payload_value;
+use futures_util::StreamExt;
+use serde_json::Value;
+use sqlx::PgPool;
+use tracing::{info, warn, error, debug};
+use uuid::Uuid;
+
+use crate::db;
+use crate::iso20022::{build_trck001, build_trck002, TrackerUpdate};
+use crate::iso20022::outbound::{IsoTransactionStatus, OutboundDocument};
+use crate::metrics::METRICS;
+use crate::models::canonical::{CanonicalPayment, PaymentStatus};
+use crate::models::stream::StreamEvent;
+use crate::models::tracking::{EventSource, PaymentEvent, PaymentTimeline};
+use crate::nats_router::NatsRouter;
+use crate::signing::MessageSigner;
+
+/// Engine subjects feeding the tracker
+pub const TRACKED_SUBJECTS: &[(&str, EventSource, &str)] = &[
+    ("deltran.obligation.create", EventSource::Compliance, "compliance.passed"),
+    ("deltran.compliance.reject", EventSource::Compliance, "compliance.rejected"),
+    ("deltran.events.obligation.created", EventSource::Obligation, "obligation.created"),
+    ("deltran.events.clearing.accepted", EventSource::Clearing, "clearing.accepted"),
+    ("deltran.events.clearing.local", EventSource::Clearing, "clearing.local"),
+    ("deltran.token.minted", EventSource::Token, "token.minted"),
+    ("deltran.token.burned", EventSource::Token, "token.burned"),
+    ("deltran.settlement.completed", EventSource::Settlement, "settlement.completed"),
+];
+
+/// Payment status an engine event implies (None = informational only)
+pub fn implied_status(event_type: &str, payload: &Value) -> Option<PaymentStatus> {
+    match event_type {
+        "compliance.rejected" => Some(PaymentStatus::Rejected),
+        "clearing.accepted" | "clearing.local" => Some(PaymentStatus::Clearing),
+        "settlement.completed" => match payload.get("status").and_then(Value::as_str) {
+            Some(status) if status.eq_ignore_ascii_case("failed") => Some(PaymentStatus::Failed),
+            _ => Some(PaymentStatus::Completed),
+        },
+        _ => None,
+    }
+}
+
+/// Identifiers an engine payload may carry to link it to a payment
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct PaymentReference {
+    pub deltran_tx_id: Option<Uuid>,
+    pub obligation_id: Option<Uuid>,
+    pub uetr: Option<Uuid>,
+}
+
+impl PaymentReference {
+    /// Engines use deltran_tx_id (compliance, obligation) or payment_id (token, settlement)
+    pub fn from_payload(payload: &Value) -> Self {
+        let uuid = |key: &str| payload.get(key)
+            .and_then(Value::as_str)
+            .and_then(|v| Uuid::parse_str(v).ok());
+
+        Self {
+            deltran_tx_id: uuid("deltran_tx_id").or_else(|| uuid("payment_id")),
+            obligation_id: uuid("obligation_id"),
+            uetr: uuid("uetr"),
+        }
+    }
+}
+
+pub struct PaymentTracker {
+    db: PgPool,
+    router: Arc<NatsRouter>,
+    signer: Arc<MessageSigner>,
+    deltran_bic: Option<String>,
+}
+
+impl PaymentTracker {
+    pub fn new(db: PgPool, router: Arc<NatsRouter>, signer: Arc<MessageSigner>, deltran_bic: Option<String>) -> Self {
+        Self { db, router, signer, deltran_bic }
+    }
+
+    /// Record a gateway-side state change (intake, funding, return, cancellation, ...)
+    pub async fn record_status_change(&self, payment: &CanonicalPayment) -> Result<PaymentEvent> {
+        let event = PaymentEvent {
+            event_id: Uuid::new_v4(),
+            deltran_tx_id: payment.deltran_tx_id,
+            uetr: payment.uetr,
+            source: EventSource::Gateway,
+            event_type: "payment.status_changed".to_string(),
+            status: Some(payment.status.to_string()),
+            iso_status: IsoTransactionStatus::from_payment_status(&payment.status).code().to_string(),
+            reason_code: payment.status_reason.as_ref().map(|r| r.code.clone()),
+            agent_bic: None,
+            details: serde_json::json!({ "status_reason": payment.status_reason }),
+            occurred_at: payment.updated_at,
+        };
+
+        self.record(payment, event).await
+    }
+
+    /// Handle an engine event received on one of the TRACKED_SUBJECTS
+    pub async fn ingest(&self, source: EventSource, event_type: &str, payload: &[u8]) -> Result<Option<PaymentEvent>> {
+        let payload = payload_value(payload)?;
+        let reference = PaymentReference::from_payload(&payload);
+
+        let Some(payment) = self.resolve(&reference).await? else {
+            debug!("{} event does not match a gateway payment: {:?}", event_type, reference);
+            return Ok(None);
+        };
+
+        // Later clearing events only carry the obligation - remember the link
+        if let (Some(obligation_id), None) = (reference.obligation_id, payment.obligation_id) {
+            db::set_payment_obligation_id(&self.db, payment.deltran_tx_id, obligation_id).await?;
+        }
+
+        let status = implied_status(event_type, &payload);
+        let event = PaymentEvent {
+            event_id: Uuid::new_v4(),
+            deltran_tx_id: payment.deltran_tx_id,
+            uetr: payment.uetr,
+            source,
+            event_type: event_type.to_string(),
+            status: status.as_ref().map(|s| s.to_string()),
+            iso_status: IsoTransactionStatus::from_payment_status(status.as_ref().unwrap_or(&payment.status))
+                .code()
+                .to_string(),
+            reason_code: None,
+            agent_bic: None,
+            occurred_at: event_timestamp(&payload).unwrap_or_else(Utc::now),
+            details: payload,
+        };
+
+        self.record(&payment, event).await.map(Some)
+    }
+
+    /// Record a trck.001 received from another agent. Returns the updated payment and timeline.
+    pub async fn record_partner_update(&self, update: &TrackerUpdate) -> Result<Option<(CanonicalPayment, Vec<PaymentEvent>)>> {
+        let reference = PaymentReference { uetr: Some(update.uetr), ..Default::default() };
+        let Some(payment) = self.resolve(&reference).await? else {
+            return Ok(None);
+        };
+
+        let event = PaymentEvent {
+            event_id: Uuid::new_v4(),
+            deltran_tx_id: payment.deltran_tx_id,
+            uetr: payment.uetr,
+            source: EventSource::Partner,
+            event_type: "tracker.update".to_string(),
+            status: None,
+            iso_status: update.status.chars().take(4).collect(),
+            reason_code: update.reason_code.clone(),
+            agent_bic: update.informing_agent_bic.clone(),
+            details: serde_json::to_value(update)?,
+            occurred_at: update.status_at.unwrap_or_else(Utc::now),
+        };
+
+        if let Some(sequence) = db::insert_payment_event(&self.db, &event).await? {
+            self.stream(&payment, sequence, &event).await;
+        }
+        METRICS.track_tracking_event(event.source.as_str());
+
+        let events = db::get_payment_events(&self.db, payment.deltran_tx_id).await?;
+        Ok(Some((payment, events)))
+    }
+
+    /// Timeline of a payment by UETR
+    pub async fn timeline(&self, uetr: Uuid) -> Result<Option<(CanonicalPayment, PaymentTimeline)>> {
+        let reference = PaymentReference { uetr: Some(uetr), ..Default::default() };
+        let Some(payment) = self.resolve(&reference).await? else {
+            return Ok(None);
+        };
+
+        let events = db::get_payment_events(&self.db, payment.deltran_tx_id).await?;
+        let timeline = PaymentTimeline {
+            deltran_tx_id: payment.deltran_tx_id,
+            uetr,
+            end_to_end_id: payment.end_to_end_id.clone(),
+            current_status: payment.status.to_string(),
+            iso_status: IsoTransactionStatus::from_payment_status(&payment.status).code().to_string(),
+            events,
+        };
+
+        Ok(Some((payment, timeline)))
+    }
+
+    /// trck.002 for a payment and its timeline, addressed to the requesting agent
+    pub fn tracker_report(
+        &self,
+        payment: &CanonicalPayment,
+        events: &[PaymentEvent],
+        original_update_id: Option<&str>,
+        recipient_bic: &str,
+    ) -> Result<OutboundDocument> {
+        build_trck002(payment, events, original_update_id, self.deltran_bic.as_deref())?
+            .with_app_header(self.deltran_bic.as_deref(), Some(recipient_bic))
+    }
+
+    async fn resolve(&self, reference: &PaymentReference) -> Result<Option<CanonicalPayment>> {
+        let tx_id = match reference {
+            PaymentReference { deltran_tx_id: Some(tx_id), .. } => Some(*tx_id),
+            PaymentReference { obligation_id: Some(obligation_id), .. } => {
+                db::get_payment_id_by_obligation(&self.db, *obligation_id).await?
+            }
+            PaymentReference { uetr: Some(uetr), .. } => db::get_payment_id_by_uetr(&self.db, *uetr).await?,
+            _ => None,
+        };
+
+        match tx_id {
+            Some(tx_id) => db::get_payment_by_id(&self.db, tx_id).await,
+            None => Ok(None),
+        }
+    }
+
+    async fn record(&self, payment: &CanonicalPayment, event: PaymentEvent) -> Result<PaymentEvent> {
+        if let Some(sequence) = db::insert_payment_event(&self.db, &event).await? {
+            self.stream(payment, sequence, &event).await;
+        }
+        METRICS.track_tracking_event(event.source.as_str());
+
+        info!("📍 {} {} for {} ({})", event.source.as_str(), event.event_type, payment.deltran_tx_id, event.iso_status);
+
+        // Untracked payments (no UETR) or unaddressable ones only keep the timeline entry
+        if let (Some(_), Some(bic)) = (payment.uetr, &payment.debtor_agent.bic) {
+            let update = build_trck001(payment, &event, self.deltran_bic.as_deref())?
+                .with_app_header(self.deltran_bic.as_deref(), Some(bic))?;
+            let signature = self.signer.sign(update.xml.as_bytes())?;
+            self.router.publish_status_report(bic, "trck.001", &update.xml, &signature).await?;
+        }
+
+        Ok(event)
+    }
+
+    /// Push a stored event to the live /ws/payments streams - clients resuming from a sequence
+    /// read it from payment_events, so a failed push is not fatal
+    async fn stream(&self, payment: &CanonicalPayment, sequence: i64, event: &PaymentEvent) {
+        let event = StreamEvent {
+            sequence,
+            end_to_end_id: payment.end_to_end_id.clone(),
+            debtor_agent_bic: payment.debtor_agent.bic.clone(),
+            creditor_agent_bic: payment.creditor_agent.bic.clone(),
+            event: event.clone(),
+        };
+
+        if let Err(e) = self.router.publish_stream_event(&event).await {
+            warn!("Failed to publish stream event {} for {}: {}", sequence, payment.deltran_tx_id, e);
+        }
+    }
+}
+
+/// Engines report RFC 3339 timestamps under different keys
+fn event_timestamp(payload: &Value) -> Option<DateTime<Utc>> {
+    ["timestamp", "completed_at", "executed_at", "accepted_at", "minted_at", "burned_at"]
+        .iter()
+        .find_map(|key| payload.get(*key).and_then(Value::as_str))
+        .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+        .map(|ts| ts.with_timezone(&Utc))
+}
+
+/// Subscribe to all TRACKED_SUBJECTS, one consumer task per subject
+pub async fn start_tracking_consumer(tracker: Arc<PaymentTracker>, nats: NatsClient) -> Result<()> {
+    for (subject, source, event_type) in TRACKED_SUBJECTS {
+        let mut subscriber = nats.subscribe(subject.to_string()).await?;
+        info!("📡 Tracking subscribed to: {}", subject);
+
+        let tracker = tracker.clone();
+        tokio::spawn(async move {
+            while let Some(msg) = subscriber.next().await {
+                if let Err(e) = tracker.ingest(*source, event_type, &msg.payload).await {
+                    error!("Failed to track {} event: {}", event_type, e);
+                }
+            }
+
+            warn!("⚠️ Tracking consumer for {} ended", subject);
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payment_reference_from_payload() {
+        let tx_id = Uuid::new_v4();
+        let obligation_id = Uuid::new_v4();
+
+        let settlement = serde_json::json!({ "payment_id": tx_id, "status": "Completed" });
+        assert_eq!(PaymentReference::from_payload(&settlement).deltran_tx_id, Some(tx_id));
+
+        let clearing = serde_json::json!({ "obligation_id": obligation_id, "window_id": 7 });
+        let reference = PaymentReference::from_payload(&clearing);
+        assert_eq!(reference.deltran_tx_id, None);
+        assert_eq!(reference.obligation_id, Some(obligation_id));
+    }
+
+    #[test]
+    fn test_implied_status() {
+        let failed = serde_json::json!({ "status": "Failed" });
+        assert!(matches!(implied_status("settlement.completed", &failed), Some(PaymentStatus::Failed)));
+        assert!(matches!(implied_status("compliance.rejected", &Value::Null), Some(PaymentStatus::Rejected)));
+        assert!(implied_status("token.minted", &Value::Null).is_none());
+    }
+
+    #[test]
+    fn test_event_timestamp() {
+        let payload = serde_json::json!({ "completed_at": "2025-01-23T10:15:00+00:00" });
+        assert_eq!(event_timestamp(&payload).unwrap().to_rfc3339(), "2025-01-23T10:15:00+00:00");
+        assert!(event_timestamp(&serde_json::json!({ "timestamp": "yesterday" })).is_none());
+    }
+}
@@ -0,0 +1,10 @@
+// Validation Layer
+// XSD conformance against the official ISO 20022 schemas shipped in iso20022/ (before canonical
+// conversion) and per-corridor business rules over the canonical payment (after it)
+
+pub mod xsd;
+pub mod rules;
+
+// Re-export commonly used types
+pub use xsd::{XsdValidator, ValidationMode, XsdValidationReport, XsdViolation, ConstraintKind};
+pub use rules::RuleEngine;
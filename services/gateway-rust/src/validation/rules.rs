@@ -0,0 +1,492 @@
+// Business Rule Validation - runs over CanonicalPayment after to_canonical
+// Rules are pluggable (BusinessRule) and parameterised per corridor from a JSON file
+// (CORRIDOR_RULES_PATH). Every violation carries the ExternalStatusReason1Code reported in pain.002.
+
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::metrics::METRICS;
+use crate::models::canonical::{AccountIdentification, CanonicalPayment, ChargeBearer, FinancialInstitution, Party, StatusReason};
+
+/// Wildcard for `from` / `to` in corridor rules
+pub const ANY_COUNTRY: &str = "*";
+
+/// A failed business rule
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RuleViolation {
+    pub rule: &'static str,
+    pub code: &'static str,     // ExternalStatusReason1Code (AC01, BE01, RC01, ...)
+    pub message: String,
+}
+
+/// Rule parameters of a corridor (debtor agent country -> creditor agent country)
+#[derive(Debug, Clone, Deserialize)]
+pub struct CorridorRules {
+    #[serde(default = "any_country")]
+    pub from: String,
+    #[serde(default = "any_country")]
+    pub to: String,
+    /// ChrgBr codes accepted on the corridor (empty = all)
+    #[serde(default)]
+    pub allowed_charge_bearers: Vec<String>,
+    #[serde(default)]
+    pub purpose_code_required: bool,
+    /// Names of rules that do not apply to the corridor
+    #[serde(default)]
+    pub disabled_rules: Vec<String>,
+}
+
+impl Default for CorridorRules {
+    fn default() -> Self {
+        Self {
+            from: any_country(),
+            to: any_country(),
+            allowed_charge_bearers: vec![],
+            purpose_code_required: false,
+            disabled_rules: vec![],
+        }
+    }
+}
+
+impl CorridorRules {
+    fn matches(&self, from: &str, to: &str) -> bool {
+        (self.from == ANY_COUNTRY || self.from.eq_ignore_ascii_case(from))
+            && (self.to == ANY_COUNTRY || self.to.eq_ignore_ascii_case(to))
+    }
+
+    /// Exact countries win over wildcards
+    fn specificity(&self) -> u8 {
+        (self.from != ANY_COUNTRY) as u8 + (self.to != ANY_COUNTRY) as u8
+    }
+
+    fn is_enabled(&self, rule: &str) -> bool {
+        !self.disabled_rules.iter().any(|r| r == rule)
+    }
+}
+
+fn any_country() -> String {
+    ANY_COUNTRY.to_string()
+}
+
+/// Contents of the corridor rules file
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CorridorRuleConfig {
+    #[serde(default)]
+    pub defaults: CorridorRules,
+    #[serde(default)]
+    pub corridors: Vec<CorridorRules>,
+}
+
+/// What a rule sees besides the payment
+pub struct RuleContext<'a> {
+    pub corridor: &'a CorridorRules,
+    pub today: NaiveDate,
+}
+
+/// A single business rule. Implementations must be stateless.
+pub trait BusinessRule: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn check(&self, payment: &CanonicalPayment, context: &RuleContext) -> Option<RuleViolation>;
+}
+
+pub struct RuleEngine {
+    rules: Vec<Box<dyn BusinessRule>>,
+    config: CorridorRuleConfig,
+}
+
+impl RuleEngine {
+    /// Engine with the built-in rules
+    pub fn new(config: CorridorRuleConfig) -> Self {
+        Self {
+            rules: vec![
+                Box::new(IbanChecksum),
+                Box::new(IbanCountry),
+                Box::new(BicFormat),
+                Box::new(ExecutionDate),
+                Box::new(ChargeBearerAllowed),
+                Box::new(PurposeCodeRequired),
+            ],
+            config,
+        }
+    }
+
+    /// Load corridor rules from a JSON file (fails on unknown rule names or ChrgBr codes)
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read corridor rules {}", path.display()))?;
+        let config: CorridorRuleConfig = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid corridor rules {}", path.display()))?;
+
+        let engine = Self::new(config);
+        engine.check_config()?;
+        Ok(engine)
+    }
+
+    /// Register an additional rule
+    pub fn with_rule(mut self, rule: impl BusinessRule + 'static) -> Self {
+        self.rules.push(Box::new(rule));
+        self
+    }
+
+    pub fn corridor_count(&self) -> usize {
+        self.config.corridors.len()
+    }
+
+    fn check_config(&self) -> Result<()> {
+        for corridor in std::iter::once(&self.config.defaults).chain(&self.config.corridors) {
+            for code in &corridor.allowed_charge_bearers {
+                if ChargeBearer::from_code(code).is_none() {
+                    bail!("Corridor {}->{}: unknown charge bearer {}", corridor.from, corridor.to, code);
+                }
+            }
+            for rule in &corridor.disabled_rules {
+                if !self.rules.iter().any(|r| r.name() == rule) {
+                    bail!("Corridor {}->{}: unknown rule {}", corridor.from, corridor.to, rule);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Most specific corridor entry for the payment, or the defaults
+    pub fn corridor_rules(&self, payment: &CanonicalPayment) -> &CorridorRules {
+        let (from, to) = corridor_countries(payment);
+        self.config.corridors.iter()
+            .filter(|c| c.matches(from, to))
+            .fold(None, |best: Option<&CorridorRules>, c| match best {
+                Some(b) if b.specificity() >= c.specificity() => Some(b),
+                _ => Some(c),
+            })
+            .unwrap_or(&self.config.defaults)
+    }
+
+    pub fn validate(&self, payment: &CanonicalPayment) -> Vec<RuleViolation> {
+        let violations = self.validate_on(payment, Utc::now().date_naive());
+        for violation in &violations {
+            METRICS.track_rule_violation(violation.rule, violation.code);
+        }
+        violations
+    }
+
+    pub fn validate_on(&self, payment: &CanonicalPayment, today: NaiveDate) -> Vec<RuleViolation> {
+        let context = RuleContext { corridor: self.corridor_rules(payment), today };
+        self.rules.iter()
+            .filter(|rule| context.corridor.is_enabled(rule.name()))
+            .filter_map(|rule| rule.check(payment, &context))
+            .collect()
+    }
+}
+
+/// Rejection reason for a payment: the first violation's code, the others as additional info
+pub fn rejection_reason(violations: &[RuleViolation]) -> Option<StatusReason> {
+    let (first, rest) = violations.split_first()?;
+    Some(StatusReason {
+        code: first.code.to_string(),
+        description: first.message.clone(),
+        additional_info: (!rest.is_empty()).then(|| {
+            rest.iter().map(|v| format!("{}: {}", v.code, v.message)).collect::<Vec<_>>().join("; ")
+        }),
+    })
+}
+
+/// Country of an agent, falling back to its customer's
+fn corridor_countries(payment: &CanonicalPayment) -> (&str, &str) {
+    fn country<'a>(agent: &'a FinancialInstitution, party: &'a Party) -> &'a str {
+        [agent.country_code.as_str(), party.country_code.as_str()]
+            .into_iter()
+            .find(|c| is_known_country(c))
+            .unwrap_or("XX")
+    }
+    (country(&payment.debtor_agent, &payment.debtor), country(&payment.creditor_agent, &payment.creditor))
+}
+
+fn is_known_country(code: &str) -> bool {
+    code.len() == 2 && code.bytes().all(|b| b.is_ascii_uppercase()) && code != "XX"
+}
+
+fn normalized_iban(iban: &str) -> String {
+    iban.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_ascii_uppercase()
+}
+
+/// ISO 13616 structure and mod-97 check digits
+pub fn iban_is_valid(iban: &str) -> bool {
+    let iban = normalized_iban(iban);
+    let bytes = iban.as_bytes();
+    if !(15..=34).contains(&bytes.len())
+        || !bytes[..2].iter().all(u8::is_ascii_uppercase)
+        || !bytes[2..4].iter().all(u8::is_ascii_digit)
+        || !bytes.iter().all(u8::is_ascii_alphanumeric)
+    {
+        return false;
+    }
+
+    let remainder = iban[4..].chars().chain(iban[..4].chars()).fold(0u32, |acc, c| {
+        let value = c.to_digit(36).unwrap_or(0);
+        if value >= 10 { (acc * 100 + value) % 97 } else { (acc * 10 + value) % 97 }
+    });
+    remainder == 1
+}
+
+/// ISO 9362: 4 bank code, 2 country, 2 location, optional 3 branch characters
+pub fn bic_is_valid(bic: &str) -> bool {
+    let bytes = bic.as_bytes();
+    matches!(bytes.len(), 8 | 11)
+        && bytes.iter().all(|b| b.is_ascii_uppercase() || b.is_ascii_digit())
+        && bytes[4..6].iter().all(u8::is_ascii_uppercase)
+}
+
+fn accounts(payment: &CanonicalPayment) -> [(&'static str, &AccountIdentification, &Party); 2] {
+    [
+        ("debtor", &payment.debtor_account, &payment.debtor),
+        ("creditor", &payment.creditor_account, &payment.creditor),
+    ]
+}
+
+/// AC01 IncorrectAccountNumber
+pub struct IbanChecksum;
+
+impl BusinessRule for IbanChecksum {
+    fn name(&self) -> &'static str {
+        "iban_checksum"
+    }
+
+    fn check(&self, payment: &CanonicalPayment, _context: &RuleContext) -> Option<RuleViolation> {
+        accounts(payment).into_iter().find_map(|(role, account, _)| {
+            let iban = account.iban.as_deref()?;
+            (!iban_is_valid(iban)).then(|| RuleViolation {
+                rule: self.name(),
+                code: "AC01",
+                message: format!("Invalid {} IBAN {}", role, iban),
+            })
+        })
+    }
+}
+
+/// BE01 InconsistentWithEndCustomer - IBAN country differs from the account holder's country
+pub struct IbanCountry;
+
+impl BusinessRule for IbanCountry {
+    fn name(&self) -> &'static str {
+        "iban_country"
+    }
+
+    fn check(&self, payment: &CanonicalPayment, _context: &RuleContext) -> Option<RuleViolation> {
+        accounts(payment).into_iter().find_map(|(role, account, party)| {
+            let iban = normalized_iban(account.iban.as_deref()?);
+            let iban_country = iban.get(..2)?;
+            (is_known_country(&party.country_code) && iban_country != party.country_code).then(|| RuleViolation {
+                rule: self.name(),
+                code: "BE01",
+                message: format!("{} IBAN country {} does not match {} country {}", role, iban_country, role, party.country_code),
+            })
+        })
+    }
+}
+
+/// RC01 BankIdentifierIncorrect
+pub struct BicFormat;
+
+impl BusinessRule for BicFormat {
+    fn name(&self) -> &'static str {
+        "bic_format"
+    }
+
+    fn check(&self, payment: &CanonicalPayment, _context: &RuleContext) -> Option<RuleViolation> {
+        [("debtor agent", &payment.debtor_agent), ("creditor agent", &payment.creditor_agent)]
+            .into_iter()
+            .find_map(|(role, agent)| {
+                let bic = agent.bic.as_deref()?;
+                (!bic_is_valid(bic)).then(|| RuleViolation {
+                    rule: self.name(),
+                    code: "RC01",
+                    message: format!("Invalid {} BIC {}", role, bic),
+                })
+            })
+    }
+}
+
+/// CH04 RequestedExecutionDateTooFarInPast
+pub struct ExecutionDate;
+
+impl BusinessRule for ExecutionDate {
+    fn name(&self) -> &'static str {
+        "execution_date"
+    }
+
+    fn check(&self, payment: &CanonicalPayment, context: &RuleContext) -> Option<RuleViolation> {
+        let date = payment.requested_execution_date?;
+        (date < context.today).then(|| RuleViolation {
+            rule: self.name(),
+            code: "CH04",
+            message: format!("Requested execution date {} is in the past", date),
+        })
+    }
+}
+
+/// CH17 ElementNotAdmitted - ChrgBr not accepted on the corridor
+pub struct ChargeBearerAllowed;
+
+impl BusinessRule for ChargeBearerAllowed {
+    fn name(&self) -> &'static str {
+        "charge_bearer"
+    }
+
+    fn check(&self, payment: &CanonicalPayment, context: &RuleContext) -> Option<RuleViolation> {
+        let allowed = &context.corridor.allowed_charge_bearers;
+        let code = payment.charge_bearer.code();
+        (!allowed.is_empty() && !allowed.iter().any(|c| c == code)).then(|| RuleViolation {
+            rule: self.name(),
+            code: "CH17",
+            message: format!("Charge bearer {} not allowed on corridor {}->{} (allowed: {})",
+                             code, context.corridor.from, context.corridor.to, allowed.join(", ")),
+        })
+    }
+}
+
+/// CH21 RequiredCompulsoryElementMissing - Purp/Cd on corridors that require it (e.g. INR inbound)
+pub struct PurposeCodeRequired;
+
+impl BusinessRule for PurposeCodeRequired {
+    fn name(&self) -> &'static str {
+        "purpose_code"
+    }
+
+    fn check(&self, payment: &CanonicalPayment, context: &RuleContext) -> Option<RuleViolation> {
+        let missing = !matches!(payment.purpose_code.as_deref(), Some(code) if !code.trim().is_empty());
+        (context.corridor.purpose_code_required && missing).then(|| RuleViolation {
+            rule: self.name(),
+            code: "CH21",
+            message: format!("Purpose code is required on corridor {}->{}", context.corridor.from, context.corridor.to),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::canonical::Currency;
+    use rust_decimal_macros::dec;
+
+    const CONFIG: &str = r#"{
+        "defaults": { "allowed_charge_bearers": ["SHAR", "SLEV", "DEBT", "CRED"] },
+        "corridors": [
+            { "to": "IN", "allowed_charge_bearers": ["SHAR", "DEBT"], "purpose_code_required": true },
+            { "from": "AE", "to": "IN", "allowed_charge_bearers": ["SHAR"], "purpose_code_required": true,
+              "disabled_rules": ["iban_country"] }
+        ]
+    }"#;
+
+    fn engine() -> RuleEngine {
+        RuleEngine::new(serde_json::from_str(CONFIG).unwrap())
+    }
+
+    fn payment(from: &str, to: &str) -> CanonicalPayment {
+        let party = |country: &str| Party {
+            name: "Party".to_string(),
+            postal_address: None,
+            identification: None,
+            country_code: country.to_string(),
+        };
+        let agent = |bic: &str, country: &str| FinancialInstitution {
+            bic: Some(bic.to_string()),
+            name: "Bank".to_string(),
+            country_code: country.to_string(),
+            clearing_system_member_id: None,
+        };
+
+        let mut payment = CanonicalPayment::new(
+            "E2E-RULES".to_string(), "INSTR-RULES".to_string(), "MSG-RULES".to_string(),
+            dec!(100.00), Currency::AED,
+            party(from), party(to),
+            agent(&format!("BANK{}AAXXX", from), from), agent(&format!("BANK{}BBXXX", to), to),
+        );
+        payment.debtor_account.iban = Some("AE07 0331 2345 6789 0123 456".to_string());
+        payment.purpose_code = Some("SALA".to_string());
+        payment
+    }
+
+    fn codes(violations: &[RuleViolation]) -> Vec<&'static str> {
+        violations.iter().map(|v| v.code).collect()
+    }
+
+    #[test]
+    fn test_iban_and_bic_format() {
+        assert!(iban_is_valid("AE070331234567890123456"));
+        assert!(iban_is_valid("GB82 WEST 1234 5698 7654 32"));
+        assert!(!iban_is_valid("GB82WEST12345698765433"));
+        assert!(!iban_is_valid("GB82"));
+
+        assert!(bic_is_valid("DEUTDEFF"));
+        assert!(bic_is_valid("DLTRAEADXXX"));
+        assert!(!bic_is_valid("BANKAEXXXX"));
+        assert!(!bic_is_valid("DEUT1EFF"));
+    }
+
+    #[test]
+    fn test_valid_payment_passes() {
+        let today = NaiveDate::from_ymd_opt(2025, 1, 25).unwrap();
+        assert!(engine().validate_on(&payment("AE", "GB"), today).is_empty());
+    }
+
+    #[test]
+    fn test_account_and_date_rules() {
+        let today = NaiveDate::from_ymd_opt(2025, 1, 25).unwrap();
+        let mut payment = payment("SA", "GB");
+        payment.creditor_account.iban = Some("GB82WEST12345698765433".to_string());
+        payment.creditor_agent.bic = Some("WEST-GB".to_string());
+        payment.requested_execution_date = NaiveDate::from_ymd_opt(2025, 1, 24);
+
+        // Debtor IBAN is Emirati but the debtor is in SA
+        assert_eq!(codes(&engine().validate_on(&payment, today)), ["AC01", "BE01", "RC01", "CH04"]);
+    }
+
+    #[test]
+    fn test_corridor_resolution() {
+        let today = NaiveDate::from_ymd_opt(2025, 1, 25).unwrap();
+        let engine = engine();
+
+        // *->IN: purpose required, CRED not allowed
+        let mut from_gb = payment("GB", "IN");
+        from_gb.debtor_account.iban = None;
+        from_gb.purpose_code = None;
+        from_gb.charge_bearer = ChargeBearer::Cred;
+        assert_eq!(codes(&engine.validate_on(&from_gb, today)), ["CH17", "CH21"]);
+
+        // AE->IN is more specific: DEBT not allowed, IBAN country check disabled
+        let mut from_ae = payment("AE", "IN");
+        from_ae.debtor.country_code = "IN".to_string();
+        from_ae.charge_bearer = ChargeBearer::Debt;
+        assert_eq!(engine.corridor_rules(&from_ae).from, "AE");
+        assert_eq!(codes(&engine.validate_on(&from_ae, today)), ["CH17"]);
+    }
+
+    #[test]
+    fn test_rejection_reason() {
+        let violations = vec![
+            RuleViolation { rule: "iban_checksum", code: "AC01", message: "Invalid creditor IBAN".to_string() },
+            RuleViolation { rule: "purpose_code", code: "CH21", message: "Purpose code is required".to_string() },
+        ];
+        let reason = rejection_reason(&violations).unwrap();
+        assert_eq!(reason.code, "AC01");
+        assert_eq!(reason.additional_info.as_deref(), Some("CH21: Purpose code is required"));
+        assert!(rejection_reason(&[]).is_none());
+    }
+
+    #[test]
+    fn test_config_rejects_unknown_names() {
+        let engine = RuleEngine::new(serde_json::from_str(r#"{ "corridors": [{ "disabled_rules": ["nope"] }] }"#).unwrap());
+        assert!(engine.check_config().is_err());
+
+        let engine = RuleEngine::new(serde_json::from_str(r#"{ "defaults": { "allowed_charge_bearers": ["OUR"] } }"#).unwrap());
+        assert!(engine.check_config().is_err());
+    }
+
+    #[test]
+    fn test_shipped_config_loads() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/config/corridor_rules.json");
+        assert!(RuleEngine::load(Path::new(path)).unwrap().corridor_count() > 0);
+    }
+}
@@ -0,0 +1,939 @@
+// XSD Validation - checks inbound ISO 20022 documents against the catalog schemas
+// Schemas are loaded once at startup from iso_message_catalog.json (xsd_location)
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use regex::Regex;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+/// Namespace prefix of every ISO 20022 message definition
+pub const ISO_NAMESPACE_PREFIX: &str = "urn:iso:std:iso:20022:tech:xsd:";
+
+/// Upper bound on violations collected per message (keeps error bodies bounded)
+const MAX_VIOLATIONS: usize = 50;
+
+/// How to treat documents whose message definition has no loaded schema
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationMode {
+    Strict,     // Reject unknown message definitions
+    Lenient,    // Skip validation for unknown message definitions
+}
+
+impl ValidationMode {
+    pub fn from_env_value(value: &str) -> Self {
+        match value.to_lowercase().as_str() {
+            "strict" => ValidationMode::Strict,
+            _ => ValidationMode::Lenient,
+        }
+    }
+}
+
+/// Kind of XSD constraint that failed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ConstraintKind {
+    WellFormedness,     // Not parseable XML
+    Namespace,          // Missing/unsupported message definition
+    Cardinality,        // minOccurs / maxOccurs
+    UnexpectedElement,  // Element not allowed at this position
+    Attribute,          // Missing or invalid attribute
+    Datatype,           // Built-in type (decimal, date, dateTime...)
+    Pattern,            // xs:pattern facet
+    Length,             // xs:length / minLength / maxLength
+    Enumeration,        // xs:enumeration facet
+    Digits,             // xs:totalDigits / fractionDigits
+    Range,              // xs:minInclusive / maxInclusive
+}
+
+/// A single XSD violation with the failing location and facet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct XsdViolation {
+    pub xpath: String,
+    pub constraint: ConstraintKind,
+    pub facet: String,              // e.g. "minOccurs=1", "pattern=[A-Z]{3,3}"
+    pub value: Option<String>,      // Offending value (if any)
+    pub message: String,
+}
+
+/// Result of validating one document against its schema
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct XsdValidationReport {
+    pub message_type: String,                  // e.g. "pain.001"
+    pub message_definition: Option<String>,    // e.g. "pain.001.001.12"
+    pub violations: Vec<XsdViolation>,
+}
+
+impl fmt::Display for XsdValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} failed XSD validation ({} violation(s))",
+            self.message_definition.as_deref().unwrap_or(&self.message_type),
+            self.violations.len()
+        )?;
+        if let Some(first) = self.violations.first() {
+            write!(f, ": {} at {}", first.message, first.xpath)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for XsdValidationReport {}
+
+// ===== Schema model =====
+
+#[derive(Debug, Clone)]
+struct ElementDecl {
+    name: String,
+    type_name: String,
+    min_occurs: usize,
+    max_occurs: Option<usize>,  // None = unbounded
+}
+
+#[derive(Debug, Clone)]
+enum Particle {
+    Element(ElementDecl),
+    Any,
+}
+
+#[derive(Debug, Clone)]
+struct AttributeDecl {
+    name: String,
+    type_name: String,
+    required: bool,
+}
+
+#[derive(Debug, Clone)]
+enum ComplexContent {
+    Sequence(Vec<Particle>),
+    Choice(Vec<Particle>),
+    SimpleContent { base: String, attributes: Vec<AttributeDecl> },
+}
+
+#[derive(Debug, Clone)]
+struct PatternFacet {
+    source: String,
+    regex: Regex,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Facets {
+    patterns: Vec<PatternFacet>,
+    enumeration: Vec<String>,
+    length: Option<usize>,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    total_digits: Option<u32>,
+    fraction_digits: Option<u32>,
+    min_inclusive: Option<Decimal>,
+    max_inclusive: Option<Decimal>,
+}
+
+#[derive(Debug, Clone)]
+struct SimpleTypeDef {
+    base: String,
+    facets: Facets,
+}
+
+/// Compiled representation of one ISO 20022 XSD
+#[derive(Debug, Clone)]
+pub struct SchemaModel {
+    pub message_definition: String,    // e.g. "pain.001.001.12"
+    pub target_namespace: String,
+    root_element: String,
+    root_type: String,
+    complex_types: HashMap<String, ComplexContent>,
+    simple_types: HashMap<String, SimpleTypeDef>,
+}
+
+fn attr_value(e: &BytesStart, name: &str) -> Result<Option<String>> {
+    for attr in e.attributes() {
+        let attr = attr.map_err(|err| anyhow!("Invalid XSD attribute: {}", err))?;
+        if attr.key.as_ref() == name.as_bytes() {
+            return Ok(Some(attr.unescape_value()?.into_owned()));
+        }
+    }
+    Ok(None)
+}
+
+fn parse_occurs(value: Option<String>, default: usize) -> Result<Option<usize>> {
+    match value.as_deref() {
+        None => Ok(Some(default)),
+        Some("unbounded") => Ok(None),
+        Some(v) => Ok(Some(v.parse().context(format!("Invalid occurrence value: {}", v))?)),
+    }
+}
+
+impl SchemaModel {
+    /// Parse an ISO 20022 XSD (flat complexType/simpleType layout as generated by Standards Editor)
+    pub fn parse(xsd: &str) -> Result<Self> {
+        let mut reader = Reader::from_str(xsd);
+        reader.trim_text(true);
+
+        let mut target_namespace = String::new();
+        let mut root_element = String::new();
+        let mut root_type = String::new();
+        let mut complex_types = HashMap::new();
+        let mut simple_types = HashMap::new();
+
+        // Parser state for the type currently being read
+        let mut current_complex: Option<String> = None;
+        let mut current_simple: Option<String> = None;
+        let mut group: Option<(bool, Vec<Particle>)> = None; // (is_choice, particles)
+        let mut simple_base: Option<String> = None;
+        let mut attributes: Vec<AttributeDecl> = Vec::new();
+        let mut facets = Facets::default();
+
+        loop {
+            let event = reader.read_event().context("Failed to read XSD")?;
+            let (e, is_empty) = match &event {
+                Event::Start(e) => (e.clone(), false),
+                Event::Empty(e) => (e.clone(), true),
+                Event::End(e) => {
+                    match e.local_name().as_ref() {
+                        b"complexType" => {
+                            if let Some(name) = current_complex.take() {
+                                let content = match (group.take(), simple_base.take()) {
+                                    (Some((true, particles)), _) => ComplexContent::Choice(particles),
+                                    (Some((false, particles)), _) => ComplexContent::Sequence(particles),
+                                    (None, Some(base)) => ComplexContent::SimpleContent {
+                                        base,
+                                        attributes: std::mem::take(&mut attributes),
+                                    },
+                                    (None, None) => ComplexContent::Sequence(Vec::new()),
+                                };
+                                complex_types.insert(name, content);
+                            }
+                        }
+                        b"simpleType" => {
+                            if let Some(name) = current_simple.take() {
+                                simple_types.insert(name, SimpleTypeDef {
+                                    base: simple_base.take().unwrap_or_else(|| "xs:string".to_string()),
+                                    facets: std::mem::take(&mut facets),
+                                });
+                            }
+                        }
+                        _ => {}
+                    }
+                    continue;
+                }
+                Event::Eof => break,
+                _ => continue,
+            };
+
+            match e.local_name().as_ref() {
+                b"schema" => {
+                    target_namespace = attr_value(&e, "targetNamespace")?.unwrap_or_default();
+                }
+                b"element" if current_complex.is_none() => {
+                    // Global element declaration (Document)
+                    root_element = attr_value(&e, "name")?.unwrap_or_default();
+                    root_type = attr_value(&e, "type")?.unwrap_or_default();
+                }
+                b"element" => {
+                    let decl = ElementDecl {
+                        name: attr_value(&e, "name")?.unwrap_or_default(),
+                        type_name: attr_value(&e, "type")?.unwrap_or_else(|| "xs:string".to_string()),
+                        min_occurs: parse_occurs(attr_value(&e, "minOccurs")?, 1)?.unwrap_or(0),
+                        max_occurs: parse_occurs(attr_value(&e, "maxOccurs")?, 1)?,
+                    };
+                    if let Some((_, particles)) = group.as_mut() {
+                        particles.push(Particle::Element(decl));
+                    }
+                }
+                b"any" => {
+                    if let Some((_, particles)) = group.as_mut() {
+                        particles.push(Particle::Any);
+                    }
+                }
+                b"complexType" => {
+                    let name = attr_value(&e, "name")?.unwrap_or_default();
+                    if is_empty {
+                        complex_types.insert(name, ComplexContent::Sequence(Vec::new()));
+                    } else {
+                        current_complex = Some(name);
+                    }
+                }
+                b"sequence" => group = Some((false, Vec::new())),
+                b"choice" => group = Some((true, Vec::new())),
+                b"extension" => simple_base = attr_value(&e, "base")?,
+                b"attribute" => attributes.push(AttributeDecl {
+                    name: attr_value(&e, "name")?.unwrap_or_default(),
+                    type_name: attr_value(&e, "type")?.unwrap_or_else(|| "xs:string".to_string()),
+                    required: attr_value(&e, "use")?.as_deref() == Some("required"),
+                }),
+                b"simpleType" => {
+                    current_simple = attr_value(&e, "name")?;
+                }
+                b"restriction" => simple_base = attr_value(&e, "base")?,
+                facet => {
+                    if current_simple.is_none() {
+                        continue;
+                    }
+                    let value = attr_value(&e, "value")?.unwrap_or_default();
+                    match facet {
+                        b"pattern" => match Regex::new(&format!("^(?:{})$", value)) {
+                            Ok(regex) => facets.patterns.push(PatternFacet { source: value, regex }),
+                            Err(err) => warn!("Skipping uncompilable XSD pattern {}: {}", value, err),
+                        },
+                        b"enumeration" => facets.enumeration.push(value),
+                        b"length" => facets.length = value.parse().ok(),
+                        b"minLength" => facets.min_length = value.parse().ok(),
+                        b"maxLength" => facets.max_length = value.parse().ok(),
+                        b"totalDigits" => facets.total_digits = value.parse().ok(),
+                        b"fractionDigits" => facets.fraction_digits = value.parse().ok(),
+                        b"minInclusive" => facets.min_inclusive = Decimal::from_str(&value).ok(),
+                        b"maxInclusive" => facets.max_inclusive = Decimal::from_str(&value).ok(),
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        if root_element.is_empty() || root_type.is_empty() {
+            return Err(anyhow!("XSD has no global element declaration"));
+        }
+
+        let message_definition = target_namespace
+            .strip_prefix(ISO_NAMESPACE_PREFIX)
+            .unwrap_or(&target_namespace)
+            .to_string();
+
+        Ok(Self {
+            message_definition,
+            target_namespace,
+            root_element,
+            root_type,
+            complex_types,
+            simple_types,
+        })
+    }
+
+    /// Validate a parsed instance document against this schema
+    fn validate(&self, root: &XmlNode, violations: &mut Vec<XsdViolation>) {
+        let path = format!("/{}", root.name);
+        if root.name != self.root_element {
+            violations.push(XsdViolation {
+                xpath: path,
+                constraint: ConstraintKind::UnexpectedElement,
+                facet: format!("element={}", self.root_element),
+                value: Some(root.name.clone()),
+                message: format!("Root element must be {}", self.root_element),
+            });
+            return;
+        }
+        self.validate_element(root, &self.root_type, &path, violations);
+    }
+
+    fn validate_element(&self, node: &XmlNode, type_name: &str, path: &str, violations: &mut Vec<XsdViolation>) {
+        if violations.len() >= MAX_VIOLATIONS {
+            return;
+        }
+
+        match self.complex_types.get(type_name) {
+            Some(ComplexContent::Sequence(particles)) => {
+                self.validate_sequence(node, particles, path, violations);
+            }
+            Some(ComplexContent::Choice(particles)) => {
+                self.validate_choice(node, particles, path, violations);
+            }
+            Some(ComplexContent::SimpleContent { base, attributes }) => {
+                self.reject_children(node, path, violations);
+                self.validate_simple(&node.text, base, path, violations);
+                for decl in attributes {
+                    let attr_path = format!("{}/@{}", path, decl.name);
+                    match node.attribute(&decl.name) {
+                        Some(value) => self.validate_simple(value, &decl.type_name, &attr_path, violations),
+                        None if decl.required => violations.push(XsdViolation {
+                            xpath: attr_path,
+                            constraint: ConstraintKind::Attribute,
+                            facet: "use=required".to_string(),
+                            value: None,
+                            message: format!("Missing required attribute {}", decl.name),
+                        }),
+                        None => {}
+                    }
+                }
+            }
+            None => {
+                self.reject_children(node, path, violations);
+                self.validate_simple(&node.text, type_name, path, violations);
+            }
+        }
+    }
+
+    fn reject_children(&self, node: &XmlNode, path: &str, violations: &mut Vec<XsdViolation>) {
+        for child in &node.children {
+            violations.push(XsdViolation {
+                xpath: format!("{}/{}", path, child.name),
+                constraint: ConstraintKind::UnexpectedElement,
+                facet: "simpleType".to_string(),
+                value: None,
+                message: format!("Element {} is not allowed in simple content", child.name),
+            });
+        }
+    }
+
+    /// Consume consecutive children named like `decl`, validating each and checking cardinality
+    fn consume_particle(
+        &self,
+        children: &[XmlNode],
+        start: usize,
+        decl: &ElementDecl,
+        path: &str,
+        violations: &mut Vec<XsdViolation>,
+    ) -> usize {
+        let mut idx = start;
+        while idx < children.len() && children[idx].name == decl.name {
+            idx += 1;
+        }
+        let count = idx - start;
+        let repeated = decl.max_occurs != Some(1);
+
+        for (i, child) in children[start..idx].iter().enumerate() {
+            let child_path = if repeated {
+                format!("{}/{}[{}]", path, decl.name, i + 1)
+            } else {
+                format!("{}/{}", path, decl.name)
+            };
+            self.validate_element(child, &decl.type_name, &child_path, violations);
+        }
+
+        if count < decl.min_occurs {
+            violations.push(XsdViolation {
+                xpath: format!("{}/{}", path, decl.name),
+                constraint: ConstraintKind::Cardinality,
+                facet: format!("minOccurs={}", decl.min_occurs),
+                value: Some(count.to_string()),
+                message: format!("Expected at least {} {} element(s), found {}", decl.min_occurs, decl.name, count),
+            });
+        }
+        if let Some(max) = decl.max_occurs {
+            if count > max {
+                violations.push(XsdViolation {
+                    xpath: format!("{}/{}", path, decl.name),
+                    constraint: ConstraintKind::Cardinality,
+                    facet: format!("maxOccurs={}", max),
+                    value: Some(count.to_string()),
+                    message: format!("Expected at most {} {} element(s), found {}", max, decl.name, count),
+                });
+            }
+        }
+
+        idx
+    }
+
+    fn validate_sequence(&self, node: &XmlNode, particles: &[Particle], path: &str, violations: &mut Vec<XsdViolation>) {
+        let mut idx = 0;
+        for particle in particles {
+            match particle {
+                Particle::Element(decl) => {
+                    idx = self.consume_particle(&node.children, idx, decl, path, violations);
+                }
+                Particle::Any => {
+                    // processContents="lax" - accept any single element
+                    if idx < node.children.len() {
+                        idx += 1;
+                    }
+                }
+            }
+        }
+        self.report_unexpected(&node.children[idx..], path, violations);
+    }
+
+    fn validate_choice(&self, node: &XmlNode, particles: &[Particle], path: &str, violations: &mut Vec<XsdViolation>) {
+        let alternatives: Vec<&ElementDecl> = particles.iter()
+            .filter_map(|p| match p {
+                Particle::Element(decl) => Some(decl),
+                Particle::Any => None,
+            })
+            .collect();
+
+        let Some(first) = node.children.first() else {
+            if alternatives.iter().all(|decl| decl.min_occurs > 0) {
+                let names: Vec<&str> = alternatives.iter().map(|decl| decl.name.as_str()).collect();
+                violations.push(XsdViolation {
+                    xpath: path.to_string(),
+                    constraint: ConstraintKind::Cardinality,
+                    facet: format!("choice={}", names.join("|")),
+                    value: Some("0".to_string()),
+                    message: format!("Expected one of {}", names.join(", ")),
+                });
+            }
+            return;
+        };
+
+        match alternatives.iter().find(|decl| decl.name == first.name) {
+            Some(decl) => {
+                let idx = self.consume_particle(&node.children, 0, decl, path, violations);
+                self.report_unexpected(&node.children[idx..], path, violations);
+            }
+            None => self.report_unexpected(&node.children, path, violations),
+        }
+    }
+
+    fn report_unexpected(&self, children: &[XmlNode], path: &str, violations: &mut Vec<XsdViolation>) {
+        for child in children {
+            if violations.len() >= MAX_VIOLATIONS {
+                return;
+            }
+            violations.push(XsdViolation {
+                xpath: format!("{}/{}", path, child.name),
+                constraint: ConstraintKind::UnexpectedElement,
+                facet: "sequence".to_string(),
+                value: None,
+                message: format!("Element {} is not expected here (unknown or out of order)", child.name),
+            });
+        }
+    }
+
+    fn validate_simple(&self, value: &str, type_name: &str, path: &str, violations: &mut Vec<XsdViolation>) {
+        let (base, facets) = match self.simple_types.get(type_name) {
+            Some(def) => (def.base.as_str(), Some(&def.facets)),
+            None => (type_name, None),
+        };
+
+        let mut push = |constraint: ConstraintKind, facet: String, message: String| {
+            violations.push(XsdViolation {
+                xpath: path.to_string(),
+                constraint,
+                facet,
+                value: Some(value.to_string()),
+                message,
+            });
+        };
+
+        if !is_valid_builtin(base, value) {
+            push(ConstraintKind::Datatype, format!("type={}", base), format!("Value is not a valid {}", base));
+            return;
+        }
+
+        let Some(facets) = facets else { return };
+        let char_count = value.chars().count();
+
+        if let Some(length) = facets.length {
+            if char_count != length {
+                push(ConstraintKind::Length, format!("length={}", length), format!("Length must be exactly {}", length));
+            }
+        }
+        if let Some(min) = facets.min_length {
+            if char_count < min {
+                push(ConstraintKind::Length, format!("minLength={}", min), format!("Length must be at least {}", min));
+            }
+        }
+        if let Some(max) = facets.max_length {
+            if char_count > max {
+                push(ConstraintKind::Length, format!("maxLength={}", max), format!("Length must be at most {}", max));
+            }
+        }
+        // Multiple patterns in one restriction are OR-ed
+        if !facets.patterns.is_empty() && !facets.patterns.iter().any(|p| p.regex.is_match(value)) {
+            let sources: Vec<&str> = facets.patterns.iter().map(|p| p.source.as_str()).collect();
+            push(
+                ConstraintKind::Pattern,
+                format!("pattern={}", sources.join("|")),
+                format!("Value does not match pattern of {}", type_name),
+            );
+        }
+        if !facets.enumeration.is_empty() && !facets.enumeration.iter().any(|e| e == value) {
+            push(
+                ConstraintKind::Enumeration,
+                format!("enumeration={}", facets.enumeration.join("|")),
+                format!("Value is not a valid {} code", type_name),
+            );
+        }
+
+        if facets.total_digits.is_some() || facets.fraction_digits.is_some()
+            || facets.min_inclusive.is_some() || facets.max_inclusive.is_some()
+        {
+            let (integer_digits, fraction_digits) = decimal_digits(value);
+            if let Some(total) = facets.total_digits {
+                if integer_digits + fraction_digits > total {
+                    push(ConstraintKind::Digits, format!("totalDigits={}", total), format!("At most {} digits allowed", total));
+                }
+            }
+            if let Some(fraction) = facets.fraction_digits {
+                if fraction_digits > fraction {
+                    push(ConstraintKind::Digits, format!("fractionDigits={}", fraction), format!("At most {} fraction digits allowed", fraction));
+                }
+            }
+            if let Ok(number) = Decimal::from_str(value) {
+                if let Some(min) = facets.min_inclusive {
+                    if number < min {
+                        push(ConstraintKind::Range, format!("minInclusive={}", min), format!("Value must be >= {}", min));
+                    }
+                }
+                if let Some(max) = facets.max_inclusive {
+                    if number > max {
+                        push(ConstraintKind::Range, format!("maxInclusive={}", max), format!("Value must be <= {}", max));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Significant integer and fraction digits of a decimal literal
+fn decimal_digits(value: &str) -> (u32, u32) {
+    let unsigned = value.trim_start_matches(['+', '-']);
+    let (integer, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    let integer = integer.trim_start_matches('0');
+    let fraction = fraction.trim_end_matches('0');
+    (integer.len() as u32, fraction.len() as u32)
+}
+
+/// Check lexical space of the XSD built-in types used by ISO 20022 schemas
+fn is_valid_builtin(base: &str, value: &str) -> bool {
+    match base {
+        "xs:decimal" => {
+            let unsigned = value.trim_start_matches(['+', '-']);
+            !unsigned.is_empty()
+                && unsigned.chars().all(|c| c.is_ascii_digit() || c == '.')
+                && unsigned.matches('.').count() <= 1
+                && unsigned != "."
+        }
+        "xs:boolean" => matches!(value, "true" | "false" | "1" | "0"),
+        "xs:date" => {
+            // Optional timezone suffix (Z or +hh:mm) is allowed on xs:date
+            let date = value.get(..10).unwrap_or(value);
+            NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok() && is_timezone_suffix(&value[date.len()..])
+        }
+        "xs:dateTime" => {
+            DateTime::parse_from_rfc3339(value).is_ok()
+                || NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f").is_ok()
+        }
+        "xs:time" => {
+            let time = value.get(..8).unwrap_or(value);
+            NaiveTime::parse_from_str(time, "%H:%M:%S").is_ok()
+                && {
+                    let rest = &value[time.len()..];
+                    let rest = rest.strip_prefix('.')
+                        .map(|r| r.trim_start_matches(|c: char| c.is_ascii_digit()))
+                        .unwrap_or(rest);
+                    is_timezone_suffix(rest)
+                }
+        }
+        "xs:gYear" => value.len() == 4 && value.chars().all(|c| c.is_ascii_digit()),
+        "xs:gYearMonth" => NaiveDate::parse_from_str(&format!("{}-01", value), "%Y-%m-%d").is_ok(),
+        _ => true,
+    }
+}
+
+fn is_timezone_suffix(suffix: &str) -> bool {
+    suffix.is_empty()
+        || suffix == "Z"
+        || (suffix.len() == 6
+            && (suffix.starts_with('+') || suffix.starts_with('-'))
+            && NaiveTime::parse_from_str(&format!("{}:00", &suffix[1..]), "%H:%M:%S").is_ok())
+}
+
+// ===== Instance document =====
+
+#[derive(Debug, Default)]
+struct XmlNode {
+    name: String,
+    namespace: Option<String>,
+    attributes: Vec<(String, String)>,
+    children: Vec<XmlNode>,
+    text: String,
+}
+
+impl XmlNode {
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn from_start(e: &BytesStart) -> Result<Self> {
+        let mut node = XmlNode {
+            name: String::from_utf8_lossy(e.local_name().as_ref()).into_owned(),
+            ..Default::default()
+        };
+        for attr in e.attributes() {
+            let attr = attr.map_err(|err| anyhow!("Invalid attribute: {}", err))?;
+            let value = attr.unescape_value()?.into_owned();
+            if attr.key.as_ref() == b"xmlns" {
+                node.namespace = Some(value);
+            } else if attr.key.as_ref().starts_with(b"xmlns:") {
+                continue;
+            } else {
+                let key = String::from_utf8_lossy(attr.key.local_name().as_ref()).into_owned();
+                node.attributes.push((key, value));
+            }
+        }
+        Ok(node)
+    }
+}
+
+/// Parse an XML document into a lightweight element tree
+fn parse_instance(xml: &str) -> Result<XmlNode> {
+    let mut reader = Reader::from_str(xml);
+    reader.trim_text(true);
+
+    let mut stack: Vec<XmlNode> = Vec::new();
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => stack.push(XmlNode::from_start(&e)?),
+            Event::Empty(e) => {
+                let node = XmlNode::from_start(&e)?;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(node),
+                    None => return Ok(node),
+                }
+            }
+            Event::Text(t) => {
+                if let Some(current) = stack.last_mut() {
+                    current.text.push_str(&t.unescape()?);
+                }
+            }
+            Event::CData(t) => {
+                if let Some(current) = stack.last_mut() {
+                    current.text.push_str(&String::from_utf8_lossy(&t));
+                }
+            }
+            Event::End(_) => {
+                let node = stack.pop().ok_or_else(|| anyhow!("Unbalanced end tag"))?;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(node),
+                    None => return Ok(node),
+                }
+            }
+            Event::Eof => return Err(anyhow!("Unexpected end of document")),
+            _ => {}
+        }
+    }
+}
+
+// ===== Validator =====
+
+#[derive(Debug, Deserialize)]
+struct Catalog {
+    messages: HashMap<String, CatalogEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CatalogEntry {
+    xsd_location: Option<String>,
+}
+
+/// Holds every compiled schema from the ISO message catalog, keyed by message definition
+pub struct XsdValidator {
+    schemas: HashMap<String, SchemaModel>,
+    mode: ValidationMode,
+}
+
+impl XsdValidator {
+    pub fn new(schemas: Vec<SchemaModel>, mode: ValidationMode) -> Self {
+        Self {
+            schemas: schemas.into_iter()
+                .map(|schema| (schema.message_definition.clone(), schema))
+                .collect(),
+            mode,
+        }
+    }
+
+    /// Load all `xsd_location` entries of iso_message_catalog.json.
+    /// Locations are relative to the directory containing the `iso20022/` folder.
+    pub fn load_from_catalog(catalog_path: &Path, mode: ValidationMode) -> Result<Self> {
+        let raw = std::fs::read_to_string(catalog_path)
+            .context(format!("Failed to read ISO message catalog: {}", catalog_path.display()))?;
+        let catalog: Catalog = serde_json::from_str(&raw).context("Invalid ISO message catalog")?;
+
+        let schema_root: PathBuf = catalog_path.parent()
+            .and_then(Path::parent)
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+
+        let mut schemas = Vec::new();
+        for (message_id, entry) in &catalog.messages {
+            let Some(location) = &entry.xsd_location else { continue };
+            let path = schema_root.join(location);
+            let xsd = std::fs::read_to_string(&path)
+                .context(format!("Failed to read XSD for {}: {}", message_id, path.display()))?;
+            let schema = SchemaModel::parse(&xsd)
+                .context(format!("Failed to compile XSD for {}", message_id))?;
+            schemas.push(schema);
+        }
+
+        info!("📐 Loaded {} ISO 20022 XSD schemas ({:?} mode)", schemas.len(), mode);
+        Ok(Self::new(schemas, mode))
+    }
+
+    pub fn loaded_definitions(&self) -> Vec<&str> {
+        let mut ids: Vec<&str> = self.schemas.keys().map(String::as_str).collect();
+        ids.sort();
+        ids
+    }
+
+    /// Validate an inbound document of the given message type (e.g. "pain.001").
+    /// The exact schema version is selected from the document namespace.
+    pub fn validate(&self, message_type: &str, xml: &str) -> std::result::Result<(), XsdValidationReport> {
+        let fail = |definition: Option<String>, violation: XsdViolation| XsdValidationReport {
+            message_type: message_type.to_string(),
+            message_definition: definition,
+            violations: vec![violation],
+        };
+
+        let root = parse_instance(xml).map_err(|e| fail(None, XsdViolation {
+            xpath: "/".to_string(),
+            constraint: ConstraintKind::WellFormedness,
+            facet: "xml".to_string(),
+            value: None,
+            message: format!("Document is not well-formed: {}", e),
+        }))?;
+
+        let definition = root.namespace.as_deref()
+            .and_then(|ns| ns.strip_prefix(ISO_NAMESPACE_PREFIX))
+            .map(str::to_string);
+
+        let Some(definition) = definition.filter(|d| d.starts_with(&format!("{}.", message_type))) else {
+            return Err(fail(None, XsdViolation {
+                xpath: format!("/{}", root.name),
+                constraint: ConstraintKind::Namespace,
+                facet: format!("xmlns={}{}.*", ISO_NAMESPACE_PREFIX, message_type),
+                value: root.namespace.clone(),
+                message: format!("Document namespace is not a {} message definition", message_type),
+            }));
+        };
+
+        let Some(schema) = self.schemas.get(&definition) else {
+            if self.mode == ValidationMode::Lenient {
+                warn!("No XSD loaded for {} - skipping schema validation", definition);
+                return Ok(());
+            }
+            return Err(fail(Some(definition.clone()), XsdViolation {
+                xpath: format!("/{}", root.name),
+                constraint: ConstraintKind::Namespace,
+                facet: format!("xmlns={}{}", ISO_NAMESPACE_PREFIX, definition),
+                value: root.namespace.clone(),
+                message: format!("Unsupported message definition {}", definition),
+            }));
+        };
+
+        let mut violations = Vec::new();
+        schema.validate(&root, &mut violations);
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(XsdValidationReport {
+                message_type: message_type.to_string(),
+                message_definition: Some(definition),
+                violations,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pain001_validator() -> XsdValidator {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../../iso20022/payments_initiation/pain.001.001.12.xsd");
+        let xsd = std::fs::read_to_string(path).unwrap();
+        XsdValidator::new(vec![SchemaModel::parse(&xsd).unwrap()], ValidationMode::Strict)
+    }
+
+    fn pain001(amount: &str, currency: &str, bic: &str) -> String {
+        format!(r#"<?xml version="1.0" encoding="UTF-8"?>
+<Document xmlns="urn:iso:std:iso:20022:tech:xsd:pain.001.001.12">
+  <CstmrCdtTrfInitn>
+    <GrpHdr>
+      <MsgId>MSG-001</MsgId>
+      <CreDtTm>2025-11-18T14:30:00Z</CreDtTm>
+      <NbOfTxs>1</NbOfTxs>
+      <InitgPty><Nm>ACME Corp</Nm></InitgPty>
+    </GrpHdr>
+    <PmtInf>
+      <PmtInfId>PMT-001</PmtInfId>
+      <PmtMtd>TRF</PmtMtd>
+      <ReqdExctnDt><Dt>2025-11-19</Dt></ReqdExctnDt>
+      <Dbtr><Nm>John Doe</Nm></Dbtr>
+      <DbtrAcct><Id><IBAN>AE070331234567890123456</IBAN></Id></DbtrAcct>
+      <DbtrAgt><FinInstnId><BICFI>{bic}</BICFI></FinInstnId></DbtrAgt>
+      <CdtTrfTxInf>
+        <PmtId><InstrId>I1</InstrId><EndToEndId>E2E-001</EndToEndId></PmtId>
+        <Amt><InstdAmt Ccy="{currency}">{amount}</InstdAmt></Amt>
+        <CdtrAgt><FinInstnId><BICFI>ICICINBBXXX</BICFI></FinInstnId></CdtrAgt>
+        <Cdtr><Nm>Jane Smith</Nm></Cdtr>
+        <CdtrAcct><Id><Othr><Id>123456789012</Id></Othr></Id></CdtrAcct>
+      </CdtTrfTxInf>
+    </PmtInf>
+  </CstmrCdtTrfInitn>
+</Document>"#)
+    }
+
+    #[test]
+    fn test_valid_pain001_passes() {
+        let validator = pain001_validator();
+        let result = validator.validate("pain.001", &pain001("10000.00", "AED", "BANKAEADXXX"));
+        assert!(result.is_ok(), "Unexpected violations: {:?}", result.err());
+    }
+
+    #[test]
+    fn test_pattern_and_digits_violations() {
+        let validator = pain001_validator();
+        let report = validator.validate("pain.001", &pain001("10.123456", "aed", "BAD")).unwrap_err();
+
+        assert_eq!(report.message_definition.as_deref(), Some("pain.001.001.12"));
+        let pattern = report.violations.iter()
+            .find(|v| v.xpath.ends_with("/DbtrAgt/FinInstnId/BICFI"))
+            .expect("BIC pattern violation");
+        assert_eq!(pattern.constraint, ConstraintKind::Pattern);
+        assert!(pattern.facet.starts_with("pattern="));
+
+        assert!(report.violations.iter().any(|v|
+            v.xpath.ends_with("/Amt/InstdAmt/@Ccy") && v.constraint == ConstraintKind::Pattern));
+        assert!(report.violations.iter().any(|v| v.facet == "fractionDigits=5"));
+    }
+
+    #[test]
+    fn test_missing_mandatory_element() {
+        let validator = pain001_validator();
+        let xml = pain001("1.00", "AED", "BANKAEADXXX").replace("<MsgId>MSG-001</MsgId>", "");
+        let report = validator.validate("pain.001", &xml).unwrap_err();
+
+        let violation = &report.violations[0];
+        assert_eq!(violation.xpath, "/Document/CstmrCdtTrfInitn/GrpHdr/MsgId");
+        assert_eq!(violation.constraint, ConstraintKind::Cardinality);
+        assert_eq!(violation.facet, "minOccurs=1");
+    }
+
+    #[test]
+    fn test_unknown_version_modes() {
+        let xml = pain001("1.00", "AED", "BANKAEADXXX").replace("pain.001.001.12", "pain.001.001.09");
+
+        let strict = pain001_validator();
+        let report = strict.validate("pain.001", &xml).unwrap_err();
+        assert_eq!(report.violations[0].constraint, ConstraintKind::Namespace);
+
+        let lenient = XsdValidator { mode: ValidationMode::Lenient, ..pain001_validator() };
+        assert!(lenient.validate("pain.001", &xml).is_ok());
+    }
+
+    #[test]
+    fn test_wrong_message_type_rejected() {
+        let validator = pain001_validator();
+        let report = validator.validate("pacs.008", &pain001("1.00", "AED", "BANKAEADXXX")).unwrap_err();
+        assert_eq!(report.violations[0].constraint, ConstraintKind::Namespace);
+    }
+
+    #[test]
+    fn test_decimal_digits() {
+        assert_eq!(decimal_digits("10000.00"), (5, 0));
+        assert_eq!(decimal_digits("0.12345"), (0, 5));
+        assert_eq!(decimal_digits("-12.5"), (2, 1));
+    }
+}
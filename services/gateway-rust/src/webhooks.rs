@@ -0,0 +1,358 @@
+// Webhook Notifications - payment notifications delivered to participants' endpoints
+// Consumes deltran.notification.* (NatsRouter::notification_message) and stores one delivery per
+// subscribed endpoint of the payment's debtor and creditor agent. The dispatcher POSTs the event
+// signed like outbound ISO reports (detached JWS in X-JWS-Signature, keys at /signing-keys),
+// retries failures with exponential backoff and dead-letters a delivery after WEBHOOK_MAX_ATTEMPTS.
+// Delivery history and replay are served by /webhooks/deliveries.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use async_nats::Client as NatsClient;
+use chrono::Utc;
+use deltran_schema::EventEnvelope;
+use futures_util::future::join_all;
+use futures_util::StreamExt;
+use sqlx::PgPool;
+use tracing::{info, warn, error};
+
+use crate::db;
+use crate::metrics::METRICS;
+use crate::models::canonical::CanonicalPayment;
+use crate::models::webhook::{DeliveryState, WebhookDelivery, WebhookEvent};
+use crate::signing::{MessageSigner, SIGNATURE_HEADER};
+
+/// Subjects of NatsRouter::notification_message
+pub const NOTIFICATION_SUBJECTS: &str = "deltran.notification.>";
+
+/// Notification types an endpoint may subscribe to
+pub const EVENT_TYPES: [&str; 2] = ["status_update", "customer_status"];
+
+pub const DELIVERY_ID_HEADER: &str = "x-deltran-delivery-id";
+pub const EVENT_TYPE_HEADER: &str = "x-deltran-event-type";
+
+/// Default attempts before a delivery is dead-lettered (WEBHOOK_MAX_ATTEMPTS)
+pub const DEFAULT_WEBHOOK_MAX_ATTEMPTS: i32 = 8;
+
+/// Default endpoint timeout per attempt (WEBHOOK_TIMEOUT_SECS)
+pub const DEFAULT_WEBHOOK_TIMEOUT_SECS: u64 = 10;
+
+/// Deliveries attempted concurrently per dispatch round
+const DISPATCH_BATCH_SIZE: i64 = 50;
+
+/// Pause between dispatch rounds when nothing is due
+const DISPATCH_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Upper bound for the retry backoff
+const MAX_RETRY_DELAY_SECS: i64 = 3600;
+
+/// Error text kept per attempt (endpoint bodies can be large)
+const MAX_ERROR_LENGTH: usize = 500;
+
+/// Result of one HTTP attempt: status code on 2xx, otherwise status code (if any) and error
+pub type AttemptResult = std::result::Result<u16, (Option<u16>, String)>;
+
+pub struct WebhookDispatcher {
+    db: PgPool,
+    signer: Arc<MessageSigner>,
+    client: reqwest::Client,
+    max_attempts: i32,
+    timeout: Duration,
+}
+
+impl WebhookDispatcher {
+    pub fn new(db: PgPool, signer: Arc<MessageSigner>, max_attempts: i32, timeout: Duration) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .redirect(reqwest::redirect::Policy::none())
+            .build()?;
+        Ok(Self { db, signer, client, max_attempts, timeout })
+    }
+
+    pub fn max_attempts_from_env_value(value: &str) -> i32 {
+        value.trim().parse::<i32>().ok().filter(|attempts| *attempts > 0).unwrap_or_else(|| {
+            warn!("Invalid WEBHOOK_MAX_ATTEMPTS '{}', using {}", value, DEFAULT_WEBHOOK_MAX_ATTEMPTS);
+            DEFAULT_WEBHOOK_MAX_ATTEMPTS
+        })
+    }
+
+    pub fn timeout_from_env_value(value: &str) -> Duration {
+        let secs = value.trim().parse::<u64>().ok().filter(|secs| *secs > 0).unwrap_or_else(|| {
+            warn!("Invalid WEBHOOK_TIMEOUT_SECS '{}', using {}s", value, DEFAULT_WEBHOOK_TIMEOUT_SECS);
+            DEFAULT_WEBHOOK_TIMEOUT_SECS
+        });
+        Duration::from_secs(secs)
+    }
+
+    /// Delay before the next attempt after `attempts` failures: 5s, 10s, 20s, ... capped at 1h
+    pub fn retry_delay(attempts: i32) -> chrono::Duration {
+        let exponent = attempts.saturating_sub(1).clamp(0, 16) as u32;
+        chrono::Duration::seconds((5i64 << exponent).min(MAX_RETRY_DELAY_SECS))
+    }
+
+    /// Store a delivery for every endpoint of the payment's agents subscribed to `event_type`.
+    /// Returns the number of deliveries queued.
+    pub async fn notify(&self, event_type: &str, payment: &CanonicalPayment) -> Result<usize> {
+        let agents: Vec<String> = [&payment.debtor_agent.bic, &payment.creditor_agent.bic]
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect();
+        let endpoints = db::find_webhook_endpoints(&self.db, &agents).await?;
+
+        let event = WebhookEvent::from_payment(event_type, payment);
+        let mut queued = 0;
+        for endpoint in endpoints.iter().filter(|endpoint| endpoint.subscribes(event_type)) {
+            db::insert_webhook_delivery(&self.db, &WebhookDelivery::new(endpoint, &event)?).await?;
+            queued += 1;
+        }
+
+        if queued > 0 {
+            info!("🔔 {} notification for {} queued for {} endpoint(s)", event_type, payment.deltran_tx_id, queued);
+        }
+        Ok(queued)
+    }
+
+    /// POST the delivery's payload, signed, to its endpoint
+    pub async fn attempt(&self, delivery: &WebhookDelivery) -> AttemptResult {
+        let body = serde_json::to_vec(&delivery.payload).map_err(|e| (None, e.to_string()))?;
+        let signature = self.signer.sign(&body).map_err(|e| (None, format!("signing failed: {}", e)))?;
+
+        let start = Instant::now();
+        let response = self.client.post(&delivery.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(SIGNATURE_HEADER, signature)
+            .header(DELIVERY_ID_HEADER, delivery.delivery_id.to_string())
+            .header(EVENT_TYPE_HEADER, &delivery.event_type)
+            .body(body)
+            .send()
+            .await;
+        METRICS.webhook_delivery_duration_seconds.observe(start.elapsed().as_secs_f64());
+
+        let response = response.map_err(|e| (None, e.to_string()))?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(status.as_u16());
+        }
+
+        let body = response.text().await.unwrap_or_default();
+        Err((Some(status.as_u16()), truncate(&format!("HTTP {}: {}", status.as_u16(), body.trim()), MAX_ERROR_LENGTH)))
+    }
+
+    /// One attempt of a leased delivery, recorded with its outcome
+    pub async fn deliver(&self, mut delivery: WebhookDelivery) -> Result<WebhookDelivery> {
+        let result = self.attempt(&delivery).await;
+        apply_attempt(&mut delivery, result, self.max_attempts);
+        METRICS.track_webhook_delivery(match delivery.state {
+            DeliveryState::Delivered => "delivered",
+            DeliveryState::Pending => "retry",
+            DeliveryState::Dead => "dead",
+        });
+
+        match delivery.state {
+            DeliveryState::Delivered => info!("🔔 Webhook {} delivered to {} ({})",
+                                              delivery.delivery_id, delivery.participant_bic, delivery.url),
+            DeliveryState::Pending => warn!("🔔 Webhook {} to {} failed (attempt {}), retry at {}: {}",
+                                            delivery.delivery_id, delivery.url, delivery.attempts,
+                                            delivery.next_attempt_at, delivery.last_error.as_deref().unwrap_or("-")),
+            DeliveryState::Dead => error!("☠️ Webhook {} to {} dead-lettered after {} attempts: {}",
+                                          delivery.delivery_id, delivery.url, delivery.attempts,
+                                          delivery.last_error.as_deref().unwrap_or("-")),
+        }
+
+        db::record_webhook_attempt(&self.db, &delivery).await?;
+        Ok(delivery)
+    }
+
+    /// Attempt one batch of due deliveries concurrently. Returns the number delivered.
+    pub async fn dispatch_once(&self) -> Result<usize> {
+        // Leased for longer than an attempt can take, so no other dispatcher picks them up meanwhile
+        let lease_secs = self.timeout.as_secs() as i64 + 30;
+        let deliveries = db::lease_due_webhook_deliveries(&self.db, DISPATCH_BATCH_SIZE, lease_secs).await?;
+
+        let mut delivered = 0;
+        for result in join_all(deliveries.into_iter().map(|delivery| self.deliver(delivery))).await {
+            match result {
+                Ok(delivery) if delivery.state == DeliveryState::Delivered => delivered += 1,
+                Ok(_) => {}
+                Err(e) => error!("Failed to record webhook attempt: {}", e),
+            }
+        }
+
+        let (pending, dead) = db::webhook_backlog(&self.db).await?;
+        METRICS.webhook_pending_deliveries.set(pending);
+        METRICS.webhook_dead_deliveries.set(dead);
+        Ok(delivered)
+    }
+}
+
+/// Move a delivery on after an attempt: delivered, pending with backoff, or dead after `max_attempts`
+pub fn apply_attempt(delivery: &mut WebhookDelivery, result: AttemptResult, max_attempts: i32) {
+    let now = Utc::now();
+    delivery.attempts += 1;
+
+    match result {
+        Ok(status) => {
+            delivery.state = DeliveryState::Delivered;
+            delivery.last_status_code = Some(status as i32);
+            delivery.last_error = None;
+            delivery.delivered_at = Some(now);
+        }
+        Err((status, error)) => {
+            delivery.last_status_code = status.map(i32::from);
+            delivery.last_error = Some(error);
+            if delivery.attempts >= max_attempts {
+                delivery.state = DeliveryState::Dead;
+            } else {
+                delivery.state = DeliveryState::Pending;
+                delivery.next_attempt_at = now + WebhookDispatcher::retry_delay(delivery.attempts);
+            }
+        }
+    }
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+/// Queue a delivery per subscribed endpoint for every payment notification
+pub async fn start_webhook_consumer(dispatcher: Arc<WebhookDispatcher>, nats: NatsClient) -> Result<()> {
+    let mut subscriber = nats.subscribe(NOTIFICATION_SUBJECTS.to_string()).await?;
+    info!("📡 Webhooks subscribed to: {}", NOTIFICATION_SUBJECTS);
+
+    tokio::spawn(async move {
+        while let Some(msg) = subscriber.next().await {
+            let event_type = msg.subject.trim_start_matches("deltran.notification.").to_string();
+            let payment = match EventEnvelope::<CanonicalPayment>::from_slice(&msg.payload) {
+                Ok(envelope) => envelope.payload,
+                Err(e) => {
+                    error!("Unparseable {} notification: {}", msg.subject, e);
+                    continue;
+                }
+            };
+
+            if let Err(e) = dispatcher.notify(&event_type, &payment).await {
+                error!("Failed to queue {} webhooks for {}: {}", event_type, payment.deltran_tx_id, e);
+            }
+        }
+
+        warn!("⚠️ Webhook consumer for {} ended", NOTIFICATION_SUBJECTS);
+    });
+
+    Ok(())
+}
+
+/// Attempt due deliveries until the process exits; drains the backlog before sleeping
+pub fn start_webhook_dispatcher(dispatcher: Arc<WebhookDispatcher>) {
+    info!("🔔 Webhook dispatcher started (max {} attempts, timeout {:?})", dispatcher.max_attempts, dispatcher.timeout);
+
+    tokio::spawn(async move {
+        loop {
+            match dispatcher.dispatch_once().await {
+                Ok(0) => tokio::time::sleep(DISPATCH_POLL_INTERVAL).await,
+                Ok(_) => {}
+                Err(e) => {
+                    error!("Webhook dispatch failed: {}", e);
+                    tokio::time::sleep(DISPATCH_POLL_INTERVAL).await;
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::webhook::WebhookEndpoint;
+    use httpmock::prelude::*;
+    use uuid::Uuid;
+
+    fn dispatcher() -> WebhookDispatcher {
+        let db = PgPool::connect_lazy("postgres://localhost/unused").unwrap();
+        let signer = Arc::new(MessageSigner::load_test_keystore());
+        WebhookDispatcher::new(db, signer, 3, Duration::from_secs(5)).unwrap()
+    }
+
+    fn delivery(url: String) -> WebhookDelivery {
+        let endpoint = WebhookEndpoint {
+            endpoint_id: Uuid::new_v4(),
+            participant_bic: "BANKAEADXXX".to_string(),
+            url,
+            event_types: Vec::new(),
+            active: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        WebhookDelivery::new(&endpoint, &WebhookEvent {
+            event_id: Uuid::new_v4(),
+            event_type: "status_update".to_string(),
+            occurred_at: Utc::now(),
+            deltran_tx_id: Uuid::new_v4(),
+            uetr: None,
+            end_to_end_id: "E2E-HOOK".to_string(),
+            status: crate::models::canonical::PaymentStatus::Funded,
+            status_reason: None,
+            instructed_amount: rust_decimal_macros::dec!(100.00),
+            settlement_amount: rust_decimal_macros::dec!(100.00),
+            currency: "AED".to_string(),
+            debtor_agent_bic: Some("BANKAEADXXX".to_string()),
+            creditor_agent_bic: Some("OTHRINBBXXX".to_string()),
+        }).unwrap()
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(WebhookDispatcher::retry_delay(1), chrono::Duration::seconds(5));
+        assert_eq!(WebhookDispatcher::retry_delay(3), chrono::Duration::seconds(20));
+        assert_eq!(WebhookDispatcher::retry_delay(30), chrono::Duration::seconds(MAX_RETRY_DELAY_SECS));
+    }
+
+    #[test]
+    fn test_apply_attempt() {
+        let mut hook = delivery("http://127.0.0.1:1/hooks".to_string());
+
+        apply_attempt(&mut hook, Err((Some(503), "HTTP 503".to_string())), 2);
+        assert_eq!((hook.state, hook.attempts, hook.last_status_code), (DeliveryState::Pending, 1, Some(503)));
+        assert!(hook.next_attempt_at > Utc::now());
+
+        apply_attempt(&mut hook, Err((None, "connection refused".to_string())), 2);
+        assert_eq!((hook.state, hook.attempts, hook.last_status_code), (DeliveryState::Dead, 2, None));
+
+        let mut replay = hook.replay();
+        apply_attempt(&mut replay, Ok(204), 2);
+        assert_eq!(replay.state, DeliveryState::Delivered);
+        assert!(replay.delivered_at.is_some() && replay.last_error.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_attempt_against_stub() {
+        let server = MockServer::start_async().await;
+        let ok = server.mock_async(|when, then| {
+            when.method(POST)
+                .path("/hooks/ok")
+                .header("content-type", "application/json")
+                .header(EVENT_TYPE_HEADER, "status_update")
+                .header_exists(SIGNATURE_HEADER)
+                .header_exists(DELIVERY_ID_HEADER)
+                .body_contains("\"end_to_end_id\":\"E2E-HOOK\"");
+            then.status(200);
+        }).await;
+        let down = server.mock_async(|when, then| {
+            when.method(POST).path("/hooks/down");
+            then.status(503).body("maintenance");
+        }).await;
+
+        let dispatcher = dispatcher();
+        assert_eq!(dispatcher.attempt(&delivery(server.url("/hooks/ok"))).await, Ok(200));
+        assert_eq!(
+            dispatcher.attempt(&delivery(server.url("/hooks/down"))).await,
+            Err((Some(503), "HTTP 503: maintenance".to_string()))
+        );
+        ok.assert_async().await;
+        down.assert_async().await;
+    }
+}
@@ -9,6 +9,7 @@ use uuid::Uuid;
 use futures_util::StreamExt;
 use rust_decimal::Decimal;
 use chrono::Utc;
+use deltran_schema::payment::CanonicalPayment;
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct NetPosition {
@@ -29,34 +30,6 @@ pub struct NetPosition {
     pub amount_saved: Decimal,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
-pub struct CanonicalPayment {
-    pub deltran_tx_id: Uuid,
-    pub uetr: Option<Uuid>,
-    pub end_to_end_id: String,
-    pub instruction_id: String,
-    pub instructed_amount: Decimal,
-    pub settlement_amount: Decimal,
-    pub currency: String,
-    pub debtor: Party,
-    pub creditor: Party,
-    pub debtor_agent: FinancialInstitution,
-    pub creditor_agent: FinancialInstitution,
-    pub status: String,
-}
-
-#[derive(Debug, Deserialize, Serialize, Clone)]
-pub struct Party {
-    pub name: String,
-}
-
-#[derive(Debug, Deserialize, Serialize, Clone)]
-pub struct FinancialInstitution {
-    pub bic: String,
-    pub name: Option<String>,
-    pub country: Option<String>,
-}
-
 #[derive(Debug, Deserialize)]
 pub struct LocalLiquidityRequest {
     pub payment: CanonicalPayment,
@@ -2,50 +2,14 @@
 // Listens to deltran.obligation.create and creates payment obligations
 
 use async_nats::Client;
-use serde::{Deserialize, Serialize};
 use tracing::{info, error, warn};
 use uuid::Uuid;
 use futures_util::StreamExt;
-use rust_decimal::Decimal;
-
-#[derive(Debug, Deserialize, Serialize, Clone)]
-pub struct CanonicalPayment {
-    pub deltran_tx_id: Uuid,
-    pub uetr: Option<Uuid>,
-    pub end_to_end_id: String,
-    pub instruction_id: String,
-    pub instructed_amount: Decimal,
-    pub settlement_amount: Decimal,
-    pub currency: String,
-    pub debtor: Party,
-    pub creditor: Party,
-    pub debtor_agent: FinancialInstitution,
-    pub creditor_agent: FinancialInstitution,
-    pub status: String,
-}
-
-#[derive(Debug, Deserialize, Serialize, Clone)]
-pub struct Party {
-    pub name: String,
-}
-
-#[derive(Debug, Deserialize, Serialize, Clone)]
-pub struct FinancialInstitution {
-    pub bic: String,
-    pub name: Option<String>,
-    pub country: Option<String>,
-}
-
-#[derive(Debug, Serialize)]
-pub struct ObligationCreatedEvent {
-    pub obligation_id: Uuid,
-    pub deltran_tx_id: Uuid,
-    pub uetr: Option<Uuid>,
-    pub amount: Decimal,
-    pub currency: String,
-    pub debtor_country: String,
-    pub creditor_country: String,
-}
+use deltran_schema::EventEnvelope;
+use deltran_schema::clearing::{ClearingSubmission, LocalRouting};
+use deltran_schema::envelope::encode_following;
+use deltran_schema::obligation::ObligationCreated;
+use deltran_schema::payment::{CanonicalPayment, FinancialInstitution};
 
 pub async fn start_obligation_consumer(nats_url: &str) -> anyhow::Result<()> {
     info!("📋 Starting Obligation Engine NATS consumer...");
@@ -66,9 +30,11 @@ pub async fn start_obligation_consumer(nats_url: &str) -> anyhow::Result<()> {
         info!("🔄 Obligation consumer task started");
 
         while let Some(msg) = subscriber.next().await {
-            // Parse CanonicalPayment from message
-            match serde_json::from_slice::<CanonicalPayment>(&msg.payload) {
-                Ok(payment) => {
+            // Parse CanonicalPayment from message (payment.canonical envelope or legacy bare payload)
+            match EventEnvelope::<CanonicalPayment>::from_slice(&msg.payload) {
+                Ok(envelope) => {
+                    let correlation_id = envelope.correlation_id;
+                    let payment = envelope.payload;
                     info!("📋 Received obligation creation request for: {} (E2E: {}, UETR: {:?})",
                           payment.deltran_tx_id, payment.end_to_end_id, payment.uetr);
 
@@ -98,13 +64,13 @@ pub async fn start_obligation_consumer(nats_url: &str) -> anyhow::Result<()> {
                             } else {
                                 info!("🏠 LOCAL payment - routing DIRECTLY to Clearing Engine");
                                 info!("   Direct token/fiat routing between banks (same jurisdiction)");
-                                if let Err(e) = publish_to_clearing_local(&nats_for_publish, &payment, &obligation).await {
+                                if let Err(e) = publish_to_clearing_local(&nats_for_publish, &payment, &obligation, correlation_id).await {
                                     error!("Failed to route to Clearing Engine: {}", e);
                                 }
                             }
 
                             // Publish obligation created event for analytics
-                            if let Err(e) = publish_obligation_created(&nats_for_publish, &obligation).await {
+                            if let Err(e) = publish_obligation_created(&nats_for_publish, &obligation, correlation_id).await {
                                 error!("Failed to publish obligation created event: {}", e);
                             }
                         }
@@ -128,15 +94,14 @@ pub async fn start_obligation_consumer(nats_url: &str) -> anyhow::Result<()> {
     Ok(())
 }
 
-async fn create_obligation(payment: &CanonicalPayment) -> anyhow::Result<ObligationCreatedEvent> {
+async fn create_obligation(payment: &CanonicalPayment) -> anyhow::Result<ObligationCreated> {
     // Generate obligation ID
     let obligation_id = Uuid::new_v4();
 
-    // Determine countries from BIC codes or other data
-    // For now, extract from BIC (first 2 chars after 4-char bank code)
+    // Countries as published by the gateway, else from the BIC (first 2 chars after 4-char bank code)
     // Real implementation would use proper BIC lookup
-    let debtor_country = extract_country_from_bic(&payment.debtor_agent.bic);
-    let creditor_country = extract_country_from_bic(&payment.creditor_agent.bic);
+    let debtor_country = agent_country(&payment.debtor_agent);
+    let creditor_country = agent_country(&payment.creditor_agent);
 
     info!("Creating obligation: {} → {} ({} {})",
           debtor_country, creditor_country, payment.settlement_amount, payment.currency);
@@ -144,7 +109,7 @@ async fn create_obligation(payment: &CanonicalPayment) -> anyhow::Result<Obligat
     // TODO: Store obligation in database
     // For now, just create the event
 
-    Ok(ObligationCreatedEvent {
+    Ok(ObligationCreated {
         obligation_id,
         deltran_tx_id: payment.deltran_tx_id,
         uetr: payment.uetr,
@@ -157,36 +122,27 @@ async fn create_obligation(payment: &CanonicalPayment) -> anyhow::Result<Obligat
 
 fn is_cross_border(payment: &CanonicalPayment) -> bool {
     // Determine if payment is cross-border
-    let debtor_country = extract_country_from_bic(&payment.debtor_agent.bic);
-    let creditor_country = extract_country_from_bic(&payment.creditor_agent.bic);
+    let debtor_country = agent_country(&payment.debtor_agent);
+    let creditor_country = agent_country(&payment.creditor_agent);
 
     debtor_country != creditor_country
 }
 
-fn extract_country_from_bic(bic: &str) -> String {
-    // BIC format: XXXXYYZZAAA
-    // XXXX = bank code (4 chars)
-    // YY = country code (2 chars)
-    // ZZ = location code (2 chars)
-    // AAA = branch code (3 chars, optional)
-
-    if bic.len() >= 6 {
-        bic[4..6].to_uppercase()
-    } else {
-        "XX".to_string() // Unknown
-    }
+fn agent_country(agent: &FinancialInstitution) -> String {
+    agent.country().unwrap_or_else(|| "XX".to_string()) // Unknown
 }
 
-async fn publish_to_clearing(nats_client: &Client, payment: &CanonicalPayment, obligation: &ObligationCreatedEvent) -> anyhow::Result<()> {
+async fn publish_to_clearing(nats_client: &Client, payment: &CanonicalPayment, obligation: &ObligationCreated, correlation_id: Uuid) -> anyhow::Result<()> {
     let subject = "deltran.clearing.submit";
 
     // Create clearing submission with obligation info
-    let clearing_data = serde_json::json!({
-        "payment": payment,
-        "obligation": obligation,
-    });
+    let submission = ClearingSubmission {
+        payment: payment.clone(),
+        obligation: obligation.clone(),
+        local: None,
+    };
 
-    let payload = serde_json::to_vec(&clearing_data)?;
+    let payload = encode_following(&submission, correlation_id)?;
 
     nats_client.publish(subject, payload.into()).await?;
 
@@ -196,9 +152,9 @@ async fn publish_to_clearing(nats_client: &Client, payment: &CanonicalPayment, o
     Ok(())
 }
 
-async fn publish_to_token_engine(nats_client: &Client, payment: &CanonicalPayment) -> anyhow::Result<()> {
+async fn publish_to_token_engine(nats_client: &Client, payment: &CanonicalPayment, correlation_id: Uuid) -> anyhow::Result<()> {
     let subject = "deltran.token.mint";
-    let payload = serde_json::to_vec(payment)?;
+    let payload = encode_following(payment, correlation_id)?;
 
     nats_client.publish(subject, payload.into()).await?;
 
@@ -209,11 +165,11 @@ async fn publish_to_token_engine(nats_client: &Client, payment: &CanonicalPaymen
 
 /// Route INTERNATIONAL payments to Risk Engine for path selection
 /// Risk Engine decides: instant buy, hedging (full/partial), or clearing
-async fn publish_to_risk_engine(nats_client: &Client, payment: &CanonicalPayment, obligation: &ObligationCreatedEvent) -> anyhow::Result<()> {
+async fn publish_to_risk_engine(nats_client: &Client, payment: &CanonicalPayment, obligation: &ObligationCreated) -> anyhow::Result<()> {
     let subject = "deltran.risk.check";
 
-    let debtor_country = extract_country_from_bic(&payment.debtor_agent.bic);
-    let creditor_country = extract_country_from_bic(&payment.creditor_agent.bic);
+    let debtor_country = agent_country(&payment.debtor_agent);
+    let creditor_country = agent_country(&payment.creditor_agent);
 
     // Create risk check request for international payment
     let risk_request = serde_json::json!({
@@ -243,23 +199,20 @@ async fn publish_to_risk_engine(nats_client: &Client, payment: &CanonicalPayment
 
 /// Route LOCAL payments directly to Clearing Engine (bypassing Risk/Liquidity)
 /// For optimal token/fiat routing between banks in same jurisdiction
-async fn publish_to_clearing_local(nats_client: &Client, payment: &CanonicalPayment, obligation: &ObligationCreatedEvent) -> anyhow::Result<()> {
+async fn publish_to_clearing_local(nats_client: &Client, payment: &CanonicalPayment, obligation: &ObligationCreated, correlation_id: Uuid) -> anyhow::Result<()> {
     let subject = "deltran.clearing.submit.local";
 
-    let jurisdiction = extract_country_from_bic(&payment.creditor_agent.bic);
-
-    // Create local clearing submission - direct token/fiat routing
-    let clearing_data = serde_json::json!({
-        "payment": payment,
-        "obligation": obligation,
-        "routing_type": "LOCAL_DIRECT",
-        "jurisdiction": jurisdiction,
-        "settlement_type": "INSTANT", // Local payments can settle instantly
-        "skip_risk_check": true, // Same jurisdiction, no FX risk
-        "skip_liquidity_router": true, // No cross-currency optimization needed
-    });
+    let jurisdiction = agent_country(&payment.creditor_agent);
+
+    // Create local clearing submission - direct token/fiat routing, instant settlement,
+    // no risk check (same jurisdiction, no FX risk) and no cross-currency optimization
+    let submission = ClearingSubmission {
+        payment: payment.clone(),
+        obligation: obligation.clone(),
+        local: Some(LocalRouting::direct(jurisdiction.clone())),
+    };
 
-    let payload = serde_json::to_vec(&clearing_data)?;
+    let payload = encode_following(&submission, correlation_id)?;
 
     nats_client.publish(subject, payload.into()).await?;
 
@@ -269,9 +222,9 @@ async fn publish_to_clearing_local(nats_client: &Client, payment: &CanonicalPaym
     Ok(())
 }
 
-async fn publish_obligation_created(nats_client: &Client, obligation: &ObligationCreatedEvent) -> anyhow::Result<()> {
+async fn publish_obligation_created(nats_client: &Client, obligation: &ObligationCreated, correlation_id: Uuid) -> anyhow::Result<()> {
     let subject = "deltran.events.obligation.created";
-    let payload = serde_json::to_vec(obligation)?;
+    let payload = encode_following(obligation, correlation_id)?;
 
     nats_client.publish(subject, payload.into()).await?;
 